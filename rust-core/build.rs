@@ -2,4 +2,24 @@ extern crate napi_build;
 
 fn main() {
   napi_build::setup();
-}
\ No newline at end of file
+
+  #[cfg(feature = "c-ffi")]
+  generate_c_header();
+}
+
+#[cfg(feature = "c-ffi")]
+fn generate_c_header() {
+  let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set");
+  let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+    .expect("Failed to read cbindgen.toml");
+
+  println!("cargo:rerun-if-changed=src/ffi.rs");
+  println!("cargo:rerun-if-changed=cbindgen.toml");
+
+  cbindgen::Builder::new()
+    .with_crate(&crate_dir)
+    .with_config(config)
+    .generate()
+    .expect("Failed to generate C header")
+    .write_to_file(format!("{}/include/in_memoria.h", crate_dir));
+}
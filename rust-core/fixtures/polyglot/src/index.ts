@@ -0,0 +1,13 @@
+import { helper } from './utils';
+
+export class UserService {
+  getUser(id: string) {
+    return helper(id);
+  }
+}
+
+function formatName(name: string) {
+  return name.trim();
+}
+
+const MAX_USERS = 100;
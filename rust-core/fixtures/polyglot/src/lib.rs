@@ -0,0 +1,10 @@
+pub struct Config {
+    pub port: u16,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        let port = 8080;
+        Config { port }
+    }
+}
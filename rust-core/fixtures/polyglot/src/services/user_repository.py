@@ -0,0 +1,13 @@
+import os
+
+
+class UserRepository:
+    def find_user(self, user_id):
+        return user_id
+
+
+def load_config():
+    return os.environ
+
+
+MAX_RETRIES = 3
@@ -0,0 +1 @@
+export const trimAll = (values: string[]) => values.map((v) => v.trim());
@@ -0,0 +1,85 @@
+#ifndef IN_MEMORIA_H
+#define IN_MEMORIA_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Opaque engine handle owning the analyzer, the pattern learner and the async runtime
+typedef struct InMemoriaEngine InMemoriaEngine;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Create a new engine. Returns null if the parsers or runtime fail to initialize.
+struct InMemoriaEngine *in_memoria_engine_new(void);
+
+// Destroy an engine created by [`in_memoria_engine_new`]
+//
+// # Safety
+// `engine` must be null or a pointer obtained from [`in_memoria_engine_new`]
+// that has not already been freed.
+void in_memoria_engine_free(struct InMemoriaEngine *engine);
+
+// Release a string returned by any `in_memoria_*` function
+//
+// # Safety
+// `s` must be null or a pointer returned by this library that has not already been freed.
+void in_memoria_string_free(char *s);
+
+// Analyze a codebase directory. `data` is a `CodebaseAnalysisResult`.
+//
+// # Safety
+// `engine` must come from [`in_memoria_engine_new`]; `path` must be a valid C string.
+char *in_memoria_analyze_codebase(struct InMemoriaEngine *engine, const char *path);
+
+// Analyze a single file. `request_json` is `{"file_path": ..., "content": ...}`;
+// `data` is an array of `SemanticConcept`.
+//
+// # Safety
+// `engine` must come from [`in_memoria_engine_new`]; `request_json` must be a valid C string.
+char *in_memoria_analyze_file(struct InMemoriaEngine *engine, const char *request_json);
+
+// Learn semantic concepts from a codebase. `data` is an array of `SemanticConcept`.
+//
+// # Safety
+// `engine` must come from [`in_memoria_engine_new`]; `path` must be a valid C string.
+char *in_memoria_learn_concepts(struct InMemoriaEngine *engine, const char *path);
+
+// Learn coding patterns from a codebase. `data` is an array of `Pattern`.
+//
+// # Safety
+// `engine` must come from [`in_memoria_engine_new`]; `path` must be a valid C string.
+char *in_memoria_learn_patterns(struct InMemoriaEngine *engine, const char *path);
+
+// Predict an approach. `request_json` is `{"problem_description": ..., "context": {...}}`;
+// `data` is an `ApproachPrediction`.
+//
+// # Safety
+// `engine` must come from [`in_memoria_engine_new`]; `request_json` must be a valid C string.
+char *in_memoria_predict_approach(struct InMemoriaEngine *engine, const char *request_json);
+
+// Find learned patterns relevant to a problem. `request_json` is
+// `{"problem_description": ..., "current_file": ..., "selected_code": ...}`;
+// `data` is an array of `Pattern`.
+//
+// # Safety
+// `engine` must come from [`in_memoria_engine_new`]; `request_json` must be a valid C string.
+char *in_memoria_find_relevant_patterns(struct InMemoriaEngine *engine, const char *request_json);
+
+// List every pattern learned so far. `data` is an array of `Pattern`.
+//
+// # Safety
+// `engine` must come from [`in_memoria_engine_new`].
+char *in_memoria_get_learned_patterns(struct InMemoriaEngine *engine);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* IN_MEMORIA_H */
@@ -0,0 +1,28 @@
+; C concept extraction
+;
+; Each pattern captures the declaration as @definition.<concept_type> and its
+; identifier as @name. Specifiers without a body are type references, not
+; definitions, so they are skipped.
+
+(struct_specifier
+  name: (type_identifier) @name
+  body: (_)) @definition.class
+
+(union_specifier
+  name: (type_identifier) @name
+  body: (_)) @definition.class
+
+(enum_specifier
+  name: (type_identifier) @name
+  body: (_)) @definition.class
+
+(function_definition
+  declarator: (function_declarator
+    declarator: (identifier) @name)) @definition.function
+
+(declaration
+  declarator: (init_declarator
+    declarator: (identifier) @name)) @definition.variable
+
+(declaration
+  declarator: (identifier) @name) @definition.variable
@@ -0,0 +1,19 @@
+; Go concept extraction
+;
+; Each pattern captures the declaration as @definition.<concept_type> and its
+; identifier as @name.
+
+(type_spec
+  name: (type_identifier) @name) @definition.struct
+
+(function_declaration
+  name: (identifier) @name) @definition.function
+
+(method_declaration
+  name: (field_identifier) @name) @definition.function
+
+(var_spec
+  name: (identifier) @name) @definition.variable
+
+(const_spec
+  name: (identifier) @name) @definition.variable
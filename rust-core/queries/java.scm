@@ -0,0 +1,22 @@
+; Java concept extraction
+;
+; Each pattern captures the declaration as @definition.<concept_type> and its
+; identifier as @name.
+
+(class_declaration
+  name: (identifier) @name) @definition.class
+
+(interface_declaration
+  name: (identifier) @name) @definition.class
+
+(enum_declaration
+  name: (identifier) @name) @definition.class
+
+(method_declaration
+  name: (identifier) @name) @definition.function
+
+(constructor_declaration
+  name: (identifier) @name) @definition.function
+
+(variable_declarator
+  name: (identifier) @name) @definition.variable
@@ -0,0 +1,629 @@
+//! API contracts: OpenAPI specs and GraphQL schemas
+//!
+//! Contracts describe an API from outside its code, and a change to a handler
+//! or a client call can break one without any code failing to compile. This
+//! module turns contract files into concepts:
+//! - `api_operation`: each OpenAPI operation, named by its operationId or as
+//!   `GET /orders/{id}`
+//! - `api_schema`: each OpenAPI component schema or Swagger definition
+//! - `graphql_type`: each type, input, interface, enum, union and scalar of a
+//!   GraphQL schema
+//! - `graphql_operation`: each field of the schema's query, mutation and
+//!   subscription types
+//!
+//! and links them to the code implementing and using them. An OpenAPI
+//! operation is served by the routes [`RouteExtractor`] finds with its method
+//! and path, and called by client code requesting its path or calling a
+//! generated client method named after its operationId. A GraphQL operation
+//! is served by resolvers named after its field and selected by client
+//! documents such as `query Orders { orders { id } }`.
+//!
+//! Handler and resolver names are recorded under the `documents`
+//! relationship, so the concept graph links the contract to their concepts;
+//! where the handlers and client calls are is kept in the `handlers` and
+//! `client_calls` metadata as `file:line` lists.
+
+use crate::analysis::workspace::{paths_match, CLIENT_EXTENSIONS, MIN_OPERATION_ID_LEN, SPEC_EXTENSIONS};
+use crate::analysis::{ApiEndpoint, CrossRootLinker, RouteExtractor};
+use crate::types::{LineRange, ParseError, SemanticConcept};
+use regex::Regex;
+use serde_yaml::Value as YamlValue;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::OnceLock;
+use walkdir::WalkDir;
+
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", ".venv", "target", "vendor"];
+
+/// Files larger than this are not read for contracts or client calls
+const MAX_CONTRACT_FILE_SIZE: u64 = 512 * 1024;
+
+const GRAPHQL_EXTENSIONS: &[&str] = &["graphql", "graphqls", "gql"];
+
+pub struct ApiContractExtractor;
+
+impl ApiContractExtractor {
+    /// OpenAPI and Swagger documents (by name) and GraphQL schema files
+    pub fn is_contract_file(file_path: &str) -> bool {
+        let path = Path::new(file_path);
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        GRAPHQL_EXTENSIONS.contains(&extension.as_str())
+            || (SPEC_EXTENSIONS.contains(&extension.as_str()) && CrossRootLinker::is_spec_name(path))
+    }
+
+    /// Contract concepts of every contract file under `root`, linked to the
+    /// handlers and client calls found under it
+    pub fn extract_directory(root: &Path, max_file_size: u64) -> Result<Vec<SemanticConcept>, ParseError> {
+        let max_file_size = max_file_size.min(MAX_CONTRACT_FILE_SIZE);
+        let mut contracts = Vec::new();
+        let mut sources = Vec::new();
+        for entry in WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if !entry.file_type().is_file() || entry.metadata().map(|m| m.len() > max_file_size).unwrap_or(true) {
+                continue;
+            }
+            let path_str = path.to_string_lossy();
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            let is_contract = Self::is_contract_file(&path_str);
+            if !is_contract && !CLIENT_EXTENSIONS.contains(&extension) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(path) else { continue };
+            if is_contract {
+                contracts.extend(Self::extract_concepts(&path_str, &content));
+            } else {
+                let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+                sources.push((relative, content));
+            }
+        }
+
+        // Routes are only worth parsing for when there is a spec to match them to
+        let endpoints = if contracts.iter().any(|c| c.concept_type == "api_operation") {
+            RouteExtractor::scan(root)?
+        } else {
+            Vec::new()
+        };
+        Self::link(&mut contracts, &endpoints, &sources);
+        Ok(contracts)
+    }
+
+    /// Operations and schemas of an OpenAPI document, or types and operations
+    /// of a GraphQL schema; other files yield no concepts
+    pub fn extract_concepts(file_path: &str, content: &str) -> Vec<SemanticConcept> {
+        let extension = Path::new(file_path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        if GRAPHQL_EXTENSIONS.contains(&extension.as_str()) {
+            Self::graphql_concepts(file_path, content)
+        } else {
+            Self::openapi_concepts(file_path, content)
+        }
+    }
+
+    /// Record the handlers and client calls of each contract concept
+    ///
+    /// `endpoints` and the `(file, content)` client sources use paths relative
+    /// to the project root, and so do the `handlers` and `client_calls` lists.
+    pub fn link(contracts: &mut [SemanticConcept], endpoints: &[ApiEndpoint], sources: &[(String, String)]) {
+        let calls: Vec<_> = sources.iter().flat_map(|(file, content)| CrossRootLinker::extract_calls(file, content)).collect();
+        let graphql_sources: Vec<&(String, String)> = sources
+            .iter()
+            .filter(|(_, content)| content.contains("gql") || content.to_lowercase().contains("graphql"))
+            .collect();
+        let selections: Vec<(String, String, String)> = graphql_sources
+            .iter()
+            .flat_map(|(file, content)| {
+                patterns().graphql_document.captures_iter(content).map(move |found| {
+                    let line = content[..found.get(2).map_or(0, |m| m.start())].matches('\n').count() + 1;
+                    (found[1].to_string(), found[2].to_string(), format!("{}:{}", file, line))
+                })
+            })
+            .collect();
+
+        for contract in contracts.iter_mut() {
+            let mut handlers = BTreeSet::new();
+            let mut handler_names = BTreeSet::new();
+            let mut client_calls = BTreeSet::new();
+
+            match contract.concept_type.as_str() {
+                "api_operation" => {
+                    let method = contract.metadata.get("method").cloned().unwrap_or_default();
+                    let path = contract.metadata.get("path").cloned().unwrap_or_default();
+                    let base_path = contract.metadata.get("base_path").cloned().unwrap_or_default();
+                    let serves = |candidate: &str| {
+                        paths_match(candidate, &path)
+                            || (!base_path.is_empty() && paths_match(candidate, &format!("{}{}", base_path, path)))
+                    };
+                    for endpoint in endpoints {
+                        let any_method = endpoint.method == "ALL" || endpoint.method == "ANY";
+                        if (any_method || endpoint.method == method) && serves(&endpoint.path) {
+                            handlers.insert(format!("{}:{}", endpoint.file_path, endpoint.line));
+                            handler_names.extend(endpoint.handler.clone());
+                        }
+                    }
+                    for call in &calls {
+                        if call.method.as_deref().is_none_or(|m| m == method) && serves(&call.path) {
+                            client_calls.insert(format!("{}:{}", call.file_path, call.line));
+                        }
+                    }
+                    // Generated clients expose operations as methods named after them
+                    let operation_id = contract.metadata.get("operation_id").filter(|id| id.len() >= MIN_OPERATION_ID_LEN);
+                    if let Some(call) = operation_id.and_then(|id| Regex::new(&format!(r"\.{}\s*\(", regex::escape(id))).ok()) {
+                        for (file, content) in sources {
+                            for (idx, line) in content.lines().enumerate() {
+                                if call.is_match(line) {
+                                    client_calls.insert(format!("{}:{}", file, idx + 1));
+                                }
+                            }
+                        }
+                    }
+                }
+                "graphql_operation" => {
+                    let field = contract.name.clone();
+                    let operation = contract.metadata.get("operation").cloned().unwrap_or_default();
+                    handler_names.insert(field.clone());
+                    handler_names.insert(format!("resolve_{}", field));
+                    handler_names.insert(format!("resolve{}", capitalize(&field)));
+                    for (kind, selected, location) in &selections {
+                        if *selected == field && *kind == operation {
+                            client_calls.insert(location.clone());
+                        }
+                    }
+                }
+                _ => continue,
+            }
+
+            let join = |items: BTreeSet<String>| items.into_iter().collect::<Vec<_>>().join(", ");
+            if !handler_names.is_empty() {
+                contract.relationships.insert("documents".to_string(), join(handler_names));
+            }
+            if !handlers.is_empty() {
+                contract.metadata.insert("handlers".to_string(), join(handlers));
+            }
+            if !client_calls.is_empty() {
+                contract.metadata.insert("client_calls".to_string(), join(client_calls));
+            }
+        }
+    }
+
+    fn openapi_concepts(file_path: &str, content: &str) -> Vec<SemanticConcept> {
+        let operations = CrossRootLinker::extract_operations(file_path, content);
+        let Ok(document) = serde_yaml::from_str::<YamlValue>(content) else {
+            return Vec::new();
+        };
+        let spec = if document.get("swagger").is_some() { "swagger" } else { "openapi" };
+        let base_path = Self::base_path(&document);
+
+        let mut concepts = Vec::new();
+        for operation in operations {
+            let name = operation
+                .operation_id
+                .clone()
+                .unwrap_or_else(|| format!("{} {}", operation.method, operation.path));
+            let mut metadata = HashMap::from([
+                ("method".to_string(), operation.method.clone()),
+                ("path".to_string(), operation.path.clone()),
+                ("spec".to_string(), spec.to_string()),
+            ]);
+            if let Some(id) = &operation.operation_id {
+                metadata.insert("operation_id".to_string(), id.clone());
+            }
+            if let Some(base_path) = &base_path {
+                metadata.insert("base_path".to_string(), base_path.clone());
+            }
+            concepts.push(contract_concept(file_path, &name, "api_operation", operation.line, operation.line, metadata));
+        }
+
+        // OpenAPI 3 keeps schemas under components, Swagger 2 under definitions
+        let (section, schemas) = match document.get("components").and_then(|c| c.get("schemas")) {
+            Some(schemas) => ("schemas", schemas),
+            None => match document.get("definitions") {
+                Some(definitions) => ("definitions", definitions),
+                None => return concepts,
+            },
+        };
+        let section_line = key_line(content, section, 1).unwrap_or(1);
+        for (name, schema) in schemas.as_mapping().into_iter().flatten() {
+            let Some(name) = name.as_str() else { continue };
+            let mut metadata = HashMap::from([("spec".to_string(), spec.to_string())]);
+            let properties: Vec<&str> = schema
+                .get("properties")
+                .and_then(YamlValue::as_mapping)
+                .into_iter()
+                .flatten()
+                .filter_map(|(property, _)| property.as_str())
+                .collect();
+            if !properties.is_empty() {
+                metadata.insert("properties".to_string(), properties.join(","));
+            }
+            let line = key_line(content, name, section_line).unwrap_or(section_line);
+            concepts.push(contract_concept(file_path, name, "api_schema", line, line, metadata));
+        }
+        concepts
+    }
+
+    /// Path prefix of the first server URL (OpenAPI 3) or the base path
+    /// (Swagger 2), e.g. `/api/v1`
+    fn base_path(document: &YamlValue) -> Option<String> {
+        let url = document
+            .get("servers")
+            .and_then(|servers| servers.get(0))
+            .and_then(|server| server.get("url"))
+            .or_else(|| document.get("basePath"))
+            .and_then(YamlValue::as_str)?;
+        let path = match url.split_once("://") {
+            Some((_, rest)) => rest.find('/').map_or("", |slash| &rest[slash..]),
+            None => url,
+        };
+        let path = path.trim_end_matches('/');
+        (path.starts_with('/') && !path.contains('{')).then(|| path.to_string())
+    }
+
+    fn graphql_concepts(file_path: &str, content: &str) -> Vec<SemanticConcept> {
+        let definitions = graphql_definitions(content);
+        let root_types = root_operation_types(&definitions);
+
+        let mut concepts = Vec::new();
+        for definition in &definitions {
+            if definition.kind == "schema" {
+                continue;
+            }
+            let operation = root_types.iter().find(|(_, name)| *name == definition.name).map(|(op, _)| *op);
+            let mut metadata = HashMap::from([("kind".to_string(), definition.kind.clone())]);
+            if !definition.fields.is_empty() {
+                let names: Vec<&str> = definition.fields.iter().map(|f| f.name.as_str()).collect();
+                metadata.insert("fields".to_string(), names.join(","));
+            }
+            concepts.push(contract_concept(
+                file_path,
+                &definition.name,
+                "graphql_type",
+                definition.line,
+                definition.end_line,
+                metadata,
+            ));
+
+            let Some(operation) = operation else { continue };
+            for field in &definition.fields {
+                let metadata = HashMap::from([
+                    ("operation".to_string(), operation.to_string()),
+                    ("return_type".to_string(), field.field_type.clone()),
+                    ("parent_type".to_string(), definition.name.clone()),
+                ]);
+                concepts.push(contract_concept(file_path, &field.name, "graphql_operation", field.line, field.line, metadata));
+            }
+        }
+        concepts
+    }
+}
+
+/// A top-level definition of a GraphQL schema
+#[derive(Debug, Clone, PartialEq)]
+struct GraphqlDefinition {
+    /// 'type', 'input', 'interface', 'enum', 'union', 'scalar' or 'schema'
+    kind: String,
+    /// Empty for `schema`
+    name: String,
+    line: u32,
+    end_line: u32,
+    fields: Vec<GraphqlField>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct GraphqlField {
+    name: String,
+    /// As written, e.g. `[Order!]!`
+    field_type: String,
+    line: u32,
+}
+
+/// Definitions of a GraphQL schema, with the fields of object, input and
+/// interface types and of the `schema` block. `extend type` adds to a type
+/// and is reported as another definition of it.
+fn graphql_definitions(content: &str) -> Vec<GraphqlDefinition> {
+    let masked = mask_graphql(content);
+    let mut definitions: Vec<GraphqlDefinition> = Vec::new();
+    let mut depth = 0u32;
+    let mut paren_depth = 0u32;
+    let mut offset = 0;
+    for (idx, line) in masked.split('\n').enumerate() {
+        let number = idx as u32 + 1;
+        let line_start = offset;
+        offset += line.len() + 1;
+        if depth == 0 {
+            if let Some(found) = patterns().graphql_definition.captures(line) {
+                definitions.push(GraphqlDefinition {
+                    kind: found[1].to_string(),
+                    name: found.get(2).map_or("", |m| m.as_str()).to_string(),
+                    line: number,
+                    end_line: number,
+                    fields: Vec::new(),
+                });
+            }
+        } else if depth == 1 && paren_depth == 0 {
+            if let (Some(definition), Some(found)) = (definitions.last_mut(), patterns().graphql_field.captures(line)) {
+                if definition.kind != "enum" {
+                    // The type follows the arguments, which may span lines
+                    let rest = &masked[line_start + found.get(1).map_or(0, |m| m.end())..];
+                    definition.fields.push(GraphqlField {
+                        name: found[1].to_string(),
+                        field_type: field_type(rest),
+                        line: number,
+                    });
+                }
+            }
+        }
+        for ch in line.chars() {
+            match ch {
+                '{' => depth += 1,
+                '}' => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        if let Some(definition) = definitions.last_mut() {
+                            definition.end_line = number;
+                        }
+                    }
+                }
+                '(' => paren_depth += 1,
+                ')' => paren_depth = paren_depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+    definitions
+}
+
+/// The type of a field from the text after its name: `(id: ID!): Order @auth` -> `Order`
+fn field_type(rest: &str) -> String {
+    let mut depth = 0u32;
+    for (idx, ch) in rest.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ':' if depth == 0 => {
+                return rest[idx + 1..]
+                    .trim_start()
+                    .chars()
+                    .take_while(|c| c.is_alphanumeric() || matches!(c, '_' | '[' | ']' | '!'))
+                    .collect();
+            }
+            '\n' | '{' | '}' if depth == 0 => break,
+            _ => {}
+        }
+    }
+    String::new()
+}
+
+/// Operation kinds and the types serving them: those named in a `schema`
+/// block, or else `Query`, `Mutation` and `Subscription`
+fn root_operation_types(definitions: &[GraphqlDefinition]) -> Vec<(&'static str, String)> {
+    let declared: Vec<(&'static str, String)> = definitions
+        .iter()
+        .filter(|definition| definition.kind == "schema")
+        .flat_map(|schema| &schema.fields)
+        .filter_map(|field| {
+            let operation = ["query", "mutation", "subscription"].into_iter().find(|op| *op == field.name)?;
+            Some((operation, field.field_type.trim_end_matches('!').to_string()))
+        })
+        .collect();
+    if !declared.is_empty() {
+        return declared;
+    }
+    vec![
+        ("query", "Query".to_string()),
+        ("mutation", "Mutation".to_string()),
+        ("subscription", "Subscription".to_string()),
+    ]
+}
+
+/// The schema with comments, strings and descriptions blanked; line breaks
+/// are kept so line numbers still match
+fn mask_graphql(content: &str) -> String {
+    let mut masked = String::with_capacity(content.len());
+    let mut rest = content;
+    let blank = |text: &str| text.chars().map(|c| if c == '\n' { '\n' } else { ' ' }).collect::<String>();
+    while let Some(start) = rest.find(['#', '"']) {
+        masked.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let end = if let Some(block) = tail.strip_prefix("\"\"\"") {
+            block.find("\"\"\"").map_or(tail.len(), |close| close + 6)
+        } else if let Some(string) = tail.strip_prefix('"') {
+            string.find(['"', '\n']).map_or(tail.len(), |close| close + 2)
+        } else {
+            tail.find('\n').unwrap_or(tail.len())
+        };
+        let end = end.min(tail.len());
+        masked.push_str(&blank(&tail[..end]));
+        rest = &tail[end..];
+    }
+    masked.push_str(rest);
+    masked
+}
+
+/// First line at or after `from` (1-based) declaring `key` in YAML or JSON
+fn key_line(content: &str, key: &str, from: u32) -> Option<u32> {
+    let yaml_key = format!("{}:", key);
+    let json_key = format!("\"{}\"", key);
+    content
+        .lines()
+        .enumerate()
+        .skip(from.saturating_sub(1) as usize)
+        .find(|(_, line)| {
+            let line = line.trim_start();
+            line.starts_with(&yaml_key) || line.starts_with(&json_key)
+        })
+        .map(|(idx, _)| idx as u32 + 1)
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    chars.next().map_or_else(String::new, |first| first.to_uppercase().chain(chars).collect())
+}
+
+fn contract_concept(
+    file_path: &str,
+    name: &str,
+    concept_type: &str,
+    line: u32,
+    end_line: u32,
+    mut metadata: HashMap<String, String>,
+) -> SemanticConcept {
+    metadata.insert("source".to_string(), "api_contract".to_string());
+
+    let mut hasher = DefaultHasher::new();
+    (file_path, concept_type, name, line).hash(&mut hasher);
+    SemanticConcept {
+        id: format!("contract_{:016x}", hasher.finish()),
+        name: name.to_string(),
+        concept_type: concept_type.to_string(),
+        // Declared in the contract rather than inferred from code
+        confidence: 0.9,
+        file_path: file_path.to_string(),
+        line_range: LineRange { start: line, end: end_line },
+        relationships: HashMap::new(),
+        metadata,
+    }
+}
+
+struct Patterns {
+    graphql_definition: Regex,
+    graphql_field: Regex,
+    graphql_document: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let re = |p: &str| Regex::new(p).expect("valid API contract regex");
+        Patterns {
+            graphql_definition: re(r"^\s*(?:extend\s+)?(type|input|interface|enum|union|scalar|schema)\b\s*([_A-Za-z]\w*)?"),
+            graphql_field: re(r"^\s*([_A-Za-z]\w*)\s*[(:]"),
+            // An operation keyword, its name and variables, then its first
+            // selected field, possibly behind an alias
+            graphql_document: re(
+                r"\b(query|mutation|subscription)\b[\w\s$:!\[\](),]*?\{\s*(?:[_A-Za-z]\w*\s*:\s*)?([_A-Za-z]\w*)",
+            ),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"openapi: 3.0.3
+servers:
+  - url: https://api.example.com/api/v1
+paths:
+  /orders:
+    post:
+      summary: Create an order
+  /orders/{orderId}:
+    get:
+      operationId: getOrder
+components:
+  schemas:
+    Order:
+      properties:
+        id: { type: string }
+        total: { type: number }
+"#;
+
+    #[test]
+    fn test_openapi_operations_link_to_handlers_and_clients() {
+        assert!(ApiContractExtractor::is_contract_file("api/openapi.yaml"));
+        assert!(ApiContractExtractor::is_contract_file("schema.graphql"));
+        assert!(!ApiContractExtractor::is_contract_file("config.yaml"));
+
+        let mut concepts = ApiContractExtractor::extract_concepts("api/openapi.yaml", SPEC);
+        let summary: Vec<(&str, &str, u32)> =
+            concepts.iter().map(|c| (c.concept_type.as_str(), c.name.as_str(), c.line_range.start)).collect();
+        assert_eq!(
+            summary,
+            vec![("api_operation", "POST /orders", 5), ("api_operation", "getOrder", 10), ("api_schema", "Order", 13)]
+        );
+        assert_eq!(concepts[1].metadata["base_path"], "/api/v1");
+        assert_eq!(concepts[2].metadata["properties"], "id,total");
+
+        let endpoints = vec![ApiEndpoint {
+            method: "GET".to_string(),
+            path: "/api/v1/orders/:id".to_string(),
+            handler: Some("getOrderHandler".to_string()),
+            framework: "express".to_string(),
+            file_path: "src/routes/orders.ts".to_string(),
+            line: 12,
+        }];
+        let sources = vec![(
+            "web/api.ts".to_string(),
+            "const order = await fetch(`/api/v1/orders/${id}`);\nawait ordersApi.getOrder({ orderId });\n\
+             await axios.post('/api/v1/orders', body);\n"
+                .to_string(),
+        )];
+        ApiContractExtractor::link(&mut concepts, &endpoints, &sources);
+
+        let get_order = &concepts[1];
+        assert_eq!(get_order.relationships["documents"], "getOrderHandler");
+        assert_eq!(get_order.metadata["handlers"], "src/routes/orders.ts:12");
+        assert_eq!(get_order.metadata["client_calls"], "web/api.ts:1, web/api.ts:2");
+        assert_eq!(concepts[0].metadata["client_calls"], "web/api.ts:3");
+        assert!(!concepts[0].relationships.contains_key("documents"));
+    }
+
+    #[test]
+    fn test_graphql_schema_operations_and_client_documents() {
+        let schema = r#"
+"""Orders placed by a customer"""
+type Order {
+  id: ID!
+  total: Float # in cents { not a brace }
+}
+
+type Query {
+  orders(
+    status: String = "open"
+  ): [Order!]!
+  order(id: ID!): Order
+}
+
+extend type Mutation {
+  cancelOrder(id: ID!): Order @auth(requires: ADMIN)
+}
+
+enum Status { OPEN CLOSED }
+"#;
+        let mut concepts = ApiContractExtractor::extract_concepts("schema.graphql", schema);
+        let summary: Vec<(&str, &str, u32, u32)> = concepts
+            .iter()
+            .map(|c| (c.concept_type.as_str(), c.name.as_str(), c.line_range.start, c.line_range.end))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("graphql_type", "Order", 3, 6),
+                ("graphql_type", "Query", 8, 13),
+                ("graphql_operation", "orders", 9, 9),
+                ("graphql_operation", "order", 12, 12),
+                ("graphql_type", "Mutation", 15, 17),
+                ("graphql_operation", "cancelOrder", 16, 16),
+                ("graphql_type", "Status", 19, 19),
+            ]
+        );
+        assert_eq!(concepts[2].metadata["return_type"], "[Order!]!");
+        assert_eq!(concepts[5].metadata["operation"], "mutation");
+        assert!(!concepts[6].metadata.contains_key("fields"));
+
+        let client = "const ORDERS = gql`\n  query OpenOrders($status: String) {\n    orders(status: $status) { id }\n  }\n`;\n\
+                      const CANCEL = gql`mutation { cancelled: cancelOrder(id: 1) { id } }`;\n";
+        ApiContractExtractor::link(&mut concepts, &[], &[("src/orders.ts".to_string(), client.to_string())]);
+        assert_eq!(concepts[2].metadata["client_calls"], "src/orders.ts:3");
+        assert_eq!(concepts[5].metadata["client_calls"], "src/orders.ts:6");
+        assert_eq!(concepts[5].relationships["documents"], "cancelOrder, resolveCancelOrder, resolve_cancelOrder");
+        assert!(!concepts[3].metadata.contains_key("client_calls"));
+    }
+}
@@ -1,25 +1,56 @@
 //! Project blueprint analysis - entry points and feature mapping
+//!
+//! [`ProjectBlueprint`] gathers every section into one typed object. Its
+//! `schema_version` is bumped whenever a field is renamed or removed, so
+//! consumers of the serialized form can tell which layout they received.
 
 #[cfg(feature = "napi-bindings")]
 use napi_derive::napi;
 
-use crate::types::ParseError;
-use crate::analysis::FrameworkInfo;
+use crate::types::{paths, validation};
+use crate::types::{ParseError, SemanticConcept};
+use crate::extractors::ConfigFileExtractor;
+use crate::analysis::{
+    ApiEndpoint, ComplexityAnalyzer, ConceptTagStore, CouplingSummary, DataModelExtractor, DataModelSummary, DebtCommentExtractor, DependencySummary,
+    DeprecatedSurface, DeprecationExtractor, DirectoryOwnership, DomainEntityUnifier, EntryPointDetector, EnvVarScanner, EnvVarSummary, FrameworkDetector, FrameworkInfo,
+    FeatureClusterer, GraphStore, IdiomScorer, IdiomSummary, ManifestParser, OwnershipAnalyzer, RouteExtractor,
+    SemanticAnalyzer, SharedDomainEntity, TagGroup, TechnicalDebtSummary, TestInventory, TestInventoryScanner,
+    BuildTaskDetector, BuildTaskSummary,
+    I18nScanner, I18nSummary,
+    SafetyReport, UnsafeAuditor,
+    BlueprintSummarizer, BlueprintSummary, DEFAULT_SUMMARY_ITEMS,
+    EventGraph, EventScanner,
+};
+use crate::patterns::types::Pattern;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::fs;
+use walkdir::WalkDir;
+
+/// Version of the [`ProjectBlueprint`] layout
+pub const BLUEPRINT_SCHEMA_VERSION: u32 = 1;
+
+/// Directories skipped when walking a project
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", "target"];
 
 /// Entry point information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "napi-bindings", napi(object))]
 pub struct EntryPoint {
     pub entry_type: String, // 'web', 'api', 'cli', 'script'
     pub file_path: String,
     pub framework: Option<String>,
     pub confidence: f64,
+    /// What declared the entry point, e.g. "Cargo.toml [[bin]] cli" or "Dockerfile CMD ..."
+    pub source: Option<String>,
 }
 
 /// Key directory information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "napi-bindings", napi(object))]
 pub struct KeyDirectory {
     pub path: String,
@@ -28,14 +59,94 @@ pub struct KeyDirectory {
 }
 
 /// Feature mapping information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "napi-bindings", napi(object))]
 pub struct FeatureMap {
     pub id: String,
     pub feature_name: String,
     pub primary_files: Vec<String>,
     pub related_files: Vec<String>,
+    /// Names of the features this one relies on
     pub dependencies: Vec<String>,
+    /// Names of the concepts defined in the feature's files
+    #[serde(default)]
+    pub concepts: Vec<String>,
+    /// Concepts the feature is entered through: those other features use and
+    /// those of the project's entry-point files
+    #[serde(default)]
+    pub entry_points: Vec<String>,
+}
+
+/// Operational structure declared in configuration files
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct OperationalStructure {
+    pub services: Vec<SemanticConcept>,
+    pub ci_jobs: Vec<SemanticConcept>,
+    pub scripts: Vec<SemanticConcept>,
+    pub build_targets: Vec<SemanticConcept>,
+    pub env_vars: Vec<SemanticConcept>,
+    /// Dockerfile build stages
+    pub container_stages: Vec<SemanticConcept>,
+    /// Terraform resources, data sources, modules and providers
+    pub infrastructure: Vec<SemanticConcept>,
+}
+
+/// Architectural layer made up of key directories
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ArchitectureLayer {
+    /// 'presentation', 'api', 'domain', 'data' or 'shared'
+    pub name: String,
+    pub directories: Vec<String>,
+    pub file_count: u32,
+}
+
+/// Size of the project and of each blueprint section
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct BlueprintMetrics {
+    pub total_files: u32,
+    /// Source files per extension, e.g. `ts` or `py`
+    pub files_by_extension: BTreeMap<String, u32>,
+    pub module_count: u32,
+    pub entry_point_count: u32,
+    pub endpoint_count: u32,
+    pub data_model_count: u32,
+    pub dependency_count: u32,
+}
+
+/// Complete blueprint of a project with a versioned layout
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ProjectBlueprint {
+    /// See [`BLUEPRINT_SCHEMA_VERSION`]
+    pub schema_version: u32,
+    pub project_path: String,
+    pub frameworks: Vec<FrameworkInfo>,
+    pub modules: Vec<FeatureMap>,
+    pub layers: Vec<ArchitectureLayer>,
+    pub entry_points: Vec<EntryPoint>,
+    pub endpoints: Vec<ApiEndpoint>,
+    pub data_models: DataModelSummary,
+    pub dependencies: DependencySummary,
+    pub metrics: BlueprintMetrics,
+    /// Concepts and files grouped by user-defined tags; empty when nothing is tagged
+    #[serde(default)]
+    pub tag_groups: Vec<TagGroup>,
+    /// Entities declared in several languages, e.g. a struct, an interface and a table
+    #[serde(default)]
+    pub shared_entities: Vec<SharedDomainEntity>,
+    /// Coupling and cohesion hot spots, overall and per directory
+    #[serde(default)]
+    pub coupling: CouplingSummary,
+    /// Topics, queues and events with their producers and consumers
+    #[serde(default)]
+    pub events: EventGraph,
 }
 
 /// Blueprint analyzer for detecting project structure
@@ -55,12 +166,82 @@ impl BlueprintAnalyzer {
         BlueprintAnalyzer
     }
 
+    /// Build the complete blueprint: modules, layers, entry points, HTTP
+    /// endpoints, data models, dependencies, size metrics, tag groups, shared
+    /// domain entities, coupling hot spots and the event graph
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_project_blueprint(path: String) -> Result<ProjectBlueprint, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+
+        let frameworks = FrameworkDetector::detect_frameworks(path.clone()).await?;
+        let entry_points = Self::detect_entry_points(path.clone(), frameworks.clone()).await?;
+        let key_directories = Self::map_key_directories(path.clone()).await?;
+        let graph = Self::concept_graph(project_path);
+        let modules = Self::features_from_graph(&graph, project_path)?;
+        let measures = ComplexityAnalyzer::measure_coupling(&graph);
+        let coupling = ComplexityAnalyzer::summarize_coupling(&measures, Some(&path));
+        let endpoints = RouteExtractor::scan(project_path)?;
+        let events = EventScanner::scan(project_path)?;
+        let data_models = DataModelExtractor::scan(project_path)?;
+        let dependencies = ManifestParser::analyze(project_path)?;
+        let tag_groups = ConceptTagStore::load(project_path)?
+            .map(|store| store.groups())
+            .unwrap_or_default();
+        let shared_entities = Self::map_shared_entities(path.clone()).await?;
+
+        let (total_files, files_by_extension) = Self::count_source_files(project_path);
+        let metrics = BlueprintMetrics {
+            total_files,
+            files_by_extension,
+            module_count: modules.len() as u32,
+            entry_point_count: entry_points.len() as u32,
+            endpoint_count: endpoints.len() as u32,
+            data_model_count: data_models.total_models,
+            dependency_count: dependencies.total_dependencies,
+        };
+
+        Ok(ProjectBlueprint {
+            schema_version: BLUEPRINT_SCHEMA_VERSION,
+            project_path: path,
+            frameworks,
+            modules,
+            layers: Self::group_layers(&key_directories),
+            entry_points,
+            endpoints,
+            data_models,
+            dependencies,
+            metrics,
+            tag_groups,
+            shared_entities,
+            coupling,
+            events,
+        })
+    }
+
+    /// Compact, ranked summary of `blueprint`: its top frameworks, its
+    /// architecture style, the dominant `patterns` and the biggest risks, each
+    /// list cut to `max_items` (5 by default, 50 at most)
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn summarize_blueprint(
+        blueprint: ProjectBlueprint,
+        max_items: Option<u32>,
+        patterns: Option<Vec<Pattern>>,
+    ) -> BlueprintSummary {
+        BlueprintSummarizer::summarize(
+            &blueprint,
+            &patterns.unwrap_or_default(),
+            max_items.unwrap_or(DEFAULT_SUMMARY_ITEMS),
+        )
+    }
+
     /// Detect entry points using AST-based analysis and pattern matching
     #[cfg_attr(feature = "napi-bindings", napi)]
     pub async fn detect_entry_points(
         path: String,
         frameworks: Vec<FrameworkInfo>,
     ) -> Result<Vec<EntryPoint>, ParseError> {
+        validation::require_directory(&path)?;
         let mut entry_points = Vec::new();
         let framework_names: Vec<String> = frameworks.iter().map(|f| f.name.clone()).collect();
 
@@ -101,6 +282,7 @@ impl BlueprintAnalyzer {
                         file_path: entry.to_string(),
                         framework: Some("react".to_string()),
                         confidence: 0.9,
+                        source: None,
                     });
                 }
             }
@@ -125,6 +307,7 @@ impl BlueprintAnalyzer {
                         file_path: entry.to_string(),
                         framework: Some("express".to_string()),
                         confidence: 0.85,
+                        source: None,
                     });
                 }
             }
@@ -159,6 +342,7 @@ impl BlueprintAnalyzer {
                         file_path: entry.to_string(),
                         framework: framework_hint,
                         confidence: 0.85,
+                        source: None,
                     });
                 }
             }
@@ -177,6 +361,7 @@ impl BlueprintAnalyzer {
                         file_path: entry.to_string(),
                         framework: Some("rust".to_string()),
                         confidence: 0.95,
+                        source: None,
                     });
                 }
             }
@@ -194,6 +379,7 @@ impl BlueprintAnalyzer {
                         file_path: entry.to_string(),
                         framework: Some("go".to_string()),
                         confidence: 0.9,
+                        source: None,
                     });
                 }
             }
@@ -209,6 +395,7 @@ impl BlueprintAnalyzer {
                     file_path: entry.to_string(),
                     framework: None,
                     confidence: 0.8,
+                    source: None,
                 });
             }
         }
@@ -216,9 +403,84 @@ impl BlueprintAnalyzer {
         Ok(())
     }
 
+    /// Extract HTTP routes declared with Express, FastAPI, Flask, Actix, Axum,
+    /// Rocket or Spring, with their method, path and handler
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_api_endpoints(path: String) -> Result<Vec<ApiEndpoint>, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+        RouteExtractor::scan(project_path)
+    }
+
+    /// Extract Prisma, SQLAlchemy, Diesel, TypeORM and SQL DDL models with
+    /// their columns, relations and the files that use them
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn map_data_model(path: String) -> Result<DataModelSummary, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+        DataModelExtractor::scan(project_path)
+    }
+
+    /// List the environment variables read in code or declared in .env files,
+    /// Dockerfiles and CI/compose configuration, with their defaults
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn map_environment_variables(path: String) -> Result<EnvVarSummary, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+        EnvVarScanner::scan(project_path)
+    }
+
+    /// Score how idiomatically Rust, Python and TypeScript are written, with
+    /// recommendations for the most frequent deviations
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn score_idioms(path: String) -> Result<IdiomSummary, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+        IdiomScorer::scan(project_path)
+    }
+
+    /// Detect jest, vitest, pytest, cargo test, JUnit and go test and list the
+    /// test suites and cases with their locations
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_test_inventory(path: String) -> Result<TestInventory, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+        TestInventoryScanner::scan(project_path)
+    }
+
+    /// List npm scripts, Makefile targets, cargo-make tasks, just recipes,
+    /// Gradle tasks and Bazel targets with the commands that run them, and how
+    /// to build and test the project
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_build_tasks(path: String) -> Result<BuildTaskSummary, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+        BuildTaskDetector::scan(project_path)
+    }
+
+    /// Map localization catalogs and the code that looks their keys up,
+    /// with keys used but never defined and keys defined but never used
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn map_i18n_keys(path: String) -> Result<I18nSummary, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+        I18nScanner::scan(project_path)
+    }
+
+    /// Inventory unsafe blocks, functions and impls, extern blocks and
+    /// functions, transmutes and raw pointers in the project's Rust code, and
+    /// which unsafe code lacks a `SAFETY:` comment
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn audit_unsafe_code(path: String) -> Result<SafetyReport, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+        UnsafeAuditor::scan(project_path)
+    }
+
     /// Map key directories in the project
     #[cfg_attr(feature = "napi-bindings", napi)]
     pub async fn map_key_directories(path: String) -> Result<Vec<KeyDirectory>, ParseError> {
+        validation::require_directory(&path)?;
         let mut key_dirs = Vec::new();
         let project_path = Path::new(&path);
 
@@ -254,11 +516,42 @@ impl BlueprintAnalyzer {
         Ok(key_dirs)
     }
 
-    /// Build feature map for the project
+    /// Build the feature map of a project by clustering its concept graph
+    ///
+    /// Uses the graph saved by the last learning run, or extracts one from the
+    /// source files when there is none. Projects without concepts to cluster
+    /// get features from well-known directory names instead.
     #[cfg_attr(feature = "napi-bindings", napi)]
     pub async fn build_feature_map(path: String) -> Result<Vec<FeatureMap>, ParseError> {
-        let mut feature_maps = Vec::new();
+        validation::require_directory(&path)?;
         let project_path = Path::new(&path);
+        Self::features_from_graph(&Self::concept_graph(project_path), project_path)
+    }
+
+    /// The graph saved by the last learning run, or one extracted from the
+    /// source files when there is none
+    fn concept_graph(project_path: &Path) -> GraphStore {
+        match GraphStore::load(project_path) {
+            Ok(Some(graph)) => graph,
+            Ok(None) => Self::extract_concept_graph(project_path),
+            Err(e) => {
+                eprintln!("Warning: rebuilding concept graph: {}", e);
+                Self::extract_concept_graph(project_path)
+            }
+        }
+    }
+
+    fn features_from_graph(graph: &GraphStore, project_path: &Path) -> Result<Vec<FeatureMap>, ParseError> {
+        let features = FeatureClusterer::cluster(graph, &EntryPointDetector::detect(project_path), project_path);
+        if !features.is_empty() {
+            return Ok(features);
+        }
+        Self::directory_feature_map(project_path)
+    }
+
+    /// Features named after well-known directories such as `auth` or `api`
+    fn directory_feature_map(project_path: &Path) -> Result<Vec<FeatureMap>, ParseError> {
+        let mut feature_maps = Vec::new();
 
         let feature_patterns: Vec<(&str, Vec<&str>)> = vec![
             ("authentication", vec!["auth", "authentication"]),
@@ -318,6 +611,8 @@ impl BlueprintAnalyzer {
                     primary_files,
                     related_files,
                     dependencies: Vec::new(),
+                    concepts: Vec::new(),
+                    entry_points: Vec::new(),
                 });
             }
         }
@@ -325,6 +620,256 @@ impl BlueprintAnalyzer {
         Ok(feature_maps)
     }
 
+    /// Map directory ownership from CODEOWNERS and git blame
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn map_ownership(path: String) -> Result<Vec<DirectoryOwnership>, ParseError> {
+        validation::require_directory(&path)?;
+        Ok(OwnershipAnalyzer::new(&path).directory_ownership())
+    }
+
+    /// Map services, CI jobs, scripts, build targets, environment variables,
+    /// container stages and infrastructure declared in CI workflows, compose
+    /// files, build manifests, Dockerfiles and Terraform files. Compose services
+    /// built from a local Dockerfile are linked to its final stage.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn map_operational_structure(path: String) -> Result<OperationalStructure, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+
+        let mut structure = OperationalStructure::default();
+        for mut concept in ConfigFileExtractor::new().extract_directory(project_path, u64::MAX) {
+            concept.file_path = paths::relative_to(&concept.file_path, project_path);
+            let bucket = match concept.concept_type.as_str() {
+                "service" => &mut structure.services,
+                "ci_job" => &mut structure.ci_jobs,
+                "script" => &mut structure.scripts,
+                "build_target" => &mut structure.build_targets,
+                "env_var" => &mut structure.env_vars,
+                "container_stage" => &mut structure.container_stages,
+                "infra_resource" | "infra_data_source" | "infra_module" | "infra_provider" => {
+                    &mut structure.infrastructure
+                }
+                _ => continue,
+            };
+            bucket.push(concept);
+        }
+
+        for service in &mut structure.services {
+            let Some(context) = service.metadata.get("build") else { continue };
+            let compose_dir = Path::new(&service.file_path).parent().unwrap_or(Path::new(""));
+            let dockerfile = service.metadata.get("dockerfile").map_or("Dockerfile", String::as_str);
+            let expected = paths::normalize_path(&compose_dir.join(context).join(dockerfile).to_string_lossy());
+            let stage = structure.container_stages.iter().find(|stage| {
+                stage.metadata.get("final").is_some_and(|f| f == "true")
+                    && paths::normalize_path(&stage.file_path) == expected
+            });
+            if let Some(stage) = stage {
+                service.relationships.insert("built_from".to_string(), stage.name.clone());
+                service.metadata.insert("dockerfile".to_string(), stage.file_path.clone());
+            }
+        }
+        Ok(structure)
+    }
+
+    /// Parse package.json, Cargo.toml, pyproject.toml and go.mod manifests into
+    /// dependency counts, licenses and workspace members
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn analyze_dependencies(path: String) -> Result<DependencySummary, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+        ManifestParser::analyze(project_path)
+    }
+
+    /// Collect TODO/FIXME/HACK/XXX/DEPRECATED comments with the concept each
+    /// one belongs to
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn map_technical_debt(path: String) -> Result<TechnicalDebtSummary, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+
+        let mut analyzer = SemanticAnalyzer::new()?;
+        let mut items = Vec::new();
+        for entry in WalkDir::new(project_path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let file_path = entry.path();
+            if !entry.file_type().is_file() || !Self::is_source_file(file_path) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(file_path) else {
+                continue;
+            };
+            if !DebtCommentExtractor::has_markers(&content) {
+                continue;
+            }
+
+            let relative = paths::relative_to(&file_path.to_string_lossy(), project_path);
+            let concepts = analyzer.extract_file_concepts_sync(&relative, &content);
+            items.extend(DebtCommentExtractor::extract(&relative, &content, &concepts));
+        }
+
+        Ok(DebtCommentExtractor::summarize(items))
+    }
+
+    /// List the deprecated APIs (doc tags, attributes, decorators and runtime
+    /// deprecation warnings) and API version annotations, with the concept
+    /// each one belongs to
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn map_deprecated_surface(path: String) -> Result<DeprecatedSurface, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+
+        let mut analyzer = SemanticAnalyzer::new()?;
+        let mut annotations = Vec::new();
+        for entry in WalkDir::new(project_path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let file_path = entry.path();
+            if !entry.file_type().is_file() || !Self::is_source_file(file_path) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(file_path) else {
+                continue;
+            };
+            if !DeprecationExtractor::has_markers(&content) {
+                continue;
+            }
+
+            let relative = paths::relative_to(&file_path.to_string_lossy(), project_path);
+            let concepts = analyzer.extract_file_concepts_sync(&relative, &content);
+            annotations.extend(DeprecationExtractor::extract(&relative, &content, &concepts));
+        }
+
+        Ok(DeprecationExtractor::summarize(annotations))
+    }
+
+    /// Find entities declared in more than one language, such as a Rust
+    /// struct, a TypeScript interface and a SQL table for the same record
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn map_shared_entities(path: String) -> Result<Vec<SharedDomainEntity>, ParseError> {
+        validation::require_directory(&path)?;
+        let project_path = Path::new(&path);
+
+        let mut analyzer = SemanticAnalyzer::new()?;
+        let mut concepts = Vec::new();
+        let mut sources = BTreeMap::new();
+        for entry in WalkDir::new(project_path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let file_path = entry.path();
+            if !entry.file_type().is_file() || !Self::is_source_file(file_path) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(file_path) else {
+                continue;
+            };
+
+            let relative = paths::relative_to(&file_path.to_string_lossy(), project_path);
+            concepts.extend(analyzer.extract_file_concepts_sync(&relative, &content));
+            sources.insert(relative, content);
+        }
+
+        let config = crate::types::AnalysisConfig::default();
+        Ok(DomainEntityUnifier::unify(
+            &concepts,
+            |file_path| config.detect_language_from_path(file_path),
+            |file_path| sources.get(file_path).cloned(),
+        ))
+    }
+
+    /// Concept graph of a project's source files, for projects not learned yet
+    fn extract_concept_graph(project_path: &Path) -> GraphStore {
+        let Ok(mut analyzer) = SemanticAnalyzer::new() else {
+            return GraphStore::default();
+        };
+        let mut concepts = Vec::new();
+        for entry in WalkDir::new(project_path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let file_path = entry.path();
+            if !entry.file_type().is_file() || !Self::is_source_file(file_path) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(file_path) else {
+                continue;
+            };
+            let relative = paths::relative_to(&file_path.to_string_lossy(), project_path);
+            concepts.extend(analyzer.extract_unstored(&relative, &content));
+        }
+        GraphStore::build(&concepts)
+    }
+
+    /// Group key directories into architectural layers, in a fixed layer order
+    fn group_layers(key_directories: &[KeyDirectory]) -> Vec<ArchitectureLayer> {
+        let mut layers: Vec<ArchitectureLayer> = ["presentation", "api", "domain", "data", "shared"]
+            .iter()
+            .map(|name| ArchitectureLayer {
+                name: name.to_string(),
+                directories: Vec::new(),
+                file_count: 0,
+            })
+            .collect();
+
+        for dir in key_directories {
+            let layer = match dir.dir_type.as_str() {
+                "components" | "views" | "pages" => "presentation",
+                "api" | "routes" | "controllers" | "middleware" => "api",
+                "services" | "auth" => "domain",
+                "models" => "data",
+                _ => "shared",
+            };
+            if let Some(layer) = layers.iter_mut().find(|l| l.name == layer) {
+                layer.directories.push(dir.path.clone());
+                layer.file_count += dir.file_count;
+            }
+        }
+
+        layers.retain(|layer| !layer.directories.is_empty());
+        layers
+    }
+
+    /// Count source files under the project, in total and per extension
+    fn count_source_files(project_path: &Path) -> (u32, BTreeMap<String, u32>) {
+        let mut total = 0;
+        let mut by_extension = BTreeMap::new();
+        for entry in WalkDir::new(project_path)
+            .into_iter()
+            .filter_entry(|e| !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let file_path = entry.path();
+            if !entry.file_type().is_file() || !Self::is_source_file(file_path) {
+                continue;
+            }
+            if let Some(extension) = file_path.extension().and_then(|e| e.to_str()) {
+                total += 1;
+                *by_extension.entry(extension.to_string()).or_insert(0) += 1;
+            }
+        }
+        (total, by_extension)
+    }
+
+    fn is_source_file(path: &Path) -> bool {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| {
+                ["ts", "tsx", "js", "jsx", "py", "rs", "go", "java", "c", "cpp", "cs", "php", "sql", "svelte"]
+                    .contains(&ext)
+            })
+    }
+
     /// Count files in directory with depth limit
     fn count_files_in_directory(dir_path: &Path, max_depth: u32, current_depth: u32) -> Result<u32, ParseError> {
         if current_depth >= max_depth {
@@ -339,7 +884,7 @@ impl BlueprintAnalyzer {
             let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
 
             // Skip common ignore patterns
-            if ["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", "target"].contains(&file_name) {
+            if IGNORED_DIRS.contains(&file_name) {
                 continue;
             }
 
@@ -372,7 +917,7 @@ impl BlueprintAnalyzer {
             let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
 
             // Skip common ignore patterns
-            if ["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", "target"].contains(&file_name) {
+            if IGNORED_DIRS.contains(&file_name) {
                 continue;
             }
 
@@ -382,10 +927,10 @@ impl BlueprintAnalyzer {
             } else if path.is_file() {
                 // Only include source code files
                 if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                    if ["ts", "tsx", "js", "jsx", "py", "rs", "go", "java", "c", "cpp", "cs"].contains(&ext) {
-                        if let Ok(relative) = path.strip_prefix(project_root) {
-                            files.push(relative.to_string_lossy().to_string());
-                        }
+                    if ["ts", "tsx", "js", "jsx", "py", "rs", "go", "java", "c", "cpp", "cs"].contains(&ext)
+                        && path.starts_with(project_root)
+                    {
+                        files.push(paths::relative_to(&path.to_string_lossy(), project_root));
                     }
                 }
             }
@@ -394,3 +939,58 @@ impl BlueprintAnalyzer {
         Ok(files)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_project_blueprint_schema() {
+        let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/polyglot").to_string();
+        let blueprint = BlueprintAnalyzer::get_project_blueprint(fixture).await.unwrap();
+
+        assert_eq!(blueprint.schema_version, BLUEPRINT_SCHEMA_VERSION);
+        assert_eq!(blueprint.metrics.total_files, 4);
+        assert_eq!(blueprint.metrics.files_by_extension.get("ts"), Some(&2));
+        assert_eq!(blueprint.metrics.module_count, blueprint.modules.len() as u32);
+        // Modules are clustered from the fixture's concepts
+        assert!(blueprint.modules.iter().any(|module| !module.concepts.is_empty()));
+        let domain = blueprint.layers.iter().find(|l| l.name == "domain").unwrap();
+        assert_eq!(domain.directories, vec!["src/services".to_string()]);
+        // Every concept's directory gets a coupling aggregate
+        assert!(blueprint.coupling.directories.iter().any(|directory| directory.path == "src/services"));
+
+        // The serialized form uses the same camelCase keys as the NAPI object
+        let json = serde_json::to_value(&blueprint).unwrap();
+        for key in ["schemaVersion", "entryPoints", "dataModels", "dependencies", "metrics", "coupling"] {
+            assert!(json.get(key).is_some(), "missing {}", key);
+        }
+        let restored: ProjectBlueprint = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.layers.len(), blueprint.layers.len());
+    }
+
+    #[tokio::test]
+    async fn test_operational_structure_links_services_to_dockerfiles() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("api")).unwrap();
+        fs::create_dir_all(root.join("infra")).unwrap();
+        fs::write(root.join("docker-compose.yml"), "services:\n  api:\n    build: ./api\n  db:\n    image: postgres\n")
+            .unwrap();
+        fs::write(
+            root.join("api/Dockerfile"),
+            "FROM node:20 AS build\nRUN npm ci\n\nFROM node:20-slim AS runtime\nCOPY --from=build /app /app\n",
+        )
+        .unwrap();
+        fs::write(root.join("infra/main.tf"), "resource \"aws_ecs_service\" \"api\" {\n  name = \"api\"\n}\n").unwrap();
+
+        let structure = BlueprintAnalyzer::map_operational_structure(root.to_string_lossy().to_string()).await.unwrap();
+        assert_eq!(structure.container_stages.len(), 2);
+        assert_eq!(structure.infrastructure.len(), 1);
+        let api = structure.services.iter().find(|s| s.name == "api").unwrap();
+        assert_eq!(api.relationships["built_from"], "runtime");
+        assert_eq!(api.metadata["dockerfile"], "api/Dockerfile");
+        let db = structure.services.iter().find(|s| s.name == "db").unwrap();
+        assert!(!db.relationships.contains_key("built_from"));
+    }
+}
@@ -0,0 +1,327 @@
+//! Compact, ranked summary of a project blueprint
+//!
+//! A full [`ProjectBlueprint`] lists every module, endpoint and data model,
+//! which is more than an agent's prompt can hold. [`BlueprintSummarizer`]
+//! keeps the few items worth saying first: the most certain frameworks, the
+//! patterns learned with the most confidence over the most occurrences, an
+//! architecture style, and the biggest risks (rule and check findings,
+//! coupling hot spots and incohesive classes), each list cut to `max_items`.
+//! The totals say how much was left out, so a host knows when to fetch the
+//! full blueprint.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::ProjectBlueprint;
+use crate::patterns::types::Pattern;
+use crate::patterns::PatternNamespace;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Items per list when a host doesn't say
+pub const DEFAULT_SUMMARY_ITEMS: u32 = 5;
+
+/// Items per list at most
+pub const MAX_SUMMARY_ITEMS: u32 = 50;
+
+/// Coupling at which a concept counts as a risk; twice this is the most severe
+const RISKY_COUPLING: u32 = 10;
+
+/// Cohesion below which a class or module counts as a risk
+const RISKY_COHESION: f64 = 0.3;
+
+/// One ranked entry of a summary list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct SummaryItem {
+    pub name: String,
+    /// One short clause to follow the name, e.g. "version 18.2.0" or "12 occurrences"
+    pub detail: String,
+    /// Confidence of a framework or pattern, severity of a risk; 0.0 to 1.0
+    pub score: f64,
+}
+
+/// The most telling parts of a blueprint, ranked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct BlueprintSummary {
+    pub project_path: String,
+    /// One sentence, e.g. "Layered project of 120 source files (mostly ts)
+    /// using react and express, in 6 modules."
+    pub headline: String,
+    /// 'monorepo', 'layered', 'modular', 'service' or 'unknown'
+    pub architecture_style: String,
+    pub architecture_confidence: f64,
+    /// Most confident first
+    pub frameworks: Vec<SummaryItem>,
+    /// Naming, structural and implementation patterns, weighing confidence
+    /// by occurrences, strongest first
+    pub dominant_patterns: Vec<SummaryItem>,
+    /// Most severe first
+    pub risks: Vec<SummaryItem>,
+    /// Items before cutting each list to `max_items`
+    pub framework_count: u32,
+    pub pattern_count: u32,
+    pub risk_count: u32,
+}
+
+pub struct BlueprintSummarizer;
+
+impl BlueprintSummarizer {
+    /// Summarize `blueprint`, taking dominant patterns and rule findings from
+    /// `patterns`; each list keeps at most `max_items` items
+    pub fn summarize(blueprint: &ProjectBlueprint, patterns: &[Pattern], max_items: u32) -> BlueprintSummary {
+        let max_items = max_items.clamp(1, MAX_SUMMARY_ITEMS) as usize;
+
+        let mut frameworks: Vec<SummaryItem> = blueprint
+            .frameworks
+            .iter()
+            .map(|framework| SummaryItem {
+                name: framework.name.clone(),
+                detail: match &framework.version {
+                    Some(version) => format!("version {}", version),
+                    None => Self::count(framework.evidence.len(), "piece of evidence", "pieces of evidence"),
+                },
+                score: framework.confidence,
+            })
+            .collect();
+        Self::rank(&mut frameworks, |item| item.score);
+
+        let mut dominant: Vec<(f64, SummaryItem)> = Vec::new();
+        let mut risks: Vec<SummaryItem> = Vec::new();
+        for pattern in patterns {
+            match PatternNamespace::of(&pattern.pattern_type) {
+                PatternNamespace::Activity => {}
+                PatternNamespace::Violation => risks.push(SummaryItem {
+                    name: pattern.description.clone(),
+                    detail: Self::count(pattern.frequency as usize, "occurrence", "occurrences"),
+                    score: Self::weight(pattern).min(1.0),
+                }),
+                _ => dominant.push((
+                    Self::weight(pattern),
+                    SummaryItem {
+                        name: pattern.description.clone(),
+                        detail: format!(
+                            "{}, {}",
+                            pattern.pattern_type,
+                            Self::count(pattern.frequency as usize, "occurrence", "occurrences")
+                        ),
+                        score: pattern.confidence,
+                    },
+                )),
+            }
+        }
+        dominant.sort_by(|(a, a_item), (b, b_item)| {
+            b.partial_cmp(a).unwrap_or(Ordering::Equal).then_with(|| a_item.name.cmp(&b_item.name))
+        });
+        let mut dominant: Vec<SummaryItem> = dominant.into_iter().map(|(_, item)| item).collect();
+
+        for concept in &blueprint.coupling.most_coupled {
+            if concept.coupling >= RISKY_COUPLING {
+                risks.push(SummaryItem {
+                    name: format!("{} `{}` is highly coupled", concept.concept_type, concept.name),
+                    detail: format!("references {} other concepts, in {}", concept.coupling, concept.file_path),
+                    score: (concept.coupling as f64 / (2 * RISKY_COUPLING) as f64).min(1.0),
+                });
+            }
+        }
+        for concept in &blueprint.coupling.least_cohesive {
+            if let Some(cohesion) = concept.cohesion.filter(|cohesion| *cohesion < RISKY_COHESION) {
+                risks.push(SummaryItem {
+                    name: format!("{} `{}` has low cohesion", concept.concept_type, concept.name),
+                    detail: format!("cohesion {:.2}, in {}", cohesion, concept.file_path),
+                    score: 1.0 - cohesion,
+                });
+            }
+        }
+        Self::rank(&mut risks, |item| item.score);
+
+        let (architecture_style, architecture_confidence) = Self::architecture_style(blueprint);
+        let framework_count = frameworks.len() as u32;
+        let pattern_count = dominant.len() as u32;
+        let risk_count = risks.len() as u32;
+        frameworks.truncate(max_items);
+        dominant.truncate(max_items);
+        risks.truncate(max_items);
+
+        BlueprintSummary {
+            project_path: blueprint.project_path.clone(),
+            headline: Self::headline(blueprint, architecture_style, &frameworks),
+            architecture_style: architecture_style.to_string(),
+            architecture_confidence,
+            frameworks,
+            dominant_patterns: dominant,
+            risks,
+            framework_count,
+            pattern_count,
+            risk_count,
+        }
+    }
+
+    /// A style and how sure it is: a workspace of several packages, three or
+    /// more architecture layers, three or more loosely dependent modules, or
+    /// at least an HTTP API
+    fn architecture_style(blueprint: &ProjectBlueprint) -> (&'static str, f64) {
+        let members = blueprint.dependencies.workspace_members.len();
+        let layers = blueprint.layers.len();
+        let modules = blueprint.modules.len();
+        let dependencies: usize = blueprint.modules.iter().map(|module| module.dependencies.len()).sum();
+        if members >= 2 {
+            ("monorepo", 0.9)
+        } else if layers >= 3 {
+            ("layered", ((layers + 4) as f64 / 10.0).min(0.9))
+        } else if modules >= 3 && dependencies <= modules {
+            ("modular", 0.7)
+        } else if !blueprint.endpoints.is_empty() {
+            ("service", 0.6)
+        } else {
+            ("unknown", 0.0)
+        }
+    }
+
+    fn headline(blueprint: &ProjectBlueprint, style: &str, frameworks: &[SummaryItem]) -> String {
+        let mut letters = style.chars();
+        let subject = match (style, letters.next()) {
+            ("unknown", _) | (_, None) => "Project".to_string(),
+            (_, Some(first)) => format!("{}{} project", first.to_uppercase(), letters.as_str()),
+        };
+        let mut headline = format!(
+            "{} of {}",
+            subject,
+            Self::count(blueprint.metrics.total_files as usize, "source file", "source files")
+        );
+        let main_extension = blueprint
+            .metrics
+            .files_by_extension
+            .iter()
+            .max_by(|(a_ext, a), (b_ext, b)| a.cmp(b).then_with(|| b_ext.cmp(a_ext)));
+        if let Some((extension, _)) = main_extension {
+            headline.push_str(&format!(" (mostly {})", extension));
+        }
+        if !frameworks.is_empty() {
+            let names: Vec<&str> = frameworks.iter().take(3).map(|item| item.name.as_str()).collect();
+            headline.push_str(&format!(" using {}", names.join(", ")));
+        }
+        if !blueprint.modules.is_empty() {
+            headline.push_str(&format!(", in {}", Self::count(blueprint.modules.len(), "module", "modules")));
+        }
+        headline.push('.');
+        headline
+    }
+
+    /// Confidence scaled by how often the pattern occurs, so a certain
+    /// pattern seen once ranks below a likely one seen everywhere
+    fn weight(pattern: &Pattern) -> f64 {
+        pattern.confidence * (1.0 + pattern.frequency as f64).ln()
+    }
+
+    /// Highest score first, then by name
+    fn rank(items: &mut [SummaryItem], score: impl Fn(&SummaryItem) -> f64) {
+        items.sort_by(|a, b| {
+            score(b).partial_cmp(&score(a)).unwrap_or(Ordering::Equal).then_with(|| a.name.cmp(&b.name))
+        });
+    }
+
+    fn count(n: usize, one: &str, many: &str) -> String {
+        format!("{} {}", n, if n == 1 { one } else { many })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{
+        ArchitectureLayer, BlueprintMetrics, ConceptCoupling, CouplingSummary, FrameworkInfo, BLUEPRINT_SCHEMA_VERSION,
+    };
+    use std::collections::BTreeMap;
+
+    fn pattern(id: &str, pattern_type: &str, frequency: u32, confidence: f64) -> Pattern {
+        Pattern {
+            id: id.to_string(),
+            pattern_type: pattern_type.to_string(),
+            description: id.to_string(),
+            frequency,
+            confidence,
+            examples: vec![],
+            contexts: vec![],
+        }
+    }
+
+    fn coupling(name: &str, coupling: u32, cohesion: Option<f64>) -> ConceptCoupling {
+        ConceptCoupling {
+            concept_id: name.to_string(),
+            name: name.to_string(),
+            concept_type: "class".to_string(),
+            file_path: format!("src/{}.ts", name),
+            coupling,
+            cohesion,
+        }
+    }
+
+    #[test]
+    fn test_summary_ranks_and_cuts_each_list() {
+        let framework = |name: &str, version: Option<&str>, confidence: f64| FrameworkInfo {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+            confidence,
+            evidence: vec!["package.json".to_string()],
+        };
+        let layer = |name: &str| ArchitectureLayer { name: name.to_string(), directories: vec![], file_count: 4 };
+        let blueprint = ProjectBlueprint {
+            schema_version: BLUEPRINT_SCHEMA_VERSION,
+            project_path: "/work/shop".to_string(),
+            frameworks: vec![
+                framework("express", None, 0.7),
+                framework("react", Some("18.2.0"), 0.95),
+                framework("jest", None, 0.5),
+            ],
+            modules: vec![],
+            layers: vec![layer("presentation"), layer("api"), layer("data")],
+            entry_points: vec![],
+            endpoints: vec![],
+            data_models: Default::default(),
+            dependencies: Default::default(),
+            metrics: BlueprintMetrics {
+                total_files: 120,
+                files_by_extension: BTreeMap::from([("ts".to_string(), 90), ("css".to_string(), 30)]),
+                ..Default::default()
+            },
+            tag_groups: vec![],
+            shared_entities: vec![],
+            coupling: CouplingSummary {
+                most_coupled: vec![coupling("OrderService", 25, None), coupling("Cart", 4, None)],
+                least_cohesive: vec![coupling("Utils", 3, Some(0.1)), coupling("Order", 2, Some(0.8))],
+                ..Default::default()
+            },
+            events: Default::default(),
+        };
+        let patterns = vec![
+            pattern("camelCase functions", "naming_function_camelCase", 200, 0.8),
+            pattern("factory", "implementation_factory", 1, 0.99),
+            pattern("repositories", "structural_repository", 30, 0.9),
+            pattern("no console.log", "custom_rule_violation", 12, 0.3),
+            pattern("change_modify", "change_modify", 500, 1.0),
+        ];
+
+        let summary = BlueprintSummarizer::summarize(&blueprint, &patterns, 2);
+        assert_eq!(
+            summary.headline,
+            "Layered project of 120 source files (mostly ts) using react, express."
+        );
+        assert_eq!((summary.architecture_style.as_str(), summary.architecture_confidence), ("layered", 0.7));
+
+        let names = |items: &[SummaryItem]| items.iter().map(|item| item.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&summary.frameworks), vec!["react", "express"]);
+        assert_eq!(summary.frameworks[0].detail, "version 18.2.0");
+        assert_eq!(names(&summary.dominant_patterns), vec!["camelCase functions", "repositories"]);
+        assert_eq!(summary.dominant_patterns[1].detail, "structural_repository, 30 occurrences");
+        assert_eq!(
+            names(&summary.risks),
+            vec!["class `OrderService` is highly coupled", "class `Utils` has low cohesion"]
+        );
+        assert_eq!((summary.framework_count, summary.pattern_count, summary.risk_count), (3, 3, 3));
+    }
+}
@@ -0,0 +1,553 @@
+//! Build orchestration and task detection
+//!
+//! Lists the tasks a project defines for its build tools, with the command
+//! that runs each one:
+//! - npm scripts in `package.json`, run with the package manager the project uses
+//! - Makefile targets, described by `## ..` after the target or a comment above it
+//! - cargo-make tasks in `Makefile.toml`
+//! - just recipes, except private ones
+//! - Gradle tasks registered in `build.gradle(.kts)` and the ones its plugins add
+//! - Bazel targets in `BUILD`/`BUILD.bazel` files of a Bazel workspace
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::TestInventoryScanner;
+use crate::types::ParseError;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use toml::Value as TomlValue;
+use walkdir::WalkDir;
+
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", ".venv", "target", "vendor"];
+
+/// Build files larger than this are not read
+const MAX_BUILD_FILE_SIZE: u64 = 256 * 1024;
+
+/// Files at the root that make `BUILD` files Bazel packages
+const BAZEL_WORKSPACE_FILES: &[&str] = &["WORKSPACE", "WORKSPACE.bazel", "MODULE.bazel"];
+
+/// Tasks a Gradle plugin adds without the build file declaring them
+const GRADLE_PLUGIN_TASKS: &[&str] = &["build", "test", "clean"];
+
+/// Task name prefixes and the category they put a task in
+const CATEGORIES: &[(&str, &[&str])] = &[
+    ("test", &["test", "tests", "spec", "e2e", "coverage", "cov"]),
+    ("build", &["build", "compile", "bundle", "dist", "package", "assemble"]),
+    ("lint", &["lint", "check", "typecheck", "clippy", "vet"]),
+    ("format", &["fmt", "format", "prettier"]),
+    ("run", &["start", "dev", "serve", "run", "watch"]),
+    ("clean", &["clean", "clobber"]),
+    ("deploy", &["deploy", "release", "publish"]),
+];
+
+/// A task defined for a build tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct BuildTask {
+    /// Script, target, recipe or task name as the tool knows it
+    pub name: String,
+    /// 'npm', 'yarn', 'pnpm', 'bun', 'make', 'cargo-make', 'just', 'gradle' or 'bazel'
+    pub tool: String,
+    /// Command line that runs the task
+    pub command: String,
+    /// Directory to run `command` in, relative to the project root; empty for the root
+    pub working_directory: String,
+    /// First command the task executes, when the file spells it out
+    pub script: Option<String>,
+    pub description: Option<String>,
+    /// 'build', 'test', 'lint', 'format', 'run', 'clean', 'deploy' or 'other'
+    pub category: String,
+    pub file_path: String,
+    pub line: u32,
+}
+
+impl BuildTask {
+    fn new(name: &str, tool: &str, command: String, working_directory: &str, file_path: &str, line: u32) -> Self {
+        BuildTask {
+            name: name.to_string(),
+            tool: tool.to_string(),
+            command,
+            working_directory: working_directory.to_string(),
+            script: None,
+            description: None,
+            category: BuildTaskDetector::categorize(name).to_string(),
+            file_path: file_path.to_string(),
+            line,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct BuildTaskSummary {
+    /// Tools with at least one task, in the order they were found
+    pub tools: Vec<String>,
+    pub tasks: Vec<BuildTask>,
+    /// How to build the project from its root: the top-level build task, or
+    /// the default for its manifest
+    pub build_command: Option<String>,
+    /// How to run the tests from the project root: the top-level test task, or
+    /// the command of the test framework with the most cases
+    pub test_command: Option<String>,
+}
+
+pub struct BuildTaskDetector;
+
+impl BuildTaskDetector {
+    /// List the build tasks defined under `root`
+    pub fn scan(root: &Path) -> Result<BuildTaskSummary, ParseError> {
+        let bazel_workspace = BAZEL_WORKSPACE_FILES.iter().any(|file| root.join(file).is_file());
+        let mut tasks = Vec::new();
+
+        for entry in WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if !entry.file_type().is_file() || entry.metadata().map(|m| m.len() > MAX_BUILD_FILE_SIZE).unwrap_or(true) {
+                continue;
+            }
+            let file_name = entry.file_name().to_str().unwrap_or("");
+            let is_build_file = matches!(
+                file_name,
+                "package.json" | "Makefile" | "makefile" | "GNUmakefile" | "Makefile.toml" | "justfile" | "Justfile"
+                    | ".justfile" | "build.gradle" | "build.gradle.kts" | "BUILD.bazel"
+            ) || (file_name == "BUILD" && bazel_workspace);
+            if !is_build_file {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(path) else { continue };
+
+            let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+            let directory = relative.rsplit_once('/').map(|(directory, _)| directory).unwrap_or("");
+            let mut found = match file_name {
+                "package.json" => Self::npm_scripts(&relative, &content, &Self::package_manager(root, directory, &content)),
+                "Makefile.toml" => Self::cargo_make_tasks(&relative, &content),
+                "build.gradle" | "build.gradle.kts" => Self::gradle_tasks(&relative, &content, root),
+                "BUILD" | "BUILD.bazel" => Self::bazel_targets(&relative, &content),
+                "Makefile" | "makefile" | "GNUmakefile" => Self::make_targets(&relative, &content),
+                _ => Self::just_recipes(&relative, &content),
+            };
+            found.sort_by_key(|task| task.line);
+            tasks.extend(found);
+        }
+
+        let mut tools: Vec<String> = Vec::new();
+        for task in &tasks {
+            if !tools.contains(&task.tool) {
+                tools.push(task.tool.clone());
+            }
+        }
+        let build_command = Self::top_level_command(&tasks, "build").or_else(|| Self::manifest_build_command(root));
+        let test_command = Self::top_level_command(&tasks, "test").or_else(|| {
+            let inventory = TestInventoryScanner::scan(root).ok()?;
+            inventory.primary_framework().map(|framework| framework.command.clone())
+        });
+
+        Ok(BuildTaskSummary { tools, tasks, build_command, test_command })
+    }
+
+    /// Category of a task from the first word of its name: `test:unit` is a test task
+    pub fn categorize(name: &str) -> &'static str {
+        let first_word = name
+            .split(|c: char| !c.is_alphanumeric())
+            .find(|word| !word.is_empty())
+            .unwrap_or("")
+            .to_lowercase();
+        CATEGORIES
+            .iter()
+            .find(|(_, prefixes)| prefixes.contains(&first_word.as_str()))
+            .map(|(category, _)| *category)
+            .unwrap_or("other")
+    }
+
+    /// The closest task to the root in `category`, preferring the one named
+    /// after it, as a command that runs from the root
+    fn top_level_command(tasks: &[BuildTask], category: &str) -> Option<String> {
+        let depth = |task: &BuildTask| match task.working_directory.as_str() {
+            "" => 0,
+            directory => directory.split('/').count(),
+        };
+        let task = tasks
+            .iter()
+            .filter(|task| task.category == category)
+            .min_by_key(|task| (depth(task), task.name != category))?;
+        Some(match task.working_directory.as_str() {
+            "" => task.command.clone(),
+            directory => format!("cd {} && {}", directory, task.command),
+        })
+    }
+
+    fn manifest_build_command(root: &Path) -> Option<String> {
+        [("Cargo.toml", "cargo build"), ("go.mod", "go build ./..."), ("pom.xml", "mvn package")]
+            .iter()
+            .find(|(manifest, _)| root.join(manifest).is_file())
+            .map(|(_, command)| command.to_string())
+    }
+
+    /// `packageManager` of the package.json, else the lockfile next to it or at the root
+    fn package_manager(root: &Path, directory: &str, content: &str) -> String {
+        let declared = serde_json::from_str::<JsonValue>(content)
+            .ok()
+            .and_then(|json| json.get("packageManager").and_then(JsonValue::as_str).map(str::to_string));
+        if let Some(name) = declared.as_deref().and_then(|d| d.split('@').next()).filter(|n| !n.is_empty()) {
+            return name.to_string();
+        }
+        let lockfiles = [("pnpm-lock.yaml", "pnpm"), ("yarn.lock", "yarn"), ("bun.lockb", "bun"), ("bun.lock", "bun")];
+        for dir in [root.join(directory), root.to_path_buf()] {
+            if let Some((_, manager)) = lockfiles.iter().find(|(lockfile, _)| dir.join(lockfile).is_file()) {
+                return manager.to_string();
+            }
+        }
+        "npm".to_string()
+    }
+
+    fn npm_scripts(file_path: &str, content: &str, manager: &str) -> Vec<BuildTask> {
+        let Ok(json) = serde_json::from_str::<JsonValue>(content) else { return Vec::new() };
+        let Some(scripts) = json.get("scripts").and_then(JsonValue::as_object) else { return Vec::new() };
+        let directory = file_path.rsplit_once('/').map(|(directory, _)| directory).unwrap_or("");
+        let scripts_line = content.lines().position(|line| line.contains("\"scripts\"")).unwrap_or(0);
+
+        scripts
+            .iter()
+            .map(|(name, script)| {
+                let command = match (manager, name.as_str()) {
+                    ("npm", "test" | "start") => format!("npm {}", name),
+                    ("yarn", _) => format!("yarn {}", name),
+                    _ => format!("{} run {}", manager, name),
+                };
+                let key = format!("\"{}\"", name);
+                let line = content
+                    .lines()
+                    .enumerate()
+                    .skip(scripts_line)
+                    .find(|(_, line)| line.trim_start().starts_with(&key))
+                    .map_or(scripts_line, |(index, _)| index);
+                let mut task = BuildTask::new(name, manager, command, directory, file_path, line as u32 + 1);
+                task.script = script.as_str().map(str::to_string);
+                task
+            })
+            .collect()
+    }
+
+    fn make_targets(file_path: &str, content: &str) -> Vec<BuildTask> {
+        let lines: Vec<&str> = content.lines().collect();
+        let directory = file_path.rsplit_once('/').map(|(directory, _)| directory).unwrap_or("");
+        let phony: HashSet<&str> = lines
+            .iter()
+            .filter_map(|line| line.strip_prefix(".PHONY:"))
+            .flat_map(str::split_whitespace)
+            .collect();
+        let mut seen = HashSet::new();
+        let mut tasks = Vec::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let Some(targets) = patterns().make_rule.captures(line).and_then(|c| c.get(1)) else { continue };
+            let description = line
+                .split_once("##")
+                .map(|(_, description)| description.trim().to_string())
+                .filter(|description| !description.is_empty())
+                .or_else(|| Self::comment_above(&lines, index));
+            let script = Self::first_body_line(&lines, index, |body| body.starts_with('\t'));
+
+            // Targets with a dot or slash are files unless declared phony
+            for name in targets.as_str().split_whitespace() {
+                if (!phony.contains(name) && name.contains(['.', '/'])) || !seen.insert(name) {
+                    continue;
+                }
+                let mut task = BuildTask::new(name, "make", format!("make {}", name), directory, file_path, index as u32 + 1);
+                task.script = script.clone();
+                task.description = description.clone();
+                tasks.push(task);
+            }
+        }
+        tasks
+    }
+
+    fn cargo_make_tasks(file_path: &str, content: &str) -> Vec<BuildTask> {
+        let Ok(table) = toml::from_str::<toml::Table>(content) else { return Vec::new() };
+        let Some(definitions) = table.get("tasks").and_then(TomlValue::as_table) else { return Vec::new() };
+        let directory = file_path.rsplit_once('/').map(|(directory, _)| directory).unwrap_or("");
+
+        definitions
+            .iter()
+            .map(|(name, definition)| {
+                let header = format!("[tasks.{}]", name);
+                let line = content.lines().position(|line| line.trim() == header).map_or(1, |index| index as u32 + 1);
+                let mut task =
+                    BuildTask::new(name, "cargo-make", format!("cargo make {}", name), directory, file_path, line);
+                task.description = definition.get("description").and_then(TomlValue::as_str).map(str::to_string);
+                task.script = match (definition.get("command").and_then(TomlValue::as_str), definition.get("script")) {
+                    (Some(command), _) => {
+                        let args = definition.get("args").and_then(TomlValue::as_array).into_iter().flatten();
+                        let words: Vec<&str> = std::iter::once(command).chain(args.filter_map(TomlValue::as_str)).collect();
+                        Some(words.join(" "))
+                    }
+                    (None, Some(TomlValue::String(script))) => {
+                        script.lines().map(str::trim).find(|line| !line.is_empty()).map(str::to_string)
+                    }
+                    (None, Some(TomlValue::Array(lines))) => lines.iter().find_map(TomlValue::as_str).map(str::to_string),
+                    _ => None,
+                };
+                task
+            })
+            .collect()
+    }
+
+    fn just_recipes(file_path: &str, content: &str) -> Vec<BuildTask> {
+        let lines: Vec<&str> = content.lines().collect();
+        let directory = file_path.rsplit_once('/').map(|(directory, _)| directory).unwrap_or("");
+        let mut tasks = Vec::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let Some(c) = patterns().just_recipe.captures(line) else { continue };
+            let name = &c[1];
+            let private = index > 0 && lines[index - 1].trim() == "[private]";
+            if name.starts_with('_') || private {
+                continue;
+            }
+            let mut task = BuildTask::new(name, "just", format!("just {}", name), directory, file_path, index as u32 + 1);
+            task.script = Self::first_body_line(&lines, index, |body| body.starts_with([' ', '\t']));
+            task.description = Self::comment_above(&lines, index);
+            tasks.push(task);
+        }
+        tasks
+    }
+
+    /// Tasks the build file registers, then the standard tasks of the Java,
+    /// Kotlin or Android plugin it applies
+    fn gradle_tasks(file_path: &str, content: &str, root: &Path) -> Vec<BuildTask> {
+        let p = patterns();
+        let directory = file_path.rsplit_once('/').map(|(directory, _)| directory).unwrap_or("");
+        // A wrapper at the root runs subprojects by path: `./gradlew :app:build`
+        let (working_directory, prefix) = if root.join(directory).join("gradlew").is_file() {
+            (directory, "./gradlew ".to_string())
+        } else if root.join("gradlew").is_file() {
+            ("", format!("./gradlew :{}:", directory.replace('/', ":")))
+        } else {
+            (directory, "gradle ".to_string())
+        };
+
+        let mut tasks: Vec<BuildTask> = Vec::new();
+        let mut add = |name: &str, line: usize| {
+            if !tasks.iter().any(|task| task.name == name) {
+                let command = format!("{}{}", prefix, name);
+                tasks.push(BuildTask::new(name, "gradle", command, working_directory, file_path, line as u32 + 1));
+            }
+        };
+        for (index, line) in content.lines().enumerate() {
+            if let Some(c) = p.gradle_task.captures(line) {
+                add(c.iter().skip(1).flatten().next().map_or("", |m| m.as_str()), index);
+            }
+        }
+        if let Some(index) = content.lines().position(|line| p.gradle_plugin.is_match(line)) {
+            for name in GRADLE_PLUGIN_TASKS {
+                add(name, index);
+            }
+        }
+        tasks
+    }
+
+    /// Every named rule, built, tested or run by its label
+    fn bazel_targets(file_path: &str, content: &str) -> Vec<BuildTask> {
+        let p = patterns();
+        let package = file_path.rsplit_once('/').map(|(directory, _)| directory).unwrap_or("");
+        let mut rule: Option<String> = None;
+        let mut tasks = Vec::new();
+
+        for (index, line) in content.lines().enumerate() {
+            if let Some(c) = p.bazel_rule.captures(line) {
+                rule = Some(c[1].to_string());
+            }
+            let Some(c) = p.bazel_name.captures(line) else { continue };
+            let Some(kind) = rule.take() else { continue };
+            let (verb, category) = if kind.ends_with("_test") {
+                ("test", "test")
+            } else if kind.ends_with("_binary") {
+                ("run", "run")
+            } else {
+                ("build", "build")
+            };
+            let command = format!("bazel {} //{}:{}", verb, package, &c[1]);
+            let mut task = BuildTask::new(&c[1], "bazel", command, "", file_path, index as u32 + 1);
+            task.category = category.to_string();
+            task.description = Some(kind);
+            tasks.push(task);
+        }
+        tasks
+    }
+
+    /// Text of the `#` comment on the line above `index`
+    fn comment_above(lines: &[&str], index: usize) -> Option<String> {
+        let above = lines.get(index.checked_sub(1)?)?.trim();
+        if above.starts_with("#!") {
+            return None;
+        }
+        let comment = above.strip_prefix('#')?.trim_start_matches('#').trim();
+        (!comment.is_empty()).then(|| comment.to_string())
+    }
+
+    /// The first line of the body under `index`, without make's and just's `@` echo marker
+    fn first_body_line(lines: &[&str], index: usize, in_body: impl Fn(&str) -> bool) -> Option<String> {
+        let body = lines.get(index + 1).filter(|line| in_body(line))?;
+        let command = body.trim().trim_start_matches(['@', '-']).trim();
+        (!command.is_empty()).then(|| command.to_string())
+    }
+}
+
+struct Patterns {
+    make_rule: Regex,
+    just_recipe: Regex,
+    gradle_task: Regex,
+    gradle_plugin: Regex,
+    bazel_rule: Regex,
+    bazel_name: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let re = |pattern: &str| Regex::new(pattern).expect("valid build task regex");
+        Patterns {
+            // `build test: deps`, but not `CC := gcc` or `a::b`
+            make_rule: re(r"^([A-Za-z0-9_][\w.\-/]*(?:[ \t]+[A-Za-z0-9_][\w.\-/]*)*)[ \t]*:(?:$|[^=:])"),
+            // `build target='x': deps`, but not `set shell := [..]` or `alias b := build`
+            just_recipe: re(r"^@?([A-Za-z_][\w-]*)\b[^:]*:(?:$|[^=])"),
+            gradle_task: re(
+                r#"tasks\.(?:register|create)(?:<[\w.]+>)?\s*\(\s*["']([\w-]+)["']|^\s*task\b\s*\(?\s*["']?([\w-]+)|\bval\s+(\w+)\s+by\s+tasks\.(?:registering|creating)"#,
+            ),
+            gradle_plugin: re(
+                r#"["'`](?:java|java-library|application|org\.jetbrains\.kotlin\.jvm|com\.android\.application|com\.android\.library)["'`]|\bkotlin\s*\(\s*["']jvm["']\s*\)|^\s*(?:java|application)\s*$"#,
+            ),
+            bazel_rule: re(r"^([A-Za-z_]\w*)\s*\("),
+            bazel_name: re(r#"\bname\s*=\s*"([^"]+)""#),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(tasks: &[BuildTask]) -> Vec<&str> {
+        tasks.iter().map(|task| task.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_makefile_and_justfile_tasks() {
+        let makefile = "\
+CC := gcc
+VERSION = 1.0
+.PHONY: build test lint dist/app.tar
+
+# Compile everything
+build: deps
+\t@cargo build --release
+
+test lint: build ## Check the project
+\tcargo test
+
+main.o: main.c
+\t$(CC) -c main.c
+
+dist/app.tar: build
+\ttar cf $@ target
+";
+        let tasks = BuildTaskDetector::make_targets("Makefile", makefile);
+        assert_eq!(names(&tasks), vec!["build", "test", "lint", "dist/app.tar"]);
+        assert_eq!(tasks[0].script.as_deref(), Some("cargo build --release"));
+        assert_eq!(tasks[0].description.as_deref(), Some("Compile everything"));
+        assert_eq!((tasks[1].description.as_deref(), tasks[1].line), (Some("Check the project"), 9));
+        assert_eq!((tasks[1].category.as_str(), tasks[2].category.as_str()), ("test", "lint"));
+        assert_eq!(tasks[3].command, "make dist/app.tar");
+
+        let justfile = "\
+set shell := [\"bash\", \"-c\"]
+alias b := build
+
+# Build the workspace
+build profile='dev':
+    cargo build --profile {{profile}}
+
+[private]
+helper:
+    echo hidden
+
+_setup:
+    echo hidden
+
+@test *args: build
+    cargo test {{args}}
+";
+        let tasks = BuildTaskDetector::just_recipes("justfile", justfile);
+        assert_eq!(names(&tasks), vec!["build", "test"]);
+        assert_eq!(tasks[0].description.as_deref(), Some("Build the workspace"));
+        assert_eq!(tasks[0].script.as_deref(), Some("cargo build --profile {{profile}}"));
+        assert_eq!((tasks[1].command.as_str(), tasks[1].line), ("just test", 15));
+    }
+
+    #[test]
+    fn test_scan_lists_tasks_with_runnable_commands() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("web")).unwrap();
+        fs::create_dir_all(root.join("app")).unwrap();
+        fs::create_dir_all(root.join("services/api")).unwrap();
+        fs::write(
+            root.join("web/package.json"),
+            "{\n  \"name\": \"web\",\n  \"scripts\": {\n    \"build\": \"vite build\",\n    \"test\": \"vitest run\",\n    \"test:e2e\": \"playwright test\"\n  }\n}\n",
+        )
+        .unwrap();
+        fs::write(root.join("web/pnpm-lock.yaml"), "lockfileVersion: '9.0'\n").unwrap();
+        fs::write(
+            root.join("Makefile.toml"),
+            "[tasks.ci]\ndescription = \"Run CI\"\ncommand = \"cargo\"\nargs = [\"test\", \"--all\"]\n",
+        )
+        .unwrap();
+        fs::write(root.join("gradlew"), "#!/bin/sh\n").unwrap();
+        fs::write(
+            root.join("app/build.gradle.kts"),
+            "plugins {\n    id(\"application\")\n}\n\ntasks.register<Copy>(\"bundleAssets\") {\n}\n",
+        )
+        .unwrap();
+        fs::write(root.join("WORKSPACE"), "").unwrap();
+        fs::write(
+            root.join("services/api/BUILD"),
+            "load(\"@rules_go//go:def.bzl\", \"go_binary\", \"go_test\")\n\ngo_binary(\n    name = \"server\",\n)\n\ngo_test(name = \"server_test\")\n",
+        )
+        .unwrap();
+
+        let summary = BuildTaskDetector::scan(root).unwrap();
+        assert_eq!(summary.tools, vec!["cargo-make", "gradle", "bazel", "pnpm"]);
+
+        let gradle: Vec<&str> = summary.tasks.iter().filter(|t| t.tool == "gradle").map(|t| t.command.as_str()).collect();
+        assert_eq!(
+            gradle,
+            vec!["./gradlew :app:build", "./gradlew :app:test", "./gradlew :app:clean", "./gradlew :app:bundleAssets"]
+        );
+
+        let ci = summary.tasks.iter().find(|t| t.name == "ci").unwrap();
+        assert_eq!((ci.command.as_str(), ci.script.as_deref()), ("cargo make ci", Some("cargo test --all")));
+
+        let bazel: Vec<&str> = summary.tasks.iter().filter(|t| t.tool == "bazel").map(|t| t.command.as_str()).collect();
+        assert_eq!(bazel, vec!["bazel run //services/api:server", "bazel test //services/api:server_test"]);
+
+        let e2e = summary.tasks.iter().find(|t| t.name == "test:e2e").unwrap();
+        assert_eq!((e2e.command.as_str(), e2e.working_directory.as_str(), e2e.line), ("pnpm run test:e2e", "web", 6));
+        assert_eq!(e2e.category, "test");
+
+        assert_eq!(summary.build_command.as_deref(), Some("./gradlew :app:build"));
+        assert_eq!(summary.test_command.as_deref(), Some("./gradlew :app:test"));
+    }
+}
@@ -1,10 +1,77 @@
 //! Complexity analysis and metrics calculation
+//!
+//! Besides size and control-flow estimates, [`ComplexityAnalyzer`] measures
+//! from the relationship graph how coupled each concept is (the distinct
+//! concepts outside its own class or module it references) and how cohesive
+//! each class or module is (how many pairs of its methods reference each
+//! other or a common member). Both are kept in concept metadata and
+//! aggregated per directory for the blueprint's hot-spot map.
 
 #[cfg(feature = "napi-bindings")]
 use napi_derive::napi;
 
-use crate::types::{SemanticConcept, ComplexityMetrics};
-use std::collections::HashMap;
+use crate::analysis::{GraphStore, RelationshipKind};
+use crate::types::{paths, SemanticConcept, ComplexityMetrics};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Metadata key holding a concept's coupling
+pub const COUPLING_METADATA_KEY: &str = "coupling";
+
+/// Metadata key holding a class or module's cohesion
+pub const COHESION_METADATA_KEY: &str = "cohesion";
+
+/// Concepts listed per hot-spot list of a [`CouplingSummary`]
+const HOTSPOT_LIMIT: usize = 10;
+
+/// Member types whose references make up cohesion
+const METHOD_TYPES: &[&str] = &["function", "method", "constructor", "arrow_function"];
+
+/// Coupling and cohesion of one concept
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ConceptCoupling {
+    pub concept_id: String,
+    pub name: String,
+    pub concept_type: String,
+    pub file_path: String,
+    /// Distinct concepts outside its own class or module that it, or one of
+    /// its members, calls, imports, extends, implements or uses
+    pub coupling: u32,
+    /// For classes and modules with two or more methods: the share of method
+    /// pairs that reference each other or a common member, 0.0 to 1.0
+    pub cohesion: Option<f64>,
+}
+
+/// Coupling and cohesion of the concepts in one directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct DirectoryCoupling {
+    /// Relative to the project root; "." for the root itself
+    pub path: String,
+    pub concept_count: u32,
+    pub average_coupling: f64,
+    pub max_coupling: u32,
+    pub average_cohesion: Option<f64>,
+}
+
+/// Coupling and cohesion across a project
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct CouplingSummary {
+    pub average_coupling: f64,
+    pub average_cohesion: Option<f64>,
+    /// Most coupled concepts, most coupled first
+    pub most_coupled: Vec<ConceptCoupling>,
+    /// Least cohesive classes and modules, least cohesive first
+    pub least_cohesive: Vec<ConceptCoupling>,
+    /// In path order
+    pub directories: Vec<DirectoryCoupling>,
+}
 
 /// Analyzer for calculating code complexity metrics
 #[cfg_attr(feature = "napi-bindings", napi)]
@@ -136,8 +203,161 @@ impl ComplexityAnalyzer {
         }
     }
 
+    /// Coupling and cohesion of every concept in a graph, in id order
+    pub fn measure_coupling(graph: &GraphStore) -> Vec<ConceptCoupling> {
+        let contains = RelationshipKind::Contains.as_str();
+        let documents = RelationshipKind::Documents.as_str();
+        let mut container_of: HashMap<&str, &str> = HashMap::new();
+        let mut members_of: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        let mut references: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        for edge in graph.graph().all_edges() {
+            if edge.kind == contains {
+                container_of.insert(&edge.to, &edge.from);
+                members_of.entry(&edge.from).or_default().insert(&edge.to);
+            } else if edge.kind != documents {
+                references.entry(&edge.from).or_default().insert(&edge.to);
+            }
+        }
+        let no_members = BTreeSet::new();
+
+        graph
+            .nodes()
+            .map(|node| {
+                let id = node.id.as_str();
+                let members = members_of.get(id).unwrap_or(&no_members);
+                // A class answers for its members; a member's own class is not external to it
+                let (sources, scope): (Vec<&str>, BTreeSet<&str>) = if !members.is_empty() {
+                    (std::iter::once(id).chain(members.iter().copied()).collect(), members | &BTreeSet::from([id]))
+                } else if let Some(&container) = container_of.get(id) {
+                    let siblings = members_of.get(container).unwrap_or(&no_members);
+                    (vec![id], siblings | &BTreeSet::from([container, id]))
+                } else {
+                    (vec![id], BTreeSet::from([id]))
+                };
+                let external: BTreeSet<&str> = sources
+                    .iter()
+                    .filter_map(|source| references.get(source))
+                    .flatten()
+                    .copied()
+                    .filter(|target| !scope.contains(target))
+                    .collect();
+
+                let methods: Vec<&str> = members
+                    .iter()
+                    .copied()
+                    .filter(|member| {
+                        graph.node(member).is_some_and(|node| METHOD_TYPES.contains(&node.concept_type.as_str()))
+                    })
+                    .collect();
+                let cohesion = (methods.len() >= 2).then(|| {
+                    let used: Vec<BTreeSet<&str>> = methods
+                        .iter()
+                        .map(|method| {
+                            let targets = references.get(method).into_iter().flatten().copied();
+                            targets.filter(|target| members.contains(target)).collect()
+                        })
+                        .collect();
+                    let mut pairs = 0;
+                    let mut connected = 0;
+                    for (a, (method_a, used_a)) in methods.iter().zip(&used).enumerate() {
+                        for (method_b, used_b) in methods.iter().zip(&used).skip(a + 1) {
+                            pairs += 1;
+                            if used_a.contains(method_b) || used_b.contains(method_a) || !used_a.is_disjoint(used_b) {
+                                connected += 1;
+                            }
+                        }
+                    }
+                    connected as f64 / pairs as f64
+                });
+
+                ConceptCoupling {
+                    concept_id: node.id.clone(),
+                    name: node.name.clone(),
+                    concept_type: node.concept_type.clone(),
+                    file_path: node.file_path.clone(),
+                    coupling: external.len() as u32,
+                    cohesion,
+                }
+            })
+            .collect()
+    }
+
+    /// Record each concept's coupling, and cohesion where it has one, in its metadata
+    pub fn annotate_coupling<'a>(
+        concepts: impl IntoIterator<Item = &'a mut SemanticConcept>,
+        measures: &[ConceptCoupling],
+    ) {
+        let by_id: HashMap<&str, &ConceptCoupling> =
+            measures.iter().map(|measure| (measure.concept_id.as_str(), measure)).collect();
+        for concept in concepts {
+            concept.metadata.remove(COHESION_METADATA_KEY);
+            let Some(measure) = by_id.get(concept.id.as_str()) else {
+                concept.metadata.remove(COUPLING_METADATA_KEY);
+                continue;
+            };
+            concept.metadata.insert(COUPLING_METADATA_KEY.to_string(), measure.coupling.to_string());
+            if let Some(cohesion) = measure.cohesion {
+                concept.metadata.insert(COHESION_METADATA_KEY.to_string(), format!("{:.2}", cohesion));
+            }
+        }
+    }
+
+    /// Project averages, hot spots and per-directory aggregates of `measures`
+    pub fn summarize_coupling(measures: &[ConceptCoupling], root: Option<&str>) -> CouplingSummary {
+        let mut most_coupled: Vec<&ConceptCoupling> = measures.iter().filter(|measure| measure.coupling > 0).collect();
+        most_coupled.sort_by(|a, b| b.coupling.cmp(&a.coupling).then_with(|| a.concept_id.cmp(&b.concept_id)));
+        let mut least_cohesive: Vec<&ConceptCoupling> =
+            measures.iter().filter(|measure| measure.cohesion.is_some_and(|cohesion| cohesion < 1.0)).collect();
+        least_cohesive.sort_by(|a, b| {
+            a.cohesion
+                .unwrap_or_default()
+                .total_cmp(&b.cohesion.unwrap_or_default())
+                .then_with(|| a.concept_id.cmp(&b.concept_id))
+        });
+
+        let mut by_directory: BTreeMap<String, Vec<&ConceptCoupling>> = BTreeMap::new();
+        for measure in measures {
+            let file = paths::relative_key(&measure.file_path, root);
+            let directory = file.rsplit_once('/').map_or(".", |(directory, _)| directory).to_string();
+            by_directory.entry(directory).or_default().push(measure);
+        }
+        let directories = by_directory
+            .into_iter()
+            .map(|(path, measures)| {
+                let (average_coupling, average_cohesion) = Self::averages(&measures);
+                DirectoryCoupling {
+                    path,
+                    concept_count: measures.len() as u32,
+                    average_coupling,
+                    max_coupling: measures.iter().map(|measure| measure.coupling).max().unwrap_or_default(),
+                    average_cohesion,
+                }
+            })
+            .collect();
+
+        let (average_coupling, average_cohesion) = Self::averages(&measures.iter().collect::<Vec<_>>());
+        CouplingSummary {
+            average_coupling,
+            average_cohesion,
+            most_coupled: most_coupled.into_iter().take(HOTSPOT_LIMIT).cloned().collect(),
+            least_cohesive: least_cohesive.into_iter().take(HOTSPOT_LIMIT).cloned().collect(),
+            directories,
+        }
+    }
+
+    /// Mean coupling, and mean cohesion of the measures that have one
+    fn averages(measures: &[&ConceptCoupling]) -> (f64, Option<f64>) {
+        if measures.is_empty() {
+            return (0.0, None);
+        }
+        let coupling = measures.iter().map(|measure| measure.coupling as f64).sum::<f64>() / measures.len() as f64;
+        let cohesions: Vec<f64> = measures.iter().filter_map(|measure| measure.cohesion).collect();
+        let cohesion = (!cohesions.is_empty()).then(|| cohesions.iter().sum::<f64>() / cohesions.len() as f64);
+        (coupling, cohesion)
+    }
+
     /// Count decision points in code (simplified heuristic)
-    fn count_decision_points(body: &str) -> f64 {
+    pub(crate) fn count_decision_points(body: &str) -> f64 {
         let mut count = 0.0;
         
         // Look for common control flow keywords
@@ -252,6 +472,48 @@ mod tests {
         assert!(ComplexityAnalyzer::count_decision_points(body3) >= 1.0);
     }
 
+    #[test]
+    fn test_coupling_and_cohesion_from_the_graph() {
+        let concept = |id: &str, concept_type: &str, file_path: &str, start: u32, end: u32, calls: &str| {
+            let mut concept = create_test_concept(id, concept_type, file_path, start, end);
+            concept.id = id.to_string();
+            if !calls.is_empty() {
+                concept.relationships.insert("calls".to_string(), calls.to_string());
+            }
+            concept
+        };
+        let mut concepts = vec![
+            concept("Cart", "class", "src/cart.ts", 1, 30, ""),
+            concept("normalize", "method", "src/cart.ts", 2, 4, ""),
+            concept("add", "method", "src/cart.ts", 5, 10, "normalize, price, log"),
+            concept("total", "method", "src/cart.ts", 11, 20, "normalize"),
+            concept("reset", "method", "src/cart.ts", 21, 29, ""),
+            concept("price", "function", "src/pricing/price.ts", 1, 5, ""),
+        ];
+        let graph = GraphStore::build(&concepts);
+        let measures = ComplexityAnalyzer::measure_coupling(&graph);
+        let measure = |id: &str| measures.iter().find(|m| m.concept_id == id).unwrap().clone();
+
+        // Only `price` is outside the class; `log` is not a known concept
+        assert_eq!(measure("add").coupling, 1);
+        assert_eq!(measure("Cart").coupling, 1);
+        assert_eq!(measure("total").coupling, 0);
+        // Three of six method pairs are linked through `normalize`; reset is linked to none
+        assert_eq!(measure("Cart").cohesion, Some(0.5));
+        assert_eq!(measure("add").cohesion, None);
+
+        ComplexityAnalyzer::annotate_coupling(concepts.iter_mut(), &measures);
+        assert_eq!(concepts[0].metadata.get(COHESION_METADATA_KEY).map(String::as_str), Some("0.50"));
+        assert_eq!(concepts[2].metadata.get(COUPLING_METADATA_KEY).map(String::as_str), Some("1"));
+
+        let summary = ComplexityAnalyzer::summarize_coupling(&measures, None);
+        assert_eq!(summary.least_cohesive[0].concept_id, "Cart");
+        assert_eq!(summary.most_coupled.len(), 2);
+        let directories: Vec<&str> = summary.directories.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(directories, vec!["src", "src/pricing"]);
+        assert_eq!(summary.directories[0].max_coupling, 1);
+    }
+
     #[test]
     fn test_relationships_impact_complexity() {
         let mut concept = create_test_concept("connected_func", "function", "test.rs", 1, 20);
@@ -0,0 +1,240 @@
+//! Source context around a concept for agent prompts
+//!
+//! A concept's line range already says where its code starts and ends, so a
+//! host asking about it shouldn't have to re-read the file and guess.
+//! [`ConceptContextExtractor`] cuts the concept's lines, plus a few lines on
+//! either side, out of its file and adds the signature of each concept it is
+//! directly related to: the declaration up to its body, e.g.
+//! `fn charge(&self, amount: u64) -> Result<Receipt>`. The same context is
+//! also rendered as one markdown block ready to paste into a prompt.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::types::SemanticConcept;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::Path;
+
+/// Lines around a concept included when a host doesn't say
+pub const DEFAULT_CONTEXT_LINES: u32 = 3;
+
+/// Lines around a concept included at most, on each side
+pub const MAX_CONTEXT_LINES: u32 = 200;
+
+/// Lines read from a related concept to find the end of its signature
+const MAX_SIGNATURE_LINES: usize = 6;
+
+/// Signatures longer than this are cut
+const MAX_SIGNATURE_CHARS: usize = 240;
+
+/// A concept directly related to the one the context is for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct RelatedSignature {
+    pub concept_id: String,
+    pub name: String,
+    pub concept_type: String,
+    /// Seen from the concept the context is for, e.g. 'calls' or 'called_by'
+    pub relationship: String,
+    pub file_path: String,
+    pub line: u32,
+    /// Declaration up to its body; the name when the source can't be read
+    pub signature: String,
+}
+
+/// A concept's source with the lines around it and its related signatures
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ConceptContext {
+    pub concept_id: String,
+    pub name: String,
+    pub concept_type: String,
+    pub file_path: String,
+    /// First and last line of `source`, 1-based
+    pub start_line: u32,
+    pub end_line: u32,
+    pub source: String,
+    /// Ordered by relationship, file and line
+    pub related: Vec<RelatedSignature>,
+    /// Everything above as markdown for a prompt
+    pub prompt: String,
+}
+
+pub struct ConceptContextExtractor;
+
+impl ConceptContextExtractor {
+    /// The concept's lines of `content` with up to `before_lines` and
+    /// `after_lines` more on either side; no related signatures yet
+    pub fn extract(concept: &SemanticConcept, content: &str, before_lines: u32, after_lines: u32) -> ConceptContext {
+        let lines: Vec<&str> = content.lines().collect();
+        let last = lines.len().max(1) as u32;
+        let start = concept.line_range.start.max(1).min(last).saturating_sub(before_lines).max(1);
+        let end = concept.line_range.end.max(concept.line_range.start).min(last).saturating_add(after_lines).min(last);
+        let source = lines
+            .get(start as usize - 1..end as usize)
+            .map(|slice| slice.join("\n"))
+            .unwrap_or_default();
+
+        let mut context = ConceptContext {
+            concept_id: concept.id.clone(),
+            name: concept.name.clone(),
+            concept_type: concept.concept_type.clone(),
+            file_path: concept.file_path.clone(),
+            start_line: start,
+            end_line: end,
+            source,
+            related: Vec::new(),
+            prompt: String::new(),
+        };
+        context.prompt = Self::prompt(&context);
+        context
+    }
+
+    /// Add a related concept, reading its signature from `content`, the
+    /// source of its file, when there is one; keeps `related` ordered and
+    /// `prompt` up to date
+    pub fn add_related(context: &mut ConceptContext, concept: &SemanticConcept, relationship: &str, content: Option<&str>) {
+        let already = context
+            .related
+            .iter()
+            .any(|related| related.concept_id == concept.id && related.relationship == relationship);
+        if already {
+            return;
+        }
+        context.related.push(RelatedSignature {
+            concept_id: concept.id.clone(),
+            name: concept.name.clone(),
+            concept_type: concept.concept_type.clone(),
+            relationship: relationship.to_string(),
+            file_path: concept.file_path.clone(),
+            line: concept.line_range.start,
+            signature: content.and_then(|content| Self::signature(concept, content)).unwrap_or_else(|| concept.name.clone()),
+        });
+        context.related.sort_by(|a, b| {
+            (a.relationship.as_str(), a.file_path.as_str(), a.line, a.name.as_str())
+                .cmp(&(b.relationship.as_str(), b.file_path.as_str(), b.line, b.name.as_str()))
+        });
+        context.prompt = Self::prompt(context);
+    }
+
+    /// A concept's declaration: its first lines up to where its body opens,
+    /// skipping attributes and decorators, joined into one line
+    pub fn signature(concept: &SemanticConcept, content: &str) -> Option<String> {
+        let start = concept.line_range.start.max(1) as usize - 1;
+        let end = concept.line_range.end.max(concept.line_range.start) as usize;
+        let mut parts = Vec::new();
+        for line in content.lines().skip(start).take((end - start).min(MAX_SIGNATURE_LINES)) {
+            let line = line.trim();
+            if line.is_empty() || (parts.is_empty() && (line.starts_with('@') || line.starts_with("#["))) {
+                continue;
+            }
+            // The body opens at the first brace, or after a Python-style colon
+            if let Some(brace) = line.find('{') {
+                parts.push(line[..brace].trim_end());
+                break;
+            }
+            parts.push(line);
+            if line.ends_with(':') || line.ends_with(';') || line.ends_with("=>") {
+                break;
+            }
+        }
+
+        let signature = parts.into_iter().filter(|part| !part.is_empty()).collect::<Vec<_>>().join(" ");
+        if signature.is_empty() {
+            return None;
+        }
+        Some(match signature.char_indices().nth(MAX_SIGNATURE_CHARS) {
+            Some((cut, _)) => format!("{}...", &signature[..cut]),
+            None => signature,
+        })
+    }
+
+    /// The context as markdown: a heading naming the concept and its lines,
+    /// the source in a fenced block, and a list of related signatures
+    pub fn prompt(context: &ConceptContext) -> String {
+        let language = Path::new(&context.file_path).extension().and_then(|e| e.to_str()).unwrap_or("");
+        let fence = if context.source.contains("```") { "````" } else { "```" };
+        let mut prompt = format!(
+            "### {} `{}` ({}:{}-{})\n\n{}{}\n{}\n{}\n",
+            context.concept_type,
+            context.name,
+            context.file_path,
+            context.start_line,
+            context.end_line,
+            fence,
+            language,
+            context.source,
+            fence
+        );
+        if !context.related.is_empty() {
+            prompt.push_str("\nRelated:\n");
+            for related in &context.related {
+                let _ = writeln!(
+                    prompt,
+                    "- {} {} `{}` ({}:{})",
+                    related.relationship, related.concept_type, related.signature, related.file_path, related.line
+                );
+            }
+        }
+        prompt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LineRange;
+    use std::collections::HashMap;
+
+    fn concept(id: &str, name: &str, concept_type: &str, file_path: &str, start: u32, end: u32) -> SemanticConcept {
+        SemanticConcept {
+            id: id.to_string(),
+            name: name.to_string(),
+            concept_type: concept_type.to_string(),
+            confidence: 0.9,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start, end },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_context_slices_source_and_lists_related_signatures() {
+        let billing = "use crate::tax;\n\n/// Charge a card\npub fn charge(amount: u64) -> u64 {\n    \
+                       amount + tax::vat(amount)\n}\n\nfn refund() {}\n";
+        let tax = "#[inline]\npub fn vat(\n    amount: u64,\n) -> u64 {\n    amount / 5\n}\n";
+        let python = "@cached\ndef rate(region):\n    return 0.2\n";
+
+        let charge = concept("c1", "charge", "function", "src/billing.rs", 4, 6);
+        let mut context = ConceptContextExtractor::extract(&charge, billing, 2, 10);
+        assert_eq!((context.start_line, context.end_line), (2, 8));
+        assert!(context.source.starts_with("\n/// Charge a card\npub fn charge"));
+        assert!(context.source.ends_with("fn refund() {}"));
+
+        let vat = concept("t1", "vat", "function", "src/tax.rs", 1, 6);
+        let rate = concept("p1", "rate", "function", "rates.py", 1, 3);
+        let caller = concept("x1", "checkout", "function", "src/cart.rs", 10, 20);
+        ConceptContextExtractor::add_related(&mut context, &vat, "calls", Some(tax));
+        ConceptContextExtractor::add_related(&mut context, &rate, "calls", Some(python));
+        ConceptContextExtractor::add_related(&mut context, &vat, "calls", Some(tax));
+        ConceptContextExtractor::add_related(&mut context, &caller, "called_by", None);
+
+        let signatures: Vec<(&str, &str)> =
+            context.related.iter().map(|r| (r.relationship.as_str(), r.signature.as_str())).collect();
+        assert_eq!(
+            signatures,
+            vec![
+                ("called_by", "checkout"),
+                ("calls", "def rate(region):"),
+                ("calls", "pub fn vat( amount: u64, ) -> u64"),
+            ]
+        );
+        assert!(context.prompt.starts_with("### function `charge` (src/billing.rs:2-8)\n\n```rs\n"));
+        assert!(context.prompt.ends_with("- calls function `pub fn vat( amount: u64, ) -> u64` (src/tax.rs:1)\n"));
+    }
+}
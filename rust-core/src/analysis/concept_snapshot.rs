@@ -0,0 +1,238 @@
+//! Concepts saved between processes
+//!
+//! A learning run that analyzes every file saves the stored concepts to
+//! `.in-memoria/concept-snapshot.json`, with a hash of the content of each
+//! file they were extracted from. `SemanticAnalyzer::open` reads them back
+//! and keeps only the concepts of files whose content still hashes the same,
+//! so a restarted server answers its first query at once without ever
+//! serving concepts of code that has since changed. The [`WarmStartReport`]
+//! lists the files whose concepts were dropped, for the host to update.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::Tombstone;
+use crate::types::validation::{ErrorCode, ValidationError};
+use crate::types::{ParseError, SemanticConcept, CONFIG_DIR};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// File holding the saved concepts, inside the project's config directory
+pub const SNAPSHOT_FILE: &str = "concept-snapshot.json";
+
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// What a warm start kept from the saved concepts and what it dropped
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct WarmStartReport {
+    pub concepts_loaded: u32,
+    /// Concepts of changed or missing files
+    pub concepts_dropped: u32,
+    pub files_unchanged: u32,
+    /// Files whose content changed since the concepts were saved, in path order
+    pub changed_files: Vec<String>,
+    /// Files that can no longer be read, in path order
+    pub missing_files: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedSnapshot {
+    version: u32,
+    files: BTreeMap<String, String>,
+    concepts: Vec<SemanticConcept>,
+    #[serde(default)]
+    tombstones: Vec<Tombstone>,
+}
+
+/// Concepts and the content hashes of the files they were extracted from
+#[derive(Debug, Clone, Default)]
+pub struct ConceptSnapshot {
+    /// Hash by concept file path
+    files: BTreeMap<String, String>,
+    concepts: Vec<SemanticConcept>,
+    /// Files removed before the snapshot was taken, whose concepts it leaves out
+    pub tombstones: Vec<Tombstone>,
+}
+
+impl ConceptSnapshot {
+    /// Snapshot of `concepts`, hashing each of their files as `read` returns
+    /// it; concepts of files `read` can't return are left out
+    pub fn capture<'a>(
+        concepts: impl IntoIterator<Item = &'a SemanticConcept>,
+        mut read: impl FnMut(&str) -> Option<String>,
+    ) -> Self {
+        let mut files: BTreeMap<String, Option<String>> = BTreeMap::new();
+        let mut kept = Vec::new();
+        for concept in concepts {
+            let hash = files
+                .entry(concept.file_path.clone())
+                .or_insert_with(|| read(&concept.file_path).map(|content| content_hash(&content)));
+            if hash.is_some() {
+                kept.push(concept.clone());
+            }
+        }
+        kept.sort_by(|a, b| a.id.cmp(&b.id));
+        ConceptSnapshot {
+            files: files.into_iter().filter_map(|(file, hash)| Some((file, hash?))).collect(),
+            concepts: kept,
+            tombstones: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.concepts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.concepts.is_empty()
+    }
+
+    /// The concepts whose file `read` still returns with the same content
+    pub fn validate(self, mut read: impl FnMut(&str) -> Option<String>) -> (Vec<SemanticConcept>, WarmStartReport) {
+        let mut report = WarmStartReport::default();
+        let mut fresh = BTreeSet::new();
+        for (file, hash) in &self.files {
+            match read(file) {
+                Some(content) if content_hash(&content) == *hash => {
+                    report.files_unchanged += 1;
+                    fresh.insert(file.as_str());
+                }
+                Some(_) => report.changed_files.push(file.clone()),
+                None => report.missing_files.push(file.clone()),
+            }
+        }
+
+        let total = self.concepts.len() as u32;
+        let concepts: Vec<SemanticConcept> =
+            self.concepts.into_iter().filter(|concept| fresh.contains(concept.file_path.as_str())).collect();
+        report.concepts_loaded = concepts.len() as u32;
+        report.concepts_dropped = total - report.concepts_loaded;
+        (concepts, report)
+    }
+
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(CONFIG_DIR).join(SNAPSHOT_FILE)
+    }
+
+    /// Read a project's saved concepts; `None` when none were saved
+    pub fn load(project_root: &Path) -> Result<Option<Self>, ParseError> {
+        let path = Self::path(project_root);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            ParseError::from_reason(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let saved: SavedSnapshot = serde_json::from_str(&content)
+            .map_err(|e| ParseError::from_reason(format!("Invalid {}: {}", SNAPSHOT_FILE, e)))?;
+        if saved.version > SNAPSHOT_VERSION {
+            return Err(ValidationError::new(
+                ErrorCode::UnsupportedVersion,
+                format!(
+                    "{} was written by a newer release (version {}; this release reads up to {}). Upgrade In Memoria or delete the file to relearn.",
+                    SNAPSHOT_FILE, saved.version, SNAPSHOT_VERSION
+                ),
+            )
+            .into());
+        }
+        Ok(Some(ConceptSnapshot {
+            files: saved.files,
+            concepts: saved.concepts,
+            tombstones: saved.tombstones,
+        }))
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<(), ParseError> {
+        let saved = SavedSnapshot {
+            version: SNAPSHOT_VERSION,
+            files: self.files.clone(),
+            concepts: self.concepts.clone(),
+            tombstones: self.tombstones.clone(),
+        };
+
+        let path = Self::path(project_root);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                ParseError::from_reason(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+        let content = serde_json::to_string(&saved)
+            .map_err(|e| ParseError::from_reason(format!("Failed to serialize concept snapshot: {}", e)))?;
+        // Write then rename so a process killed mid-write leaves the previous snapshot intact
+        let staging = path.with_extension("json.tmp");
+        std::fs::write(&staging, content)
+            .and_then(|_| std::fs::rename(&staging, &path))
+            .map_err(|e| ParseError::from_reason(format!("Failed to write {}: {}", path.display(), e)))
+    }
+}
+
+/// Stable across runs and platforms, unlike `DefaultHasher`
+fn content_hash(content: &str) -> String {
+    let hash = content.bytes().fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LineRange;
+    use std::collections::HashMap;
+
+    fn concept(id: &str, file_path: &str) -> SemanticConcept {
+        SemanticConcept {
+            id: id.to_string(),
+            name: id.to_string(),
+            concept_type: "function".to_string(),
+            confidence: 0.9,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start: 1, end: 3 },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_drops_stale_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut files = HashMap::from([
+            ("src/a.ts", "export function a() {}"),
+            ("src/b.ts", "export function b() {}"),
+            ("src/c.ts", "export function c() {}"),
+        ]);
+        let concepts = vec![
+            concept("a1", "src/a.ts"),
+            concept("a2", "src/a.ts"),
+            concept("b1", "src/b.ts"),
+            concept("c1", "src/c.ts"),
+            concept("overlay", "src/unsaved.ts"),
+        ];
+
+        let snapshot = ConceptSnapshot::capture(&concepts, |file| files.get(file).map(|c| c.to_string()));
+        assert_eq!(snapshot.len(), 4);
+        snapshot.save(dir.path()).unwrap();
+        assert!(ConceptSnapshot::load(&dir.path().join("elsewhere")).unwrap().is_none());
+
+        files.insert("src/b.ts", "export function b(x) {}");
+        files.remove("src/c.ts");
+        let loaded = ConceptSnapshot::load(dir.path()).unwrap().unwrap();
+        let (kept, report) = loaded.validate(|file| files.get(file).map(|c| c.to_string()));
+
+        let ids: Vec<&str> = kept.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["a1", "a2"]);
+        assert_eq!(
+            report,
+            WarmStartReport {
+                concepts_loaded: 2,
+                concepts_dropped: 2,
+                files_unchanged: 1,
+                changed_files: vec!["src/b.ts".to_string()],
+                missing_files: vec!["src/c.ts".to_string()],
+            }
+        );
+    }
+}
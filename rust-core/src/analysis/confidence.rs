@@ -0,0 +1,283 @@
+//! Evidence-based confidence calibration for extracted concepts
+//!
+//! Extractors assign a flat confidence per language. Calibration replaces it with
+//! a score built from observable signals in the source, and records the method
+//! and signals in the concept metadata so the number can be audited later.
+
+use crate::types::SemanticConcept;
+
+/// How a concept was extracted from source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractionMethod {
+    TreeSitter,
+    Fallback,
+}
+
+impl ExtractionMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExtractionMethod::TreeSitter => "tree_sitter",
+            ExtractionMethod::Fallback => "fallback",
+        }
+    }
+
+    /// Starting score before any evidence is considered
+    fn base_confidence(&self) -> f64 {
+        match self {
+            ExtractionMethod::TreeSitter => 0.55,
+            ExtractionMethod::Fallback => 0.35,
+        }
+    }
+}
+
+const SPECIFIC_KIND_WEIGHT: f64 = 0.15;
+const GENERAL_KIND_WEIGHT: f64 = 0.05;
+const SIGNATURE_WEIGHT: f64 = 0.1;
+const DOC_COMMENT_WEIGHT: f64 = 0.1;
+const TEST_REFERENCE_WEIGHT: f64 = 0.1;
+const BODY_WEIGHT: f64 = 0.05;
+
+const MIN_CONFIDENCE: f64 = 0.05;
+const MAX_CONFIDENCE: f64 = 0.99;
+
+/// Scores concept confidence from signal strength in the surrounding source
+pub struct ConfidenceCalibrator;
+
+impl ConfidenceCalibrator {
+    /// Recalibrate every concept extracted from `content` and record the evidence in metadata
+    pub fn calibrate(concepts: &mut [SemanticConcept], content: &str, method: ExtractionMethod) {
+        let lines: Vec<&str> = content.lines().collect();
+        let test_lines: Vec<(usize, &str)> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| Self::is_test_line(line))
+            .map(|(idx, line)| (idx + 1, *line))
+            .collect();
+
+        for concept in concepts.iter_mut() {
+            let (score, signals) = Self::score(concept, &lines, &test_lines, method);
+
+            concept.metadata.insert(
+                "extractor_confidence".to_string(),
+                format!("{:.2}", concept.confidence),
+            );
+            concept
+                .metadata
+                .insert("extraction_method".to_string(), method.as_str().to_string());
+            concept.metadata.insert(
+                "confidence_signals".to_string(),
+                if signals.is_empty() {
+                    "none".to_string()
+                } else {
+                    signals.join(",")
+                },
+            );
+            concept.confidence = score;
+        }
+    }
+
+    /// Compute the calibrated score and the names of the signals that contributed
+    fn score(
+        concept: &SemanticConcept,
+        lines: &[&str],
+        test_lines: &[(usize, &str)],
+        method: ExtractionMethod,
+    ) -> (f64, Vec<&'static str>) {
+        let mut score = method.base_confidence();
+        let mut signals = Vec::new();
+
+        let is_declaration = match Self::kind_specificity(&concept.concept_type) {
+            Some(true) => {
+                score += SPECIFIC_KIND_WEIGHT;
+                signals.push("specific_kind");
+                true
+            }
+            Some(false) => {
+                score += GENERAL_KIND_WEIGHT;
+                signals.push("general_kind");
+                true
+            }
+            None => false,
+        };
+
+        let start = concept.line_range.start as usize;
+        let end = (concept.line_range.end as usize).max(start);
+
+        if is_declaration {
+            if let Some(first_line) = start.checked_sub(1).and_then(|idx| lines.get(idx)) {
+                if Self::has_signature(first_line) {
+                    score += SIGNATURE_WEIGHT;
+                    signals.push("signature");
+                }
+            }
+
+            if Self::has_doc_comment(lines, start) {
+                score += DOC_COMMENT_WEIGHT;
+                signals.push("doc_comment");
+            }
+        }
+
+        let referenced_by_test = test_lines
+            .iter()
+            .any(|(line_no, line)| (*line_no < start || *line_no > end) && Self::contains_word(line, &concept.name));
+        if referenced_by_test {
+            score += TEST_REFERENCE_WEIGHT;
+            signals.push("test_reference");
+        }
+
+        if end > start {
+            score += BODY_WEIGHT;
+            signals.push("multi_line_body");
+        }
+
+        (score.clamp(MIN_CONFIDENCE, MAX_CONFIDENCE), signals)
+    }
+
+    /// `Some(true)` for node kinds that only match real declarations,
+    /// `Some(false)` for broad kinds like variables, `None` for anything else
+    fn kind_specificity(concept_type: &str) -> Option<bool> {
+        match concept_type {
+            "class" | "struct" | "interface" | "trait" | "enum" | "function" | "method"
+            | "constructor" | "procedure" | "table" | "view" | "type" | "module" => Some(true),
+            "variable" | "constant" | "field" | "property" | "import" => Some(false),
+            _ => None,
+        }
+    }
+
+    fn has_signature(line: &str) -> bool {
+        let trimmed = line.trim_end();
+        trimmed.contains('(') || trimmed.ends_with('{') || trimmed.ends_with(':')
+    }
+
+    /// Look for a comment directly above the declaration (skipping attributes and
+    /// decorators), or a Python docstring directly below it
+    fn has_doc_comment(lines: &[&str], start: usize) -> bool {
+        let mut idx = start.saturating_sub(1);
+        while idx > 0 {
+            idx -= 1;
+            let line = lines.get(idx).map(|l| l.trim()).unwrap_or("");
+            if line.starts_with("#[") || line.starts_with('@') {
+                continue;
+            }
+            if line.starts_with("///")
+                || line.starts_with("//")
+                || line.starts_with("/**")
+                || line.starts_with("*/")
+                || line.starts_with('*')
+                || line.starts_with('#')
+            {
+                return true;
+            }
+            break;
+        }
+
+        lines
+            .get(start)
+            .map(|l| {
+                let l = l.trim();
+                l.starts_with("\"\"\"") || l.starts_with("'''")
+            })
+            .unwrap_or(false)
+    }
+
+    fn is_test_line(line: &str) -> bool {
+        let lower = line.to_lowercase();
+        lower.contains("test") || lower.contains("assert") || lower.contains("expect(")
+    }
+
+    fn contains_word(line: &str, word: &str) -> bool {
+        if word.is_empty() {
+            return false;
+        }
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+        line.match_indices(word).any(|(pos, _)| {
+            let before = line[..pos].chars().next_back();
+            let after = line[pos + word.len()..].chars().next();
+            !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LineRange;
+    use std::collections::HashMap;
+
+    fn concept(name: &str, concept_type: &str, start: u32, end: u32) -> SemanticConcept {
+        SemanticConcept {
+            id: format!("test_{}", name),
+            name: name.to_string(),
+            concept_type: concept_type.to_string(),
+            confidence: 0.8,
+            file_path: "test.rs".to_string(),
+            line_range: LineRange { start, end },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_tree_sitter_scores_above_fallback_for_same_evidence() {
+        let content = "fn build() {\n}\n";
+        let mut tree = vec![concept("build", "function", 1, 2)];
+        let mut fallback = vec![concept("build", "function", 1, 2)];
+
+        ConfidenceCalibrator::calibrate(&mut tree, content, ExtractionMethod::TreeSitter);
+        ConfidenceCalibrator::calibrate(&mut fallback, content, ExtractionMethod::Fallback);
+
+        assert!(tree[0].confidence > fallback[0].confidence);
+        assert_eq!(tree[0].metadata.get("extraction_method"), Some(&"tree_sitter".to_string()));
+        assert_eq!(fallback[0].metadata.get("extraction_method"), Some(&"fallback".to_string()));
+        assert_eq!(tree[0].metadata.get("extractor_confidence"), Some(&"0.80".to_string()));
+    }
+
+    #[test]
+    fn test_signals_raise_confidence_and_are_recorded() {
+        let bare = "fn helper() {\n}\n";
+        let documented = "/// Builds the thing\nfn helper() {\n}\n\n#[test]\nfn test_helper() { assert!(helper()); }\n";
+
+        let mut plain = vec![concept("helper", "function", 1, 2)];
+        let mut rich = vec![concept("helper", "function", 2, 3)];
+        ConfidenceCalibrator::calibrate(&mut plain, bare, ExtractionMethod::TreeSitter);
+        ConfidenceCalibrator::calibrate(&mut rich, documented, ExtractionMethod::TreeSitter);
+
+        assert!(rich[0].confidence > plain[0].confidence);
+        let signals = rich[0].metadata.get("confidence_signals").unwrap();
+        assert!(signals.contains("doc_comment"));
+        assert!(signals.contains("test_reference"));
+        assert!(signals.contains("signature"));
+        assert!(!plain[0].metadata.get("confidence_signals").unwrap().contains("doc_comment"));
+    }
+
+    #[test]
+    fn test_python_docstring_counts_as_doc_comment() {
+        let content = "def load(path):\n    \"\"\"Load a file.\"\"\"\n    return path\n";
+        let mut concepts = vec![concept("load", "function", 1, 3)];
+        ConfidenceCalibrator::calibrate(&mut concepts, content, ExtractionMethod::TreeSitter);
+
+        assert!(concepts[0].metadata.get("confidence_signals").unwrap().contains("doc_comment"));
+    }
+
+    #[test]
+    fn test_test_reference_requires_whole_word() {
+        let content = "fn run() {}\nfn test_runner() { assert!(runner()); }\n";
+        let mut concepts = vec![concept("run", "function", 1, 1)];
+        ConfidenceCalibrator::calibrate(&mut concepts, content, ExtractionMethod::TreeSitter);
+
+        assert!(!concepts[0].metadata.get("confidence_signals").unwrap().contains("test_reference"));
+    }
+
+    #[test]
+    fn test_confidence_stays_in_range() {
+        let content = "/// doc\nclass Widget {\n}\n// test Widget\n";
+        let mut strong = vec![concept("Widget", "class", 2, 3)];
+        let mut weak = vec![concept("widget", "file", 1, 1)];
+        ConfidenceCalibrator::calibrate(&mut strong, content, ExtractionMethod::TreeSitter);
+        ConfidenceCalibrator::calibrate(&mut weak, "", ExtractionMethod::Fallback);
+
+        assert!(strong[0].confidence <= MAX_CONFIDENCE);
+        assert!(weak[0].confidence >= MIN_CONFIDENCE);
+        assert_eq!(weak[0].metadata.get("confidence_signals"), Some(&"none".to_string()));
+    }
+}
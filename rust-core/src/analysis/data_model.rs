@@ -0,0 +1,921 @@
+//! Data model extraction for ORM schemas and SQL DDL
+//!
+//! Reads model declarations as text rather than running them:
+//! - Prisma: `model User { ... }` blocks in `.prisma` files
+//! - SQLAlchemy: classes with `__tablename__`, `Column(...)` or `mapped_column(...)`
+//! - Diesel: `table! { users (id) { ... } }` and `joinable!(posts -> users (user_id))`
+//! - TypeORM: `@Entity()` classes with `@Column` and relation decorators
+//! - SQL: `CREATE TABLE` statements, from the SQL extractor's table and column concepts
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::SemanticAnalyzer;
+use crate::types::{LineRange, ParseError, SemanticConcept};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use walkdir::WalkDir;
+
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", ".venv", "target", "vendor"];
+
+/// Files larger than this are not read for models or usages
+const MAX_MODEL_FILE_SIZE: u64 = 512 * 1024;
+
+/// Files listed as using one model
+const MAX_USAGES: usize = 25;
+
+/// Files searched for references to a model
+const USAGE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "py", "rs", "go", "java", "cs", "php"];
+
+/// A column of a model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ModelField {
+    pub name: String,
+    /// Type as written in the schema, e.g. 'String', 'Integer', 'Nullable<Text>'
+    pub field_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+    pub line: u32,
+}
+
+/// A link from a model to another model or table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ModelRelation {
+    /// Field or column holding the relation
+    pub field: String,
+    /// Related model, or table for foreign keys
+    pub target: String,
+    /// 'one', 'many', 'many_to_many', 'foreign_key', or 'relationship' when
+    /// the cardinality isn't declared
+    pub kind: String,
+    pub line: u32,
+}
+
+/// A model or table and the code that refers to it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct DataModel {
+    /// Model name; the table name for Diesel and SQL
+    pub name: String,
+    /// Table name when it is declared apart from the model name
+    pub table_name: Option<String>,
+    /// 'prisma', 'sqlalchemy', 'diesel', 'typeorm' or 'sql'
+    pub source: String,
+    pub file_path: String,
+    pub line: u32,
+    pub end_line: u32,
+    pub fields: Vec<ModelField>,
+    pub relations: Vec<ModelRelation>,
+    /// Source files mentioning the model by name, besides the one declaring it
+    pub used_in: Vec<String>,
+}
+
+/// Data model section of the project blueprint
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct DataModelSummary {
+    pub total_models: u32,
+    pub by_source: BTreeMap<String, u32>,
+    pub models: Vec<DataModel>,
+}
+
+pub struct DataModelExtractor;
+
+impl DataModelExtractor {
+    /// Find models under `root` and the source files that use each of them
+    pub fn scan(root: &Path) -> Result<DataModelSummary, ParseError> {
+        let mut analyzer = SemanticAnalyzer::new()?;
+        let mut models = Vec::new();
+        for (relative, path) in Self::files(root) {
+            let Ok(content) = fs::read_to_string(&path) else { continue };
+            if relative.ends_with(".sql") {
+                if content.to_ascii_uppercase().contains("CREATE TABLE") {
+                    let concepts = analyzer.extract_file_concepts_sync(&relative, &content);
+                    models.extend(Self::from_sql_concepts(&concepts));
+                }
+            } else {
+                models.extend(Self::extract(&relative, &content));
+            }
+        }
+
+        if !models.is_empty() {
+            Self::link_usages(root, &mut models);
+        }
+
+        let mut by_source: BTreeMap<String, u32> = BTreeMap::new();
+        for model in &models {
+            *by_source.entry(model.source.clone()).or_insert(0) += 1;
+        }
+        Ok(DataModelSummary {
+            total_models: models.len() as u32,
+            by_source,
+            models,
+        })
+    }
+
+    /// Models declared in a Prisma schema or an ORM model file
+    pub fn extract(file_path: &str, content: &str) -> Vec<DataModel> {
+        let extension = Path::new(file_path).extension().and_then(|e| e.to_str()).unwrap_or("");
+        match extension {
+            "prisma" => Self::prisma_models(file_path, content),
+            "py" if content.contains("Column(") || content.contains("mapped_column(") => {
+                Self::sqlalchemy_models(file_path, content)
+            }
+            "rs" if content.contains("table!") => Self::diesel_models(file_path, content),
+            "ts" | "js" if content.contains("@Entity") => Self::typeorm_models(file_path, content),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Models for the `CREATE TABLE` statements found by the SQL extractor
+    pub fn from_sql_concepts(concepts: &[SemanticConcept]) -> Vec<DataModel> {
+        concepts
+            .iter()
+            .filter(|c| c.relationships.get("sql_object_type").is_some_and(|t| t.ends_with("table")))
+            .map(|table| {
+                let mut model = Self::model(&table.name, "sql", &table.file_path, table.line_range.start);
+                model.end_line = table.line_range.end;
+
+                let columns = concepts.iter().filter(|c| {
+                    c.concept_type == "column"
+                        && c.file_path == table.file_path
+                        && c.relationships.get("parent_table") == Some(&table.name)
+                });
+                for column in columns {
+                    let primary_key = column.metadata.get("primary_key").is_some_and(|v| v == "true");
+                    model.fields.push(ModelField {
+                        name: column.name.clone(),
+                        field_type: column.metadata.get("data_type").cloned().unwrap_or_default(),
+                        nullable: !primary_key && column.metadata.get("nullable").is_none_or(|v| v != "false"),
+                        primary_key,
+                        line: column.line_range.start,
+                    });
+                    if let Some(target) = column.metadata.get("references") {
+                        model.relations.push(ModelRelation {
+                            field: column.name.clone(),
+                            target: target.clone(),
+                            kind: "foreign_key".to_string(),
+                            line: column.line_range.start,
+                        });
+                    }
+                }
+                model
+            })
+            .collect()
+    }
+
+    /// Table, column and relation concepts for the models
+    pub fn to_concepts(models: &[DataModel]) -> Vec<SemanticConcept> {
+        let mut concepts = Vec::new();
+        for model in models {
+            let table_name = model.table_name.clone().unwrap_or_else(|| model.name.clone());
+
+            let mut metadata = HashMap::new();
+            metadata.insert("orm".to_string(), model.source.clone());
+            metadata.insert("table_name".to_string(), table_name);
+            metadata.insert("column_count".to_string(), model.fields.len().to_string());
+            let mut relationships = HashMap::new();
+            relationships.insert("data_model_type".to_string(), "table".to_string());
+            concepts.push(SemanticConcept {
+                id: format!("{}_model_{}_{}", model.source, model.file_path, model.name),
+                name: model.name.clone(),
+                concept_type: "table".to_string(),
+                confidence: 0.9,
+                file_path: model.file_path.clone(),
+                line_range: LineRange { start: model.line, end: model.end_line },
+                relationships,
+                metadata,
+            });
+
+            for field in &model.fields {
+                let mut metadata = HashMap::new();
+                metadata.insert("orm".to_string(), model.source.clone());
+                metadata.insert("data_type".to_string(), field.field_type.clone());
+                metadata.insert("nullable".to_string(), field.nullable.to_string());
+                metadata.insert("primary_key".to_string(), field.primary_key.to_string());
+                let mut relationships = HashMap::new();
+                relationships.insert("parent_table".to_string(), model.name.clone());
+                concepts.push(SemanticConcept {
+                    id: format!("{}_column_{}_{}_{}", model.source, model.file_path, model.name, field.name),
+                    name: field.name.clone(),
+                    concept_type: "column".to_string(),
+                    confidence: 0.9,
+                    file_path: model.file_path.clone(),
+                    line_range: LineRange { start: field.line, end: field.line },
+                    relationships,
+                    metadata,
+                });
+            }
+
+            for relation in &model.relations {
+                let mut metadata = HashMap::new();
+                metadata.insert("orm".to_string(), model.source.clone());
+                metadata.insert("kind".to_string(), relation.kind.clone());
+                let mut relationships = HashMap::new();
+                relationships.insert("source_model".to_string(), model.name.clone());
+                relationships.insert("target_model".to_string(), relation.target.clone());
+                concepts.push(SemanticConcept {
+                    id: format!("{}_relation_{}_{}_{}", model.source, model.file_path, model.name, relation.field),
+                    name: format!("{}.{}", model.name, relation.field),
+                    concept_type: "relation".to_string(),
+                    confidence: 0.85,
+                    file_path: model.file_path.clone(),
+                    line_range: LineRange { start: relation.line, end: relation.line },
+                    relationships,
+                    metadata,
+                });
+            }
+        }
+        concepts
+    }
+
+    /// Files that may declare models, with their path relative to `root`
+    fn files(root: &Path) -> Vec<(String, PathBuf)> {
+        WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter(|e| e.metadata().map(|m| m.len() <= MAX_MODEL_FILE_SIZE).unwrap_or(false))
+            .map(|e| {
+                let relative = e.path().strip_prefix(root).unwrap_or(e.path()).to_string_lossy().replace('\\', "/");
+                (relative, e.into_path())
+            })
+            .collect()
+    }
+
+    /// Record the source files that mention each model by name
+    fn link_usages(root: &Path, models: &mut [DataModel]) {
+        let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+        for (idx, model) in models.iter().enumerate() {
+            by_name.entry(model.name.clone()).or_default().push(idx);
+        }
+        let mut names: Vec<&String> = by_name.keys().collect();
+        names.sort();
+        let alternatives: Vec<String> = names.iter().map(|n| regex::escape(n)).collect();
+        let Ok(pattern) = Regex::new(&format!(r"\b(?:{})\b", alternatives.join("|"))) else {
+            return;
+        };
+
+        for (relative, path) in Self::files(root) {
+            let is_source = Path::new(&relative)
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| USAGE_EXTENSIONS.contains(&ext));
+            if !is_source {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path) else { continue };
+
+            let mentioned: HashSet<&str> = pattern.find_iter(&content).map(|m| m.as_str()).collect();
+            for name in mentioned {
+                for &idx in &by_name[name] {
+                    let model = &mut models[idx];
+                    if model.file_path != relative && model.used_in.len() < MAX_USAGES {
+                        model.used_in.push(relative.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    fn model(name: &str, source: &str, file_path: &str, line: u32) -> DataModel {
+        DataModel {
+            name: name.to_string(),
+            table_name: None,
+            source: source.to_string(),
+            file_path: file_path.to_string(),
+            line,
+            end_line: line,
+            fields: Vec::new(),
+            relations: Vec::new(),
+            used_in: Vec::new(),
+        }
+    }
+
+    fn prisma_models(file_path: &str, content: &str) -> Vec<DataModel> {
+        let p = patterns();
+        let mut models = Vec::new();
+        let mut current: Option<DataModel> = None;
+
+        for (idx, line) in content.lines().enumerate() {
+            let line_number = idx as u32 + 1;
+            let trimmed = line.trim();
+
+            let Some(model) = current.as_mut() else {
+                if let Some(c) = p.prisma_model.captures(trimmed) {
+                    current = Some(Self::model(&c[1], "prisma", file_path, line_number));
+                }
+                continue;
+            };
+
+            if trimmed.starts_with('}') {
+                model.end_line = line_number;
+                models.extend(current.take());
+            } else if let Some(c) = p.prisma_map.captures(trimmed) {
+                model.table_name = Some(c[1].to_string());
+            } else if let Some(c) = p.prisma_field.captures(trimmed) {
+                let attributes = c.get(5).map_or("", |m| m.as_str());
+                model.fields.push(ModelField {
+                    name: c[1].to_string(),
+                    field_type: format!("{}{}", &c[2], c.get(3).map_or("", |m| m.as_str())),
+                    nullable: c.get(4).is_some(),
+                    primary_key: attributes.contains("@id"),
+                    line: line_number,
+                });
+            }
+        }
+
+        // Fields typed with another model of the schema are relations, not columns
+        let names: HashSet<String> = models.iter().map(|m| m.name.clone()).collect();
+        for model in &mut models {
+            let (relations, fields): (Vec<ModelField>, Vec<ModelField>) = std::mem::take(&mut model.fields)
+                .into_iter()
+                .partition(|f| names.contains(f.field_type.trim_end_matches("[]")));
+            model.fields = fields;
+            model.relations = relations
+                .into_iter()
+                .map(|f| ModelRelation {
+                    kind: if f.field_type.ends_with("[]") { "many" } else { "one" }.to_string(),
+                    target: f.field_type.trim_end_matches("[]").to_string(),
+                    field: f.name,
+                    line: f.line,
+                })
+                .collect();
+        }
+
+        models
+    }
+
+    fn sqlalchemy_models(file_path: &str, content: &str) -> Vec<DataModel> {
+        let p = patterns();
+        let lines: Vec<&str> = content.lines().collect();
+        let mut models = Vec::new();
+
+        let mut idx = 0;
+        while idx < lines.len() {
+            let Some(c) = p.python_class.captures(lines[idx]) else {
+                idx += 1;
+                continue;
+            };
+            let indent = c[1].len();
+            let declarative = c.get(3).is_some_and(|bases| p.declarative_base.is_match(bases.as_str()));
+            let mut model = Self::model(&c[2], "sqlalchemy", file_path, idx as u32 + 1);
+
+            let mut body = idx + 1;
+            while body < lines.len() {
+                let line = lines[body];
+                if !line.trim().is_empty() {
+                    if line.len() - line.trim_start().len() <= indent {
+                        break;
+                    }
+                    model.end_line = body as u32 + 1;
+                    Self::sqlalchemy_member(line, body as u32 + 1, &mut model);
+                }
+                body += 1;
+            }
+
+            // Mixins and the declarative base itself have no table of their own
+            if !model.fields.is_empty() && (declarative || model.table_name.is_some()) {
+                models.push(model);
+            }
+            idx = body;
+        }
+
+        models
+    }
+
+    fn sqlalchemy_member(line: &str, line_number: u32, model: &mut DataModel) {
+        let p = patterns();
+        if let Some(c) = p.sa_tablename.captures(line) {
+            model.table_name = Some(c[1].to_string());
+            return;
+        }
+        let Some(c) = p.sa_member.captures(line) else {
+            return;
+        };
+
+        let name = c[1].to_string();
+        let annotation = c.get(2).map(|m| m.as_str().trim());
+        let args = c.get(4).map_or("", |m| m.as_str());
+
+        if &c[3] == "relationship" {
+            let target = p
+                .sa_relationship_target
+                .captures(args)
+                .or_else(|| annotation.and_then(|a| p.sa_annotation_target.captures(a)))
+                .map(|t| t[1].to_string());
+            let Some(target) = target else { return };
+
+            let kind = if args.contains("secondary") {
+                "many_to_many"
+            } else if annotation.is_some_and(|a| ["List[", "list[", "Set[", "set["].iter().any(|l| a.contains(l))) {
+                "many"
+            } else if annotation.is_some() || args.contains("uselist=False") {
+                "one"
+            } else {
+                "relationship"
+            };
+            model.relations.push(ModelRelation {
+                field: name,
+                target,
+                kind: kind.to_string(),
+                line: line_number,
+            });
+            return;
+        }
+
+        let primary_key = args.contains("primary_key=True");
+        let nullable = if args.contains("nullable=False") {
+            false
+        } else if args.contains("nullable=True") {
+            true
+        } else if primary_key {
+            false
+        } else if let Some(annotation) = annotation {
+            annotation.contains("Optional") || annotation.contains("None")
+        } else {
+            true
+        };
+        let field_type = p
+            .sa_column_type
+            .captures(args)
+            .map(|t| t[1].to_string())
+            .or_else(|| {
+                annotation.map(|a| {
+                    a.strip_prefix("Mapped[")
+                        .and_then(|inner| inner.strip_suffix(']'))
+                        .unwrap_or(a)
+                        .to_string()
+                })
+            })
+            .unwrap_or_default();
+
+        if let Some(fk) = p.sa_foreign_key.captures(args) {
+            // "schema.table.column" and "table.column" both point at the table
+            let parts: Vec<&str> = fk[1].split('.').collect();
+            let target = if parts.len() >= 2 { parts[parts.len() - 2] } else { parts[0] };
+            model.relations.push(ModelRelation {
+                field: name.clone(),
+                target: target.to_string(),
+                kind: "foreign_key".to_string(),
+                line: line_number,
+            });
+        }
+
+        model.fields.push(ModelField {
+            name,
+            field_type,
+            nullable,
+            primary_key,
+            line: line_number,
+        });
+    }
+
+    fn diesel_models(file_path: &str, content: &str) -> Vec<DataModel> {
+        let p = patterns();
+        let mut models: Vec<DataModel> = Vec::new();
+        let mut joins = Vec::new();
+        let mut in_macro = false;
+        let mut current: Option<(DataModel, Vec<String>)> = None;
+
+        for (idx, line) in content.lines().enumerate() {
+            let line_number = idx as u32 + 1;
+            let trimmed = line.trim();
+
+            if let Some(c) = p.diesel_joinable.captures(trimmed) {
+                joins.push((c[1].to_string(), c[2].to_string(), c[3].to_string(), line_number));
+                continue;
+            }
+            if trimmed.contains("table!") {
+                in_macro = true;
+                continue;
+            }
+            if !in_macro {
+                continue;
+            }
+
+            if let Some((model, _)) = current.as_mut() {
+                if trimmed.starts_with('}') {
+                    model.end_line = line_number;
+                    if let Some((mut model, primary_keys)) = current.take() {
+                        for field in &mut model.fields {
+                            field.primary_key = primary_keys.contains(&field.name);
+                        }
+                        models.push(model);
+                    }
+                } else if let Some(c) = p.diesel_column.captures(trimmed) {
+                    let field_type = c[2].trim().to_string();
+                    model.fields.push(ModelField {
+                        name: c[1].to_string(),
+                        nullable: field_type.starts_with("Nullable<"),
+                        field_type,
+                        primary_key: false,
+                        line: line_number,
+                    });
+                }
+            } else if let Some(c) = p.diesel_table.captures(trimmed) {
+                // Diesel defaults the primary key to `id` when none is listed
+                let primary_keys = c.get(2).map_or_else(
+                    || vec!["id".to_string()],
+                    |keys| keys.as_str().split(',').map(|k| k.trim().to_string()).collect(),
+                );
+                current = Some((Self::model(&c[1], "diesel", file_path, line_number), primary_keys));
+            } else if trimmed.starts_with('}') {
+                in_macro = false;
+            }
+        }
+
+        for (child, parent, foreign_key, line) in joins {
+            if let Some(model) = models.iter_mut().find(|m| m.name == child) {
+                model.relations.push(ModelRelation {
+                    field: foreign_key,
+                    target: parent,
+                    kind: "foreign_key".to_string(),
+                    line,
+                });
+            }
+        }
+
+        models
+    }
+
+    fn typeorm_models(file_path: &str, content: &str) -> Vec<DataModel> {
+        let p = patterns();
+        let mut models = Vec::new();
+        let mut entity: Option<Option<String>> = None;
+        let mut current: Option<DataModel> = None;
+        let mut brace_depth = 0i32;
+        let mut opened = false;
+        // Decorators waiting for the property they annotate, and one still
+        // open across lines with its unbalanced parenthesis count
+        let mut pending: Vec<(String, String)> = Vec::new();
+        let mut open: Option<(String, String, i32)> = None;
+
+        for (idx, line) in content.lines().enumerate() {
+            let line_number = idx as u32 + 1;
+            let trimmed = line.trim();
+
+            let Some(model) = current.as_mut() else {
+                if let Some(c) = p.ts_entity.captures(trimmed) {
+                    entity = Some(c.get(1).map(|m| m.as_str().to_string()));
+                } else if entity.is_some() {
+                    if let Some(c) = p.ts_class.captures(trimmed) {
+                        let mut model = Self::model(&c[1], "typeorm", file_path, line_number);
+                        model.table_name = entity.take().flatten();
+                        brace_depth = brace_delta(trimmed);
+                        opened = brace_depth > 0;
+                        pending.clear();
+                        current = Some(model);
+                    }
+                }
+                continue;
+            };
+
+            brace_depth += brace_delta(trimmed);
+            if !opened {
+                opened = brace_depth > 0;
+                continue;
+            }
+            if brace_depth <= 0 {
+                model.end_line = line_number;
+                models.extend(current.take());
+                continue;
+            }
+
+            if let Some((name, mut args, depth)) = open.take() {
+                args.push(' ');
+                args.push_str(trimmed);
+                let depth = depth + paren_delta(trimmed);
+                if depth > 0 {
+                    open = Some((name, args, depth));
+                } else {
+                    pending.push((name, args));
+                }
+                continue;
+            }
+
+            if let Some(c) = p.ts_decorator.captures(trimmed) {
+                let args = c.get(2).map_or("", |m| m.as_str()).to_string();
+                let depth = paren_delta(&args);
+                if depth > 0 {
+                    open = Some((c[1].to_string(), args, depth));
+                    continue;
+                }
+                pending.push((c[1].to_string(), args));
+            }
+
+            if let Some(c) = p.ts_property.captures(trimmed) {
+                for (decorator, args) in pending.drain(..) {
+                    Self::typeorm_member(model, &decorator, &args, &c, line_number);
+                }
+            }
+        }
+
+        models
+    }
+
+    fn typeorm_member(model: &mut DataModel, decorator: &str, args: &str, property: &regex::Captures<'_>, line: u32) {
+        let p = patterns();
+        let name = property[1].to_string();
+        let optional = property.get(2).is_some_and(|m| m.as_str() == "?");
+        let ts_type = property[3].trim();
+
+        let kind = match decorator {
+            "ManyToOne" | "OneToOne" => "one",
+            "OneToMany" => "many",
+            "ManyToMany" => "many_to_many",
+            "Column" | "PrimaryColumn" | "PrimaryGeneratedColumn" | "CreateDateColumn" | "UpdateDateColumn"
+            | "DeleteDateColumn" | "VersionColumn" | "ObjectIdColumn" => {
+                let primary_key = decorator.starts_with("Primary") || decorator == "ObjectIdColumn";
+                let field_type = p
+                    .ts_column_type
+                    .captures(args)
+                    .and_then(|c| c.get(1).or_else(|| c.get(2)))
+                    .map_or_else(|| ts_type.to_string(), |m| m.as_str().to_string());
+                let nullable = !primary_key
+                    && (optional || args.replace(' ', "").contains("nullable:true") || ts_type.contains("null"));
+                model.fields.push(ModelField {
+                    name,
+                    field_type,
+                    nullable,
+                    primary_key,
+                    line,
+                });
+                return;
+            }
+            _ => return,
+        };
+
+        let target = p
+            .ts_relation_target
+            .captures(args)
+            .map_or_else(|| ts_type.trim_end_matches("[]").to_string(), |c| c[1].to_string());
+        model.relations.push(ModelRelation {
+            field: name,
+            target,
+            kind: kind.to_string(),
+            line,
+        });
+    }
+}
+
+fn brace_delta(line: &str) -> i32 {
+    line.matches('{').count() as i32 - line.matches('}').count() as i32
+}
+
+fn paren_delta(text: &str) -> i32 {
+    text.matches('(').count() as i32 - text.matches(')').count() as i32
+}
+
+struct Patterns {
+    prisma_model: Regex,
+    prisma_map: Regex,
+    prisma_field: Regex,
+    python_class: Regex,
+    declarative_base: Regex,
+    sa_tablename: Regex,
+    sa_member: Regex,
+    sa_column_type: Regex,
+    sa_foreign_key: Regex,
+    sa_relationship_target: Regex,
+    sa_annotation_target: Regex,
+    diesel_table: Regex,
+    diesel_column: Regex,
+    diesel_joinable: Regex,
+    ts_entity: Regex,
+    ts_class: Regex,
+    ts_decorator: Regex,
+    ts_property: Regex,
+    ts_column_type: Regex,
+    ts_relation_target: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let re = |pattern: &str| Regex::new(pattern).expect("valid data model regex");
+        Patterns {
+            prisma_model: re(r"^model\s+(\w+)\s*\{"),
+            prisma_map: re(r#"^@@map\(\s*"([^"]+)""#),
+            prisma_field: re(r"^(\w+)\s+(\w+)(\[\])?(\?)?(.*)$"),
+            python_class: re(r"^(\s*)class\s+(\w+)\s*(?:\(([^)]*)\))?\s*:"),
+            declarative_base: re(r"\b(?:Base|Model|DeclarativeBase|SQLModel)\b"),
+            sa_tablename: re(r#"^\s+__tablename__\s*=\s*["']([^"']+)["']"#),
+            sa_member: re(r"^\s+(\w+)\s*(?::\s*([^=]+?))?\s*=\s*(?:\w+\.)?(Column|mapped_column|relationship)\((.*)$"),
+            sa_column_type: re(r#"^\s*(?:["'][^"']*["']\s*,\s*)?(?:\w+\.)?([A-Z]\w*)"#),
+            sa_foreign_key: re(r#"ForeignKey\(\s*["']([^"']+)["']"#),
+            sa_relationship_target: re(r#"^\s*["'](\w+)["']"#),
+            sa_annotation_target: re(r#"(\w+)["']?\]+$"#),
+            diesel_table: re(r"^(?:\w+\.)?(\w+)\s*(?:\(([^)]*)\))?\s*\{"),
+            diesel_column: re(r"^(\w+)\s*->\s*([^,]+?),?$"),
+            diesel_joinable: re(r"joinable!\s*\(\s*(\w+)\s*->\s*(\w+)\s*\(\s*(\w+)\s*\)"),
+            ts_entity: re(r#"^@Entity\b(?:\(\s*(?:\{[^}]*?name:\s*)?["']([^"']+)["'])?"#),
+            ts_class: re(r"^(?:export\s+)?(?:default\s+)?(?:abstract\s+)?class\s+(\w+)"),
+            ts_decorator: re(r"^@(\w+)(\(.*)?$"),
+            ts_property: re(
+                r"^(?:@\w+(?:\(.*\))?\s+)*(?:(?:public|private|protected|readonly|declare)\s+)*(\w+)([?!])?\s*:\s*([^;=]+)",
+            ),
+            ts_column_type: re(r#"^\(\s*["'](\w+)["']|type:\s*["'](\w+)["']"#),
+            ts_relation_target: re(r"=>\s*(\w+)"),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field<'a>(model: &'a DataModel, name: &str) -> &'a ModelField {
+        model.fields.iter().find(|f| f.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_prisma_models_and_relations() {
+        let schema = r#"
+model User {
+  id      Int      @id @default(autoincrement())
+  email   String   @unique
+  name    String?
+  posts   Post[]
+
+  @@map("users")
+}
+
+model Post {
+  id       Int  @id
+  authorId Int
+  author   User @relation(fields: [authorId], references: [id])
+}
+
+enum Role {
+  ADMIN
+}
+"#;
+        let models = DataModelExtractor::extract("prisma/schema.prisma", schema);
+        assert_eq!(models.len(), 2);
+
+        let user = &models[0];
+        assert_eq!(user.name, "User");
+        assert_eq!(user.table_name.as_deref(), Some("users"));
+        assert_eq!((user.line, user.end_line), (2, 9));
+        assert_eq!(user.fields.len(), 3);
+        assert!(field(user, "id").primary_key);
+        assert!(field(user, "name").nullable);
+        assert!(!field(user, "email").nullable);
+        assert_eq!(user.relations[0].target, "Post");
+        assert_eq!(user.relations[0].kind, "many");
+
+        let post = &models[1];
+        assert_eq!(post.relations.len(), 1);
+        assert_eq!(post.relations[0].field, "author");
+        assert_eq!(post.relations[0].kind, "one");
+    }
+
+    #[test]
+    fn test_sqlalchemy_models() {
+        let source = r#"
+from sqlalchemy.orm import DeclarativeBase, Mapped, mapped_column, relationship
+
+class Base(DeclarativeBase):
+    pass
+
+class TimestampMixin:
+    created_at = Column(DateTime)
+
+class User(Base):
+    __tablename__ = "users"
+
+    id = Column(Integer, primary_key=True)
+    email: Mapped[str] = mapped_column(String(255))
+    org_id = Column(Integer, ForeignKey("orgs.id"), nullable=False)
+    posts: Mapped[List["Post"]] = relationship(back_populates="author")
+    groups = relationship("Group", secondary=user_groups)
+
+    def display_name(self):
+        return self.email
+"#;
+        let models = DataModelExtractor::extract("app/models.py", source);
+        assert_eq!(models.len(), 1);
+
+        let user = &models[0];
+        assert_eq!(user.name, "User");
+        assert_eq!(user.table_name.as_deref(), Some("users"));
+        assert_eq!(user.fields.len(), 3);
+        assert!(field(user, "id").primary_key);
+        assert_eq!(field(user, "email").field_type, "String");
+        assert!(!field(user, "email").nullable);
+        assert!(!field(user, "org_id").nullable);
+
+        let targets: Vec<(&str, &str)> = user.relations.iter().map(|r| (r.target.as_str(), r.kind.as_str())).collect();
+        assert_eq!(targets, vec![("orgs", "foreign_key"), ("Post", "many"), ("Group", "many_to_many")]);
+    }
+
+    #[test]
+    fn test_diesel_schema() {
+        let schema = r#"
+diesel::table! {
+    users (id) {
+        id -> Int4,
+        name -> Varchar,
+        bio -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    posts {
+        id -> Int4,
+        user_id -> Int4,
+    }
+}
+
+diesel::joinable!(posts -> users (user_id));
+"#;
+        let models = DataModelExtractor::extract("src/schema.rs", schema);
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].name, "users");
+        assert!(field(&models[0], "id").primary_key);
+        assert!(field(&models[0], "bio").nullable);
+        assert_eq!(field(&models[0], "bio").field_type, "Nullable<Text>");
+        assert!(field(&models[1], "id").primary_key);
+        assert_eq!(models[1].relations[0].field, "user_id");
+        assert_eq!(models[1].relations[0].target, "users");
+    }
+
+    #[test]
+    fn test_typeorm_entities() {
+        let source = r#"
+import { Entity, Column, PrimaryGeneratedColumn, ManyToOne, OneToMany } from "typeorm";
+
+@Entity("users")
+export class User {
+  @PrimaryGeneratedColumn()
+  id: number;
+
+  @Column({
+    type: "varchar",
+    nullable: true,
+  })
+  nickname: string;
+
+  @Column() email: string;
+
+  @ManyToOne(() => Organization, (org) => org.users)
+  organization: Organization;
+
+  @OneToMany(() => Post, (post) => post.author)
+  posts: Post[];
+
+  greet(): string {
+    return `hi ${this.email}`;
+  }
+}
+"#;
+        let models = DataModelExtractor::extract("src/entities/user.ts", source);
+        assert_eq!(models.len(), 1);
+
+        let user = &models[0];
+        assert_eq!(user.table_name.as_deref(), Some("users"));
+        assert_eq!(user.fields.len(), 3);
+        assert!(field(user, "id").primary_key);
+        assert_eq!(field(user, "nickname").field_type, "varchar");
+        assert!(field(user, "nickname").nullable);
+        assert!(!field(user, "email").nullable);
+        assert_eq!(user.relations.len(), 2);
+        assert_eq!((user.relations[0].target.as_str(), user.relations[0].kind.as_str()), ("Organization", "one"));
+        assert_eq!((user.relations[1].target.as_str(), user.relations[1].kind.as_str()), ("Post", "many"));
+        assert_eq!(user.end_line, 26);
+    }
+
+    #[test]
+    fn test_scan_links_models_to_code() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("prisma")).unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("prisma/schema.prisma"), "model Invoice {\n  id Int @id\n}\n").unwrap();
+        fs::write(dir.path().join("src/billing.ts"), "export function total(invoice: Invoice) {}\n").unwrap();
+        fs::write(dir.path().join("src/other.ts"), "export const invoices = [];\n").unwrap();
+
+        let summary = DataModelExtractor::scan(dir.path()).unwrap();
+        assert_eq!(summary.total_models, 1);
+        assert_eq!(summary.by_source["prisma"], 1);
+        assert_eq!(summary.models[0].used_in, vec!["src/billing.ts".to_string()]);
+
+        let concepts = DataModelExtractor::to_concepts(&summary.models);
+        let types: Vec<&str> = concepts.iter().map(|c| c.concept_type.as_str()).collect();
+        assert_eq!(types, vec!["table", "column"]);
+        assert_eq!(concepts[1].relationships["parent_table"], "Invoice");
+    }
+}
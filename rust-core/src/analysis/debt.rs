@@ -0,0 +1,283 @@
+//! Technical debt markers written down in code comments
+//!
+//! Collects TODO/FIXME/HACK/XXX/DEPRECATED comments, attaches the concept they
+//! sit in (or directly above), and groups them so the blueprint can list the
+//! known issues the team already wrote down.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::types::SemanticConcept;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+/// Comments this many lines above a concept are attributed to it
+const LEADING_COMMENT_LINES: u32 = 3;
+
+/// Files listed in the hotspot section of a summary
+const MAX_HOTSPOTS: usize = 10;
+
+/// One marker comment
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct DebtItem {
+    pub file_path: String,
+    pub line: u32,
+    /// 'todo', 'fixme', 'hack', 'xxx' or 'deprecated'
+    pub category: String,
+    /// 'high' for fixme/xxx, 'medium' for hack/deprecated, 'low' for todo
+    pub priority: String,
+    pub text: String,
+    /// Name in `TODO(name)` style markers
+    pub author: Option<String>,
+    pub concept_name: Option<String>,
+    pub concept_type: Option<String>,
+}
+
+/// Number of markers in one file
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct DebtHotspot {
+    pub file_path: String,
+    pub item_count: u32,
+}
+
+/// Technical debt section of the project blueprint
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct TechnicalDebtSummary {
+    pub total_items: u32,
+    pub by_category: BTreeMap<String, u32>,
+    /// Files with the most markers first
+    pub hotspots: Vec<DebtHotspot>,
+    /// Highest priority first, then by file and line
+    pub items: Vec<DebtItem>,
+}
+
+pub struct DebtCommentExtractor;
+
+impl DebtCommentExtractor {
+    /// Cheap check used to avoid parsing files without any marker
+    pub fn has_markers(content: &str) -> bool {
+        marker_regex().is_match(content)
+    }
+
+    /// Collect marker comments from a file, using `concepts` for context
+    pub fn extract(file_path: &str, content: &str, concepts: &[SemanticConcept]) -> Vec<DebtItem> {
+        let mut items = Vec::new();
+        let mut in_block_comment = false;
+
+        for (idx, line) in content.lines().enumerate() {
+            let comment = Self::comment_text(line, &mut in_block_comment);
+            let Some(comment) = comment else {
+                continue;
+            };
+            let Some(captures) = marker_regex().captures(comment) else {
+                continue;
+            };
+
+            let marker = captures.get(1).map_or("", |m| m.as_str());
+            let category = marker.trim_start_matches('@').to_lowercase();
+            let author = captures
+                .get(2)
+                .map(|m| m.as_str().trim().to_string())
+                .filter(|a| !a.is_empty());
+            let text = captures
+                .get(3)
+                .map_or("", |m| m.as_str())
+                .trim()
+                .trim_end_matches("*/")
+                .trim_end_matches("-->")
+                .trim()
+                .to_string();
+
+            let line_number = idx as u32 + 1;
+            let concept = Self::enclosing_concept(concepts, line_number);
+            items.push(DebtItem {
+                file_path: file_path.to_string(),
+                line: line_number,
+                priority: Self::priority(&category).to_string(),
+                category,
+                text,
+                author,
+                concept_name: concept.map(|c| c.name.clone()),
+                concept_type: concept.map(|c| c.concept_type.clone()),
+            });
+        }
+
+        items
+    }
+
+    /// Group items into a summary, keeping every item
+    pub fn summarize(mut items: Vec<DebtItem>) -> TechnicalDebtSummary {
+        let mut by_category: BTreeMap<String, u32> = BTreeMap::new();
+        let mut per_file: HashMap<String, u32> = HashMap::new();
+        for item in &items {
+            *by_category.entry(item.category.clone()).or_insert(0) += 1;
+            *per_file.entry(item.file_path.clone()).or_insert(0) += 1;
+        }
+
+        let mut hotspots: Vec<DebtHotspot> = per_file
+            .into_iter()
+            .map(|(file_path, item_count)| DebtHotspot { file_path, item_count })
+            .collect();
+        hotspots.sort_by(|a, b| b.item_count.cmp(&a.item_count).then_with(|| a.file_path.cmp(&b.file_path)));
+        hotspots.truncate(MAX_HOTSPOTS);
+
+        items.sort_by(|a, b| {
+            Self::priority_rank(&a.priority)
+                .cmp(&Self::priority_rank(&b.priority))
+                .then_with(|| a.file_path.cmp(&b.file_path))
+                .then_with(|| a.line.cmp(&b.line))
+        });
+
+        TechnicalDebtSummary {
+            total_items: items.len() as u32,
+            by_category,
+            hotspots,
+            items,
+        }
+    }
+
+    /// The comment part of a line, tracking `/* ... */` blocks across lines
+    fn comment_text<'a>(line: &'a str, in_block_comment: &mut bool) -> Option<&'a str> {
+        if *in_block_comment {
+            if line.contains("*/") {
+                *in_block_comment = false;
+            }
+            return Some(line);
+        }
+
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('*') {
+            return Some(trimmed);
+        }
+
+        let start = ["//", "/*", "<!--", "#", "--"]
+            .iter()
+            .filter_map(|opener| line.find(opener))
+            .min()?;
+        let comment = &line[start..];
+        if comment.starts_with("/*") && !comment.contains("*/") {
+            *in_block_comment = true;
+        }
+        Some(comment)
+    }
+
+    /// Innermost concept containing the line, else the first concept starting
+    /// just below it
+    fn enclosing_concept(concepts: &[SemanticConcept], line: u32) -> Option<&SemanticConcept> {
+        let containing = concepts
+            .iter()
+            .filter(|c| c.line_range.start <= line && line <= c.line_range.end)
+            .min_by_key(|c| c.line_range.end - c.line_range.start);
+        if containing.is_some() {
+            return containing;
+        }
+
+        concepts
+            .iter()
+            .filter(|c| c.line_range.start > line && c.line_range.start - line <= LEADING_COMMENT_LINES)
+            .min_by_key(|c| c.line_range.start)
+    }
+
+    fn priority(category: &str) -> &'static str {
+        match category {
+            "fixme" | "xxx" => "high",
+            "hack" | "deprecated" => "medium",
+            _ => "low",
+        }
+    }
+
+    fn priority_rank(priority: &str) -> u8 {
+        match priority {
+            "high" => 0,
+            "medium" => 1,
+            _ => 2,
+        }
+    }
+}
+
+fn marker_regex() -> &'static Regex {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    MARKER.get_or_init(|| {
+        Regex::new(r"(?:^|\W)(TODO|FIXME|HACK|XXX|DEPRECATED|@deprecated)\b(?:\(([^)]*)\))?:?\s*(.*)")
+            .expect("valid debt marker regex")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LineRange;
+
+    fn concept(name: &str, start: u32, end: u32) -> SemanticConcept {
+        SemanticConcept {
+            id: name.to_string(),
+            name: name.to_string(),
+            concept_type: "function".to_string(),
+            confidence: 0.9,
+            file_path: "src/app.ts".to_string(),
+            line_range: LineRange { start, end },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    const SOURCE: &str = "\
+// TODO(alice): split this module
+function load() {
+  const todoList = [];
+  // FIXME: retries forever on 500s
+  return fetch(url);
+}
+
+/**
+ * @deprecated use loadAll instead
+ */
+function loadOne() {}
+";
+
+    #[test]
+    fn test_extracts_markers_with_context() {
+        let concepts = vec![concept("load", 2, 6), concept("loadOne", 11, 11)];
+        let items = DebtCommentExtractor::extract("src/app.ts", SOURCE, &concepts);
+
+        assert_eq!(items.len(), 3);
+
+        assert_eq!(items[0].category, "todo");
+        assert_eq!(items[0].author.as_deref(), Some("alice"));
+        assert_eq!(items[0].text, "split this module");
+        assert_eq!(items[0].concept_name.as_deref(), Some("load"));
+
+        assert_eq!(items[1].category, "fixme");
+        assert_eq!(items[1].priority, "high");
+        assert_eq!(items[1].line, 4);
+        assert_eq!(items[1].concept_name.as_deref(), Some("load"));
+
+        assert_eq!(items[2].category, "deprecated");
+        assert_eq!(items[2].text, "use loadAll instead");
+        assert_eq!(items[2].concept_name.as_deref(), Some("loadOne"));
+    }
+
+    #[test]
+    fn test_markers_outside_comments_are_ignored() {
+        let content = "let message = \"TODO\";\nfn TODO_list() {}\n";
+        assert!(DebtCommentExtractor::extract("src/lib.rs", content, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_summary_orders_by_priority() {
+        let mut items = DebtCommentExtractor::extract("src/app.ts", SOURCE, &[]);
+        items.extend(DebtCommentExtractor::extract("src/db.py", "# HACK: sleep until the lock clears\n", &[]));
+
+        let summary = DebtCommentExtractor::summarize(items);
+        assert_eq!(summary.total_items, 4);
+        assert_eq!(summary.by_category["todo"], 1);
+        assert_eq!(summary.items[0].category, "fixme");
+        assert_eq!(summary.items.last().unwrap().category, "todo");
+        assert_eq!(summary.hotspots[0].file_path, "src/app.ts");
+        assert_eq!(summary.hotspots[0].item_count, 3);
+    }
+}
@@ -0,0 +1,636 @@
+//! Dependency manifest parsing - package.json, Cargo.toml, pyproject.toml, go.mod
+//!
+//! Manifests are parsed properly rather than searched for substrings, so the
+//! names, versions and kinds of every declared dependency are available along
+//! with the package license and any workspace members.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::types::ParseError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use toml::Value as TomlValue;
+use walkdir::WalkDir;
+
+/// Manifest file names understood by the parser
+pub const MANIFEST_FILES: &[&str] = &["package.json", "Cargo.toml", "pyproject.toml", "go.mod", "go.work"];
+
+/// Directories that hold vendored or generated manifests
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", "target", "venv", ".venv", "vendor"];
+
+/// Optional-dependency groups in pyproject.toml treated as development dependencies
+const PYTHON_DEV_GROUPS: &[&str] = &["dev", "develop", "development", "test", "tests", "testing", "lint", "docs", "typing"];
+
+/// One declared dependency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct Dependency {
+    pub name: String,
+    /// Version requirement as written, if any
+    pub version: Option<String>,
+    /// 'direct', 'dev', 'build', 'peer', 'optional' or 'indirect'
+    pub kind: String,
+}
+
+/// A parsed dependency manifest
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct PackageManifest {
+    /// Path relative to the analyzed root
+    pub path: String,
+    /// 'npm', 'cargo', 'python' or 'go'
+    pub ecosystem: String,
+    pub package_name: Option<String>,
+    pub version: Option<String>,
+    pub license: Option<String>,
+    /// Workspace member paths or globs as declared
+    pub workspace_members: Vec<String>,
+    pub dependencies: Vec<Dependency>,
+}
+
+/// Dependency section of the project blueprint
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct DependencySummary {
+    pub manifests: Vec<PackageManifest>,
+    pub total_dependencies: u32,
+    pub direct_dependencies: u32,
+    pub dev_dependencies: u32,
+    /// Build, peer, optional and indirect dependencies
+    pub other_dependencies: u32,
+    pub licenses: Vec<String>,
+    pub workspace_members: Vec<String>,
+}
+
+pub struct ManifestParser;
+
+impl ManifestParser {
+    /// Parse a manifest by file name. Returns `Ok(None)` for unknown file names.
+    pub fn parse(file_name: &str, content: &str) -> Result<Option<PackageManifest>, ParseError> {
+        let manifest = match file_name {
+            "package.json" => Self::parse_package_json(content)?,
+            "Cargo.toml" => Self::parse_cargo_toml(content)?,
+            "pyproject.toml" => Self::parse_pyproject(content)?,
+            "go.mod" => Self::parse_go_mod(content),
+            "go.work" => Self::parse_go_work(content),
+            _ => return Ok(None),
+        };
+        Ok(Some(manifest))
+    }
+
+    /// Parse every manifest under `root`, falling back to a LICENSE file next to
+    /// the manifest when it declares no license
+    pub fn analyze(root: &Path) -> Result<DependencySummary, ParseError> {
+        let mut manifests = Vec::new();
+        for entry in WalkDir::new(root)
+            .max_depth(4)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let file_path = entry.path();
+            let file_name = entry.file_name().to_str().unwrap_or("");
+            if !entry.file_type().is_file() || !MANIFEST_FILES.contains(&file_name) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(file_path) else {
+                continue;
+            };
+            // A malformed manifest shouldn't hide the others
+            let Ok(Some(mut manifest)) = Self::parse(file_name, &content) else {
+                continue;
+            };
+
+            manifest.path = file_path
+                .strip_prefix(root)
+                .unwrap_or(file_path)
+                .to_string_lossy()
+                .to_string();
+            if manifest.license.is_none() {
+                manifest.license = file_path.parent().and_then(Self::detect_license_file);
+            }
+            manifests.push(manifest);
+        }
+
+        Ok(Self::summarize(manifests))
+    }
+
+    pub fn summarize(manifests: Vec<PackageManifest>) -> DependencySummary {
+        let mut summary = DependencySummary::default();
+        let mut licenses = BTreeSet::new();
+        let mut members = BTreeSet::new();
+
+        for manifest in &manifests {
+            for dependency in &manifest.dependencies {
+                summary.total_dependencies += 1;
+                match dependency.kind.as_str() {
+                    "direct" => summary.direct_dependencies += 1,
+                    "dev" => summary.dev_dependencies += 1,
+                    _ => summary.other_dependencies += 1,
+                }
+            }
+            licenses.extend(manifest.license.clone());
+            members.extend(manifest.workspace_members.iter().cloned());
+        }
+
+        summary.licenses = licenses.into_iter().collect();
+        summary.workspace_members = members.into_iter().collect();
+        summary.manifests = manifests;
+        summary
+    }
+
+    fn parse_package_json(content: &str) -> Result<PackageManifest, ParseError> {
+        let json: JsonValue = serde_json::from_str(content)
+            .map_err(|e| ParseError::from_reason(format!("Invalid package.json: {}", e)))?;
+
+        let mut dependencies = Vec::new();
+        for (section, kind) in [
+            ("dependencies", "direct"),
+            ("devDependencies", "dev"),
+            ("peerDependencies", "peer"),
+            ("optionalDependencies", "optional"),
+        ] {
+            if let Some(deps) = json.get(section).and_then(JsonValue::as_object) {
+                for (name, version) in deps {
+                    dependencies.push(Dependency {
+                        name: name.clone(),
+                        version: version.as_str().map(str::to_string),
+                        kind: kind.to_string(),
+                    });
+                }
+            }
+        }
+
+        // "workspaces": [..] or "workspaces": { "packages": [..] }
+        let workspaces = json.get("workspaces");
+        let workspace_members = workspaces
+            .and_then(JsonValue::as_array)
+            .or_else(|| workspaces.and_then(|w| w.get("packages")).and_then(JsonValue::as_array))
+            .map(|members| members.iter().filter_map(|m| m.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+
+        let license = match json.get("license") {
+            Some(JsonValue::String(license)) => Some(license.clone()),
+            Some(other) => other.get("type").and_then(JsonValue::as_str).map(str::to_string),
+            None => None,
+        };
+
+        Ok(PackageManifest {
+            path: "package.json".to_string(),
+            ecosystem: "npm".to_string(),
+            package_name: json.get("name").and_then(JsonValue::as_str).map(str::to_string),
+            version: json.get("version").and_then(JsonValue::as_str).map(str::to_string),
+            license,
+            workspace_members,
+            dependencies,
+        })
+    }
+
+    fn parse_cargo_toml(content: &str) -> Result<PackageManifest, ParseError> {
+        let toml: toml::Table = toml::from_str(content)
+            .map_err(|e| ParseError::from_reason(format!("Invalid Cargo.toml: {}", e)))?;
+
+        let mut dependencies = Vec::new();
+        Self::collect_cargo_tables(&toml, &mut dependencies);
+        if let Some(targets) = toml.get("target").and_then(TomlValue::as_table) {
+            for target in targets.values().filter_map(TomlValue::as_table) {
+                Self::collect_cargo_tables(target, &mut dependencies);
+            }
+        }
+
+        let package = toml.get("package");
+        let workspace = toml.get("workspace");
+        if let Some(shared) = workspace.and_then(|w| w.get("dependencies")).and_then(TomlValue::as_table) {
+            Self::collect_cargo_dependencies(shared, "direct", &mut dependencies);
+        }
+
+        Ok(PackageManifest {
+            path: "Cargo.toml".to_string(),
+            ecosystem: "cargo".to_string(),
+            package_name: Self::toml_str(package, "name"),
+            version: Self::toml_str(package, "version"),
+            license: Self::toml_str(package, "license")
+                .or_else(|| Self::toml_str(workspace.and_then(|w| w.get("package")), "license")),
+            workspace_members: Self::toml_str_array(workspace.and_then(|w| w.get("members"))),
+            dependencies,
+        })
+    }
+
+    fn collect_cargo_tables(table: &toml::Table, dependencies: &mut Vec<Dependency>) {
+        for (section, kind) in [
+            ("dependencies", "direct"),
+            ("dev-dependencies", "dev"),
+            ("build-dependencies", "build"),
+        ] {
+            if let Some(deps) = table.get(section).and_then(TomlValue::as_table) {
+                Self::collect_cargo_dependencies(deps, kind, dependencies);
+            }
+        }
+    }
+
+    fn collect_cargo_dependencies(deps: &toml::Table, kind: &str, dependencies: &mut Vec<Dependency>) {
+        for (name, spec) in deps {
+            let version = match spec {
+                TomlValue::String(version) => Some(version.clone()),
+                TomlValue::Table(table) => table
+                    .get("version")
+                    .and_then(TomlValue::as_str)
+                    .map(str::to_string)
+                    .or_else(|| {
+                        table
+                            .get("workspace")
+                            .and_then(TomlValue::as_bool)
+                            .filter(|inherited| *inherited)
+                            .map(|_| "workspace".to_string())
+                    }),
+                _ => None,
+            };
+            // `renamed = { package = "real-name" }` depends on the real crate
+            let name = spec
+                .get("package")
+                .and_then(TomlValue::as_str)
+                .unwrap_or(name)
+                .to_string();
+            dependencies.push(Dependency {
+                name,
+                version,
+                kind: kind.to_string(),
+            });
+        }
+    }
+
+    fn parse_pyproject(content: &str) -> Result<PackageManifest, ParseError> {
+        let toml: toml::Table = toml::from_str(content)
+            .map_err(|e| ParseError::from_reason(format!("Invalid pyproject.toml: {}", e)))?;
+
+        let project = toml.get("project");
+        let poetry = toml.get("tool").and_then(|t| t.get("poetry"));
+        let mut dependencies = Vec::new();
+
+        // PEP 621
+        if let Some(deps) = project.and_then(|p| p.get("dependencies")).and_then(TomlValue::as_array) {
+            for requirement in deps.iter().filter_map(TomlValue::as_str) {
+                dependencies.extend(Self::parse_pep508(requirement, "direct"));
+            }
+        }
+        if let Some(groups) = project
+            .and_then(|p| p.get("optional-dependencies"))
+            .and_then(TomlValue::as_table)
+        {
+            for (group, deps) in groups {
+                let kind = if PYTHON_DEV_GROUPS.contains(&group.as_str()) { "dev" } else { "optional" };
+                for requirement in deps.as_array().into_iter().flatten().filter_map(TomlValue::as_str) {
+                    dependencies.extend(Self::parse_pep508(requirement, kind));
+                }
+            }
+        }
+        // PEP 735 dependency groups
+        if let Some(groups) = toml.get("dependency-groups").and_then(TomlValue::as_table) {
+            for deps in groups.values() {
+                for requirement in deps.as_array().into_iter().flatten().filter_map(TomlValue::as_str) {
+                    dependencies.extend(Self::parse_pep508(requirement, "dev"));
+                }
+            }
+        }
+
+        // Poetry
+        if let Some(deps) = poetry.and_then(|p| p.get("dependencies")).and_then(TomlValue::as_table) {
+            Self::collect_poetry_dependencies(deps, "direct", &mut dependencies);
+        }
+        if let Some(deps) = poetry.and_then(|p| p.get("dev-dependencies")).and_then(TomlValue::as_table) {
+            Self::collect_poetry_dependencies(deps, "dev", &mut dependencies);
+        }
+        if let Some(groups) = poetry.and_then(|p| p.get("group")).and_then(TomlValue::as_table) {
+            for group in groups.values() {
+                if let Some(deps) = group.get("dependencies").and_then(TomlValue::as_table) {
+                    Self::collect_poetry_dependencies(deps, "dev", &mut dependencies);
+                }
+            }
+        }
+
+        // `license = "MIT"` or `license = { text = "MIT" }`
+        let license = project
+            .and_then(|p| p.get("license"))
+            .and_then(|l| l.as_str().or_else(|| l.get("text").and_then(TomlValue::as_str)))
+            .map(str::to_string)
+            .or_else(|| Self::toml_str(poetry, "license"));
+
+        let uv_workspace = toml
+            .get("tool")
+            .and_then(|t| t.get("uv"))
+            .and_then(|u| u.get("workspace"));
+
+        Ok(PackageManifest {
+            path: "pyproject.toml".to_string(),
+            ecosystem: "python".to_string(),
+            package_name: Self::toml_str(project, "name").or_else(|| Self::toml_str(poetry, "name")),
+            version: Self::toml_str(project, "version").or_else(|| Self::toml_str(poetry, "version")),
+            license,
+            workspace_members: Self::toml_str_array(uv_workspace.and_then(|w| w.get("members"))),
+            dependencies,
+        })
+    }
+
+    fn collect_poetry_dependencies(deps: &toml::Table, kind: &str, dependencies: &mut Vec<Dependency>) {
+        for (name, spec) in deps {
+            // Poetry lists the interpreter alongside packages
+            if name == "python" {
+                continue;
+            }
+            let version = spec
+                .as_str()
+                .or_else(|| spec.get("version").and_then(TomlValue::as_str))
+                .map(str::to_string);
+            dependencies.push(Dependency {
+                name: name.to_lowercase(),
+                version,
+                kind: kind.to_string(),
+            });
+        }
+    }
+
+    /// Split a PEP 508 requirement like `requests[socks]>=2.0; python_version<"3.8"`
+    fn parse_pep508(requirement: &str, kind: &str) -> Option<Dependency> {
+        let requirement = requirement.split(';').next().unwrap_or("").trim();
+        let name_end = requirement
+            .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+            .unwrap_or(requirement.len());
+        let name = &requirement[..name_end];
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut rest = requirement[name_end..].trim_start();
+        if rest.starts_with('[') {
+            rest = rest.find(']').map_or("", |end| &rest[end + 1..]).trim_start();
+        }
+        let version = rest.trim_start_matches('(').trim_end_matches(')').trim();
+
+        Some(Dependency {
+            name: name.to_lowercase(),
+            version: (!version.is_empty()).then(|| version.to_string()),
+            kind: kind.to_string(),
+        })
+    }
+
+    fn parse_go_mod(content: &str) -> PackageManifest {
+        let mut module = None;
+        let mut dependencies = Vec::new();
+
+        for (directive, line) in Self::go_directives(content) {
+            match directive {
+                "module" => module = line.split_whitespace().next().map(str::to_string),
+                "require" => {
+                    let indirect = line.contains("// indirect");
+                    let mut parts = line.split("//").next().unwrap_or("").split_whitespace();
+                    if let Some(name) = parts.next() {
+                        dependencies.push(Dependency {
+                            name: name.to_string(),
+                            version: parts.next().map(str::to_string),
+                            kind: if indirect { "indirect" } else { "direct" }.to_string(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        PackageManifest {
+            path: "go.mod".to_string(),
+            ecosystem: "go".to_string(),
+            package_name: module,
+            version: None,
+            license: None,
+            workspace_members: Vec::new(),
+            dependencies,
+        }
+    }
+
+    fn parse_go_work(content: &str) -> PackageManifest {
+        let workspace_members = Self::go_directives(content)
+            .into_iter()
+            .filter(|(directive, _)| *directive == "use")
+            .filter_map(|(_, line)| line.split("//").next())
+            .map(|member| member.trim().to_string())
+            .filter(|member| !member.is_empty())
+            .collect();
+
+        PackageManifest {
+            path: "go.work".to_string(),
+            ecosystem: "go".to_string(),
+            package_name: None,
+            version: None,
+            license: None,
+            workspace_members,
+            dependencies: Vec::new(),
+        }
+    }
+
+    /// (directive, argument) pairs from a go.mod/go.work file, expanding
+    /// `directive ( ... )` blocks into one pair per line
+    fn go_directives(content: &str) -> Vec<(&str, &str)> {
+        let mut directives = Vec::new();
+        let mut block: Option<&str> = None;
+
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            if let Some(directive) = block {
+                if line == ")" {
+                    block = None;
+                } else {
+                    directives.push((directive, line));
+                }
+                continue;
+            }
+
+            let (directive, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim();
+            if rest == "(" {
+                block = Some(directive);
+            } else {
+                directives.push((directive, rest));
+            }
+        }
+
+        directives
+    }
+
+    /// Identify a license from a LICENSE/COPYING file's text
+    fn detect_license_file(dir: &Path) -> Option<String> {
+        let content = ["LICENSE", "LICENSE.md", "LICENSE.txt", "COPYING"]
+            .iter()
+            .find_map(|name| fs::read_to_string(dir.join(name)).ok())?;
+        let text = content.to_lowercase();
+
+        let license = if text.contains("permission is hereby granted, free of charge") {
+            "MIT"
+        } else if text.contains("apache license") && text.contains("version 2.0") {
+            "Apache-2.0"
+        } else if text.contains("gnu affero general public license") {
+            "AGPL-3.0"
+        } else if text.contains("gnu lesser general public license") {
+            "LGPL"
+        } else if text.contains("gnu general public license") {
+            if text.contains("version 2") && !text.contains("version 3") { "GPL-2.0" } else { "GPL-3.0" }
+        } else if text.contains("mozilla public license") {
+            "MPL-2.0"
+        } else if text.contains("redistribution and use in source and binary forms") {
+            "BSD"
+        } else if text.contains("unlicense") || text.contains("free and unencumbered software") {
+            "Unlicense"
+        } else {
+            return None;
+        };
+        Some(license.to_string())
+    }
+
+    fn toml_str(table: Option<&TomlValue>, key: &str) -> Option<String> {
+        table.and_then(|t| t.get(key)).and_then(TomlValue::as_str).map(str::to_string)
+    }
+
+    fn toml_str_array(value: Option<&TomlValue>) -> Vec<String> {
+        value
+            .and_then(TomlValue::as_array)
+            .map(|items| items.iter().filter_map(|i| i.as_str().map(str::to_string)).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn names<'a>(manifest: &'a PackageManifest, kind: &str) -> Vec<&'a str> {
+        manifest
+            .dependencies
+            .iter()
+            .filter(|d| d.kind == kind)
+            .map(|d| d.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_package_json() {
+        let content = r#"{
+            "name": "web",
+            "license": "MIT",
+            "workspaces": { "packages": ["packages/*"] },
+            "dependencies": { "react": "^18.2.0" },
+            "devDependencies": { "typescript": "~5.4.0", "vitest": "^1.0.0" }
+        }"#;
+        let manifest = ManifestParser::parse("package.json", content).unwrap().unwrap();
+
+        assert_eq!(manifest.package_name.as_deref(), Some("web"));
+        assert_eq!(manifest.license.as_deref(), Some("MIT"));
+        assert_eq!(manifest.workspace_members, vec!["packages/*"]);
+        assert_eq!(names(&manifest, "direct"), vec!["react"]);
+        assert_eq!(names(&manifest, "dev").len(), 2);
+        assert_eq!(manifest.dependencies[0].version.as_deref(), Some("^18.2.0"));
+    }
+
+    #[test]
+    fn test_parse_cargo_toml() {
+        let content = r#"
+[package]
+name = "core"
+version = "0.1.0"
+license = "Apache-2.0"
+
+[workspace]
+members = ["crates/*"]
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+tokio = "1.48"
+shared = { workspace = true }
+rx = { package = "regex", version = "1" }
+
+[dev-dependencies]
+tempfile = "3"
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+"#;
+        let manifest = ManifestParser::parse("Cargo.toml", content).unwrap().unwrap();
+
+        assert_eq!(manifest.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(manifest.workspace_members, vec!["crates/*"]);
+        let direct = names(&manifest, "direct");
+        assert!(direct.contains(&"serde") && direct.contains(&"libc") && direct.contains(&"regex"));
+        assert_eq!(names(&manifest, "dev"), vec!["tempfile"]);
+        let shared = manifest.dependencies.iter().find(|d| d.name == "shared").unwrap();
+        assert_eq!(shared.version.as_deref(), Some("workspace"));
+    }
+
+    #[test]
+    fn test_parse_pyproject() {
+        let content = r#"
+[project]
+name = "svc"
+license = { text = "BSD-3-Clause" }
+dependencies = ["FastAPI>=0.110", "requests[socks] (>=2.31) ; python_version >= '3.8'"]
+
+[project.optional-dependencies]
+test = ["pytest"]
+postgres = ["psycopg"]
+
+[tool.poetry.group.lint.dependencies]
+ruff = "^0.4"
+"#;
+        let manifest = ManifestParser::parse("pyproject.toml", content).unwrap().unwrap();
+
+        assert_eq!(manifest.license.as_deref(), Some("BSD-3-Clause"));
+        assert_eq!(names(&manifest, "direct"), vec!["fastapi", "requests"]);
+        assert_eq!(manifest.dependencies[1].version.as_deref(), Some(">=2.31"));
+        assert_eq!(names(&manifest, "optional"), vec!["psycopg"]);
+        let dev = names(&manifest, "dev");
+        assert!(dev.contains(&"pytest") && dev.contains(&"ruff"));
+    }
+
+    #[test]
+    fn test_parse_go_mod_and_work() {
+        let go_mod = "module example.com/api\n\ngo 1.22\n\nrequire github.com/spf13/cobra v1.8.0\n\nrequire (\n\tgithub.com/gin-gonic/gin v1.9.1\n\tgolang.org/x/sys v0.20.0 // indirect\n)\n";
+        let manifest = ManifestParser::parse("go.mod", go_mod).unwrap().unwrap();
+
+        assert_eq!(manifest.package_name.as_deref(), Some("example.com/api"));
+        assert_eq!(names(&manifest, "direct"), vec!["github.com/spf13/cobra", "github.com/gin-gonic/gin"]);
+        assert_eq!(names(&manifest, "indirect"), vec!["golang.org/x/sys"]);
+
+        let go_work = "go 1.22\n\nuse (\n\t./api\n\t./worker\n)\n";
+        let workspace = ManifestParser::parse("go.work", go_work).unwrap().unwrap();
+        assert_eq!(workspace.workspace_members, vec!["./api", "./worker"]);
+    }
+
+    #[test]
+    fn test_analyze_summarizes_project() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{"dependencies": {"express": "^4"}, "devDependencies": {"jest": "^29"}}"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join("LICENSE"), "Permission is hereby granted, free of charge, to any person").unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/express")).unwrap();
+        fs::write(dir.path().join("node_modules/express/package.json"), r#"{"dependencies": {"qs": "1"}}"#).unwrap();
+        fs::write(dir.path().join("broken.json"), "{").unwrap();
+
+        let summary = ManifestParser::analyze(dir.path()).unwrap();
+        assert_eq!(summary.manifests.len(), 1);
+        assert_eq!(summary.total_dependencies, 2);
+        assert_eq!(summary.direct_dependencies, 1);
+        assert_eq!(summary.dev_dependencies, 1);
+        assert_eq!(summary.licenses, vec!["MIT"]);
+    }
+}
@@ -0,0 +1,481 @@
+//! Deprecation markers and API version annotations
+//!
+//! Agents suggest deprecated APIs because nothing in a concept says they are
+//! deprecated. This reads `@deprecated` doc tags, `#[deprecated]`,
+//! `@Deprecated` and `[Obsolete]` attributes, deprecation decorators,
+//! `warnings.warn(..., DeprecationWarning)` calls and Sphinx
+//! `.. deprecated::` directives, plus API version decorators such as NestJS
+//! `@Version('2')` and ASP.NET `[ApiVersion("2.0")]`. Each marker is attached
+//! to the concept it declares (or, for runtime warnings, sits in) through the
+//! concept's metadata, and collected into a deprecated-surface report.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::types::SemanticConcept;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// Annotations this many lines above a concept declare it
+const DECLARATION_LINES: u32 = 10;
+
+/// Lines a `warnings.warn(` call may span
+const WARN_CALL_LINES: usize = 5;
+
+/// Metadata set to "true" on deprecated concepts
+pub const DEPRECATED_METADATA_KEY: &str = "deprecated";
+/// Metadata holding the deprecation message
+pub const DEPRECATION_NOTE_METADATA_KEY: &str = "deprecation_note";
+/// Metadata holding the version a concept was deprecated in
+pub const DEPRECATED_SINCE_METADATA_KEY: &str = "deprecated_since";
+/// Metadata listing the API versions a concept serves, comma separated
+pub const API_VERSION_METADATA_KEY: &str = "api_version";
+
+/// One deprecation marker or API version annotation
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ApiAnnotation {
+    pub file_path: String,
+    pub line: u32,
+    /// 'deprecated' or 'api_version'
+    pub kind: String,
+    /// How it was written: 'doc_tag', 'attribute', 'decorator', 'runtime_warning',
+    /// 'docstring' or 'version_attribute'
+    pub source: String,
+    pub note: Option<String>,
+    /// Version the API was deprecated in
+    pub since: Option<String>,
+    /// API versions for 'api_version' annotations, comma separated
+    pub version: Option<String>,
+    pub concept_name: Option<String>,
+    pub concept_type: Option<String>,
+}
+
+/// Versioned APIs sharing a version
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ApiVersionGroup {
+    pub version: String,
+    pub annotations: Vec<ApiAnnotation>,
+}
+
+/// Deprecated and versioned API surface of a project
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct DeprecatedSurface {
+    pub total_deprecated: u32,
+    pub by_source: BTreeMap<String, u32>,
+    /// By file and line
+    pub deprecated: Vec<ApiAnnotation>,
+    /// In version order
+    pub api_versions: Vec<ApiVersionGroup>,
+}
+
+/// Which concept an annotation belongs to
+#[derive(Clone, Copy)]
+enum Target {
+    /// The concept declared just below, for attributes, decorators and doc tags
+    Declared,
+    /// The innermost concept containing the line, for runtime warnings and docstrings
+    Enclosing,
+}
+
+pub struct DeprecationExtractor;
+
+impl DeprecationExtractor {
+    /// Cheap check used to avoid scanning files without any marker
+    pub fn has_markers(content: &str) -> bool {
+        let lower = content.to_lowercase();
+        lower.contains("deprecat") || lower.contains("obsolete") || lower.contains("version")
+    }
+
+    /// Collect the annotations of a file, using `concepts` to name what each
+    /// one belongs to
+    pub fn extract(file_path: &str, content: &str, concepts: &[SemanticConcept]) -> Vec<ApiAnnotation> {
+        Self::scan(file_path, content, concepts)
+            .into_iter()
+            .map(|(annotation, _)| annotation)
+            .collect()
+    }
+
+    /// Collect the annotations of a file and record them in the metadata of
+    /// the concepts they belong to
+    pub fn annotate(file_path: &str, content: &str, concepts: &mut [SemanticConcept]) -> Vec<ApiAnnotation> {
+        let found = Self::scan(file_path, content, concepts);
+        for (annotation, index) in &found {
+            let Some(concept) = index.and_then(|index| concepts.get_mut(index)) else {
+                continue;
+            };
+            let metadata = &mut concept.metadata;
+            match annotation.kind.as_str() {
+                "deprecated" => {
+                    metadata.insert(DEPRECATED_METADATA_KEY.to_string(), "true".to_string());
+                    if let Some(note) = &annotation.note {
+                        metadata.entry(DEPRECATION_NOTE_METADATA_KEY.to_string()).or_insert_with(|| note.clone());
+                    }
+                    if let Some(since) = &annotation.since {
+                        metadata.entry(DEPRECATED_SINCE_METADATA_KEY.to_string()).or_insert_with(|| since.clone());
+                    }
+                }
+                _ => {
+                    if let Some(version) = &annotation.version {
+                        metadata.insert(API_VERSION_METADATA_KEY.to_string(), version.clone());
+                    }
+                }
+            }
+        }
+        found.into_iter().map(|(annotation, _)| annotation).collect()
+    }
+
+    /// Group annotations into a report, keeping every annotation
+    pub fn summarize(annotations: Vec<ApiAnnotation>) -> DeprecatedSurface {
+        let mut deprecated = Vec::new();
+        let mut by_source: BTreeMap<String, u32> = BTreeMap::new();
+        let mut versions: BTreeMap<String, Vec<ApiAnnotation>> = BTreeMap::new();
+        for annotation in annotations {
+            if annotation.kind == "deprecated" {
+                *by_source.entry(annotation.source.clone()).or_insert(0) += 1;
+                deprecated.push(annotation);
+            } else if let Some(version) = annotation.version.clone() {
+                for version in version.split(',') {
+                    versions.entry(version.to_string()).or_default().push(annotation.clone());
+                }
+            }
+        }
+
+        let by_location = |a: &ApiAnnotation, b: &ApiAnnotation| a.file_path.cmp(&b.file_path).then(a.line.cmp(&b.line));
+        deprecated.sort_by(by_location);
+        let api_versions = versions
+            .into_iter()
+            .map(|(version, mut annotations)| {
+                annotations.sort_by(by_location);
+                ApiVersionGroup { version, annotations }
+            })
+            .collect();
+
+        DeprecatedSurface {
+            total_deprecated: deprecated.len() as u32,
+            by_source,
+            deprecated,
+            api_versions,
+        }
+    }
+
+    /// Annotations in line order with the index of the concept each belongs to
+    fn scan(file_path: &str, content: &str, concepts: &[SemanticConcept]) -> Vec<(ApiAnnotation, Option<usize>)> {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut found = Vec::new();
+        let mut push = |line: usize, kind: &str, source: &str, target: Target, fields: Fields| {
+            let line = line as u32 + 1;
+            let index = match target {
+                Target::Declared => Self::declared_concept(concepts, line),
+                Target::Enclosing => Self::enclosing_concept(concepts, line),
+            };
+            let concept = index.map(|index| &concepts[index]);
+            found.push((
+                ApiAnnotation {
+                    file_path: file_path.to_string(),
+                    line,
+                    kind: kind.to_string(),
+                    source: source.to_string(),
+                    note: fields.note,
+                    since: fields.since,
+                    version: fields.version,
+                    concept_name: concept.map(|c| c.name.clone()),
+                    concept_type: concept.map(|c| c.concept_type.clone()),
+                },
+                index,
+            ));
+        };
+
+        for (idx, line) in lines.iter().enumerate() {
+            let trimmed = line.trim_start();
+
+            if Self::is_doc_comment(trimmed) {
+                if let Some(captures) = regexes().doc_tag.captures(trimmed) {
+                    let note = captures.get(1).map(|m| Self::clean_comment(m.as_str()));
+                    push(idx, "deprecated", "doc_tag", Target::Declared, Fields::note(note));
+                }
+                continue;
+            }
+
+            if let Some(captures) = regexes().docstring.captures(line) {
+                let fields = Fields {
+                    since: captures.get(1).map(|m| m.as_str().to_string()),
+                    note: captures.get(2).map(|m| m.as_str().trim().to_string()).filter(|n| !n.is_empty()),
+                    version: None,
+                };
+                push(idx, "deprecated", "docstring", Target::Enclosing, fields);
+                continue;
+            }
+
+            if let Some(captures) = regexes().attribute.captures(trimmed) {
+                let args = captures.get(2).or_else(|| captures.get(3));
+                let fields = match (captures.get(1), args) {
+                    (Some(value), _) => Fields::note(Some(value.as_str().to_string())),
+                    (None, Some(args)) => Fields::from_args(args.as_str()),
+                    (None, None) => Fields::default(),
+                };
+                push(idx, "deprecated", "attribute", Target::Declared, fields);
+            } else if let Some(captures) = regexes().decorator.captures(trimmed) {
+                let fields = captures.get(1).map(|args| Fields::from_args(args.as_str())).unwrap_or_default();
+                push(idx, "deprecated", "decorator", Target::Declared, fields);
+            }
+
+            if let Some(captures) = regexes().version.captures(trimmed) {
+                let args = captures.get(1).map_or("", |m| m.as_str());
+                let versions: Vec<&str> = string_literals(args.split(['=', ':']).next().unwrap_or(args)).collect();
+                if !versions.is_empty() {
+                    let version = versions.join(",");
+                    if regexes().deprecated_flag.is_match(args) {
+                        let note = Some(format!("API version {} is deprecated", version));
+                        push(idx, "deprecated", "version_attribute", Target::Declared, Fields::note(note));
+                    }
+                    let fields = Fields { version: Some(version), ..Default::default() };
+                    push(idx, "api_version", "version_attribute", Target::Declared, fields);
+                }
+            }
+
+            // warnings.warn(...) in Python, process.emitWarning(...) in Node
+            if let Some(opener) = ["warn(", "emitWarning("].iter().find(|opener| trimmed.contains(*opener)) {
+                let call = lines[idx..lines.len().min(idx + WARN_CALL_LINES)].join(" ");
+                let call = call.split(opener).nth(1).unwrap_or("");
+                let call = &call[..Self::call_end(call)];
+                if regexes().deprecation_warning.is_match(call) {
+                    let note = string_literals(call).next().map(str::to_string);
+                    push(idx, "deprecated", "runtime_warning", Target::Enclosing, Fields::note(note));
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Length of a call's arguments up to its closing parenthesis
+    fn call_end(args: &str) -> usize {
+        let mut depth = 0;
+        for (idx, c) in args.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' if depth == 0 => return idx,
+                ')' => depth -= 1,
+                _ => {}
+            }
+        }
+        args.len()
+    }
+
+    fn is_doc_comment(trimmed: &str) -> bool {
+        ["/**", "*", "///", "//", "#"].iter().any(|prefix| trimmed.starts_with(prefix))
+            && !trimmed.starts_with("#[")
+    }
+
+    fn clean_comment(text: &str) -> String {
+        text.trim().trim_end_matches("*/").trim().to_string()
+    }
+
+    /// First concept starting at or just below the line, outermost first
+    fn declared_concept(concepts: &[SemanticConcept], line: u32) -> Option<usize> {
+        concepts
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.line_range.start >= line && c.line_range.start - line <= DECLARATION_LINES)
+            .min_by_key(|(_, c)| (c.line_range.start, u32::MAX - (c.line_range.end - c.line_range.start)))
+            .map(|(index, _)| index)
+    }
+
+    /// Innermost concept containing the line
+    fn enclosing_concept(concepts: &[SemanticConcept], line: u32) -> Option<usize> {
+        concepts
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.line_range.start <= line && line <= c.line_range.end)
+            .min_by_key(|(_, c)| c.line_range.end - c.line_range.start)
+            .map(|(index, _)| index)
+    }
+}
+
+/// Details read from a marker's arguments
+#[derive(Default)]
+struct Fields {
+    note: Option<String>,
+    since: Option<String>,
+    version: Option<String>,
+}
+
+impl Fields {
+    fn note(note: Option<String>) -> Self {
+        Fields { note: note.filter(|n| !n.is_empty()), ..Default::default() }
+    }
+
+    /// `since = "1.2", note = "..."` style arguments, or a lone message
+    fn from_args(args: &str) -> Self {
+        let mut fields = Fields::default();
+        for captures in regexes().named_arg.captures_iter(args) {
+            let value = captures.get(2).or_else(|| captures.get(3)).map_or("", |m| m.as_str()).to_string();
+            match captures[1].to_lowercase().as_str() {
+                "note" | "message" | "reason" | "details" => fields.note = Some(value),
+                "since" | "deprecated_in" | "version" => fields.since = Some(value),
+                _ => {}
+            }
+        }
+        if fields.note.is_none() && !regexes().named_arg.is_match(args) {
+            fields.note = string_literals(args).next().map(str::to_string);
+        }
+        fields
+    }
+}
+
+/// Contents of the quoted strings in `text`, in order
+fn string_literals(text: &str) -> impl Iterator<Item = &str> {
+    regexes().string.captures_iter(text).filter_map(|captures| {
+        captures.get(1).or_else(|| captures.get(2)).map(|m| m.as_str())
+    })
+}
+
+struct Regexes {
+    doc_tag: Regex,
+    docstring: Regex,
+    attribute: Regex,
+    decorator: Regex,
+    version: Regex,
+    deprecated_flag: Regex,
+    deprecation_warning: Regex,
+    named_arg: Regex,
+    string: Regex,
+}
+
+fn regexes() -> &'static Regexes {
+    static REGEXES: OnceLock<Regexes> = OnceLock::new();
+    REGEXES.get_or_init(|| Regexes {
+        doc_tag: Regex::new(r"@deprecated\b:?\s*(.*)").expect("valid doc tag regex"),
+        docstring: Regex::new(r"^\s*\.\. deprecated::\s*(\S+)?\s*(.*)").expect("valid docstring regex"),
+        // #[deprecated], #[deprecated = "..."], #[deprecated(...)] and C# [Obsolete(...)]
+        attribute: Regex::new(r#"^#\[deprecated\b(?:\s*=\s*"([^"]*)"|\s*\((.*)\))?|^\[Obsolete\b(?:\((.*)\))?"#)
+            .expect("valid attribute regex"),
+        // @Deprecated, @deprecated("..."), @typing_extensions.deprecated(...)
+        decorator: Regex::new(r"^@(?:[\w.]+\.)?[Dd]eprecated\b(?:\((.*)\))?").expect("valid decorator regex"),
+        // @Version('2'), @ApiVersion("2"), [ApiVersion("2.0")], [MapToApiVersion("2.0")], @api_version("v2")
+        version: Regex::new(r"^[@\[](?:[\w.]+\.)?(?:Version|ApiVersion|MapToApiVersion|api_version)\s*\((.*)\)")
+            .expect("valid version regex"),
+        deprecated_flag: Regex::new(r"(?i)\bdeprecated\s*[=:]\s*true\b").expect("valid flag regex"),
+        deprecation_warning: Regex::new(r"\b(?:Pending)?DeprecationWarning\b").expect("valid warning regex"),
+        named_arg: Regex::new(r#"(\w+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("valid argument regex"),
+        string: Regex::new(r#"[rbuf]?"([^"]*)"|[rbuf]?'([^']*)'"#).expect("valid string regex"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LineRange;
+    use std::collections::HashMap;
+
+    fn concept(name: &str, start: u32, end: u32) -> SemanticConcept {
+        SemanticConcept {
+            id: name.to_string(),
+            name: name.to_string(),
+            concept_type: "function".to_string(),
+            confidence: 0.9,
+            file_path: "src/lib.rs".to_string(),
+            line_range: LineRange { start, end },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_doc_tags_and_attributes_mark_the_declared_concept() {
+        let source = "\
+/**
+ * Load one user.
+ * @deprecated use loadUsers instead
+ */
+export function loadUser(id) {}
+
+#[deprecated(since = \"1.4.0\", note = \"use parse_all\")]
+pub fn parse() {}
+
+[Obsolete(\"Use Save\")]
+public void Store() {}
+";
+        let mut concepts = vec![concept("loadUser", 5, 5), concept("parse", 8, 8), concept("Store", 11, 11)];
+        let annotations = DeprecationExtractor::annotate("src/lib.rs", source, &mut concepts);
+
+        assert_eq!(annotations.len(), 3);
+        assert_eq!(annotations[0].source, "doc_tag");
+        assert_eq!(annotations[0].concept_name.as_deref(), Some("loadUser"));
+        assert_eq!(concepts[0].metadata.get("deprecation_note").map(String::as_str), Some("use loadUsers instead"));
+
+        assert_eq!(annotations[1].since.as_deref(), Some("1.4.0"));
+        assert_eq!(concepts[1].metadata.get("deprecated_since").map(String::as_str), Some("1.4.0"));
+        assert_eq!(concepts[1].metadata.get("deprecation_note").map(String::as_str), Some("use parse_all"));
+
+        assert_eq!(annotations[2].note.as_deref(), Some("Use Save"));
+        assert_eq!(concepts[2].metadata.get("deprecated").map(String::as_str), Some("true"));
+    }
+
+    #[test]
+    fn test_python_warnings_decorators_and_docstrings() {
+        let source = "\
+def old_api(x):
+    warnings.warn(
+        \"old_api is deprecated, use new_api\",
+        DeprecationWarning,
+        stacklevel=2,
+    )
+    return new_api(x)
+
+@deprecated(\"use Client.send\")
+def post(url):
+    \"\"\"Send a request.
+
+    .. deprecated:: 2.1 Use send instead.
+    \"\"\"
+
+def noisy():
+    warnings.warn(\"slow path\", RuntimeWarning)
+";
+        let concepts = vec![concept("old_api", 1, 7), concept("post", 10, 14), concept("noisy", 16, 17)];
+        let annotations = DeprecationExtractor::extract("api.py", source, &concepts);
+
+        let summary: Vec<(&str, Option<&str>, Option<&str>)> = annotations
+            .iter()
+            .map(|a| (a.source.as_str(), a.concept_name.as_deref(), a.note.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("runtime_warning", Some("old_api"), Some("old_api is deprecated, use new_api")),
+                ("decorator", Some("post"), Some("use Client.send")),
+                ("docstring", Some("post"), Some("Use send instead.")),
+            ]
+        );
+        assert_eq!(annotations[2].since.as_deref(), Some("2.1"));
+    }
+
+    #[test]
+    fn test_api_versions_are_grouped_in_the_report() {
+        let source = "\
+@Version(['1', '2'])
+@Get('orders')
+findAll() {}
+
+[ApiVersion(\"1.0\", Deprecated = true)]
+public class OrdersV1Controller {}
+";
+        let mut concepts = vec![concept("findAll", 3, 3), concept("OrdersV1Controller", 6, 6)];
+        let annotations = DeprecationExtractor::annotate("orders.ts", source, &mut concepts);
+        assert_eq!(concepts[0].metadata.get("api_version").map(String::as_str), Some("1,2"));
+        assert_eq!(concepts[1].metadata.get("deprecated").map(String::as_str), Some("true"));
+
+        let report = DeprecationExtractor::summarize(annotations);
+        assert_eq!(report.total_deprecated, 1);
+        assert_eq!(report.by_source.get("version_attribute"), Some(&1));
+        let versions: Vec<(&str, usize)> =
+            report.api_versions.iter().map(|g| (g.version.as_str(), g.annotations.len())).collect();
+        assert_eq!(versions, vec![("1", 1), ("1.0", 1), ("2", 1)]);
+        assert!(!DeprecationExtractor::has_markers("fn main() {}\n"));
+    }
+}
@@ -0,0 +1,346 @@
+//! Concept-level diffing between two versions of a file
+//!
+//! Concepts are matched by type and name first. Concepts left unmatched on both
+//! sides are paired as renames when their bodies agree once the name itself is
+//! masked out, and matched concepts whose relative order changed are reported
+//! as moved rather than unchanged.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::types::{LineRange, SemanticConcept};
+use regex::Regex;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Minimum body similarity for an unmatched removal/addition pair to count as a rename
+const RENAME_SIMILARITY: f64 = 0.75;
+
+/// One concept in a file diff, with its line range in each version it appears in
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ConceptChange {
+    pub name: String,
+    /// Name in the old version, set for renames
+    pub previous_name: Option<String>,
+    pub concept_type: String,
+    pub old_range: Option<LineRange>,
+    pub new_range: Option<LineRange>,
+}
+
+/// Concepts added, removed, renamed and moved between two versions of a file
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct FileConceptDiff {
+    pub file_path: String,
+    pub added: Vec<ConceptChange>,
+    pub removed: Vec<ConceptChange>,
+    pub renamed: Vec<ConceptChange>,
+    pub moved: Vec<ConceptChange>,
+    /// Concepts present in both versions in the same relative order
+    pub unchanged: Vec<ConceptChange>,
+}
+
+impl FileConceptDiff {
+    pub fn has_changes(&self) -> bool {
+        !(self.added.is_empty() && self.removed.is_empty() && self.renamed.is_empty() && self.moved.is_empty())
+    }
+
+    /// Dominant kind of change, used to label incremental learning events
+    pub fn change_kind(&self) -> &'static str {
+        let (added, removed) = (self.added.len(), self.removed.len());
+        if added > removed {
+            "addition"
+        } else if removed > added {
+            "deletion"
+        } else if !self.renamed.is_empty() && added == 0 {
+            "rename"
+        } else if !self.moved.is_empty() && added == 0 && self.renamed.is_empty() {
+            "move"
+        } else {
+            "modification"
+        }
+    }
+}
+
+/// Computes a [`FileConceptDiff`] from the concepts extracted from each version
+pub struct ConceptDiffer;
+
+impl ConceptDiffer {
+    pub fn diff(
+        file_path: &str,
+        old_concepts: &[SemanticConcept],
+        old_content: &str,
+        new_concepts: &[SemanticConcept],
+        new_content: &str,
+    ) -> FileConceptDiff {
+        let old = Self::in_source_order(old_concepts);
+        let new = Self::in_source_order(new_concepts);
+
+        // Pair concepts with the same type and name, in order of appearance
+        let mut queues: HashMap<(&str, &str), VecDeque<usize>> = HashMap::new();
+        for (idx, concept) in new.iter().enumerate() {
+            queues
+                .entry((concept.concept_type.as_str(), concept.name.as_str()))
+                .or_default()
+                .push_back(idx);
+        }
+        let mut pairs = Vec::new();
+        let mut unmatched_old = Vec::new();
+        for (old_idx, concept) in old.iter().enumerate() {
+            match queues
+                .get_mut(&(concept.concept_type.as_str(), concept.name.as_str()))
+                .and_then(|queue| queue.pop_front())
+            {
+                Some(new_idx) => pairs.push((old_idx, new_idx)),
+                None => unmatched_old.push(old_idx),
+            }
+        }
+        let matched_new: HashSet<usize> = pairs.iter().map(|(_, new_idx)| *new_idx).collect();
+        let unmatched_new: Vec<usize> = (0..new.len()).filter(|idx| !matched_new.contains(idx)).collect();
+
+        let mut diff = FileConceptDiff {
+            file_path: file_path.to_string(),
+            ..Default::default()
+        };
+
+        // Matched concepts outside the longest in-order run changed position
+        let in_order = Self::longest_increasing_run(&pairs);
+        for (pos, (old_idx, new_idx)) in pairs.iter().enumerate() {
+            let change = Self::change(Some(old[*old_idx]), Some(new[*new_idx]));
+            if in_order.contains(&pos) {
+                diff.unchanged.push(change);
+            } else {
+                diff.moved.push(change);
+            }
+        }
+
+        // Pair the remaining removals and additions whose bodies match up to the name
+        let old_lines: Vec<&str> = old_content.lines().collect();
+        let new_lines: Vec<&str> = new_content.lines().collect();
+        let mut candidates = Vec::new();
+        for &old_idx in &unmatched_old {
+            for &new_idx in &unmatched_new {
+                let (before, after) = (old[old_idx], new[new_idx]);
+                if before.concept_type != after.concept_type {
+                    continue;
+                }
+                let similarity = Self::body_similarity(
+                    &Self::masked_body(before, &old_lines),
+                    &Self::masked_body(after, &new_lines),
+                );
+                if similarity >= RENAME_SIMILARITY {
+                    candidates.push((similarity, old_idx, new_idx));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| {
+            b.0.partial_cmp(&a.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| (a.1, a.2).cmp(&(b.1, b.2)))
+        });
+
+        let mut renamed_old = HashSet::new();
+        let mut renamed_new = HashSet::new();
+        for (_, old_idx, new_idx) in candidates {
+            if renamed_old.contains(&old_idx) || renamed_new.contains(&new_idx) {
+                continue;
+            }
+            renamed_old.insert(old_idx);
+            renamed_new.insert(new_idx);
+            diff.renamed.push(Self::change(Some(old[old_idx]), Some(new[new_idx])));
+        }
+
+        diff.removed = unmatched_old
+            .iter()
+            .filter(|idx| !renamed_old.contains(idx))
+            .map(|idx| Self::change(Some(old[*idx]), None))
+            .collect();
+        diff.added = unmatched_new
+            .iter()
+            .filter(|idx| !renamed_new.contains(idx))
+            .map(|idx| Self::change(None, Some(new[*idx])))
+            .collect();
+
+        diff
+    }
+
+    fn in_source_order(concepts: &[SemanticConcept]) -> Vec<&SemanticConcept> {
+        let mut ordered: Vec<&SemanticConcept> = concepts.iter().collect();
+        ordered.sort_by_key(|c| (c.line_range.start, c.line_range.end));
+        ordered
+    }
+
+    fn change(old: Option<&SemanticConcept>, new: Option<&SemanticConcept>) -> ConceptChange {
+        let current = new.or(old).expect("a change has at least one side");
+        ConceptChange {
+            name: current.name.clone(),
+            previous_name: match (old, new) {
+                (Some(before), Some(after)) if before.name != after.name => Some(before.name.clone()),
+                _ => None,
+            },
+            concept_type: current.concept_type.clone(),
+            old_range: old.map(|c| c.line_range.clone()),
+            new_range: new.map(|c| c.line_range.clone()),
+        }
+    }
+
+    /// Positions in `pairs` (sorted by old index) forming the longest run whose
+    /// new indices also increase
+    fn longest_increasing_run(pairs: &[(usize, usize)]) -> HashSet<usize> {
+        let mut lengths = vec![1usize; pairs.len()];
+        let mut previous: Vec<Option<usize>> = vec![None; pairs.len()];
+        for i in 0..pairs.len() {
+            for j in 0..i {
+                if pairs[j].1 < pairs[i].1 && lengths[j] + 1 > lengths[i] {
+                    lengths[i] = lengths[j] + 1;
+                    previous[i] = Some(j);
+                }
+            }
+        }
+
+        let mut run = HashSet::new();
+        let mut current = (0..pairs.len()).max_by_key(|&i| (lengths[i], std::cmp::Reverse(i)));
+        while let Some(i) = current {
+            run.insert(i);
+            current = previous[i];
+        }
+        run
+    }
+
+    /// Trimmed body lines with the concept's own name masked out, skipping
+    /// blank and punctuation-only lines that every body shares
+    fn masked_body(concept: &SemanticConcept, lines: &[&str]) -> Vec<String> {
+        let start = (concept.line_range.start as usize).saturating_sub(1);
+        let end = (concept.line_range.end as usize).max(start + 1).min(lines.len());
+        let name_pattern = Regex::new(&format!(r"\b{}\b", regex::escape(&concept.name))).ok();
+
+        lines
+            .get(start..end)
+            .unwrap_or(&[])
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| line.chars().any(|c| c.is_alphanumeric()))
+            .map(|line| match &name_pattern {
+                Some(pattern) => pattern.replace_all(line, "$$").into_owned(),
+                None => line.replace(&concept.name, "$"),
+            })
+            .collect()
+    }
+
+    /// Multiset overlap of two bodies' lines, from 0.0 to 1.0
+    fn body_similarity(old: &[String], new: &[String]) -> f64 {
+        if old.is_empty() || new.is_empty() {
+            return 0.0;
+        }
+
+        let mut remaining: HashMap<&str, usize> = HashMap::new();
+        for line in old {
+            *remaining.entry(line.as_str()).or_insert(0) += 1;
+        }
+        let mut shared = 0;
+        for line in new {
+            if let Some(count) = remaining.get_mut(line.as_str()).filter(|count| **count > 0) {
+                *count -= 1;
+                shared += 1;
+            }
+        }
+
+        shared as f64 / old.len().max(new.len()) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn concept(name: &str, concept_type: &str, start: u32, end: u32) -> SemanticConcept {
+        SemanticConcept {
+            id: format!("test_{}", name),
+            name: name.to_string(),
+            concept_type: concept_type.to_string(),
+            confidence: 0.8,
+            file_path: "test.ts".to_string(),
+            line_range: LineRange { start, end },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn names(changes: &[ConceptChange]) -> Vec<&str> {
+        changes.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_added_and_removed_concepts() {
+        let old = "function a() {}\nfunction b() {}\n";
+        let new = "function a() {}\nfunction c() { return 1; }\n";
+        let diff = ConceptDiffer::diff(
+            "test.ts",
+            &[concept("a", "function", 1, 1), concept("b", "function", 2, 2)],
+            old,
+            &[concept("a", "function", 1, 1), concept("c", "function", 2, 2)],
+            new,
+        );
+
+        assert_eq!(names(&diff.added), vec!["c"]);
+        assert_eq!(names(&diff.removed), vec!["b"]);
+        assert_eq!(names(&diff.unchanged), vec!["a"]);
+        assert!(diff.renamed.is_empty());
+        assert_eq!(diff.change_kind(), "modification");
+    }
+
+    #[test]
+    fn test_rename_detected_from_masked_body() {
+        let old = "function getUser(id) {\n  const user = load(id);\n  return user;\n}\n";
+        let new = "function fetchUser(id) {\n  const user = load(id);\n  return user;\n}\n";
+        let diff = ConceptDiffer::diff(
+            "test.ts",
+            &[concept("getUser", "function", 1, 4)],
+            old,
+            &[concept("fetchUser", "function", 1, 4)],
+            new,
+        );
+
+        assert_eq!(diff.renamed.len(), 1);
+        assert_eq!(diff.renamed[0].name, "fetchUser");
+        assert_eq!(diff.renamed[0].previous_name.as_deref(), Some("getUser"));
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.change_kind(), "rename");
+    }
+
+    #[test]
+    fn test_reordered_concept_is_moved_with_line_mapping() {
+        let old = "function a() {}\nfunction b() {}\nfunction c() {}\n";
+        let new = "function c() {}\nfunction a() {}\nfunction b() {}\n";
+        let diff = ConceptDiffer::diff(
+            "test.ts",
+            &[concept("a", "function", 1, 1), concept("b", "function", 2, 2), concept("c", "function", 3, 3)],
+            old,
+            &[concept("c", "function", 1, 1), concept("a", "function", 2, 2), concept("b", "function", 3, 3)],
+            new,
+        );
+
+        assert_eq!(names(&diff.moved), vec!["c"]);
+        assert_eq!(names(&diff.unchanged), vec!["a", "b"]);
+        let moved = &diff.moved[0];
+        assert_eq!(moved.old_range.as_ref().map(|r| r.start), Some(3));
+        assert_eq!(moved.new_range.as_ref().map(|r| r.start), Some(1));
+        assert_eq!(diff.change_kind(), "move");
+    }
+
+    #[test]
+    fn test_shifted_lines_are_not_moves() {
+        let old = "function a() {}\nfunction b() {}\n";
+        let new = "// header\n\nfunction a() {}\nfunction b() {}\n";
+        let diff = ConceptDiffer::diff(
+            "test.ts",
+            &[concept("a", "function", 1, 1), concept("b", "function", 2, 2)],
+            old,
+            &[concept("a", "function", 3, 3), concept("b", "function", 4, 4)],
+            new,
+        );
+
+        assert!(!diff.has_changes());
+        assert_eq!(diff.unchanged[1].new_range.as_ref().map(|r| r.start), Some(4));
+    }
+}
@@ -0,0 +1,417 @@
+//! Domain entities shared across languages
+//!
+//! In a full-stack repository one entity is declared several times: a Rust
+//! struct `User`, a TypeScript interface `UserDto`, a SQL table `users`.
+//! Type-like concepts are grouped by their entity name (lower-cased words,
+//! without suffixes such as `Dto` or `Model`, last word singular), and a group
+//! declared in two or more languages becomes a [`SharedDomainEntity`] when the
+//! members' fields agree. Fields are read from the declaration's lines, or
+//! from the column concepts nested in a SQL table.
+//!
+//! Entities are stored as `shared_domain_entity` concepts whose `unifies`
+//! relationship lists the members, so a change to one declaration can be
+//! traced to the others.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::patterns::naming::NamingPatternAnalyzer;
+use crate::types::SemanticConcept;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::OnceLock;
+
+/// Concept type of the concepts recording shared entities
+pub const SHARED_ENTITY_TYPE: &str = "shared_domain_entity";
+
+/// Concept types that can declare an entity
+const ENTITY_TYPES: &[&str] = &["struct", "interface", "class", "table", "type", "type_alias", "record", "model"];
+
+/// Nested concept types that are fields of the entity around them
+const FIELD_TYPES: &[&str] = &["column", "field", "property", "attribute"];
+
+/// Trailing words naming the role of a declaration rather than the entity
+const ROLE_SUFFIXES: &[&str] = &["dto", "model", "entity", "record", "row", "schema", "table", "type", "interface"];
+
+/// Share of fields two declarations must have in common to be one entity
+const MIN_FIELD_SIMILARITY: f64 = 0.3;
+
+/// Confidence of entities matched by name alone
+const NAME_ONLY_CONFIDENCE: f64 = 0.5;
+
+/// One declaration of a shared entity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct EntityMember {
+    pub concept_id: String,
+    pub name: String,
+    pub concept_type: String,
+    pub language: String,
+    pub file_path: String,
+    pub line: u32,
+    /// Field names as lower-cased words joined by `_`, sorted
+    pub fields: Vec<String>,
+}
+
+/// An entity declared in several languages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct SharedDomainEntity {
+    /// Id of the `shared_domain_entity` concept, e.g. `shared_entity_user`
+    pub id: String,
+    /// Entity name, e.g. `user` or `order_item`
+    pub name: String,
+    /// Languages declaring the entity, sorted
+    pub languages: Vec<String>,
+    /// Declarations, by language then file
+    pub members: Vec<EntityMember>,
+    /// Fields declared by at least two members
+    pub shared_fields: Vec<String>,
+    /// Average share of fields common to two members; unset when fewer than
+    /// two members have known fields
+    pub field_similarity: Option<f64>,
+    pub confidence: f64,
+}
+
+pub struct DomainEntityUnifier;
+
+impl DomainEntityUnifier {
+    /// Entities declared in two or more languages among `concepts`
+    ///
+    /// `language_of` maps a file path to its language ("generic" and
+    /// "unknown" are ignored); `source_of` returns a file's content, for
+    /// reading fields, or `None` when it isn't available.
+    pub fn unify(
+        concepts: &[SemanticConcept],
+        language_of: impl Fn(&str) -> String,
+        source_of: impl Fn(&str) -> Option<String>,
+    ) -> Vec<SharedDomainEntity> {
+        let mut groups: BTreeMap<String, Vec<&SemanticConcept>> = BTreeMap::new();
+        for concept in concepts {
+            if !ENTITY_TYPES.contains(&concept.concept_type.as_str()) {
+                continue;
+            }
+            if let Some(key) = Self::entity_key(&concept.name) {
+                groups.entry(key).or_default().push(concept);
+            }
+        }
+
+        let mut sources: HashMap<String, Option<String>> = HashMap::new();
+        let mut entities = Vec::new();
+        for (key, declarations) in groups {
+            let languages: BTreeSet<String> = declarations
+                .iter()
+                .map(|concept| language_of(&concept.file_path))
+                .filter(|language| language != "generic" && language != "unknown")
+                .collect();
+            if languages.len() < 2 {
+                continue;
+            }
+
+            let mut members: Vec<EntityMember> = declarations
+                .iter()
+                .filter_map(|concept| {
+                    let language = language_of(&concept.file_path);
+                    if !languages.contains(&language) {
+                        return None;
+                    }
+                    let source = sources
+                        .entry(concept.file_path.clone())
+                        .or_insert_with(|| source_of(&concept.file_path));
+                    Some(EntityMember {
+                        concept_id: concept.id.clone(),
+                        name: concept.name.clone(),
+                        concept_type: concept.concept_type.clone(),
+                        fields: Self::fields(concept, concepts, &language, source.as_deref()),
+                        language,
+                        file_path: concept.file_path.clone(),
+                        line: concept.line_range.start,
+                    })
+                })
+                .collect();
+            members.sort_by(|a, b| {
+                a.language
+                    .cmp(&b.language)
+                    .then_with(|| a.file_path.cmp(&b.file_path))
+                    .then_with(|| a.line.cmp(&b.line))
+            });
+
+            let field_similarity = Self::field_similarity(&members);
+            if field_similarity.is_some_and(|similarity| similarity < MIN_FIELD_SIMILARITY) {
+                continue;
+            }
+
+            let mut field_counts: BTreeMap<&str, usize> = BTreeMap::new();
+            for member in &members {
+                for field in &member.fields {
+                    *field_counts.entry(field.as_str()).or_insert(0) += 1;
+                }
+            }
+            let shared_fields = field_counts
+                .into_iter()
+                .filter(|(_, count)| *count >= 2)
+                .map(|(field, _)| field.to_string())
+                .collect();
+
+            entities.push(SharedDomainEntity {
+                id: format!("shared_entity_{}", key),
+                name: key,
+                languages: languages.into_iter().collect(),
+                members,
+                shared_fields,
+                confidence: field_similarity.map_or(NAME_ONLY_CONFIDENCE, |similarity| 0.5 + 0.5 * similarity),
+                field_similarity,
+            });
+        }
+        entities
+    }
+
+    /// The `shared_domain_entity` concept recording an entity, placed at its
+    /// first member
+    pub fn to_concept(entity: &SharedDomainEntity) -> SemanticConcept {
+        let first = entity.members.first();
+        let ids: Vec<&str> = entity.members.iter().map(|member| member.concept_id.as_str()).collect();
+        let mut metadata = HashMap::from([("languages".to_string(), entity.languages.join(", "))]);
+        if !entity.shared_fields.is_empty() {
+            metadata.insert("shared_fields".to_string(), entity.shared_fields.join(", "));
+        }
+
+        SemanticConcept {
+            id: entity.id.clone(),
+            name: entity.name.clone(),
+            concept_type: SHARED_ENTITY_TYPE.to_string(),
+            confidence: entity.confidence,
+            file_path: first.map(|member| member.file_path.clone()).unwrap_or_default(),
+            line_range: crate::types::LineRange {
+                start: first.map_or(0, |member| member.line),
+                end: first.map_or(0, |member| member.line),
+            },
+            relationships: HashMap::from([("unifies".to_string(), ids.join(", "))]),
+            metadata,
+        }
+    }
+
+    /// Entity name of a declaration: `UserDto` and `users` are both `user`
+    fn entity_key(name: &str) -> Option<String> {
+        let mut words: Vec<String> = NamingPatternAnalyzer::split_words(name)
+            .into_iter()
+            .map(|word| word.to_lowercase())
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .collect();
+        while words.len() > 1 && words.last().is_some_and(|word| ROLE_SUFFIXES.contains(&word.as_str())) {
+            words.pop();
+        }
+        let last = words.pop()?;
+        words.push(Self::singular(&last));
+        Some(words.join("_"))
+    }
+
+    fn singular(word: &str) -> String {
+        if let Some(stem) = word.strip_suffix("ies").filter(|stem| stem.len() > 1) {
+            format!("{}y", stem)
+        } else if word.len() > 3 && word.ends_with('s') && !word.ends_with("ss") && !word.ends_with("us") {
+            word[..word.len() - 1].to_string()
+        } else {
+            word.to_string()
+        }
+    }
+
+    /// Field names of a declaration: nested field concepts, else the lines of
+    /// its body that declare a field
+    fn fields(concept: &SemanticConcept, concepts: &[SemanticConcept], language: &str, source: Option<&str>) -> Vec<String> {
+        let mut fields: BTreeSet<String> = concepts
+            .iter()
+            .filter(|field| {
+                FIELD_TYPES.contains(&field.concept_type.as_str())
+                    && field.file_path == concept.file_path
+                    && field.id != concept.id
+                    && concept.line_range.start <= field.line_range.start
+                    && field.line_range.end <= concept.line_range.end
+            })
+            .filter_map(|field| Self::field_key(&field.name))
+            .collect();
+
+        if fields.is_empty() {
+            if let Some(source) = source {
+                let regex = field_regex(language);
+                let first = concept.line_range.start as usize;
+                let last = concept.line_range.end as usize;
+                fields.extend(
+                    source
+                        .lines()
+                        .enumerate()
+                        .filter(|(index, _)| *index + 1 > first && *index < last)
+                        .filter_map(|(_, line)| regex.captures(line))
+                        .filter_map(|captures| captures.get(1).and_then(|name| Self::field_key(name.as_str()))),
+                );
+            }
+        }
+        fields.into_iter().collect()
+    }
+
+    fn field_key(name: &str) -> Option<String> {
+        let words: Vec<String> = NamingPatternAnalyzer::split_words(name)
+            .into_iter()
+            .map(|word| word.to_lowercase())
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .collect();
+        (!words.is_empty()).then(|| words.join("_"))
+    }
+
+    /// Mean Jaccard similarity of the field sets of members that have fields
+    fn field_similarity(members: &[EntityMember]) -> Option<f64> {
+        let sets: Vec<BTreeSet<&String>> = members
+            .iter()
+            .filter(|member| !member.fields.is_empty())
+            .map(|member| member.fields.iter().collect())
+            .collect();
+        if sets.len() < 2 {
+            return None;
+        }
+
+        let mut total = 0.0;
+        let mut pairs = 0;
+        for (i, a) in sets.iter().enumerate() {
+            for b in &sets[i + 1..] {
+                total += a.intersection(b).count() as f64 / a.union(b).count() as f64;
+                pairs += 1;
+            }
+        }
+        Some(total / pairs as f64)
+    }
+}
+
+/// Field declarations of a language; the first capture is the field name
+fn field_regex(language: &str) -> &'static Regex {
+    static COLON: OnceLock<Regex> = OnceLock::new();
+    static GO: OnceLock<Regex> = OnceLock::new();
+    static TYPED: OnceLock<Regex> = OnceLock::new();
+    match language {
+        // `ID int64 `json:"id"``
+        "go" => GO.get_or_init(|| {
+            Regex::new(r"^\s*([A-Za-z_]\w*)\s+[\[\]\*\w.]+\s*(?:`.*`)?\s*(?://.*)?$").expect("valid Go field regex")
+        }),
+        // `private String name;`, `public int Id { get; set; }`
+        "java" | "csharp" | "c" | "cpp" => TYPED.get_or_init(|| {
+            Regex::new(r"^\s*(?:(?:public|private|protected|internal|static|final|readonly|const)\s+)*[\w<>\[\],.?]+\s+([A-Za-z_]\w*)\s*(?:;|=|\{)")
+                .expect("valid typed field regex")
+        }),
+        // `pub name: String`, `name?: string;`, `name: str = ""`
+        _ => COLON.get_or_init(|| {
+            Regex::new(r"^\s*(?:(?:pub(?:\([^)]*\))?|public|private|protected|readonly|export)\s+)*([A-Za-z_]\w*)\??\s*:(?:[^:]|$)")
+                .expect("valid field regex")
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LineRange;
+
+    fn concept(name: &str, concept_type: &str, file_path: &str, start: u32, end: u32) -> SemanticConcept {
+        SemanticConcept {
+            id: format!("{}:{}", file_path, name),
+            name: name.to_string(),
+            concept_type: concept_type.to_string(),
+            confidence: 0.9,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start, end },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn language_of(path: &str) -> String {
+        match path.rsplit('.').next() {
+            Some("rs") => "rust",
+            Some("ts") => "typescript",
+            Some("go") => "go",
+            Some("sql") => "sql",
+            _ => "generic",
+        }
+        .to_string()
+    }
+
+    const RUST: &str = "pub struct User {\n    pub id: i64,\n    pub email: String,\n    pub display_name: String,\n}\n";
+    const TS: &str = "export interface UserDto {\n  id: number;\n  email: string;\n  displayName?: string;\n}\n";
+
+    fn source_of(path: &str) -> Option<String> {
+        match path {
+            "src/user.rs" => Some(RUST.to_string()),
+            "web/user.ts" => Some(TS.to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_struct_interface_and_table_are_one_entity() {
+        let concepts = vec![
+            concept("User", "struct", "src/user.rs", 1, 5),
+            concept("UserDto", "interface", "web/user.ts", 1, 5),
+            concept("users", "table", "db/schema.sql", 1, 5),
+            concept("id", "column", "db/schema.sql", 2, 2),
+            concept("email", "column", "db/schema.sql", 3, 3),
+            concept("created_at", "column", "db/schema.sql", 4, 4),
+            // Declared in one language only
+            concept("Session", "struct", "src/session.rs", 10, 20),
+        ];
+
+        let entities = DomainEntityUnifier::unify(&concepts, language_of, source_of);
+        assert_eq!(entities.len(), 1);
+        let entity = &entities[0];
+        assert_eq!(entity.id, "shared_entity_user");
+        assert_eq!(entity.languages, vec!["rust", "sql", "typescript"]);
+        let names: Vec<&str> = entity.members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "users", "UserDto"]);
+        assert_eq!(entity.members[0].fields, vec!["display_name", "email", "id"]);
+        assert_eq!(entity.members[1].fields, vec!["created_at", "email", "id"]);
+        assert_eq!(entity.shared_fields, vec!["display_name", "email", "id"]);
+        assert!(entity.field_similarity.unwrap() >= 0.5);
+        assert!(entity.confidence > NAME_ONLY_CONFIDENCE);
+    }
+
+    #[test]
+    fn test_same_name_with_different_fields_is_not_unified() {
+        let source = |path: &str| match path {
+            "src/config.rs" => Some("pub struct Config {\n    pub port: u16,\n    pub workers: usize,\n}\n".to_string()),
+            "web/config.ts" => Some("interface Config {\n  theme: string;\n  locale: string;\n}\n".to_string()),
+            _ => None,
+        };
+        let concepts = vec![
+            concept("Config", "struct", "src/config.rs", 1, 4),
+            concept("Config", "interface", "web/config.ts", 1, 4),
+        ];
+        assert!(DomainEntityUnifier::unify(&concepts, language_of, source).is_empty());
+    }
+
+    #[test]
+    fn test_entity_concept_lists_its_members() {
+        let concepts = vec![
+            concept("OrderItem", "struct", "src/order.rs", 1, 5),
+            concept("order_items", "table", "db/orders.sql", 1, 5),
+        ];
+        let entities = DomainEntityUnifier::unify(&concepts, language_of, |_| None);
+        assert_eq!(entities[0].name, "order_item");
+        assert_eq!(entities[0].field_similarity, None);
+        assert_eq!(entities[0].confidence, NAME_ONLY_CONFIDENCE);
+
+        let concept = DomainEntityUnifier::to_concept(&entities[0]);
+        assert_eq!(concept.concept_type, SHARED_ENTITY_TYPE);
+        assert_eq!(concept.relationships["unifies"], "src/order.rs:OrderItem, db/orders.sql:order_items");
+        assert_eq!(concept.metadata["languages"], "rust, sql");
+    }
+
+    #[test]
+    fn test_entity_keys() {
+        assert_eq!(DomainEntityUnifier::entity_key("UserDto").as_deref(), Some("user"));
+        assert_eq!(DomainEntityUnifier::entity_key("categories").as_deref(), Some("category"));
+        assert_eq!(DomainEntityUnifier::entity_key("Address").as_deref(), Some("address"));
+        assert_eq!(DomainEntityUnifier::entity_key("Status").as_deref(), Some("status"));
+        assert_eq!(DomainEntityUnifier::entity_key("Model").as_deref(), Some("model"));
+    }
+}
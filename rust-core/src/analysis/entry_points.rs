@@ -0,0 +1,347 @@
+//! Entry point detection from manifests, main functions and Dockerfiles
+//!
+//! Complements the framework file-name checks in the blueprint with places
+//! execution is declared to start: Cargo and npm binaries, `__main__.py`
+//! modules, `main()` functions and container CMD/ENTRYPOINT instructions.
+
+use crate::analysis::EntryPoint;
+use regex::Regex;
+use serde_json::Value as JsonValue;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use toml::Value as TomlValue;
+use walkdir::WalkDir;
+
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", ".venv", "target", "vendor"];
+
+/// Extensions of languages whose main functions are recognized
+const MAIN_EXTENSIONS: &[&str] = &["go", "rs", "java", "c", "cc", "cpp", "cs", "py"];
+
+/// Upper bound on source files read while looking for main functions
+const MAX_SCANNED_FILES: usize = 2000;
+
+/// Source files larger than this are not scanned for main functions
+const MAX_SCANNED_FILE_SIZE: u64 = 512 * 1024;
+
+/// Language-specific patterns for a program's main function
+struct MainPatterns {
+    go_package: Regex,
+    go_main: Regex,
+    rust_main: Regex,
+    java_main: Regex,
+    c_main: Regex,
+    csharp_main: Regex,
+    python_guard: Regex,
+}
+
+impl MainPatterns {
+    fn new() -> Self {
+        let compile = |pattern: &str| Regex::new(pattern).expect("valid main function regex");
+        MainPatterns {
+            go_package: compile(r"(?m)^package\s+main\b"),
+            go_main: compile(r"(?m)^func\s+main\s*\(\s*\)"),
+            rust_main: compile(r"(?m)^\s*(?:pub\s+)?(?:async\s+)?fn\s+main\s*\("),
+            java_main: compile(r"public\s+static\s+void\s+main\s*\("),
+            c_main: compile(r"(?m)^\s*int\s+main\s*\("),
+            csharp_main: compile(r"static\s+(?:async\s+)?(?:void|int|Task(?:<int>)?)\s+Main\s*\("),
+            python_guard: compile(r#"if\s+__name__\s*==\s*['"]__main__['"]"#),
+        }
+    }
+}
+
+pub struct EntryPointDetector;
+
+impl EntryPointDetector {
+    /// Detect entry points under `root`. Manifest declarations come first and
+    /// win over main-function matches for the same file.
+    pub fn detect(root: &Path) -> Vec<EntryPoint> {
+        let mut entry_points = Vec::new();
+        let mut source_files = Vec::new();
+
+        for entry in WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = entry.path();
+            let file_name = entry.file_name().to_str().unwrap_or("");
+
+            match file_name {
+                "Cargo.toml" => Self::from_cargo_manifest(root, path, &mut entry_points),
+                "package.json" => Self::from_package_json(root, path, &mut entry_points),
+                "__main__.py" => {
+                    entry_points.push(Self::entry("cli", Self::relative(root, path), Some("python"), 0.9, "__main__.py"))
+                }
+                name if name == "Dockerfile" || name.starts_with("Dockerfile.") || name.ends_with(".Dockerfile") => {
+                    if let Some(entry_point) = Self::from_dockerfile(path, Self::relative(root, path)) {
+                        entry_points.push(entry_point);
+                    }
+                }
+                _ => {
+                    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+                    if MAIN_EXTENSIONS.contains(&extension) {
+                        source_files.push(path.to_path_buf());
+                    }
+                }
+            }
+        }
+
+        let patterns = MainPatterns::new();
+        for path in source_files.iter().take(MAX_SCANNED_FILES) {
+            if path.metadata().map(|m| m.len() > MAX_SCANNED_FILE_SIZE).unwrap_or(true) {
+                continue;
+            }
+            if let Some(entry_point) = Self::from_main_function(root, path, &patterns) {
+                entry_points.push(entry_point);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        entry_points.retain(|e| seen.insert((e.entry_type.clone(), e.file_path.clone())));
+        entry_points
+    }
+
+    fn from_cargo_manifest(root: &Path, manifest: &Path, entry_points: &mut Vec<EntryPoint>) {
+        let Some(dir) = manifest.parent() else { return };
+        let Ok(content) = fs::read_to_string(manifest) else { return };
+        let Ok(toml) = toml::from_str::<toml::Table>(&content) else { return };
+        // Virtual workspace manifests have no targets of their own
+        let Some(package) = toml.get("package") else { return };
+        let package_name = package.get("name").and_then(TomlValue::as_str).unwrap_or("");
+
+        let mut has_bin = false;
+        for bin in toml.get("bin").and_then(TomlValue::as_array).into_iter().flatten() {
+            let Some(name) = bin.get("name").and_then(TomlValue::as_str) else { continue };
+            let path = match bin.get("path").and_then(TomlValue::as_str) {
+                Some(path) => dir.join(path),
+                None if name == package_name => dir.join("src/main.rs"),
+                None => dir.join(format!("src/bin/{}.rs", name)),
+            };
+            has_bin = true;
+            entry_points.push(Self::entry(
+                "cli",
+                Self::relative(root, &path),
+                Some("rust"),
+                0.95,
+                &format!("Cargo.toml [[bin]] {}", name),
+            ));
+        }
+
+        // Cargo's implicit targets
+        let main_rs = dir.join("src/main.rs");
+        if !has_bin && main_rs.is_file() {
+            entry_points.push(Self::entry("cli", Self::relative(root, &main_rs), Some("rust"), 0.95, "Cargo.toml src/main.rs"));
+        }
+        if let Ok(bins) = fs::read_dir(dir.join("src/bin")) {
+            let mut bins: Vec<_> = bins
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "rs"))
+                .collect();
+            bins.sort();
+            for bin in bins {
+                entry_points.push(Self::entry("cli", Self::relative(root, &bin), Some("rust"), 0.9, "Cargo.toml src/bin"));
+            }
+        }
+
+        let lib = toml
+            .get("lib")
+            .and_then(|lib| lib.get("path"))
+            .and_then(TomlValue::as_str)
+            .map(|path| dir.join(path))
+            .unwrap_or_else(|| dir.join("src/lib.rs"));
+        if toml.get("lib").is_some() || lib.is_file() {
+            entry_points.push(Self::entry("library", Self::relative(root, &lib), Some("rust"), 0.95, "Cargo.toml [lib]"));
+        }
+    }
+
+    fn from_package_json(root: &Path, manifest: &Path, entry_points: &mut Vec<EntryPoint>) {
+        let Some(dir) = manifest.parent() else { return };
+        let Ok(content) = fs::read_to_string(manifest) else { return };
+        let Ok(json) = serde_json::from_str::<JsonValue>(&content) else { return };
+
+        match json.get("bin") {
+            Some(JsonValue::String(bin)) => {
+                let name = json.get("name").and_then(JsonValue::as_str).unwrap_or("bin");
+                entry_points.push(Self::entry(
+                    "cli",
+                    Self::relative(root, &dir.join(bin)),
+                    Some("node"),
+                    0.95,
+                    &format!("package.json bin {}", name),
+                ));
+            }
+            Some(JsonValue::Object(bins)) => {
+                for (name, bin) in bins {
+                    if let Some(bin) = bin.as_str() {
+                        entry_points.push(Self::entry(
+                            "cli",
+                            Self::relative(root, &dir.join(bin)),
+                            Some("node"),
+                            0.95,
+                            &format!("package.json bin {}", name),
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(main) = json.get("main").and_then(JsonValue::as_str) {
+            entry_points.push(Self::entry("library", Self::relative(root, &dir.join(main)), Some("node"), 0.9, "package.json main"));
+        }
+    }
+
+    /// The container's start command; ENTRYPOINT wins over CMD when both exist
+    fn from_dockerfile(path: &Path, relative: String) -> Option<EntryPoint> {
+        let content = fs::read_to_string(path).ok()?;
+        let mut entrypoint = None;
+        let mut cmd = None;
+
+        for line in content.lines().map(str::trim) {
+            let Some((instruction, argument)) = line.split_once(char::is_whitespace) else { continue };
+            let command = Self::docker_command(argument.trim());
+            match instruction.to_uppercase().as_str() {
+                "ENTRYPOINT" => entrypoint = Some(command),
+                "CMD" => cmd = Some(command),
+                // Each build stage starts over; only the final stage runs
+                "FROM" => {
+                    entrypoint = None;
+                    cmd = None;
+                }
+                _ => {}
+            }
+        }
+
+        let source = match (entrypoint, cmd) {
+            (Some(entrypoint), Some(cmd)) => format!("Dockerfile ENTRYPOINT {} {}", entrypoint, cmd),
+            (Some(entrypoint), None) => format!("Dockerfile ENTRYPOINT {}", entrypoint),
+            (None, Some(cmd)) => format!("Dockerfile CMD {}", cmd),
+            (None, None) => return None,
+        };
+        Some(Self::entry("container", relative, Some("docker"), 0.9, &source))
+    }
+
+    /// `["node", "server.js"]` exec form or plain shell form as one command line
+    fn docker_command(argument: &str) -> String {
+        serde_json::from_str::<Vec<String>>(argument)
+            .map(|parts| parts.join(" "))
+            .unwrap_or_else(|_| argument.to_string())
+    }
+
+    fn from_main_function(root: &Path, path: &Path, patterns: &MainPatterns) -> Option<EntryPoint> {
+        let extension = path.extension()?.to_str()?;
+        let content = fs::read_to_string(path).ok()?;
+
+        let (entry_type, language, confidence, source) = match extension {
+            "go" if patterns.go_package.is_match(&content) && patterns.go_main.is_match(&content) => {
+                ("cli", "go", 0.9, "func main()")
+            }
+            "rs" if patterns.rust_main.is_match(&content) => ("cli", "rust", 0.85, "fn main()"),
+            "java" if patterns.java_main.is_match(&content) => ("cli", "java", 0.85, "static void main()"),
+            "c" | "cc" | "cpp" if patterns.c_main.is_match(&content) => ("cli", "c", 0.85, "int main()"),
+            "cs" if patterns.csharp_main.is_match(&content) => ("cli", "csharp", 0.85, "static Main()"),
+            "py" if patterns.python_guard.is_match(&content) => ("script", "python", 0.7, "if __name__ == \"__main__\""),
+            _ => return None,
+        };
+        Some(Self::entry(entry_type, Self::relative(root, path), Some(language), confidence, source))
+    }
+
+    fn entry(entry_type: &str, file_path: String, framework: Option<&str>, confidence: f64, source: &str) -> EntryPoint {
+        EntryPoint {
+            entry_type: entry_type.to_string(),
+            file_path,
+            framework: framework.map(str::to_string),
+            confidence,
+            source: Some(source.to_string()),
+        }
+    }
+
+    /// Root-relative path with forward slashes and no `./` segments
+    fn relative(root: &Path, path: &Path) -> String {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        relative
+            .components()
+            .filter(|c| !matches!(c, std::path::Component::CurDir))
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &TempDir, path: &str, content: &str) {
+        let full_path = dir.path().join(path);
+        fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+        fs::write(full_path, content).unwrap();
+    }
+
+    fn find<'a>(entry_points: &'a [EntryPoint], file_path: &str) -> &'a EntryPoint {
+        entry_points
+            .iter()
+            .find(|e| e.file_path == file_path)
+            .unwrap_or_else(|| panic!("no entry point for {}", file_path))
+    }
+
+    #[test]
+    fn test_manifest_entry_points() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "Cargo.toml", "[package]\nname = \"tool\"\n\n[[bin]]\nname = \"tool-admin\"\npath = \"./src/admin.rs\"\n");
+        write(&dir, "src/lib.rs", "pub fn run() {}\n");
+        write(&dir, "src/admin.rs", "fn main() {}\n");
+        write(&dir, "web/package.json", r#"{"name": "web", "main": "dist/index.js", "bin": {"web-cli": "./bin/cli.js"}}"#);
+
+        let entry_points = EntryPointDetector::detect(dir.path());
+
+        let admin = find(&entry_points, "src/admin.rs");
+        assert_eq!(admin.entry_type, "cli");
+        assert_eq!(admin.source.as_deref(), Some("Cargo.toml [[bin]] tool-admin"));
+        assert_eq!(find(&entry_points, "src/lib.rs").entry_type, "library");
+        assert_eq!(find(&entry_points, "web/bin/cli.js").source.as_deref(), Some("package.json bin web-cli"));
+        assert_eq!(find(&entry_points, "web/dist/index.js").entry_type, "library");
+        // The bin declared in Cargo.toml is not reported again for its main()
+        assert_eq!(entry_points.iter().filter(|e| e.file_path == "src/admin.rs").count(), 1);
+    }
+
+    #[test]
+    fn test_main_functions_and_python_modules() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "cmd/server/main.go", "package main\n\nfunc main() {\n}\n");
+        write(&dir, "pkg/util.go", "package util\n\nfunc main() {}\n");
+        write(&dir, "app/__main__.py", "from app import cli\ncli()\n");
+        write(&dir, "scripts/seed.py", "def seed():\n    pass\n\nif __name__ == '__main__':\n    seed()\n");
+        write(&dir, "src/Program.cs", "class Program { static async Task Main(string[] args) {} }\n");
+
+        let entry_points = EntryPointDetector::detect(dir.path());
+
+        assert_eq!(find(&entry_points, "cmd/server/main.go").framework.as_deref(), Some("go"));
+        assert!(entry_points.iter().all(|e| e.file_path != "pkg/util.go"));
+        assert_eq!(find(&entry_points, "app/__main__.py").entry_type, "cli");
+        assert_eq!(find(&entry_points, "scripts/seed.py").entry_type, "script");
+        assert_eq!(find(&entry_points, "src/Program.cs").framework.as_deref(), Some("csharp"));
+    }
+
+    #[test]
+    fn test_dockerfile_uses_final_stage_command() {
+        let dir = TempDir::new().unwrap();
+        write(
+            &dir,
+            "Dockerfile",
+            "FROM node:20 AS build\nCMD [\"npm\", \"run\", \"build\"]\n\nFROM node:20-slim\nENTRYPOINT [\"node\"]\nCMD [\"dist/server.js\"]\n",
+        );
+
+        let entry_points = EntryPointDetector::detect(dir.path());
+        let container = find(&entry_points, "Dockerfile");
+        assert_eq!(container.entry_type, "container");
+        assert_eq!(container.source.as_deref(), Some("Dockerfile ENTRYPOINT node dist/server.js"));
+    }
+}
@@ -0,0 +1,447 @@
+//! Environment variable usage across code and configuration
+//!
+//! Finds the places that read environment variables (`process.env.X`,
+//! `os.environ`, `os.getenv`, `std::env::var`, `os.Getenv`, `System.getenv`)
+//! and the places that declare them (`.env` files, Dockerfile `ENV`, CI and
+//! compose configuration), then merges both into one entry per variable.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::extractors::ConfigFileExtractor;
+use crate::types::ParseError;
+use regex::{Captures, Regex};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use walkdir::WalkDir;
+
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", ".venv", "target", "vendor"];
+
+/// Files larger than this are not scanned
+const MAX_ENV_FILE_SIZE: u64 = 512 * 1024;
+
+const SOURCE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "py", "rs", "go", "java", "cs"];
+
+/// One place that reads or declares a variable
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct EnvVarReference {
+    pub file_path: String,
+    pub line: u32,
+    /// 'read' for code, 'declared' for .env files, Dockerfiles and CI/compose configuration
+    pub kind: String,
+    /// What reads or declares it, e.g. 'process.env', 'os.getenv', 'std::env::var', 'dotenv'
+    pub source: String,
+    /// Whether the read site falls back to a value, or the declaration assigns one
+    pub has_default: bool,
+    pub default_value: Option<String>,
+}
+
+/// Everything known about one variable
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct EnvVarUsage {
+    pub name: String,
+    pub read_count: u32,
+    /// Declared in a .env file, Dockerfile or CI/compose configuration
+    pub declared: bool,
+    /// Some read site or declaration provides a value
+    pub has_default: bool,
+    /// Some read site has no fallback of its own
+    pub required: bool,
+    pub references: Vec<EnvVarReference>,
+}
+
+/// Environment section of the project blueprint
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct EnvVarSummary {
+    pub total_variables: u32,
+    /// Sorted by name
+    pub variables: Vec<EnvVarUsage>,
+    /// Read without a fallback and never declared anywhere in the project
+    pub undeclared_required: Vec<String>,
+}
+
+/// A read found in one line of code
+struct Read {
+    name: String,
+    source: &'static str,
+    has_default: bool,
+    default_value: Option<String>,
+}
+
+impl Read {
+    fn new(name: &str, source: &'static str, default_value: Option<String>) -> Self {
+        Read {
+            name: name.to_string(),
+            source,
+            has_default: default_value.is_some(),
+            default_value,
+        }
+    }
+}
+
+pub struct EnvVarScanner;
+
+impl EnvVarScanner {
+    /// Scan every source, .env, Dockerfile and CI/compose file under `root`
+    pub fn scan(root: &Path) -> Result<EnvVarSummary, ParseError> {
+        let mut found: Vec<(String, EnvVarReference)> = Vec::new();
+
+        for entry in WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if !entry.file_type().is_file() || entry.metadata().map(|m| m.len() > MAX_ENV_FILE_SIZE).unwrap_or(true) {
+                continue;
+            }
+            let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+            let file_name = entry.file_name().to_str().unwrap_or("");
+            let is_source = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext));
+            if !is_source && !Self::is_dotenv(file_name) && !Self::is_dockerfile(file_name) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(path) else { continue };
+            found.extend(Self::extract(&relative, &content));
+        }
+
+        for concept in ConfigFileExtractor::new().extract_directory(root, MAX_ENV_FILE_SIZE) {
+            if concept.concept_type != "env_var" {
+                continue;
+            }
+            let file_path = Path::new(&concept.file_path);
+            let relative = file_path.strip_prefix(root).unwrap_or(file_path).to_string_lossy().replace('\\', "/");
+            let source = concept.metadata.get("config_kind").cloned().unwrap_or_else(|| "config".to_string());
+            found.push((
+                concept.name,
+                EnvVarReference {
+                    file_path: relative,
+                    line: concept.line_range.start,
+                    kind: "declared".to_string(),
+                    source,
+                    has_default: false,
+                    default_value: None,
+                },
+            ));
+        }
+
+        Ok(Self::summarize(found))
+    }
+
+    /// Reads in a source file, or declarations in a .env file or Dockerfile
+    pub fn extract(file_path: &str, content: &str) -> Vec<(String, EnvVarReference)> {
+        let file_name = file_path.rsplit('/').next().unwrap_or(file_path);
+        if Self::is_dotenv(file_name) {
+            return Self::declarations(file_path, content, &patterns().dotenv, "dotenv");
+        }
+        if Self::is_dockerfile(file_name) {
+            return Self::declarations(file_path, content, &patterns().dockerfile_env, "dockerfile");
+        }
+
+        let extension = Path::new(file_path).extension().and_then(|e| e.to_str()).unwrap_or("");
+        let readers: fn(&str, &mut Vec<Read>) = match extension {
+            "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" => Self::javascript_reads,
+            "py" => Self::python_reads,
+            "rs" => Self::rust_reads,
+            "go" | "java" | "cs" => Self::plain_reads,
+            _ => return Vec::new(),
+        };
+
+        let mut references = Vec::new();
+        for (idx, line) in content.lines().enumerate() {
+            let mut reads = Vec::new();
+            readers(line, &mut reads);
+            for read in reads {
+                references.push((
+                    read.name,
+                    EnvVarReference {
+                        file_path: file_path.to_string(),
+                        line: idx as u32 + 1,
+                        kind: "read".to_string(),
+                        source: read.source.to_string(),
+                        has_default: read.has_default,
+                        default_value: read.default_value,
+                    },
+                ));
+            }
+        }
+        references
+    }
+
+    /// Merge references into one entry per variable
+    pub fn summarize(found: Vec<(String, EnvVarReference)>) -> EnvVarSummary {
+        let mut by_name: BTreeMap<String, Vec<EnvVarReference>> = BTreeMap::new();
+        for (name, reference) in found {
+            by_name.entry(name).or_default().push(reference);
+        }
+
+        let variables: Vec<EnvVarUsage> = by_name
+            .into_iter()
+            .map(|(name, references)| EnvVarUsage {
+                read_count: references.iter().filter(|r| r.kind == "read").count() as u32,
+                required: references.iter().any(|r| r.kind == "read" && !r.has_default),
+                declared: references.iter().any(|r| r.kind == "declared"),
+                has_default: references.iter().any(|r| r.has_default),
+                name,
+                references,
+            })
+            .collect();
+
+        let undeclared_required = variables
+            .iter()
+            .filter(|v| v.required && !v.declared)
+            .map(|v| v.name.clone())
+            .collect();
+
+        EnvVarSummary {
+            total_variables: variables.len() as u32,
+            variables,
+            undeclared_required,
+        }
+    }
+
+    fn is_dotenv(file_name: &str) -> bool {
+        file_name == ".env" || file_name.starts_with(".env.") || file_name.ends_with(".env")
+    }
+
+    fn is_dockerfile(file_name: &str) -> bool {
+        file_name == "Dockerfile" || file_name.starts_with("Dockerfile.") || file_name.ends_with(".Dockerfile")
+    }
+
+    fn declarations(file_path: &str, content: &str, pattern: &Regex, source: &str) -> Vec<(String, EnvVarReference)> {
+        content
+            .lines()
+            .enumerate()
+            .filter_map(|(idx, line)| {
+                let c = pattern.captures(line.trim())?;
+                let value = c.get(2).map(|v| unquote(v.as_str().trim())).filter(|v| !v.is_empty());
+                Some((
+                    c[1].to_string(),
+                    EnvVarReference {
+                        file_path: file_path.to_string(),
+                        line: idx as u32 + 1,
+                        kind: "declared".to_string(),
+                        source: source.to_string(),
+                        has_default: value.is_some(),
+                        default_value: value,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    fn javascript_reads(line: &str, reads: &mut Vec<Read>) {
+        let p = patterns();
+        for c in p.js_env.captures_iter(line) {
+            let name = c.get(2).or_else(|| c.get(3)).map_or("", |m| m.as_str());
+            let source = if &c[1] == "process" { "process.env" } else { "import.meta.env" };
+            reads.push(Read::new(name, source, fallback(&p.js_fallback, line, &c)));
+        }
+        if let Some(c) = p.js_destructure.captures(line) {
+            for binding in c[1].split(',') {
+                // `NAME`, `NAME = fallback` or `NAME: alias = fallback`
+                let (target, default_value) = match binding.split_once('=') {
+                    Some((target, value)) => (target, Some(unquote(value.trim()))),
+                    None => (binding, None),
+                };
+                let name = target.split(':').next().unwrap_or("").trim();
+                if !name.is_empty() && !name.starts_with("...") {
+                    reads.push(Read::new(name, "process.env", default_value));
+                }
+            }
+        }
+    }
+
+    fn python_reads(line: &str, reads: &mut Vec<Read>) {
+        let p = patterns();
+        for c in p.py_environ_index.captures_iter(line) {
+            reads.push(Read::new(&c[1], "os.environ", None));
+        }
+        for c in p.py_getenv.captures_iter(line) {
+            let source = if c[1].starts_with("environ") { "os.environ.get" } else { "os.getenv" };
+            let default_value = c.get(3).map(|d| unquote(d.as_str().trim())).filter(|d| d != "None");
+            reads.push(Read::new(&c[2], source, default_value));
+        }
+    }
+
+    fn rust_reads(line: &str, reads: &mut Vec<Read>) {
+        let p = patterns();
+        for c in p.rust_env_var.captures_iter(line) {
+            let rest = &line[c.get(0).map_or(line.len(), |m| m.end())..];
+            let mut read = Read::new(&c[1], "std::env::var", None);
+            // `unwrap_or_else` and `unwrap_or_default` fall back without a literal value
+            if let Some(f) = p.rust_fallback.captures(rest) {
+                read.has_default = true;
+                read.default_value = f.get(1).map(|value| unquote(value.as_str()));
+            }
+            reads.push(read);
+        }
+        for c in p.rust_env_macro.captures_iter(line) {
+            let source = if &c[1] == "option_env" { "option_env!" } else { "env!" };
+            reads.push(Read::new(&c[2], source, None));
+        }
+    }
+
+    fn plain_reads(line: &str, reads: &mut Vec<Read>) {
+        for c in patterns().plain_env.captures_iter(line) {
+            let source = match &c[1] {
+                "os.Getenv" => "os.Getenv",
+                "os.LookupEnv" => "os.LookupEnv",
+                "System.getenv" => "System.getenv",
+                _ => "Environment.GetEnvironmentVariable",
+            };
+            reads.push(Read::new(&c[2], source, None));
+        }
+    }
+}
+
+/// Fallback written right after a read, e.g. `process.env.PORT || 3000`
+fn fallback(pattern: &Regex, line: &str, read: &Captures<'_>) -> Option<String> {
+    let end = read.get(0).map_or(line.len(), |m| m.end());
+    pattern.captures(&line[end..]).map(|f| unquote(&f[1]))
+}
+
+fn unquote(value: &str) -> String {
+    value.trim_matches(|c| c == '"' || c == '\'' || c == '`').to_string()
+}
+
+struct Patterns {
+    js_env: Regex,
+    js_fallback: Regex,
+    js_destructure: Regex,
+    py_environ_index: Regex,
+    py_getenv: Regex,
+    rust_env_var: Regex,
+    rust_fallback: Regex,
+    rust_env_macro: Regex,
+    plain_env: Regex,
+    dotenv: Regex,
+    dockerfile_env: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let re = |pattern: &str| Regex::new(pattern).expect("valid environment variable regex");
+        Patterns {
+            js_env: re(r#"\b(process|import\.meta)\.env(?:\.([A-Za-z_]\w*)|\[\s*["'`]([A-Za-z_]\w*)["'`]\s*\])"#),
+            js_fallback: re(r#"^\s*(?:\|\||\?\?)\s*("[^"]*"|'[^']*'|`[^`]*`|[^\s;,)]+)"#),
+            js_destructure: re(r"\{([^}]*)\}\s*=\s*process\.env\b"),
+            py_environ_index: re(r#"\bos\.environ\[\s*["'](\w+)["']\s*\]"#),
+            py_getenv: re(r#"\bos\.(environ\.get|getenv)\(\s*["'](\w+)["']\s*(?:,\s*([^)]+?))?\s*\)"#),
+            rust_env_var: re(r#"\benv::var(?:_os)?\(\s*"(\w+)"\s*\)"#),
+            rust_fallback: re(r#"^\s*(?:\.ok\(\)\s*)?\.unwrap_or(?:_else|_default)?\(\s*("[^"]*")?"#),
+            rust_env_macro: re(r#"\b(env|option_env)!\(\s*"(\w+)""#),
+            plain_env: re(r#"\b(os\.Getenv|os\.LookupEnv|System\.getenv|Environment\.GetEnvironmentVariable)\(\s*"(\w+)""#),
+            dotenv: re(r"^(?:export\s+)?([A-Za-z_]\w*)\s*=\s*(.*)$"),
+            dockerfile_env: re(r"^ENV\s+([A-Za-z_]\w*)(?:\s*=\s*|\s+)?(.*)$"),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reads(file_path: &str, content: &str) -> Vec<(String, Option<String>)> {
+        EnvVarScanner::extract(file_path, content)
+            .into_iter()
+            .map(|(name, reference)| (name, reference.default_value))
+            .collect()
+    }
+
+    #[test]
+    fn test_javascript_reads_and_fallbacks() {
+        let source = "\
+const port = process.env.PORT || 3000;
+const url = process.env['DATABASE_URL'];
+const mode = import.meta.env.MODE ?? 'development';
+const { API_KEY, REGION = \"us-east-1\" } = process.env;
+";
+        assert_eq!(
+            reads("src/config.ts", source),
+            vec![
+                ("PORT".to_string(), Some("3000".to_string())),
+                ("DATABASE_URL".to_string(), None),
+                ("MODE".to_string(), Some("development".to_string())),
+                ("API_KEY".to_string(), None),
+                ("REGION".to_string(), Some("us-east-1".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_python_rust_and_go_reads() {
+        let python = "\
+secret = os.environ[\"SECRET_KEY\"]
+debug = os.environ.get(\"DEBUG\", \"false\")
+token = os.getenv('TOKEN', None)
+";
+        assert_eq!(
+            reads("app/settings.py", python),
+            vec![
+                ("SECRET_KEY".to_string(), None),
+                ("DEBUG".to_string(), Some("false".to_string())),
+                ("TOKEN".to_string(), None),
+            ]
+        );
+
+        let rust = "\
+let level = std::env::var(\"RUST_LOG\").unwrap_or(\"info\".to_string());
+let home = env::var(\"HOME\")?;
+let version = env!(\"CARGO_PKG_VERSION\");
+";
+        assert_eq!(
+            reads("src/main.rs", rust),
+            vec![
+                ("RUST_LOG".to_string(), Some("info".to_string())),
+                ("HOME".to_string(), None),
+                ("CARGO_PKG_VERSION".to_string(), None),
+            ]
+        );
+
+        assert_eq!(
+            reads("cmd/server/main.go", "addr := os.Getenv(\"ADDR\")\n"),
+            vec![("ADDR".to_string(), None)]
+        );
+    }
+
+    #[test]
+    fn test_scan_merges_reads_and_declarations() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(
+            dir.path().join("src/server.js"),
+            "const port = process.env.PORT || 8080;\nconst db = process.env.DATABASE_URL;\nconst key = process.env.API_KEY;\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join(".env.example"), "# local settings\nDATABASE_URL=postgres://localhost/app\n").unwrap();
+        fs::write(dir.path().join("Dockerfile"), "FROM node:20\nENV NODE_ENV=production\n").unwrap();
+
+        let summary = EnvVarScanner::scan(dir.path()).unwrap();
+        let names: Vec<&str> = summary.variables.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["API_KEY", "DATABASE_URL", "NODE_ENV", "PORT"]);
+
+        let database_url = &summary.variables[1];
+        assert!(database_url.declared);
+        assert!(database_url.required);
+        assert!(database_url.has_default);
+        assert_eq!(database_url.references.len(), 2);
+
+        let node_env = &summary.variables[2];
+        assert_eq!(node_env.read_count, 0);
+        assert_eq!(node_env.references[0].default_value.as_deref(), Some("production"));
+
+        assert!(!summary.variables[3].required);
+        assert_eq!(summary.undeclared_required, vec!["API_KEY".to_string()]);
+    }
+}
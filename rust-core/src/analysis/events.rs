@@ -0,0 +1,585 @@
+//! Event and message topology: what each module produces and consumes
+//!
+//! An event-driven system barely shows in its directory names; its shape is
+//! in the messaging client calls. [`EventScanner`] walks JavaScript,
+//! TypeScript and Python ASTs for them:
+//! - Kafka: `producer.send({ topic })`, `consumer.subscribe({ topics })`,
+//!   `producer.produce('orders', ...)`, `KafkaConsumer('orders')`
+//! - SQS: `new SendMessageCommand({ QueueUrl })`, `ReceiveMessageCommand`,
+//!   `sqs.sendMessage(...)`, boto3's `send_message(QueueUrl=...)` and `receive_message`
+//! - RabbitMQ: `sendToQueue('jobs')`, `publish(exchange, routingKey, ...)`,
+//!   `consume('jobs', handler)`, pika's `basic_publish` and `basic_consume`
+//! - Pub/sub clients such as Redis and NATS: `publish('channel', ...)`, `subscribe('channel')`
+//! - Event emitters: `emit('order.created')`, `on('order.created', handler)`
+//!
+//! Every topic, queue, exchange or event name becomes an [`EventChannel`] with
+//! its producers and consumers, and each producer is joined to each consumer
+//! of its channel by an [`EventFlow`]. Channels given as an expression, such
+//! as `process.env.ORDERS_QUEUE_URL`, are named by that expression; emitter and
+//! pub/sub channels only count when they are string literals.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::parsing::{FileWalker, ParserManager, TreeWalker};
+use crate::types::ParseError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use tree_sitter::Node;
+
+/// Files larger than this are not parsed for messaging calls
+const MAX_EVENT_FILE_SIZE: u64 = 512 * 1024;
+
+/// Flows kept in one graph; a busy channel joins every producer to every consumer
+pub const MAX_EVENT_FLOWS: usize = 500;
+
+/// Stream, socket and process events that are not part of the domain's topology
+const LIFECYCLE_EVENTS: &[&str] = &[
+    "error", "data", "end", "close", "finish", "drain", "readable", "open", "connect", "connection", "disconnect",
+    "exit", "message", "listening", "request", "response", "timeout", "ready", "uncaughtException",
+    "unhandledRejection", "SIGINT", "SIGTERM",
+];
+
+/// Import and variable name fragments naming a messaging client
+const TRANSPORT_HINTS: &[(&str, &str)] = &[
+    ("kafka", "kafka"),
+    ("client-sqs", "sqs"),
+    ("boto3", "sqs"),
+    ("sqs", "sqs"),
+    ("amqplib", "rabbitmq"),
+    ("amqp", "rabbitmq"),
+    ("pika", "rabbitmq"),
+    ("rabbit", "rabbitmq"),
+    ("nats", "nats"),
+    ("redis", "redis"),
+];
+
+/// Where a message is sent or handled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct EventClient {
+    pub file_path: String,
+    pub line: u32,
+    /// Function, method or class making the call, when it is named
+    pub symbol: Option<String>,
+}
+
+/// A topic, queue, exchange or event name and who uses it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct EventChannel {
+    pub name: String,
+    /// 'kafka', 'sqs', 'rabbitmq', 'redis', 'nats', 'pubsub' or 'event-emitter'
+    pub transport: String,
+    pub producers: Vec<EventClient>,
+    pub consumers: Vec<EventClient>,
+}
+
+/// A producer reaching a consumer through a channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct EventFlow {
+    /// `file#symbol` of the producer, or its file when the call is not in a named symbol
+    pub producer: String,
+    pub consumer: String,
+    pub channel: String,
+    pub transport: String,
+}
+
+/// Producers and consumers of a project joined by the channels they share
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct EventGraph {
+    /// By transport, then name
+    pub channels: Vec<EventChannel>,
+    /// At most [`MAX_EVENT_FLOWS`]
+    pub flows: Vec<EventFlow>,
+    pub transports: Vec<String>,
+    pub producer_count: u32,
+    pub consumer_count: u32,
+}
+
+impl EventGraph {
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    /// Channels of `transport`
+    pub fn channels_of<'a>(&'a self, transport: &'a str) -> impl Iterator<Item = &'a EventChannel> {
+        self.channels.iter().filter(move |channel| channel.transport == transport)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Producer,
+    Consumer,
+}
+
+/// One messaging call found in a file
+struct EventUse {
+    role: Role,
+    transport: &'static str,
+    channel: String,
+    client: EventClient,
+}
+
+pub struct EventScanner;
+
+impl EventScanner {
+    /// Event graph of every supported source file under `root`
+    pub fn scan(root: &Path) -> Result<EventGraph, ParseError> {
+        let mut parser_manager = ParserManager::new()?;
+        let mut uses = Vec::new();
+
+        let walk = FileWalker::new()
+            .extensions(&["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs", "py"])
+            .max_file_size(MAX_EVENT_FILE_SIZE)
+            .walk(root);
+        for path in walk.files {
+            let Some(language) = path.extension().and_then(|e| e.to_str()).and_then(Self::language_for_extension) else {
+                continue;
+            };
+            let Ok(content) = fs::read_to_string(&path) else { continue };
+            if !Self::may_use_events(&content) {
+                continue;
+            }
+            let Ok(tree) = parser_manager.parse(&content, language) else { continue };
+
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            let ctx = FileContext { file_path: &relative, content: &content, transport: Self::file_transport(&content) };
+            uses.extend(ctx.extract(tree.root_node(), language));
+        }
+
+        Ok(Self::build(uses))
+    }
+
+    /// Event graph of one parsed file
+    pub fn extract(root: Node<'_>, language: &str, file_path: &str, content: &str) -> EventGraph {
+        let ctx = FileContext { file_path, content, transport: Self::file_transport(content) };
+        Self::build(ctx.extract(root, language))
+    }
+
+    fn build(uses: Vec<EventUse>) -> EventGraph {
+        let mut channels: BTreeMap<(&'static str, String), EventChannel> = BTreeMap::new();
+        let (mut producer_count, mut consumer_count) = (0u32, 0u32);
+        for event in uses {
+            let channel = channels.entry((event.transport, event.channel.clone())).or_insert_with(|| EventChannel {
+                name: event.channel,
+                transport: event.transport.to_string(),
+                producers: Vec::new(),
+                consumers: Vec::new(),
+            });
+            match event.role {
+                Role::Producer => {
+                    producer_count += 1;
+                    channel.producers.push(event.client);
+                }
+                Role::Consumer => {
+                    consumer_count += 1;
+                    channel.consumers.push(event.client);
+                }
+            }
+        }
+
+        let mut flows = BTreeSet::new();
+        for channel in channels.values() {
+            for producer in &channel.producers {
+                for consumer in &channel.consumers {
+                    let (from, to) = (Self::client_id(producer), Self::client_id(consumer));
+                    if from != to {
+                        flows.insert((from, to, channel.name.clone(), channel.transport.clone()));
+                    }
+                }
+            }
+        }
+
+        let transports: BTreeSet<String> = channels.keys().map(|(transport, _)| transport.to_string()).collect();
+        EventGraph {
+            channels: channels.into_values().collect(),
+            flows: flows
+                .into_iter()
+                .take(MAX_EVENT_FLOWS)
+                .map(|(producer, consumer, channel, transport)| EventFlow { producer, consumer, channel, transport })
+                .collect(),
+            transports: transports.into_iter().collect(),
+            producer_count,
+            consumer_count,
+        }
+    }
+
+    fn client_id(client: &EventClient) -> String {
+        match &client.symbol {
+            Some(symbol) => format!("{}#{}", client.file_path, symbol),
+            None => client.file_path.clone(),
+        }
+    }
+
+    fn language_for_extension(extension: &str) -> Option<&'static str> {
+        match extension {
+            "ts" | "tsx" | "mts" | "cts" => Some("typescript"),
+            "js" | "jsx" | "mjs" | "cjs" => Some("javascript"),
+            "py" => Some("python"),
+            _ => None,
+        }
+    }
+
+    /// Cheap text check so files without any messaging-like call are not parsed
+    fn may_use_events(content: &str) -> bool {
+        [
+            "emit(", ".on(", "once(", "Listener(", "publish(", "subscribe(", "send(", "produce(", "consume(",
+            "Queue(", "Message", "KafkaConsumer",
+        ]
+        .iter()
+        .any(|hint| content.contains(hint))
+    }
+
+    /// First messaging client the file mentions, for calls such as `publish`
+    /// that several clients share
+    fn file_transport(content: &str) -> Option<&'static str> {
+        TRANSPORT_HINTS.iter().find(|(hint, _)| content.contains(hint)).map(|(_, transport)| *transport)
+    }
+}
+
+struct FileContext<'a> {
+    file_path: &'a str,
+    content: &'a str,
+    transport: Option<&'static str>,
+}
+
+/// The parts of a call the rules look at
+struct Call<'t> {
+    node: Node<'t>,
+    /// Called function, method or constructor name
+    name: String,
+    /// Text of the object the method is called on
+    receiver: String,
+    /// Positional arguments
+    arguments: Vec<Node<'t>>,
+    /// Python keyword arguments
+    keywords: Vec<(String, Node<'t>)>,
+}
+
+impl FileContext<'_> {
+    fn extract(&self, root: Node<'_>, language: &str) -> Vec<EventUse> {
+        let mut uses = Vec::new();
+        // A tree deeper than the walker allows still yields the calls found so far
+        let _ = TreeWalker::default().walk(root, &mut |node| {
+            let call = match (language, node.kind()) {
+                ("javascript" | "typescript", "call_expression" | "new_expression") => self.js_call(node),
+                ("python", "call") => self.python_call(node),
+                _ => None,
+            };
+            if let Some(call) = call {
+                self.classify(&call, &mut uses);
+            }
+            Ok(())
+        });
+        uses
+    }
+
+    fn text(&self, node: Node<'_>) -> &str {
+        node.utf8_text(self.content.as_bytes()).unwrap_or("")
+    }
+
+    fn js_call<'t>(&self, node: Node<'t>) -> Option<Call<'t>> {
+        let function = node.child_by_field_name("function").or_else(|| node.child_by_field_name("constructor"))?;
+        let (name, receiver) = match function.kind() {
+            "member_expression" => (
+                self.text(function.child_by_field_name("property")?).to_string(),
+                function.child_by_field_name("object").map(|o| self.text(o).to_string()).unwrap_or_default(),
+            ),
+            "identifier" => (self.text(function).to_string(), String::new()),
+            _ => return None,
+        };
+        let arguments = node.child_by_field_name("arguments")?;
+        let mut cursor = arguments.walk();
+        let arguments = arguments.named_children(&mut cursor).filter(|n| n.kind() != "comment").collect();
+        Some(Call { node, name, receiver, arguments, keywords: Vec::new() })
+    }
+
+    fn python_call<'t>(&self, node: Node<'t>) -> Option<Call<'t>> {
+        let function = node.child_by_field_name("function")?;
+        let (name, receiver) = match function.kind() {
+            "attribute" => (
+                self.text(function.child_by_field_name("attribute")?).to_string(),
+                function.child_by_field_name("object").map(|o| self.text(o).to_string()).unwrap_or_default(),
+            ),
+            "identifier" => (self.text(function).to_string(), String::new()),
+            _ => return None,
+        };
+        let mut arguments = Vec::new();
+        let mut keywords = Vec::new();
+        let argument_list = node.child_by_field_name("arguments")?;
+        let mut cursor = argument_list.walk();
+        for argument in argument_list.named_children(&mut cursor) {
+            match argument.kind() {
+                "keyword_argument" => {
+                    if let (Some(key), Some(value)) =
+                        (argument.child_by_field_name("name"), argument.child_by_field_name("value"))
+                    {
+                        keywords.push((self.text(key).to_string(), value));
+                    }
+                }
+                "comment" => {}
+                _ => arguments.push(argument),
+            }
+        }
+        Some(Call { node, name, receiver, arguments, keywords })
+    }
+
+    fn classify(&self, call: &Call<'_>, uses: &mut Vec<EventUse>) {
+        let first = call.arguments.first().copied();
+        let options = first.filter(|f| f.kind() == "object");
+        let option = |key: &str| options.and_then(|o| self.property(o, key)).or_else(|| call.keyword(key));
+        let transport = self.call_transport(call);
+
+        match call.name.as_str() {
+            // Kafka
+            "send" if option("topic").is_some() => self.push(uses, call, Role::Producer, "kafka", option("topic"), false),
+            "send" if call.receiver.to_lowercase().contains("producer") => {
+                self.push(uses, call, Role::Producer, "kafka", first.or(option("topic")), false)
+            }
+            "produce" => self.push(uses, call, Role::Producer, "kafka", first.or(option("topic")), false),
+            "subscribe" if option("topic").or(option("topics")).is_some() => {
+                self.push(uses, call, Role::Consumer, "kafka", option("topic").or(option("topics")), false)
+            }
+            "subscribe" if transport == Some("kafka") => self.push(uses, call, Role::Consumer, "kafka", first, false),
+            "KafkaConsumer" => {
+                for topic in call.arguments.iter().filter(|a| self.literal(**a).is_some()) {
+                    self.push(uses, call, Role::Consumer, "kafka", Some(*topic), true);
+                }
+            }
+            // SQS
+            "sendMessage" | "sendMessageBatch" | "send_message" | "send_message_batch" | "SendMessageCommand"
+            | "SendMessageBatchCommand" => self.push(uses, call, Role::Producer, "sqs", option("QueueUrl"), false),
+            "receiveMessage" | "receive_message" | "ReceiveMessageCommand" => {
+                self.push(uses, call, Role::Consumer, "sqs", option("QueueUrl"), false)
+            }
+            // RabbitMQ
+            "sendToQueue" => self.push(uses, call, Role::Producer, "rabbitmq", first, false),
+            "consume" if transport == Some("rabbitmq") => self.push(uses, call, Role::Consumer, "rabbitmq", first, false),
+            "basic_consume" => self.push(uses, call, Role::Consumer, "rabbitmq", call.keyword("queue").or(first), false),
+            "basic_publish" => {
+                let exchange = call.keyword("exchange").or(first);
+                let routing_key = call.keyword("routing_key").or_else(|| call.arguments.get(1).copied());
+                self.push(uses, call, Role::Producer, "rabbitmq", self.exchange_or_key(exchange, routing_key), false)
+            }
+            "publish" if transport == Some("rabbitmq") && call.arguments.len() >= 3 => {
+                let channel = self.exchange_or_key(first, call.arguments.get(1).copied());
+                self.push(uses, call, Role::Producer, "rabbitmq", channel, false)
+            }
+            // Pub/sub clients
+            "publish" => self.push(uses, call, Role::Producer, transport.unwrap_or("pubsub"), first, true),
+            "subscribe" | "psubscribe" => {
+                self.push(uses, call, Role::Consumer, transport.unwrap_or("pubsub"), first, true)
+            }
+            // Event emitters
+            "emit" => self.push(uses, call, Role::Producer, "event-emitter", first, true),
+            "on" | "once" | "addListener" | "prependListener" => {
+                // A handler argument, or a decorator such as pyee's `@bus.on('saved')`
+                let registers = call.arguments.len() >= 2 || call.node.parent().is_some_and(|p| p.kind() == "decorator");
+                let domain_event =
+                    first.and_then(|f| self.literal(f)).is_some_and(|name| !LIFECYCLE_EVENTS.contains(&name.as_str()));
+                if registers && domain_event {
+                    self.push(uses, call, Role::Consumer, "event-emitter", first, true)
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Client named by the receiver, e.g. `redisClient`, else the one the file mentions
+    fn call_transport(&self, call: &Call<'_>) -> Option<&'static str> {
+        let receiver = call.receiver.to_lowercase();
+        TRANSPORT_HINTS
+            .iter()
+            .find(|(hint, _)| receiver.contains(hint))
+            .map(|(_, transport)| *transport)
+            .or(self.transport)
+    }
+
+    /// The exchange, or the routing key when publishing to the default exchange
+    fn exchange_or_key<'t>(&self, exchange: Option<Node<'t>>, routing_key: Option<Node<'t>>) -> Option<Node<'t>> {
+        match exchange {
+            Some(exchange) if self.literal(exchange).is_some_and(|name| name.is_empty()) => routing_key,
+            other => other.or(routing_key),
+        }
+    }
+
+    /// Record a use of each channel `channel` names; literals only when `literal_only`
+    fn push(
+        &self,
+        uses: &mut Vec<EventUse>,
+        call: &Call<'_>,
+        role: Role,
+        transport: &'static str,
+        channel: Option<Node<'_>>,
+        literal_only: bool,
+    ) {
+        let Some(channel) = channel else { return };
+        let names: Vec<String> = match channel.kind() {
+            "array" | "list" | "tuple" => {
+                let mut cursor = channel.walk();
+                let elements: Vec<Node<'_>> = channel.named_children(&mut cursor).collect();
+                elements.into_iter().filter_map(|element| self.literal(element)).collect()
+            }
+            _ => match self.literal(channel) {
+                Some(name) => vec![name],
+                None if literal_only => vec![],
+                None => vec![self.text(channel).split_whitespace().collect::<Vec<_>>().join(" ")],
+            },
+        };
+        for name in names.into_iter().filter(|name| !name.is_empty()) {
+            uses.push(EventUse {
+                role,
+                transport,
+                channel: name,
+                client: EventClient {
+                    file_path: self.file_path.to_string(),
+                    line: call.node.start_position().row as u32 + 1,
+                    symbol: self.enclosing_symbol(call.node),
+                },
+            });
+        }
+    }
+
+    /// Value of a string literal without interpolations
+    fn literal(&self, node: Node<'_>) -> Option<String> {
+        let plain = match node.kind() {
+            "string" => {
+                let mut cursor = node.walk();
+                let interpolated = node.named_children(&mut cursor).any(|child| child.kind() == "interpolation");
+                !interpolated
+            }
+            "template_string" => node.named_child_count() == 0,
+            _ => false,
+        };
+        plain.then(|| unquote(self.text(node)))
+    }
+
+    /// Value of `key` in a JavaScript object literal; `{ topic }` gives the identifier
+    fn property<'t>(&self, object: Node<'t>, key: &str) -> Option<Node<'t>> {
+        let mut cursor = object.walk();
+        let found = object.named_children(&mut cursor).find_map(|member| match member.kind() {
+            "pair" => {
+                let name = member.child_by_field_name("key")?;
+                let name = self.literal(name).unwrap_or_else(|| self.text(name).to_string());
+                (name == key).then(|| member.child_by_field_name("value"))?
+            }
+            "shorthand_property_identifier" => (self.text(member) == key).then_some(member),
+            _ => None,
+        });
+        found
+    }
+
+    /// Nearest named function, method or class around `node`
+    fn enclosing_symbol(&self, node: Node<'_>) -> Option<String> {
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            if matches!(
+                parent.kind(),
+                "function_declaration"
+                    | "method_definition"
+                    | "function_definition"
+                    | "class_declaration"
+                    | "class_definition"
+                    | "variable_declarator"
+            ) {
+                if let Some(name) = parent.child_by_field_name("name") {
+                    return Some(self.text(name).to_string());
+                }
+            }
+            current = parent.parent();
+        }
+        None
+    }
+}
+
+impl Call<'_> {
+    fn keyword(&self, key: &str) -> Option<Node<'_>> {
+        self.keywords.iter().find(|(name, _)| name == key).map(|(_, value)| *value)
+    }
+}
+
+/// Strip string prefixes (b, f, r) and quotes from a literal
+fn unquote(literal: &str) -> String {
+    literal
+        .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+        .trim_matches(|c| c == '"' || c == '\'' || c == '`')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(language: &str, file_path: &str, code: &str) -> EventGraph {
+        let mut parser_manager = ParserManager::new().unwrap();
+        let tree = parser_manager.parse(code, language).unwrap();
+        EventScanner::extract(tree.root_node(), language, file_path, code)
+    }
+
+    #[test]
+    fn test_messaging_calls_form_an_event_graph() {
+        let node = r#"
+import { Kafka } from 'kafkajs';
+import { SQSClient, SendMessageCommand } from '@aws-sdk/client-sqs';
+
+async function placeOrder(order) {
+  await producer.send({ topic: 'orders', messages: [{ value: JSON.stringify(order) }] });
+  await sqs.send(new SendMessageCommand({ QueueUrl: process.env.EMAIL_QUEUE_URL, MessageBody: '' }));
+  bus.emit('order.placed', order);
+}
+
+async function startBilling() {
+  await consumer.subscribe({ topics: ['orders', 'refunds'] });
+  bus.on('order.placed', chargeCard);
+  stream.on('error', console.error);
+  bus.emit(`order.${kind}`);
+}
+"#;
+        let events = graph("typescript", "src/orders.ts", node);
+        assert_eq!(events.transports, ["event-emitter", "kafka", "sqs"]);
+        assert_eq!((events.producer_count, events.consumer_count), (3, 3));
+
+        let orders = events.channels.iter().find(|c| c.name == "orders").unwrap();
+        assert_eq!(orders.transport, "kafka");
+        assert_eq!(orders.producers[0].symbol.as_deref(), Some("placeOrder"));
+        assert_eq!(orders.consumers[0].symbol.as_deref(), Some("startBilling"));
+        assert!(events.channels_of("sqs").any(|c| c.name == "process.env.EMAIL_QUEUE_URL" && c.consumers.is_empty()));
+        assert!(events.channels.iter().all(|c| c.name != "error" && !c.name.contains('$')));
+        assert!(events.flows.contains(&EventFlow {
+            producer: "src/orders.ts#placeOrder".to_string(),
+            consumer: "src/orders.ts#startBilling".to_string(),
+            channel: "order.placed".to_string(),
+            transport: "event-emitter".to_string(),
+        }));
+
+        let python = r#"
+import pika
+import redis
+
+def publish_invoice(channel):
+    channel.basic_publish(exchange='', routing_key='invoices', body=b'')
+    redis_client.publish('invoices.ready', '1')
+
+def run_worker(channel):
+    channel.basic_consume(queue='invoices', on_message_callback=handle)
+"#;
+        let events = graph("python", "billing/worker.py", python);
+        let invoices = events.channels_of("rabbitmq").find(|c| c.name == "invoices").unwrap();
+        assert_eq!((invoices.producers.len(), invoices.consumers.len()), (1, 1));
+        assert_eq!(invoices.producers[0].line, 6);
+        // The file imports pika first, but the receiver names the client
+        assert!(events.channels_of("redis").any(|c| c.name == "invoices.ready" && c.consumers.is_empty()));
+        assert_eq!(events.flows.len(), 1);
+    }
+}
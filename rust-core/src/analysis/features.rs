@@ -0,0 +1,333 @@
+//! Feature map derived by clustering the concept graph
+//!
+//! Features used to be a fixed list of directory names ("auth", "api",
+//! "utils") matched against the project. They are now found in the code:
+//! files are clustered by label propagation over the relationships between
+//! their concepts. Every file starts in its directory's cluster and is pulled
+//! back towards it by each other file there, so the directory layout acts as a
+//! prior that stronger code links can override. Each cluster is named after
+//! the directory it grew from, or after the words its concepts use most, and
+//! lists its concepts, the concepts other features (or the project's entry
+//! points) enter it through, the features it depends on and the files outside
+//! it that it's linked to.
+
+use crate::analysis::{EdgeDirection, EntryPoint, FeatureMap, GraphNode, GraphStore, RelationshipKind};
+use crate::patterns::naming::NamingPatternAnalyzer;
+use crate::patterns::KeywordExtractor;
+use crate::types::paths;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+/// Pull of a file's directory per other file in it, against 1.0 per relationship
+const DIRECTORY_WEIGHT: f64 = 1.0;
+
+/// Label propagation stops after this many passes even if labels still change
+const MAX_ITERATIONS: usize = 20;
+
+/// Directory names that say where code lives, not what it does
+const GENERIC_SEGMENTS: &[&str] = &[
+    "src", "lib", "libs", "app", "apps", "source", "sources", "main", "java", "kotlin", "python", "packages",
+    "pkg", "internal", "core", "rust-core", "cmd", "modules",
+];
+
+pub struct FeatureClusterer;
+
+impl FeatureClusterer {
+    /// Features of a project's concept graph; file paths are made relative
+    /// to `project_root`, where `entry_points` are already relative
+    pub fn cluster(graph: &GraphStore, entry_points: &[EntryPoint], project_root: &Path) -> Vec<FeatureMap> {
+        let relative = |path: &str| paths::relative_to(path, project_root);
+
+        let mut files: BTreeMap<String, Vec<&GraphNode>> = BTreeMap::new();
+        let mut file_of: HashMap<&str, String> = HashMap::new();
+        for node in graph.nodes() {
+            let file = relative(&node.file_path);
+            file_of.insert(node.id.as_str(), file.clone());
+            files.entry(file).or_default().push(node);
+        }
+        if files.is_empty() {
+            return Vec::new();
+        }
+
+        let links = Self::file_links(graph, &file_of);
+        let labels = Self::propagate_labels(&files, &links);
+
+        let mut clusters: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for (file, label) in &labels {
+            clusters.entry(label.as_str()).or_default().push(file.as_str());
+        }
+        let mut clusters: Vec<(&str, Vec<&str>)> = clusters.into_iter().collect();
+        clusters.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
+
+        let mut used_names = BTreeSet::new();
+        let names: Vec<String> = clusters
+            .iter()
+            .map(|(label, members)| {
+                let base = Self::feature_name(label, members, &files);
+                let mut name = base.clone();
+                let mut suffix = 2;
+                while !used_names.insert(name.clone()) {
+                    name = format!("{}-{}", base, suffix);
+                    suffix += 1;
+                }
+                name
+            })
+            .collect();
+        let feature_of: HashMap<&str, usize> = clusters
+            .iter()
+            .enumerate()
+            .flat_map(|(index, (_, members))| members.iter().map(move |file| (*file, index)))
+            .collect();
+        let entry_files: BTreeSet<&str> = entry_points.iter().map(|entry| entry.file_path.as_str()).collect();
+
+        clusters
+            .iter()
+            .enumerate()
+            .map(|(index, (_, members))| {
+                let mut concepts = BTreeSet::new();
+                let mut entered_through = BTreeSet::new();
+                let mut related_files = BTreeSet::new();
+                let mut dependencies = BTreeSet::new();
+
+                for file in members {
+                    for node in &files[*file] {
+                        concepts.insert(node.name.clone());
+                        let nested = graph
+                            .graph()
+                            .edges_of(&node.id, Some(RelationshipKind::Contains), EdgeDirection::Incoming)
+                            .next()
+                            .is_some();
+                        if entry_files.contains(file) && !nested {
+                            entered_through.insert(node.name.clone());
+                        }
+                    }
+                }
+                for edge in graph.graph().all_edges() {
+                    if edge.kind == RelationshipKind::Contains.as_str() {
+                        continue;
+                    }
+                    let (Some(from), Some(to)) = (file_of.get(edge.from.as_str()), file_of.get(edge.to.as_str())) else {
+                        continue;
+                    };
+                    let (from_feature, to_feature) = (feature_of[from.as_str()], feature_of[to.as_str()]);
+                    if from_feature == to_feature {
+                        continue;
+                    }
+                    if to_feature == index {
+                        if let Some(node) = graph.node(&edge.to) {
+                            entered_through.insert(node.name.clone());
+                        }
+                        related_files.insert(from.clone());
+                    } else if from_feature == index {
+                        dependencies.insert(names[to_feature].clone());
+                        related_files.insert(to.clone());
+                    }
+                }
+
+                FeatureMap {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    feature_name: names[index].clone(),
+                    primary_files: members.iter().map(|file| file.to_string()).collect(),
+                    related_files: related_files.into_iter().collect(),
+                    dependencies: dependencies.into_iter().collect(),
+                    concepts: concepts.into_iter().collect(),
+                    entry_points: entered_through.into_iter().collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Weight of the relationships between each pair of different files
+    fn file_links(graph: &GraphStore, file_of: &HashMap<&str, String>) -> HashMap<String, BTreeMap<String, f64>> {
+        let mut links: HashMap<String, BTreeMap<String, f64>> = HashMap::new();
+        for edge in graph.graph().all_edges() {
+            let weight = match RelationshipKind::parse(&edge.kind) {
+                // Documentation that mentions a file doesn't make it part of a feature
+                Some(RelationshipKind::Contains | RelationshipKind::Documents) | None => continue,
+                Some(RelationshipKind::Imports) => 0.5,
+                Some(_) => 1.0,
+            };
+            let (Some(from), Some(to)) = (file_of.get(edge.from.as_str()), file_of.get(edge.to.as_str())) else {
+                continue;
+            };
+            if from != to {
+                *links.entry(from.clone()).or_default().entry(to.clone()).or_insert(0.0) += weight;
+                *links.entry(to.clone()).or_default().entry(from.clone()).or_insert(0.0) += weight;
+            }
+        }
+        links
+    }
+
+    /// Cluster label of every file: files start labelled with their directory
+    /// and repeatedly take the label weighing most among their linked files and
+    /// the pull of their directory, keeping their own on ties
+    fn propagate_labels(
+        files: &BTreeMap<String, Vec<&GraphNode>>,
+        links: &HashMap<String, BTreeMap<String, f64>>,
+    ) -> BTreeMap<String, String> {
+        let directories: BTreeMap<&str, String> = files
+            .keys()
+            .map(|file| {
+                let directory = Path::new(file)
+                    .parent()
+                    .map(|dir| dir.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                (file.as_str(), directory)
+            })
+            .collect();
+        let mut directory_sizes: HashMap<&str, usize> = HashMap::new();
+        for directory in directories.values() {
+            *directory_sizes.entry(directory.as_str()).or_insert(0) += 1;
+        }
+        let mut labels: BTreeMap<String, String> = directories
+            .iter()
+            .map(|(file, directory)| (file.to_string(), directory.clone()))
+            .collect();
+
+        for _ in 0..MAX_ITERATIONS {
+            let mut changed = false;
+            for file in files.keys() {
+                let directory = directories[file.as_str()].as_str();
+                let mut scores: BTreeMap<&str, f64> = BTreeMap::new();
+                for (linked, weight) in links.get(file).into_iter().flatten() {
+                    *scores.entry(labels[linked].as_str()).or_insert(0.0) += weight;
+                }
+                let siblings = directory_sizes[directory] - 1;
+                if siblings > 0 {
+                    *scores.entry(directory).or_insert(0.0) += DIRECTORY_WEIGHT * siblings as f64;
+                }
+
+                let current = labels[file].as_str();
+                let best = scores.values().cloned().fold(0.0, f64::max);
+                if best == 0.0 || scores.get(current).is_some_and(|score| *score >= best) {
+                    continue;
+                }
+                let Some((label, _)) = scores.iter().find(|(_, score)| **score >= best) else {
+                    continue;
+                };
+                let label = label.to_string();
+                labels.insert(file.clone(), label);
+                changed = true;
+            }
+            if !changed {
+                break;
+            }
+        }
+        labels
+    }
+
+    /// Last meaningful segment of the directory a cluster grew from, else the
+    /// most frequent word of its concept names
+    fn feature_name(label: &str, members: &[&str], files: &BTreeMap<String, Vec<&GraphNode>>) -> String {
+        let segment = label
+            .split(['/', '\\'])
+            .rfind(|segment| !segment.is_empty() && !GENERIC_SEGMENTS.contains(&segment.to_lowercase().as_str()));
+        if let Some(segment) = segment {
+            return Self::slug(segment);
+        }
+
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for node in members.iter().flat_map(|file| &files[*file]) {
+            for word in NamingPatternAnalyzer::split_words(&node.name) {
+                let word = word.to_lowercase();
+                if word.len() > 2 && KeywordExtractor::normalize(&word).is_some() {
+                    *counts.entry(word).or_insert(0) += 1;
+                }
+            }
+        }
+        counts
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+            .map(|(word, _)| word)
+            .unwrap_or_else(|| "root".to_string())
+    }
+
+    fn slug(segment: &str) -> String {
+        segment
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{LineRange, SemanticConcept};
+
+    fn concept(name: &str, file_path: &str, relationships: &[(&str, &str)]) -> SemanticConcept {
+        SemanticConcept {
+            id: format!("{}::{}", file_path, name),
+            name: name.to_string(),
+            concept_type: "class".to_string(),
+            confidence: 0.9,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start: 1, end: 1 },
+            relationships: relationships.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn entry_point(file_path: &str) -> EntryPoint {
+        EntryPoint {
+            entry_type: "api".to_string(),
+            file_path: file_path.to_string(),
+            framework: None,
+            confidence: 0.9,
+            source: None,
+        }
+    }
+
+    fn feature<'a>(features: &'a [FeatureMap], name: &str) -> &'a FeatureMap {
+        features.iter().find(|f| f.feature_name == name).unwrap_or_else(|| panic!("no feature {}", name))
+    }
+
+    #[test]
+    fn test_files_cluster_by_directory_and_code_links() {
+        let root = Path::new("/repo");
+        let graph = GraphStore::build(&[
+            concept("CheckoutController", "/repo/src/checkout/controller.ts", &[("uses", "CheckoutService")]),
+            concept("CheckoutService", "/repo/src/checkout/service.ts", &[("uses", "PaymentGateway, CartStore")]),
+            concept("PaymentGateway", "/repo/src/payments/gateway.ts", &[]),
+            concept("StripeClient", "/repo/src/payments/stripe.ts", &[("implements", "PaymentGateway")]),
+            concept("CartStore", "/repo/src/cart/store.ts", &[]),
+            concept("CartItem", "/repo/src/cart/item.ts", &[]),
+            // Lives among the helpers but only works for checkout
+            concept("CheckoutTotals", "/repo/src/utils/totals.ts", &[("uses", "CheckoutService, CheckoutController")]),
+            concept("formatDate", "/repo/src/utils/dates.ts", &[]),
+        ]);
+
+        let features = FeatureClusterer::cluster(&graph, &[entry_point("src/checkout/controller.ts")], root);
+        let names: BTreeSet<&str> = features.iter().map(|f| f.feature_name.as_str()).collect();
+        assert_eq!(names, BTreeSet::from(["cart", "checkout", "payments", "utils"]));
+
+        let checkout = feature(&features, "checkout");
+        assert_eq!(checkout.primary_files, vec![
+            "src/checkout/controller.ts".to_string(),
+            "src/checkout/service.ts".to_string(),
+            "src/utils/totals.ts".to_string(),
+        ]);
+        assert_eq!(checkout.dependencies, vec!["cart".to_string(), "payments".to_string()]);
+        assert!(checkout.concepts.contains(&"CheckoutTotals".to_string()));
+        // The declared entry point's file
+        assert_eq!(checkout.entry_points, vec!["CheckoutController".to_string()]);
+
+        let payments = feature(&features, "payments");
+        assert_eq!(payments.entry_points, vec!["PaymentGateway".to_string()]);
+        assert!(payments.related_files.contains(&"src/checkout/service.ts".to_string()));
+        assert_eq!(feature(&features, "utils").primary_files, vec!["src/utils/dates.ts".to_string()]);
+    }
+
+    #[test]
+    fn test_features_without_a_shared_directory_are_named_by_their_concepts() {
+        let graph = GraphStore::build(&[
+            concept("InvoiceBuilder", "invoice.ts", &[("uses", "InvoiceLine")]),
+            concept("InvoiceLine", "lines.ts", &[]),
+        ]);
+        let features = FeatureClusterer::cluster(&graph, &[], Path::new("/elsewhere"));
+        assert_eq!(features.len(), 1);
+        assert_eq!(features[0].feature_name, "invoice");
+        assert!(FeatureClusterer::cluster(&GraphStore::default(), &[], Path::new("/")).is_empty());
+    }
+}
@@ -3,14 +3,70 @@
 #[cfg(feature = "napi-bindings")]
 use napi_derive::napi;
 
+use crate::analysis::{ManifestParser, PackageManifest};
 use crate::types::ParseError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use walkdir::WalkDir;
 use std::fs;
 
+/// Dependency names that identify a framework, per manifest ecosystem. Names
+/// ending in '/', '-' or '_' match as prefixes; Go module paths always do.
+const NPM_FRAMEWORKS: &[(&str, &[&str])] = &[
+    ("React", &["react", "@types/react"]),
+    ("Vue.js", &["vue", "@vue/"]),
+    ("Angular", &["@angular/"]),
+    ("Express", &["express", "@types/express"]),
+    ("Next.js", &["next", "@next/"]),
+    ("Svelte", &["svelte", "@sveltejs/"]),
+    ("Webpack", &["webpack"]),
+    ("Vite", &["vite", "@vitejs/"]),
+    ("Jest", &["jest", "@jest/"]),
+    ("TypeScript", &["typescript"]),
+    ("Tailwind CSS", &["tailwindcss", "@tailwindcss/"]),
+    ("Material-UI", &["@mui/", "@material-ui/"]),
+    ("Lodash", &["lodash", "@types/lodash"]),
+];
+
+const CARGO_FRAMEWORKS: &[(&str, &[&str])] = &[
+    ("Tokio", &["tokio", "tokio-"]),
+    ("Serde", &["serde", "serde_"]),
+    ("Actix Web", &["actix-web", "actix-"]),
+    ("Rocket", &["rocket", "rocket_"]),
+    ("Diesel", &["diesel", "diesel_"]),
+    ("SQLx", &["sqlx", "sqlx-"]),
+    ("Clap", &["clap", "structopt"]),
+    ("Reqwest", &["reqwest"]),
+    ("Tree-sitter", &["tree-sitter", "tree-sitter-"]),
+];
+
+const PYTHON_FRAMEWORKS: &[(&str, &[&str])] = &[
+    ("Django", &["django"]),
+    ("Flask", &["flask"]),
+    ("FastAPI", &["fastapi"]),
+    ("NumPy", &["numpy"]),
+    ("Pandas", &["pandas"]),
+    ("Matplotlib", &["matplotlib"]),
+    ("SQLAlchemy", &["sqlalchemy"]),
+    ("Requests", &["requests"]),
+    ("PyTorch", &["torch"]),
+    ("TensorFlow", &["tensorflow"]),
+];
+
+const GO_FRAMEWORKS: &[(&str, &[&str])] = &[
+    ("Gin", &["github.com/gin-gonic/gin"]),
+    ("Echo", &["github.com/labstack/echo"]),
+    ("Fiber", &["github.com/gofiber/fiber"]),
+    ("GORM", &["gorm.io/gorm"]),
+    ("Cobra", &["github.com/spf13/cobra"]),
+    ("Viper", &["github.com/spf13/viper"]),
+];
+
 /// Framework detection results
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "napi-bindings", napi(object))]
 pub struct FrameworkInfo {
     pub name: String,
@@ -33,7 +89,11 @@ impl FrameworkDetector {
     /// Detect frameworks used in a codebase
     #[cfg_attr(feature = "napi-bindings", napi)]
     pub async fn detect_frameworks(path: String) -> Result<Vec<FrameworkInfo>, ParseError> {
-        let path = path.as_str();
+        Self::detect(&path)
+    }
+
+    /// Frameworks used in a codebase, most confident first
+    pub fn detect(path: &str) -> Result<Vec<FrameworkInfo>, ParseError> {
         let mut frameworks = Vec::new();
         let mut evidence_map: HashMap<String, (HashSet<String>, Option<String>)> = HashMap::new();
 
@@ -50,17 +110,24 @@ impl FrameworkDetector {
         for (framework_name, (evidence_set, version)) in evidence_map {
             let confidence = Self::calculate_confidence(&framework_name, &evidence_set);
             if confidence > 0.3 { // Only include frameworks with reasonable confidence
+                let mut evidence: Vec<String> = evidence_set.into_iter().collect();
+                evidence.sort();
                 frameworks.push(FrameworkInfo {
                     name: framework_name,
                     version,
                     confidence,
-                    evidence: evidence_set.into_iter().collect(),
+                    evidence,
                 });
             }
         }
 
-        // Sort by confidence
-        frameworks.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+        // Sort by confidence, ties by name
+        frameworks.sort_by(|a, b| {
+            b.confidence
+                .partial_cmp(&a.confidence)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.name.cmp(&b.name))
+        });
 
         Ok(frameworks)
     }
@@ -74,6 +141,7 @@ impl FrameworkDetector {
             "package.json",
             "Cargo.toml",
             "requirements.txt",
+            "pyproject.toml",
             "Pipfile",
             "pom.xml",
             "build.gradle",
@@ -105,6 +173,13 @@ impl FrameworkDetector {
 
         let file_name = file_path.file_name().unwrap_or_default().to_str().unwrap_or("");
 
+        // Parsed manifests are matched by dependency name; malformed ones still
+        // get the substring checks below
+        if let Ok(Some(manifest)) = ManifestParser::parse(file_name, &content) {
+            Self::match_manifest_dependencies(file_name, &manifest, evidence_map);
+            return Ok(());
+        }
+
         match file_name {
             "package.json" => Self::parse_package_json(&content, evidence_map),
             "Cargo.toml" => Self::parse_cargo_toml(&content, evidence_map),
@@ -117,6 +192,43 @@ impl FrameworkDetector {
         Ok(())
     }
 
+    /// Record frameworks whose packages are declared in a parsed manifest
+    fn match_manifest_dependencies(
+        file_name: &str,
+        manifest: &PackageManifest,
+        evidence_map: &mut HashMap<String, (HashSet<String>, Option<String>)>,
+    ) {
+        let frameworks = match manifest.ecosystem.as_str() {
+            "npm" => NPM_FRAMEWORKS,
+            "cargo" => CARGO_FRAMEWORKS,
+            "python" => PYTHON_FRAMEWORKS,
+            "go" => GO_FRAMEWORKS,
+            _ => return,
+        };
+        let is_go = manifest.ecosystem == "go";
+
+        for (framework, names) in frameworks {
+            for dependency in &manifest.dependencies {
+                let matches = names.iter().any(|name| {
+                    if is_go || name.ends_with(['/', '-', '_']) {
+                        dependency.name.starts_with(name)
+                    } else {
+                        dependency.name == *name
+                    }
+                });
+                if !matches {
+                    continue;
+                }
+
+                let entry = evidence_map.entry(framework.to_string()).or_insert_with(|| (HashSet::new(), None));
+                entry.0.insert(format!("{} dependency: {}", file_name, dependency.name));
+                if let Some(version) = dependency.version.as_ref().filter(|v| *v != "workspace") {
+                    entry.1.get_or_insert_with(|| version.clone());
+                }
+            }
+        }
+    }
+
     /// Parse package.json for JavaScript/TypeScript dependencies
     fn parse_package_json(content: &str, evidence_map: &mut HashMap<String, (HashSet<String>, Option<String>)>) {
         // Simple JSON parsing for common frameworks
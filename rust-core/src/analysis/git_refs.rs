@@ -0,0 +1,190 @@
+//! Files of a git revision, read without checking it out
+//!
+//! Trees and blobs are read from the object database through libgit2, so
+//! comparing two branches leaves the working tree, the index and any
+//! uncommitted changes alone.
+
+use crate::types::validation::{ErrorCode, ValidationError};
+use crate::types::ParseError;
+use git2::{ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Blobs larger than this are not read
+const MAX_BLOB_SIZE: usize = 1024 * 1024;
+
+/// One commit's files
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GitRevision {
+    pub commit: String,
+    /// Blob id of every file, by path relative to the repository directory the
+    /// revision was read from
+    pub files: BTreeMap<String, String>,
+}
+
+impl GitRevision {
+    /// Paths whose contents differ between two revisions, including files
+    /// present on only one side
+    pub fn changed_files<'a>(&'a self, other: &'a GitRevision) -> Vec<&'a str> {
+        let mut changed: Vec<&str> = self
+            .files
+            .keys()
+            .chain(other.files.keys())
+            .filter(|path| self.files.get(*path) != other.files.get(*path))
+            .map(String::as_str)
+            .collect();
+        changed.sort();
+        changed.dedup();
+        changed
+    }
+}
+
+pub struct GitRepository {
+    repository: Repository,
+    /// The directory the repository was opened at, relative to its working tree
+    prefix: PathBuf,
+}
+
+impl GitRepository {
+    /// The repository containing `dir`; paths are read relative to `dir`
+    pub fn open(dir: &Path) -> Result<Self, ParseError> {
+        let repository = Repository::discover(dir).map_err(|_| {
+            ParseError::from(ValidationError::new(
+                ErrorCode::InvalidArgument,
+                format!("{} is not inside a git repository", dir.display()),
+            ))
+        })?;
+        let prefix = match (repository.workdir().map(Path::canonicalize), dir.canonicalize()) {
+            (Some(Ok(workdir)), Ok(dir)) => dir.strip_prefix(&workdir).map(Path::to_path_buf).unwrap_or_default(),
+            _ => PathBuf::new(),
+        };
+        Ok(GitRepository { repository, prefix })
+    }
+
+    /// Resolve a branch, tag or commit and list its files
+    pub fn revision(&self, reference: &str) -> Result<GitRevision, ParseError> {
+        let reference = reference.trim();
+        if reference.is_empty() {
+            let message = format!("Invalid git ref '{}'", reference);
+            return Err(ValidationError::new(ErrorCode::InvalidArgument, message).into());
+        }
+        let commit = self
+            .repository
+            .revparse_single(reference)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| {
+                ParseError::from(ValidationError::new(
+                    ErrorCode::InvalidArgument,
+                    format!("Unknown git ref '{}'", reference),
+                ))
+            })?;
+
+        let mut tree = commit.tree().map_err(git_error)?;
+        if !self.prefix.as_os_str().is_empty() {
+            // A directory the revision doesn't have holds no files
+            let Ok(entry) = tree.get_path(&self.prefix) else {
+                return Ok(GitRevision { commit: commit.id().to_string(), files: BTreeMap::new() });
+            };
+            tree = entry.to_object(&self.repository).and_then(|object| object.peel_to_tree()).map_err(git_error)?;
+        }
+
+        // `parent` is the entry's directory with a trailing slash, or empty
+        let mut files = BTreeMap::new();
+        tree.walk(TreeWalkMode::PreOrder, |parent, entry| {
+            if let (Some(ObjectType::Blob), Some(name)) = (entry.kind(), entry.name()) {
+                files.insert(format!("{}{}", parent, name), entry.id().to_string());
+            }
+            TreeWalkResult::Ok
+        })
+        .map_err(git_error)?;
+        Ok(GitRevision { commit: commit.id().to_string(), files })
+    }
+
+    /// Contents of the given blobs by id; binary and oversized blobs are left out
+    pub fn read_blobs(&self, ids: &[&str]) -> Result<HashMap<String, String>, ParseError> {
+        let objects = self.repository.odb().map_err(git_error)?;
+        let mut blobs = HashMap::new();
+        for id in ids {
+            let Ok(oid) = Oid::from_str(id) else { continue };
+            // The header carries the size, so oversized blobs are never loaded
+            match objects.read_header(oid) {
+                Ok((size, ObjectType::Blob)) if size <= MAX_BLOB_SIZE => {}
+                _ => continue,
+            }
+            let blob = self.repository.find_blob(oid).map_err(git_error)?;
+            if let Ok(text) = std::str::from_utf8(blob.content()) {
+                blobs.insert(id.to_string(), text.to_string());
+            }
+        }
+        Ok(blobs)
+    }
+}
+
+fn git_error(e: git2::Error) -> ParseError {
+    ParseError::from_reason(format!("Failed to read git objects: {}", e.message()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> bool {
+        Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn test_reads_revisions_without_checkout() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        if !git(root, &["init", "-q", "-b", "main"]) {
+            return; // git not available
+        }
+        let commit = |message: &str| {
+            assert!(git(root, &["add", "-A"]));
+            let identity = ["-c", "user.name=Test", "-c", "user.email=test@example.com"];
+            assert!(git(root, &[&identity[..], &["commit", "-q", "-m", message]].concat()));
+        };
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/app.ts"), "export function start() {}\n").unwrap();
+        fs::write(root.join("README.md"), "# App\n").unwrap();
+        fs::write(root.join("src/table.ts"), "0,".repeat(MAX_BLOB_SIZE)).unwrap();
+        commit("base");
+        assert!(git(root, &["checkout", "-q", "-b", "feature"]));
+        fs::write(root.join("src/app.ts"), "export function start() {}\nexport function stop() {}\n").unwrap();
+        fs::write(root.join("src/extra.ts"), "export const extra = 1;\n").unwrap();
+        commit("feature");
+        assert!(git(root, &["checkout", "-q", "main"]));
+        // Uncommitted work on the checked-out branch is not read
+        fs::write(root.join("src/app.ts"), "dirty\n").unwrap();
+
+        let repository = GitRepository::open(root).unwrap();
+        let base = repository.revision("main").unwrap();
+        let head = repository.revision("feature").unwrap();
+        assert_eq!(base.files.keys().collect::<Vec<_>>(), vec!["README.md", "src/app.ts", "src/table.ts"]);
+        assert_eq!(base.changed_files(&head), vec!["src/app.ts", "src/extra.ts"]);
+
+        let ids: Vec<&str> = [&base.files["src/app.ts"], &head.files["src/app.ts"]].map(String::as_str).to_vec();
+        let blobs = repository.read_blobs(&ids).unwrap();
+        assert_eq!(blobs[ids[0]], "export function start() {}\n");
+        assert!(blobs[ids[1]].contains("stop"));
+        // Oversized blobs are left out
+        let table = base.files["src/table.ts"].as_str();
+        assert!(repository.read_blobs(&[table]).unwrap().is_empty());
+
+        // Paths are relative to the directory the repository was opened at
+        let nested = GitRepository::open(&root.join("src")).unwrap().revision("feature").unwrap();
+        assert_eq!(nested.files.keys().collect::<Vec<_>>(), vec!["app.ts", "extra.ts", "table.ts"]);
+
+        assert!(repository.revision("missing-branch").is_err());
+        assert!(repository.revision("--output=/tmp/x").is_err());
+    }
+}
@@ -0,0 +1,395 @@
+//! Persistent concept graph and multi-hop graph queries
+//!
+//! The relationship graph is rebuilt whenever concepts are learned and saved to
+//! `.in-memoria/concept-graph.json` as a node list and an edge list, so a
+//! process that didn't run the learning pass can still answer graph questions.
+//! On top of the per-concept edges of [`ConceptGraph`] it answers neighborhoods
+//! several hops out, the shortest path between two concepts, and the subgraph
+//! of a feature: everything within reach of the concepts whose name, file or
+//! tags mention it ("show me everything involved in checkout").
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::{ConceptGraph, EdgeDirection, RelationshipEdge, RelationshipKind, RelationshipLearner, TAGS_METADATA_KEY};
+use crate::types::validation::{ErrorCode, ValidationError};
+use crate::types::{ParseError, SemanticConcept, CONFIG_DIR};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// File holding the saved graph, inside the project's config directory
+pub const GRAPH_FILE: &str = "concept-graph.json";
+
+const GRAPH_VERSION: u32 = 1;
+
+/// Furthest a neighborhood or feature subgraph reaches, in edges
+pub const MAX_GRAPH_DEPTH: u32 = 5;
+
+/// Nodes one query returns before it is cut off
+const MAX_SLICE_NODES: usize = 200;
+
+/// A concept as the graph knows it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct GraphNode {
+    pub id: String,
+    pub name: String,
+    pub concept_type: String,
+    pub file_path: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Part of the graph: a set of concepts and the edges between them
+#[derive(Debug, Clone, Default, PartialEq, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct GraphSlice {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<RelationshipEdge>,
+    /// More concepts were in reach than a query returns
+    pub truncated: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedGraph {
+    version: u32,
+    nodes: Vec<GraphNode>,
+    edges: Vec<RelationshipEdge>,
+}
+
+/// Concepts and their typed relationships, queryable and saved with the project
+#[derive(Debug, Clone, Default)]
+pub struct GraphStore {
+    nodes: BTreeMap<String, GraphNode>,
+    graph: ConceptGraph,
+}
+
+impl GraphStore {
+    /// Graph of concepts and the relationships learned between them
+    pub fn build<'a>(concepts: impl IntoIterator<Item = &'a SemanticConcept>) -> Self {
+        let concepts: Vec<&SemanticConcept> = concepts.into_iter().collect();
+        GraphStore {
+            nodes: concepts
+                .iter()
+                .map(|concept| (concept.id.clone(), Self::to_node(concept)))
+                .collect(),
+            graph: RelationshipLearner::learn_concept_relationships(concepts.iter().copied()),
+        }
+    }
+
+    /// Replace the nodes and edges of the concepts of `files`, leaving the
+    /// rest of the graph as it was
+    pub fn relearn_files<'a>(&mut self, concepts: impl IntoIterator<Item = &'a SemanticConcept>, files: &HashSet<&str>) {
+        let concepts: Vec<&SemanticConcept> = concepts.into_iter().collect();
+        let existing: HashSet<&str> = concepts.iter().map(|concept| concept.id.as_str()).collect();
+        self.nodes
+            .retain(|id, node| existing.contains(id.as_str()) && !files.contains(node.file_path.as_str()));
+        for concept in concepts.iter().filter(|concept| files.contains(concept.file_path.as_str())) {
+            self.nodes.insert(concept.id.clone(), Self::to_node(concept));
+        }
+        RelationshipLearner::relearn_file_relationships(&mut self.graph, concepts, files);
+    }
+
+    /// Update the node of a concept already in the graph, e.g. after tagging
+    pub fn refresh_node(&mut self, concept: &SemanticConcept) {
+        if let Some(node) = self.nodes.get_mut(&concept.id) {
+            *node = Self::to_node(concept);
+        }
+    }
+
+    pub fn graph(&self) -> &ConceptGraph {
+        &self.graph
+    }
+
+    pub fn node(&self, concept_id: &str) -> Option<&GraphNode> {
+        self.nodes.get(concept_id)
+    }
+
+    /// Every node, by id
+    pub fn nodes(&self) -> impl Iterator<Item = &GraphNode> {
+        self.nodes.values()
+    }
+
+    /// Concepts up to `depth` edges away from a concept, following edges of
+    /// `kind` in `direction`, with the edges between them
+    pub fn neighbors(
+        &self,
+        concept_id: &str,
+        kind: Option<RelationshipKind>,
+        direction: EdgeDirection,
+        depth: u32,
+    ) -> GraphSlice {
+        self.expand(vec![concept_id], kind, direction, depth)
+    }
+
+    /// Fewest edges connecting two concepts, in either direction, ordered from
+    /// `from`; `None` when they aren't connected
+    pub fn shortest_path(&self, from: &str, to: &str) -> Option<Vec<RelationshipEdge>> {
+        if !self.nodes.contains_key(from) || !self.nodes.contains_key(to) {
+            return None;
+        }
+
+        let mut reached: HashMap<&str, Option<&RelationshipEdge>> = HashMap::from([(from, None)]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                break;
+            }
+            for edge in self.graph.edges_of(current, None, EdgeDirection::Both) {
+                let next = if edge.from == current { edge.to.as_str() } else { edge.from.as_str() };
+                if !reached.contains_key(next) {
+                    reached.insert(next, Some(edge));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut path = Vec::new();
+        let mut current = to;
+        while current != from {
+            let edge = (*reached.get(current)?)?;
+            current = if edge.to == current { edge.from.as_str() } else { edge.to.as_str() };
+            path.push(edge.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Concepts whose name, file path or tags mention `feature` (ignoring
+    /// case), everything up to `depth` edges from them, and the edges between
+    pub fn feature_subgraph(&self, feature: &str, depth: u32) -> GraphSlice {
+        let feature = feature.trim().to_lowercase();
+        if feature.is_empty() {
+            return GraphSlice::default();
+        }
+        let seeds = self
+            .nodes
+            .values()
+            .filter(|node| {
+                node.name.to_lowercase().contains(&feature)
+                    || node.file_path.to_lowercase().contains(&feature)
+                    || node.tags.contains(&feature)
+            })
+            .map(|node| node.id.as_str())
+            .collect();
+        self.expand(seeds, None, EdgeDirection::Both, depth)
+    }
+
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(CONFIG_DIR).join(GRAPH_FILE)
+    }
+
+    /// Read a project's saved graph; `None` when none was saved
+    pub fn load(project_root: &Path) -> Result<Option<Self>, ParseError> {
+        let path = Self::path(project_root);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            ParseError::from_reason(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let saved: SavedGraph = serde_json::from_str(&content)
+            .map_err(|e| ParseError::from_reason(format!("Invalid {}: {}", GRAPH_FILE, e)))?;
+        if saved.version > GRAPH_VERSION {
+            return Err(ValidationError::new(
+                ErrorCode::UnsupportedVersion,
+                format!(
+                    "{} was written by a newer release (version {}; this release reads up to {}). Upgrade In Memoria or delete the file to relearn.",
+                    GRAPH_FILE, saved.version, GRAPH_VERSION
+                ),
+            )
+            .into());
+        }
+
+        let mut graph = ConceptGraph::default();
+        for edge in &saved.edges {
+            if let Some(kind) = RelationshipKind::parse(&edge.kind) {
+                graph.add_edge(&edge.from, &edge.to, kind);
+            }
+        }
+        Ok(Some(GraphStore {
+            nodes: saved.nodes.into_iter().map(|node| (node.id.clone(), node)).collect(),
+            graph,
+        }))
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<(), ParseError> {
+        let saved = SavedGraph {
+            version: GRAPH_VERSION,
+            nodes: self.nodes.values().cloned().collect(),
+            edges: self.graph.all_edges().to_vec(),
+        };
+
+        let path = Self::path(project_root);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                ParseError::from_reason(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+        let content = serde_json::to_string(&saved)
+            .map_err(|e| ParseError::from_reason(format!("Failed to serialize concept graph: {}", e)))?;
+        std::fs::write(&path, content).map_err(|e| {
+            ParseError::from_reason(format!("Failed to write {}: {}", path.display(), e))
+        })
+    }
+
+    fn to_node(concept: &SemanticConcept) -> GraphNode {
+        GraphNode {
+            id: concept.id.clone(),
+            name: concept.name.clone(),
+            concept_type: concept.concept_type.clone(),
+            file_path: concept.file_path.clone(),
+            tags: concept
+                .metadata
+                .get(TAGS_METADATA_KEY)
+                .map(|tags| tags.split(',').map(str::to_string).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Breadth-first reach from `seeds`, nearest concepts first, and the edges
+    /// of `kind` between the concepts reached
+    fn expand(&self, seeds: Vec<&str>, kind: Option<RelationshipKind>, direction: EdgeDirection, depth: u32) -> GraphSlice {
+        let mut reached: Vec<&str> = Vec::new();
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut queue: VecDeque<(&str, u32)> = VecDeque::new();
+        for seed in seeds {
+            if self.nodes.contains_key(seed) && seen.insert(seed) {
+                queue.push_back((seed, 0));
+            }
+        }
+
+        let mut truncated = false;
+        while let Some((current, distance)) = queue.pop_front() {
+            if reached.len() == MAX_SLICE_NODES {
+                truncated = true;
+                break;
+            }
+            reached.push(current);
+            if distance == depth {
+                continue;
+            }
+            for edge in self.graph.edges_of(current, kind, direction) {
+                let next = if edge.from == current { edge.to.as_str() } else { edge.from.as_str() };
+                if self.nodes.contains_key(next) && seen.insert(next) {
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        let included: HashSet<&str> = reached.iter().copied().collect();
+        let edges = reached
+            .iter()
+            .flat_map(|id| self.graph.edges_of(id, kind, EdgeDirection::Outgoing))
+            .filter(|edge| included.contains(edge.to.as_str()))
+            .cloned()
+            .collect();
+        GraphSlice {
+            nodes: reached.iter().filter_map(|id| self.nodes.get(*id)).cloned().collect(),
+            edges,
+            truncated,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LineRange;
+
+    fn concept(id: &str, concept_type: &str, file_path: &str, relationships: &[(&str, &str)]) -> SemanticConcept {
+        SemanticConcept {
+            id: id.to_string(),
+            name: id.to_string(),
+            concept_type: concept_type.to_string(),
+            confidence: 0.9,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start: 1, end: 1 },
+            relationships: relationships.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// CheckoutController -> CheckoutService -> PaymentGateway, CartRepository;
+    /// ReportJob -> CartRepository; AuditLog stands alone
+    fn store() -> GraphStore {
+        let mut audit = concept("AuditLog", "class", "src/audit.ts", &[]);
+        audit.metadata.insert(TAGS_METADATA_KEY.to_string(), "checkout,compliance".to_string());
+        GraphStore::build(&[
+            concept("CheckoutController", "class", "src/web/checkout.ts", &[("uses", "CheckoutService")]),
+            concept("CheckoutService", "class", "src/checkout/service.ts", &[("uses", "PaymentGateway, CartRepository")]),
+            concept("PaymentGateway", "interface", "src/payments/gateway.ts", &[]),
+            concept("CartRepository", "class", "src/cart/repository.ts", &[]),
+            concept("ReportJob", "class", "src/jobs/report.ts", &[("uses", "CartRepository")]),
+            audit,
+        ])
+    }
+
+    fn ids(slice: &GraphSlice) -> Vec<&str> {
+        slice.nodes.iter().map(|node| node.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_neighbors_reach_as_many_hops_as_asked() {
+        let store = store();
+        let one = store.neighbors("CheckoutService", None, EdgeDirection::Both, 1);
+        assert_eq!(ids(&one), vec!["CheckoutService", "PaymentGateway", "CartRepository", "CheckoutController"]);
+        assert_eq!(one.edges.len(), 3);
+
+        let outgoing = store.neighbors("CheckoutController", Some(RelationshipKind::Uses), EdgeDirection::Outgoing, 2);
+        assert_eq!(outgoing.nodes.len(), 4);
+        assert!(!ids(&outgoing).contains(&"ReportJob"));
+        assert!(store.neighbors("CheckoutController", Some(RelationshipKind::Calls), EdgeDirection::Both, 3).edges.is_empty());
+    }
+
+    #[test]
+    fn test_shortest_path_follows_edges_either_way() {
+        let store = store();
+        let path = store.shortest_path("CheckoutController", "ReportJob").unwrap();
+        let hops: Vec<(&str, &str)> = path.iter().map(|e| (e.from.as_str(), e.to.as_str())).collect();
+        assert_eq!(hops, vec![
+            ("CheckoutController", "CheckoutService"),
+            ("CheckoutService", "CartRepository"),
+            ("ReportJob", "CartRepository"),
+        ]);
+        assert_eq!(store.shortest_path("ReportJob", "ReportJob"), Some(Vec::new()));
+        assert_eq!(store.shortest_path("ReportJob", "AuditLog"), None);
+        assert_eq!(store.shortest_path("ReportJob", "Missing"), None);
+    }
+
+    #[test]
+    fn test_feature_subgraph_starts_from_names_paths_and_tags() {
+        let store = store();
+        let checkout = store.feature_subgraph("Checkout", 1);
+        let mut found = ids(&checkout);
+        found.sort();
+        assert_eq!(found, vec!["AuditLog", "CartRepository", "CheckoutController", "CheckoutService", "PaymentGateway"]);
+        assert_eq!(checkout.edges.len(), 3);
+        assert!(!checkout.truncated);
+
+        assert_eq!(ids(&store.feature_subgraph("gateway", 0)), vec!["PaymentGateway"]);
+        assert!(store.feature_subgraph("  ", 2).nodes.is_empty());
+    }
+
+    #[test]
+    fn test_graph_is_saved_and_loaded_with_the_project() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(GraphStore::load(dir.path()).unwrap().is_none());
+
+        let store = store();
+        store.save(dir.path()).unwrap();
+        let loaded = GraphStore::load(dir.path()).unwrap().unwrap();
+        assert_eq!(loaded.graph().all_edges(), store.graph().all_edges());
+        assert_eq!(loaded.node("AuditLog").unwrap().tags, vec!["checkout".to_string(), "compliance".to_string()]);
+        assert_eq!(loaded.shortest_path("CheckoutController", "ReportJob").unwrap().len(), 3);
+
+        std::fs::write(GraphStore::path(dir.path()), r#"{"version": 99, "nodes": [], "edges": []}"#).unwrap();
+        let error = GraphStore::load(dir.path()).unwrap_err();
+        assert!(error.to_string().contains("[UNSUPPORTED_VERSION]"));
+    }
+}
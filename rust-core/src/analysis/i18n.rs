@@ -0,0 +1,454 @@
+//! Localization keys, their catalogs and where the code uses them
+//!
+//! Reads message catalogs (JSON/YAML under `locales/`, `i18n/` and similar
+//! directories, gettext `.po`/`.pot`, Fluent `.ftl`) and the calls that look
+//! keys up:
+//! - `t('key')`, `$t('key')`, `t!("key")`, `<Trans i18nKey="key">`, `formatMessage({ id: 'key' })`
+//! - gettext: `_("Message")`, `gettext(..)`, `ngettext(..)`, `gettext_lazy(..)`
+//! - Fluent: `getString('id')`, `fl!(loader, "id")`, `<Localized id="id">`, `data-l10n-id="id"`
+//!
+//! Keys used in code but in no catalog are missing; keys in a catalog that no
+//! code uses are unused. Dynamic keys (`t(name)`, `` t(`a.${b}`) ``) are not seen.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::ManifestParser;
+use crate::types::ParseError;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use walkdir::WalkDir;
+
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", ".venv", "target", "vendor"];
+
+/// Files larger than this are not read for keys
+const MAX_I18N_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Directories whose JSON and YAML files are message catalogs
+const CATALOG_DIRS: &[&str] = &["locales", "locale", "i18n", "lang", "langs", "translations", "messages", "l10n", "intl"];
+
+const SOURCE_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx", "mjs", "cjs", "vue", "svelte", "py", "rs", "html"];
+
+/// Suffixes of plural forms looked up through their base key: `items_one` by `t('items', { count })`
+const PLURAL_SUFFIXES: &[&str] = &["_zero", "_one", "_two", "_few", "_many", "_other", "_plural"];
+
+/// Dependencies that show which localization library a project uses
+const I18N_LIBRARIES: &[&str] = &[
+    "i18next", "react-i18next", "next-i18next", "react-intl", "vue-i18n", "next-intl", "svelte-i18n",
+    "fluent", "fluent-bundle", "fluent-templates", "i18n-embed", "rust-i18n", "babel", "flask-babel",
+];
+
+/// Package scopes of localization libraries
+const I18N_LIBRARY_SCOPES: &[&str] = &["@formatjs/", "@lingui/", "@fluent/"];
+
+/// One file of translations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct MessageCatalog {
+    pub file_path: String,
+    /// 'json', 'yaml', 'po', 'pot' or 'ftl'
+    pub format: String,
+    /// Locale from the file or directory name, e.g. 'en' or 'pt-BR'; none for `.pot` templates
+    pub locale: Option<String>,
+    /// File stem of catalogs split per locale directory, e.g. 'common' for `locales/en/common.json`
+    pub namespace: Option<String>,
+    pub key_count: u32,
+}
+
+/// A place the code looks a key up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct KeyUsage {
+    pub file_path: String,
+    pub line: u32,
+    /// 't', 'Trans', 'formatMessage', 'gettext' or 'fluent'
+    pub function: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct I18nKey {
+    pub key: String,
+    /// Locales whose catalogs define the key
+    pub locales: Vec<String>,
+    /// Locales with catalogs for the key's namespace that lack it
+    pub missing_locales: Vec<String>,
+    pub usages: Vec<KeyUsage>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct I18nSummary {
+    /// Localization libraries among the project's dependencies
+    pub libraries: Vec<String>,
+    pub locales: Vec<String>,
+    pub catalogs: Vec<MessageCatalog>,
+    /// Every key defined or used, sorted
+    pub keys: Vec<I18nKey>,
+    /// Keys the code uses that no catalog defines
+    pub missing_keys: Vec<String>,
+    /// Keys catalogs define that no code uses
+    pub unused_keys: Vec<String>,
+}
+
+pub struct I18nScanner;
+
+impl I18nScanner {
+    /// Map the message catalogs and key lookups under `root`
+    pub fn scan(root: &Path) -> Result<I18nSummary, ParseError> {
+        let mut catalogs = Vec::new();
+        let mut usages = Vec::new();
+
+        for entry in WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if !entry.file_type().is_file() || entry.metadata().map(|m| m.len() > MAX_I18N_FILE_SIZE).unwrap_or(true) {
+                continue;
+            }
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            let is_catalog = matches!(extension, "json" | "yaml" | "yml" | "po" | "pot" | "ftl");
+            if !is_catalog && !SOURCE_EXTENSIONS.contains(&extension) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(path) else { continue };
+
+            let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+            if is_catalog {
+                catalogs.extend(Self::parse_catalog(&relative, &content));
+            } else {
+                usages.extend(Self::extract_usages(&relative, &content));
+            }
+        }
+
+        Ok(Self::summarize(Self::libraries(root), catalogs, usages))
+    }
+
+    /// The catalog in a file and its keys; `None` for JSON and YAML files
+    /// that aren't tied to a locale
+    pub fn parse_catalog(file_path: &str, content: &str) -> Option<(MessageCatalog, Vec<String>)> {
+        let p = patterns();
+        let file_name = file_path.rsplit('/').next().unwrap_or(file_path);
+        let (stem, extension) = file_name.rsplit_once('.')?;
+        let directories: Vec<&str> = file_path.split('/').rev().skip(1).collect();
+        let directory_locale = directories.iter().find(|d| p.locale.is_match(d)).map(|d| d.to_string());
+        let mut locale = if p.locale.is_match(stem) { Some(stem.to_string()) } else { directory_locale };
+        let mut namespace = None;
+
+        let keys = match extension {
+            "json" | "yaml" | "yml" => {
+                let in_catalog_dir = directories.iter().any(|d| CATALOG_DIRS.contains(d));
+                if !in_catalog_dir && !p.locale.is_match(stem) {
+                    return None;
+                }
+                let mut document: JsonValue = if extension == "json" {
+                    serde_json::from_str(content).ok()?
+                } else {
+                    serde_yaml::from_str::<serde_yaml::Value>(content)
+                        .ok()
+                        .and_then(|yaml| serde_json::to_value(yaml).ok())?
+                };
+                // Rails-style catalogs nest everything under the locale: `en: { .. }`
+                let nested_locale = document
+                    .as_object()
+                    .filter(|object| object.len() == 1)
+                    .and_then(|object| object.keys().next())
+                    .filter(|key| p.locale.is_match(key))
+                    .cloned();
+                if let Some(nested) = nested_locale {
+                    document = document[&nested].take();
+                    locale = Some(nested);
+                }
+                locale.as_ref()?;
+                if !p.locale.is_match(stem) {
+                    namespace = Some(stem.to_string());
+                }
+                let mut keys = Vec::new();
+                Self::flatten_keys(&document, "", &mut keys);
+                keys
+            }
+            "po" | "pot" => content
+                .lines()
+                .filter_map(|line| p.po_msgid.captures(line))
+                .map(|c| c[1].to_string())
+                // The empty msgid holds the catalog header
+                .filter(|msgid| !msgid.is_empty())
+                .collect(),
+            "ftl" => content
+                .lines()
+                .filter_map(|line| p.ftl_message.captures(line))
+                .map(|c| c[1].to_string())
+                .collect(),
+            _ => return None,
+        };
+        if extension == "pot" {
+            locale = None;
+        }
+
+        let format = if extension == "yml" { "yaml" } else { extension };
+        let catalog = MessageCatalog {
+            file_path: file_path.to_string(),
+            format: format.to_string(),
+            locale,
+            namespace,
+            key_count: keys.len() as u32,
+        };
+        Some((catalog, keys))
+    }
+
+    /// Keys looked up with literal arguments in one source file
+    pub fn extract_usages(file_path: &str, content: &str) -> Vec<(String, KeyUsage)> {
+        let mut usages = Vec::new();
+        for (idx, line) in content.lines().enumerate() {
+            for (pattern, function) in &patterns().usages {
+                for c in pattern.captures_iter(line) {
+                    usages.push((
+                        first_group(&c),
+                        KeyUsage { file_path: file_path.to_string(), line: idx as u32 + 1, function: function.to_string() },
+                    ));
+                }
+            }
+        }
+        usages
+    }
+
+    /// Leaf keys of a nested catalog, joined with '.'
+    fn flatten_keys(value: &JsonValue, prefix: &str, keys: &mut Vec<String>) {
+        match value.as_object() {
+            Some(object) => {
+                for (key, child) in object {
+                    let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                    Self::flatten_keys(child, &path, keys);
+                }
+            }
+            None if !prefix.is_empty() => keys.push(prefix.to_string()),
+            None => {}
+        }
+    }
+
+    fn libraries(root: &Path) -> Vec<String> {
+        let Ok(summary) = ManifestParser::analyze(root) else {
+            return Vec::new();
+        };
+        let libraries: BTreeSet<String> = summary
+            .manifests
+            .iter()
+            .flat_map(|manifest| &manifest.dependencies)
+            .map(|dependency| dependency.name.as_str())
+            .filter(|name| I18N_LIBRARIES.contains(name) || I18N_LIBRARY_SCOPES.iter().any(|scope| name.starts_with(scope)))
+            .map(str::to_string)
+            .collect();
+        libraries.into_iter().collect()
+    }
+
+    /// The key a plural form is looked up by
+    fn plural_base(key: &str) -> Option<&str> {
+        PLURAL_SUFFIXES.iter().find_map(|suffix| key.strip_suffix(suffix))
+    }
+
+    fn summarize(
+        libraries: Vec<String>,
+        catalogs: Vec<(MessageCatalog, Vec<String>)>,
+        usages: Vec<(String, KeyUsage)>,
+    ) -> I18nSummary {
+        // Key to the locales and namespaces defining it
+        let mut defined: BTreeMap<String, (BTreeSet<String>, BTreeSet<Option<String>>)> = BTreeMap::new();
+        let mut namespace_locales: HashMap<Option<String>, BTreeSet<String>> = HashMap::new();
+        for (catalog, keys) in &catalogs {
+            if let Some(locale) = &catalog.locale {
+                namespace_locales.entry(catalog.namespace.clone()).or_default().insert(locale.clone());
+            }
+            for key in keys {
+                let (locales, namespaces) = defined.entry(key.clone()).or_default();
+                locales.extend(catalog.locale.clone());
+                namespaces.insert(catalog.namespace.clone());
+            }
+        }
+        let defined_bases: HashSet<&str> = defined.keys().filter_map(|key| Self::plural_base(key)).collect();
+        let is_defined = |key: &str| defined.contains_key(key) || defined_bases.contains(key);
+
+        // i18next addresses other namespaces as `namespace:key`
+        let namespaces: HashSet<&str> = catalogs.iter().filter_map(|(catalog, _)| catalog.namespace.as_deref()).collect();
+        let mut used: BTreeMap<String, Vec<KeyUsage>> = BTreeMap::new();
+        for (key, usage) in usages {
+            let key = match key.split_once(':') {
+                Some((namespace, rest)) if namespaces.contains(namespace) && !is_defined(&key) => rest.to_string(),
+                _ => key,
+            };
+            used.entry(key).or_default().push(usage);
+        }
+        let is_used = |key: &str| used.contains_key(key) || Self::plural_base(key).is_some_and(|base| used.contains_key(base));
+
+        let names: BTreeSet<&String> = defined.keys().chain(used.keys()).collect();
+        let keys: Vec<I18nKey> = names
+            .into_iter()
+            .map(|key| {
+                let (locales, namespaces) = defined.get(key).cloned().unwrap_or_default();
+                let expected: BTreeSet<&String> = namespaces
+                    .iter()
+                    .filter_map(|namespace| namespace_locales.get(namespace))
+                    .flatten()
+                    .collect();
+                I18nKey {
+                    key: key.clone(),
+                    missing_locales: expected.into_iter().filter(|l| !locales.contains(*l)).cloned().collect(),
+                    locales: locales.into_iter().collect(),
+                    usages: used.get(key).cloned().unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        // Without catalogs there is nothing a key could be missing from
+        let missing_keys = if catalogs.is_empty() {
+            Vec::new()
+        } else {
+            used.keys().filter(|key| !is_defined(key)).cloned().collect()
+        };
+        let unused_keys = defined.keys().filter(|key| !is_used(key)).cloned().collect();
+        let locales: BTreeSet<String> = namespace_locales.into_values().flatten().collect();
+
+        I18nSummary {
+            libraries,
+            locales: locales.into_iter().collect(),
+            catalogs: catalogs.into_iter().map(|(catalog, _)| catalog).collect(),
+            keys,
+            missing_keys,
+            unused_keys,
+        }
+    }
+}
+
+/// The first alternative of a pattern that matched
+fn first_group(c: &Captures) -> String {
+    c.iter().skip(1).flatten().next().map(|m| m.as_str().to_string()).unwrap_or_default()
+}
+
+struct Patterns {
+    locale: Regex,
+    po_msgid: Regex,
+    ftl_message: Regex,
+    usages: Vec<(Regex, &'static str)>,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let re = |pattern: &str| Regex::new(pattern).expect("valid i18n regex");
+        Patterns {
+            // 'en', 'pt-BR', 'zh_Hans', 'es-419'
+            locale: re(r"^[a-z]{2}(?:[-_](?:[A-Z]{2}|[A-Z][a-z]{3}|\d{3}))?$"),
+            po_msgid: re(r#"^msgid\s+"((?:[^"\\]|\\.)*)""#),
+            // Messages, not `-terms` or attributes
+            ftl_message: re(r"^([A-Za-z][\w-]*)\s*="),
+            usages: vec![
+                (re(r#"(?:\$t|\bt!?)\(\s*(?:'([^'\n]+)'|"([^"\n]+)"|`([^`$\n]+)`)"#), "t"),
+                (re(r#"\bi18nKey=\{?\s*["']([^"']+)["']"#), "Trans"),
+                (re(r#"\bformatMessage\(\s*\{\s*id:\s*["']([^"']+)["']|<FormattedMessage\b[^>]*?\bid=["']([^"']+)["']"#), "formatMessage"),
+                (re(r#"\b(?:_|gettext|ngettext|gettext_lazy|lazy_gettext|ugettext)\(\s*(?:'([^'\n]+)'|"([^"\n]+)")"#), "gettext"),
+                (
+                    re(r#"(?:\bgetString|\bget_message|\bformatValue|\bfl!)\(\s*(?:[\w.]+\s*,\s*)?["']([\w-]+)["']|<Localized\s+id=["']([\w-]+)["']|data-l10n-id=["']([\w-]+)["']"#),
+                    "fluent",
+                ),
+            ],
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usages_and_catalog_formats() {
+        let source = "\
+const title = t('nav.home');
+return <Trans i18nKey=\"welcome\" />;
+intl.formatMessage({ id: 'cart.total' });
+label = _(\"Save changes\")
+document.l10n.formatValue('confirm-dialog');
+const dynamic = t(`errors.${code}`);
+const formatted = format('not a key');
+";
+        let usages: Vec<(String, u32, String)> = I18nScanner::extract_usages("src/app.tsx", source)
+            .into_iter()
+            .map(|(key, usage)| (key, usage.line, usage.function))
+            .collect();
+        assert_eq!(
+            usages,
+            vec![
+                ("nav.home".to_string(), 1, "t".to_string()),
+                ("welcome".to_string(), 2, "Trans".to_string()),
+                ("cart.total".to_string(), 3, "formatMessage".to_string()),
+                ("Save changes".to_string(), 4, "gettext".to_string()),
+                ("confirm-dialog".to_string(), 5, "fluent".to_string()),
+            ]
+        );
+
+        let (catalog, keys) = I18nScanner::parse_catalog(
+            "config/locales/fr.yml",
+            "fr:\n  nav:\n    home: Accueil\n  welcome: Bienvenue\n",
+        )
+        .unwrap();
+        assert_eq!((catalog.format.as_str(), catalog.locale.as_deref()), ("yaml", Some("fr")));
+        assert_eq!(keys, vec!["nav.home".to_string(), "welcome".to_string()]);
+
+        let po = "msgid \"\"\nmsgstr \"Content-Type: text/plain\"\n\nmsgid \"Save changes\"\nmsgstr \"Enregistrer\"\n";
+        let (catalog, keys) = I18nScanner::parse_catalog("locale/fr/LC_MESSAGES/django.po", po).unwrap();
+        assert_eq!((catalog.locale.as_deref(), keys), (Some("fr"), vec!["Save changes".to_string()]));
+
+        let ftl = "confirm-dialog = Are you sure?\n    .title = Confirm\n-brand = Acme\n";
+        let (catalog, keys) = I18nScanner::parse_catalog("l10n/en-US/main.ftl", ftl).unwrap();
+        assert_eq!((catalog.locale.as_deref(), keys), (Some("en-US"), vec!["confirm-dialog".to_string()]));
+
+        assert!(I18nScanner::parse_catalog("src/config/settings.json", "{\"a\": 1}").is_none());
+    }
+
+    #[test]
+    fn test_scan_maps_keys_to_usages_and_flags_gaps() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("public/locales/en")).unwrap();
+        fs::create_dir_all(root.join("public/locales/de")).unwrap();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("package.json"), r#"{"dependencies": {"react-i18next": "^14.0.0", "i18next": "^23.0.0"}}"#).unwrap();
+        fs::write(
+            root.join("public/locales/en/common.json"),
+            r#"{"title": "Shop", "items_one": "{{count}} item", "items_other": "{{count}} items", "legacy": "Old"}"#,
+        )
+        .unwrap();
+        fs::write(root.join("public/locales/de/common.json"), r#"{"title": "Laden", "items_one": "{{count}} Artikel"}"#).unwrap();
+        fs::write(
+            root.join("src/Header.tsx"),
+            "const { t } = useTranslation();\nt('common:title');\nt('items', { count });\nt('checkout.cta');\n",
+        )
+        .unwrap();
+
+        let summary = I18nScanner::scan(root).unwrap();
+        assert_eq!(summary.libraries, vec!["i18next".to_string(), "react-i18next".to_string()]);
+        assert_eq!(summary.locales, vec!["de".to_string(), "en".to_string()]);
+        assert_eq!(summary.catalogs.len(), 2);
+        assert_eq!(summary.catalogs[0].namespace.as_deref(), Some("common"));
+        assert_eq!(summary.missing_keys, vec!["checkout.cta".to_string()]);
+        assert_eq!(summary.unused_keys, vec!["legacy".to_string()]);
+
+        let title = summary.keys.iter().find(|k| k.key == "title").unwrap();
+        assert_eq!(title.locales, vec!["de".to_string(), "en".to_string()]);
+        assert_eq!((title.usages[0].file_path.as_str(), title.usages[0].line), ("src/Header.tsx", 2));
+
+        let items_other = summary.keys.iter().find(|k| k.key == "items_other").unwrap();
+        assert_eq!(items_other.missing_locales, vec!["de".to_string()]);
+    }
+}
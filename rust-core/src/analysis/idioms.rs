@@ -0,0 +1,448 @@
+//! Per-language idiom scoring
+//!
+//! Counts markers of idiomatic and unidiomatic style in each language and
+//! turns them into a 0-1 score per language, so agents get a sense of how a
+//! team writes code:
+//! - Rust: `?` and `Result` returns and iterator chains against index loops
+//!   and `unwrap()`
+//! - Python: comprehensions and type hints against `range(len(..))` loops and
+//!   unannotated functions
+//! - TypeScript: `unknown`, `readonly` and a strict `tsconfig.json` against
+//!   `any`, `@ts-ignore` and non-null assertions
+//!
+//! Test files and Rust `#[cfg(test)]` modules are left out.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::types::{paths, ParseError};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use walkdir::WalkDir;
+
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", ".venv", "target", "vendor"];
+
+/// Files larger than this are not scanned
+const MAX_IDIOM_FILE_SIZE: u64 = 512 * 1024;
+
+/// How often one idiom, or one deviation from it, appears
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct IdiomMarker {
+    /// e.g. 'iterator_chain', 'index_loop', 'any_type'
+    pub name: String,
+    pub description: String,
+    /// False for deviations from the language's idioms
+    pub idiomatic: bool,
+    pub count: u32,
+}
+
+/// Idiom score of one language
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct LanguageIdiomScore {
+    /// 'rust', 'python' or 'typescript'
+    pub language: String,
+    pub files: u32,
+    /// Share of idiomatic markers among all markers found, from 0 to 1
+    pub score: f64,
+    pub markers: Vec<IdiomMarker>,
+    /// One line per kind of deviation found
+    pub deviations: Vec<String>,
+}
+
+/// Idiom section of the project blueprint
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct IdiomSummary {
+    pub languages: Vec<LanguageIdiomScore>,
+    /// Advice for the most frequent deviations first
+    pub recommendations: Vec<String>,
+}
+
+/// A marker counted on every line it matches, unless `unless` matches too
+///
+/// Rules without a pattern are project-wide markers counted by `scan`.
+struct Rule {
+    name: &'static str,
+    idiomatic: bool,
+    description: &'static str,
+    /// Advice given when a deviation is found
+    advice: &'static str,
+    pattern: Option<Regex>,
+    unless: Option<Regex>,
+}
+
+/// Marker counts and file count of one language
+#[derive(Default)]
+struct Tally {
+    files: u32,
+    counts: BTreeMap<&'static str, u32>,
+}
+
+pub struct IdiomScorer;
+
+impl IdiomScorer {
+    /// Score every Rust, Python and TypeScript file under `root`
+    pub fn scan(root: &Path) -> Result<IdiomSummary, ParseError> {
+        let mut tallies: BTreeMap<&'static str, Tally> = BTreeMap::new();
+
+        for entry in WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if !entry.file_type().is_file() || entry.metadata().map(|m| m.len() > MAX_IDIOM_FILE_SIZE).unwrap_or(true) {
+                continue;
+            }
+            let relative = paths::relative_to(&path.to_string_lossy(), root);
+            let Some(language) = Self::language(&relative) else { continue };
+            let Ok(content) = fs::read_to_string(path) else { continue };
+
+            let tally = tallies.entry(language).or_default();
+            tally.files += 1;
+            for (name, count) in Self::count_markers(language, &content) {
+                *tally.counts.entry(name).or_default() += count;
+            }
+        }
+
+        // Strict compiler settings are a project-wide TypeScript idiom
+        if let Some(tally) = tallies.get_mut("typescript") {
+            if let Ok(tsconfig) = fs::read_to_string(root.join("tsconfig.json")) {
+                let name = if patterns().ts_strict.is_match(&tsconfig) { "strict_config" } else { "non_strict_config" };
+                *tally.counts.entry(name).or_default() += 1;
+            }
+        }
+
+        Ok(Self::summarize(tallies))
+    }
+
+    /// Language scored for a file, or None for other files and tests
+    pub fn language(file_path: &str) -> Option<&'static str> {
+        let file_name = file_path.rsplit('/').next().unwrap_or(file_path);
+        let is_test = file_path.starts_with("tests/")
+            || file_path.contains("/tests/")
+            || file_name.starts_with("test_")
+            || file_name.contains(".test.")
+            || file_name.contains(".spec.")
+            || file_name.ends_with("_test.py");
+        if is_test || file_name.ends_with(".d.ts") {
+            return None;
+        }
+        match file_name.rsplit('.').next()? {
+            "rs" => Some("rust"),
+            "py" => Some("python"),
+            "ts" | "tsx" => Some("typescript"),
+            _ => None,
+        }
+    }
+
+    /// Number of lines matching each marker of `language` in one file
+    pub fn count_markers(language: &str, content: &str) -> BTreeMap<&'static str, u32> {
+        let mut counts = BTreeMap::new();
+        let comment = if language == "python" { "#" } else { "//" };
+
+        for line in content.lines() {
+            let trimmed = line.trim_start();
+            // Unit tests sit at the end of Rust files
+            if language == "rust" && trimmed.starts_with("#[cfg(test)]") {
+                break;
+            }
+            // `@ts-ignore` lives in comments, everything else is code
+            if trimmed.starts_with(comment) && !trimmed.contains("@ts-") {
+                continue;
+            }
+            for rule in rules(language) {
+                let Some(pattern) = &rule.pattern else { continue };
+                if pattern.is_match(line) && !rule.unless.as_ref().is_some_and(|u| u.is_match(line)) {
+                    *counts.entry(rule.name).or_default() += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    fn summarize(tallies: BTreeMap<&'static str, Tally>) -> IdiomSummary {
+        let mut summary = IdiomSummary::default();
+        // (count, advice) for every deviation found, across languages
+        let mut advice: Vec<(u32, String)> = Vec::new();
+
+        for (language, tally) in tallies {
+            let mut markers = Vec::new();
+            let mut deviations = Vec::new();
+            let (mut idiomatic, mut total) = (0u32, 0u32);
+
+            for rule in rules(language) {
+                let count = tally.counts.get(rule.name).copied().unwrap_or(0);
+                if count == 0 {
+                    continue;
+                }
+                total += count;
+                if rule.idiomatic {
+                    idiomatic += count;
+                } else {
+                    deviations.push(format!("{} ({} occurrences)", rule.description, count));
+                    advice.push((count, format!("{}: {} ({} occurrences)", language, rule.advice, count)));
+                }
+                markers.push(IdiomMarker {
+                    name: rule.name.to_string(),
+                    description: rule.description.to_string(),
+                    idiomatic: rule.idiomatic,
+                    count,
+                });
+            }
+            if total == 0 {
+                continue;
+            }
+
+            summary.languages.push(LanguageIdiomScore {
+                language: language.to_string(),
+                files: tally.files,
+                score: (idiomatic as f64 / total as f64 * 100.0).round() / 100.0,
+                markers,
+                deviations,
+            });
+        }
+
+        advice.sort_by_key(|a| std::cmp::Reverse(a.0));
+        summary.recommendations = advice.into_iter().map(|(_, text)| text).collect();
+        summary
+    }
+}
+
+fn rules(language: &str) -> &'static [Rule] {
+    static RUST: OnceLock<Vec<Rule>> = OnceLock::new();
+    static PYTHON: OnceLock<Vec<Rule>> = OnceLock::new();
+    static TYPESCRIPT: OnceLock<Vec<Rule>> = OnceLock::new();
+
+    let compile = |pattern: &str| Regex::new(pattern).expect("valid idiom regex");
+    let rule = |name, idiomatic, description, advice, pattern: &str, unless: Option<&str>| Rule {
+        name,
+        idiomatic,
+        description,
+        advice,
+        pattern: Some(compile(pattern)),
+        unless: unless.map(compile),
+    };
+    let project_rule = |name, idiomatic, description, advice| Rule {
+        name,
+        idiomatic,
+        description,
+        advice,
+        pattern: None,
+        unless: None,
+    };
+
+    match language {
+        "rust" => RUST.get_or_init(|| {
+            vec![
+                rule("question_mark", true, "errors propagated with ?", "", r"\)\?(?:[;.),]|\s*$)", None),
+                rule("result_return", true, "functions returning Result or Option", "", r"->\s*(?:[\w:]*Result|Option)<", None),
+                rule(
+                    "iterator_chain",
+                    true,
+                    "iterator adapter chains",
+                    "",
+                    r"\.(?:map|filter|filter_map|flat_map|fold|any|all|find|find_map|take_while|zip|enumerate|sum)\(",
+                    None,
+                ),
+                rule(
+                    "index_loop",
+                    false,
+                    "index-based loops over collections",
+                    "iterate with .iter()/.enumerate() instead of indexing in `for i in 0..v.len()` loops",
+                    r"\bfor\s+\w+\s+in\s+0\s*\.\.=?\s*[\w.]+\.len\(\)",
+                    None,
+                ),
+                rule(
+                    "unwrap",
+                    false,
+                    "unwrap() outside tests",
+                    "propagate errors with ? or handle them instead of calling unwrap()",
+                    r"\.unwrap\(\)",
+                    None,
+                ),
+            ]
+        }),
+        "python" => PYTHON.get_or_init(|| {
+            let typed_def = r"^\s*(?:async\s+)?def\s+\w+\s*\(.*(?:\w\s*:\s*[\w\[.'\x22]|\)\s*->)";
+            vec![
+                rule(
+                    "comprehension",
+                    true,
+                    "list, set, dict and generator comprehensions",
+                    "",
+                    r"[\[{(][^\[\]{}()]*?\S\s+for\s+[\w, ]+\s+in\s",
+                    None,
+                ),
+                rule("type_hints", true, "functions with type hints", "", typed_def, None),
+                rule(
+                    "untyped_def",
+                    false,
+                    "functions without type hints",
+                    "add parameter and return type hints to functions",
+                    r"^\s*(?:async\s+)?def\s+\w+\s*\(",
+                    Some(typed_def),
+                ),
+                rule(
+                    "index_loop",
+                    false,
+                    "range(len(..)) loops",
+                    "iterate directly or with enumerate() instead of `for i in range(len(items))`",
+                    r"\bfor\s+\w+\s+in\s+range\(\s*len\(",
+                    None,
+                ),
+            ]
+        }),
+        "typescript" => TYPESCRIPT.get_or_init(|| {
+            vec![
+                project_rule("strict_config", true, "strict mode in tsconfig.json", ""),
+                rule("unknown_type", true, "unknown instead of any", "", r":\s*unknown\b|\bas\s+unknown\b", None),
+                rule("readonly", true, "readonly members and as const", "", r"\breadonly\b|\bas\s+const\b", None),
+                project_rule(
+                    "non_strict_config",
+                    false,
+                    "tsconfig.json without strict mode",
+                    "enable \"strict\": true in tsconfig.json",
+                ),
+                rule(
+                    "any_type",
+                    false,
+                    "explicit any types",
+                    "replace any with unknown or a precise type",
+                    r":\s*any\b|\bas\s+any\b|<any>|\bany\[\]",
+                    None,
+                ),
+                rule(
+                    "ts_suppression",
+                    false,
+                    "@ts-ignore and @ts-nocheck comments",
+                    "fix the type errors behind @ts-ignore/@ts-nocheck comments",
+                    r"@ts-(?:ignore|nocheck)\b",
+                    None,
+                ),
+                rule(
+                    "non_null_assertion",
+                    false,
+                    "non-null assertions",
+                    "narrow nullable values instead of asserting them with !",
+                    r"[\w\])]!(?:\.|\)|;|,)",
+                    None,
+                ),
+            ]
+        }),
+        _ => &[],
+    }
+}
+
+struct Patterns {
+    ts_strict: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        ts_strict: Regex::new(r#""strict"\s*:\s*true"#).expect("valid idiom regex"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(counts: &BTreeMap<&'static str, u32>, name: &str) -> u32 {
+        counts.get(name).copied().unwrap_or(0)
+    }
+
+    #[test]
+    fn test_rust_markers_skip_test_module() {
+        let source = "\
+fn load(path: &Path) -> Result<Vec<String>, Error> {
+    let content = fs::read_to_string(path)?;
+    let names = content.lines().filter(|l| !l.is_empty()).map(String::from).collect();
+    for i in 0..items.len() {
+        println!(\"{}\", items[i]);
+    }
+    let first = names.first().unwrap();
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    fn check() { load(path).unwrap(); }
+}
+";
+        let counts = IdiomScorer::count_markers("rust", source);
+        assert_eq!(count(&counts, "question_mark"), 1);
+        assert_eq!(count(&counts, "result_return"), 1);
+        assert_eq!(count(&counts, "iterator_chain"), 1);
+        assert_eq!(count(&counts, "index_loop"), 1);
+        assert_eq!(count(&counts, "unwrap"), 1);
+    }
+
+    #[test]
+    fn test_python_and_typescript_markers() {
+        let python = "\
+def total(values: list[int]) -> int:
+    return sum(v * 2 for v in values)
+
+def legacy(values):
+    # for i in range(len(values)) is fine in comments
+    for i in range(len(values)):
+        print(values[i])
+    return [v for v in values if v]
+";
+        let counts = IdiomScorer::count_markers("python", python);
+        assert_eq!(count(&counts, "type_hints"), 1);
+        assert_eq!(count(&counts, "untyped_def"), 1);
+        assert_eq!(count(&counts, "comprehension"), 2);
+        assert_eq!(count(&counts, "index_loop"), 1);
+
+        let typescript = "\
+// @ts-ignore
+const data: any = load();
+const parsed = JSON.parse(raw) as unknown;
+const config = { mode: 'dev' } as const;
+const name = user!.name;
+if (value !== undefined && !flag) {}
+";
+        let counts = IdiomScorer::count_markers("typescript", typescript);
+        assert_eq!(count(&counts, "ts_suppression"), 1);
+        assert_eq!(count(&counts, "any_type"), 1);
+        assert_eq!(count(&counts, "unknown_type"), 1);
+        assert_eq!(count(&counts, "readonly"), 1);
+        assert_eq!(count(&counts, "non_null_assertion"), 1);
+    }
+
+    #[test]
+    fn test_scan_scores_languages_and_recommends() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(dir.path().join("tsconfig.json"), "{\n  // comments are allowed\n  \"compilerOptions\": { \"strict\": true }\n}\n").unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/app.ts"), "const a: unknown = 1;\nconst b: any = 2;\nconst c: any = 3;\n").unwrap();
+        fs::write(dir.path().join("src/app.test.ts"), "const d: any = 4;\n").unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "fn run() -> Result<(), Error> {\n    step()?;\n    Ok(())\n}\n").unwrap();
+
+        let summary = IdiomScorer::scan(dir.path()).unwrap();
+        let languages: Vec<&str> = summary.languages.iter().map(|l| l.language.as_str()).collect();
+        assert_eq!(languages, vec!["rust", "typescript"]);
+
+        let rust = &summary.languages[0];
+        assert_eq!(rust.score, 1.0);
+        assert!(rust.deviations.is_empty());
+
+        let typescript = &summary.languages[1];
+        assert_eq!(typescript.files, 1);
+        assert_eq!(typescript.score, 0.5);
+        assert_eq!(typescript.deviations, vec!["explicit any types (2 occurrences)".to_string()]);
+        assert_eq!(
+            summary.recommendations,
+            vec!["typescript: replace any with unknown or a precise type (2 occurrences)".to_string()]
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! Per-file timing and skip tracking for codebase analysis
+//!
+//! Records how long each file took to parse and extract, which files hit the
+//! per-file timeout or fell back to regex extraction, and why files were
+//! skipped, so a run that "looks hung" can be diagnosed afterwards.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::SamplingReport;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Skipped files listed individually; beyond this only the per-reason counts grow
+const MAX_SKIPPED_DETAILS: usize = 200;
+
+/// Default number of files in the slowest-files list
+const DEFAULT_SLOWEST_LIMIT: usize = 20;
+
+/// Timing for one analyzed file
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct FileTiming {
+    pub file_path: String,
+    pub language: String,
+    pub duration_ms: f64,
+    pub concept_count: u32,
+    pub used_fallback: bool,
+    pub timed_out: bool,
+}
+
+/// A file that was not analyzed and why
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct SkippedFile {
+    pub file_path: String,
+    pub reason: String,
+    /// Evidence for generated, minified, vendored and binary files
+    pub detail: Option<String>,
+}
+
+/// Diagnostics for the last codebase analysis
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct AnalysisReport {
+    pub files_analyzed: u32,
+    pub total_duration_ms: f64,
+    /// Slowest files first
+    pub slowest_files: Vec<FileTiming>,
+    /// Files that hit the per-file parse timeout
+    pub timeouts: Vec<String>,
+    /// Files analyzed with regex fallback instead of tree-sitter
+    pub fallback_files: Vec<String>,
+    /// Whether the whole analysis was cut short by the overall timeout
+    pub analysis_timed_out: bool,
+    /// The first skipped files with their reasons
+    pub skipped_files: Vec<SkippedFile>,
+    pub skipped_by_reason: BTreeMap<String, u32>,
+    pub sampling: Option<SamplingReport>,
+}
+
+/// Collects timings and skips while a codebase is analyzed
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisRecorder {
+    timings: Vec<FileTiming>,
+    skipped: Vec<SkippedFile>,
+    skipped_by_reason: BTreeMap<String, u32>,
+    total_duration: Duration,
+    analysis_timed_out: bool,
+}
+
+impl AnalysisRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn record_file(&mut self, timing: FileTiming) {
+        self.timings.push(timing);
+    }
+
+    /// Count a skipped file; only the first few are kept individually
+    pub fn record_skip(&mut self, file_path: &str, reason: &str) {
+        self.record_skip_with_detail(file_path, reason, None);
+    }
+
+    /// Count a skipped file along with the evidence for skipping it
+    pub fn record_skip_with_detail(&mut self, file_path: &str, reason: &str, detail: Option<String>) {
+        *self.skipped_by_reason.entry(reason.to_string()).or_insert(0) += 1;
+        if self.skipped.len() < MAX_SKIPPED_DETAILS {
+            self.skipped.push(SkippedFile {
+                file_path: file_path.to_string(),
+                reason: reason.to_string(),
+                detail,
+            });
+        }
+    }
+
+    /// Count files skipped in bulk, e.g. those left out by sampling
+    pub fn record_skipped_count(&mut self, reason: &str, count: u32) {
+        if count > 0 {
+            *self.skipped_by_reason.entry(reason.to_string()).or_insert(0) += count;
+        }
+    }
+
+    pub fn finish(&mut self, total_duration: Duration, timed_out: bool) {
+        self.total_duration = total_duration;
+        self.analysis_timed_out = timed_out;
+    }
+
+    /// Build the report, listing at most `limit` slowest files
+    pub fn report(&self, limit: Option<usize>, sampling: Option<SamplingReport>) -> AnalysisReport {
+        let mut slowest = self.timings.clone();
+        slowest.sort_by(|a, b| {
+            b.duration_ms
+                .partial_cmp(&a.duration_ms)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.file_path.cmp(&b.file_path))
+        });
+        slowest.truncate(limit.unwrap_or(DEFAULT_SLOWEST_LIMIT));
+
+        let paths_where = |predicate: fn(&FileTiming) -> bool| -> Vec<String> {
+            self.timings
+                .iter()
+                .filter(|t| predicate(t))
+                .map(|t| t.file_path.clone())
+                .collect()
+        };
+
+        AnalysisReport {
+            files_analyzed: self.timings.len() as u32,
+            total_duration_ms: self.total_duration.as_secs_f64() * 1000.0,
+            slowest_files: slowest,
+            timeouts: paths_where(|t| t.timed_out),
+            fallback_files: paths_where(|t| t.used_fallback),
+            analysis_timed_out: self.analysis_timed_out,
+            skipped_files: self.skipped.clone(),
+            skipped_by_reason: self.skipped_by_reason.clone(),
+            sampling,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(file_path: &str, duration_ms: f64, used_fallback: bool, timed_out: bool) -> FileTiming {
+        FileTiming {
+            file_path: file_path.to_string(),
+            language: "rust".to_string(),
+            duration_ms,
+            concept_count: 1,
+            used_fallback,
+            timed_out,
+        }
+    }
+
+    #[test]
+    fn test_report_orders_slowest_first() {
+        let mut recorder = AnalysisRecorder::new();
+        recorder.record_file(timing("fast.rs", 1.0, false, false));
+        recorder.record_file(timing("slow.rs", 900.0, true, true));
+        recorder.record_file(timing("medium.rs", 50.0, true, false));
+        recorder.finish(Duration::from_millis(1200), false);
+
+        let report = recorder.report(Some(2), None);
+        assert_eq!(report.files_analyzed, 3);
+        let slowest: Vec<&str> = report.slowest_files.iter().map(|t| t.file_path.as_str()).collect();
+        assert_eq!(slowest, vec!["slow.rs", "medium.rs"]);
+        assert_eq!(report.timeouts, vec!["slow.rs".to_string()]);
+        assert_eq!(report.fallback_files.len(), 2);
+        assert!((report.total_duration_ms - 1200.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_skips_are_counted_beyond_detail_limit() {
+        let mut recorder = AnalysisRecorder::new();
+        for i in 0..(MAX_SKIPPED_DETAILS + 5) {
+            recorder.record_skip(&format!("node_modules/{}.js", i), "ignored_directory");
+        }
+        recorder.record_skipped_count("file_limit", 3);
+        recorder.record_skipped_count("unused", 0);
+
+        let report = recorder.report(None, None);
+        assert_eq!(report.skipped_files.len(), MAX_SKIPPED_DETAILS);
+        assert_eq!(report.skipped_by_reason["ignored_directory"], (MAX_SKIPPED_DETAILS + 5) as u32);
+        assert_eq!(report.skipped_by_reason["file_limit"], 3);
+        assert!(!report.skipped_by_reason.contains_key("unused"));
+
+        recorder.reset();
+        assert!(recorder.report(None, None).skipped_by_reason.is_empty());
+    }
+}
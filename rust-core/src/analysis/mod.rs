@@ -3,9 +3,83 @@ pub mod complexity;
 pub mod relationships;
 pub mod frameworks;
 pub mod blueprint;
+pub mod confidence;
+pub mod ownership;
+pub mod diff;
+pub mod sampling;
+pub mod progress;
+pub mod instrumentation;
+pub mod debt;
+pub mod dependencies;
+pub mod entry_points;
+pub mod routes;
+pub mod data_model;
+pub mod env_vars;
+pub mod idioms;
+pub mod tags;
+pub mod graph_store;
+pub mod features;
+pub mod walker;
+pub mod pacing;
+pub mod telemetry;
+pub mod overlay;
+pub mod workspace;
+pub mod deprecations;
+pub mod refactoring;
+pub mod domain_entities;
+pub mod quick_scan;
+pub mod testing;
+pub mod build_tasks;
+pub mod i18n;
+pub mod unsafe_audit;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod git_refs;
+pub mod api_contracts;
+pub mod concept_context;
+pub mod concept_snapshot;
+pub mod tombstones;
+pub mod blueprint_summary;
+pub mod events;
 
 pub use semantic::*;
 pub use complexity::*;
 pub use relationships::*;
 pub use frameworks::*;
-pub use blueprint::*;
\ No newline at end of file
+pub use blueprint::*;
+pub use confidence::*;
+pub use ownership::*;
+pub use diff::*;
+pub use sampling::*;
+pub use progress::*;
+pub use instrumentation::*;
+pub use debt::*;
+pub use dependencies::*;
+pub use entry_points::*;
+pub use routes::*;
+pub use data_model::*;
+pub use env_vars::*;
+pub use idioms::*;
+pub use tags::*;
+pub use graph_store::*;
+pub use features::*;
+pub use walker::*;
+pub use pacing::*;
+pub use telemetry::*;
+pub use overlay::*;
+pub use workspace::*;
+pub use deprecations::*;
+pub use refactoring::*;
+pub use domain_entities::*;
+pub use quick_scan::*;
+pub use testing::*;
+pub use build_tasks::*;
+pub use i18n::*;
+pub use unsafe_audit::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use git_refs::*;
+pub use api_contracts::*;
+pub use concept_context::*;
+pub use concept_snapshot::*;
+pub use tombstones::*;
+pub use blueprint_summary::*;
+pub use events::*;
@@ -0,0 +1,125 @@
+//! In-memory file contents that shadow the disk
+//!
+//! Editors analyze unsaved buffers and tests build synthetic projects, neither
+//! of which should have to write files first. A [`FileOverlay`] maps paths to
+//! contents: during a codebase walk an overlaid file is read from memory
+//! instead of disk, and overlaid files that don't exist on disk are analyzed
+//! as if they did. Paths may be absolute or relative to the analyzed root.
+
+use crate::analysis::SourceWalker;
+use crate::types::{paths, validation, ParseError};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default)]
+pub struct FileOverlay {
+    /// Normalized path, absolute or project-relative, to content
+    files: BTreeMap<String, String>,
+}
+
+impl FileOverlay {
+    pub fn new(files: HashMap<String, String>) -> Self {
+        let files = files
+            .into_iter()
+            .map(|(path, content)| (Self::key(&path), content))
+            .collect();
+        FileOverlay { files }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Check the root of a project to analyze: with files overlaid the root
+    /// may exist only in the overlay, otherwise it must be a directory
+    pub fn require_root(&self, path: &str) -> Result<(), ParseError> {
+        if self.is_empty() || Path::new(path).exists() {
+            validation::require_directory(path)?;
+        } else {
+            validation::require_non_empty("path", path)?;
+        }
+        Ok(())
+    }
+
+    /// Content held for a file of the project at `root`
+    pub fn get(&self, path: &Path, root: &Path) -> Option<&str> {
+        let path = path.to_string_lossy();
+        self.files
+            .get(&paths::relative_to(&path, root))
+            .or_else(|| self.files.get(&paths::normalize_path(&path)))
+            .map(String::as_str)
+    }
+
+    /// Read a file of the project at `root`, preferring the overlay
+    pub fn read_to_string(&self, path: &Path, root: &Path) -> std::io::Result<String> {
+        match self.get(path, root) {
+            Some(content) => Ok(content.to_string()),
+            None => std::fs::read_to_string(path),
+        }
+    }
+
+    /// Overlaid files inside `root` as paths under it, in path order;
+    /// absolute paths outside the root are left out
+    pub fn files_under(&self, root: &Path) -> Vec<PathBuf> {
+        self.files
+            .keys()
+            .filter_map(|key| {
+                let relative = paths::relative_to(key, root);
+                (!Self::is_absolute(&relative)).then(|| root.join(relative))
+            })
+            .collect()
+    }
+
+    /// Absolute keys are resolved through their parent directory, so they
+    /// match the canonical paths of a walk even for files not yet on disk
+    fn key(path: &str) -> String {
+        let normalized = paths::normalize_path(path);
+        if !Self::is_absolute(&normalized) {
+            return normalized;
+        }
+        let path = Path::new(path);
+        match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) if parent.is_dir() => {
+                paths::normalize_path(&SourceWalker::canonical_root(parent).join(name).to_string_lossy())
+            }
+            _ => normalized,
+        }
+    }
+
+    fn is_absolute(normalized: &str) -> bool {
+        normalized.starts_with('/') || normalized.as_bytes().get(1) == Some(&b':')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_shadows_and_adds_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = SourceWalker::canonical_root(dir.path());
+        std::fs::write(root.join("saved.ts"), "export const saved = 1;\n").unwrap();
+        std::fs::write(root.join("edited.ts"), "export const old = 1;\n").unwrap();
+
+        let overlay = FileOverlay::new(HashMap::from([
+            (r"src\draft.ts".to_string(), "export const draft = 1;\n".to_string()),
+            (root.join("edited.ts").to_string_lossy().to_string(), "export const edited = 2;\n".to_string()),
+            ("/elsewhere/other.ts".to_string(), "export const other = 1;\n".to_string()),
+        ]));
+        assert_eq!(overlay.len(), 3);
+
+        let edited = overlay.read_to_string(&root.join("edited.ts"), &root).unwrap();
+        assert!(edited.contains("edited = 2"));
+        let saved = overlay.read_to_string(&root.join("saved.ts"), &root).unwrap();
+        assert!(saved.contains("saved = 1"));
+        assert_eq!(overlay.get(&root.join("src").join("draft.ts"), &root), Some("export const draft = 1;\n"));
+
+        let files = overlay.files_under(&root);
+        assert_eq!(files, vec![root.join("edited.ts"), root.join("src/draft.ts")]);
+    }
+}
@@ -0,0 +1,384 @@
+//! Code ownership from CODEOWNERS files and git blame
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::types::{paths, SemanticConcept};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+
+/// Locations GitHub and GitLab look for a CODEOWNERS file, in priority order
+const CODEOWNERS_LOCATIONS: &[&str] = &["CODEOWNERS", ".github/CODEOWNERS", "docs/CODEOWNERS", ".gitlab/CODEOWNERS"];
+
+/// Upper bound on files blamed when aggregating directory ownership
+const MAX_BLAMED_FILES: usize = 500;
+
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", "target"];
+const SOURCE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "py", "rs", "go", "java", "c", "cpp", "cs", "php", "sql", "svelte"];
+
+/// Ownership summary for one directory of the project
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct DirectoryOwnership {
+    pub path: String,
+    /// Owners from CODEOWNERS covering most files in the directory
+    pub owners: Vec<String>,
+    /// Author with the most blamed lines across the directory's files
+    pub primary_author: Option<String>,
+    pub file_count: u32,
+}
+
+/// Parsed CODEOWNERS rules. As in git, the last matching rule wins.
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    rules: Vec<(Regex, Vec<String>)>,
+}
+
+impl CodeOwners {
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let pattern = parts.next()?;
+                let owners: Vec<String> = parts
+                    .take_while(|p| !p.starts_with('#'))
+                    .map(str::to_string)
+                    .collect();
+                Some((Self::pattern_to_regex(pattern)?, owners))
+            })
+            .collect();
+        CodeOwners { rules }
+    }
+
+    /// Load the first CODEOWNERS file found under `root`
+    pub fn load(root: &Path) -> Option<Self> {
+        CODEOWNERS_LOCATIONS
+            .iter()
+            .find_map(|location| std::fs::read_to_string(root.join(location)).ok())
+            .map(|content| Self::parse(&content))
+    }
+
+    /// Owners of a path relative to the repository root. An empty slice means the
+    /// last matching rule explicitly removed ownership.
+    pub fn owners_for(&self, relative_path: &str) -> Option<&[String]> {
+        let path = relative_path.trim_start_matches("./").trim_start_matches('/');
+        self.rules
+            .iter()
+            .rev()
+            .find(|(regex, _)| regex.is_match(path))
+            .map(|(_, owners)| owners.as_slice())
+    }
+
+    /// Translate a gitignore-style CODEOWNERS pattern into a regex over relative paths
+    fn pattern_to_regex(pattern: &str) -> Option<Regex> {
+        let anchored = pattern.starts_with('/') || pattern.trim_end_matches('/').contains('/');
+        let body = pattern.trim_start_matches('/').trim_end_matches('/');
+        if body.is_empty() {
+            return None;
+        }
+
+        let mut translated = String::new();
+        let mut chars = body.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        translated.push_str("(?:.*/)?");
+                    } else {
+                        translated.push_str(".*");
+                    }
+                }
+                '*' => translated.push_str("[^/]*"),
+                '?' => translated.push_str("[^/]"),
+                other => translated.push_str(&regex::escape(&other.to_string())),
+            }
+        }
+
+        let prefix = if anchored { "^" } else { "^(?:.*/)?" };
+        // A match on a directory covers everything beneath it
+        Regex::new(&format!("{}{}(?:/.*)?$", prefix, translated)).ok()
+    }
+}
+
+/// Attaches owners and primary authors to concepts and aggregates them per directory
+pub struct OwnershipAnalyzer {
+    root: PathBuf,
+    codeowners: Option<CodeOwners>,
+    /// Author of each line per file, `None` when the file can't be blamed
+    blame_cache: HashMap<String, Option<Vec<String>>>,
+}
+
+impl OwnershipAnalyzer {
+    pub fn new(root: &str) -> Self {
+        let root = PathBuf::from(root);
+        OwnershipAnalyzer {
+            codeowners: CodeOwners::load(&root),
+            root,
+            blame_cache: HashMap::new(),
+        }
+    }
+
+    /// Use the given CODEOWNERS content instead of the repository's file
+    pub fn with_codeowners(mut self, content: &str) -> Self {
+        self.codeowners = Some(CodeOwners::parse(content));
+        self
+    }
+
+    /// Record `owners`, `primary_author` and `ownership_source` in each concept's metadata
+    pub fn attach_ownership(&mut self, concepts: &mut [SemanticConcept]) {
+        for concept in concepts.iter_mut() {
+            let relative = self.relative_path(&concept.file_path);
+            let mut source = None;
+
+            if let Some(owners) = self.codeowners.as_ref().and_then(|c| c.owners_for(&relative)) {
+                if !owners.is_empty() {
+                    concept.metadata.insert("owners".to_string(), owners.join(" "));
+                    source = Some("codeowners");
+                }
+            }
+
+            let first_line = (concept.line_range.start as usize).saturating_sub(1);
+            let line_count = (concept.line_range.end as usize).saturating_sub(first_line).max(1);
+            if let Some(author) = self
+                .line_authors(&relative)
+                .and_then(|authors| Self::primary_author(authors.iter().skip(first_line).take(line_count)))
+            {
+                concept.metadata.insert("primary_author".to_string(), author);
+                source.get_or_insert("git_blame");
+            }
+
+            if let Some(source) = source {
+                concept
+                    .metadata
+                    .insert("ownership_source".to_string(), source.to_string());
+            }
+        }
+    }
+
+    /// Aggregate ownership for every directory containing source files
+    pub fn directory_ownership(&mut self) -> Vec<DirectoryOwnership> {
+        let mut files_by_dir: HashMap<String, Vec<String>> = HashMap::new();
+        for entry in WalkDir::new(&self.root)
+            .max_depth(6)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            let is_source = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext));
+            if !entry.file_type().is_file() || !is_source {
+                continue;
+            }
+            let relative = self.relative_path(&path.to_string_lossy());
+            let dir = Path::new(&relative)
+                .parent()
+                .map(|p| p.to_string_lossy().to_string())
+                .filter(|p| !p.is_empty())
+                .unwrap_or_else(|| ".".to_string());
+            files_by_dir.entry(dir).or_default().push(relative);
+        }
+
+        let mut blamed = 0;
+        let mut dirs: Vec<_> = files_by_dir.into_iter().collect();
+        dirs.sort_by(|a, b| a.0.cmp(&b.0));
+
+        dirs.into_iter()
+            .map(|(dir, files)| {
+                let mut owner_votes: HashMap<Vec<String>, u32> = HashMap::new();
+                let mut author_lines: Vec<String> = Vec::new();
+
+                for file in &files {
+                    if let Some(owners) = self.codeowners.as_ref().and_then(|c| c.owners_for(file)) {
+                        if !owners.is_empty() {
+                            *owner_votes.entry(owners.to_vec()).or_insert(0) += 1;
+                        }
+                    }
+                    if blamed < MAX_BLAMED_FILES {
+                        blamed += 1;
+                        if let Some(authors) = self.line_authors(file) {
+                            author_lines.extend(authors.iter().cloned());
+                        }
+                    }
+                }
+
+                let owners = owner_votes
+                    .into_iter()
+                    .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+                    .map(|(owners, _)| owners)
+                    .unwrap_or_default();
+
+                DirectoryOwnership {
+                    path: dir,
+                    owners,
+                    primary_author: Self::primary_author(author_lines.iter()),
+                    file_count: files.len() as u32,
+                }
+            })
+            .collect()
+    }
+
+    fn relative_path(&self, file_path: &str) -> String {
+        paths::relative_to(file_path, &self.root)
+    }
+
+    /// Most frequent author, ties broken alphabetically
+    fn primary_author<'a>(authors: impl Iterator<Item = &'a String>) -> Option<String> {
+        let mut counts: HashMap<&str, u32> = HashMap::new();
+        for author in authors {
+            *counts.entry(author.as_str()).or_insert(0) += 1;
+        }
+        counts
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(author, _)| author.to_string())
+    }
+
+    fn line_authors(&mut self, relative_path: &str) -> Option<&Vec<String>> {
+        if !self.blame_cache.contains_key(relative_path) {
+            let authors = Self::blame(&self.root, relative_path);
+            self.blame_cache.insert(relative_path.to_string(), authors);
+        }
+        self.blame_cache.get(relative_path).and_then(|a| a.as_ref())
+    }
+
+    /// Run `git blame --line-porcelain` and return the author of each line
+    fn blame(root: &Path, relative_path: &str) -> Option<Vec<String>> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args(["blame", "--line-porcelain", "--", relative_path])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        let authors: Vec<String> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.strip_prefix("author "))
+            .map(str::to_string)
+            .collect();
+        (!authors.is_empty()).then_some(authors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LineRange;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const CODEOWNERS: &str = "\
+# Default owners
+*               @org/core
+*.py            @org/data
+/src/payments/  @org/payments @alice
+docs/**/*.md    @org/docs
+src/payments/generated/
+";
+
+    fn concept(file_path: &str, start: u32, end: u32) -> SemanticConcept {
+        SemanticConcept {
+            id: "c1".to_string(),
+            name: "charge".to_string(),
+            concept_type: "function".to_string(),
+            confidence: 0.8,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start, end },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn git(dir: &Path, args: &[&str]) -> bool {
+        Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn test_codeowners_last_match_wins() {
+        let owners = CodeOwners::parse(CODEOWNERS);
+
+        assert_eq!(owners.owners_for("README.md").unwrap(), ["@org/core"]);
+        assert_eq!(owners.owners_for("scripts/etl.py").unwrap(), ["@org/data"]);
+        assert_eq!(owners.owners_for("src/payments/charge.ts").unwrap(), ["@org/payments", "@alice"]);
+        assert_eq!(owners.owners_for("docs/guide/intro.md").unwrap(), ["@org/docs"]);
+        assert!(owners.owners_for("src/payments/generated/client.ts").unwrap().is_empty());
+        // Anchored patterns don't match deeper copies of the directory
+        assert_eq!(owners.owners_for("vendor/src/payments/x.ts").unwrap(), ["@org/core"]);
+    }
+
+    #[test]
+    fn test_attach_ownership_from_codeowners() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        let mut analyzer = OwnershipAnalyzer::new(&root).with_codeowners(CODEOWNERS);
+
+        let mut concepts = vec![concept(&format!("{}/src/payments/charge.ts", root), 1, 3)];
+        analyzer.attach_ownership(&mut concepts);
+
+        assert_eq!(concepts[0].metadata.get("owners"), Some(&"@org/payments @alice".to_string()));
+        assert_eq!(concepts[0].metadata.get("ownership_source"), Some(&"codeowners".to_string()));
+    }
+
+    #[test]
+    fn test_git_blame_ownership() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        if !git(root, &["init", "-q"]) {
+            return; // git not available
+        }
+        fs::create_dir_all(root.join("src/payments")).unwrap();
+        fs::write(root.join("src/payments/charge.ts"), "function charge() {\n  return 1;\n}\n").unwrap();
+        assert!(git(root, &["add", "."]));
+        assert!(git(
+            root,
+            &["-c", "user.name=Alice", "-c", "user.email=alice@example.com", "commit", "-q", "-m", "init"]
+        ));
+
+        let mut analyzer = OwnershipAnalyzer::new(&root.to_string_lossy());
+        let mut concepts = vec![concept("src/payments/charge.ts", 1, 3)];
+        analyzer.attach_ownership(&mut concepts);
+        assert_eq!(concepts[0].metadata.get("primary_author"), Some(&"Alice".to_string()));
+        assert_eq!(concepts[0].metadata.get("ownership_source"), Some(&"git_blame".to_string()));
+
+        let dirs = analyzer.directory_ownership();
+        let payments = dirs.iter().find(|d| d.path == "src/payments").unwrap();
+        assert_eq!(payments.primary_author.as_deref(), Some("Alice"));
+        assert_eq!(payments.file_count, 1);
+    }
+
+    #[test]
+    fn test_directory_ownership_from_codeowners() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src/payments")).unwrap();
+        fs::write(root.join("src/payments/charge.ts"), "export {}\n").unwrap();
+        fs::write(root.join("src/payments/refund.ts"), "export {}\n").unwrap();
+        fs::write(root.join("CODEOWNERS"), CODEOWNERS).unwrap();
+
+        let dirs = OwnershipAnalyzer::new(&root.to_string_lossy()).directory_ownership();
+        let payments = dirs.iter().find(|d| d.path == "src/payments").unwrap();
+        assert_eq!(payments.owners, vec!["@org/payments".to_string(), "@alice".to_string()]);
+        assert_eq!(payments.file_count, 2);
+        assert_eq!(payments.primary_author, None);
+    }
+}
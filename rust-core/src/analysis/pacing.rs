@@ -0,0 +1,126 @@
+//! Yield points for long learning loops
+//!
+//! Parsing and pattern analysis are synchronous, so an async learning call
+//! that walks a large codebase never gives the runtime a chance to run
+//! anything else: progress callbacks and other requests on the same worker
+//! wait until the whole run is done. A [`Pacer`] yields to the runtime every
+//! few items, with the chunk size set by a "nice" level:
+//!
+//! | nice | items between yields |
+//! |------|----------------------|
+//! | 0    | never yields         |
+//! | 1    | 64                   |
+//! | 2    | 16 (default)         |
+//! | 3    | 4                    |
+//! | 4    | 1                    |
+
+use crate::types::validation::{ErrorCode, ValidationError};
+
+/// Highest nice level; yields after every item
+pub const MAX_NICE: u8 = 4;
+
+/// Nice level used when neither the project nor the caller sets one
+pub const DEFAULT_NICE: u8 = 2;
+
+/// Check a nice level passed in by a host
+pub fn nice_level(level: u32) -> Result<u8, ValidationError> {
+    if level > MAX_NICE as u32 {
+        return Err(ValidationError::new(
+            ErrorCode::InvalidArgument,
+            format!("Nice level must be between 0 and {}, got {}", MAX_NICE, level),
+        ));
+    }
+    Ok(level as u8)
+}
+
+/// Counts work items and yields to the async runtime once per chunk
+#[derive(Debug, Clone)]
+pub struct Pacer {
+    chunk: Option<usize>,
+    since_yield: usize,
+}
+
+impl Pacer {
+    /// A pacer for a nice level; levels above [`MAX_NICE`] are treated as it
+    pub fn new(nice: u8) -> Self {
+        Self { chunk: Self::chunk_size(nice), since_yield: 0 }
+    }
+
+    /// Items processed between yields, or `None` when the level never yields
+    pub fn chunk_size(nice: u8) -> Option<usize> {
+        match nice.min(MAX_NICE) {
+            0 => None,
+            level => Some(1 << (2 * (MAX_NICE - level))),
+        }
+    }
+
+    /// Record one finished item, yielding when a chunk is complete
+    pub async fn tick(&mut self) {
+        let Some(chunk) = self.chunk else { return };
+        self.since_yield += 1;
+        if self.since_yield >= chunk {
+            self.since_yield = 0;
+            tokio::task::yield_now().await;
+        }
+    }
+
+    /// Yield between phases of a run unless the level never yields
+    pub async fn pause(&mut self) {
+        if self.chunk.is_some() {
+            self.since_yield = 0;
+            tokio::task::yield_now().await;
+        }
+    }
+}
+
+impl Default for Pacer {
+    fn default() -> Self {
+        Self::new(DEFAULT_NICE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_chunk_sizes_follow_nice_level() {
+        let sizes: Vec<Option<usize>> = (0..=MAX_NICE + 1).map(Pacer::chunk_size).collect();
+        assert_eq!(sizes, vec![None, Some(64), Some(16), Some(4), Some(1), Some(1)]);
+
+        assert_eq!(nice_level(3).unwrap(), 3);
+        assert_eq!(nice_level(5).unwrap_err().code, ErrorCode::InvalidArgument);
+    }
+
+    /// Run 32 items on a single-threaded runtime next to a task counting how
+    /// often it got to run before the loop finished
+    async fn interleavings(nice: u8) -> usize {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let done = Arc::new(AtomicBool::new(false));
+        let (counter, finished) = (runs.clone(), done.clone());
+        let other = tokio::spawn(async move {
+            while !finished.load(Ordering::SeqCst) {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::task::yield_now().await;
+            }
+        });
+
+        let mut pacer = Pacer::new(nice);
+        for _ in 0..32 {
+            pacer.tick().await;
+        }
+        let seen = runs.load(Ordering::SeqCst);
+        done.store(true, Ordering::SeqCst);
+        other.await.unwrap();
+        seen
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_loops_give_other_tasks_a_turn() {
+        assert_eq!(interleavings(0).await, 0);
+        assert_eq!(interleavings(3).await, 8);
+        assert_eq!(interleavings(MAX_NICE).await, 32);
+    }
+}
@@ -0,0 +1,183 @@
+//! Partial results for codebase learning that runs out of time
+//!
+//! When learning hits its time budget the concepts extracted so far are kept
+//! together with the files still to analyze. The caller gets a [`PartialResult`]
+//! with a resume token and can continue the run with a follow-up call instead of
+//! starting over.
+//!
+//! The same state is written to a checkpoint file every few files, so a run in a
+//! process that crashed or was killed can be resumed from disk with
+//! `resume_learning` without re-analyzing the files it already completed.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::types::{ParseError, SemanticConcept, CONFIG_DIR};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Checkpoint file of an unfinished run, inside the project's config directory
+pub const CHECKPOINT_FILE: &str = "learning-checkpoint.json";
+
+const CHECKPOINT_VERSION: u32 = 1;
+
+/// Outcome of a learning call that may have stopped early
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct PartialResult {
+    /// Every concept learned in the run so far, including earlier calls
+    pub concepts: Vec<SemanticConcept>,
+    pub files_processed: u32,
+    /// Files not analyzed yet; empty once the run is complete
+    pub files_remaining: Vec<String>,
+    /// Pass back to continue the run; `None` once it is complete
+    pub resume_token: Option<String>,
+    /// Checkpoint file to pass to `resume_learning` if this process goes away;
+    /// `None` once the run is complete or when checkpointing is disabled
+    pub checkpoint_path: Option<String>,
+}
+
+impl PartialResult {
+    pub fn is_complete(&self) -> bool {
+        self.resume_token.is_none()
+    }
+}
+
+/// A learning run that stopped before analyzing all of its files
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PendingRun {
+    pub version: u32,
+    pub root: String,
+    pub concepts: Vec<SemanticConcept>,
+    pub files_processed: usize,
+    pub remaining: Vec<PathBuf>,
+    /// Time spent across all calls of the run
+    pub elapsed: Duration,
+}
+
+impl PendingRun {
+    pub fn new(root: &str, files: Vec<PathBuf>) -> Self {
+        PendingRun {
+            version: CHECKPOINT_VERSION,
+            root: root.to_string(),
+            remaining: files,
+            ..Default::default()
+        }
+    }
+
+    /// Token identifying this point of the run; it changes as files are processed
+    /// so a stale token can't resume a run twice
+    pub fn token(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.root.hash(&mut hasher);
+        self.remaining.hash(&mut hasher);
+        format!("{:016x}-{}", hasher.finish(), self.files_processed)
+    }
+
+    pub fn to_result(&self) -> PartialResult {
+        PartialResult {
+            concepts: self.concepts.clone(),
+            files_processed: self.files_processed as u32,
+            files_remaining: self
+                .remaining
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+            resume_token: if self.remaining.is_empty() {
+                None
+            } else {
+                Some(self.token())
+            },
+            checkpoint_path: None,
+        }
+    }
+
+    pub fn checkpoint_path(root: &Path) -> PathBuf {
+        root.join(CONFIG_DIR).join(CHECKPOINT_FILE)
+    }
+
+    /// Write the run to its checkpoint file, replacing any earlier checkpoint
+    pub fn save_checkpoint(&self) -> Result<PathBuf, ParseError> {
+        let path = Self::checkpoint_path(Path::new(&self.root));
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                ParseError::from_reason(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+
+        let content = serde_json::to_string(self)
+            .map_err(|e| ParseError::from_reason(format!("Failed to serialize checkpoint: {}", e)))?;
+        // Write then rename so a process killed mid-write leaves the previous checkpoint intact
+        let staging = path.with_extension("json.tmp");
+        std::fs::write(&staging, content)
+            .and_then(|_| std::fs::rename(&staging, &path))
+            .map_err(|e| ParseError::from_reason(format!("Failed to write {}: {}", path.display(), e)))?;
+        Ok(path)
+    }
+
+    pub fn load_checkpoint(path: &Path) -> Result<Self, ParseError> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ParseError::from_reason(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let run: PendingRun = serde_json::from_str(&content)
+            .map_err(|e| ParseError::from_reason(format!("Invalid {}: {}", CHECKPOINT_FILE, e)))?;
+        if run.version != CHECKPOINT_VERSION {
+            return Err(ParseError::from_reason(format!(
+                "Unsupported checkpoint version {} in {}",
+                run.version,
+                path.display()
+            )));
+        }
+        Ok(run)
+    }
+
+    /// Delete the checkpoint of a run that completed
+    pub fn remove_checkpoint(root: &str) {
+        let _ = std::fs::remove_file(Self::checkpoint_path(Path::new(root)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_changes_as_run_advances() {
+        let mut run = PendingRun::new("/repo", vec![PathBuf::from("/repo/a.ts"), PathBuf::from("/repo/b.ts")]);
+        let first = run.to_result();
+        assert!(!first.is_complete());
+        assert_eq!(first.files_remaining.len(), 2);
+
+        run.remaining.remove(0);
+        run.files_processed = 1;
+        assert_ne!(run.token(), first.resume_token.unwrap());
+
+        run.remaining.clear();
+        run.files_processed = 2;
+        let done = run.to_result();
+        assert!(done.is_complete());
+        assert_eq!(done.files_processed, 2);
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        let mut run = PendingRun::new(&root, vec![dir.path().join("b.ts")]);
+        run.files_processed = 1;
+        run.elapsed = Duration::from_millis(1500);
+
+        let path = run.save_checkpoint().unwrap();
+        assert_eq!(path, PendingRun::checkpoint_path(dir.path()));
+        let loaded = PendingRun::load_checkpoint(&path).unwrap();
+        assert_eq!(loaded.token(), run.token());
+        assert_eq!(loaded.elapsed, run.elapsed);
+
+        PendingRun::remove_checkpoint(&root);
+        assert!(PendingRun::load_checkpoint(&path).is_err());
+    }
+}
@@ -0,0 +1,389 @@
+//! Estimates for an instant project summary
+//!
+//! A quick scan lists every file but reads only a stratified sample of them
+//! (see [`FileSampler`](crate::analysis::FileSampler)), within a time budget.
+//! File counts per language are exact; lines, naming conventions and how
+//! widely each framework is referenced are estimated from the sample and
+//! reported with 95% intervals, so a setup wizard can show a summary in a
+//! couple of seconds and say how sure it is.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::FrameworkInfo;
+use crate::types::SemanticConcept;
+use std::collections::BTreeMap;
+
+/// Files read by a quick scan unless the caller asks for another number
+pub const QUICK_SCAN_SAMPLE_SIZE: usize = 150;
+
+/// Time a quick scan spends reading sampled files
+pub const QUICK_SCAN_BUDGET: std::time::Duration = std::time::Duration::from_millis(1500);
+
+/// z-score of a two-sided 95% interval
+const Z_95: f64 = 1.96;
+
+/// Names of one category a convention is reported for, at least
+const MIN_CONVENTION_NAMES: u32 = 3;
+
+const FUNCTION_TYPES: &[&str] = &["function", "method"];
+const TYPE_TYPES: &[&str] = &["class", "struct", "interface", "enum", "trait", "type_alias"];
+
+/// A value estimated from a sample, with its 95% interval
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct Estimate {
+    pub value: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+/// Files and estimated lines of one language
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct LanguageEstimate {
+    pub language: String,
+    /// Files of the language in the project; exact
+    pub files: u32,
+    pub sampled_files: u32,
+    pub lines: Estimate,
+    /// Share of the project's estimated lines
+    pub line_share: f64,
+}
+
+/// The most common naming convention of a language's functions or types
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ConventionEstimate {
+    pub language: String,
+    /// 'functions' or 'types'
+    pub category: String,
+    /// 'camelCase', 'PascalCase', 'snake_case' or 'SCREAMING_SNAKE_CASE'
+    pub convention: String,
+    /// Sampled names whose convention could be told apart
+    pub sampled_names: u32,
+    /// Share of those names following the convention
+    pub share: Estimate,
+}
+
+/// A framework found in the project's manifests and configuration
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct FrameworkGuess {
+    pub name: String,
+    pub version: Option<String>,
+    /// Confidence of the manifest and configuration evidence
+    pub confidence: f64,
+    pub evidence: Vec<String>,
+    /// Share of the sampled source files mentioning the framework
+    pub file_share: Estimate,
+}
+
+/// Instant summary of a project from a sample of its files
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct QuickScanSummary {
+    pub total_files: u32,
+    pub sampled_files: u32,
+    /// False when the time budget ran out before every sampled file was read
+    pub complete: bool,
+    pub elapsed_ms: u32,
+    /// Most lines first
+    pub languages: Vec<LanguageEstimate>,
+    pub conventions: Vec<ConventionEstimate>,
+    pub frameworks: Vec<FrameworkGuess>,
+}
+
+/// What a quick scan keeps of one sampled file
+#[derive(Debug, Clone)]
+pub struct ScannedFile {
+    pub language: String,
+    pub lines: u32,
+    /// (category, name) of the functions and types declared in the file
+    pub names: Vec<(&'static str, String)>,
+    /// Lower-cased content, searched for framework names
+    pub text: String,
+}
+
+impl ScannedFile {
+    pub fn new(language: &str, content: &str, concepts: &[SemanticConcept]) -> Self {
+        let names = concepts
+            .iter()
+            .filter_map(|concept| {
+                let category = if FUNCTION_TYPES.contains(&concept.concept_type.as_str()) {
+                    "functions"
+                } else if TYPE_TYPES.contains(&concept.concept_type.as_str()) {
+                    "types"
+                } else {
+                    return None;
+                };
+                Some((category, concept.name.clone()))
+            })
+            .collect();
+        ScannedFile {
+            language: language.to_string(),
+            lines: content.lines().count() as u32,
+            names,
+            text: content.to_lowercase(),
+        }
+    }
+}
+
+pub struct QuickScanner;
+
+impl QuickScanner {
+    /// Estimates from the sampled `files`, given the number of files of each
+    /// language in the whole project
+    pub fn summarize(
+        population: &BTreeMap<String, u32>,
+        files: &[ScannedFile],
+        frameworks: Vec<FrameworkInfo>,
+    ) -> (Vec<LanguageEstimate>, Vec<ConventionEstimate>, Vec<FrameworkGuess>) {
+        (
+            Self::languages(population, files),
+            Self::conventions(files),
+            Self::frameworks(files, frameworks),
+        )
+    }
+
+    fn languages(population: &BTreeMap<String, u32>, files: &[ScannedFile]) -> Vec<LanguageEstimate> {
+        let mut estimates: Vec<LanguageEstimate> = population
+            .iter()
+            .map(|(language, &total)| {
+                let lines: Vec<f64> = files
+                    .iter()
+                    .filter(|file| &file.language == language)
+                    .map(|file| file.lines as f64)
+                    .collect();
+                LanguageEstimate {
+                    language: language.clone(),
+                    files: total,
+                    sampled_files: lines.len() as u32,
+                    lines: Self::total_estimate(&lines, total),
+                    line_share: 0.0,
+                }
+            })
+            .collect();
+
+        let all_lines: f64 = estimates.iter().map(|estimate| estimate.lines.value).sum();
+        for estimate in &mut estimates {
+            if all_lines > 0.0 {
+                estimate.line_share = estimate.lines.value / all_lines;
+            }
+        }
+        estimates.sort_by(|a, b| {
+            b.lines
+                .value
+                .partial_cmp(&a.lines.value)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.language.cmp(&b.language))
+        });
+        estimates
+    }
+
+    fn conventions(files: &[ScannedFile]) -> Vec<ConventionEstimate> {
+        let mut counts: BTreeMap<(&str, &str), BTreeMap<&'static str, u32>> = BTreeMap::new();
+        for file in files {
+            for (category, name) in &file.names {
+                if let Some(convention) = Self::convention(name) {
+                    *counts
+                        .entry((file.language.as_str(), category))
+                        .or_default()
+                        .entry(convention)
+                        .or_insert(0) += 1;
+                }
+            }
+        }
+
+        counts
+            .into_iter()
+            .filter_map(|((language, category), conventions)| {
+                let sampled: u32 = conventions.values().sum();
+                if sampled < MIN_CONVENTION_NAMES {
+                    return None;
+                }
+                let (convention, count) = conventions
+                    .into_iter()
+                    .max_by(|(a_name, a), (b_name, b)| a.cmp(b).then_with(|| b_name.cmp(a_name)))?;
+                Some(ConventionEstimate {
+                    language: language.to_string(),
+                    category: category.to_string(),
+                    convention: convention.to_string(),
+                    sampled_names: sampled,
+                    share: Self::proportion(count, sampled),
+                })
+            })
+            .collect()
+    }
+
+    fn frameworks(files: &[ScannedFile], frameworks: Vec<FrameworkInfo>) -> Vec<FrameworkGuess> {
+        frameworks
+            .into_iter()
+            .map(|framework| {
+                let needle = Self::framework_keyword(&framework.name);
+                let mentions = files
+                    .iter()
+                    .filter(|file| !needle.is_empty() && file.text.contains(&needle))
+                    .count() as u32;
+                FrameworkGuess {
+                    file_share: Self::proportion(mentions, files.len() as u32),
+                    name: framework.name,
+                    version: framework.version,
+                    confidence: framework.confidence,
+                    evidence: framework.evidence,
+                }
+            })
+            .collect()
+    }
+
+    /// Convention of a name, when its spelling tells; single lower-case words
+    /// fit both camelCase and snake_case and are left out
+    fn convention(name: &str) -> Option<&'static str> {
+        let has_lower = name.chars().any(|c| c.is_lowercase());
+        let has_upper = name.chars().any(|c| c.is_uppercase());
+        let has_underscore = name.trim_matches('_').contains('_');
+        let first = name.trim_start_matches('_').chars().next()?;
+
+        match (first.is_uppercase(), has_lower, has_upper, has_underscore) {
+            (true, false, _, _) if name.len() > 1 => Some("SCREAMING_SNAKE_CASE"),
+            (true, true, _, false) => Some("PascalCase"),
+            (false, true, true, false) => Some("camelCase"),
+            (false, true, false, true) => Some("snake_case"),
+            _ => None,
+        }
+    }
+
+    /// First word of a framework name, as it appears in imports: `next` for
+    /// Next.js, `spring` for Spring Boot
+    fn framework_keyword(name: &str) -> String {
+        name.split(|c: char| !c.is_alphanumeric())
+            .find(|word| !word.is_empty())
+            .unwrap_or("")
+            .to_lowercase()
+    }
+
+    /// Wilson score interval of `successes` out of `trials`
+    fn proportion(successes: u32, trials: u32) -> Estimate {
+        if trials == 0 {
+            return Estimate { value: 0.0, low: 0.0, high: 1.0 };
+        }
+        let n = trials as f64;
+        let p = successes as f64 / n;
+        let z2 = Z_95 * Z_95;
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let margin = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / (1.0 + z2 / n);
+        Estimate {
+            value: p,
+            low: (center - margin).max(0.0),
+            high: (center + margin).min(1.0),
+        }
+    }
+
+    /// Total of a quantity over `population` files from the values of a
+    /// sample of them, with a finite population correction
+    fn total_estimate(sample: &[f64], population: u32) -> Estimate {
+        let observed: f64 = sample.iter().sum();
+        let n = sample.len() as f64;
+        let big_n = population as f64;
+        if sample.is_empty() {
+            return Estimate { value: 0.0, low: 0.0, high: 0.0 };
+        }
+
+        let mean = observed / n;
+        let total = mean * big_n;
+        if sample.len() as u32 >= population {
+            return Estimate { value: total, low: total, high: total };
+        }
+        let margin = if sample.len() < 2 {
+            total
+        } else {
+            let variance = sample.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            let correction = ((big_n - n) / (big_n - 1.0)).sqrt();
+            Z_95 * big_n * (variance / n).sqrt() * correction
+        };
+        Estimate {
+            value: total,
+            low: (total - margin).max(observed),
+            high: total + margin,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(language: &str, lines: u32, names: &[(&'static str, &str)], text: &str) -> ScannedFile {
+        ScannedFile {
+            language: language.to_string(),
+            lines,
+            names: names.iter().map(|(category, name)| (*category, name.to_string())).collect(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_line_estimates_scale_the_sample_to_the_population() {
+        let population = BTreeMap::from([("rust".to_string(), 10), ("python".to_string(), 2)]);
+        let files = vec![
+            file("rust", 100, &[], ""),
+            file("rust", 120, &[], ""),
+            file("rust", 80, &[], ""),
+            file("python", 40, &[], ""),
+            file("python", 60, &[], ""),
+        ];
+
+        let (languages, _, _) = QuickScanner::summarize(&population, &files, Vec::new());
+        assert_eq!(languages[0].language, "rust");
+        assert_eq!(languages[0].lines.value, 1000.0);
+        assert!(languages[0].lines.low < 1000.0 && languages[0].lines.high > 1000.0);
+        assert!(languages[0].lines.low >= 300.0);
+        // Every python file was read: the total is exact
+        assert_eq!(languages[1].lines, Estimate { value: 100.0, low: 100.0, high: 100.0 });
+        assert!((languages[0].line_share - 1000.0 / 1100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dominant_convention_with_interval() {
+        let files = vec![
+            file("typescript", 10, &[("functions", "loadUser"), ("functions", "saveUser"), ("types", "UserStore")], ""),
+            file("typescript", 10, &[("functions", "parseDate"), ("functions", "format_date"), ("functions", "run")], ""),
+        ];
+        let (_, conventions, _) = QuickScanner::summarize(&BTreeMap::new(), &files, Vec::new());
+
+        assert_eq!(conventions.len(), 1);
+        let functions = &conventions[0];
+        assert_eq!(functions.category, "functions");
+        assert_eq!(functions.convention, "camelCase");
+        assert_eq!(functions.sampled_names, 4);
+        assert_eq!(functions.share.value, 0.75);
+        assert!(functions.share.low < 0.75 && functions.share.high > 0.75 && functions.share.high <= 1.0);
+    }
+
+    #[test]
+    fn test_framework_mentions_in_the_sample() {
+        let files = vec![
+            file("typescript", 10, &[], "import react from 'react';"),
+            file("typescript", 10, &[], "export const x = 1;"),
+        ];
+        let frameworks = vec![FrameworkInfo {
+            name: "React".to_string(),
+            version: Some("18.2.0".to_string()),
+            confidence: 0.9,
+            evidence: vec!["package.json dependency".to_string()],
+        }];
+        let (_, _, guesses) = QuickScanner::summarize(&BTreeMap::new(), &files, frameworks);
+        assert_eq!(guesses[0].name, "React");
+        assert_eq!(guesses[0].file_share.value, 0.5);
+    }
+
+    #[test]
+    fn test_conventions_of_names() {
+        assert_eq!(QuickScanner::convention("loadUser"), Some("camelCase"));
+        assert_eq!(QuickScanner::convention("load_user"), Some("snake_case"));
+        assert_eq!(QuickScanner::convention("UserStore"), Some("PascalCase"));
+        assert_eq!(QuickScanner::convention("MAX_SIZE"), Some("SCREAMING_SNAKE_CASE"));
+        assert_eq!(QuickScanner::convention("load"), None);
+        assert_eq!(QuickScanner::framework_keyword("Next.js"), "next");
+    }
+}
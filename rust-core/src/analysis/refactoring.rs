@@ -0,0 +1,551 @@
+//! Refactoring opportunities
+//!
+//! Reads concept sizes, decision points and the relationship graph to point
+//! at code worth restructuring:
+//!
+//! - long functions: callables over a line budget that touch many concepts
+//! - god classes: types holding too many members or depending on too much
+//! - feature envy: callables that reach into one other class or file more
+//!   than into their own
+//! - shotgun surgery: files whose concepts are used from so many other files
+//!   that changing them means editing all of those
+//!
+//! Each suggestion carries the measurements that triggered it.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::{ComplexityAnalyzer, ConceptGraph, EdgeDirection, RelationshipKind};
+use crate::types::validation::{ErrorCode, ValidationError};
+use crate::types::{LineRange, ParseError, SemanticConcept};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+const CALLABLE_TYPES: &[&str] = &["function", "method", "constructor", "arrow_function"];
+
+const CLASS_TYPES: &[&str] = &["class", "struct", "trait", "interface", "impl", "module"];
+
+/// Relationships a concept depends on its target through
+const DEPENDENCY_KINDS: &[RelationshipKind] = &[
+    RelationshipKind::Calls,
+    RelationshipKind::Uses,
+    RelationshipKind::Extends,
+    RelationshipKind::Implements,
+];
+
+/// Limits beyond which a suggestion is made; unset fields use the defaults
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct RefactoringThresholds {
+    /// Lines a function may span (60)
+    pub max_function_lines: Option<u32>,
+    /// Distinct concepts a long function may call or use (5)
+    pub max_function_touches: Option<u32>,
+    /// Members a class may contain (20)
+    pub max_class_members: Option<u32>,
+    /// Distinct concepts outside a class its members may depend on (15)
+    pub max_class_dependencies: Option<u32>,
+    /// References into one foreign class or file that count as envy (3)
+    pub min_envy_references: Option<u32>,
+    /// Other files that may depend on one file (8)
+    pub max_dependent_files: Option<u32>,
+}
+
+impl RefactoringThresholds {
+    pub fn validate(&self) -> Result<(), ParseError> {
+        let limits = [
+            ("maxFunctionLines", self.max_function_lines),
+            ("maxFunctionTouches", self.max_function_touches),
+            ("maxClassMembers", self.max_class_members),
+            ("maxClassDependencies", self.max_class_dependencies),
+            ("minEnvyReferences", self.min_envy_references),
+            ("maxDependentFiles", self.max_dependent_files),
+        ];
+        match limits.iter().find(|(_, limit)| *limit == Some(0)) {
+            Some((name, _)) => Err(ValidationError::new(ErrorCode::InvalidArgument, format!("{} must be at least 1", name)).into()),
+            None => Ok(()),
+        }
+    }
+
+    fn function_lines(&self) -> u32 {
+        self.max_function_lines.unwrap_or(60)
+    }
+
+    fn function_touches(&self) -> u32 {
+        self.max_function_touches.unwrap_or(5)
+    }
+
+    fn class_members(&self) -> u32 {
+        self.max_class_members.unwrap_or(20)
+    }
+
+    fn class_dependencies(&self) -> u32 {
+        self.max_class_dependencies.unwrap_or(15)
+    }
+
+    fn envy_references(&self) -> u32 {
+        self.min_envy_references.unwrap_or(3)
+    }
+
+    fn dependent_files(&self) -> u32 {
+        self.max_dependent_files.unwrap_or(8)
+    }
+}
+
+/// One measurement behind a suggestion
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct RefactoringEvidence {
+    /// e.g. 'lines', 'touched_concepts', 'members', 'dependent_files'
+    pub metric: String,
+    pub value: f64,
+    /// Limit the value is compared with, when there is one
+    pub threshold: Option<f64>,
+    /// Concepts or files the measurement counted
+    pub examples: Vec<String>,
+}
+
+/// A place worth restructuring and why
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct RefactoringSuggestion {
+    /// 'long_function', 'god_class', 'feature_envy' or 'shotgun_surgery'
+    pub kind: String,
+    /// 'high', 'medium' or 'low', from how far the worst measurement is over its limit
+    pub severity: String,
+    /// The concept to refactor; unset for file-level suggestions
+    pub concept_id: Option<String>,
+    /// Concept name, or the file path for file-level suggestions
+    pub name: String,
+    pub file_path: String,
+    pub line_range: LineRange,
+    pub recommendation: String,
+    pub evidence: Vec<RefactoringEvidence>,
+}
+
+pub struct RefactoringDetector;
+
+impl RefactoringDetector {
+    /// Suggestions for `concepts` and the graph learned from them, most
+    /// severe first, then by file and line
+    pub fn detect<'a>(
+        concepts: impl IntoIterator<Item = &'a SemanticConcept>,
+        graph: &ConceptGraph,
+        thresholds: &RefactoringThresholds,
+    ) -> Vec<RefactoringSuggestion> {
+        let by_id: HashMap<&str, &SemanticConcept> = concepts.into_iter().map(|c| (c.id.as_str(), c)).collect();
+        let mut ordered: Vec<&SemanticConcept> = by_id.values().copied().collect();
+        ordered.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut suggestions = Vec::new();
+        for concept in &ordered {
+            let concept_type = concept.concept_type.as_str();
+            if CALLABLE_TYPES.contains(&concept_type) {
+                suggestions.extend(Self::long_function(concept, graph, &by_id, thresholds));
+                suggestions.extend(Self::feature_envy(concept, graph, &by_id, thresholds));
+            } else if CLASS_TYPES.contains(&concept_type) {
+                suggestions.extend(Self::god_class(concept, graph, &by_id, thresholds));
+            }
+        }
+        suggestions.extend(Self::shotgun_surgery(&ordered, graph, &by_id, thresholds));
+
+        suggestions.sort_by(|a, b| {
+            Self::severity_rank(&a.severity)
+                .cmp(&Self::severity_rank(&b.severity))
+                .then_with(|| a.file_path.cmp(&b.file_path))
+                .then_with(|| a.line_range.start.cmp(&b.line_range.start))
+                .then_with(|| a.kind.cmp(&b.kind))
+        });
+        suggestions
+    }
+
+    fn long_function(
+        concept: &SemanticConcept,
+        graph: &ConceptGraph,
+        by_id: &HashMap<&str, &SemanticConcept>,
+        thresholds: &RefactoringThresholds,
+    ) -> Option<RefactoringSuggestion> {
+        let lines = Self::lines(concept);
+        let touched = Self::dependencies(&concept.id, graph, by_id);
+        if lines <= thresholds.function_lines() || touched.len() as u32 <= thresholds.function_touches() {
+            return None;
+        }
+
+        let mut evidence = vec![
+            Self::measured("lines", lines, thresholds.function_lines(), Vec::new()),
+            Self::measured(
+                "touched_concepts",
+                touched.len() as u32,
+                thresholds.function_touches(),
+                Self::names(&touched, by_id),
+            ),
+        ];
+        if let Some(body) = concept.metadata.get("body") {
+            evidence.push(RefactoringEvidence {
+                metric: "decision_points".to_string(),
+                value: ComplexityAnalyzer::count_decision_points(body),
+                threshold: None,
+                examples: Vec::new(),
+            });
+        }
+
+        Some(Self::suggestion(
+            "long_function",
+            concept,
+            format!(
+                "Split {} into smaller functions; it spans {} lines and works with {} other concepts",
+                concept.name,
+                lines,
+                touched.len()
+            ),
+            evidence,
+        ))
+    }
+
+    fn god_class(
+        concept: &SemanticConcept,
+        graph: &ConceptGraph,
+        by_id: &HashMap<&str, &SemanticConcept>,
+        thresholds: &RefactoringThresholds,
+    ) -> Option<RefactoringSuggestion> {
+        let members: BTreeSet<&str> = graph
+            .edges_of(&concept.id, Some(RelationshipKind::Contains), EdgeDirection::Outgoing)
+            .map(|edge| edge.to.as_str())
+            .collect();
+        let mut dependencies = Self::dependencies(&concept.id, graph, by_id);
+        for member in &members {
+            dependencies.extend(Self::dependencies(member, graph, by_id));
+        }
+        dependencies.retain(|id| *id != concept.id && !members.contains(id));
+
+        let over_members = members.len() as u32 > thresholds.class_members();
+        let over_dependencies = dependencies.len() as u32 > thresholds.class_dependencies();
+        if !over_members && !over_dependencies {
+            return None;
+        }
+
+        Some(Self::suggestion(
+            "god_class",
+            concept,
+            format!(
+                "Break {} up by responsibility; it holds {} members depending on {} other concepts",
+                concept.name,
+                members.len(),
+                dependencies.len()
+            ),
+            vec![
+                Self::measured("members", members.len() as u32, thresholds.class_members(), Self::names(&members, by_id)),
+                Self::measured(
+                    "dependencies",
+                    dependencies.len() as u32,
+                    thresholds.class_dependencies(),
+                    Self::names(&dependencies, by_id),
+                ),
+                RefactoringEvidence {
+                    metric: "lines".to_string(),
+                    value: Self::lines(concept) as f64,
+                    threshold: None,
+                    examples: Vec::new(),
+                },
+            ],
+        ))
+    }
+
+    fn feature_envy(
+        concept: &SemanticConcept,
+        graph: &ConceptGraph,
+        by_id: &HashMap<&str, &SemanticConcept>,
+        thresholds: &RefactoringThresholds,
+    ) -> Option<RefactoringSuggestion> {
+        let home = Self::home(&concept.id, graph, by_id)?;
+        let mut per_home: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        for target in Self::dependencies(&concept.id, graph, by_id) {
+            if let Some(target_home) = Self::home(target, graph, by_id) {
+                per_home.entry(target_home).or_default().push(target);
+            }
+        }
+
+        let local = per_home.remove(&home).map_or(0, |targets| targets.len());
+        let (envied, targets) = per_home.into_iter().max_by(|(a_home, a), (b_home, b)| {
+            a.len().cmp(&b.len()).then_with(|| b_home.cmp(a_home))
+        })?;
+        if (targets.len() as u32) < thresholds.envy_references() || targets.len() <= local {
+            return None;
+        }
+
+        let target_ids: BTreeSet<&str> = targets.into_iter().collect();
+        Some(Self::suggestion(
+            "feature_envy",
+            concept,
+            format!(
+                "Move {} next to {}; it uses {} concepts there and {} of its own",
+                concept.name,
+                envied,
+                target_ids.len(),
+                local
+            ),
+            vec![
+                Self::measured(
+                    "foreign_references",
+                    target_ids.len() as u32,
+                    thresholds.envy_references(),
+                    Self::names(&target_ids, by_id),
+                ),
+                RefactoringEvidence {
+                    metric: "local_references".to_string(),
+                    value: local as f64,
+                    threshold: None,
+                    examples: Vec::new(),
+                },
+            ],
+        ))
+    }
+
+    fn shotgun_surgery(
+        concepts: &[&SemanticConcept],
+        graph: &ConceptGraph,
+        by_id: &HashMap<&str, &SemanticConcept>,
+        thresholds: &RefactoringThresholds,
+    ) -> Vec<RefactoringSuggestion> {
+        let mut dependents: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        let mut spans: BTreeMap<&str, LineRange> = BTreeMap::new();
+        for concept in concepts {
+            let file = concept.file_path.as_str();
+            let range = spans.entry(file).or_insert_with(|| concept.line_range.clone());
+            range.start = range.start.min(concept.line_range.start);
+            range.end = range.end.max(concept.line_range.end);
+
+            for edge in graph.edges_of(&concept.id, None, EdgeDirection::Incoming) {
+                if edge.kind == RelationshipKind::Contains.as_str() {
+                    continue;
+                }
+                if let Some(source) = by_id.get(edge.from.as_str()) {
+                    if source.file_path != concept.file_path {
+                        dependents.entry(file).or_default().insert(source.file_path.as_str());
+                    }
+                }
+            }
+        }
+
+        dependents
+            .into_iter()
+            .filter(|(_, files)| files.len() as u32 > thresholds.dependent_files())
+            .map(|(file, files)| {
+                let evidence = Self::measured(
+                    "dependent_files",
+                    files.len() as u32,
+                    thresholds.dependent_files(),
+                    files.iter().map(|f| f.to_string()).collect(),
+                );
+                RefactoringSuggestion {
+                    kind: "shotgun_surgery".to_string(),
+                    severity: Self::severity(&[&evidence]).to_string(),
+                    concept_id: None,
+                    name: file.to_string(),
+                    file_path: file.to_string(),
+                    line_range: spans[file].clone(),
+                    recommendation: format!(
+                        "Put a narrower interface in front of {}; changes to it reach {} other files",
+                        file,
+                        files.len()
+                    ),
+                    evidence: vec![evidence],
+                }
+            })
+            .collect()
+    }
+
+    /// Distinct concepts a concept calls, uses, extends or implements
+    fn dependencies<'a>(
+        concept_id: &str,
+        graph: &'a ConceptGraph,
+        by_id: &HashMap<&str, &SemanticConcept>,
+    ) -> BTreeSet<&'a str> {
+        graph
+            .edges_of(concept_id, None, EdgeDirection::Outgoing)
+            .filter(|edge| DEPENDENCY_KINDS.iter().any(|kind| edge.kind == kind.as_str()))
+            .map(|edge| edge.to.as_str())
+            .filter(|to| *to != concept_id && by_id.contains_key(to))
+            .collect()
+    }
+
+    /// Class a concept is a member of, else its file
+    fn home(concept_id: &str, graph: &ConceptGraph, by_id: &HashMap<&str, &SemanticConcept>) -> Option<String> {
+        let container = graph
+            .edges_of(concept_id, Some(RelationshipKind::Contains), EdgeDirection::Incoming)
+            .filter_map(|edge| by_id.get(edge.from.as_str()))
+            .min_by_key(|c| Self::lines(c));
+        match container {
+            Some(container) => Some(container.name.clone()),
+            None => by_id.get(concept_id).map(|c| c.file_path.clone()),
+        }
+    }
+
+    fn suggestion(
+        kind: &str,
+        concept: &SemanticConcept,
+        recommendation: String,
+        evidence: Vec<RefactoringEvidence>,
+    ) -> RefactoringSuggestion {
+        RefactoringSuggestion {
+            kind: kind.to_string(),
+            severity: Self::severity(&evidence.iter().collect::<Vec<_>>()).to_string(),
+            concept_id: Some(concept.id.clone()),
+            name: concept.name.clone(),
+            file_path: concept.file_path.clone(),
+            line_range: concept.line_range.clone(),
+            recommendation,
+            evidence,
+        }
+    }
+
+    fn measured(metric: &str, value: u32, threshold: u32, examples: Vec<String>) -> RefactoringEvidence {
+        RefactoringEvidence {
+            metric: metric.to_string(),
+            value: value as f64,
+            threshold: Some(threshold as f64),
+            examples,
+        }
+    }
+
+    /// 'high' at twice a limit, 'medium' at one and a half times
+    fn severity(evidence: &[&RefactoringEvidence]) -> &'static str {
+        let ratio = evidence
+            .iter()
+            .filter_map(|e| e.threshold.map(|threshold| e.value / threshold))
+            .fold(0.0, f64::max);
+        if ratio >= 2.0 {
+            "high"
+        } else if ratio >= 1.5 {
+            "medium"
+        } else {
+            "low"
+        }
+    }
+
+    fn severity_rank(severity: &str) -> u8 {
+        match severity {
+            "high" => 0,
+            "medium" => 1,
+            _ => 2,
+        }
+    }
+
+    fn names(ids: &BTreeSet<&str>, by_id: &HashMap<&str, &SemanticConcept>) -> Vec<String> {
+        let mut names: Vec<String> = ids.iter().filter_map(|id| by_id.get(id)).map(|c| c.name.clone()).collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn lines(concept: &SemanticConcept) -> u32 {
+        concept.line_range.end.saturating_sub(concept.line_range.start) + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::RelationshipLearner;
+
+    fn concept(id: &str, concept_type: &str, file_path: &str, start: u32, end: u32) -> SemanticConcept {
+        SemanticConcept {
+            id: id.to_string(),
+            name: id.to_string(),
+            concept_type: concept_type.to_string(),
+            confidence: 0.9,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start, end },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn with(mut concept: SemanticConcept, kind: &str, names: &[&str]) -> SemanticConcept {
+        concept.relationships.insert(kind.to_string(), names.join(", "));
+        concept
+    }
+
+    fn detect(concepts: &[SemanticConcept], thresholds: &RefactoringThresholds) -> Vec<RefactoringSuggestion> {
+        let graph = RelationshipLearner::learn_concept_relationships(concepts);
+        RefactoringDetector::detect(concepts, &graph, thresholds)
+    }
+
+    #[test]
+    fn test_long_function_needs_length_and_reach() {
+        let helpers: Vec<String> = (0..7).map(|i| format!("helper{}", i)).collect();
+        let helper_names: Vec<&str> = helpers.iter().map(String::as_str).collect();
+        let mut concepts = vec![
+            with(concept("process", "function", "src/app.ts", 1, 150), "calls", &helper_names),
+            with(concept("short", "function", "src/app.ts", 200, 210), "calls", &helper_names),
+        ];
+        concepts.extend(helpers.iter().enumerate().map(|(i, h)| concept(h, "function", "src/app.ts", 300 + i as u32 * 5, 302 + i as u32 * 5)));
+
+        let suggestions = detect(&concepts, &RefactoringThresholds::default());
+        let long: Vec<_> = suggestions.iter().filter(|s| s.kind == "long_function").collect();
+        assert_eq!(long.len(), 1);
+        assert_eq!(long[0].name, "process");
+        assert_eq!(long[0].severity, "high");
+        assert_eq!(long[0].evidence[0].value, 150.0);
+        assert_eq!(long[0].evidence[1].examples.len(), 7);
+    }
+
+    #[test]
+    fn test_god_class_counts_members() {
+        let mut concepts = vec![concept("Manager", "class", "src/manager.ts", 1, 400)];
+        concepts.extend((0..25).map(|i| concept(&format!("m{}", i), "method", "src/manager.ts", 2 + i * 10, 8 + i * 10)));
+
+        let suggestions = detect(&concepts, &RefactoringThresholds::default());
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].kind, "god_class");
+        assert_eq!(suggestions[0].severity, "low");
+        assert_eq!(suggestions[0].evidence[0].metric, "members");
+        assert_eq!(suggestions[0].evidence[0].value, 25.0);
+    }
+
+    #[test]
+    fn test_feature_envy_points_at_envied_class() {
+        let concepts = vec![
+            concept("Invoice", "class", "src/invoice.ts", 1, 20),
+            with(concept("total", "method", "src/invoice.ts", 2, 10), "calls", &["price", "discount", "taxRate"]),
+            concept("Customer", "class", "src/customer.ts", 1, 30),
+            concept("price", "method", "src/customer.ts", 2, 5),
+            concept("discount", "method", "src/customer.ts", 6, 10),
+            concept("taxRate", "method", "src/customer.ts", 11, 15),
+        ];
+
+        let suggestions = detect(&concepts, &RefactoringThresholds::default());
+        let envy = suggestions.iter().find(|s| s.kind == "feature_envy").expect("feature envy");
+        assert_eq!(envy.name, "total");
+        assert!(envy.recommendation.contains("next to Customer"));
+        assert_eq!(envy.evidence[0].examples, vec!["discount", "price", "taxRate"]);
+    }
+
+    #[test]
+    fn test_shotgun_surgery_counts_dependent_files() {
+        let mut concepts = vec![concept("formatDate", "function", "src/dates.ts", 1, 5)];
+        concepts.extend((0..4).map(|i| with(concept(&format!("view{}", i), "function", &format!("src/view{}.ts", i), 1, 5), "calls", &["formatDate"])));
+
+        let thresholds = RefactoringThresholds {
+            max_dependent_files: Some(3),
+            ..Default::default()
+        };
+        let suggestions = detect(&concepts, &thresholds);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].kind, "shotgun_surgery");
+        assert_eq!(suggestions[0].concept_id, None);
+        assert_eq!(suggestions[0].file_path, "src/dates.ts");
+        assert_eq!(suggestions[0].evidence[0].value, 4.0);
+
+        assert!(detect(&concepts, &RefactoringThresholds::default()).is_empty());
+    }
+
+    #[test]
+    fn test_zero_thresholds_are_rejected() {
+        let thresholds = RefactoringThresholds {
+            max_class_members: Some(0),
+            ..Default::default()
+        };
+        assert!(thresholds.validate().is_err());
+        assert!(RefactoringThresholds::default().validate().is_ok());
+    }
+}
@@ -1,384 +1,613 @@
-//! Relationship analysis and learning between semantic concepts
+//! Typed relationships between semantic concepts
+//!
+//! Relationships used to be guessed from file layout: every concept in a file
+//! was linked to every other one, and names that looked alike were linked too.
+//! They are now read from the syntax tree. While a file is parsed,
+//! [`RelationshipLearner::record_references`] notes which names each concept
+//! calls, imports, extends, implements and uses, under those keys of
+//! `SemanticConcept::relationships` (names joined by ", "), so the evidence is
+//! kept with the concept through checkpoints and stores.
+//! [`RelationshipLearner::learn_concept_relationships`] then resolves the names
+//! to concepts and builds a [`ConceptGraph`] of directed, typed edges, adding
+//! `contains` edges from classes, modules and the like to the concepts nested
+//! in them. Documentation concepts record the identifiers they mention under
+//! `documents`, which resolves the same way.
 
 #[cfg(feature = "napi-bindings")]
 use napi_derive::napi;
 
 use crate::types::SemanticConcept;
-use std::collections::HashMap;
-
-/// Analyzer for learning and discovering relationships between code concepts
-#[cfg_attr(feature = "napi-bindings", napi)]
-pub struct RelationshipLearner;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use tree_sitter::Node;
+
+/// Call sites and the field naming the callee
+const CALL_NODES: &[(&str, &str)] = &[
+    // TypeScript, JavaScript, Rust, Go, C, C++
+    ("call_expression", "function"),
+    // Python
+    ("call", "function"),
+    // Java
+    ("method_invocation", "name"),
+    // C#
+    ("invocation_expression", "function"),
+    // PHP
+    ("function_call_expression", "function"),
+    ("member_call_expression", "name"),
+];
+
+/// Constructor calls and the field naming the constructed type
+const NEW_NODES: &[(&str, &str)] = &[("new_expression", "constructor"), ("object_creation_expression", "type")];
+
+const EXTENDS_NODES: &[&str] = &[
+    "extends_clause",
+    "extends_type_clause",
+    "superclass",
+    "extends_interfaces",
+    "base_class_clause",
+    "base_clause",
+    "base_list",
+];
+
+const IMPLEMENTS_NODES: &[&str] = &["implements_clause", "super_interfaces", "class_interface_clause"];
+
+const IMPORT_NODES: &[&str] = &[
+    "import_statement",
+    "import_from_statement",
+    "use_declaration",
+    "import_declaration",
+    "using_directive",
+    "namespace_use_declaration",
+];
+
+/// Nodes naming something by a path (`models.User`, `crate::db::Pool`); the
+/// last segment is the name
+const PATH_NODES: &[&str] = &[
+    "attribute",
+    "dotted_name",
+    "member_expression",
+    "nested_identifier",
+    "qualified_identifier",
+    "qualified_name",
+    "scoped_identifier",
+    "scoped_type_identifier",
+    "selector_expression",
+    "field_expression",
+];
+
+const NAME_NODES: &[&str] = &["identifier", "type_identifier", "name", "constant", "property_identifier"];
+
+/// Parts of a clause that name something other than the related concept
+const SKIPPED_NODES: &[&str] = &[
+    "type_arguments",
+    "type_parameters",
+    "arguments",
+    "keyword_argument",
+    "string",
+    "string_literal",
+    "interpreted_string_literal",
+    "import_alias",
+];
+
+const CALLABLE_TYPES: &[&str] = &["function", "method", "constructor", "arrow_function"];
+
+const TYPE_LIKE_TYPES: &[&str] = &["class", "interface", "struct", "trait", "enum", "type", "type_alias", "record", "union"];
+
+/// Concept types whose line range encloses their members
+const CONTAINER_TYPES: &[&str] = &[
+    "class", "interface", "struct", "trait", "enum", "record", "union", "impl", "module", "namespace",
+];
+
+/// How one concept relates to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RelationshipKind {
+    Calls,
+    Imports,
+    Extends,
+    Implements,
+    Uses,
+    Contains,
+    Documents,
+}
 
-#[cfg_attr(feature = "napi-bindings", napi)]
-impl RelationshipLearner {
-    #[cfg_attr(feature = "napi-bindings", napi(constructor))]
-    pub fn new() -> Self {
-        RelationshipLearner
+impl RelationshipKind {
+    pub const ALL: [RelationshipKind; 7] = [
+        RelationshipKind::Calls,
+        RelationshipKind::Imports,
+        RelationshipKind::Extends,
+        RelationshipKind::Implements,
+        RelationshipKind::Uses,
+        RelationshipKind::Contains,
+        RelationshipKind::Documents,
+    ];
+
+    /// Kinds recorded from references in the source; `contains` comes from line ranges
+    const REFERENCED: [RelationshipKind; 6] = [
+        RelationshipKind::Calls,
+        RelationshipKind::Imports,
+        RelationshipKind::Extends,
+        RelationshipKind::Implements,
+        RelationshipKind::Uses,
+        RelationshipKind::Documents,
+    ];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == name)
     }
 
-    /// Learn relationships between concepts and update the relationships map
-    pub fn learn_concept_relationships(
-        concepts: &Vec<SemanticConcept>,
-        relationships: &mut HashMap<String, Vec<String>>,
-    ) {
-        Self::analyze_spatial_relationships(concepts, relationships);
-        Self::analyze_naming_relationships(concepts, relationships);
-        Self::analyze_type_relationships(concepts, relationships);
-        Self::analyze_file_relationships(concepts, relationships);
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelationshipKind::Calls => "calls",
+            RelationshipKind::Imports => "imports",
+            RelationshipKind::Extends => "extends",
+            RelationshipKind::Implements => "implements",
+            RelationshipKind::Uses => "uses",
+            RelationshipKind::Contains => "contains",
+            RelationshipKind::Documents => "documents",
+        }
     }
 
-    /// Analyze relationships based on proximity in source code
-    fn analyze_spatial_relationships(
-        concepts: &Vec<SemanticConcept>,
-        relationships: &mut HashMap<String, Vec<String>>,
-    ) {
-        // Group concepts by file for spatial analysis
-        let mut file_concepts: HashMap<String, Vec<&SemanticConcept>> = HashMap::new();
-        
-        for concept in concepts {
-            file_concepts
-                .entry(concept.file_path.clone())
-                .or_default()
-                .push(concept);
+    /// Name of the relationship seen from its target, e.g. `called_by`
+    pub fn inverse(&self) -> &'static str {
+        match self {
+            RelationshipKind::Calls => "called_by",
+            RelationshipKind::Imports => "imported_by",
+            RelationshipKind::Extends => "extended_by",
+            RelationshipKind::Implements => "implemented_by",
+            RelationshipKind::Uses => "used_by",
+            RelationshipKind::Contains => "member_of",
+            RelationshipKind::Documents => "documented_by",
         }
+    }
 
-        // Analyze spatial relationships within each file
-        for concepts_in_file in file_concepts.values() {
-            for (i, concept1) in concepts_in_file.iter().enumerate() {
-                for concept2 in concepts_in_file.iter().skip(i + 1) {
-                    let distance = Self::calculate_line_distance(concept1, concept2);
-                    
-                    // Consider concepts close if within 10 lines
-                    if distance <= 10 {
-                        Self::add_relationship(
-                            relationships,
-                            &concept1.id,
-                            &concept2.id,
-                            "spatial_proximity",
-                        );
-                        Self::add_relationship(
-                            relationships,
-                            &concept2.id,
-                            &concept1.id,
-                            "spatial_proximity",
-                        );
-                    }
-                }
+    /// Whether a concept of `concept_type` can be the target of this relationship
+    fn accepts(&self, concept_type: &str) -> bool {
+        match self {
+            RelationshipKind::Calls => CALLABLE_TYPES.contains(&concept_type) || concept_type == "class",
+            RelationshipKind::Extends | RelationshipKind::Implements | RelationshipKind::Uses => {
+                TYPE_LIKE_TYPES.contains(&concept_type)
             }
+            RelationshipKind::Imports | RelationshipKind::Contains => true,
+            // Documentation describes code, not other documentation
+            RelationshipKind::Documents => !concept_type.starts_with("doc_"),
         }
     }
+}
 
-    /// Analyze relationships based on naming patterns
-    fn analyze_naming_relationships(
-        concepts: &Vec<SemanticConcept>,
-        relationships: &mut HashMap<String, Vec<String>>,
-    ) {
-        for concept1 in concepts {
-            for concept2 in concepts {
-                if concept1.id == concept2.id {
-                    continue;
-                }
-
-                let similarity = Self::calculate_name_similarity(&concept1.name, &concept2.name);
-                
-                // Consider names similar if they share significant prefixes/suffixes
-                if similarity > 0.6 {
-                    Self::add_relationship(
-                        relationships,
-                        &concept1.id,
-                        &concept2.id,
-                        "naming_similarity",
-                    );
-                }
+/// Which edges of a concept to return
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeDirection {
+    /// Edges from the concept
+    Outgoing,
+    /// Edges to the concept
+    Incoming,
+    Both,
+}
 
-                // Check for common naming patterns
-                if Self::has_naming_relationship(&concept1.name, &concept2.name) {
-                    Self::add_relationship(
-                        relationships,
-                        &concept1.id,
-                        &concept2.id,
-                        "naming_pattern",
-                    );
-                }
-            }
+impl EdgeDirection {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "outgoing" => Some(EdgeDirection::Outgoing),
+            "incoming" => Some(EdgeDirection::Incoming),
+            "both" => Some(EdgeDirection::Both),
+            _ => None,
         }
     }
+}
 
-    /// Analyze relationships based on concept types
-    fn analyze_type_relationships(
-        concepts: &Vec<SemanticConcept>,
-        relationships: &mut HashMap<String, Vec<String>>,
-    ) {
-        // Group concepts by type
-        let mut type_groups: HashMap<String, Vec<&SemanticConcept>> = HashMap::new();
-        
-        for concept in concepts {
-            type_groups
-                .entry(concept.concept_type.clone())
-                .or_default()
-                .push(concept);
-        }
+/// A directed relationship between two concepts, e.g. `from` calls `to`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct RelationshipEdge {
+    pub from: String,
+    pub to: String,
+    /// One of calls, imports, extends, implements, uses, contains, documents
+    pub kind: String,
+}
 
-        // Analyze relationships within type groups
-        for (_concept_type, group_concepts) in type_groups {
-            for (i, concept1) in group_concepts.iter().enumerate() {
-                for concept2 in group_concepts.iter().skip(i + 1) {
-                    Self::add_relationship(
-                        relationships,
-                        &concept1.id,
-                        &concept2.id,
-                        "same_type",
-                    );
-                    Self::add_relationship(
-                        relationships,
-                        &concept2.id,
-                        &concept1.id,
-                        "same_type",
-                    );
-                }
-            }
-        }
+/// Typed edges between concepts, indexed by source and target
+#[derive(Debug, Clone, Default)]
+pub struct ConceptGraph {
+    edges: Vec<RelationshipEdge>,
+    outgoing: HashMap<String, Vec<usize>>,
+    incoming: HashMap<String, Vec<usize>>,
+}
 
-        // Analyze cross-type relationships (e.g., functions in classes)
-        Self::analyze_cross_type_relationships(concepts, relationships);
+impl ConceptGraph {
+    pub fn len(&self) -> usize {
+        self.edges.len()
     }
 
-    /// Analyze relationships between different concept types
-    fn analyze_cross_type_relationships(
-        concepts: &Vec<SemanticConcept>,
-        relationships: &mut HashMap<String, Vec<String>>,
-    ) {
-        for concept in concepts {
-            match concept.concept_type.as_str() {
-                "function" | "method" => {
-                    // Look for classes or interfaces this function might belong to
-                    for other_concept in concepts {
-                        if (other_concept.concept_type == "class" || other_concept.concept_type == "interface") && Self::is_function_in_class(concept, other_concept) {
-                            Self::add_relationship(
-                                relationships,
-                                &concept.id,
-                                &other_concept.id,
-                                "member_of",
-                            );
-                            Self::add_relationship(
-                                relationships,
-                                &other_concept.id,
-                                &concept.id,
-                                "contains",
-                            );
-                        }
-                    }
-                }
-                "variable" | "field" => {
-                    // Look for functions or classes this variable might belong to
-                    for other_concept in concepts {
-                        if (other_concept.concept_type == "function" || other_concept.concept_type == "class") && Self::is_variable_in_scope(concept, other_concept) {
-                            Self::add_relationship(
-                                relationships,
-                                &concept.id,
-                                &other_concept.id,
-                                "scoped_in",
-                            );
-                        }
-                    }
-                }
-                _ => {}
-            }
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Every edge, in the order added
+    pub fn all_edges(&self) -> &[RelationshipEdge] {
+        &self.edges
+    }
+
+    /// Add an edge; false if the same edge is already present
+    pub fn add_edge(&mut self, from: &str, to: &str, kind: RelationshipKind) -> bool {
+        let exists = self
+            .outgoing
+            .get(from)
+            .is_some_and(|indices| indices.iter().any(|&i| self.edges[i].to == to && self.edges[i].kind == kind.as_str()));
+        if exists {
+            return false;
         }
+        let index = self.edges.len();
+        self.edges.push(RelationshipEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind: kind.as_str().to_string(),
+        });
+        self.outgoing.entry(from.to_string()).or_default().push(index);
+        self.incoming.entry(to.to_string()).or_default().push(index);
+        true
     }
 
-    /// Analyze relationships based on file organization
-    fn analyze_file_relationships(
-        concepts: &Vec<SemanticConcept>,
-        relationships: &mut HashMap<String, Vec<String>>,
-    ) {
-        // Group concepts by file
-        let mut file_groups: HashMap<String, Vec<&SemanticConcept>> = HashMap::new();
-        
-        for concept in concepts {
-            file_groups
-                .entry(concept.file_path.clone())
-                .or_default()
-                .push(concept);
+    /// Keep only the edges `keep` accepts, in the order they were added
+    pub fn retain(&mut self, keep: impl Fn(&RelationshipEdge) -> bool) {
+        let edges = std::mem::take(&mut self.edges);
+        self.outgoing.clear();
+        self.incoming.clear();
+        for edge in edges.into_iter().filter(|edge| keep(edge)) {
+            let index = self.edges.len();
+            self.outgoing.entry(edge.from.clone()).or_default().push(index);
+            self.incoming.entry(edge.to.clone()).or_default().push(index);
+            self.edges.push(edge);
         }
+    }
 
-        // Analyze relationships within files
-        for concepts_in_file in file_groups.values() {
-            for (i, concept1) in concepts_in_file.iter().enumerate() {
-                for concept2 in concepts_in_file.iter().skip(i + 1) {
-                    Self::add_relationship(
-                        relationships,
-                        &concept1.id,
-                        &concept2.id,
-                        "same_file",
-                    );
-                }
+    /// Edges of a concept, optionally of one kind, outgoing edges first
+    pub fn edges(&self, concept_id: &str, kind: Option<RelationshipKind>, direction: EdgeDirection) -> Vec<RelationshipEdge> {
+        self.edges_of(concept_id, kind, direction).cloned().collect()
+    }
+
+    /// Like [`edges`](Self::edges), borrowing the edges
+    pub fn edges_of<'a>(
+        &'a self,
+        concept_id: &str,
+        kind: Option<RelationshipKind>,
+        direction: EdgeDirection,
+    ) -> impl Iterator<Item = &'a RelationshipEdge> + 'a {
+        let outgoing = matches!(direction, EdgeDirection::Outgoing | EdgeDirection::Both)
+            .then(|| self.outgoing.get(concept_id))
+            .flatten();
+        let incoming = matches!(direction, EdgeDirection::Incoming | EdgeDirection::Both)
+            .then(|| self.incoming.get(concept_id))
+            .flatten();
+
+        outgoing
+            .into_iter()
+            .chain(incoming)
+            .flatten()
+            .map(|&i| &self.edges[i])
+            .filter(move |edge| kind.is_none_or(|kind| edge.kind == kind.as_str()))
+    }
+
+    /// Edges of a concept as `kind:id` strings, incoming edges under the
+    /// inverse name (`called_by:id`)
+    pub fn describe(&self, concept_id: &str) -> Vec<String> {
+        let mut descriptions: Vec<String> = self
+            .edges(concept_id, None, EdgeDirection::Outgoing)
+            .into_iter()
+            .map(|edge| format!("{}:{}", edge.kind, edge.to))
+            .collect();
+        descriptions.extend(self.edges(concept_id, None, EdgeDirection::Incoming).into_iter().filter_map(|edge| {
+            RelationshipKind::parse(&edge.kind).map(|kind| format!("{}:{}", kind.inverse(), edge.from))
+        }));
+        descriptions
+    }
+}
+
+/// Concepts in id order, looked up by name and, for containers, by file
+struct ConceptIndex<'a> {
+    concepts: Vec<&'a SemanticConcept>,
+    by_name: HashMap<&'a str, Vec<&'a SemanticConcept>>,
+    containers_by_file: HashMap<&'a str, Vec<&'a SemanticConcept>>,
+}
+
+impl<'a> ConceptIndex<'a> {
+    fn new(concepts: impl IntoIterator<Item = &'a SemanticConcept>) -> Self {
+        let mut concepts: Vec<&SemanticConcept> = concepts.into_iter().collect();
+        concepts.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut by_name: HashMap<&str, Vec<&SemanticConcept>> = HashMap::new();
+        let mut containers_by_file: HashMap<&str, Vec<&SemanticConcept>> = HashMap::new();
+        for concept in &concepts {
+            by_name.entry(concept.name.as_str()).or_default().push(concept);
+            if CONTAINER_TYPES.contains(&concept.concept_type.as_str()) {
+                containers_by_file.entry(concept.file_path.as_str()).or_default().push(concept);
             }
         }
+        ConceptIndex { concepts, by_name, containers_by_file }
+    }
+}
+
+/// Learns typed relationships between code concepts from the syntax tree
+#[cfg_attr(feature = "napi-bindings", napi)]
+pub struct RelationshipLearner;
 
-        // Analyze cross-file relationships based on import/export patterns
-        Self::analyze_import_relationships(&file_groups, relationships);
+#[cfg_attr(feature = "napi-bindings", napi)]
+impl RelationshipLearner {
+    #[cfg_attr(feature = "napi-bindings", napi(constructor))]
+    pub fn new() -> Self {
+        RelationshipLearner
     }
+}
 
-    /// Analyze import/export relationships between files
-    fn analyze_import_relationships(
-        file_groups: &HashMap<String, Vec<&SemanticConcept>>,
-        relationships: &mut HashMap<String, Vec<String>>,
-    ) {
-        // This is a simplified analysis - in practice, you'd parse import statements
-        for (file1, concepts1) in file_groups {
-            for (file2, concepts2) in file_groups {
-                if file1 == file2 {
-                    continue;
+impl RelationshipLearner {
+    /// Record the names each concept of one file calls, imports, extends,
+    /// implements and uses, found in the file's syntax tree
+    ///
+    /// A reference belongs to the innermost concept whose lines enclose it;
+    /// file-level imports belong to every top-level concept of the file.
+    pub fn record_references(root: Node<'_>, content: &str, concepts: &mut [SemanticConcept]) {
+        let mut references: Vec<BTreeMap<RelationshipKind, BTreeSet<String>>> = vec![BTreeMap::new(); concepts.len()];
+        let mut imports = BTreeSet::new();
+
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            let kind = node.kind();
+            let line = node.start_position().row as u32 + 1;
+            let owner = Self::innermost_concept(concepts, line);
+            let mut note = |owner: Option<usize>, kind: RelationshipKind, name: String| {
+                if let Some(owner) = owner {
+                    if !name.is_empty() && name != concepts[owner].name {
+                        references[owner].entry(kind).or_default().insert(name);
+                    }
                 }
+            };
 
-                // Look for potential import relationships based on naming
-                for concept1 in concepts1 {
-                    for concept2 in concepts2 {
-                        if (concept1.concept_type == "class" || concept1.concept_type == "interface") && concept2.metadata.contains_key("imports") {
-                            if let Some(imports) = concept2.metadata.get("imports") {
-                                if imports.contains(&concept1.name) {
-                                    Self::add_relationship(
-                                        relationships,
-                                        &concept2.id,
-                                        &concept1.id,
-                                        "imports",
-                                    );
-                                    Self::add_relationship(
-                                        relationships,
-                                        &concept1.id,
-                                        &concept2.id,
-                                        "imported_by",
-                                    );
-                                }
-                            }
-                        }
+            if IMPORT_NODES.contains(&kind) {
+                imports.extend(Self::clause_names(node, content));
+                continue;
+            }
+            if EXTENDS_NODES.contains(&kind) || IMPLEMENTS_NODES.contains(&kind) {
+                let relationship = if EXTENDS_NODES.contains(&kind) {
+                    RelationshipKind::Extends
+                } else {
+                    RelationshipKind::Implements
+                };
+                for name in Self::clause_names(node, content) {
+                    note(owner, relationship, name);
+                }
+                continue;
+            }
+            if kind == "impl_item" {
+                // `impl Trait for Type`: the type implements the trait
+                if let (Some(trait_node), Some(type_node)) = (node.child_by_field_name("trait"), node.child_by_field_name("type")) {
+                    let type_name = Self::type_name(type_node, content);
+                    let implementor = concepts
+                        .iter()
+                        .position(|c| c.name == type_name && TYPE_LIKE_TYPES.contains(&c.concept_type.as_str()));
+                    note(implementor, RelationshipKind::Implements, Self::type_name(trait_node, content));
+                }
+                if let Some(body) = node.child_by_field_name("body") {
+                    stack.push(body);
+                }
+                continue;
+            }
+            if kind == "class_definition" {
+                // Python lists base classes as call-like arguments
+                if let Some(superclasses) = node.child_by_field_name("superclasses") {
+                    for name in Self::clause_names(superclasses, content) {
+                        note(owner, RelationshipKind::Extends, name);
                     }
                 }
+                if let Some(body) = node.child_by_field_name("body") {
+                    stack.push(body);
+                }
+                continue;
             }
+            if let Some((_, field)) = CALL_NODES.iter().find(|(call, _)| *call == kind) {
+                if let Some(callee) = node.child_by_field_name(field) {
+                    note(owner, RelationshipKind::Calls, Self::last_segment(Self::text(callee, content)).to_string());
+                }
+            } else if let Some((_, field)) = NEW_NODES.iter().find(|(new, _)| *new == kind) {
+                if let Some(constructed) = node.child_by_field_name(field) {
+                    note(owner, RelationshipKind::Uses, Self::type_name(constructed, content));
+                }
+            } else if kind == "type_identifier" && !Self::is_declared_name(node) {
+                note(owner, RelationshipKind::Uses, Self::text(node, content).to_string());
+            }
+
+            let mut cursor = node.walk();
+            stack.extend(node.named_children(&mut cursor));
         }
-    }
 
-    /// Calculate distance between concepts in lines
-    fn calculate_line_distance(concept1: &SemanticConcept, concept2: &SemanticConcept) -> u32 {
-        if concept1.file_path != concept2.file_path {
-            return u32::MAX; // Different files have infinite distance
+        if !imports.is_empty() {
+            for index in 0..concepts.len() {
+                let nested = concepts
+                    .iter()
+                    .enumerate()
+                    .any(|(other, outer)| other != index && Self::encloses(outer, &concepts[index]));
+                if !nested {
+                    references[index]
+                        .entry(RelationshipKind::Imports)
+                        .or_default()
+                        .extend(imports.iter().filter(|name| **name != concepts[index].name).cloned());
+                }
+            }
         }
 
-        let start1 = concept1.line_range.start;
-        let end1 = concept1.line_range.end;
-        let start2 = concept2.line_range.start;
-        let end2 = concept2.line_range.end;
-
-        if end1 < start2 {
-            start2 - end1
-        } else if end2 < start1 {
-            start1.saturating_sub(end2)
-        } else {
-            0 // Overlapping ranges
+        for (concept, found) in concepts.iter_mut().zip(references) {
+            for (kind, names) in found {
+                let entry = concept.relationships.entry(kind.as_str().to_string()).or_default();
+                let mut merged: BTreeSet<String> = Self::names(entry).map(str::to_string).collect();
+                merged.extend(names);
+                *entry = merged.into_iter().collect::<Vec<_>>().join(", ");
+            }
         }
     }
 
-    /// Calculate similarity between two names
-    fn calculate_name_similarity(name1: &str, name2: &str) -> f64 {
-        if name1 == name2 {
-            return 1.0;
+    /// Resolve the recorded references of concepts to a graph of typed edges
+    ///
+    /// A name resolves to the concepts of that name whose type fits the
+    /// relationship, preferring ones in the same file; unresolved names, such
+    /// as library calls, are dropped.
+    pub fn learn_concept_relationships<'a>(concepts: impl IntoIterator<Item = &'a SemanticConcept>) -> ConceptGraph {
+        let index = ConceptIndex::new(concepts);
+        let mut graph = ConceptGraph::default();
+        for concept in &index.concepts {
+            Self::resolve_references(&mut graph, concept, &index, |_| true);
+            Self::resolve_container(&mut graph, concept, &index);
         }
+        graph
+    }
 
-        let len1 = name1.len();
-        let len2 = name2.len();
-        let max_len = len1.max(len2);
-        
-        if max_len == 0 {
-            return 1.0;
+    /// Resolve again the edges from and to the concepts of `files`, after
+    /// their concepts changed, keeping the rest of `graph`
+    ///
+    /// Edges of concepts that no longer exist are dropped. Concepts of other
+    /// files only have the names that resolve into `files` looked up again.
+    pub fn relearn_file_relationships<'a>(
+        graph: &mut ConceptGraph,
+        concepts: impl IntoIterator<Item = &'a SemanticConcept>,
+        files: &HashSet<&str>,
+    ) {
+        let index = ConceptIndex::new(concepts);
+        let existing: HashSet<&str> = index.concepts.iter().map(|concept| concept.id.as_str()).collect();
+        let changed: HashSet<&str> = index
+            .concepts
+            .iter()
+            .filter(|concept| files.contains(concept.file_path.as_str()))
+            .map(|concept| concept.id.as_str())
+            .collect();
+        graph.retain(|edge| {
+            let kept = |id: &str| existing.contains(id) && !changed.contains(id);
+            kept(&edge.from) && kept(&edge.to)
+        });
+
+        for concept in &index.concepts {
+            if changed.contains(concept.id.as_str()) {
+                Self::resolve_references(graph, concept, &index, |_| true);
+                Self::resolve_container(graph, concept, &index);
+            } else {
+                Self::resolve_references(graph, concept, &index, |target| {
+                    files.contains(target.file_path.as_str())
+                });
+            }
         }
-
-        // Simple longest common subsequence similarity
-        let common = Self::longest_common_subsequence(name1, name2);
-        common as f64 / max_len as f64
     }
 
-    /// Calculate longest common subsequence length
-    fn longest_common_subsequence(s1: &str, s2: &str) -> usize {
-        let chars1: Vec<char> = s1.chars().collect();
-        let chars2: Vec<char> = s2.chars().collect();
-        let len1 = chars1.len();
-        let len2 = chars2.len();
-
-        let mut dp = vec![vec![0; len2 + 1]; len1 + 1];
-
-        for i in 1..=len1 {
-            for j in 1..=len2 {
-                if chars1[i - 1] == chars2[j - 1] {
-                    dp[i][j] = dp[i - 1][j - 1] + 1;
-                } else {
-                    dp[i][j] = dp[i - 1][j].max(dp[i][j - 1]);
+    /// Add the edges of the names a concept references, to the targets `keep` accepts
+    fn resolve_references(
+        graph: &mut ConceptGraph,
+        concept: &SemanticConcept,
+        index: &ConceptIndex<'_>,
+        keep: impl Fn(&SemanticConcept) -> bool,
+    ) {
+        for kind in RelationshipKind::REFERENCED {
+            let Some(names) = concept.relationships.get(kind.as_str()) else {
+                continue;
+            };
+            for name in Self::names(names) {
+                let candidates: Vec<&SemanticConcept> = index
+                    .by_name
+                    .get(name)
+                    .into_iter()
+                    .flatten()
+                    .copied()
+                    .filter(|target| target.id != concept.id && kind.accepts(&target.concept_type))
+                    .collect();
+                let same_file = candidates.iter().any(|target| target.file_path == concept.file_path);
+                for target in candidates {
+                    if (!same_file || target.file_path == concept.file_path) && keep(target) {
+                        graph.add_edge(&concept.id, &target.id, kind);
+                    }
                 }
             }
         }
-
-        dp[len1][len2]
     }
 
-    /// Check if two names have a naming relationship (e.g., getter/setter)
-    fn has_naming_relationship(name1: &str, name2: &str) -> bool {
-        let name1_lower = name1.to_lowercase();
-        let name2_lower = name2.to_lowercase();
-
-        // Check for getter/setter patterns
-        if name1_lower.starts_with("get") && name2_lower.starts_with("set") {
-            let suffix1 = &name1_lower[3..];
-            let suffix2 = &name2_lower[3..];
-            return suffix1 == suffix2;
+    /// Add the edge from the innermost container around a concept, which is
+    /// always in the concept's own file
+    fn resolve_container(graph: &mut ConceptGraph, concept: &SemanticConcept, index: &ConceptIndex<'_>) {
+        let container = index
+            .containers_by_file
+            .get(concept.file_path.as_str())
+            .into_iter()
+            .flatten()
+            .filter(|outer| Self::encloses(outer, concept))
+            .min_by_key(|outer| outer.line_range.end - outer.line_range.start);
+        if let Some(container) = container {
+            graph.add_edge(&container.id, &concept.id, RelationshipKind::Contains);
         }
+    }
 
-        // Check for test/implementation patterns
-        if name1_lower.contains("test") || name2_lower.contains("test") {
-            let clean1 = name1_lower.replace("test", "");
-            let clean2 = name2_lower.replace("test", "");
-            return clean1 == clean2 || clean1.is_empty() || clean2.is_empty();
-        }
+    /// Index of the concept with the smallest line range around `line`
+    fn innermost_concept(concepts: &[SemanticConcept], line: u32) -> Option<usize> {
+        concepts
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.line_range.start <= line && line <= c.line_range.end)
+            .min_by_key(|(_, c)| (c.line_range.end - c.line_range.start, u32::MAX - c.line_range.start))
+            .map(|(index, _)| index)
+    }
 
-        false
+    /// Whether `inner` lies within the lines of `outer`, in the same file
+    fn encloses(outer: &SemanticConcept, inner: &SemanticConcept) -> bool {
+        outer.id != inner.id
+            && outer.file_path == inner.file_path
+            && outer.line_range.start <= inner.line_range.start
+            && inner.line_range.end <= outer.line_range.end
+            && (outer.line_range.start, inner.line_range.end) != (inner.line_range.start, outer.line_range.end)
     }
 
-    /// Check if a function is likely a member of a class
-    fn is_function_in_class(function: &SemanticConcept, class: &SemanticConcept) -> bool {
-        // Same file check
-        if function.file_path != class.file_path {
-            return false;
+    /// Names listed by a heritage clause or import, skipping type arguments,
+    /// aliases and module strings
+    fn clause_names(node: Node<'_>, content: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            let kind = node.kind();
+            if SKIPPED_NODES.contains(&kind) {
+                continue;
+            }
+            if PATH_NODES.contains(&kind) || NAME_NODES.contains(&kind) {
+                names.push(Self::last_segment(Self::text(node, content)).to_string());
+                continue;
+            }
+            let mut cursor = node.walk();
+            stack.extend(node.named_children(&mut cursor));
         }
+        names.retain(|name| !name.is_empty());
+        names
+    }
 
-        // Check if function is within class line range (with some tolerance)
-        function.line_range.start >= class.line_range.start 
-            && function.line_range.end <= class.line_range.end + 5
+    /// Name of a type node without generic arguments: `Repository<User>` -> `Repository`
+    fn type_name(node: Node<'_>, content: &str) -> String {
+        let text = Self::text(node, content);
+        Self::last_segment(text.split('<').next().unwrap_or(text)).to_string()
     }
 
-    /// Check if a variable is in scope of another concept
-    fn is_variable_in_scope(variable: &SemanticConcept, scope: &SemanticConcept) -> bool {
-        // Same file check
-        if variable.file_path != scope.file_path {
-            return false;
-        }
+    /// Whether a type identifier is the name being declared, as in `class User`
+    fn is_declared_name(node: Node<'_>) -> bool {
+        node.parent()
+            .and_then(|parent| parent.child_by_field_name("name"))
+            .is_some_and(|name| name.id() == node.id())
+    }
 
-        // Check if variable is within scope line range
-        variable.line_range.start >= scope.line_range.start 
-            && variable.line_range.end <= scope.line_range.end
+    fn text<'c>(node: Node<'_>, content: &'c str) -> &'c str {
+        content.get(node.byte_range()).unwrap_or("")
     }
 
-    /// Add a relationship to the relationships map
-    fn add_relationship(
-        relationships: &mut HashMap<String, Vec<String>>,
-        from_id: &str,
-        to_id: &str,
-        relationship_type: &str,
-    ) {
-        let entry = relationships.entry(from_id.to_string()).or_default();
-        let relationship = format!("{}:{}", relationship_type, to_id);
-        if !entry.contains(&relationship) {
-            entry.push(relationship);
-        }
+    /// Last identifier of a path or call: `this.repo.findById` -> `findById`
+    fn last_segment(text: &str) -> &str {
+        let text = text.split(['(', '<']).next().unwrap_or(text);
+        text.rsplit(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+            .find(|segment| !segment.is_empty())
+            .unwrap_or("")
+    }
+
+    /// Names in a relationships value, which joins them with ", "
+    fn names(value: &str) -> impl Iterator<Item = &str> {
+        value.split(',').map(str::trim).filter(|name| !name.is_empty())
     }
 }
 
@@ -391,6 +620,7 @@ impl Default for RelationshipLearner {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parsing::ParserManager;
     use crate::types::LineRange;
 
     fn create_test_concept(
@@ -413,6 +643,22 @@ mod tests {
         }
     }
 
+    fn record(language: &str, code: &str, concepts: &mut [SemanticConcept]) {
+        let mut manager = ParserManager::new().unwrap();
+        let tree = manager.parse(code, language).unwrap();
+        RelationshipLearner::record_references(tree.root_node(), code, concepts);
+    }
+
+    fn targets(graph: &ConceptGraph, id: &str, kind: RelationshipKind) -> Vec<String> {
+        let mut targets: Vec<String> = graph
+            .edges(id, Some(kind), EdgeDirection::Outgoing)
+            .into_iter()
+            .map(|edge| edge.to)
+            .collect();
+        targets.sort();
+        targets
+    }
+
     #[test]
     fn test_relationship_learner_creation() {
         let _learner = RelationshipLearner::new();
@@ -420,107 +666,177 @@ mod tests {
     }
 
     #[test]
-    fn test_spatial_relationships() {
-        let concepts = vec![
-            create_test_concept("1", "func1", "function", "test.rs", 1, 5),
-            create_test_concept("2", "func2", "function", "test.rs", 8, 12),
-            create_test_concept("3", "func3", "function", "test.rs", 50, 60),
-        ];
+    fn test_typescript_references_become_typed_edges() {
+        let code = "\
+import { Logger } from './logger';
 
-        let mut relationships = HashMap::new();
-        RelationshipLearner::learn_concept_relationships(&concepts, &mut relationships);
+interface Repository<T> {
+  find(id: string): T;
+}
 
-        // func1 and func2 should be spatially related (within 10 lines)
-        assert!(relationships.contains_key("1"));
-        assert!(relationships.contains_key("2"));
-        
-        let func1_rels = relationships.get("1").unwrap();
-        assert!(func1_rels.iter().any(|r| r.contains("spatial_proximity:2")));
-    }
+class BaseService {}
 
-    #[test]
-    fn test_naming_relationships() {
-        let concepts = vec![
-            create_test_concept("1", "getUserName", "function", "test.rs", 1, 5),
-            create_test_concept("2", "setUserName", "function", "test.rs", 10, 15),
-            create_test_concept("3", "getData", "function", "test.rs", 20, 25),
-        ];
-
-        let mut relationships = HashMap::new();
-        RelationshipLearner::learn_concept_relationships(&concepts, &mut relationships);
+class UserService extends BaseService implements Repository<User> {
+  find(id: string): User {
+    this.audit(id);
+    return new User(id);
+  }
+  audit(id: string) {}
+}
 
-        // getUserName and setUserName should have naming relationship
-        let get_rels = relationships.get("1").unwrap();
-        assert!(get_rels.iter().any(|r| r.contains("naming_pattern:2")));
+class User {}
+";
+        let mut concepts = vec![
+            create_test_concept("repo", "Repository", "interface", "user.ts", 3, 5),
+            create_test_concept("base", "BaseService", "class", "user.ts", 7, 7),
+            create_test_concept("service", "UserService", "class", "user.ts", 9, 15),
+            create_test_concept("find", "find", "method", "user.ts", 10, 13),
+            create_test_concept("audit", "audit", "method", "user.ts", 14, 14),
+            create_test_concept("user", "User", "class", "user.ts", 17, 17),
+        ];
+        record("typescript", code, &mut concepts);
+
+        let service = &concepts[2];
+        assert_eq!(service.relationships.get("extends").map(String::as_str), Some("BaseService"));
+        assert_eq!(service.relationships.get("implements").map(String::as_str), Some("Repository"));
+        assert_eq!(service.relationships.get("imports").map(String::as_str), Some("Logger"));
+        assert_eq!(concepts[3].relationships.get("calls").map(String::as_str), Some("audit"));
+        assert_eq!(concepts[3].relationships.get("uses").map(String::as_str), Some("User"));
+        // Methods are nested, so the file's imports aren't theirs
+        assert!(!concepts[3].relationships.contains_key("imports"));
+
+        let graph = RelationshipLearner::learn_concept_relationships(&concepts);
+        assert_eq!(targets(&graph, "service", RelationshipKind::Extends), vec!["base"]);
+        assert_eq!(targets(&graph, "service", RelationshipKind::Implements), vec!["repo"]);
+        assert_eq!(targets(&graph, "service", RelationshipKind::Contains), vec!["audit", "find"]);
+        assert_eq!(targets(&graph, "find", RelationshipKind::Calls), vec!["audit"]);
+        assert_eq!(targets(&graph, "find", RelationshipKind::Uses), vec!["user"]);
+        // `Logger` isn't a known concept
+        assert!(targets(&graph, "service", RelationshipKind::Imports).is_empty());
     }
 
     #[test]
-    fn test_type_relationships() {
-        let concepts = vec![
-            create_test_concept("1", "func1", "function", "test.rs", 1, 5),
-            create_test_concept("2", "func2", "function", "test.rs", 10, 15),
-            create_test_concept("3", "Class1", "class", "test.rs", 20, 30),
-        ];
+    fn test_rust_trait_impls_and_python_bases() {
+        let rust = "\
+struct User;
 
-        let mut relationships = HashMap::new();
-        RelationshipLearner::learn_concept_relationships(&concepts, &mut relationships);
+trait Greet {
+    fn greet(&self);
+}
 
-        // Functions should be related by same type
-        let func1_rels = relationships.get("1").unwrap();
-        assert!(func1_rels.iter().any(|r| r.contains("same_type:2")));
+impl Greet for User {
+    fn greet(&self) {
+        helper();
     }
+}
 
-    #[test]
-    fn test_cross_type_relationships() {
-        let concepts = vec![
-            create_test_concept("1", "TestClass", "class", "test.rs", 1, 30),
-            create_test_concept("2", "method1", "function", "test.rs", 5, 10),
-            create_test_concept("3", "field1", "variable", "test.rs", 15, 15),
+fn helper() {}
+";
+        let mut concepts = vec![
+            create_test_concept("user", "User", "struct", "lib.rs", 1, 1),
+            create_test_concept("greet_trait", "Greet", "trait", "lib.rs", 3, 5),
+            create_test_concept("greet", "greet", "function", "lib.rs", 8, 10),
+            create_test_concept("helper", "helper", "function", "lib.rs", 13, 13),
         ];
-
-        let mut relationships = HashMap::new();
-        RelationshipLearner::learn_concept_relationships(&concepts, &mut relationships);
-
-        // Method should be member of class
-        let method_rels = relationships.get("2").unwrap();
-        assert!(method_rels.iter().any(|r| r.contains("member_of:1")));
-
-        // Class should contain method
-        let class_rels = relationships.get("1").unwrap();
-        assert!(class_rels.iter().any(|r| r.contains("contains:2")));
+        record("rust", rust, &mut concepts);
+        assert_eq!(concepts[0].relationships.get("implements").map(String::as_str), Some("Greet"));
+        assert_eq!(concepts[2].relationships.get("calls").map(String::as_str), Some("helper"));
+
+        let python = "\
+from app.models import Model
+
+class Account(Model, metaclass=Meta):
+    def save(self):
+        validate(self)
+
+def validate(account):
+    pass
+";
+        let mut concepts = vec![
+            create_test_concept("account", "Account", "class", "models.py", 3, 5),
+            create_test_concept("save", "save", "method", "models.py", 4, 5),
+            create_test_concept("validate", "validate", "function", "models.py", 7, 8),
+        ];
+        record("python", python, &mut concepts);
+        assert_eq!(concepts[0].relationships.get("extends").map(String::as_str), Some("Model"));
+        assert_eq!(concepts[1].relationships.get("calls").map(String::as_str), Some("validate"));
+        assert_eq!(concepts[2].relationships.get("imports").map(String::as_str), Some("Model, models"));
     }
 
     #[test]
-    fn test_calculate_line_distance() {
-        let concept1 = create_test_concept("1", "func1", "function", "test.rs", 1, 5);
-        let concept2 = create_test_concept("2", "func2", "function", "test.rs", 10, 15);
-        let concept3 = create_test_concept("3", "func3", "function", "other.rs", 1, 5);
-
-        assert_eq!(RelationshipLearner::calculate_line_distance(&concept1, &concept2), 5);
-        assert_eq!(RelationshipLearner::calculate_line_distance(&concept1, &concept3), u32::MAX);
+    fn test_graph_resolves_names_and_answers_queries() {
+        let mut caller = create_test_concept("a::run", "run", "function", "a.ts", 1, 5);
+        caller.relationships.insert("calls".to_string(), "save, console, log".to_string());
+        let local_save = create_test_concept("a::save", "save", "function", "a.ts", 7, 9);
+        let other_save = create_test_concept("b::save", "save", "function", "b.ts", 1, 3);
+        let mut importer = create_test_concept("c::main", "main", "function", "c.ts", 1, 4);
+        importer.relationships.insert("calls".to_string(), "save".to_string());
+
+        let concepts = [caller, local_save, other_save, importer];
+        let graph = RelationshipLearner::learn_concept_relationships(&concepts);
+
+        // Same-file definitions win; otherwise every definition of the name
+        assert_eq!(targets(&graph, "a::run", RelationshipKind::Calls), vec!["a::save"]);
+        assert_eq!(targets(&graph, "c::main", RelationshipKind::Calls), vec!["a::save", "b::save"]);
+        assert_eq!(graph.len(), 3);
+
+        let incoming = graph.edges("a::save", None, EdgeDirection::Incoming);
+        assert_eq!(incoming.len(), 2);
+        assert!(incoming.iter().all(|edge| edge.to == "a::save" && edge.kind == "calls"));
+        assert!(graph.edges("a::save", None, EdgeDirection::Outgoing).is_empty());
+        assert_eq!(graph.edges("a::save", Some(RelationshipKind::Uses), EdgeDirection::Both).len(), 0);
+        assert!(graph.describe("b::save").contains(&"called_by:c::main".to_string()));
+        assert!(!graph.clone().add_edge("a::run", "a::save", RelationshipKind::Calls));
     }
 
     #[test]
-    fn test_name_similarity() {
-        assert_eq!(RelationshipLearner::calculate_name_similarity("test", "test"), 1.0);
-        assert!(RelationshipLearner::calculate_name_similarity("test", "testing") > 0.7);
-        assert!(RelationshipLearner::calculate_name_similarity("abc", "xyz") < 0.3);
+    fn test_relearning_a_file_matches_a_full_rebuild() {
+        let mut caller = create_test_concept("a::run", "run", "function", "a.ts", 1, 5);
+        caller.relationships.insert("calls".to_string(), "save, load".to_string());
+        let service = create_test_concept("b::service", "Store", "class", "b.ts", 1, 10);
+        let save = create_test_concept("b::save", "save", "method", "b.ts", 2, 4);
+        let old_load = create_test_concept("b::load", "load", "method", "b.ts", 5, 7);
+        let mut concepts = vec![caller, service, save, old_load];
+        let mut graph = RelationshipLearner::learn_concept_relationships(&concepts);
+        assert_eq!(targets(&graph, "a::run", RelationshipKind::Calls), vec!["b::load", "b::save"]);
+
+        // b.ts changes: load is gone, fetch is new and calls back into a.ts
+        concepts.pop();
+        let mut fetch = create_test_concept("b::fetch", "fetch", "method", "b.ts", 5, 8);
+        fetch.relationships.insert("calls".to_string(), "run".to_string());
+        concepts.push(fetch);
+        RelationshipLearner::relearn_file_relationships(&mut graph, &concepts, &HashSet::from(["b.ts"]));
+
+        let edges = |graph: &ConceptGraph| {
+            let mut edges: Vec<String> =
+                graph.all_edges().iter().map(|e| format!("{} {} {}", e.from, e.kind, e.to)).collect();
+            edges.sort();
+            edges
+        };
+        assert_eq!(edges(&graph), edges(&RelationshipLearner::learn_concept_relationships(&concepts)));
+        assert_eq!(targets(&graph, "b::fetch", RelationshipKind::Calls), vec!["a::run"]);
+        assert_eq!(targets(&graph, "b::service", RelationshipKind::Contains), vec!["b::fetch", "b::save"]);
     }
 
     #[test]
-    fn test_naming_patterns() {
-        assert!(RelationshipLearner::has_naming_relationship("getName", "setName"));
-        assert!(RelationshipLearner::has_naming_relationship("testFunction", "function"));
-        assert!(!RelationshipLearner::has_naming_relationship("foo", "bar"));
+    fn test_documentation_links_to_code_only() {
+        let mut usage = create_test_concept("readme::usage", "Usage", "doc_code_block", "README.md", 3, 8);
+        usage.relationships.insert("documents".to_string(), "PaymentClient, Setup".to_string());
+        let client = create_test_concept("a::client", "PaymentClient", "class", "src/client.ts", 1, 20);
+        let setup = create_test_concept("readme::setup", "Setup", "doc_section", "README.md", 10, 12);
+
+        let graph = RelationshipLearner::learn_concept_relationships(&[usage, client, setup]);
+        assert_eq!(targets(&graph, "readme::usage", RelationshipKind::Documents), vec!["a::client"]);
+        assert!(graph.describe("a::client").contains(&"documented_by:readme::usage".to_string()));
     }
 
     #[test]
-    fn test_function_in_class_detection() {
-        let class = create_test_concept("1", "TestClass", "class", "test.rs", 1, 30);
-        let method_inside = create_test_concept("2", "method1", "function", "test.rs", 5, 10);
-        let method_outside = create_test_concept("3", "method2", "function", "test.rs", 35, 40);
-
-        assert!(RelationshipLearner::is_function_in_class(&method_inside, &class));
-        assert!(!RelationshipLearner::is_function_in_class(&method_outside, &class));
+    fn test_kind_and_direction_names() {
+        for kind in RelationshipKind::ALL {
+            assert_eq!(RelationshipKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(RelationshipKind::parse("same_file"), None);
+        assert_eq!(EdgeDirection::parse("incoming"), Some(EdgeDirection::Incoming));
+        assert_eq!(EdgeDirection::parse("sideways"), None);
     }
-}
\ No newline at end of file
+}
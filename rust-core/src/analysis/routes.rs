@@ -0,0 +1,552 @@
+//! HTTP route extraction for common web frameworks
+//!
+//! Walks tree-sitter ASTs for route declarations:
+//! - Express: `app.get('/users', handler)`, `router.route('/users').post(handler)`
+//! - FastAPI/Flask: `@app.get("/users")`, `@bp.route("/users", methods=["POST"])`
+//! - Actix/Rocket: `#[get("/users")]`; Actix/Axum: `.route("/users", get(handler))`
+//! - Spring: `@GetMapping("/users")`, `@RequestMapping` on classes and methods
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::parsing::{ParserManager, TreeWalker};
+use crate::types::ParseError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tree_sitter::Node;
+use walkdir::WalkDir;
+
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", ".venv", "target", "vendor"];
+
+/// Files larger than this are not parsed for routes
+const MAX_ROUTE_FILE_SIZE: u64 = 512 * 1024;
+
+const HTTP_METHODS: &[&str] = &["get", "post", "put", "delete", "patch", "options", "head", "all"];
+
+/// A declared HTTP route
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ApiEndpoint {
+    /// Upper-case HTTP method, 'ALL' or 'ANY' when not restricted
+    pub method: String,
+    pub path: String,
+    /// Handler function or method, when it is named
+    pub handler: Option<String>,
+    /// 'express', 'fastapi', 'flask', 'actix', 'axum', 'rocket' or 'spring'
+    pub framework: String,
+    pub file_path: String,
+    pub line: u32,
+}
+
+pub struct RouteExtractor;
+
+impl RouteExtractor {
+    /// Extract routes from every supported source file under `root`
+    pub fn scan(root: &Path) -> Result<Vec<ApiEndpoint>, ParseError> {
+        let mut parser_manager = ParserManager::new()?;
+        let mut endpoints = Vec::new();
+
+        for entry in WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            let Some(language) = path.extension().and_then(|e| e.to_str()).and_then(Self::language_for_extension) else {
+                continue;
+            };
+            if !entry.file_type().is_file() || entry.metadata().map(|m| m.len() > MAX_ROUTE_FILE_SIZE).unwrap_or(true) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(path) else { continue };
+            if !Self::may_declare_routes(&content) {
+                continue;
+            }
+            let Ok(tree) = parser_manager.parse(&content, language) else { continue };
+
+            let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+            endpoints.extend(Self::extract(tree.root_node(), language, &relative, &content));
+        }
+
+        Ok(endpoints)
+    }
+
+    /// Extract routes from a parsed file
+    pub fn extract(root: Node<'_>, language: &str, file_path: &str, content: &str) -> Vec<ApiEndpoint> {
+        let mut endpoints = Vec::new();
+        let ctx = FileContext { file_path, content };
+
+        // A tree deeper than the walker allows still yields the routes found so far
+        let _ = TreeWalker::default().walk(root, &mut |node| {
+            match (language, node.kind()) {
+                ("javascript" | "typescript", "call_expression") => ctx.express_route(node, &mut endpoints),
+                ("python", "decorated_definition") => ctx.python_routes(node, &mut endpoints),
+                ("rust", "function_item") => ctx.rust_attribute_routes(node, &mut endpoints),
+                ("rust", "call_expression") => ctx.rust_builder_routes(node, &mut endpoints),
+                ("java", "method_declaration") => ctx.spring_routes(node, &mut endpoints),
+                _ => {}
+            }
+            Ok(())
+        });
+
+        endpoints
+    }
+
+    fn language_for_extension(extension: &str) -> Option<&'static str> {
+        match extension {
+            "ts" | "mts" | "cts" => Some("typescript"),
+            "js" | "mjs" | "cjs" | "jsx" => Some("javascript"),
+            "py" => Some("python"),
+            "rs" => Some("rust"),
+            "java" => Some("java"),
+            _ => None,
+        }
+    }
+
+    /// Cheap text check so files without any route-like call are not parsed
+    fn may_declare_routes(content: &str) -> bool {
+        ["get(", "post(", "put(", "delete(", "patch(", "route(", "Mapping", ".all("]
+            .iter()
+            .any(|hint| content.contains(hint))
+    }
+}
+
+struct FileContext<'a> {
+    file_path: &'a str,
+    content: &'a str,
+}
+
+impl FileContext<'_> {
+    fn text(&self, node: Node<'_>) -> &str {
+        node.utf8_text(self.content.as_bytes()).unwrap_or("")
+    }
+
+    fn push(&self, endpoints: &mut Vec<ApiEndpoint>, method: &str, path: String, handler: Option<String>, framework: &str, node: Node<'_>) {
+        endpoints.push(ApiEndpoint {
+            method: method.to_uppercase(),
+            path,
+            handler,
+            framework: framework.to_string(),
+            file_path: self.file_path.to_string(),
+            line: node.start_position().row as u32 + 1,
+        });
+    }
+
+    /// String literal value, if the node is one
+    fn string_value(&self, node: Node<'_>) -> Option<String> {
+        matches!(node.kind(), "string" | "string_literal" | "template_string" | "raw_string_literal")
+            .then(|| unquote(self.text(node)))
+    }
+
+    fn first_named_child<'t>(node: Node<'t>) -> Option<Node<'t>> {
+        node.named_child(0)
+    }
+
+    /// `app.get('/path', handler)` and `router.route('/path').get(handler)`
+    fn express_route(&self, node: Node<'_>, endpoints: &mut Vec<ApiEndpoint>) {
+        let Some(function) = node.child_by_field_name("function").filter(|f| f.kind() == "member_expression") else {
+            return;
+        };
+        let Some(arguments) = node.child_by_field_name("arguments") else { return };
+        let method = function.child_by_field_name("property").map(|p| self.text(p)).unwrap_or("");
+        if !HTTP_METHODS.contains(&method) {
+            return;
+        }
+
+        let mut cursor = arguments.walk();
+        let args: Vec<Node<'_>> = arguments.named_children(&mut cursor).filter(|n| n.kind() != "comment").collect();
+
+        let path = match args.first().and_then(|first| self.string_value(*first)) {
+            Some(path) if args.len() >= 2 && (path.starts_with('/') || path == "*") => path,
+            Some(_) => return,
+            // router.route('/path').get(handler)
+            None => match function
+                .child_by_field_name("object")
+                .filter(|o| o.kind() == "call_expression")
+                .and_then(|inner| self.chained_route_path(inner))
+            {
+                Some(path) => path,
+                None => return,
+            },
+        };
+
+        let handler = args.last().and_then(|h| self.js_handler(*h));
+        self.push(endpoints, method, path, handler, "express", node);
+    }
+
+    fn chained_route_path(&self, call: Node<'_>) -> Option<String> {
+        let function = call.child_by_field_name("function")?;
+        let property = function.child_by_field_name("property")?;
+        if self.text(property) != "route" {
+            return None;
+        }
+        let arguments = call.child_by_field_name("arguments")?;
+        self.string_value(Self::first_named_child(arguments)?)
+    }
+
+    fn js_handler(&self, node: Node<'_>) -> Option<String> {
+        matches!(node.kind(), "identifier" | "member_expression").then(|| self.text(node).to_string())
+    }
+
+    /// `@app.get("/path")`, `@router.post(...)`, `@bp.route("/path", methods=[...])`
+    fn python_routes(&self, node: Node<'_>, endpoints: &mut Vec<ApiEndpoint>) {
+        let handler = node
+            .child_by_field_name("definition")
+            .and_then(|d| d.child_by_field_name("name"))
+            .map(|n| self.text(n).to_string());
+        let framework = if self.content.contains("fastapi") { "fastapi" } else { "flask" };
+
+        let mut cursor = node.walk();
+        for decorator in node.named_children(&mut cursor).filter(|n| n.kind() == "decorator") {
+            let Some(call) = Self::first_named_child(decorator).filter(|c| c.kind() == "call") else { continue };
+            let Some(attribute) = call.child_by_field_name("function").filter(|f| f.kind() == "attribute") else {
+                continue;
+            };
+            let name = attribute.child_by_field_name("attribute").map(|a| self.text(a)).unwrap_or("");
+            let Some(arguments) = call.child_by_field_name("arguments") else { continue };
+            let Some(path) = Self::first_named_child(arguments).and_then(|a| self.string_value(a)) else {
+                continue;
+            };
+            if !(path.is_empty() || path.starts_with('/')) {
+                continue;
+            }
+
+            let methods = match name {
+                "route" | "api_route" => {
+                    let declared = self.python_methods_kwarg(arguments);
+                    if declared.is_empty() { vec!["GET".to_string()] } else { declared }
+                }
+                method if HTTP_METHODS.contains(&method) => vec![method.to_string()],
+                _ => continue,
+            };
+            for method in methods {
+                self.push(endpoints, &method, path.clone(), handler.clone(), framework, decorator);
+            }
+        }
+    }
+
+    fn python_methods_kwarg(&self, arguments: Node<'_>) -> Vec<String> {
+        let mut cursor = arguments.walk();
+        let methods = arguments
+            .named_children(&mut cursor)
+            .filter(|a| a.kind() == "keyword_argument")
+            .find(|a| a.child_by_field_name("name").map(|n| self.text(n)) == Some("methods"))
+            .and_then(|a| a.child_by_field_name("value"));
+
+        let Some(methods) = methods else { return Vec::new() };
+        let mut cursor = methods.walk();
+        methods
+            .named_children(&mut cursor)
+            .filter_map(|m| self.string_value(m))
+            .collect()
+    }
+
+    /// `#[get("/path")]` and `#[route("/path", method = "GET")]` on a function
+    fn rust_attribute_routes(&self, node: Node<'_>, endpoints: &mut Vec<ApiEndpoint>) {
+        let handler = node.child_by_field_name("name").map(|n| self.text(n).to_string());
+        let framework = if self.content.contains("rocket") { "rocket" } else { "actix" };
+
+        let mut sibling = node.prev_named_sibling();
+        while let Some(attribute_item) = sibling.filter(|s| matches!(s.kind(), "attribute_item" | "line_comment")) {
+            sibling = attribute_item.prev_named_sibling();
+            let Some(attribute) = Self::first_named_child(attribute_item).filter(|a| a.kind() == "attribute") else {
+                continue;
+            };
+            let Some(path_node) = Self::first_named_child(attribute) else { continue };
+            let name = self.text(path_node).rsplit("::").next().unwrap_or("");
+            let Some(tokens) = attribute.child_by_field_name("arguments") else { continue };
+
+            let mut cursor = tokens.walk();
+            let token_nodes: Vec<Node<'_>> = tokens.named_children(&mut cursor).collect();
+            let Some(path) = token_nodes.iter().find_map(|t| self.string_value(*t)) else { continue };
+
+            let methods: Vec<String> = match name {
+                "route" => {
+                    let declared: Vec<String> = token_nodes
+                        .windows(2)
+                        .filter(|pair| self.text(pair[0]) == "method")
+                        .filter_map(|pair| self.string_value(pair[1]))
+                        .collect();
+                    if declared.is_empty() { vec!["ANY".to_string()] } else { declared }
+                }
+                method if HTTP_METHODS.contains(&method) => vec![method.to_string()],
+                _ => continue,
+            };
+            for method in methods {
+                self.push(endpoints, &method, path.clone(), handler.clone(), framework, attribute_item);
+            }
+        }
+    }
+
+    /// `.route("/path", get(handler).post(other))` (Axum) and
+    /// `.route("/path", web::get().to(handler))` (Actix)
+    fn rust_builder_routes(&self, node: Node<'_>, endpoints: &mut Vec<ApiEndpoint>) {
+        let Some(function) = node.child_by_field_name("function").filter(|f| f.kind() == "field_expression") else {
+            return;
+        };
+        if function.child_by_field_name("field").map(|f| self.text(f)) != Some("route") {
+            return;
+        }
+        let Some(arguments) = node.child_by_field_name("arguments") else { return };
+        let mut cursor = arguments.walk();
+        let args: Vec<Node<'_>> = arguments.named_children(&mut cursor).collect();
+        let [path_node, method_router, ..] = args.as_slice() else { return };
+        let Some(path) = self.string_value(*path_node) else { return };
+
+        let framework = if self.content.contains("axum") { "axum" } else { "actix" };
+        for (method, handler) in self.rust_method_chain(*method_router) {
+            self.push(endpoints, &method, path.clone(), handler, framework, node);
+        }
+    }
+
+    /// Unwind `get(a).post(b)` / `web::get().to(a)` into (method, handler) pairs
+    fn rust_method_chain(&self, node: Node<'_>) -> Vec<(String, Option<String>)> {
+        if node.kind() != "call_expression" {
+            return Vec::new();
+        }
+        let Some(function) = node.child_by_field_name("function") else { return Vec::new() };
+        let handler = node
+            .child_by_field_name("arguments")
+            .and_then(Self::first_named_child)
+            .filter(|a| matches!(a.kind(), "identifier" | "scoped_identifier"))
+            .map(|a| self.text(a).to_string());
+
+        match function.kind() {
+            "identifier" | "scoped_identifier" => {
+                let name = self.text(function).rsplit("::").next().unwrap_or("");
+                if HTTP_METHODS.contains(&name) {
+                    vec![(name.to_string(), handler)]
+                } else {
+                    Vec::new()
+                }
+            }
+            "field_expression" => {
+                let mut chain = function
+                    .child_by_field_name("value")
+                    .map(|value| self.rust_method_chain(value))
+                    .unwrap_or_default();
+                let field = function.child_by_field_name("field").map(|f| self.text(f)).unwrap_or("");
+                if field == "to" {
+                    if let Some(last) = chain.last_mut() {
+                        last.1 = handler;
+                    }
+                } else if HTTP_METHODS.contains(&field) {
+                    chain.push((field.to_string(), handler));
+                }
+                chain
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// `@GetMapping("/path")` and `@RequestMapping(value = "/path", method = RequestMethod.POST)`,
+    /// prefixed by the class-level `@RequestMapping`
+    fn spring_routes(&self, node: Node<'_>, endpoints: &mut Vec<ApiEndpoint>) {
+        let Some((method, path, annotation)) = self.spring_mapping(node) else { return };
+        let handler = node.child_by_field_name("name").map(|n| self.text(n).to_string());
+
+        let prefix = node
+            .parent()
+            .filter(|body| body.kind() == "class_body")
+            .and_then(|body| body.parent())
+            .and_then(|class| self.spring_mapping(class))
+            .map(|(_, prefix, _)| prefix)
+            .unwrap_or_default();
+
+        self.push(endpoints, &method, join_paths(&prefix, &path), handler, "spring", annotation);
+    }
+
+    /// (method, path, annotation) from a declaration's mapping annotation
+    fn spring_mapping<'t>(&self, declaration: Node<'t>) -> Option<(String, String, Node<'t>)> {
+        let mut cursor = declaration.walk();
+        let modifiers = declaration.named_children(&mut cursor).find(|c| c.kind() == "modifiers")?;
+
+        let mut cursor = modifiers.walk();
+        let annotations: Vec<Node<'t>> = modifiers
+            .named_children(&mut cursor)
+            .filter(|m| matches!(m.kind(), "annotation" | "marker_annotation"))
+            .collect();
+
+        annotations.into_iter().find_map(|annotation| {
+            let name = annotation.child_by_field_name("name").map(|n| self.text(n))?;
+            let name = name.rsplit('.').next().unwrap_or(name);
+            let method = match name {
+                "GetMapping" => "GET",
+                "PostMapping" => "POST",
+                "PutMapping" => "PUT",
+                "DeleteMapping" => "DELETE",
+                "PatchMapping" => "PATCH",
+                "RequestMapping" => "ANY",
+                _ => return None,
+            };
+
+            let mut path = String::new();
+            let mut method = method.to_string();
+            if let Some(arguments) = annotation.child_by_field_name("arguments") {
+                let mut cursor = arguments.walk();
+                for argument in arguments.named_children(&mut cursor) {
+                    if argument.kind() != "element_value_pair" {
+                        if let Some(value) = self.first_string(argument) {
+                            path = value;
+                        }
+                        continue;
+                    }
+                    let key = argument.child_by_field_name("key").map(|k| self.text(k)).unwrap_or("");
+                    let Some(value) = argument.child_by_field_name("value") else { continue };
+                    match key {
+                        "value" | "path" => path = self.first_string(value).unwrap_or_default(),
+                        "method" => {
+                            let text = self.text(value);
+                            let last = text.trim_matches(|c| c == '{' || c == '}').split(',').next().unwrap_or("");
+                            method = last.trim().rsplit('.').next().unwrap_or("ANY").to_string();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Some((method, path, annotation))
+        })
+    }
+
+    /// A string literal, or the first one in an array initializer
+    fn first_string(&self, node: Node<'_>) -> Option<String> {
+        if let Some(value) = self.string_value(node) {
+            return Some(value);
+        }
+        let mut cursor = node.walk();
+        let found = node.named_children(&mut cursor).find_map(|child| self.string_value(child));
+        found
+    }
+}
+
+/// Strip string prefixes (r, b, f, #) and quotes from a literal
+fn unquote(literal: &str) -> String {
+    literal
+        .trim_start_matches(|c: char| c.is_ascii_alphabetic() || c == '#')
+        .trim_end_matches('#')
+        .trim_matches(|c| c == '"' || c == '\'' || c == '`')
+        .to_string()
+}
+
+/// Join a controller prefix and a method path with exactly one slash
+fn join_paths(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    let path = path.trim_start_matches('/');
+    match (prefix.is_empty(), path.is_empty()) {
+        (true, true) => "/".to_string(),
+        (true, false) => format!("/{}", path),
+        (false, true) => prefix.to_string(),
+        (false, false) => format!("{}/{}", prefix, path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn routes(language: &str, file_path: &str, code: &str) -> Vec<(String, String, Option<String>, String)> {
+        let mut manager = ParserManager::new().unwrap();
+        let tree = manager.parse(code, language).unwrap();
+        RouteExtractor::extract(tree.root_node(), language, file_path, code)
+            .into_iter()
+            .map(|e| (e.method, e.path, e.handler, e.framework))
+            .collect()
+    }
+
+    fn route(method: &str, path: &str, handler: Option<&str>, framework: &str) -> (String, String, Option<String>, String) {
+        (method.to_string(), path.to_string(), handler.map(str::to_string), framework.to_string())
+    }
+
+    #[test]
+    fn test_express_routes() {
+        let code = r#"
+const router = express.Router();
+router.get('/users', listUsers);
+router.post('/users/:id', auth, users.update);
+router.route('/health').get((req, res) => res.send('ok'));
+cache.get('users');
+app.use('/api', router);
+"#;
+        assert_eq!(
+            routes("javascript", "routes.js", code),
+            vec![
+                route("GET", "/users", Some("listUsers"), "express"),
+                route("POST", "/users/:id", Some("users.update"), "express"),
+                route("GET", "/health", None, "express"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_python_routes() {
+        let fastapi = "from fastapi import APIRouter\nrouter = APIRouter()\n\n@router.get(\"/items/{item_id}\")\nasync def read_item(item_id: int):\n    return item_id\n";
+        assert_eq!(
+            routes("python", "api.py", fastapi),
+            vec![route("GET", "/items/{item_id}", Some("read_item"), "fastapi")]
+        );
+
+        let flask = "@app.route('/login', methods=['GET', 'POST'])\ndef login():\n    pass\n\n@cache.memoize(50)\ndef helper():\n    pass\n";
+        assert_eq!(
+            routes("python", "views.py", flask),
+            vec![
+                route("GET", "/login", Some("login"), "flask"),
+                route("POST", "/login", Some("login"), "flask"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rust_routes() {
+        let actix = "use actix_web::{get, web};\n\n#[get(\"/users/{id}\")]\nasync fn get_user(id: web::Path<u32>) -> String {\n    String::new()\n}\n";
+        assert_eq!(
+            routes("rust", "handlers.rs", actix),
+            vec![route("GET", "/users/{id}", Some("get_user"), "actix")]
+        );
+
+        let axum = "use axum::{routing::get, Router};\n\nfn app() -> Router {\n    Router::new().route(\"/users\", get(list_users).post(create_user))\n}\n";
+        assert_eq!(
+            routes("rust", "main.rs", axum),
+            vec![
+                route("GET", "/users", Some("list_users"), "axum"),
+                route("POST", "/users", Some("create_user"), "axum"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spring_routes() {
+        let code = r#"
+@RestController
+@RequestMapping("/api/orders")
+public class OrderController {
+    @GetMapping("/{id}")
+    public Order get(@PathVariable long id) { return null; }
+
+    @RequestMapping(value = "/search", method = RequestMethod.POST)
+    public List<Order> search() { return null; }
+
+    @PostMapping
+    public Order create() { return null; }
+}
+"#;
+        assert_eq!(
+            routes("java", "OrderController.java", code),
+            vec![
+                route("GET", "/api/orders/{id}", Some("get"), "spring"),
+                route("POST", "/api/orders/search", Some("search"), "spring"),
+                route("POST", "/api/orders", Some("create"), "spring"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_join_paths() {
+        assert_eq!(join_paths("", ""), "/");
+        assert_eq!(join_paths("/api/", "/users"), "/api/users");
+        assert_eq!(join_paths("", "users"), "/users");
+    }
+}
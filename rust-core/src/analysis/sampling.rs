@@ -0,0 +1,239 @@
+//! Stratified file sampling for repositories larger than the file cap
+//!
+//! Instead of stopping at the first N files in walk order, candidates are grouped
+//! by top-level directory and language and the cap is shared between the groups
+//! in proportion to their size, with every group guaranteed at least one file
+//! when the cap allows. Within a group, files are picked at even intervals in
+//! path order so nested directories are covered too.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A file eligible for analysis
+#[derive(Debug, Clone)]
+pub struct SampleCandidate {
+    pub path: PathBuf,
+    pub language: String,
+}
+
+/// How many files of one directory/language group were analyzed
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct SamplingStratum {
+    /// Top-level directory relative to the analyzed root, or "." for root files
+    pub directory: String,
+    pub language: String,
+    pub total_files: u32,
+    pub sampled_files: u32,
+}
+
+/// What a sampled analysis covered and what it skipped
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct SamplingReport {
+    pub total_files: u32,
+    pub sampled_files: u32,
+    pub skipped_files: u32,
+    pub file_limit: u32,
+    pub strata: Vec<SamplingStratum>,
+}
+
+pub struct FileSampler;
+
+impl FileSampler {
+    /// Pick at most `limit` candidates. Returns the chosen paths in path order and,
+    /// when anything was skipped, a report of the coverage per stratum.
+    pub fn sample(
+        root: &Path,
+        candidates: Vec<SampleCandidate>,
+        limit: usize,
+    ) -> (Vec<PathBuf>, Option<SamplingReport>) {
+        if candidates.len() <= limit {
+            let mut paths: Vec<PathBuf> = candidates.into_iter().map(|c| c.path).collect();
+            paths.sort();
+            return (paths, None);
+        }
+
+        let total = candidates.len();
+        let mut strata: BTreeMap<(String, String), Vec<PathBuf>> = BTreeMap::new();
+        for candidate in candidates {
+            let directory = Self::top_level_directory(root, &candidate.path);
+            strata
+                .entry((directory, candidate.language))
+                .or_default()
+                .push(candidate.path);
+        }
+
+        let sizes: Vec<usize> = strata.values().map(|paths| paths.len()).collect();
+        let quotas = Self::allocate(&sizes, limit);
+
+        let mut selected = Vec::with_capacity(limit);
+        let mut report_strata = Vec::with_capacity(strata.len());
+        for (((directory, language), mut paths), quota) in strata.into_iter().zip(quotas) {
+            paths.sort();
+            report_strata.push(SamplingStratum {
+                directory,
+                language,
+                total_files: paths.len() as u32,
+                sampled_files: quota as u32,
+            });
+            selected.extend(Self::spread(&paths, quota));
+        }
+        selected.sort();
+
+        let report = SamplingReport {
+            total_files: total as u32,
+            sampled_files: selected.len() as u32,
+            skipped_files: (total - selected.len()) as u32,
+            file_limit: limit as u32,
+            strata: report_strata,
+        };
+        (selected, Some(report))
+    }
+
+    /// Split `limit` across strata: one file each (largest strata first if the
+    /// limit is smaller than the number of strata), the rest proportionally to
+    /// the files left in each stratum using largest remainders
+    fn allocate(sizes: &[usize], limit: usize) -> Vec<usize> {
+        let mut quotas = vec![0usize; sizes.len()];
+        let mut by_size: Vec<usize> = (0..sizes.len()).filter(|&i| sizes[i] > 0).collect();
+        by_size.sort_by(|&a, &b| sizes[b].cmp(&sizes[a]).then(a.cmp(&b)));
+
+        if by_size.len() >= limit {
+            for &i in by_size.iter().take(limit) {
+                quotas[i] = 1;
+            }
+            return quotas;
+        }
+
+        for &i in &by_size {
+            quotas[i] = 1;
+        }
+        let remaining = limit - by_size.len();
+        let leftover: usize = by_size.iter().map(|&i| sizes[i] - 1).sum();
+        if leftover == 0 {
+            return quotas;
+        }
+
+        let mut remainders = Vec::with_capacity(by_size.len());
+        let mut assigned = 0;
+        for &i in &by_size {
+            let exact = remaining as f64 * (sizes[i] - 1) as f64 / leftover as f64;
+            let share = (exact.floor() as usize).min(sizes[i] - 1);
+            quotas[i] += share;
+            assigned += share;
+            remainders.push((exact - share as f64, i));
+        }
+        remainders.sort_by(|a, b| {
+            b.0.partial_cmp(&a.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.1.cmp(&b.1))
+        });
+        for (_, i) in remainders {
+            if assigned >= remaining.min(leftover) {
+                break;
+            }
+            if quotas[i] < sizes[i] {
+                quotas[i] += 1;
+                assigned += 1;
+            }
+        }
+        quotas
+    }
+
+    /// `count` paths at even intervals through `paths`
+    fn spread(paths: &[PathBuf], count: usize) -> Vec<PathBuf> {
+        if count >= paths.len() {
+            return paths.to_vec();
+        }
+        (0..count)
+            .map(|i| paths[i * paths.len() / count].clone())
+            .collect()
+    }
+
+    fn top_level_directory(root: &Path, path: &Path) -> String {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let mut components = relative.components();
+        match (components.next(), components.next()) {
+            (Some(first), Some(_)) => first.as_os_str().to_string_lossy().to_string(),
+            _ => ".".to_string(),
+        }
+    }
+}
+
+impl SamplingReport {
+    /// One-line summary for logs
+    pub fn summary(&self) -> String {
+        format!(
+            "Sampled {} of {} files (limit {}) across {} directory/language groups; skipped {}",
+            self.sampled_files,
+            self.total_files,
+            self.file_limit,
+            self.strata.len(),
+            self.skipped_files
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(dir: &str, language: &str, extension: &str, count: usize) -> Vec<SampleCandidate> {
+        (0..count)
+            .map(|i| SampleCandidate {
+                path: PathBuf::from(format!("/repo/{}/file{:03}.{}", dir, i, extension)),
+                language: language.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_under_limit_keeps_everything() {
+        let (paths, report) = FileSampler::sample(Path::new("/repo"), candidates("src", "rust", "rs", 3), 10);
+        assert_eq!(paths.len(), 3);
+        assert!(report.is_none());
+    }
+
+    #[test]
+    fn test_every_stratum_is_represented() {
+        let mut all = candidates("aaa_generated", "typescript", "ts", 90);
+        all.extend(candidates("src", "rust", "rs", 8));
+        all.extend(candidates("tools", "python", "py", 2));
+
+        let (paths, report) = FileSampler::sample(Path::new("/repo"), all, 10);
+        let report = report.unwrap();
+
+        assert_eq!(paths.len(), 10);
+        assert_eq!(report.total_files, 100);
+        assert_eq!(report.skipped_files, 90);
+        for stratum in &report.strata {
+            assert!(stratum.sampled_files >= 1, "{} was not sampled", stratum.directory);
+        }
+        assert!(paths.iter().any(|p| p.starts_with("/repo/tools")));
+        assert!(paths.iter().any(|p| p.starts_with("/repo/src")));
+    }
+
+    #[test]
+    fn test_quotas_follow_stratum_size() {
+        let quotas = FileSampler::allocate(&[60, 30, 10], 10);
+        assert_eq!(quotas.iter().sum::<usize>(), 10);
+        assert!(quotas[0] > quotas[1] && quotas[1] >= quotas[2]);
+
+        // More strata than the limit: the largest strata get one file each
+        assert_eq!(FileSampler::allocate(&[5, 50, 20], 2), vec![0, 1, 1]);
+    }
+
+    #[test]
+    fn test_sample_spreads_within_stratum() {
+        let (paths, _) = FileSampler::sample(Path::new("/repo"), candidates("src", "rust", "rs", 100), 4);
+        let names: Vec<String> = paths
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["file000.rs", "file025.rs", "file050.rs", "file075.rs"]);
+    }
+}
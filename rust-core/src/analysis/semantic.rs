@@ -3,22 +3,52 @@
 #[cfg(feature = "napi-bindings")]
 use napi_derive::napi;
 
-use crate::types::{SemanticConcept, CodebaseAnalysisResult, ParseError, AnalysisConfig};
-use crate::parsing::{ParserManager, FallbackExtractor, TreeWalker};
+use crate::types::{sort_concepts, SemanticConcept, CodebaseAnalysisResult, ParseError, AnalysisConfig, ProjectConfig, FileClassifier, mark_generated};
+use crate::types::validation::{self, AnalysisInput};
+use crate::types::paths;
+use crate::parsing::{FileWalker, ParserManager, FallbackExtractor, TreeWalker, QueryRegistry, QueryExtractor};
 use crate::extractors::*;
-use crate::analysis::{ComplexityAnalyzer, RelationshipLearner, FrameworkDetector};
+use crate::patterns::NamingPatternAnalyzer;
+use crate::quotas::EvictionPolicy;
+use crate::analysis::{ComplexityAnalyzer, DataModelExtractor, RelationshipLearner, FrameworkDetector, ConfidenceCalibrator, ExtractionMethod, OwnershipAnalyzer, ConceptDiffer, FileConceptDiff, FileSampler, SampleCandidate, SamplingReport, PartialResult, PendingRun, AnalysisRecorder, AnalysisReport, FileTiming, ConceptTagStore, TagGroup, GraphStore, GraphSlice, RelationshipEdge, RelationshipKind, EdgeDirection, MAX_GRAPH_DEPTH, SourceWalker, Pacer, nice_level, SharedTelemetry, noop_telemetry, FileOverlay, CrossRootLinker, MultiRootAnalysis, RootAnalysis, DeprecationExtractor, RefactoringDetector, RefactoringSuggestion, RefactoringThresholds, DomainEntityUnifier, SharedDomainEntity, SHARED_ENTITY_TYPE, QuickScanner, QuickScanSummary, ScannedFile, QUICK_SCAN_BUDGET, QUICK_SCAN_SAMPLE_SIZE, ApiContractExtractor, ConceptContext, ConceptContextExtractor, DEFAULT_CONTEXT_LINES, MAX_CONTEXT_LINES, ConceptSnapshot, WarmStartReport, Tombstone, TombstoneStore};
 
-use std::collections::HashMap;
-use walkdir::WalkDir;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 
+/// Time one file may spend in tree-sitter extraction before fallback extraction is used
+const PARSE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
 /// Main semantic analyzer that orchestrates concept extraction across languages
 #[cfg_attr(feature = "napi-bindings", napi)]
 pub struct SemanticAnalyzer {
     parser_manager: ParserManager,
+    query_registry: QueryRegistry,
     config: AnalysisConfig,
+    /// Coverage of the last codebase walk that hit the file limit
+    sampling_report: Option<SamplingReport>,
+    /// Timings and skips from the last codebase walk
+    recorder: AnalysisRecorder,
     concepts: HashMap<String, SemanticConcept>,
-    relationships: HashMap<String, Vec<String>>,
+    /// Typed edges between the stored concepts, saved with the project
+    graph: GraphStore,
+    /// Learning run that ran out of time, kept until it is resumed or restarted
+    pending_run: Option<PendingRun>,
+    /// User-defined concept tags of the project being analyzed
+    tag_store: ConceptTagStore,
+    /// Root tags are saved under; set by learning or `load_concept_tags`
+    project_root: Option<String>,
+    /// Nice level set by the host, taking precedence over the project's
+    nice: Option<u8>,
+    /// Receives per-file, timeout and fallback events; a no-op unless the host sets one
+    telemetry: SharedTelemetry,
+    /// Contents read instead of the files on disk during codebase analysis
+    overlay: FileOverlay,
+    /// Entities found by the last `unify_domain_entities`
+    shared_entities: Vec<SharedDomainEntity>,
+    /// What the saved concepts loaded last kept and dropped
+    warm_start: Option<WarmStartReport>,
+    /// Files found removed by complete learning runs, whose concepts were dropped
+    tombstones: TombstoneStore,
 }
 
 #[cfg_attr(feature = "napi-bindings", napi)]
@@ -27,12 +57,135 @@ impl SemanticAnalyzer {
     pub fn new() -> Result<Self, ParseError> {
         Ok(SemanticAnalyzer {
             parser_manager: ParserManager::new()?,
+            query_registry: QueryRegistry::new(),
             config: AnalysisConfig::default(),
+            sampling_report: None,
+            recorder: AnalysisRecorder::new(),
             concepts: HashMap::new(),
-            relationships: HashMap::new(),
+            graph: GraphStore::default(),
+            pending_run: None,
+            tag_store: ConceptTagStore::default(),
+            project_root: None,
+            nice: None,
+            telemetry: noop_telemetry(),
+            overlay: FileOverlay::default(),
+            shared_entities: Vec::new(),
+            warm_start: None,
+            tombstones: TombstoneStore::default(),
         })
     }
 
+    /// An analyzer for a project, warm-started from the concepts its last
+    /// complete learning run saved
+    ///
+    /// Loads the project's configuration and concept tags, then its saved
+    /// concepts (see `load_concept_snapshot`), or only its saved graph when
+    /// no concepts were saved.
+    #[cfg_attr(feature = "napi-bindings", napi(factory))]
+    pub fn open(project_path: String) -> Result<Self, ParseError> {
+        validation::require_directory(&project_path)?;
+        let mut analyzer = Self::new()?;
+        analyzer.load_project_config(project_path.clone())?;
+        analyzer.load_concept_tags(project_path.clone())?;
+        if !analyzer.load_concept_snapshot(project_path.clone())? {
+            analyzer.load_concept_graph(project_path)?;
+        }
+        Ok(analyzer)
+    }
+
+    /// Apply the `.in-memoria` configuration found in a project root
+    ///
+    /// Returns false and keeps the current settings when the project has no
+    /// configuration file; concept ids are keyed relative to the root either
+    /// way. Codebase analysis calls this for the analyzed path.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn load_project_config(&mut self, project_path: String) -> Result<bool, ParseError> {
+        let root = SourceWalker::canonical_root(std::path::Path::new(&project_path));
+        match ProjectConfig::load(&root)? {
+            Some(project) => {
+                self.config = AnalysisConfig::from_project_config(&project, &root)?;
+                Ok(true)
+            }
+            None => {
+                // Concept ids are still keyed relative to the project
+                self.config.project_root = Some(root);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Set how often codebase learning yields to other work on the runtime:
+    /// 0 never yields, 4 yields after every file. Overrides the project's
+    /// `analysis.nice` setting.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn set_nice(&mut self, level: u32) -> Result<(), ParseError> {
+        self.nice = Some(nice_level(level)?);
+        Ok(())
+    }
+
+    /// Analyze these contents instead of the files on disk, e.g. unsaved editor
+    /// buffers, until the overlay is cleared or replaced
+    ///
+    /// Keys are absolute or project-relative paths. Overlaid files missing on
+    /// disk are analyzed as if they existed, so a project made only of
+    /// overlaid files can be analyzed under a root that doesn't exist.
+    /// Returns the number of overlaid files.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn set_file_overlay(&mut self, files: HashMap<String, String>) -> u32 {
+        self.overlay = FileOverlay::new(files);
+        self.overlay.len() as u32
+    }
+
+    /// Go back to reading every file from disk
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn clear_file_overlay(&mut self) {
+        self.overlay = FileOverlay::default();
+    }
+
+    /// Send analysis events to a host-side telemetry implementation
+    pub fn set_telemetry(&mut self, telemetry: SharedTelemetry) {
+        self.telemetry = telemetry;
+    }
+
+    /// Call `callback` with a `TelemetryEvent` for every file analyzed,
+    /// parse timeout and fallback extraction. The callback runs without
+    /// blocking analysis.
+    #[cfg(feature = "napi-bindings")]
+    #[napi]
+    pub fn set_telemetry_callback(
+        &mut self,
+        callback: napi::threadsafe_function::ThreadsafeFunction<
+            crate::analysis::TelemetryEvent,
+            (),
+            crate::analysis::TelemetryEvent,
+            napi::Status,
+            false,
+        >,
+    ) {
+        self.telemetry = std::sync::Arc::new(crate::analysis::CallbackTelemetry::new(callback));
+    }
+
+    /// What the last `open` or `load_concept_snapshot` kept from the saved
+    /// concepts and which files changed since; `None` before either
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_warm_start_report(&self) -> Option<WarmStartReport> {
+        self.warm_start.clone()
+    }
+
+    /// Which files the last codebase analysis sampled and skipped, if it hit the file limit
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_sampling_report(&self) -> Option<SamplingReport> {
+        self.sampling_report.clone()
+    }
+
+    /// Diagnostics for the last codebase analysis: the slowest files (20 unless
+    /// `limit` is given), per-file timeouts, fallback usage and skipped files
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_analysis_report(&self, limit: Option<u32>) -> AnalysisReport {
+        self.recorder
+            .report(limit.map(|l| l as usize), self.sampling_report.clone())
+    }
+
     /// Analyzes an entire codebase for semantic concepts and patterns
     /// 
     /// # Safety
@@ -43,10 +196,12 @@ impl SemanticAnalyzer {
         &mut self,
         path: String,
     ) -> Result<CodebaseAnalysisResult, ParseError> {
+        self.overlay.require_root(&path)?;
         let languages = self.detect_languages(&path).await?;
         let framework_info = FrameworkDetector::detect_frameworks(path.clone()).await?;
         let frameworks: Vec<String> = framework_info.into_iter().map(|f| f.name).collect();
-        let concepts = self.extract_concepts(&path).await?;
+        let mut concepts = self.extract_concepts(&path).await?;
+        sort_concepts(&mut concepts);
         let complexity = ComplexityAnalyzer::calculate_complexity(&concepts);
 
         Ok(CodebaseAnalysisResult {
@@ -57,6 +212,137 @@ impl SemanticAnalyzer {
         })
     }
 
+    /// Instant summary of a project from a stratified sample of its files
+    ///
+    /// Reads at most `sample_size` files (150 by default) for about a second
+    /// and a half. File counts per language are exact; lines, naming
+    /// conventions and framework usage are estimates with 95% intervals.
+    /// Nothing is added to the analyzer's concepts, and the scanned project's
+    /// configuration is used for the scan only.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn quick_scan(
+        &self,
+        path: String,
+        sample_size: Option<u32>,
+    ) -> Result<QuickScanSummary, ParseError> {
+        let start = std::time::Instant::now();
+        let deadline = start + QUICK_SCAN_BUDGET;
+        let sample_size = match sample_size {
+            Some(0) => {
+                return Err(validation::ValidationError::new(
+                    validation::ErrorCode::InvalidArgument,
+                    "sample_size must be at least 1",
+                )
+                .into())
+            }
+            Some(size) => size as usize,
+            None => QUICK_SCAN_SAMPLE_SIZE,
+        };
+        validation::require_directory(&path)?;
+        let root = SourceWalker::canonical_root(std::path::Path::new(&path));
+        // Parsed with the scanned project's configuration, leaving this analyzer's alone
+        let mut scanner = SemanticAnalyzer::new()?;
+        let project = ProjectConfig::load(&root)?.unwrap_or_default();
+        scanner.config = AnalysisConfig::from_project_config(&project, &root)?;
+        let config = &scanner.config;
+
+        let walk = SourceWalker::new(config.follow_symlinks).walk(&root, |_| true);
+        let mut population: BTreeMap<String, u32> = BTreeMap::new();
+        let mut candidates = Vec::new();
+        for file_path in walk.files {
+            if config.exclusion(&file_path).is_some() {
+                continue;
+            }
+            let language = config.detect_file_language(&file_path);
+            *population.entry(language.clone()).or_insert(0) += 1;
+            candidates.push(SampleCandidate { path: file_path, language });
+        }
+        let total_files = candidates.len() as u32;
+        let (sample, _) = FileSampler::sample(&root, candidates, sample_size);
+
+        let mut scanned = Vec::new();
+        let mut complete = true;
+        for file_path in &sample {
+            if !scanned.is_empty() && std::time::Instant::now() >= deadline {
+                complete = false;
+                break;
+            }
+            let Ok(content) = self.overlay.read_to_string(file_path, &root) else {
+                continue;
+            };
+            let file_path = file_path.to_string_lossy();
+            let language = scanner.config.detect_language(&file_path, &content);
+            let concepts = scanner.extract_unstored(&file_path, &content);
+            scanned.push(ScannedFile::new(&language, &content, &concepts));
+        }
+
+        let frameworks = FrameworkDetector::detect_frameworks(path).await?;
+        let (languages, conventions, frameworks) = QuickScanner::summarize(&population, &scanned, frameworks);
+        Ok(QuickScanSummary {
+            total_files,
+            sampled_files: scanned.len() as u32,
+            complete,
+            elapsed_ms: start.elapsed().as_millis() as u32,
+            languages,
+            conventions,
+            frameworks,
+        })
+    }
+
+    /// Analyze several project roots of one workspace, e.g. `frontend/`,
+    /// `backend/` and `infra/`, in a single call
+    ///
+    /// Each root is analyzed with its own configuration. The combined result
+    /// treats the workspace as one project, and cross-root edges record HTTP
+    /// calls, OpenAPI client calls and package dependencies from one root to
+    /// another, found from the files on disk.
+    ///
+    /// # Safety
+    /// This function is marked unsafe for NAPI compatibility. It performs file system operations
+    /// and language parsing that are inherently safe but marked unsafe for JavaScript interop.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async unsafe fn analyze_roots(&mut self, root_paths: Vec<String>) -> Result<MultiRootAnalysis, ParseError> {
+        if root_paths.is_empty() {
+            return Err(validation::ValidationError::new(
+                validation::ErrorCode::InvalidArgument,
+                "root_paths must name at least one project root",
+            )
+            .into());
+        }
+        let mut roots = Vec::new();
+        for path in &root_paths {
+            self.overlay.require_root(path)?;
+            let root = SourceWalker::canonical_root(std::path::Path::new(path));
+            if roots.contains(&root) {
+                return Err(validation::ValidationError::new(
+                    validation::ErrorCode::InvalidArgument,
+                    format!("Project root listed twice: {}", path),
+                )
+                .into());
+            }
+            roots.push(root);
+        }
+
+        let labels = CrossRootLinker::labels(&roots);
+        let mut analyses = Vec::new();
+        let mut surfaces = Vec::new();
+        for ((path, root), label) in root_paths.into_iter().zip(&roots).zip(labels) {
+            let analysis = self.analyze_codebase(path).await?;
+            surfaces.push(CrossRootLinker::scan(root, &label)?);
+            analyses.push(RootAnalysis {
+                root: paths::normalize_path(&root.to_string_lossy()),
+                label,
+                analysis,
+            });
+        }
+
+        Ok(MultiRootAnalysis {
+            combined: CrossRootLinker::combine(&analyses),
+            cross_root_edges: CrossRootLinker::link(&surfaces),
+            roots: analyses,
+        })
+    }
+
     /// Analyzes the content of a specific file for semantic concepts
     /// 
     /// # Safety
@@ -68,7 +354,8 @@ impl SemanticAnalyzer {
         file_path: String,
         content: String,
     ) -> Result<Vec<SemanticConcept>, ParseError> {
-        let language = self.config.detect_language_from_path(&file_path);
+        validation::require_non_empty("file_path", &file_path)?;
+        let language = self.config.detect_language(&file_path, &content);
 
         let concepts = match self
             .parse_file_content(&file_path, &content, &language)
@@ -77,7 +364,8 @@ impl SemanticAnalyzer {
             Ok(tree_concepts) => tree_concepts,
             Err(_) => {
                 // Fallback to pattern-based extraction for unsupported languages
-                FallbackExtractor::new().extract_concepts(&file_path, &content)
+                self.telemetry.on_fallback_used(&file_path, "parse_error");
+                self.fallback_concepts(&file_path, &content)
             }
         };
 
@@ -85,11 +373,15 @@ impl SemanticAnalyzer {
         for concept in &concepts {
             self.concepts.insert(concept.id.clone(), concept.clone());
         }
+        self.relearn_file_relationships(concepts.iter().map(|concept| concept.file_path.clone()).collect());
 
         Ok(concepts)
     }
 
     /// Learns semantic concepts from analyzing an entire codebase
+    ///
+    /// When the learning time budget runs out, the concepts found so far are
+    /// kept and the error carries a resume token for `learn_from_codebase_partial`.
     /// 
     /// # Safety
     /// This function is marked unsafe for NAPI compatibility. It performs file system operations
@@ -99,54 +391,437 @@ impl SemanticAnalyzer {
         &mut self,
         path: String,
     ) -> Result<Vec<SemanticConcept>, ParseError> {
-        // Add overall timeout for the entire learning process (5 minutes)
-        let learning_result = match tokio::time::timeout(
-            tokio::time::Duration::from_secs(300),
-            self.extract_concepts(&path)
-        ).await {
-            Ok(concepts_result) => concepts_result?,
-            Err(_timeout) => {
-                eprintln!("Learning process timed out after 5 minutes");
-                return Err(ParseError::from_reason(
-                    "Learning process timed out. This can happen with very large codebases or complex file structures."
-                ));
-            }
-        };
-
-        // Learn relationships between concepts
-        RelationshipLearner::learn_concept_relationships(&learning_result, &mut self.relationships);
-
-        // Update internal knowledge
-        for concept in &learning_result {
-            self.concepts.insert(concept.id.clone(), concept.clone());
+        let result = self.learn_until_deadline(&path, None).await?;
+        match result.resume_token {
+            None => Ok(result.concepts),
+            Some(token) => Err(ParseError::from_reason(format!(
+                "Learning process timed out after {} files with {} remaining. This can happen with very large codebases or complex file structures. Continue with learn_from_codebase_partial using resume token {}.",
+                result.files_processed,
+                result.files_remaining.len(),
+                token
+            ))),
         }
+    }
 
-        Ok(learning_result)
+    /// Learns semantic concepts from a codebase, returning what was learned when
+    /// the time budget runs out instead of failing
+    ///
+    /// Pass the `resume_token` of an incomplete result to continue that run with
+    /// the files it did not reach.
+    ///
+    /// # Safety
+    /// This function is marked unsafe for NAPI compatibility. It performs file system operations
+    /// and language parsing that are inherently safe but marked unsafe for JavaScript interop.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async unsafe fn learn_from_codebase_partial(
+        &mut self,
+        path: String,
+        resume_token: Option<String>,
+    ) -> Result<PartialResult, ParseError> {
+        self.learn_until_deadline(&path, resume_token.as_deref()).await
+    }
+
+    /// Continue a learning run from the checkpoint file it left behind
+    ///
+    /// Runs write `.in-memoria/learning-checkpoint.json` every `checkpointInterval`
+    /// files, so a run in a process that crashed or was killed picks up with the
+    /// files it had not completed. Returns the same result as `learn_from_codebase_partial`.
+    ///
+    /// # Safety
+    /// This function is marked unsafe for NAPI compatibility. It performs file system operations
+    /// and language parsing that are inherently safe but marked unsafe for JavaScript interop.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async unsafe fn resume_learning(&mut self, checkpoint_path: String) -> Result<PartialResult, ParseError> {
+        let checkpoint_path = validation::require_path(&checkpoint_path)?;
+        let run = PendingRun::load_checkpoint(&checkpoint_path)?;
+        validation::require_directory(&run.root)?;
+        self.load_project_config(run.root.clone())?;
+        self.load_concept_tags(run.root.clone())?;
+
+        let root = run.root.clone();
+        let token = run.token();
+        self.pending_run = Some(run);
+        self.learn_until_deadline(&root, Some(&token)).await
     }
 
     /// Updates the analyzer's internal state from analysis data (from original implementation)
     ///
+    /// Reported concepts are stored for relationship queries. Malformed data is
+    /// rejected with an `INVALID_JSON` or `SCHEMA_MISMATCH` error.
+    ///
     /// # Safety
-    /// This function uses unsafe because it needs to interact with the Node.js runtime
-    /// through N-API bindings. The caller must ensure the analysis data is valid JSON.
+    /// This function is marked unsafe for NAPI compatibility. It performs data parsing
+    /// that is inherently safe but marked unsafe for JavaScript interop.
     #[cfg_attr(feature = "napi-bindings", napi)]
     pub async unsafe fn update_from_analysis(
         &mut self,
-        _analysis_data: String,
+        analysis_data: String,
     ) -> Result<bool, ParseError> {
-        // Parse analysis data and update internal state
-        // This would typically be called when file changes are detected
+        let analysis: AnalysisInput = validation::parse_json(&analysis_data, "analysis data")?;
+        let mut concepts: Vec<SemanticConcept> =
+            analysis.concepts.into_iter().map(|concept| concept.into_concept()).collect();
+        self.assign_concept_ids(&mut concepts);
+        let files = concepts.iter().map(|concept| concept.file_path.clone()).collect();
+        for concept in concepts {
+            self.concepts.insert(concept.id.clone(), concept);
+        }
+        self.relearn_file_relationships(files);
         Ok(true)
     }
 
-    /// Get concept relationships for a specific concept ID (from original implementation)
+    /// Relationships of a concept as `kind:conceptId` strings; relationships
+    /// pointing at the concept use the inverse kind, e.g. `called_by:conceptId`
     #[cfg_attr(feature = "napi-bindings", napi)]
     pub fn get_concept_relationships(&self, concept_id: String) -> Result<Vec<String>, ParseError> {
-        Ok(self
-            .relationships
-            .get(&concept_id)
+        Ok(self.graph.graph().describe(&concept_id))
+    }
+
+    /// Typed relationship edges of a concept
+    ///
+    /// `kind` limits the edges to calls, imports, extends, implements, uses,
+    /// contains or documents; `direction` is outgoing, incoming or both (the default).
+    /// Unknown kinds and directions are rejected with `INVALID_ARGUMENT`.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_relationship_edges(
+        &self,
+        concept_id: String,
+        kind: Option<String>,
+        direction: Option<String>,
+    ) -> Result<Vec<RelationshipEdge>, ParseError> {
+        validation::require_non_empty("concept_id", &concept_id)?;
+        let (kind, direction) = Self::edge_filter(kind, direction)?;
+        Ok(self.graph.graph().edges(&concept_id, kind, direction))
+    }
+
+    /// Concepts up to `depth` relationships away from a concept (1 by default,
+    /// at most 5) and the relationships between them
+    ///
+    /// `kind` and `direction` limit the relationships followed, as for
+    /// `get_relationship_edges`.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_concept_neighbors(
+        &self,
+        concept_id: String,
+        depth: Option<u32>,
+        kind: Option<String>,
+        direction: Option<String>,
+    ) -> Result<GraphSlice, ParseError> {
+        self.require_graph_node(&concept_id)?;
+        let depth = Self::graph_depth(depth)?;
+        let (kind, direction) = Self::edge_filter(kind, direction)?;
+        Ok(self.graph.neighbors(&concept_id, kind, direction, depth))
+    }
+
+    /// A concept's source with `before_lines` and `after_lines` around it (3
+    /// by default, at most 200), formatted for a prompt
+    ///
+    /// Unless `include_related` is false, the signatures of the concepts it is
+    /// directly related to are added, each with the relationship seen from
+    /// the concept, e.g. `calls` or `called_by`.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_concept_context(
+        &self,
+        concept_id: String,
+        before_lines: Option<u32>,
+        after_lines: Option<u32>,
+        include_related: Option<bool>,
+    ) -> Result<ConceptContext, ParseError> {
+        validation::require_non_empty("concept_id", &concept_id)?;
+        let concept = self.stored_concept(&concept_id).ok_or_else(|| {
+            validation::ValidationError::new(
+                validation::ErrorCode::InvalidArgument,
+                format!("Unknown concept: {}", concept_id),
+            )
+        })?;
+        let before_lines = Self::context_lines("before_lines", before_lines)?;
+        let after_lines = Self::context_lines("after_lines", after_lines)?;
+
+        let path = self.source_path(&concept.file_path);
+        let content = self
+            .read_source(&path)
+            .map_err(|e| ParseError::from_reason(format!("Failed to read {}: {}", path.display(), e)))?;
+        let mut context = ConceptContextExtractor::extract(concept, &content, before_lines, after_lines);
+        if !include_related.unwrap_or(true) {
+            return Ok(context);
+        }
+
+        let mut sources: HashMap<String, Option<String>> = HashMap::from([(concept.file_path.clone(), Some(content))]);
+        for edge in self.graph.graph().edges_of(&concept_id, None, EdgeDirection::Both) {
+            let (other, relationship) = if edge.from == concept_id {
+                (&edge.to, edge.kind.clone())
+            } else {
+                let kind = RelationshipKind::parse(&edge.kind);
+                (&edge.from, kind.map_or_else(|| edge.kind.clone(), |kind| kind.inverse().to_string()))
+            };
+            let Some(related) = self.concepts.get(other.as_str()) else { continue };
+            let source = sources
+                .entry(related.file_path.clone())
+                .or_insert_with(|| self.read_source(&self.source_path(&related.file_path)).ok());
+            ConceptContextExtractor::add_related(&mut context, related, &relationship, source.as_deref());
+        }
+        Ok(context)
+    }
+
+    /// Shortest chain of relationships linking two concepts, followed in
+    /// either direction; empty when they aren't connected
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn find_concept_path(&self, from_id: String, to_id: String) -> Result<Vec<RelationshipEdge>, ParseError> {
+        self.require_graph_node(&from_id)?;
+        self.require_graph_node(&to_id)?;
+        Ok(self.graph.shortest_path(&from_id, &to_id).unwrap_or_default())
+    }
+
+    /// Everything involved in a feature: concepts whose name, file path or
+    /// tags mention `feature`, concepts up to `depth` relationships from them
+    /// (1 by default, at most 5) and the relationships between them
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_feature_subgraph(&self, feature: String, depth: Option<u32>) -> Result<GraphSlice, ParseError> {
+        validation::require_non_empty("feature", &feature)?;
+        let depth = Self::graph_depth(depth)?;
+        Ok(self.graph.feature_subgraph(&feature, depth))
+    }
+
+    /// Long functions, god classes, feature envy and shotgun surgery among the
+    /// stored concepts, with the measurements behind each suggestion
+    ///
+    /// Unset thresholds use the defaults; zero thresholds are rejected with
+    /// `INVALID_ARGUMENT`.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn find_refactoring_opportunities(
+        &self,
+        thresholds: Option<RefactoringThresholds>,
+    ) -> Result<Vec<RefactoringSuggestion>, ParseError> {
+        let thresholds = thresholds.unwrap_or_default();
+        thresholds.validate()?;
+        Ok(RefactoringDetector::detect(self.concepts.values(), self.graph.graph(), &thresholds))
+    }
+
+    /// Link the declarations of one entity in different languages, such as a
+    /// Rust struct, a TypeScript interface and a SQL table
+    ///
+    /// Each entity is stored as a `shared_domain_entity` concept whose
+    /// `unifies` relationship lists its members, replacing the entities of
+    /// the previous call.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn unify_domain_entities(&mut self) -> Vec<SharedDomainEntity> {
+        self.concepts.retain(|_, concept| concept.concept_type != SHARED_ENTITY_TYPE);
+        let mut concepts: Vec<SemanticConcept> = self.concepts.values().cloned().collect();
+        sort_concepts(&mut concepts);
+
+        let entities = DomainEntityUnifier::unify(
+            &concepts,
+            |file_path| self.config.detect_language_from_path(file_path),
+            |file_path| self.read_source(&self.source_path(file_path)).ok(),
+        );
+        for entity in &entities {
+            let concept = DomainEntityUnifier::to_concept(entity);
+            self.concepts.insert(concept.id.clone(), concept);
+        }
+        self.relearn_relationships();
+        self.shared_entities = entities.clone();
+        entities
+    }
+
+    /// The shared entity with this id or declared by this concept; its other
+    /// members are the declarations a change to the concept likely affects
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_shared_entity(&self, concept_id: String) -> Option<SharedDomainEntity> {
+        self.shared_entities
+            .iter()
+            .find(|entity| entity.id == concept_id || entity.members.iter().any(|member| member.concept_id == concept_id))
             .cloned()
-            .unwrap_or_default())
+    }
+
+    /// Replace the relationship graph with the one saved in a project by its
+    /// last learning run; returns false when the project has none
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn load_concept_graph(&mut self, project_path: String) -> Result<bool, ParseError> {
+        let root = validation::require_directory(&project_path)?;
+        match GraphStore::load(&root)? {
+            Some(graph) => {
+                self.graph = graph;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Replace the stored concepts with those saved in a project by its last
+    /// complete learning run and rebuild the relationship graph over them;
+    /// returns false, changing nothing, when the project has none
+    ///
+    /// Only the concepts of files whose content hasn't changed since are
+    /// kept; `get_warm_start_report` lists the files whose concepts were
+    /// dropped.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn load_concept_snapshot(&mut self, project_path: String) -> Result<bool, ParseError> {
+        let root = validation::require_directory(&project_path)?;
+        let Some(mut snapshot) = ConceptSnapshot::load(&root)? else {
+            return Ok(false);
+        };
+
+        self.tombstones = TombstoneStore::new(std::mem::take(&mut snapshot.tombstones));
+        let (concepts, report) = snapshot.validate(|file_path| self.read_source(&self.source_path(file_path)).ok());
+        self.concepts.clear();
+        for mut concept in concepts {
+            self.tag_store.apply(&mut concept);
+            self.concepts.insert(concept.id.clone(), concept);
+        }
+        self.relearn_relationships();
+        self.warm_start = Some(report);
+        Ok(true)
+    }
+
+    /// Number of concepts stored for relationship and search queries
+    pub fn concept_count(&self) -> usize {
+        self.concepts.len()
+    }
+
+    /// Drop stored concepts until `max` remain, those `policy` ranks lowest
+    /// first, and rebuild the graph over the rest; returns how many went
+    pub fn evict_concepts(&mut self, max: usize, policy: EvictionPolicy) -> usize {
+        if self.concepts.len() <= max {
+            return 0;
+        }
+        let evicted = policy.concepts_to_evict(self.concepts.values(), max);
+        for id in &evicted {
+            self.concepts.remove(id);
+        }
+        self.relearn_relationships();
+        evicted.len()
+    }
+
+    /// Concepts stored for relationship and search queries, in no particular order
+    pub fn stored_concepts(&self) -> impl Iterator<Item = &SemanticConcept> {
+        self.concepts.values()
+    }
+
+    /// A stored concept by id
+    pub fn stored_concept(&self, concept_id: &str) -> Option<&SemanticConcept> {
+        self.concepts.get(concept_id)
+    }
+
+    /// Root of the project last learned or loaded, if any
+    pub fn project_root(&self) -> Option<&str> {
+        self.project_root.as_deref()
+    }
+
+    /// Stored concepts whose name or tags contain `query`, or whose name has
+    /// every word of it, ignoring case, most relevant first
+    ///
+    /// Exact name matches rank above name prefixes, those above names holding
+    /// every word of the query (see `NamingPatternAnalyzer::name_tokens`), and
+    /// those above other name matches; a tag equal to the query ranks a concept
+    /// above plain name matches. Ties are broken by name.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn search_concepts(&self, query: String, limit: Option<u32>) -> Vec<SemanticConcept> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<(u32, &SemanticConcept)> = self
+            .concepts
+            .values()
+            .filter_map(|concept| {
+                let score = Self::search_relevance(concept, &query);
+                (score > 0).then_some((score, concept))
+            })
+            .collect();
+        matches.sort_by(|(score_a, a), (score_b, b)| {
+            score_b
+                .cmp(score_a)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        if let Some(limit) = limit {
+            matches.truncate(limit as usize);
+        }
+        matches.into_iter().map(|(_, concept)| concept.clone()).collect()
+    }
+
+    /// Tag a concept with domain terms such as "billing" or "auth"
+    ///
+    /// Tags are lower-cased, kept in the concept's `tags` metadata, used by
+    /// `search_concepts` and the blueprint's tag groups, and saved to
+    /// `.in-memoria/concept-tags.json` once a project has been learned. They
+    /// follow the concept by name and file, so they survive relearning.
+    /// Returns all tags of the concept.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn tag_concept(&mut self, concept_id: String, tags: Vec<String>) -> Result<Vec<String>, ParseError> {
+        let concept = self.concept_to_tag(&concept_id, &tags)?;
+        let all_tags = self.tag_store.add(&concept, &tags);
+        self.store_tags(&concept_id)?;
+        Ok(all_tags)
+    }
+
+    /// Remove tags from a concept, returning the tags it keeps
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn untag_concept(&mut self, concept_id: String, tags: Vec<String>) -> Result<Vec<String>, ParseError> {
+        let concept = self.concept_to_tag(&concept_id, &tags)?;
+        let remaining = self.tag_store.remove(&concept, &tags);
+        self.store_tags(&concept_id)?;
+        Ok(remaining)
+    }
+
+    /// Tags of a stored concept; empty for untagged or unknown concepts
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_concept_tags(&self, concept_id: String) -> Vec<String> {
+        self.concepts
+            .get(&concept_id)
+            .map(|concept| self.tag_store.tags_for(concept))
+            .unwrap_or_default()
+    }
+
+    /// Concepts and files grouped by tag
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_tag_groups(&self) -> Vec<TagGroup> {
+        self.tag_store.groups()
+    }
+
+    /// Load the tags saved for a project and apply them to the stored concepts
+    ///
+    /// Later tag changes are saved to that project. Returns false when the
+    /// project has no saved tags. Learning a codebase does this for its path.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn load_concept_tags(&mut self, project_path: String) -> Result<bool, ParseError> {
+        let root = validation::require_directory(&project_path)?;
+        let store = ConceptTagStore::load(&root)?;
+        let found = store.is_some();
+        self.tag_store = store.unwrap_or_default();
+        self.project_root = Some(project_path);
+        for concept in self.concepts.values_mut() {
+            self.tag_store.apply(concept);
+        }
+        Ok(found)
+    }
+
+    /// Attach `owners` (from CODEOWNERS) and `primary_author` (from git blame) to concepts
+    /// under `root_path`, updating the analyzer's stored copies as well
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn attach_ownership(&mut self, root_path: String, concepts: Vec<SemanticConcept>) -> Vec<SemanticConcept> {
+        let mut concepts = concepts;
+        OwnershipAnalyzer::new(&root_path).attach_ownership(&mut concepts);
+
+        for concept in &concepts {
+            if let Some(stored) = self.concepts.get_mut(&concept.id) {
+                stored.metadata = concept.metadata.clone();
+            }
+        }
+
+        concepts
+    }
+
+    /// Register a tree-sitter extraction query for a language
+    ///
+    /// The query captures each declaration as `@definition.<concept_type>` and its
+    /// identifier as `@name`. It replaces any bundled query for that language.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn register_extraction_query(&mut self, language: String, query: String) -> Result<(), ParseError> {
+        self.query_registry
+            .register(&language, &query, &self.parser_manager)
+    }
+
+    /// Register every `<language>.scm` extraction query in a directory
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn load_extraction_queries(&mut self, dir: String) -> Result<u32, ParseError> {
+        self.query_registry.load_dir(&dir, &self.parser_manager)
     }
 
     /// Parse file content with tree-sitter and extract concepts
@@ -156,17 +831,29 @@ impl SemanticAnalyzer {
         content: &str,
         language: &str,
     ) -> Result<Vec<SemanticConcept>, ParseError> {
-        // Add per-file timeout protection
+        self.parse_with_timeout(file_path, content, language).await.0
+    }
+
+    /// Parse with per-file timeout protection. The flag is set when the timeout
+    /// was hit and the concepts came from fallback extraction instead.
+    async fn parse_with_timeout(
+        &mut self,
+        file_path: &str,
+        content: &str,
+        language: &str,
+    ) -> (Result<Vec<SemanticConcept>, ParseError>, bool) {
         let parsing_result = tokio::time::timeout(
-            tokio::time::Duration::from_secs(30), // 30 second timeout per file
+            PARSE_TIMEOUT,
             self.parse_file_with_language(file_path, content, language)
         ).await;
 
         match parsing_result {
-            Ok(result) => result,
+            Ok(result) => (result, false),
             Err(_timeout) => {
                 eprintln!("Timeout parsing {}, using fallback", file_path);
-                Ok(FallbackExtractor::new().extract_concepts(file_path, content))
+                self.telemetry.on_timeout("file", Some(file_path), PARSE_TIMEOUT.as_secs_f64() * 1000.0);
+                self.telemetry.on_fallback_used(file_path, "timeout");
+                (Ok(self.fallback_concepts(file_path, content)), true)
             }
         }
     }
@@ -177,11 +864,113 @@ impl SemanticAnalyzer {
         file_path: &str,
         content: &str,
         language: &str,
+    ) -> Result<Vec<SemanticConcept>, ParseError> {
+        self.extract_with_language(file_path, content, language)
+    }
+
+    /// Synchronously extract concepts from in-memory content, falling back to
+    /// regex extraction when tree-sitter can't handle the file. Used by hosts
+    /// without an async runtime (e.g. the WASM build).
+    pub fn extract_file_concepts_sync(&mut self, file_path: &str, content: &str) -> Vec<SemanticConcept> {
+        let concepts = self.extract_unstored(file_path, content);
+
+        for concept in &concepts {
+            self.concepts.insert(concept.id.clone(), concept.clone());
+        }
+
+        concepts
+    }
+
+    /// Parse both versions of a file and report which concepts were added,
+    /// removed, renamed or moved, with their old and new line ranges
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn diff_file_concepts(&mut self, old_content: String, new_content: String, path: String) -> FileConceptDiff {
+        let old_concepts = self.extract_unstored(&path, &old_content);
+        let new_concepts = self.extract_unstored(&path, &new_content);
+        ConceptDiffer::diff(&path, &old_concepts, &old_content, &new_concepts, &new_content)
+    }
+
+    /// Extract concepts without recording them in the analyzer's knowledge
+    pub(crate) fn extract_unstored(&mut self, file_path: &str, content: &str) -> Vec<SemanticConcept> {
+        let language = self.config.detect_language(file_path, content);
+        self.extract_with_language(file_path, content, &language)
+            .unwrap_or_else(|_| self.fallback_concepts(file_path, content))
+    }
+
+    /// Parse with tree-sitter and run the language-specific extractor
+    fn extract_with_language(
+        &mut self,
+        file_path: &str,
+        content: &str,
+        language: &str,
+    ) -> Result<Vec<SemanticConcept>, ParseError> {
+        let mut concepts = self.extract_from_tree(file_path, content, language)?;
+        self.assign_concept_ids(&mut concepts);
+        NamingPatternAnalyzer::annotate_name_tokens(concepts.iter_mut());
+        Ok(concepts)
+    }
+
+    /// Read configuration files as data, notebooks cell by cell, Markdown for
+    /// its structure, and parse anything else as source in its language
+    fn extract_from_tree(
+        &mut self,
+        file_path: &str,
+        content: &str,
+        language: &str,
+    ) -> Result<Vec<SemanticConcept>, ParseError> {
+        // CI workflows, compose files and manifests are read as data, not parsed
+        if ConfigFileExtractor::is_config_file(file_path) {
+            return Ok(ConfigFileExtractor::new().extract_concepts(file_path, content));
+        }
+        if Notebook::is_notebook(file_path) {
+            return self.extract_notebook(file_path, content);
+        }
+        if MarkdownExtractor::is_documentation(file_path) {
+            return Ok(MarkdownExtractor::new().extract_concepts(file_path, content));
+        }
+        self.extract_source(file_path, content, language)
+    }
+
+    /// Extract a notebook's own concepts and, for Python notebooks, each code
+    /// cell's concepts placed at the cell's lines. Cells that fail to parse
+    /// are skipped rather than failing the notebook.
+    fn extract_notebook(&mut self, file_path: &str, content: &str) -> Result<Vec<SemanticConcept>, ParseError> {
+        let notebook = Notebook::parse(content)?;
+        let mut concepts = notebook.concepts(file_path);
+        if !notebook.is_python() {
+            return Ok(concepts);
+        }
+        for cell in notebook.code_cells() {
+            let Some(source) = cell.python_source() else { continue };
+            let Ok(mut cell_concepts) = self.extract_source(file_path, &source, "python") else {
+                continue;
+            };
+            notebook.place(cell, &mut cell_concepts);
+            concepts.extend(cell_concepts);
+        }
+        Ok(concepts)
+    }
+
+    /// Parse source code and run the query or node-kind extractor for its language
+    fn extract_source(
+        &mut self,
+        file_path: &str,
+        content: &str,
+        language: &str,
     ) -> Result<Vec<SemanticConcept>, ParseError> {
         let tree = self.parser_manager.parse(content, language)?;
+
+        // Prefer declarative query extraction when the language has a query
+        if let Some(query) = self.query_registry.get(language, &self.parser_manager) {
+            let mut concepts = QueryExtractor::extract(query, tree.root_node(), file_path, content);
+            ConfidenceCalibrator::calibrate(&mut concepts, content, ExtractionMethod::TreeSitter);
+            RelationshipLearner::record_references(tree.root_node(), content, &mut concepts);
+            return Ok(concepts);
+        }
+
         let mut concepts = Vec::new();
 
-        // Use language-specific extraction
+        // Fall back to node-kind extractors for languages without a query
         match language {
             "typescript" | "javascript" => {
                 let extractor = TypeScriptExtractor::new();
@@ -229,131 +1018,645 @@ impl SemanticAnalyzer {
             }
         }
 
-        Ok(concepts)
+        ConfidenceCalibrator::calibrate(&mut concepts, content, ExtractionMethod::TreeSitter);
+        RelationshipLearner::record_references(tree.root_node(), content, &mut concepts);
+
+        // ORM models add table, column and relation concepts on top of the classes
+        concepts.extend(DataModelExtractor::to_concepts(&DataModelExtractor::extract(file_path, content)));
+        Ok(concepts)
+    }
+
+    /// Regex-based extraction for files tree-sitter couldn't handle, with calibrated confidence
+    fn fallback_concepts(&self, file_path: &str, content: &str) -> Vec<SemanticConcept> {
+        let mut concepts = FallbackExtractor::new().extract_concepts(file_path, content);
+        ConfidenceCalibrator::calibrate(&mut concepts, content, ExtractionMethod::Fallback);
+        self.assign_concept_ids(&mut concepts);
+        NamingPatternAnalyzer::annotate_name_tokens(concepts.iter_mut());
+        concepts
+    }
+
+    /// Key concepts by their project-relative path so ids match across
+    /// platforms and runs. Extractors that reach a declaration twice (an
+    /// `export` and the declaration under it) yield identical copies, which
+    /// are dropped; other same-line concepts get a numeric suffix.
+    fn assign_concept_ids(&self, concepts: &mut Vec<SemanticConcept>) {
+        let root = self.config.project_root.as_ref().map(|root| root.to_string_lossy().to_string());
+        let mut identical = std::collections::HashSet::new();
+        concepts.retain(|concept| {
+            identical.insert((
+                concept.file_path.clone(),
+                concept.name.clone(),
+                concept.concept_type.clone(),
+                concept.line_range.start,
+                concept.line_range.end,
+            ))
+        });
+        let mut seen = std::collections::HashSet::new();
+        for concept in concepts.iter_mut() {
+            let id = paths::concept_id(concept, root.as_deref());
+            let mut unique = id.clone();
+            let mut suffix = 2;
+            while !seen.insert(unique.clone()) {
+                unique = format!("{}#{}", id, suffix);
+                suffix += 1;
+            }
+            concept.id = unique;
+        }
+    }
+
+    /// Walk tree and extract concepts using a specific extractor
+    fn walk_and_extract<T>(
+        &self,
+        node: tree_sitter::Node<'_>,
+        file_path: &str,
+        content: &str,
+        extractor: &T,
+        concepts: &mut Vec<SemanticConcept>,
+    ) -> Result<(), ParseError>
+    where
+        T: HasExtractConcepts,
+    {
+        let walker = TreeWalker::default();
+        
+        walker.walk(node, &mut |node| {
+            extractor.extract_concepts(node, file_path, content, concepts)
+                .map_err(|e| format!("Extraction error: {}", e))
+        }).map_err(ParseError::from_reason)?;
+
+        Ok(())
+    }
+
+    /// Run or continue a learning pass until every file is analyzed or the
+    /// configured time budget is spent
+    async fn learn_until_deadline(
+        &mut self,
+        path: &str,
+        resume_token: Option<&str>,
+    ) -> Result<PartialResult, ParseError> {
+        self.overlay.require_root(path)?;
+        let call_start = std::time::Instant::now();
+
+        let mut run = match resume_token {
+            Some(token) => self.take_pending_run(path, token)?,
+            None => {
+                self.pending_run = None;
+                // Nothing is saved for a project that only exists in the overlay
+                self.project_root = std::path::Path::new(path).is_dir().then(|| path.to_string());
+                self.tag_store = match ConceptTagStore::load(std::path::Path::new(path)) {
+                    Ok(store) => store.unwrap_or_default(),
+                    Err(e) => {
+                        eprintln!("Warning: ignoring concept tags: {}", e);
+                        ConceptTagStore::default()
+                    }
+                };
+                PendingRun::new(path, self.collect_files(path)?)
+            }
+        };
+        let deadline = call_start + std::time::Duration::from_secs(self.config.learning_timeout_secs);
+        let prior_elapsed = run.elapsed;
+        let checkpoint_interval = self.config.checkpoint_interval;
+        let mut checkpoint_path = None;
+
+        // Work in batches of the checkpoint interval, saving progress after each
+        // one so a killed process loses at most one batch
+        loop {
+            let batch_size = if checkpoint_interval == 0 { run.remaining.len() } else { checkpoint_interval };
+            let rest = run.remaining.split_off(batch_size.min(run.remaining.len()));
+            let batch = std::mem::replace(&mut run.remaining, rest);
+            let batch_len = batch.len();
+
+            let (concepts, mut unprocessed) = self.process_files(batch, Some(deadline)).await;
+            run.files_processed += batch_len - unprocessed.len();
+            run.concepts.extend(concepts);
+            unprocessed.append(&mut run.remaining);
+            run.remaining = unprocessed;
+            run.elapsed = prior_elapsed + call_start.elapsed();
+
+            if run.remaining.is_empty() {
+                break;
+            }
+            if checkpoint_interval > 0 && self.project_root.is_some() {
+                match run.save_checkpoint() {
+                    Ok(path) => checkpoint_path = Some(path.to_string_lossy().to_string()),
+                    Err(e) => eprintln!("Warning: failed to write learning checkpoint: {}", e),
+                }
+            }
+            if std::time::Instant::now() >= deadline {
+                break;
+            }
+        }
+
+        let timed_out = !run.remaining.is_empty();
+        if timed_out {
+            eprintln!(
+                "Learning process timed out after {} files; {} files remaining",
+                run.files_processed,
+                run.remaining.len()
+            );
+            self.telemetry.on_timeout("learning", None, run.elapsed.as_secs_f64() * 1000.0);
+        } else {
+            run.concepts.extend(self.config_file_concepts(path));
+            run.concepts.extend(self.documentation_concepts(path));
+            run.concepts.extend(self.api_contract_concepts(path));
+            PendingRun::remove_checkpoint(&run.root);
+        }
+        self.recorder.finish(run.elapsed, timed_out);
+        for concept in &mut run.concepts {
+            self.tag_store.apply(concept);
+        }
+
+        // Update internal knowledge
+        for concept in &run.concepts {
+            self.concepts.insert(concept.id.clone(), concept.clone());
+        }
+        if !timed_out {
+            self.bury_removed_files();
+        }
+        self.relearn_relationships();
+        if !timed_out {
+            self.save_concept_graph();
+            self.save_concept_snapshot();
+        }
+
+        let mut result = run.to_result();
+        sort_concepts(&mut result.concepts);
+        if timed_out {
+            result.checkpoint_path = checkpoint_path;
+            self.pending_run = Some(run);
+        }
+        Ok(result)
+    }
+
+    /// Rebuild the relationship graph over every stored concept and record
+    /// each concept's coupling and cohesion
+    fn relearn_relationships(&mut self) {
+        self.graph = GraphStore::build(self.concepts.values());
+        let measures = ComplexityAnalyzer::measure_coupling(&self.graph);
+        ComplexityAnalyzer::annotate_coupling(self.concepts.values_mut(), &measures);
+    }
+
+    /// Resolve the relationships of the concepts of `files` again after they
+    /// changed, keeping the rest of the graph, and record every concept's
+    /// coupling and cohesion
+    fn relearn_file_relationships(&mut self, files: BTreeSet<String>) {
+        let files: HashSet<&str> = files.iter().map(String::as_str).collect();
+        self.graph.relearn_files(self.concepts.values(), &files);
+        let measures = ComplexityAnalyzer::measure_coupling(&self.graph);
+        ComplexityAnalyzer::annotate_coupling(self.concepts.values_mut(), &measures);
+    }
+
+    /// Save the relationship graph with the current project, once per
+    /// completed learning run
+    fn save_concept_graph(&self) {
+        let Some(root) = &self.project_root else { return };
+        if let Err(e) = self.graph.save(std::path::Path::new(root)) {
+            eprintln!("Warning: failed to save concept graph: {}", e);
+        }
+    }
+
+    /// Take the concepts of files that no longer exist out of the store,
+    /// leaving a tombstone per file, and forget the tombstones of files that
+    /// exist again or were removed longer ago than the grace period
+    fn bury_removed_files(&mut self) {
+        let now = chrono::Utc::now().to_rfc3339();
+        let files: BTreeSet<&str> = self.concepts.values().map(|c| c.file_path.as_str()).collect();
+        let removed: BTreeSet<String> = files
+            .into_iter()
+            .filter(|file_path| !file_path.is_empty() && !self.source_exists(file_path))
+            .map(str::to_string)
+            .collect();
+
+        let mut buried: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        self.concepts.retain(|id, concept| {
+            let keep = !removed.contains(&concept.file_path);
+            if !keep {
+                buried.entry(concept.file_path.clone()).or_default().push(id.clone());
+            }
+            keep
+        });
+        for (file_path, ids) in buried {
+            self.tombstones.bury(&file_path, ids, &now);
+        }
+        for tombstone in self.tombstones.list() {
+            if self.source_exists(&tombstone.file_path) {
+                self.tombstones.revive(&tombstone.file_path);
+            }
+        }
+        self.tombstones.collect_garbage(&now, self.config.tombstone_grace_secs);
+    }
+
+    /// Files whose concepts complete learning runs dropped because the file
+    /// was removed, in path order, until the grace period passes
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_tombstones(&self) -> Vec<Tombstone> {
+        self.tombstones.list()
     }
 
-    /// Walk tree and extract concepts using a specific extractor
-    fn walk_and_extract<T>(
-        &self,
-        node: tree_sitter::Node<'_>,
-        file_path: &str,
-        content: &str,
-        extractor: &T,
-        concepts: &mut Vec<SemanticConcept>,
-    ) -> Result<(), ParseError>
-    where
-        T: HasExtractConcepts,
-    {
-        let walker = TreeWalker::default();
-        
-        walker.walk(node, &mut |node| {
-            extractor.extract_concepts(node, file_path, content, concepts)
-                .map_err(|e| format!("Extraction error: {}", e))
-        }).map_err(ParseError::from_reason)?;
+    /// Save the stored concepts with the current project for `open`
+    fn save_concept_snapshot(&self) {
+        let Some(root) = &self.project_root else { return };
+        let mut snapshot = ConceptSnapshot::capture(self.concepts.values(), |file_path| {
+            self.read_source(&self.source_path(file_path)).ok()
+        });
+        snapshot.tombstones = self.tombstones.list();
+        if let Err(e) = snapshot.save(std::path::Path::new(root)) {
+            eprintln!("Warning: failed to save concept snapshot: {}", e);
+        }
+    }
+
+    /// Relationship kind and direction filters from their names
+    fn edge_filter(
+        kind: Option<String>,
+        direction: Option<String>,
+    ) -> Result<(Option<RelationshipKind>, EdgeDirection), ParseError> {
+        let kind = match kind {
+            Some(kind) => Some(RelationshipKind::parse(&kind).ok_or_else(|| {
+                validation::ValidationError::new(
+                    validation::ErrorCode::InvalidArgument,
+                    format!("Unknown relationship kind: {}", kind),
+                )
+            })?),
+            None => None,
+        };
+        let direction = match direction {
+            Some(direction) => EdgeDirection::parse(&direction).ok_or_else(|| {
+                validation::ValidationError::new(
+                    validation::ErrorCode::InvalidArgument,
+                    format!("Unknown relationship direction: {}", direction),
+                )
+            })?,
+            None => EdgeDirection::Both,
+        };
+        Ok((kind, direction))
+    }
+
+    fn graph_depth(depth: Option<u32>) -> Result<u32, ParseError> {
+        match depth.unwrap_or(1) {
+            depth if depth <= MAX_GRAPH_DEPTH => Ok(depth),
+            depth => Err(validation::ValidationError::new(
+                validation::ErrorCode::InvalidArgument,
+                format!("depth must be at most {}, got {}", MAX_GRAPH_DEPTH, depth),
+            )
+            .into()),
+        }
+    }
+
+    fn context_lines(name: &str, lines: Option<u32>) -> Result<u32, ParseError> {
+        match lines.unwrap_or(DEFAULT_CONTEXT_LINES) {
+            lines if lines <= MAX_CONTEXT_LINES => Ok(lines),
+            lines => Err(validation::ValidationError::new(
+                validation::ErrorCode::InvalidArgument,
+                format!("{} must be at most {}, got {}", name, MAX_CONTEXT_LINES, lines),
+            )
+            .into()),
+        }
+    }
 
+    fn require_graph_node(&self, concept_id: &str) -> Result<(), ParseError> {
+        validation::require_non_empty("concept_id", concept_id)?;
+        if self.graph.node(concept_id).is_none() {
+            return Err(validation::ValidationError::new(
+                validation::ErrorCode::InvalidArgument,
+                format!("Unknown concept: {}", concept_id),
+            )
+            .into());
+        }
         Ok(())
     }
 
+    /// Ranking of a concept for a lower-cased search query; 0 when it doesn't match
+    fn search_relevance(concept: &SemanticConcept, query: &str) -> u32 {
+        let name = concept.name.to_lowercase();
+        // Every word of the query is a word of the name: "html file" finds parseHTMLFile
+        let query_words = NamingPatternAnalyzer::name_tokens(query);
+        let name_words = NamingPatternAnalyzer::concept_tokens(concept);
+        let name_score = if name == query {
+            30
+        } else if name.starts_with(query) {
+            20
+        } else if !query_words.is_empty() && query_words.iter().all(|word| name_words.contains(word)) {
+            15
+        } else if name.contains(query) {
+            10
+        } else {
+            0
+        };
+
+        let tags = concept.metadata.get(crate::analysis::TAGS_METADATA_KEY);
+        let tag_score = match tags {
+            Some(tags) if !query.is_empty() && tags.split(',').any(|tag| tag == query) => 25,
+            Some(tags) if !query.is_empty() && tags.contains(query) => 5,
+            _ => 0,
+        };
+
+        name_score + tag_score
+    }
+
+    /// The stored concept to change tags of, after checking the arguments
+    fn concept_to_tag(&self, concept_id: &str, tags: &[String]) -> Result<SemanticConcept, ParseError> {
+        validation::require_non_empty("concept_id", concept_id)?;
+        if !tags.iter().any(|tag| ConceptTagStore::normalize(tag).is_some()) {
+            return Err(validation::ValidationError::new(
+                validation::ErrorCode::InvalidArgument,
+                "tags must contain at least one non-blank tag",
+            )
+            .into());
+        }
+        self.concepts.get(concept_id).cloned().ok_or_else(|| {
+            validation::ValidationError::new(
+                validation::ErrorCode::InvalidArgument,
+                format!("Unknown concept: {}", concept_id),
+            )
+            .into()
+        })
+    }
+
+    /// Refresh a concept's tag metadata and save the tags of the current project
+    fn store_tags(&mut self, concept_id: &str) -> Result<(), ParseError> {
+        if let Some(concept) = self.concepts.get_mut(concept_id) {
+            self.tag_store.apply(concept);
+            self.graph.refresh_node(concept);
+        }
+        match &self.project_root {
+            Some(root) => {
+                self.tag_store.save(std::path::Path::new(root))?;
+                self.graph.save(std::path::Path::new(root))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// The stopped run a resume token refers to; it must be for the same path
+    fn take_pending_run(&mut self, path: &str, token: &str) -> Result<PendingRun, ParseError> {
+        match self.pending_run.take() {
+            Some(run) if run.root == path && run.token() == token => Ok(run),
+            other => {
+                self.pending_run = other;
+                Err(validation::ValidationError::new(
+                    validation::ErrorCode::InvalidArgument,
+                    format!("Unknown or expired resume token for {}: {}", path, token),
+                )
+                .into())
+            }
+        }
+    }
+
+    fn pacer(&self) -> Pacer {
+        Pacer::new(self.nice.unwrap_or(self.config.nice))
+    }
+
+    /// Where a stored concept's file is; concept paths may be relative to the
+    /// project root
+    fn source_path(&self, file_path: &str) -> std::path::PathBuf {
+        match &self.config.project_root {
+            Some(root) => root.join(file_path),
+            None => std::path::PathBuf::from(file_path),
+        }
+    }
+
+    /// Whether a concept's file exists, on disk or in the overlay
+    fn source_exists(&self, file_path: &str) -> bool {
+        let path = self.source_path(file_path);
+        path.is_file() || self.config.project_root.as_ref().is_some_and(|root| self.overlay.get(&path, root).is_some())
+    }
+
+    /// A source file's content, from the overlay when it holds the file
+    fn read_source(&self, file_path: &std::path::Path) -> std::io::Result<String> {
+        match &self.config.project_root {
+            Some(root) => self.overlay.read_to_string(file_path, root),
+            None => fs::read_to_string(file_path),
+        }
+    }
+
     /// Extract concepts from entire codebase
     async fn extract_concepts(&mut self, path: &str) -> Result<Vec<SemanticConcept>, ParseError> {
-        let mut all_concepts = Vec::new();
-        let mut processed_count = 0;
-        let debug_enabled = std::env::var("IN_MEMORIA_DEBUG").is_ok();
+        let analysis_start = std::time::Instant::now();
+        let files = self.collect_files(path)?;
+        let (mut all_concepts, _) = self.process_files(files, None).await;
+        all_concepts.extend(self.config_file_concepts(path));
+        all_concepts.extend(self.documentation_concepts(path));
+        all_concepts.extend(self.api_contract_concepts(path));
+        self.recorder.finish(analysis_start.elapsed(), false);
+        Ok(all_concepts)
+    }
 
-        for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                let file_path = entry.path();
+    /// Walk the codebase and pick the source files to analyze, recording skipped ones
+    fn collect_files(&mut self, path: &str) -> Result<Vec<std::path::PathBuf>, ParseError> {
+        // Walk from the canonical root so every run keys files the same way
+        let root = SourceWalker::canonical_root(std::path::Path::new(path));
+        self.load_project_config(path.to_string())?;
 
-                if debug_enabled {
-                    eprintln!("[DEBUG] entry {}", file_path.display());
-                }
+        let debug_enabled = std::env::var("IN_MEMORIA_DEBUG").is_ok();
+        self.recorder.reset();
+
+        let mut walk = SourceWalker::new(self.config.follow_symlinks).walk(&root, |_| true);
+        if !self.overlay.is_empty() {
+            // Overlaid files are analyzed whether or not they exist on disk
+            walk.files.extend(self.overlay.files_under(&root));
+            walk.files.sort();
+            walk.files.dedup();
+        }
+        for skip in walk.skipped {
+            if debug_enabled {
+                eprintln!("[DEBUG] skipped path {} ({})", skip.path, skip.reason);
+            }
+            self.recorder.record_skip_with_detail(&skip.path, skip.reason, skip.detail);
+        }
 
-                if self.config.should_analyze_file(file_path) {
+        let mut candidates = Vec::new();
+        for file_path in walk.files {
+            if debug_enabled {
+                eprintln!("[DEBUG] entry {}", file_path.display());
+            }
+
+            let overlaid = self.overlay.get(&file_path, &root);
+            let exclusion = match overlaid {
+                Some(content) => self.config.exclusion_for_content(&file_path, content),
+                None => self.config.exclusion(&file_path),
+            };
+            match exclusion {
+                None => candidates.push(SampleCandidate {
+                    language: match overlaid {
+                        Some(content) => self.config.detect_language(&file_path.to_string_lossy(), content),
+                        None => self.config.detect_file_language(&file_path),
+                    },
+                    path: file_path,
+                }),
+                Some(exclusion) => {
                     if debug_enabled {
-                        eprintln!("[DEBUG] processing file {}", file_path.display());
-                    }
-                    processed_count += 1;
-                    
-                    // Prevent processing too many files
-                    if processed_count > self.config.max_files {
-                        eprintln!("Warning: Reached maximum file limit ({}), stopping analysis", self.config.max_files);
-                        break;
+                        eprintln!("[DEBUG] skipped file {} ({})", file_path.display(), exclusion.reason);
                     }
+                    self.recorder.record_skip_with_detail(
+                        &file_path.to_string_lossy(),
+                        exclusion.reason,
+                        exclusion.detail,
+                    );
+                }
+            }
+        }
+
+        // Sample across directories and languages rather than stopping at the first files found
+        let (files, report) = FileSampler::sample(&root, candidates, self.config.max_files);
+        if let Some(report) = &report {
+            eprintln!("Warning: reached maximum file limit. {}", report.summary());
+            self.recorder.record_skipped_count("file_limit", report.skipped_files);
+        }
+        self.sampling_report = report;
+        Ok(files)
+    }
 
-                    match fs::read_to_string(file_path) {
-                        Ok(content) => {
-                            let language = self.config.detect_language_from_path(
-                                file_path.to_str().unwrap_or(""));
+    /// Extract concepts from source files in order. With a deadline, stops once
+    /// it has passed (after at least one file) and returns the files not reached.
+    async fn process_files(
+        &mut self,
+        files: Vec<std::path::PathBuf>,
+        deadline: Option<std::time::Instant>,
+    ) -> (Vec<SemanticConcept>, Vec<std::path::PathBuf>) {
+        let mut all_concepts = Vec::new();
+        let mut processed_count = 0;
+        let debug_enabled = std::env::var("IN_MEMORIA_DEBUG").is_ok();
+        let mut pacer = self.pacer();
 
-                            match self.parse_file_content(
+        let mut files = files.into_iter();
+        for file_path in files.by_ref() {
+            if debug_enabled {
+                eprintln!("[DEBUG] processing file {}", file_path.display());
+            }
+            processed_count += 1;
+
+            let file_start = std::time::Instant::now();
+            match self.read_source(&file_path) {
+                Ok(content) => {
+                    let language = self.config.detect_language(
+                        file_path.to_str().unwrap_or(""), &content);
+
+                    let (parsed, timed_out) = self.parse_with_timeout(
+                        file_path.to_str().unwrap_or(""),
+                        &content,
+                        &language,
+                    ).await;
+                    let (mut concepts, used_fallback) = match parsed {
+                        Ok(concepts) => (concepts, timed_out),
+                        Err(_) => {
+                            // Fallback to regex-based extraction if tree-sitter fails
+                            eprintln!("Tree-sitter parsing failed for {}, using fallback", file_path.display());
+                            self.telemetry.on_fallback_used(&file_path.to_string_lossy(), "parse_error");
+                            let fallback_concepts = self.fallback_concepts(
                                 file_path.to_str().unwrap_or(""),
                                 &content,
-                                &language,
-                            ).await {
-                                Ok(mut concepts) => {
-                                    all_concepts.append(&mut concepts);
-                                }
-                                Err(_) => {
-                                    // Fallback to regex-based extraction if tree-sitter fails
-                                    eprintln!("Tree-sitter parsing failed for {}, using fallback", file_path.display());
-                                    let fallback_concepts = FallbackExtractor::new()
-                                        .extract_concepts(
-                                            file_path.to_str().unwrap_or(""),
-                                            &content,
-                                        );
-                                    all_concepts.extend(fallback_concepts);
-                                }
-                            };
+                            );
+                            (fallback_concepts, true)
                         }
-                        Err(_) => {
-                            // Skip files that can't be read
-                            continue;
+                    };
+                    if self.config.include_generated {
+                        if let Some(detail) = FileClassifier::generated_by(&file_path, &content) {
+                            mark_generated(&mut concepts, &detail);
                         }
                     }
-                } else if debug_enabled {
-                    eprintln!("[DEBUG] skipped file {}", file_path.display());
+                    if DeprecationExtractor::has_markers(&content) {
+                        DeprecationExtractor::annotate(file_path.to_str().unwrap_or(""), &content, &mut concepts);
+                    }
+
+                    let timing = FileTiming {
+                        file_path: file_path.to_string_lossy().to_string(),
+                        language,
+                        duration_ms: file_start.elapsed().as_secs_f64() * 1000.0,
+                        concept_count: concepts.len() as u32,
+                        used_fallback,
+                        timed_out,
+                    };
+                    self.telemetry.on_file_parsed(&timing);
+                    self.recorder.record_file(timing);
+                    all_concepts.extend(concepts);
                 }
+                Err(_) => {
+                    // Skip files that can't be read
+                    self.recorder.record_skip(&file_path.to_string_lossy(), "unreadable");
+                }
+            }
+
+            if deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline) {
+                break;
             }
+            pacer.tick().await;
         }
 
         eprintln!("Processed {} source files and found {} concepts", processed_count, all_concepts.len());
-        Ok(all_concepts)
+        (all_concepts, files.collect())
+    }
+
+    /// Configuration files aren't source files, so they're found in a separate pass
+    fn config_file_concepts(&self, path: &str) -> Vec<SemanticConcept> {
+        let config_concepts = ConfigFileExtractor::new()
+            .extract_directory(std::path::Path::new(path), self.config.max_file_size);
+        if std::env::var("IN_MEMORIA_DEBUG").is_ok() {
+            eprintln!("[DEBUG] found {} concepts in configuration files", config_concepts.len());
+        }
+        config_concepts
+    }
+
+    /// READMEs and docs are found in their own pass too; the sections and code
+    /// blocks that mention identifiers are linked to them once the graph is built
+    fn documentation_concepts(&self, path: &str) -> Vec<SemanticConcept> {
+        let doc_concepts = MarkdownExtractor::new()
+            .extract_directory(std::path::Path::new(path), self.config.max_file_size);
+        if std::env::var("IN_MEMORIA_DEBUG").is_ok() {
+            eprintln!("[DEBUG] found {} concepts in documentation", doc_concepts.len());
+        }
+        doc_concepts
+    }
+
+    /// OpenAPI specs and GraphQL schemas, linked to the routes and client
+    /// calls that implement and use them
+    fn api_contract_concepts(&self, path: &str) -> Vec<SemanticConcept> {
+        match ApiContractExtractor::extract_directory(std::path::Path::new(path), self.config.max_file_size) {
+            Ok(contract_concepts) => {
+                if std::env::var("IN_MEMORIA_DEBUG").is_ok() {
+                    eprintln!("[DEBUG] found {} concepts in API contracts", contract_concepts.len());
+                }
+                contract_concepts
+            }
+            Err(e) => {
+                eprintln!("Warning: failed to read API contracts: {}", e);
+                Vec::new()
+            }
+        }
     }
 
     /// Detect programming languages in codebase
     async fn detect_languages(&self, path: &str) -> Result<Vec<String>, ParseError> {
         let mut languages = std::collections::HashSet::new();
 
-        for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                let file_path = entry.path();
-                
-                if let Some(extension) = file_path.extension().and_then(|s| s.to_str()) {
-                    let language = match extension.to_lowercase().as_str() {
-                        "ts" | "tsx" => Some("typescript"),
-                        "js" | "jsx" => Some("javascript"),
-                        "rs" => Some("rust"),
-                        "py" => Some("python"),
-                        "php" | "phtml" | "inc" => Some("php"),
-                        "sql" => Some("sql"),
-                        "go" => Some("go"),
-                        "java" => Some("java"),
-                        "c" => Some("c"),
-                        "cpp" | "cc" | "cxx" => Some("cpp"),
-                        "cs" => Some("csharp"),
-                        "svelte" => Some("svelte"),
-                        "vue" => Some("javascript"), // Fallback to JS for Vue
-                        _ => None,
-                    };
-
-                    if let Some(lang) = language {
-                        languages.insert(lang.to_string());
-                    }
+        let disk_files = FileWalker::new().walk(std::path::Path::new(path)).files;
+        let overlay_files = self.overlay.files_under(std::path::Path::new(path));
+        for file_path in disk_files.into_iter().chain(overlay_files) {
+            if let Some(extension) = file_path.extension().and_then(|s| s.to_str()) {
+                let language = match extension.to_lowercase().as_str() {
+                    "ts" | "tsx" => Some("typescript"),
+                    "js" | "jsx" => Some("javascript"),
+                    "rs" => Some("rust"),
+                    "py" => Some("python"),
+                    "php" | "phtml" | "inc" => Some("php"),
+                    "sql" => Some("sql"),
+                    "go" => Some("go"),
+                    "java" => Some("java"),
+                    "c" => Some("c"),
+                    "cpp" | "cc" | "cxx" => Some("cpp"),
+                    "cs" => Some("csharp"),
+                    "svelte" => Some("svelte"),
+                    "vue" => Some("javascript"), // Fallback to JS for Vue
+                    _ => None,
+                };
+
+                if let Some(lang) = language {
+                    languages.insert(lang.to_string());
                 }
             }
         }
 
-        Ok(languages.into_iter().collect())
+        let mut languages: Vec<String> = languages.into_iter().collect();
+        languages.sort();
+        Ok(languages)
     }
 }
 
@@ -562,16 +1865,17 @@ mod tests {
     #[tokio::test]
     async fn test_learn_from_codebase() {
         let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let fixture = crate::golden_tests::polyglot_copy();
         
         let result = unsafe {
-            analyzer.learn_from_codebase(".".to_string()).await
+            analyzer.learn_from_codebase(fixture.path().to_string_lossy().to_string()).await
         };
         
         assert!(result.is_ok());
         let concepts = result.unwrap();
         println!("Learned {} concepts from codebase", concepts.len());
         
-        // Should find some concepts in the current Rust codebase
+        // Should find some concepts in the sample project
         assert!(!concepts.is_empty());
     }
 
@@ -588,6 +1892,43 @@ mod tests {
         assert!(result.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_invalid_requests_are_rejected() {
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+
+        let missing = unsafe { analyzer.analyze_codebase("/no/such/project".to_string()).await };
+        assert!(missing.unwrap_err().to_string().contains("[PATH_NOT_FOUND]"));
+
+        let malformed = unsafe { analyzer.update_from_analysis("{\"concepts\": ".to_string()).await };
+        assert!(malformed.unwrap_err().to_string().contains("[INVALID_JSON]"));
+
+        let wrong_shape = unsafe { analyzer.update_from_analysis(r#"{"concepts": {}}"#.to_string()).await };
+        assert!(wrong_shape.unwrap_err().to_string().contains("[SCHEMA_MISMATCH]"));
+
+        let stored = unsafe {
+            analyzer
+                .update_from_analysis(r#"{"concepts": [{"name": "UserService", "type": "class", "file": "src\\user.ts"}]}"#.to_string())
+                .await
+        };
+        assert!(stored.unwrap());
+        assert!(analyzer.concepts.contains_key("src/user.ts::class::UserService::L1"));
+    }
+
+    #[test]
+    fn test_concept_ids_are_relative_to_the_project_root() {
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        analyzer.config.project_root = Some(std::path::PathBuf::from(r"C:\Projects\shop"));
+        let content = "export class CartService {}\nexport class CartService {}\n";
+
+        let windows = analyzer.extract_unstored(r"C:\Projects\Shop\src\cart.ts", content);
+        let unix = analyzer.extract_unstored("src/cart.ts", content);
+        let windows_ids: Vec<&str> = windows.iter().map(|c| c.id.as_str()).collect();
+        let unix_ids: Vec<&str> = unix.iter().map(|c| c.id.as_str()).collect();
+        assert!(windows_ids.contains(&"src/cart.ts::class::CartService::L1"));
+        assert!(windows_ids.contains(&"src/cart.ts::class::CartService::L2"));
+        assert_eq!(windows_ids, unix_ids);
+    }
+
     #[test]
     fn test_get_concept_relationships() {
         let analyzer = SemanticAnalyzer::new().unwrap();
@@ -819,4 +2160,596 @@ mod tests {
         assert!(!concept.name.is_empty());
         assert!(concept.confidence > 0.0);
     }
+
+    #[test]
+    fn test_extract_file_concepts_sync() {
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+
+        let concepts = analyzer.extract_file_concepts_sync(
+            "test.ts",
+            "class UserService { getUser() { return null; } }",
+        );
+        assert!(concepts.iter().any(|c| c.name == "UserService"));
+
+        // Unknown extensions still yield concepts through the fallback extractor
+        let fallback = analyzer.extract_file_concepts_sync("test.unknown", "function calculate() { return 42; }");
+        assert!(!fallback.is_empty());
+    }
+
+    #[test]
+    fn test_query_extraction_for_bundled_and_registered_languages() {
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+
+        let go = analyzer.extract_file_concepts_sync("main.go", "package main\n\nfunc main() {\n}\n");
+        let main = go.iter().find(|c| c.name == "main").unwrap();
+        assert_eq!(main.concept_type, "function");
+        assert_eq!(main.metadata.get("extractor"), Some(&"query".to_string()));
+
+        // A registered query takes over a language that used a node-kind extractor
+        analyzer
+            .register_extraction_query(
+                "rust".to_string(),
+                "(mod_item name: (identifier) @name) @definition.module".to_string(),
+            )
+            .unwrap();
+        let rust = analyzer.extract_file_concepts_sync("lib.rs", "mod api {}\nfn helper() {}\n");
+        assert_eq!(rust.len(), 1);
+        assert_eq!(rust[0].name, "api");
+        assert_eq!(rust[0].concept_type, "module");
+
+        assert!(analyzer
+            .register_extraction_query("rust".to_string(), "(mod_item".to_string())
+            .is_err());
+    }
+
+    #[test]
+    fn test_concepts_record_calibrated_confidence() {
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+
+        let concepts = analyzer.extract_file_concepts_sync("test.ts", "class UserService {
+  getUser() { return null; }
+}
+");
+        let class = concepts.iter().find(|c| c.name == "UserService").unwrap();
+        assert_eq!(class.metadata.get("extraction_method"), Some(&"tree_sitter".to_string()));
+        assert!(class.metadata.contains_key("confidence_signals"));
+
+        let fallback = analyzer.extract_file_concepts_sync("test.unknown", "function calculate() { return 42; }");
+        assert!(fallback.iter().all(|c| c.metadata.get("extraction_method") == Some(&"fallback".to_string())));
+        assert!(fallback.iter().all(|c| c.confidence < class.confidence));
+    }
+
+    #[test]
+    fn test_load_project_config() {
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path().to_str().unwrap().to_string();
+
+        assert!(!analyzer.load_project_config(root.clone()).unwrap());
+        assert_eq!(analyzer.config.max_files, 1000);
+
+        std::fs::create_dir_all(dir.path().join(".in-memoria")).unwrap();
+        std::fs::write(
+            dir.path().join(".in-memoria/config.json"),
+            r#"{"project": {"languages": ["python"]}, "watching": {"ignored": ["**/migrations/**"]}, "analysis": {"maxFiles": 25}}"#,
+        )
+        .unwrap();
+
+        assert!(analyzer.load_project_config(root).unwrap());
+        assert_eq!(analyzer.config.max_files, 25);
+        assert!(analyzer.config.matches_project_filters(&dir.path().join("app/models.py")));
+        assert!(!analyzer.config.matches_project_filters(&dir.path().join("app/index.ts")));
+        assert!(!analyzer.config.matches_project_filters(&dir.path().join("app/migrations/0001.py")));
+    }
+
+    #[tokio::test]
+    async fn test_nice_level_is_validated_and_learning_still_completes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for name in ["a", "b", "c"] {
+            std::fs::write(dir.path().join(format!("{}.ts", name)), format!("export function {}Task() {{}}\n", name)).unwrap();
+        }
+
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        assert!(analyzer.set_nice(5).unwrap_err().to_string().contains("[INVALID_ARGUMENT]"));
+        analyzer.set_nice(crate::analysis::MAX_NICE as u32).unwrap();
+        let concepts = unsafe { analyzer.learn_from_codebase(dir.path().to_string_lossy().to_string()).await }.unwrap();
+        assert_eq!(concepts.iter().filter(|c| c.name.ends_with("Task")).count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_sees_parsed_files_and_fallbacks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a.ts"), "export function aTask() {}\n").unwrap();
+        std::fs::write(dir.path().join("b.py"), "def b_task():\n    pass\n").unwrap();
+
+        let telemetry = std::sync::Arc::new(crate::analysis::RecordingTelemetry::default());
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        analyzer.set_telemetry(telemetry.clone());
+
+        unsafe { analyzer.learn_from_codebase(dir.path().to_string_lossy().to_string()).await }.unwrap();
+        assert_eq!(telemetry.kinds(), vec!["file_parsed", "file_parsed"]);
+
+        unsafe { analyzer.analyze_file_content("notes.rb".to_string(), "def notes\nend\n".to_string()).await }.unwrap();
+        let events = telemetry.events.lock().unwrap();
+        let fallback = events.last().unwrap();
+        assert_eq!(fallback.kind, "fallback_used");
+        assert_eq!(fallback.detail.as_deref(), Some("parse_error"));
+    }
+
+    #[tokio::test]
+    async fn test_learned_concepts_are_ordered_by_file_and_line() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("b.ts"), "export function second() {}\nexport function first() {}\n").unwrap();
+        std::fs::write(dir.path().join("a.py"), "def alpha():\n    pass\n").unwrap();
+
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let concepts = unsafe { analyzer.learn_from_codebase(dir.path().to_string_lossy().to_string()).await }.unwrap();
+        let mut sorted = concepts.clone();
+        sort_concepts(&mut sorted);
+        let ids = |concepts: &[SemanticConcept]| concepts.iter().map(|c| c.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&concepts), ids(&sorted));
+
+        let analysis = unsafe { analyzer.analyze_codebase(dir.path().to_string_lossy().to_string()).await }.unwrap();
+        assert_eq!(analysis.languages, vec!["python".to_string(), "typescript".to_string()]);
+        assert_eq!(ids(&analysis.concepts), ids(&sorted));
+    }
+
+    #[tokio::test]
+    async fn test_file_overlay_shadows_disk_and_adds_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("saved.ts"), "export function savedOnDisk() {}\n").unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let files = HashMap::from([
+            ("saved.ts".to_string(), "export function editedInBuffer() {}\n".to_string()),
+            ("src/draft.py".to_string(), "def draft_only():\n    pass\n".to_string()),
+        ]);
+        assert_eq!(analyzer.set_file_overlay(files), 2);
+
+        let analysis = unsafe { analyzer.analyze_codebase(path.clone()).await }.unwrap();
+        let names: Vec<&str> = analysis.concepts.iter().map(|c| c.name.as_str()).collect();
+        assert!(names.contains(&"editedInBuffer"));
+        assert!(names.contains(&"draft_only"));
+        assert!(!names.contains(&"savedOnDisk"));
+        assert_eq!(analysis.languages, vec!["python".to_string(), "typescript".to_string()]);
+        assert!(!dir.path().join("src").exists());
+
+        // A project that only exists in the overlay
+        let virtual_root = dir.path().join("virtual");
+        let analysis = unsafe { analyzer.analyze_codebase(virtual_root.to_string_lossy().to_string()).await }.unwrap();
+        assert!(analysis.concepts.iter().any(|c| c.name == "draft_only"));
+        assert!(!virtual_root.exists());
+
+        analyzer.clear_file_overlay();
+        let analysis = unsafe { analyzer.analyze_codebase(path).await }.unwrap();
+        assert!(analysis.concepts.iter().any(|c| c.name == "savedOnDisk"));
+        assert!(unsafe { analyzer.analyze_codebase(virtual_root.to_string_lossy().to_string()).await }.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_deprecated_concepts_carry_their_note() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("users.ts"),
+            "/**\n * @deprecated use loadUsers instead\n */\nexport function loadUser(id: string) {}\n\nexport function loadUsers() {}\n",
+        )
+        .unwrap();
+
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let analysis = unsafe { analyzer.analyze_codebase(dir.path().to_string_lossy().to_string()).await }.unwrap();
+        let concept = |name: &str| analysis.concepts.iter().find(|c| c.name == name).unwrap();
+        assert_eq!(concept("loadUser").metadata.get("deprecated").map(String::as_str), Some("true"));
+        assert_eq!(
+            concept("loadUser").metadata.get("deprecation_note").map(String::as_str),
+            Some("use loadUsers instead")
+        );
+        assert!(!concept("loadUsers").metadata.contains_key("deprecated"));
+    }
+
+    #[tokio::test]
+    async fn test_analyze_roots_links_frontend_to_backend() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "frontend/src/api.ts",
+            "export async function loadOrder(id: string) {\n  return fetch(`/api/orders/${id}`);\n}\n\nexport function listAll() {\n  return ordersApi.listOrders();\n}\n",
+        );
+        write("frontend/package.json", r#"{"name": "shop-web", "dependencies": {"shop-shared": "^1.0.0"}}"#);
+        write(
+            "backend/server.js",
+            "const app = express();\napp.get('/api/orders/:id', function getOrder(req, res) {\n  res.json({});\n});\n",
+        );
+        write("backend/openapi.yaml", "openapi: 3.0.0\npaths:\n  /api/orders:\n    get:\n      operationId: listOrders\n");
+        write("shared/package.json", r#"{"name": "shop-shared", "version": "1.0.0"}"#);
+        write("shared/index.ts", "export function formatPrice(cents: number) {\n  return cents / 100;\n}\n");
+
+        let root = |name: &str| dir.path().join(name).to_string_lossy().to_string();
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let result = unsafe { analyzer.analyze_roots(vec![root("frontend"), root("backend"), root("shared")]).await }.unwrap();
+
+        let labels: Vec<&str> = result.roots.iter().map(|r| r.label.as_str()).collect();
+        assert_eq!(labels, vec!["frontend", "backend", "shared"]);
+        assert!(result.combined.concepts.iter().any(|c| c.id.starts_with("frontend/src/api.ts::") && c.name == "loadOrder"));
+        assert!(result.combined.concepts.iter().all(|c| c.metadata.contains_key("root")));
+        assert_eq!(result.combined.languages, vec!["javascript".to_string(), "typescript".to_string()]);
+
+        let edges: Vec<(&str, &str, &str, &str)> = result
+            .cross_root_edges
+            .iter()
+            .map(|e| (e.kind.as_str(), e.from_root.as_str(), e.to_root.as_str(), e.target.as_str()))
+            .collect();
+        assert!(edges.contains(&("http_call", "frontend", "backend", "GET /api/orders/:id")));
+        assert!(edges.contains(&("openapi_client", "frontend", "backend", "listOrders")));
+        assert!(edges.contains(&("package", "frontend", "shared", "shop-shared")));
+
+        let duplicate = unsafe { analyzer.analyze_roots(vec![root("shared"), root("shared")]).await };
+        assert!(duplicate.is_err());
+        assert!(unsafe { analyzer.analyze_roots(Vec::new()).await }.is_err());
+    }
+
+    #[test]
+    fn test_diff_file_concepts() {
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let old = "function loadUser(id) {\n  return db.find(id);\n}\n\nfunction saveUser(user) {\n  db.save(user);\n}\n";
+        let new = "function fetchUser(id) {\n  return db.find(id);\n}\n\nclass UserCache {\n  clear() {}\n}\n";
+
+        let diff = analyzer.diff_file_concepts(old.to_string(), new.to_string(), "users.js".to_string());
+        assert!(diff.renamed.iter().any(|c| c.name == "fetchUser" && c.previous_name.as_deref() == Some("loadUser")));
+        assert!(diff.removed.iter().any(|c| c.name == "saveUser"));
+        assert!(diff.added.iter().any(|c| c.name == "UserCache"));
+        assert!(analyzer.concepts.is_empty());
+    }
+
+    #[test]
+    fn test_config_files_yield_operational_concepts() {
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let compose = "services:\n  api:\n    image: node:20\n    environment:\n      PORT: 3000\n";
+
+        let concepts = analyzer.extract_file_concepts_sync("docker-compose.yml", compose);
+        assert!(concepts.iter().any(|c| c.name == "api" && c.concept_type == "service"));
+        assert!(concepts.iter().any(|c| c.name == "PORT" && c.concept_type == "env_var"));
+    }
+
+    #[tokio::test]
+    async fn test_analysis_report_records_timings() {
+        // Learning saves its graph under the root, so learn from a copy of src/types
+        let dir = tempfile::TempDir::new().unwrap();
+        for entry in std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/src/types")).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|extension| extension == "rs") {
+                std::fs::copy(&path, dir.path().join(path.file_name().unwrap())).unwrap();
+            }
+        }
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        unsafe { analyzer.learn_from_codebase(dir.path().to_string_lossy().to_string()).await }.unwrap();
+
+        let report = analyzer.get_analysis_report(Some(2));
+        assert!(report.files_analyzed >= 3);
+        assert!(report.slowest_files.len() <= 2);
+        assert!(report.slowest_files.windows(2).all(|w| w[0].duration_ms >= w[1].duration_ms));
+        assert!(report.slowest_files.iter().all(|t| t.language == "rust"));
+        assert!(!report.analysis_timed_out);
+        assert!(report.sampling.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_learning_resumes_from_partial_result() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for name in ["a", "b", "c"] {
+            std::fs::write(dir.path().join(format!("{}.ts", name)), format!("export function {}Handler() {{}}\n", name)).unwrap();
+        }
+        std::fs::create_dir_all(dir.path().join(crate::types::CONFIG_DIR)).unwrap();
+        std::fs::write(
+            dir.path().join(crate::types::CONFIG_DIR).join("config.json"),
+            r#"{"analysis": {"learningTimeoutSecs": 0}}"#,
+        )
+        .unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+
+        // A zero budget stops after one file per call
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let error = unsafe { analyzer.learn_from_codebase(path.clone()).await }.unwrap_err();
+        assert!(error.to_string().contains("resume token"));
+        assert!(analyzer.get_analysis_report(None).analysis_timed_out);
+
+        let mut result = unsafe { analyzer.learn_from_codebase_partial(path.clone(), None).await }.unwrap();
+        assert_eq!(result.files_processed, 1);
+        assert_eq!(result.files_remaining.len(), 2);
+        assert!(result.concepts.iter().any(|c| c.name == "aHandler"));
+        assert!(!analyzer.search_concepts("aHandler".to_string(), None).is_empty());
+
+        let stale = result.resume_token.clone().unwrap();
+        while let Some(token) = result.resume_token.clone() {
+            result = unsafe { analyzer.learn_from_codebase_partial(path.clone(), Some(token)).await }.unwrap();
+        }
+        assert!(result.is_complete());
+        assert_eq!(result.files_processed, 3);
+        for name in ["aHandler", "bHandler", "cHandler"] {
+            assert!(result.concepts.iter().any(|c| c.name == name));
+        }
+
+        let expired = unsafe { analyzer.learn_from_codebase_partial(path, Some(stale)).await }.unwrap_err();
+        assert!(expired.to_string().contains("[INVALID_ARGUMENT]"));
+    }
+
+    #[tokio::test]
+    async fn test_resume_learning_from_checkpoint_in_new_analyzer() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for name in ["a", "b", "c"] {
+            std::fs::write(dir.path().join(format!("{}.py", name)), format!("def {}_task():\n    pass\n", name)).unwrap();
+        }
+        std::fs::create_dir_all(dir.path().join(crate::types::CONFIG_DIR)).unwrap();
+        std::fs::write(
+            dir.path().join(crate::types::CONFIG_DIR).join("config.json"),
+            r#"{"analysis": {"learningTimeoutSecs": 0, "checkpointInterval": 1}}"#,
+        )
+        .unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+
+        let first = {
+            let mut analyzer = SemanticAnalyzer::new().unwrap();
+            unsafe { analyzer.learn_from_codebase_partial(path.clone(), None).await }.unwrap()
+        };
+        let checkpoint = first.checkpoint_path.clone().unwrap();
+        assert_eq!(first.files_processed, 1);
+
+        // A fresh analyzer, as after a crash, continues from the file on disk
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let mut result = unsafe { analyzer.resume_learning(checkpoint.clone()).await }.unwrap();
+        assert_eq!(result.files_processed, 2);
+        while let Some(path) = result.checkpoint_path.clone() {
+            result = unsafe { analyzer.resume_learning(path).await }.unwrap();
+        }
+        assert!(result.is_complete());
+        assert_eq!(result.files_processed, 3);
+        for name in ["a_task", "b_task", "c_task"] {
+            assert!(result.concepts.iter().any(|c| c.name == name));
+        }
+        assert!(!std::path::Path::new(&checkpoint).exists());
+
+        let missing = unsafe { analyzer.resume_learning(checkpoint).await }.unwrap_err();
+        assert!(missing.to_string().contains("[PATH_NOT_FOUND]"));
+    }
+
+    #[tokio::test]
+    async fn test_generated_files_are_reported_not_analyzed() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("service.ts"), "export class OrderService {}\n").unwrap();
+        std::fs::write(
+            dir.path().join("schema.ts"),
+            "// @generated by graphql-codegen\nexport class OrderQuery {}\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("third_party")).unwrap();
+        std::fs::write(dir.path().join("third_party/lodash.js"), "function chunk() {}\n").unwrap();
+
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let concepts = unsafe { analyzer.learn_from_codebase(dir.path().to_string_lossy().to_string()).await }.unwrap();
+        assert!(concepts.iter().any(|c| c.name == "OrderService"));
+        assert!(!concepts.iter().any(|c| c.name == "OrderQuery" || c.name == "chunk"));
+
+        let report = analyzer.get_analysis_report(None);
+        assert_eq!(report.skipped_by_reason.get("generated"), Some(&1));
+        assert_eq!(report.skipped_by_reason.get("vendored"), Some(&1));
+        let generated = report.skipped_files.iter().find(|f| f.reason == "generated").unwrap();
+        assert!(generated.file_path.ends_with("schema.ts"));
+        assert_eq!(generated.detail.as_deref(), Some("'graphql-codegen' marker"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlinked_packages_are_analyzed_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("packages/billing")).unwrap();
+        std::fs::write(dir.path().join("packages/billing/ledger.ts"), "export class LedgerService {}\n").unwrap();
+        std::fs::create_dir_all(dir.path().join("apps")).unwrap();
+        std::os::unix::fs::symlink(dir.path().join("packages/billing"), dir.path().join("apps/billing")).unwrap();
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("packages/root")).unwrap();
+        std::fs::create_dir_all(dir.path().join(crate::types::CONFIG_DIR)).unwrap();
+        std::fs::write(
+            dir.path().join(crate::types::CONFIG_DIR).join("config.json"),
+            r#"{"analysis": {"followSymlinks": true}}"#,
+        )
+        .unwrap();
+
+        // A relative spelling of the root yields the same canonical keys
+        let path = dir.path().join("packages/..").to_string_lossy().to_string();
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let concepts = unsafe { analyzer.learn_from_codebase(path).await }.unwrap();
+        let ledgers: Vec<&SemanticConcept> = concepts.iter().filter(|c| c.name == "LedgerService").collect();
+        assert_eq!(ledgers.len(), 1);
+        let expected = SourceWalker::canonical_root(dir.path()).join("packages/billing/ledger.ts");
+        assert_eq!(ledgers[0].file_path, expected.to_string_lossy());
+
+        let report = analyzer.get_analysis_report(None);
+        assert_eq!(report.skipped_by_reason.get("duplicate_path"), Some(&1));
+        assert_eq!(report.skipped_by_reason.get("symlink_cycle"), Some(&1));
+    }
+
+    #[tokio::test]
+    async fn test_concept_tags_drive_search_and_survive_relearning() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("invoice.ts"), "export class InvoiceService {}\n").unwrap();
+        std::fs::write(dir.path().join("billing.ts"), "export class BillingAddress {}\n").unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let concepts = unsafe { analyzer.learn_from_codebase(path.clone()).await }.unwrap();
+        let invoice = concepts.iter().find(|c| c.name == "InvoiceService").unwrap().id.clone();
+
+        let tags = analyzer.tag_concept(invoice.clone(), vec!["Billing".to_string(), " ".to_string()]).unwrap();
+        assert_eq!(tags, vec!["billing".to_string()]);
+        let error = analyzer.tag_concept("missing".to_string(), vec!["billing".to_string()]).unwrap_err();
+        assert!(error.to_string().contains("[INVALID_ARGUMENT]"));
+        assert!(analyzer.tag_concept(invoice.clone(), vec![" ".to_string()]).is_err());
+
+        // The exact tag outranks a name that merely contains the query
+        let found: Vec<String> = analyzer
+            .search_concepts("billing".to_string(), None)
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(found, vec!["InvoiceService".to_string(), "BillingAddress".to_string()]);
+
+        // Saved with the project and re-applied by a fresh analyzer
+        assert!(ConceptTagStore::path(dir.path()).is_file());
+        let mut relearned = SemanticAnalyzer::new().unwrap();
+        let concepts = unsafe { relearned.learn_from_codebase(path.clone()).await }.unwrap();
+        let invoice = concepts.iter().find(|c| c.name == "InvoiceService").unwrap();
+        assert_eq!(invoice.metadata.get("tags").map(String::as_str), Some("billing"));
+        assert_eq!(relearned.get_tag_groups()[0].concepts, vec!["InvoiceService".to_string()]);
+
+        assert!(relearned.untag_concept(invoice.id.clone(), vec!["billing".to_string()]).unwrap().is_empty());
+        assert!(relearned.get_concept_tags(invoice.id.clone()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_relationship_edges_by_kind_and_direction() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("user.ts"), "export class User {}\n").unwrap();
+        std::fs::write(
+            dir.path().join("service.ts"),
+            "import { User } from './user';\n\nexport class UserService {\n  load(): User {\n    return new User();\n  }\n}\n",
+        )
+        .unwrap();
+
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let concepts = unsafe { analyzer.learn_from_codebase(dir.path().to_string_lossy().to_string()).await }.unwrap();
+        let user = concepts.iter().find(|c| c.name == "User").unwrap().id.clone();
+        let service = concepts.iter().find(|c| c.name == "UserService").unwrap().id.clone();
+
+        let imports = analyzer
+            .get_relationship_edges(service.clone(), Some("imports".to_string()), Some("outgoing".to_string()))
+            .unwrap();
+        assert_eq!(imports, vec![RelationshipEdge { from: service.clone(), to: user.clone(), kind: "imports".to_string() }]);
+
+        let incoming = analyzer.get_relationship_edges(user.clone(), None, Some("incoming".to_string())).unwrap();
+        assert!(incoming.iter().any(|edge| edge.from == service && edge.kind == "imports"));
+        assert!(incoming.iter().any(|edge| edge.kind == "uses"));
+        assert!(analyzer
+            .get_concept_relationships(user.clone())
+            .unwrap()
+            .contains(&format!("imported_by:{}", service)));
+
+        let error = analyzer
+            .get_relationship_edges(user.clone(), Some("same_file".to_string()), None)
+            .unwrap_err();
+        assert!(error.to_string().contains("[INVALID_ARGUMENT]"));
+        assert!(analyzer.get_relationship_edges(user, None, Some("up".to_string())).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_entities_declared_in_two_languages_are_unified() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("user.rs"), "pub struct User {\n    pub id: u64,\n    pub email: String,\n}\n").unwrap();
+        std::fs::write(dir.path().join("user.ts"), "export interface User {\n  id: number;\n  email: string;\n}\n").unwrap();
+
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let concepts = unsafe { analyzer.learn_from_codebase(dir.path().to_string_lossy().to_string()).await }.unwrap();
+        let rust_user = concepts.iter().find(|c| c.name == "User" && c.file_path.ends_with(".rs")).unwrap().id.clone();
+
+        let entities = analyzer.unify_domain_entities();
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].languages, vec!["rust", "typescript"]);
+        assert_eq!(analyzer.get_shared_entity(rust_user).map(|e| e.id), Some("shared_entity_user".to_string()));
+        assert!(analyzer.stored_concepts().any(|c| c.concept_type == SHARED_ENTITY_TYPE));
+
+        // Unifying again replaces the entities rather than adding to them
+        analyzer.unify_domain_entities();
+        assert_eq!(analyzer.stored_concepts().filter(|c| c.concept_type == SHARED_ENTITY_TYPE).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_quick_scan_estimates_without_storing_concepts() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for i in 0..6 {
+            std::fs::write(
+                dir.path().join(format!("module_{}.ts", i)),
+                format!("export function loadUser{i}() {{}}\nexport function saveUser{i}() {{}}\n"),
+            )
+            .unwrap();
+        }
+        std::fs::write(dir.path().join("lib.rs"), "pub fn parse_config() {}\n").unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+
+        let analyzer = SemanticAnalyzer::new().unwrap();
+        let summary = analyzer.quick_scan(path.clone(), Some(4)).await.unwrap();
+        assert_eq!(summary.total_files, 7);
+        assert_eq!(summary.sampled_files, 4);
+        assert!(summary.complete);
+        let typescript = summary.languages.iter().find(|l| l.language == "typescript").unwrap();
+        assert_eq!(typescript.files, 6);
+        assert!(typescript.lines.low <= typescript.lines.value && typescript.lines.value <= typescript.lines.high);
+        let functions = summary.conventions.iter().find(|c| c.language == "typescript").unwrap();
+        assert_eq!(functions.convention, "camelCase");
+        assert_eq!(analyzer.stored_concepts().count(), 0);
+
+        assert!(analyzer.quick_scan(path, Some(0)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_quick_scan_leaves_the_learned_project_alone() {
+        let learned = tempfile::TempDir::new().unwrap();
+        std::fs::write(learned.path().join("cart.ts"), "export class Cart {\n  total() {}\n}\n").unwrap();
+        let scanned = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(scanned.path().join(crate::types::CONFIG_DIR)).unwrap();
+        std::fs::write(scanned.path().join(crate::types::CONFIG_DIR).join("config.json"), "{}").unwrap();
+        std::fs::write(scanned.path().join("cart.ts"), "// another project\n").unwrap();
+
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        unsafe { analyzer.learn_from_codebase(learned.path().to_string_lossy().to_string()).await }.unwrap();
+        let root = analyzer.project_root().map(str::to_string);
+        let cart = analyzer.stored_concepts().find(|c| c.name == "Cart").unwrap().id.clone();
+        let before = analyzer.get_concept_context(cart.clone(), None, None, None).unwrap();
+
+        analyzer.quick_scan(scanned.path().to_string_lossy().to_string(), None).await.unwrap();
+        assert_eq!(analyzer.project_root().map(str::to_string), root);
+        let after = analyzer.get_concept_context(cart, None, None, None).unwrap();
+        assert_eq!(serde_json::to_string(&after).unwrap(), serde_json::to_string(&before).unwrap());
+        assert!(serde_json::to_string(&after).unwrap().contains("total()"));
+    }
+
+    #[tokio::test]
+    async fn test_saved_concept_graph_answers_queries_after_reload() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("cart.ts"), "export class Cart {}\n").unwrap();
+        std::fs::write(
+            dir.path().join("checkout.ts"),
+            "import { Cart } from './cart';\n\nexport class Checkout {}\n",
+        )
+        .unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+
+        let mut analyzer = SemanticAnalyzer::new().unwrap();
+        let concepts = unsafe { analyzer.learn_from_codebase(path.clone()).await }.unwrap();
+        let cart = concepts.iter().find(|c| c.name == "Cart").unwrap().id.clone();
+        let checkout = concepts.iter().find(|c| c.name == "Checkout").unwrap().id.clone();
+        assert!(GraphStore::path(dir.path()).is_file());
+
+        let mut reloaded = SemanticAnalyzer::new().unwrap();
+        assert!(reloaded.load_concept_graph(path).unwrap());
+        let route = reloaded.find_concept_path(cart.clone(), checkout.clone()).unwrap();
+        assert_eq!(route.len(), 1);
+        assert_eq!(route[0].kind, "imports");
+
+        let feature = reloaded.get_feature_subgraph("checkout".to_string(), None).unwrap();
+        assert!(feature.nodes.iter().any(|node| node.id == cart));
+        let neighbors = reloaded.get_concept_neighbors(cart.clone(), Some(0), None, None).unwrap();
+        assert_eq!(neighbors.nodes.len(), 1);
+
+        let error = reloaded.get_concept_neighbors(cart.clone(), Some(9), None, None).unwrap_err();
+        assert!(error.to_string().contains("[INVALID_ARGUMENT]"));
+        assert!(reloaded.find_concept_path(cart, "missing".to_string()).is_err());
+
+        // Single-file analysis updates the graph in memory only
+        std::fs::remove_file(GraphStore::path(dir.path())).unwrap();
+        let file = dir.path().join("cart.ts").to_string_lossy().to_string();
+        unsafe { analyzer.analyze_file_content(file, "export class Cart {}\n".to_string()).await }.unwrap();
+        assert!(!GraphStore::path(dir.path()).exists());
+    }
 }
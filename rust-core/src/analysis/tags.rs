@@ -0,0 +1,251 @@
+//! User-defined tags on concepts
+//!
+//! Tags carry domain knowledge the code doesn't spell out ("billing", "auth").
+//! Concept ids change between runs, so a tag is attached to a concept's name and
+//! file and re-applied whenever that concept is extracted again. Tags are saved
+//! to `.in-memoria/concept-tags.json` with file paths relative to the project.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::types::{paths, ParseError, SemanticConcept, CONFIG_DIR};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// File holding concept tags, inside the project's config directory
+pub const TAGS_FILE: &str = "concept-tags.json";
+
+const TAGS_VERSION: u32 = 1;
+
+/// Metadata key listing a concept's tags, comma separated
+pub const TAGS_METADATA_KEY: &str = "tags";
+
+/// Tags attached to one concept
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct TaggedConcept {
+    pub name: String,
+    pub file_path: String,
+    pub tags: Vec<String>,
+}
+
+/// Concepts and files sharing a tag
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct TagGroup {
+    pub tag: String,
+    pub concepts: Vec<String>,
+    pub files: Vec<String>,
+}
+
+/// All tags of a project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ConceptTagStore {
+    pub version: u32,
+    pub concepts: Vec<TaggedConcept>,
+}
+
+impl ConceptTagStore {
+    /// Lower-case, trimmed form of a tag; `None` for blank tags
+    pub fn normalize(tag: &str) -> Option<String> {
+        let tag = tag.trim().to_lowercase();
+        (!tag.is_empty()).then_some(tag)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.concepts.is_empty()
+    }
+
+    /// Tags of a concept, sorted
+    pub fn tags_for(&self, concept: &SemanticConcept) -> Vec<String> {
+        self.concepts
+            .iter()
+            .find(|entry| Self::matches(entry, concept))
+            .map(|entry| entry.tags.clone())
+            .unwrap_or_default()
+    }
+
+    /// Add tags to a concept, returning all of its tags
+    pub fn add(&mut self, concept: &SemanticConcept, tags: &[String]) -> Vec<String> {
+        let index = match self.concepts.iter().position(|entry| Self::matches(entry, concept)) {
+            Some(index) => index,
+            None => {
+                self.concepts.push(TaggedConcept {
+                    name: concept.name.clone(),
+                    file_path: concept.file_path.clone(),
+                    tags: Vec::new(),
+                });
+                self.concepts.len() - 1
+            }
+        };
+
+        let entry = &mut self.concepts[index];
+        entry.tags.extend(tags.iter().filter_map(|tag| Self::normalize(tag)));
+        entry.tags.sort();
+        entry.tags.dedup();
+        entry.tags.clone()
+    }
+
+    /// Remove tags from a concept, returning the tags it keeps
+    pub fn remove(&mut self, concept: &SemanticConcept, tags: &[String]) -> Vec<String> {
+        let removed: Vec<String> = tags.iter().filter_map(|tag| Self::normalize(tag)).collect();
+        let Some(entry) = self.concepts.iter_mut().find(|entry| Self::matches(entry, concept)) else {
+            return Vec::new();
+        };
+        entry.tags.retain(|tag| !removed.contains(tag));
+        let remaining = entry.tags.clone();
+        self.concepts.retain(|entry| !entry.tags.is_empty());
+        remaining
+    }
+
+    /// Record a concept's tags in its metadata so they travel with it
+    pub fn apply(&self, concept: &mut SemanticConcept) {
+        let tags = self.tags_for(concept);
+        if tags.is_empty() {
+            concept.metadata.remove(TAGS_METADATA_KEY);
+        } else {
+            concept.metadata.insert(TAGS_METADATA_KEY.to_string(), tags.join(","));
+        }
+    }
+
+    /// Concepts and files per tag, tags in alphabetical order
+    pub fn groups(&self) -> Vec<TagGroup> {
+        let mut groups: BTreeMap<&str, TagGroup> = BTreeMap::new();
+        for entry in &self.concepts {
+            for tag in &entry.tags {
+                let group = groups.entry(tag.as_str()).or_insert_with(|| TagGroup {
+                    tag: tag.clone(),
+                    concepts: Vec::new(),
+                    files: Vec::new(),
+                });
+                group.concepts.push(entry.name.clone());
+                group.files.push(entry.file_path.clone());
+            }
+        }
+
+        groups
+            .into_values()
+            .map(|mut group| {
+                group.concepts.sort();
+                group.concepts.dedup();
+                group.files.sort();
+                group.files.dedup();
+                group
+            })
+            .collect()
+    }
+
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(CONFIG_DIR).join(TAGS_FILE)
+    }
+
+    /// Read a project's tags; `None` when none were saved
+    pub fn load(project_root: &Path) -> Result<Option<Self>, ParseError> {
+        let path = Self::path(project_root);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            ParseError::from_reason(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| ParseError::from_reason(format!("Invalid {}: {}", TAGS_FILE, e)))
+    }
+
+    /// Write the tags with file paths relative to the project root
+    pub fn save(&self, project_root: &Path) -> Result<(), ParseError> {
+        let mut store = self.clone();
+        store.version = TAGS_VERSION;
+        for entry in &mut store.concepts {
+            entry.file_path = paths::relative_to(&entry.file_path, project_root);
+        }
+        store.concepts.sort_by(|a, b| (&a.file_path, &a.name).cmp(&(&b.file_path, &b.name)));
+        store.concepts.dedup_by(|a, b| a.file_path == b.file_path && a.name == b.name);
+
+        let path = Self::path(project_root);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                ParseError::from_reason(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+        let content = serde_json::to_string_pretty(&store)
+            .map_err(|e| ParseError::from_reason(format!("Failed to serialize concept tags: {}", e)))?;
+        std::fs::write(&path, content).map_err(|e| {
+            ParseError::from_reason(format!("Failed to write {}: {}", path.display(), e))
+        })
+    }
+
+    /// Same name, and the same file or a file ending with the saved relative
+    /// path, whichever separators either was written with
+    fn matches(entry: &TaggedConcept, concept: &SemanticConcept) -> bool {
+        entry.name == concept.name && paths::same_file(&concept.file_path, &entry.file_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LineRange;
+    use std::collections::HashMap;
+
+    fn concept(name: &str, file_path: &str) -> SemanticConcept {
+        SemanticConcept {
+            id: format!("{}_{}", file_path, name),
+            name: name.to_string(),
+            concept_type: "class".to_string(),
+            confidence: 0.9,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start: 1, end: 1 },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_tags_are_normalized_and_grouped() {
+        let invoice = concept("InvoiceService", "/repo/src/billing/invoice.ts");
+        let login = concept("LoginController", "/repo/src/auth/login.ts");
+
+        let mut store = ConceptTagStore::default();
+        assert_eq!(store.add(&invoice, &[" Billing ".to_string(), "".to_string()]), vec!["billing"]);
+        assert_eq!(store.add(&invoice, &["payments".to_string(), "billing".to_string()]), vec!["billing", "payments"]);
+        store.add(&login, &["auth".to_string()]);
+
+        let groups = store.groups();
+        let tags: Vec<&str> = groups.iter().map(|g| g.tag.as_str()).collect();
+        assert_eq!(tags, vec!["auth", "billing", "payments"]);
+        assert_eq!(groups[1].concepts, vec!["InvoiceService".to_string()]);
+
+        let mut tagged = invoice.clone();
+        store.apply(&mut tagged);
+        assert_eq!(tagged.metadata.get(TAGS_METADATA_KEY).map(String::as_str), Some("billing,payments"));
+
+        assert!(store.remove(&login, &["AUTH".to_string()]).is_empty());
+        assert_eq!(store.concepts.len(), 1);
+    }
+
+    #[test]
+    fn test_saved_tags_follow_concepts_across_roots() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("src/billing/invoice.ts");
+        let invoice = concept("InvoiceService", &file.to_string_lossy());
+
+        let mut store = ConceptTagStore::default();
+        store.add(&invoice, &["billing".to_string()]);
+        store.save(dir.path()).unwrap();
+
+        let loaded = ConceptTagStore::load(dir.path()).unwrap().unwrap();
+        assert_eq!(loaded.version, TAGS_VERSION);
+        assert_eq!(loaded.concepts[0].file_path, "src/billing/invoice.ts");
+        assert_eq!(loaded.tags_for(&invoice), vec!["billing".to_string()]);
+        let windows = concept("InvoiceService", r"D:\checkout\src\billing\invoice.ts");
+        assert_eq!(loaded.tags_for(&windows), vec!["billing".to_string()]);
+        assert!(loaded.tags_for(&concept("InvoiceService", "src/other.ts")).is_empty());
+    }
+}
@@ -0,0 +1,235 @@
+//! Hooks for host-side metrics
+//!
+//! The core reports what it did through a [`Telemetry`] implementation and
+//! never links a metrics backend itself. Every hook has a no-op default, so an
+//! implementation only overrides the events it cares about. Node.js hosts pass
+//! a callback that receives each event as a [`TelemetryEvent`].
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::FileTiming;
+use crate::patterns::types::Pattern;
+use std::sync::Arc;
+
+/// Receives events from analysis and learning. Hooks run on the analyzing
+/// thread, so implementations should hand work off rather than block.
+pub trait Telemetry: Send + Sync {
+    /// A file was analyzed, by tree-sitter or by fallback extraction
+    fn on_file_parsed(&self, _timing: &FileTiming) {}
+
+    /// A pattern was added to the learned set; `source` names the analyzer
+    /// or store it came from
+    fn on_pattern_learned(&self, _pattern: &Pattern, _source: &str) {}
+
+    /// A time budget ran out. `scope` is `file` for one file's parse and
+    /// `learning` for a whole run.
+    fn on_timeout(&self, _scope: &str, _file_path: Option<&str>, _elapsed_ms: f64) {}
+
+    /// A file was analyzed with regex extraction because tree-sitter timed out
+    /// or failed (`reason` is `timeout` or `parse_error`)
+    fn on_fallback_used(&self, _file_path: &str, _reason: &str) {}
+}
+
+/// Handle analyzers keep to their telemetry
+pub type SharedTelemetry = Arc<dyn Telemetry>;
+
+/// Telemetry that drops every event; the default for analyzers
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopTelemetry;
+
+impl Telemetry for NoopTelemetry {}
+
+pub fn noop_telemetry() -> SharedTelemetry {
+    Arc::new(NoopTelemetry)
+}
+
+/// One telemetry hook call, flattened for the host callback. Fields that
+/// don't apply to the event's `kind` are unset.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct TelemetryEvent {
+    /// `file_parsed`, `pattern_learned`, `timeout` or `fallback_used`
+    pub kind: String,
+    pub file_path: Option<String>,
+    pub language: Option<String>,
+    pub duration_ms: Option<f64>,
+    pub concept_count: Option<u32>,
+    pub pattern_id: Option<String>,
+    pub pattern_type: Option<String>,
+    pub confidence: Option<f64>,
+    /// Analyzer a pattern came from, timeout scope or fallback reason
+    pub detail: Option<String>,
+}
+
+impl TelemetryEvent {
+    pub fn file_parsed(timing: &FileTiming) -> Self {
+        TelemetryEvent {
+            kind: "file_parsed".to_string(),
+            file_path: Some(timing.file_path.clone()),
+            language: Some(timing.language.clone()),
+            duration_ms: Some(timing.duration_ms),
+            concept_count: Some(timing.concept_count),
+            ..Default::default()
+        }
+    }
+
+    pub fn pattern_learned(pattern: &Pattern, source: &str) -> Self {
+        TelemetryEvent {
+            kind: "pattern_learned".to_string(),
+            pattern_id: Some(pattern.id.clone()),
+            pattern_type: Some(pattern.pattern_type.clone()),
+            confidence: Some(pattern.confidence),
+            detail: Some(source.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn timeout(scope: &str, file_path: Option<&str>, elapsed_ms: f64) -> Self {
+        TelemetryEvent {
+            kind: "timeout".to_string(),
+            file_path: file_path.map(str::to_string),
+            duration_ms: Some(elapsed_ms),
+            detail: Some(scope.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn fallback_used(file_path: &str, reason: &str) -> Self {
+        TelemetryEvent {
+            kind: "fallback_used".to_string(),
+            file_path: Some(file_path.to_string()),
+            detail: Some(reason.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Telemetry forwarding each event to a JavaScript callback without waiting
+/// for it to run
+#[cfg(feature = "napi-bindings")]
+pub struct CallbackTelemetry {
+    callback: napi::threadsafe_function::ThreadsafeFunction<TelemetryEvent, (), TelemetryEvent, napi::Status, false>,
+}
+
+#[cfg(feature = "napi-bindings")]
+impl CallbackTelemetry {
+    pub fn new(
+        callback: napi::threadsafe_function::ThreadsafeFunction<TelemetryEvent, (), TelemetryEvent, napi::Status, false>,
+    ) -> Self {
+        CallbackTelemetry { callback }
+    }
+
+    fn send(&self, event: TelemetryEvent) {
+        self.callback
+            .call(event, napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking);
+    }
+}
+
+#[cfg(feature = "napi-bindings")]
+impl Telemetry for CallbackTelemetry {
+    fn on_file_parsed(&self, timing: &FileTiming) {
+        self.send(TelemetryEvent::file_parsed(timing));
+    }
+
+    fn on_pattern_learned(&self, pattern: &Pattern, source: &str) {
+        self.send(TelemetryEvent::pattern_learned(pattern, source));
+    }
+
+    fn on_timeout(&self, scope: &str, file_path: Option<&str>, elapsed_ms: f64) {
+        self.send(TelemetryEvent::timeout(scope, file_path, elapsed_ms));
+    }
+
+    fn on_fallback_used(&self, file_path: &str, reason: &str) {
+        self.send(TelemetryEvent::fallback_used(file_path, reason));
+    }
+}
+
+/// Telemetry that keeps every event, for tests
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub(crate) struct RecordingTelemetry {
+    pub events: std::sync::Mutex<Vec<TelemetryEvent>>,
+}
+
+#[cfg(test)]
+impl RecordingTelemetry {
+    pub fn kinds(&self) -> Vec<String> {
+        self.events.lock().unwrap().iter().map(|event| event.kind.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+impl Telemetry for RecordingTelemetry {
+    fn on_file_parsed(&self, timing: &FileTiming) {
+        self.events.lock().unwrap().push(TelemetryEvent::file_parsed(timing));
+    }
+
+    fn on_pattern_learned(&self, pattern: &Pattern, source: &str) {
+        self.events.lock().unwrap().push(TelemetryEvent::pattern_learned(pattern, source));
+    }
+
+    fn on_timeout(&self, scope: &str, file_path: Option<&str>, elapsed_ms: f64) {
+        self.events.lock().unwrap().push(TelemetryEvent::timeout(scope, file_path, elapsed_ms));
+    }
+
+    fn on_fallback_used(&self, file_path: &str, reason: &str) {
+        self.events.lock().unwrap().push(TelemetryEvent::fallback_used(file_path, reason));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Overrides one hook and relies on the defaults for the rest
+    #[derive(Default)]
+    struct TimeoutCounter(std::sync::atomic::AtomicUsize);
+
+    impl Telemetry for TimeoutCounter {
+        fn on_timeout(&self, _scope: &str, _file_path: Option<&str>, _elapsed_ms: f64) {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_hooks_default_to_no_ops() {
+        let timing = FileTiming {
+            file_path: "src/app.ts".to_string(),
+            language: "typescript".to_string(),
+            duration_ms: 1.5,
+            concept_count: 3,
+            used_fallback: false,
+            timed_out: false,
+        };
+        let counter = TimeoutCounter::default();
+        counter.on_file_parsed(&timing);
+        counter.on_fallback_used("src/app.ts", "parse_error");
+        counter.on_timeout("learning", None, 60_000.0);
+        assert_eq!(counter.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        noop_telemetry().on_file_parsed(&timing);
+    }
+
+    #[test]
+    fn test_events_carry_the_hook_arguments() {
+        let pattern = Pattern {
+            id: "naming_camel".to_string(),
+            pattern_type: "naming".to_string(),
+            description: "camelCase functions".to_string(),
+            frequency: 4,
+            confidence: 0.8,
+            examples: vec![],
+            contexts: vec![],
+        };
+        let event = TelemetryEvent::pattern_learned(&pattern, "naming");
+        assert_eq!(event.kind, "pattern_learned");
+        assert_eq!(event.pattern_type.as_deref(), Some("naming"));
+        assert_eq!(event.confidence, Some(0.8));
+        assert_eq!(event.file_path, None);
+
+        let timeout = TelemetryEvent::timeout("file", Some("src/huge.ts"), 30_000.0);
+        assert_eq!(timeout.detail.as_deref(), Some("file"));
+        assert_eq!(timeout.file_path.as_deref(), Some("src/huge.ts"));
+    }
+}
@@ -0,0 +1,545 @@
+//! Test framework detection and test inventory
+//!
+//! Recognizes jest, vitest, pytest, cargo test, JUnit and go test from
+//! manifests, config files and the test files themselves, then lists each test
+//! file as a suite with its cases:
+//! - jest/vitest: `describe(..)`, `it(..)`/`test(..)` in `*.test.*`, `*.spec.*` and `__tests__/`
+//! - pytest: `class Test..`, `def test_..` in `test_*.py` and `*_test.py`
+//! - cargo test: `#[cfg(test)] mod ..`, functions marked `#[test]`
+//! - JUnit: the test class, methods marked `@Test`
+//! - go test: `func TestX(t *testing.T)` in `*_test.go`
+//!
+//! Counts are textual, so a parameterized or generated case counts once.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::ManifestParser;
+use crate::types::ParseError;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use walkdir::WalkDir;
+
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", ".venv", "target", "vendor"];
+
+/// Files larger than this are not read for tests
+const MAX_TEST_FILE_SIZE: u64 = 512 * 1024;
+
+const JAVASCRIPT_EXTENSIONS: &[&str] = &["js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts"];
+
+/// A test framework the project uses
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct TestFramework {
+    /// 'jest', 'vitest', 'pytest', 'cargo-test', 'junit' or 'go-test'
+    pub name: String,
+    pub language: String,
+    /// Command that runs the framework's tests
+    pub command: String,
+    /// Dependencies and config files that declare the framework
+    pub evidence: Vec<String>,
+    pub suite_count: u32,
+    pub case_count: u32,
+    /// The test file with the most cases, to model new tests on
+    pub example_file: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct TestCase {
+    pub name: String,
+    pub line: u32,
+}
+
+/// The tests in one file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct TestSuite {
+    /// First `describe` block, test class or test module; the file stem otherwise
+    pub name: String,
+    pub file_path: String,
+    pub framework: String,
+    pub line: u32,
+    pub cases: Vec<TestCase>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct TestInventory {
+    /// Frameworks with the most cases first
+    pub frameworks: Vec<TestFramework>,
+    pub suites: Vec<TestSuite>,
+    pub total_cases: u32,
+}
+
+impl TestInventory {
+    /// The framework most of the project's tests are written with
+    pub fn primary_framework(&self) -> Option<&TestFramework> {
+        self.frameworks.first()
+    }
+}
+
+/// What manifests and config files say about the test setup
+#[derive(Default)]
+struct Declarations {
+    evidence: BTreeMap<&'static str, BTreeSet<String>>,
+    /// `scripts.test` of the root package.json
+    npm_test_script: Option<String>,
+    maven: bool,
+}
+
+impl Declarations {
+    fn add(&mut self, framework: &'static str, evidence: String) {
+        self.evidence.entry(framework).or_default().insert(evidence);
+    }
+}
+
+pub struct TestInventoryScanner;
+
+impl TestInventoryScanner {
+    /// Detect test frameworks and list the test suites under `root`
+    pub fn scan(root: &Path) -> Result<TestInventory, ParseError> {
+        let mut declarations = Declarations::default();
+        let mut suites = Vec::new();
+
+        for entry in WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if !entry.file_type().is_file() || entry.metadata().map(|m| m.len() > MAX_TEST_FILE_SIZE).unwrap_or(true) {
+                continue;
+            }
+            let file_name = entry.file_name().to_str().unwrap_or("");
+            let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+
+            if let Some(framework) = Self::config_framework(file_name) {
+                declarations.add(framework, relative);
+                continue;
+            }
+            let is_manifest = Self::is_manifest(file_name);
+            if !is_manifest && Self::candidate_framework(&relative).is_none() {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(path) else { continue };
+            if is_manifest {
+                Self::read_manifest(file_name, &relative, &content, &mut declarations);
+            } else {
+                suites.extend(Self::extract(&relative, &content));
+            }
+        }
+
+        Ok(Self::summarize(suites, declarations))
+    }
+
+    /// The suite in one file, when it has at least one test case
+    pub fn extract(file_path: &str, content: &str) -> Option<TestSuite> {
+        let framework = match Self::candidate_framework(file_path)? {
+            "javascript" => Self::javascript_framework(content),
+            framework => framework,
+        };
+        let p = patterns();
+        let mut suite: Option<(String, u32)> = None;
+        let mut cases = Vec::new();
+        // An attribute or annotation marked the next function as a test
+        let mut pending_case = false;
+        // `#[cfg(test)]` marked the next module as the test module
+        let mut pending_module = false;
+
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index as u32 + 1;
+            let (suite_match, case_match) = match framework {
+                "pytest" => (p.python_class.captures(line), p.python_case.captures(line)),
+                "cargo-test" => {
+                    if p.rust_test_attribute.is_match(line) {
+                        pending_case = true;
+                    } else if p.rust_cfg_test.is_match(line) {
+                        pending_module = true;
+                    }
+                    let module = p.rust_module.captures(line);
+                    let function = p.rust_function.captures(line);
+                    let suite_match = module.filter(|_| std::mem::take(&mut pending_module));
+                    let case_match = function.filter(|_| std::mem::take(&mut pending_case));
+                    (suite_match, case_match)
+                }
+                "junit" => {
+                    if p.java_test_annotation.is_match(line) {
+                        pending_case = true;
+                    }
+                    let method = p.java_method.captures(line);
+                    (p.java_class.captures(line), method.filter(|_| std::mem::take(&mut pending_case)))
+                }
+                "go-test" => (None, p.go_case.captures(line)),
+                _ => (p.javascript_suite.captures(line), p.javascript_case.captures(line)),
+            };
+            if let Some(c) = suite_match {
+                suite.get_or_insert_with(|| (first_group(&c), line_number));
+            }
+            if let Some(c) = case_match {
+                cases.push(TestCase { name: first_group(&c), line: line_number });
+            }
+        }
+
+        let first_case = cases.first()?.line;
+        let file_name = file_path.rsplit('/').next().unwrap_or(file_path);
+        let stem = file_name.split('.').next().unwrap_or(file_name);
+        let (name, line) = suite.unwrap_or_else(|| (stem.to_string(), first_case));
+        Some(TestSuite { name, file_path: file_path.to_string(), framework: framework.to_string(), line, cases })
+    }
+
+    /// Framework a file's tests would be written with, 'javascript' when it is
+    /// a JS/TS test file that could be either jest or vitest
+    fn candidate_framework(file_path: &str) -> Option<&'static str> {
+        let file_name = file_path.rsplit('/').next().unwrap_or(file_path);
+        let extension = file_name.rsplit_once('.').map(|(_, e)| e).unwrap_or("");
+        match extension {
+            e if JAVASCRIPT_EXTENSIONS.contains(&e) => {
+                let is_test = file_name.contains(".test.")
+                    || file_name.contains(".spec.")
+                    || file_path.split('/').any(|segment| segment == "__tests__");
+                is_test.then_some("javascript")
+            }
+            "py" => (file_name.starts_with("test_") || file_name.ends_with("_test.py")).then_some("pytest"),
+            "rs" => Some("cargo-test"),
+            "java" => Some("junit"),
+            "go" => file_name.ends_with("_test.go").then_some("go-test"),
+            _ => None,
+        }
+    }
+
+    /// Which runner a JS/TS test file was written for; empty when it imports
+    /// neither and the project's declarations decide
+    fn javascript_framework(content: &str) -> &'static str {
+        let p = patterns();
+        if p.vitest_import.is_match(content) {
+            "vitest"
+        } else if content.contains("@jest/globals") || p.jest_api.is_match(content) {
+            "jest"
+        } else {
+            ""
+        }
+    }
+
+    fn config_framework(file_name: &str) -> Option<&'static str> {
+        if file_name.starts_with("jest.config.") {
+            Some("jest")
+        } else if file_name.starts_with("vitest.config.") || file_name.starts_with("vitest.workspace.") {
+            Some("vitest")
+        } else if file_name == "pytest.ini" || file_name == "conftest.py" {
+            Some("pytest")
+        } else {
+            None
+        }
+    }
+
+    fn is_manifest(file_name: &str) -> bool {
+        matches!(file_name, "package.json" | "pyproject.toml" | "pom.xml" | "build.gradle" | "build.gradle.kts")
+            || (file_name.starts_with("requirements") && file_name.ends_with(".txt"))
+    }
+
+    fn read_manifest(file_name: &str, relative: &str, content: &str, declarations: &mut Declarations) {
+        match file_name {
+            "package.json" => {
+                if let Ok(Some(manifest)) = ManifestParser::parse(file_name, content) {
+                    for dependency in manifest.dependencies {
+                        let name = dependency.name.as_str();
+                        if matches!(name, "jest" | "ts-jest" | "babel-jest") || name.starts_with("@jest/") {
+                            declarations.add("jest", format!("{}: {}", relative, name));
+                        } else if name == "vitest" || name.starts_with("@vitest/") {
+                            declarations.add("vitest", format!("{}: {}", relative, name));
+                        }
+                    }
+                }
+                let json: Option<JsonValue> = serde_json::from_str(content).ok();
+                let Some(script) = json.as_ref().and_then(|j| j["scripts"]["test"].as_str()) else { return };
+                if script.contains("vitest") {
+                    declarations.add("vitest", format!("{}: scripts.test", relative));
+                } else if script.contains("jest") {
+                    declarations.add("jest", format!("{}: scripts.test", relative));
+                }
+                if relative == "package.json" {
+                    declarations.npm_test_script = Some(script.to_string());
+                }
+            }
+            "pyproject.toml" => {
+                if content.contains("[tool.pytest") {
+                    declarations.add("pytest", format!("{}: [tool.pytest]", relative));
+                }
+                let declares_pytest = ManifestParser::parse(file_name, content)
+                    .ok()
+                    .flatten()
+                    .is_some_and(|m| m.dependencies.iter().any(|d| d.name == "pytest"));
+                if declares_pytest {
+                    declarations.add("pytest", format!("{}: pytest", relative));
+                }
+            }
+            "pom.xml" | "build.gradle" | "build.gradle.kts" => {
+                declarations.maven |= file_name == "pom.xml";
+                if content.contains("junit") {
+                    declarations.add("junit", format!("{}: junit", relative));
+                }
+            }
+            _ => {
+                // requirements*.txt: `pytest`, `pytest==8.0`, `pytest>=7; python_version > "3.8"`
+                let declares_pytest = content.lines().any(|line| {
+                    let name_end = line.find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_')).unwrap_or(line.len());
+                    line[..name_end].eq_ignore_ascii_case("pytest")
+                });
+                if declares_pytest {
+                    declarations.add("pytest", format!("{}: pytest", relative));
+                }
+            }
+        }
+    }
+
+    fn summarize(mut suites: Vec<TestSuite>, declarations: Declarations) -> TestInventory {
+        // Test files that import neither runner use whichever one the project declares
+        let javascript_default =
+            if declarations.evidence.contains_key("vitest") && !declarations.evidence.contains_key("jest") {
+                "vitest"
+            } else {
+                "jest"
+            };
+        for suite in suites.iter_mut().filter(|s| s.framework.is_empty()) {
+            suite.framework = javascript_default.to_string();
+        }
+
+        let mut names: BTreeSet<&str> = declarations.evidence.keys().copied().collect();
+        names.extend(suites.iter().map(|s| s.framework.as_str()));
+
+        let mut frameworks: Vec<TestFramework> = names
+            .into_iter()
+            .map(|name| {
+                let framework_suites: Vec<&TestSuite> = suites.iter().filter(|s| s.framework == name).collect();
+                let language = match name {
+                    "jest" | "vitest" => {
+                        let typescript = framework_suites.iter().any(|s| {
+                            let extension = s.file_path.rsplit('.').next().unwrap_or("");
+                            matches!(extension, "ts" | "tsx" | "mts" | "cts")
+                        });
+                        if typescript { "typescript" } else { "javascript" }
+                    }
+                    "pytest" => "python",
+                    "cargo-test" => "rust",
+                    "junit" => "java",
+                    _ => "go",
+                };
+                let command = match name {
+                    "jest" | "vitest" if declarations.npm_test_script.as_deref().is_some_and(|s| s.contains(name)) => {
+                        "npm test"
+                    }
+                    "jest" => "npx jest",
+                    "vitest" => "npx vitest run",
+                    "pytest" => "pytest",
+                    "cargo-test" => "cargo test",
+                    "junit" if declarations.maven => "mvn test",
+                    "junit" => "gradle test",
+                    _ => "go test ./...",
+                };
+                TestFramework {
+                    name: name.to_string(),
+                    language: language.to_string(),
+                    command: command.to_string(),
+                    evidence: declarations.evidence.get(name).map(|e| e.iter().cloned().collect()).unwrap_or_default(),
+                    suite_count: framework_suites.len() as u32,
+                    case_count: framework_suites.iter().map(|s| s.cases.len() as u32).sum(),
+                    example_file: framework_suites
+                        .iter()
+                        .min_by_key(|s| Reverse(s.cases.len()))
+                        .map(|s| s.file_path.clone()),
+                }
+            })
+            .collect();
+        frameworks.sort_by(|a, b| b.case_count.cmp(&a.case_count).then_with(|| a.name.cmp(&b.name)));
+
+        let total_cases = suites.iter().map(|s| s.cases.len() as u32).sum();
+        TestInventory { frameworks, suites, total_cases }
+    }
+}
+
+/// The first alternative of a pattern that matched
+fn first_group(c: &Captures) -> String {
+    c.iter().skip(1).flatten().next().map(|m| m.as_str().to_string()).unwrap_or_default()
+}
+
+struct Patterns {
+    javascript_suite: Regex,
+    javascript_case: Regex,
+    vitest_import: Regex,
+    jest_api: Regex,
+    python_class: Regex,
+    python_case: Regex,
+    rust_test_attribute: Regex,
+    rust_cfg_test: Regex,
+    rust_module: Regex,
+    rust_function: Regex,
+    java_test_annotation: Regex,
+    java_class: Regex,
+    java_method: Regex,
+    go_case: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let re = |pattern: &str| Regex::new(pattern).expect("valid test pattern regex");
+        Patterns {
+            javascript_suite: re(
+                r#"^\s*describe(?:\.(?:only|skip|concurrent))?\s*\(\s*(?:'([^']*)'|"([^"]*)"|`([^`]*)`)"#,
+            ),
+            javascript_case: re(
+                r#"^\s*(?:it|test)(?:\.(?:only|skip|todo|concurrent|failing))?\s*\(\s*(?:'([^']*)'|"([^"]*)"|`([^`]*)`)"#,
+            ),
+            vitest_import: re(r#"from\s+['"]vitest['"]|require\(\s*['"]vitest['"]\s*\)"#),
+            jest_api: re(r"\bjest\.(?:fn|mock|spyOn|useFakeTimers)\b"),
+            python_class: re(r"^class\s+(?:(Test\w*)|(\w+)\s*\([^)]*TestCase\s*\))"),
+            python_case: re(r"^\s*(?:async\s+)?def\s+(test\w*)\s*\("),
+            rust_test_attribute: re(r"^\s*#\[(?:(?:tokio|async_std)::)?test\b|^\s*#\[rstest\]"),
+            rust_cfg_test: re(r"^\s*#\[cfg\(test\)\]"),
+            rust_module: re(r"^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+(\w+)"),
+            rust_function: re(r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:async\s+)?fn\s+(\w+)"),
+            java_test_annotation: re(r"^\s*@(?:Test|ParameterizedTest|RepeatedTest|TestFactory)\b"),
+            java_class: re(r"^\s*(?:(?:public|final|abstract)\s+)*class\s+(\w+)"),
+            java_method: re(r"\bvoid\s+(\w+)\s*\("),
+            go_case: re(r"^func\s+(Test\w+)\s*\(\s*\w+\s+\*testing\.T\s*\)"),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case_names(suite: &TestSuite) -> Vec<&str> {
+        suite.cases.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_extract_suites_per_framework() {
+        let javascript = "\
+import { describe, it, expect } from 'vitest';
+
+describe('UserService', () => {
+  it('creates users', () => {});
+  it.skip(\"rejects duplicates\", () => {});
+  test(`counts ${kind}`, () => {});
+});
+";
+        let suite = TestInventoryScanner::extract("src/user.spec.ts", javascript).unwrap();
+        assert_eq!((suite.name.as_str(), suite.framework.as_str(), suite.line), ("UserService", "vitest", 3));
+        assert_eq!(case_names(&suite), vec!["creates users", "rejects duplicates", "counts ${kind}"]);
+        assert!(TestInventoryScanner::extract("src/user.ts", javascript).is_none());
+
+        let python = "\
+import pytest
+
+class TestParser:
+    def test_empty(self):
+        pass
+
+    async def test_stream(self):
+        pass
+
+def helper():
+    pass
+";
+        let suite = TestInventoryScanner::extract("tests/test_parser.py", python).unwrap();
+        assert_eq!((suite.name.as_str(), suite.framework.as_str()), ("TestParser", "pytest"));
+        assert_eq!(case_names(&suite), vec!["test_empty", "test_stream"]);
+
+        let rust = "\
+pub fn parse() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() {}
+
+    #[test]
+    #[should_panic]
+    fn test_parse() {}
+
+    #[tokio::test]
+    async fn test_async_parse() {}
+}
+";
+        let suite = TestInventoryScanner::extract("src/parser.rs", rust).unwrap();
+        assert_eq!((suite.name.as_str(), suite.line), ("tests", 4));
+        assert_eq!(case_names(&suite), vec!["test_parse", "test_async_parse"]);
+        assert!(TestInventoryScanner::extract("src/lib.rs", "pub fn parse() {}\n").is_none());
+
+        let java = "\
+public class ParserTest {
+    @Test
+    public void parsesEmptyInput() {}
+
+    private void helper() {}
+
+    @ParameterizedTest
+    void parsesNumbers(int n) {}
+}
+";
+        let suite = TestInventoryScanner::extract("src/test/java/ParserTest.java", java).unwrap();
+        assert_eq!((suite.name.as_str(), suite.framework.as_str()), ("ParserTest", "junit"));
+        assert_eq!(case_names(&suite), vec!["parsesEmptyInput", "parsesNumbers"]);
+
+        let go = "package parser\n\nfunc TestParse(t *testing.T) {}\n\nfunc BenchmarkParse(b *testing.B) {}\n";
+        let suite = TestInventoryScanner::extract("parser/parser_test.go", go).unwrap();
+        assert_eq!((suite.name.as_str(), suite.line), ("parser_test", 3));
+        assert_eq!(case_names(&suite), vec!["TestParse"]);
+    }
+
+    #[test]
+    fn test_scan_detects_frameworks_and_counts() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("web/__tests__")).unwrap();
+        fs::create_dir_all(root.join("api/tests")).unwrap();
+        fs::write(
+            root.join("package.json"),
+            r#"{"scripts": {"test": "jest --coverage"}, "devDependencies": {"jest": "^29.0.0", "ts-jest": "^29.0.0"}}"#,
+        )
+        .unwrap();
+        fs::write(root.join("web/__tests__/app.js"), "describe('App', () => {\n  it('renders', () => {});\n});\n").unwrap();
+        fs::write(
+            root.join("web/cart.test.ts"),
+            "test('adds items', () => {});\ntest('removes items', () => {});\ntest('totals', () => {});\n",
+        )
+        .unwrap();
+        fs::write(root.join("api/requirements-dev.txt"), "pytest>=7\nrequests\n").unwrap();
+        fs::write(root.join("api/tests/test_routes.py"), "def test_index():\n    pass\n").unwrap();
+
+        let inventory = TestInventoryScanner::scan(root).unwrap();
+        assert_eq!(inventory.total_cases, 5);
+        assert_eq!(inventory.suites.len(), 3);
+
+        let jest = inventory.primary_framework().unwrap();
+        assert_eq!(jest.name, "jest");
+        assert_eq!(jest.language, "typescript");
+        assert_eq!(jest.command, "npm test");
+        assert_eq!((jest.suite_count, jest.case_count), (2, 4));
+        assert_eq!(jest.example_file.as_deref(), Some("web/cart.test.ts"));
+        assert_eq!(
+            jest.evidence,
+            vec!["package.json: jest".to_string(), "package.json: scripts.test".to_string(), "package.json: ts-jest".to_string()]
+        );
+
+        let pytest = &inventory.frameworks[1];
+        assert_eq!((pytest.name.as_str(), pytest.command.as_str(), pytest.case_count), ("pytest", "pytest", 1));
+        assert_eq!(pytest.evidence, vec!["api/requirements-dev.txt: pytest".to_string()]);
+    }
+}
@@ -0,0 +1,135 @@
+//! Tombstones for code removed from a project
+//!
+//! Relearning a codebase used to leave the concepts of deleted files in the
+//! concept store and their examples in pattern frequencies. Now a relearn
+//! that finds a file missing takes its concepts out of the store, subtracts
+//! its examples from the patterns they were learned into, and leaves a
+//! tombstone saying when the file was first found missing and what it
+//! contributed to. Relearning again changes nothing more, as the file's
+//! contributions are already gone; a file that comes back (after a branch
+//! switch, say) loses its tombstone and is learned like any other.
+//!
+//! Patterns left with no occurrences are kept until the tombstones of their
+//! files are older than the grace period (`analysis.tombstoneGraceSecs`,
+//! a week by default), then deleted with them.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use chrono::DateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A file found missing by a relearn, and what it contributed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct Tombstone {
+    /// As stored on the file's concepts and pattern examples
+    pub file_path: String,
+    /// RFC 3339 time the file was first found missing
+    pub removed_at: String,
+    /// Ids of the concepts extracted from the file, or of the patterns its
+    /// examples were subtracted from, in id order
+    pub ids: Vec<String>,
+}
+
+/// Tombstones of the removed files of one project, by file
+#[derive(Debug, Clone, Default)]
+pub struct TombstoneStore {
+    tombstones: BTreeMap<String, Tombstone>,
+}
+
+impl TombstoneStore {
+    pub fn new(tombstones: Vec<Tombstone>) -> Self {
+        TombstoneStore {
+            tombstones: tombstones.into_iter().map(|t| (t.file_path.clone(), t)).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tombstones.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tombstones.is_empty()
+    }
+
+    pub fn is_buried(&self, file_path: &str) -> bool {
+        self.tombstones.contains_key(file_path)
+    }
+
+    /// Record that `file_path` was found missing at `now`; a file already
+    /// buried keeps its time and gains the new ids
+    pub fn bury(&mut self, file_path: &str, ids: impl IntoIterator<Item = String>, now: &str) {
+        let tombstone = self.tombstones.entry(file_path.to_string()).or_insert_with(|| Tombstone {
+            file_path: file_path.to_string(),
+            removed_at: now.to_string(),
+            ids: Vec::new(),
+        });
+        tombstone.ids.extend(ids);
+        tombstone.ids.sort();
+        tombstone.ids.dedup();
+    }
+
+    /// Forget the tombstone of a file that exists again
+    pub fn revive(&mut self, file_path: &str) -> Option<Tombstone> {
+        self.tombstones.remove(file_path)
+    }
+
+    /// Forget the tombstones of files removed more than `grace_secs` before
+    /// `now`, returning them; tombstones with an unreadable time go too
+    pub fn collect_garbage(&mut self, now: &str, grace_secs: u64) -> Vec<Tombstone> {
+        let Ok(now) = DateTime::parse_from_rfc3339(now) else {
+            return Vec::new();
+        };
+        let expired: Vec<String> = self
+            .tombstones
+            .values()
+            .filter(|tombstone| {
+                DateTime::parse_from_rfc3339(&tombstone.removed_at)
+                    .ok()
+                    .is_none_or(|removed| (now - removed).num_seconds() >= grace_secs as i64)
+            })
+            .map(|tombstone| tombstone.file_path.clone())
+            .collect();
+        expired.iter().filter_map(|file_path| self.tombstones.remove(file_path)).collect()
+    }
+
+    /// Every tombstone, in file order
+    pub fn list(&self) -> Vec<Tombstone> {
+        self.tombstones.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEEK: u64 = 7 * 24 * 60 * 60;
+
+    #[test]
+    fn test_bury_revive_and_collect_after_grace_period() {
+        let mut store = TombstoneStore::default();
+        store.bury("src/old.ts", ["c2".to_string(), "c1".to_string()], "2026-03-01T10:00:00Z");
+        store.bury("src/gone.py", ["c3".to_string()], "2026-03-05T10:00:00Z");
+        // Found missing again by a later relearn: the first time is kept
+        store.bury("src/old.ts", ["c1".to_string(), "c4".to_string()], "2026-03-04T10:00:00Z");
+        store.bury("src/back.rs", Vec::new(), "2026-03-01T10:00:00Z");
+
+        assert_eq!(store.revive("src/back.rs").map(|t| t.file_path), Some("src/back.rs".to_string()));
+        assert!(store.revive("src/back.rs").is_none());
+        let tombstones = store.list();
+        let old = &tombstones[1];
+        assert_eq!(old.removed_at, "2026-03-01T10:00:00Z");
+        assert_eq!(old.ids, vec!["c1", "c2", "c4"]);
+
+        assert!(store.collect_garbage("2026-03-08T09:59:59Z", WEEK).is_empty());
+        let expired = store.collect_garbage("2026-03-08T10:00:00+00:00", WEEK);
+        assert_eq!(expired.iter().map(|t| t.file_path.as_str()).collect::<Vec<_>>(), vec!["src/old.ts"]);
+        assert!(store.is_buried("src/gone.py") && !store.is_buried("src/old.ts"));
+        assert!(store.collect_garbage("not a time", 0).is_empty());
+        assert_eq!(store.len(), 1);
+    }
+}
@@ -0,0 +1,550 @@
+//! Inventory of unsafe code and FFI in Rust crates
+//!
+//! A line-based pass over `.rs` files listing what a safety review has to read:
+//! - `unsafe` blocks, functions, impls and traits
+//! - `extern` blocks and the foreign functions they declare
+//! - `extern "C"` functions exported to other languages
+//! - `mem::transmute` calls
+//! - raw pointer types and null pointers
+//!
+//! Unsafe blocks, functions, impls and traits need a justification: a
+//! `// SAFETY:` comment on or right above them, or a `# Safety` doc section for
+//! unsafe functions and traits; an unsafe block inside an unsafe function
+//! needs its own. Transmutes and raw pointers share the justification of the
+//! unsafe block or function they are in. `#[cfg(test)]` modules are skipped.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::ManifestParser;
+use crate::types::ParseError;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use walkdir::WalkDir;
+
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", ".venv", "target", "vendor"];
+
+/// Files larger than this are not audited
+const MAX_AUDIT_FILE_SIZE: u64 = 2 * 1024 * 1024;
+
+/// Kinds of usage in report order
+const KINDS: &[&str] = &[
+    "unsafe_block", "unsafe_fn", "unsafe_impl", "unsafe_trait", "extern_block", "foreign_fn", "extern_fn", "transmute",
+    "raw_pointer",
+];
+
+/// Kinds that are unsafe themselves and need their own justification
+const JUSTIFIED_KINDS: &[&str] = &["unsafe_block", "unsafe_fn", "unsafe_impl", "unsafe_trait"];
+
+/// How far above an item a justification comment is looked for
+const MAX_COMMENT_LINES: usize = 20;
+
+/// One place a safety review has to look at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct UnsafeUsage {
+    /// 'unsafe_block', 'unsafe_fn', 'unsafe_impl', 'unsafe_trait', 'extern_block',
+    /// 'foreign_fn', 'extern_fn', 'transmute' or 'raw_pointer'
+    pub kind: String,
+    pub file_path: String,
+    pub line: u32,
+    pub code: String,
+    /// Function the usage is in, or the function itself for 'unsafe_fn',
+    /// 'foreign_fn' and 'extern_fn'
+    pub function: Option<String>,
+    /// Text of the `SAFETY:` comment or `# Safety` section covering the usage
+    pub justification: Option<String>,
+}
+
+impl UnsafeUsage {
+    /// Unsafe code without a justification
+    pub fn is_unjustified(&self) -> bool {
+        JUSTIFIED_KINDS.contains(&self.kind.as_str()) && self.justification.is_none()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct UnsafeKindCount {
+    pub kind: String,
+    pub count: u32,
+    pub unjustified: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct FileSafety {
+    pub file_path: String,
+    pub usages: u32,
+    pub unjustified: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct SafetyReport {
+    /// Rust packages under the root
+    pub crates: Vec<String>,
+    pub files_scanned: u32,
+    /// Files declaring `#![forbid(unsafe_code)]` or `#![deny(unsafe_code)]`
+    pub unsafe_code_denied: Vec<String>,
+    pub kinds: Vec<UnsafeKindCount>,
+    /// Files with usages, most unjustified first
+    pub files: Vec<FileSafety>,
+    pub unjustified_count: u32,
+    /// Every usage, ordered by file and line
+    pub usages: Vec<UnsafeUsage>,
+}
+
+/// A function, unsafe block or extern block being read
+struct Scope {
+    kind: &'static str,
+    /// Function name for functions
+    name: Option<String>,
+    justification: Option<String>,
+    /// Brace depth before the scope's opening brace
+    level: usize,
+    /// The opening brace has been seen
+    opened: bool,
+}
+
+/// Blanks string literals and comments so braces and keywords in them are not read as code
+#[derive(Default)]
+struct CodeMask {
+    /// Inside a string: the number of `#`s closing a raw string, or `None` for a plain one
+    string: Option<Option<usize>>,
+    block_comment: bool,
+}
+
+impl CodeMask {
+    fn mask(&mut self, line: &str) -> String {
+        let chars: Vec<char> = line.chars().collect();
+        let mut code = String::with_capacity(line.len());
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let next = chars.get(i + 1).copied();
+            if self.block_comment {
+                if c == '*' && next == Some('/') {
+                    self.block_comment = false;
+                    i += 1;
+                }
+                code.push(' ');
+            } else if let Some(raw) = self.string {
+                let closes = c == '"' && raw.is_none_or(|hashes| chars[i + 1..].iter().take_while(|&&h| h == '#').count() >= hashes);
+                if closes {
+                    self.string = None;
+                    code.push('"');
+                    i += raw.unwrap_or(0);
+                } else {
+                    if c == '\\' && raw.is_none() {
+                        i += 1;
+                    }
+                    code.push(' ');
+                }
+            } else if c == '/' && next == Some('/') {
+                break;
+            } else if c == '/' && next == Some('*') {
+                self.block_comment = true;
+                code.push(' ');
+                i += 1;
+            } else if c == '"' {
+                self.string = Some(None);
+                code.push('"');
+            } else if c == 'r' && matches!(next, Some('"') | Some('#')) && (i == 0 || !chars[i - 1].is_alphanumeric()) {
+                let hashes = chars[i + 1..].iter().take_while(|&&h| h == '#').count();
+                if chars.get(i + 1 + hashes) == Some(&'"') {
+                    self.string = Some(Some(hashes));
+                    code.push('"');
+                    i += 1 + hashes;
+                } else {
+                    code.push(c);
+                }
+            } else if c == '\'' && chars.get(i + 2) == Some(&'\'') {
+                // A char literal such as '"' or '{'
+                code.push_str("' '");
+                i += 2;
+            } else if c == '\'' && next == Some('\\') {
+                let end = chars[i + 2..].iter().position(|&ch| ch == '\'').map_or(chars.len(), |p| i + 2 + p);
+                code.push_str("' '");
+                i = end;
+            } else {
+                code.push(c);
+            }
+            i += 1;
+        }
+        code
+    }
+}
+
+pub struct UnsafeAuditor;
+
+impl UnsafeAuditor {
+    /// Audit the Rust sources under `root`
+    pub fn scan(root: &Path) -> Result<SafetyReport, ParseError> {
+        let mut report = SafetyReport::default();
+
+        for entry in WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if !entry.file_type().is_file()
+                || path.extension().and_then(|e| e.to_str()) != Some("rs")
+                || entry.metadata().map(|m| m.len() > MAX_AUDIT_FILE_SIZE).unwrap_or(true)
+            {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(path) else { continue };
+            let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+
+            report.files_scanned += 1;
+            if patterns().deny_unsafe.is_match(&content) {
+                report.unsafe_code_denied.push(relative.clone());
+            }
+            report.usages.extend(Self::audit_file(&relative, &content));
+        }
+
+        report.crates = ManifestParser::analyze(root)
+            .map(|summary| {
+                summary
+                    .manifests
+                    .into_iter()
+                    .filter(|manifest| manifest.ecosystem == "cargo")
+                    .filter_map(|manifest| manifest.package_name)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut kinds: BTreeMap<&str, (u32, u32)> = BTreeMap::new();
+        let mut files: BTreeMap<&str, (u32, u32)> = BTreeMap::new();
+        for usage in &report.usages {
+            let unjustified = usage.is_unjustified() as u32;
+            for counts in [kinds.entry(usage.kind.as_str()).or_default(), files.entry(usage.file_path.as_str()).or_default()] {
+                counts.0 += 1;
+                counts.1 += unjustified;
+            }
+        }
+        report.kinds = KINDS
+            .iter()
+            .filter_map(|kind| kinds.get(kind).map(|&(count, unjustified)| (kind, count, unjustified)))
+            .map(|(kind, count, unjustified)| UnsafeKindCount { kind: kind.to_string(), count, unjustified })
+            .collect();
+        report.files = files
+            .into_iter()
+            .map(|(file_path, (usages, unjustified))| FileSafety { file_path: file_path.to_string(), usages, unjustified })
+            .collect();
+        report.files.sort_by_key(|file| std::cmp::Reverse((file.unjustified, file.usages)));
+        report.unjustified_count = report.usages.iter().filter(|usage| usage.is_unjustified()).count() as u32;
+        Ok(report)
+    }
+
+    /// Unsafe and FFI usages in one Rust file
+    pub fn audit_file(file_path: &str, content: &str) -> Vec<UnsafeUsage> {
+        let p = patterns();
+        let lines: Vec<&str> = content.lines().collect();
+        let mut usages = Vec::new();
+        let mut scopes: Vec<Scope> = Vec::new();
+        let mut mask = CodeMask::default();
+        let mut depth = 0usize;
+        let mut pending_test_module = false;
+        let mut test_module: Option<usize> = None;
+
+        for (idx, line) in lines.iter().enumerate() {
+            let code = mask.mask(line);
+            let trimmed = line.trim_start();
+            let opens = code.matches('{').count();
+            let closes = code.matches('}').count();
+
+            if trimmed.starts_with("#[cfg(test)]") {
+                pending_test_module = true;
+            } else if pending_test_module && p.module.is_match(&code) {
+                pending_test_module = false;
+                test_module = Some(depth);
+            } else if !trimmed.starts_with("#[") && !trimmed.is_empty() {
+                pending_test_module = false;
+            }
+
+            if test_module.is_none() && !code.trim().is_empty() {
+                let mut found = |kind: &str, function: Option<String>, justification: Option<String>| {
+                    usages.push(UnsafeUsage {
+                        kind: kind.to_string(),
+                        file_path: file_path.to_string(),
+                        line: idx as u32 + 1,
+                        code: line.trim().to_string(),
+                        function,
+                        justification,
+                    });
+                };
+                // Depth of the brace a match at this byte offset would open
+                let level_at = |offset: usize| depth + code[..offset].matches('{').count();
+                let enclosing_function = scopes.iter().rev().find_map(|scope| scope.name.clone());
+                let in_extern_block = scopes.last().is_some_and(|scope| scope.kind == "extern_block" && scope.opened);
+
+                if let Some(c) = p.unsafe_fn.captures(&code) {
+                    let name = c[1].to_string();
+                    let justification = Self::justification(&lines, idx, true);
+                    found("unsafe_fn", Some(name.clone()), justification.clone());
+                    if p.extern_fn.is_match(&code) {
+                        found("extern_fn", Some(name.clone()), justification.clone());
+                    }
+                    scopes.push(Scope { kind: "fn", name: Some(name), justification, level: level_at(c.get(0).unwrap().start()), opened: false });
+                } else if let Some(c) = p.fn_item.captures(&code) {
+                    let name = c[1].to_string();
+                    if in_extern_block {
+                        found("foreign_fn", Some(name.clone()), None);
+                    } else if p.extern_fn.is_match(&code) {
+                        found("extern_fn", Some(name.clone()), None);
+                    }
+                    scopes.push(Scope { kind: "fn", name: Some(name), justification: None, level: level_at(c.get(0).unwrap().start()), opened: false });
+                }
+                if p.unsafe_impl.is_match(&code) {
+                    found("unsafe_impl", enclosing_function.clone(), Self::justification(&lines, idx, false));
+                }
+                if p.unsafe_trait.is_match(&code) {
+                    found("unsafe_trait", enclosing_function.clone(), Self::justification(&lines, idx, true));
+                }
+                if let Some(m) = p.extern_block.find(&code) {
+                    found("extern_block", enclosing_function.clone(), None);
+                    scopes.push(Scope { kind: "extern_block", name: None, justification: None, level: level_at(m.start()), opened: false });
+                }
+                for m in p.unsafe_block.find_iter(&code) {
+                    let justification = Self::justification(&lines, idx, false);
+                    found("unsafe_block", enclosing_function.clone(), justification.clone());
+                    scopes.push(Scope { kind: "unsafe_block", name: None, justification, level: level_at(m.start()), opened: false });
+                }
+                // Including scopes opened on this line, e.g. an unsafe function's signature
+                let function = scopes.iter().rev().find_map(|scope| scope.name.clone());
+                let justification = scopes
+                    .iter()
+                    .rev()
+                    .filter(|scope| scope.kind != "extern_block")
+                    .find_map(|scope| scope.justification.clone());
+                for _ in p.transmute.find_iter(&code) {
+                    found("transmute", function.clone(), justification.clone());
+                }
+                if p.raw_pointer.is_match(&code) {
+                    found("raw_pointer", function, justification);
+                }
+            }
+
+            depth = (depth + opens).saturating_sub(closes);
+
+            if test_module.is_some_and(|level| depth <= level && closes > 0) {
+                test_module = None;
+            }
+            for scope in scopes.iter_mut() {
+                if !scope.opened && depth > scope.level {
+                    scope.opened = true;
+                }
+            }
+            while let Some(scope) = scopes.last() {
+                let closed = if scope.opened {
+                    depth <= scope.level
+                } else {
+                    // Body closed on the same line, or a declaration without one
+                    opens > 0 || code.trim_end().ends_with(';')
+                };
+                if closed {
+                    scopes.pop();
+                } else {
+                    break;
+                }
+            }
+        }
+        usages
+    }
+
+    /// A `SAFETY:` comment on the line or in the comments and attributes right
+    /// above it; with `doc_section`, also the first line of a `# Safety` doc section
+    fn justification(lines: &[&str], idx: usize, doc_section: bool) -> Option<String> {
+        let p = patterns();
+        if let Some(c) = lines[idx].split_once("//").and_then(|(_, comment)| p.safety_comment.captures(comment)) {
+            return Some(Self::comment_text(&c[1], lines[idx]));
+        }
+
+        let mut comments = Vec::new();
+        for line in lines[..idx].iter().rev().take(MAX_COMMENT_LINES) {
+            let trimmed = line.trim();
+            if trimmed.starts_with("#[") {
+                continue;
+            }
+            if !(trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with('*')) {
+                break;
+            }
+            comments.push(trimmed);
+        }
+        comments.reverse();
+
+        for (position, comment) in comments.iter().enumerate() {
+            if let Some(c) = p.safety_comment.captures(comment) {
+                return Some(Self::comment_text(&c[1], comments.get(position + 1).copied().unwrap_or(comment)));
+            }
+            if doc_section && p.safety_section.is_match(comment) {
+                let section = comments[position + 1..]
+                    .iter()
+                    .map(|line| line.trim_start_matches('/').trim())
+                    .take_while(|line| !line.starts_with('#'))
+                    .find(|line| !line.is_empty());
+                return Some(section.unwrap_or("# Safety").to_string());
+            }
+        }
+        None
+    }
+
+    /// The text after `SAFETY:`, or the next comment line when it starts there
+    fn comment_text(rest: &str, fallback: &str) -> String {
+        let rest = rest.trim();
+        if rest.is_empty() {
+            fallback.trim_start_matches(['/', '*', ' ']).trim().to_string()
+        } else {
+            rest.to_string()
+        }
+    }
+}
+
+struct Patterns {
+    module: Regex,
+    unsafe_fn: Regex,
+    fn_item: Regex,
+    extern_fn: Regex,
+    unsafe_impl: Regex,
+    unsafe_trait: Regex,
+    extern_block: Regex,
+    unsafe_block: Regex,
+    transmute: Regex,
+    raw_pointer: Regex,
+    safety_comment: Regex,
+    safety_section: Regex,
+    deny_unsafe: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let re = |pattern: &str| Regex::new(pattern).expect("valid unsafe audit regex");
+        // String contents are blanked, so any ABI string matches
+        Patterns {
+            module: re(r"^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+\w+"),
+            unsafe_fn: re(r#"\bunsafe\s+(?:extern\s*(?:"[^"]*"\s*)?)?fn\s+(\w+)"#),
+            fn_item: re(r"\bfn\s+(\w+)"),
+            extern_fn: re(r#"\bextern\s*(?:"[^"]*"\s*)?fn\s+\w+"#),
+            unsafe_impl: re(r"\bunsafe\s+impl\b"),
+            unsafe_trait: re(r"\bunsafe\s+(?:auto\s+)?trait\s+\w+"),
+            extern_block: re(r#"\bextern\s*(?:"[^"]*"\s*)?\{"#),
+            unsafe_block: re(r"\bunsafe\s*\{"),
+            transmute: re(r"\btransmute(?:_copy)?\s*(?:::\s*<[^(]*>)?\s*\("),
+            raw_pointer: re(r"\*(?:const|mut)\s+\S|\bptr::null(?:_mut)?\b"),
+            safety_comment: re(r"(?i)\bsafety:(.*)$"),
+            safety_section: re(r"^///?\s*#+\s*Safety\s*$"),
+            deny_unsafe: re(r"#!\[(?:forbid|deny)\(unsafe_code\)\]"),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(usages: &[UnsafeUsage]) -> Vec<(&str, u32, Option<&str>)> {
+        usages.iter().map(|u| (u.kind.as_str(), u.line, u.justification.as_deref())).collect()
+    }
+
+    #[test]
+    fn test_usages_and_justifications() {
+        let source = r#"use std::ffi::c_char;
+
+extern "C" {
+    fn strlen(s: *const c_char) -> usize;
+}
+
+/// Length of a C string
+///
+/// # Safety
+/// `s` must point to a NUL-terminated string.
+pub unsafe fn length(s: *const c_char) -> usize {
+    strlen(s)
+}
+
+#[no_mangle]
+pub extern "C" fn answer() -> u32 {
+    let bits = unsafe { std::mem::transmute::<f32, u32>(1.0) }; // SAFETY: f32 and u32 have the same size
+    // SAFETY: the pointer comes from a live Box
+    let value = unsafe {
+        let raw: *mut u32 = std::ptr::null_mut();
+        std::mem::transmute::<u32, f32>(*raw)
+    };
+    let message = "unsafe { not code }";
+    bits + unsafe { helper() }
+}
+
+unsafe impl Send for Handle {}
+
+#[cfg(test)]
+mod tests {
+    fn check() { unsafe { helper() } }
+}
+"#;
+        let usages = UnsafeAuditor::audit_file("src/lib.rs", source);
+        assert_eq!(
+            kinds(&usages),
+            vec![
+                ("extern_block", 3, None),
+                ("foreign_fn", 4, None),
+                ("raw_pointer", 4, None),
+                ("unsafe_fn", 11, Some("`s` must point to a NUL-terminated string.")),
+                ("raw_pointer", 11, Some("`s` must point to a NUL-terminated string.")),
+                ("extern_fn", 16, None),
+                ("unsafe_block", 17, Some("f32 and u32 have the same size")),
+                ("transmute", 17, Some("f32 and u32 have the same size")),
+                ("unsafe_block", 19, Some("the pointer comes from a live Box")),
+                ("raw_pointer", 20, Some("the pointer comes from a live Box")),
+                ("transmute", 21, Some("the pointer comes from a live Box")),
+                ("unsafe_block", 24, None),
+                ("unsafe_impl", 27, None),
+            ]
+        );
+        assert_eq!(usages[1].function.as_deref(), Some("strlen"));
+        assert_eq!(usages[8].function.as_deref(), Some("answer"));
+        assert_eq!(usages.iter().filter(|u| u.is_unjustified()).count(), 2);
+    }
+
+    #[test]
+    fn test_scan_reports_rust_core_ffi_shims() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"in-memoria-core\"\nversion = \"0.1.0\"\n").unwrap();
+        // Dogfood on this crate's own C ABI
+        fs::write(root.join("src/ffi.rs"), include_str!("../ffi.rs")).unwrap();
+        fs::write(root.join("src/safe.rs"), "#![forbid(unsafe_code)]\npub fn add(a: u32, b: u32) -> u32 { a + b }\n").unwrap();
+
+        let report = UnsafeAuditor::scan(root).unwrap();
+        assert_eq!(report.crates, vec!["in-memoria-core".to_string()]);
+        assert_eq!(report.files_scanned, 2);
+        assert_eq!(report.unsafe_code_denied, vec!["src/safe.rs".to_string()]);
+        assert_eq!(report.files.len(), 1);
+
+        let exported: Vec<&str> = report
+            .usages
+            .iter()
+            .filter(|u| u.kind == "extern_fn")
+            .filter_map(|u| u.function.as_deref())
+            .collect();
+        assert!(exported.contains(&"in_memoria_engine_new"));
+        assert!(exported.contains(&"in_memoria_engine_free"));
+        // Calls in the test module are not counted
+        assert!(report.usages.iter().all(|u| u.function.as_deref() != Some("take_json")));
+        assert_eq!(report.unjustified_count, report.kinds.iter().map(|k| k.unjustified).sum::<u32>());
+    }
+}
@@ -0,0 +1,178 @@
+//! Project walks that reach each file once
+//!
+//! Symlinked monorepos can reach the same file through several paths, and a
+//! link back to an ancestor directory would loop forever. [`SourceWalker`]
+//! walks from the canonical project root, keys files by their canonical path
+//! when it lies inside the project, drops files already reached through
+//! another path and reports symlink cycles instead of following them.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use walkdir::{DirEntry, WalkDir};
+
+/// A path the walk left out, with the reason and supporting detail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkSkip {
+    pub path: String,
+    pub reason: &'static str,
+    pub detail: Option<String>,
+}
+
+/// Files found by a walk, in path order, and the paths it skipped
+#[derive(Debug, Default)]
+pub struct WalkOutcome {
+    pub files: Vec<PathBuf>,
+    pub skipped: Vec<WalkSkip>,
+}
+
+pub struct SourceWalker {
+    follow_symlinks: bool,
+}
+
+impl SourceWalker {
+    pub fn new(follow_symlinks: bool) -> Self {
+        Self { follow_symlinks }
+    }
+
+    /// The canonical form of a project root, or the path as given when it
+    /// cannot be resolved
+    pub fn canonical_root(root: &Path) -> PathBuf {
+        canonicalize(root).unwrap_or_else(|| root.to_path_buf())
+    }
+
+    /// Walk `root`, descending only into directories `keep_dir` accepts
+    pub fn walk<F>(&self, root: &Path, mut keep_dir: F) -> WalkOutcome
+    where
+        F: FnMut(&DirEntry) -> bool,
+    {
+        let root = Self::canonical_root(root);
+        let mut outcome = WalkOutcome::default();
+        // Canonical path of every file kept, mapped to the path it was kept under
+        let mut seen: HashMap<PathBuf, PathBuf> = HashMap::new();
+
+        let walker = WalkDir::new(&root)
+            .follow_links(self.follow_symlinks)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| !e.file_type().is_dir() || e.depth() == 0 || keep_dir(e));
+
+        for entry in walker {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(error) => {
+                    if let (Some(path), Some(ancestor)) = (error.path(), error.loop_ancestor()) {
+                        outcome.skipped.push(WalkSkip {
+                            path: path.to_string_lossy().to_string(),
+                            reason: "symlink_cycle",
+                            detail: Some(format!("links back to {}", ancestor.display())),
+                        });
+                    }
+                    continue;
+                }
+            };
+
+            if entry.path_is_symlink() && !self.follow_symlinks {
+                outcome.skipped.push(WalkSkip {
+                    path: entry.path().to_string_lossy().to_string(),
+                    reason: "symlink",
+                    detail: std::fs::read_link(entry.path()).ok().map(|target| format!("links to {}", target.display())),
+                });
+                continue;
+            }
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let walked = entry.into_path();
+            let canonical = canonicalize(&walked).unwrap_or_else(|| walked.clone());
+            if let Some(first) = seen.get(&canonical) {
+                outcome.skipped.push(WalkSkip {
+                    path: walked.to_string_lossy().to_string(),
+                    reason: "duplicate_path",
+                    detail: Some(format!("same file as {}", first.display())),
+                });
+                continue;
+            }
+
+            // Files inside the project are keyed by their real location so the
+            // same file gets the same key however the walk reached it
+            let path = if canonical.starts_with(&root) { canonical.clone() } else { walked };
+            seen.insert(canonical, path.clone());
+            outcome.files.push(path);
+        }
+
+        outcome.files.sort();
+        outcome
+    }
+}
+
+/// Canonicalize without the `\\?\` prefix Windows adds, so keys stay comparable
+/// with paths built from user input
+fn canonicalize(path: &Path) -> Option<PathBuf> {
+    let canonical = std::fs::canonicalize(path).ok()?;
+    #[cfg(windows)]
+    {
+        let text = canonical.to_string_lossy();
+        if let Some(stripped) = text.strip_prefix(r"\\?\") {
+            if !stripped.starts_with("UNC\\") {
+                return Some(PathBuf::from(stripped));
+            }
+        }
+    }
+    Some(canonical)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/a.ts"), "export const a = 1;").unwrap();
+        fs::write(dir.path().join("src/b.ts"), "export const b = 2;").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_walk_keys_files_by_canonical_path() {
+        let dir = project();
+        let root = SourceWalker::canonical_root(dir.path());
+        let outcome = SourceWalker::new(false).walk(&dir.path().join("src/.."), |_| true);
+        assert_eq!(outcome.files, vec![root.join("src/a.ts"), root.join("src/b.ts")]);
+        assert!(outcome.skipped.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_are_skipped_unless_followed() {
+        let dir = project();
+        let root = SourceWalker::canonical_root(dir.path());
+        std::os::unix::fs::symlink(root.join("src"), root.join("linked")).unwrap();
+        std::os::unix::fs::symlink(root.join("src/a.ts"), root.join("alias.ts")).unwrap();
+
+        let outcome = SourceWalker::new(false).walk(&root, |_| true);
+        assert_eq!(outcome.files, vec![root.join("src/a.ts"), root.join("src/b.ts")]);
+        let reasons: Vec<&str> = outcome.skipped.iter().map(|s| s.reason).collect();
+        assert_eq!(reasons, vec!["symlink", "symlink"]);
+
+        // Following links reaches src twice more, but each file is kept once
+        let outcome = SourceWalker::new(true).walk(&root, |_| true);
+        assert_eq!(outcome.files, vec![root.join("src/a.ts"), root.join("src/b.ts")]);
+        assert_eq!(outcome.skipped.iter().filter(|s| s.reason == "duplicate_path").count(), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_cycles_are_reported() {
+        let dir = project();
+        let root = SourceWalker::canonical_root(dir.path());
+        std::os::unix::fs::symlink(&root, root.join("src/loop")).unwrap();
+
+        let outcome = SourceWalker::new(true).walk(&root, |e| e.file_name() != "node_modules");
+        assert_eq!(outcome.files, vec![root.join("src/a.ts"), root.join("src/b.ts")]);
+        let cycle = outcome.skipped.iter().find(|s| s.reason == "symlink_cycle").unwrap();
+        assert!(cycle.path.ends_with("loop"));
+    }
+}
@@ -0,0 +1,495 @@
+//! Workspaces made of several project roots
+//!
+//! A workspace such as `frontend/`, `backend/` and `infra/` is analyzed root by
+//! root and the results combined as if the workspace were one project. The
+//! [`CrossRootLinker`] then finds where one root depends on another:
+//! - HTTP calls (`fetch`, `axios.get`, `requests.post`, ...) to a route another
+//!   root declares or documents in an OpenAPI spec
+//! - generated OpenAPI clients calling an operation of another root's spec
+//! - package manifests depending on the package another root publishes
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::{ApiEndpoint, ComplexityAnalyzer, ManifestParser, PackageManifest, RouteExtractor};
+use crate::types::{paths, sort_concepts, CodebaseAnalysisResult, ParseError};
+use regex::Regex;
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use walkdir::WalkDir;
+
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", ".next", "__pycache__", "venv", ".venv", "target", "vendor"];
+
+/// Files larger than this are not scanned for calls or specs
+const MAX_SCAN_FILE_SIZE: u64 = 512 * 1024;
+
+pub(crate) const CLIENT_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "vue", "svelte", "py"];
+
+pub(crate) const SPEC_EXTENSIONS: &[&str] = &["yaml", "yml", "json"];
+
+const HTTP_METHODS: &[&str] = &["get", "post", "put", "patch", "delete", "head", "options"];
+
+/// Operation ids shorter than this are too generic to look for in client code
+pub(crate) const MIN_OPERATION_ID_LEN: usize = 4;
+
+/// Analysis of one root of a workspace
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct RootAnalysis {
+    /// Canonical path of the root
+    pub root: String,
+    /// The root's path below the directory all roots share, e.g. `frontend`
+    pub label: String,
+    /// Concept ids are relative to this root
+    pub analysis: CodebaseAnalysisResult,
+}
+
+/// A dependency of one root on another
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct CrossRootEdge {
+    /// 'http_call', 'openapi_client' or 'package'
+    pub kind: String,
+    /// Label of the depending root
+    pub from_root: String,
+    /// File in the depending root, relative to it
+    pub from_file: String,
+    pub from_line: Option<u32>,
+    /// Label of the root depended on
+    pub to_root: String,
+    /// Route, spec or manifest file in that root, relative to it
+    pub to_file: String,
+    pub to_line: Option<u32>,
+    /// What is used: 'GET /orders/:id', an operation id or a package name
+    pub target: String,
+    pub confidence: f64,
+}
+
+/// Per-root results, their combination and the links between roots
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct MultiRootAnalysis {
+    pub roots: Vec<RootAnalysis>,
+    /// All roots as one project: concept ids are prefixed with their root's
+    /// label and carry it in `metadata.root`
+    pub combined: CodebaseAnalysisResult,
+    pub cross_root_edges: Vec<CrossRootEdge>,
+}
+
+/// An HTTP request to a literal path found in client code
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpCall {
+    /// Upper-case method, `None` when the call doesn't name one (`fetch`)
+    pub method: Option<String>,
+    pub path: String,
+    pub file_path: String,
+    pub line: u32,
+}
+
+/// An operation declared in an OpenAPI or Swagger spec
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenApiOperation {
+    pub operation_id: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub file_path: String,
+    pub line: u32,
+}
+
+/// What one root offers to and uses from the others
+#[derive(Debug, Clone, Default)]
+pub struct RootSurface {
+    pub label: String,
+    pub endpoints: Vec<ApiEndpoint>,
+    pub operations: Vec<OpenApiOperation>,
+    pub calls: Vec<HttpCall>,
+    pub manifests: Vec<PackageManifest>,
+    /// Client source files, kept to look for generated client calls
+    client_sources: Vec<(String, String)>,
+}
+
+struct Patterns {
+    fetch: Regex,
+    method_call: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        const URL: &str = r#"\s*\(\s*f?(?:'([^'\n]*)'|"([^"\n]*)"|`([^`\n]*)`)"#;
+        Patterns {
+            fetch: Regex::new(&format!(r"\bfetch{}", URL)).unwrap(),
+            method_call: Regex::new(&format!(
+                r"\b(?:axios|requests|httpx|http|api|client|\$http|this\.http)\.(get|post|put|patch|delete){}",
+                URL
+            ))
+            .unwrap(),
+        }
+    })
+}
+
+pub struct CrossRootLinker;
+
+impl CrossRootLinker {
+    /// Label each root by its path below the directory all roots share,
+    /// falling back to the directory name for a root that is that directory
+    pub fn labels(roots: &[PathBuf]) -> Vec<String> {
+        let normalized: Vec<String> = roots.iter().map(|root| paths::normalize_path(&root.to_string_lossy())).collect();
+        let mut common: Vec<&str> = normalized.first().map(|root| root.split('/').collect()).unwrap_or_default();
+        for root in normalized.iter().skip(1) {
+            let shared = common.iter().zip(root.split('/')).take_while(|(a, b)| **a == *b).count();
+            common.truncate(shared);
+        }
+        let common = common.join("/");
+
+        let mut seen = HashSet::new();
+        normalized
+            .iter()
+            .map(|root| {
+                let mut label = paths::relative_key(root, Some(&common));
+                if label == "." || label == *root {
+                    label = root.rsplit('/').find(|segment| !segment.is_empty()).unwrap_or(root).to_string();
+                }
+                let mut unique = label.clone();
+                let mut suffix = 2;
+                while !seen.insert(unique.clone()) {
+                    unique = format!("{}#{}", label, suffix);
+                    suffix += 1;
+                }
+                unique
+            })
+            .collect()
+    }
+
+    /// Collect a root's routes, OpenAPI operations, HTTP calls and manifests
+    pub fn scan(root: &Path, label: &str) -> Result<RootSurface, ParseError> {
+        let mut surface = RootSurface {
+            label: label.to_string(),
+            endpoints: RouteExtractor::scan(root)?,
+            manifests: ManifestParser::analyze(root)?.manifests,
+            ..Default::default()
+        };
+
+        for entry in WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if !entry.file_type().is_file() || entry.metadata().map(|m| m.len() > MAX_SCAN_FILE_SIZE).unwrap_or(true) {
+                continue;
+            }
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            let is_client = CLIENT_EXTENSIONS.contains(&extension);
+            let is_spec = SPEC_EXTENSIONS.contains(&extension) && Self::is_spec_name(path);
+            if !is_client && !is_spec {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(path) else { continue };
+            let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+            if is_spec {
+                surface.operations.extend(Self::extract_operations(&relative, &content));
+            } else {
+                surface.calls.extend(Self::extract_calls(&relative, &content));
+                surface.client_sources.push((relative, content));
+            }
+        }
+        Ok(surface)
+    }
+
+    pub(crate) fn is_spec_name(path: &Path) -> bool {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_lowercase();
+        name.contains("openapi") || name.contains("swagger")
+    }
+
+    /// HTTP requests to literal paths, e.g. `fetch('/api/orders')` or
+    /// `axios.post(`${API}/orders/${id}`)`
+    pub fn extract_calls(file_path: &str, content: &str) -> Vec<HttpCall> {
+        let mut calls = Vec::new();
+        for (idx, line) in content.lines().enumerate() {
+            let found = patterns()
+                .fetch
+                .captures_iter(line)
+                .map(|captures| (None, captures))
+                .chain(patterns().method_call.captures_iter(line).map(|captures| {
+                    let method = captures.get(1).map(|m| m.as_str().to_uppercase());
+                    (method, captures)
+                }));
+            for (method, captures) in found {
+                let url = (2..=4).find_map(|group| captures.get(group)).map(|m| m.as_str()).unwrap_or("");
+                if let Some(path) = Self::request_path(url) {
+                    calls.push(HttpCall {
+                        method,
+                        path,
+                        file_path: file_path.to_string(),
+                        line: idx as u32 + 1,
+                    });
+                }
+            }
+        }
+        calls
+    }
+
+    /// The path a request URL asks for, without scheme, host, a leading base
+    /// URL placeholder or query; `None` for URLs that aren't paths
+    fn request_path(url: &str) -> Option<String> {
+        let mut url = url.trim();
+        if let Some(rest) = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://")) {
+            url = rest.find('/').map(|slash| &rest[slash..]).unwrap_or("/");
+        }
+        for open in ["${", "{"] {
+            if url.starts_with(open) {
+                url = url.find('}').map(|close| &url[close + 1..]).unwrap_or("");
+            }
+        }
+        let path = url.split(['?', '#']).next().unwrap_or("");
+        (path.starts_with('/') && route_segments(path).iter().any(Option::is_some)).then(|| path.to_string())
+    }
+
+    /// Operations of an OpenAPI or Swagger document, in document order
+    pub fn extract_operations(file_path: &str, content: &str) -> Vec<OpenApiOperation> {
+        let Ok(document) = serde_yaml::from_str::<serde_yaml::Value>(content) else {
+            return Vec::new();
+        };
+        if document.get("openapi").is_none() && document.get("swagger").is_none() {
+            return Vec::new();
+        }
+        let Some(spec_paths) = document.get("paths").and_then(|p| p.as_mapping()) else {
+            return Vec::new();
+        };
+
+        let mut operations = Vec::new();
+        for (path, methods) in spec_paths {
+            let (Some(path), Some(methods)) = (path.as_str(), methods.as_mapping()) else {
+                continue;
+            };
+            for (method, operation) in methods {
+                let Some(method) = method.as_str().filter(|m| HTTP_METHODS.contains(m)) else {
+                    continue;
+                };
+                let operation_id = operation.get("operationId").and_then(|id| id.as_str()).map(str::to_string);
+                let line = operation_id
+                    .as_deref()
+                    .and_then(|id| Self::line_of(content, id))
+                    .or_else(|| Self::line_of(content, path))
+                    .unwrap_or(1);
+                operations.push(OpenApiOperation {
+                    operation_id,
+                    method: method.to_uppercase(),
+                    path: path.to_string(),
+                    file_path: file_path.to_string(),
+                    line,
+                });
+            }
+        }
+        operations
+    }
+
+    fn line_of(content: &str, needle: &str) -> Option<u32> {
+        content.lines().position(|line| line.contains(needle)).map(|idx| idx as u32 + 1)
+    }
+
+    /// Edges from each root to the others it calls or depends on, sorted by
+    /// depending root, file and line
+    pub fn link(surfaces: &[RootSurface]) -> Vec<CrossRootEdge> {
+        let mut edges = Vec::new();
+        for from in surfaces {
+            for to in surfaces.iter().filter(|to| to.label != from.label) {
+                Self::link_calls(from, to, &mut edges);
+                Self::link_clients(from, to, &mut edges);
+                Self::link_packages(from, to, &mut edges);
+            }
+        }
+        edges.sort_by(|a, b| {
+            (&a.from_root, &a.from_file, a.from_line, &a.kind, &a.to_root, &a.target)
+                .cmp(&(&b.from_root, &b.from_file, b.from_line, &b.kind, &b.to_root, &b.target))
+        });
+        edges.dedup_by(|a, b| {
+            (&a.from_root, &a.from_file, a.from_line, &a.kind, &a.to_root, &a.target)
+                == (&b.from_root, &b.from_file, b.from_line, &b.kind, &b.to_root, &b.target)
+        });
+        edges
+    }
+
+    fn link_calls(from: &RootSurface, to: &RootSurface, edges: &mut Vec<CrossRootEdge>) {
+        let routes = to
+            .endpoints
+            .iter()
+            .map(|e| (e.method.as_str(), e.path.as_str(), e.file_path.as_str(), e.line))
+            .chain(to.operations.iter().map(|o| (o.method.as_str(), o.path.as_str(), o.file_path.as_str(), o.line)));
+        let routes: Vec<_> = routes.collect();
+
+        for call in &from.calls {
+            for (method, path, file_path, line) in &routes {
+                let any_method = *method == "ALL" || *method == "ANY";
+                let method_matches = call.method.as_deref().is_none_or(|m| any_method || m == *method);
+                if method_matches && paths_match(&call.path, path) {
+                    edges.push(CrossRootEdge {
+                        kind: "http_call".to_string(),
+                        from_root: from.label.clone(),
+                        from_file: call.file_path.clone(),
+                        from_line: Some(call.line),
+                        to_root: to.label.clone(),
+                        to_file: file_path.to_string(),
+                        to_line: Some(*line),
+                        target: format!("{} {}", method, path),
+                        confidence: if call.method.is_some() { 0.9 } else { 0.7 },
+                    });
+                }
+            }
+        }
+    }
+
+    /// Calls like `ordersApi.listOrders(` to an operation id of another root's spec
+    fn link_clients(from: &RootSurface, to: &RootSurface, edges: &mut Vec<CrossRootEdge>) {
+        for operation in &to.operations {
+            let Some(id) = operation.operation_id.as_deref().filter(|id| id.len() >= MIN_OPERATION_ID_LEN) else {
+                continue;
+            };
+            let Ok(call) = Regex::new(&format!(r"\.{}\s*\(", regex::escape(id))) else {
+                continue;
+            };
+            for (file_path, content) in &from.client_sources {
+                if let Some(line) = content.lines().position(|line| call.is_match(line)) {
+                    edges.push(CrossRootEdge {
+                        kind: "openapi_client".to_string(),
+                        from_root: from.label.clone(),
+                        from_file: file_path.clone(),
+                        from_line: Some(line as u32 + 1),
+                        to_root: to.label.clone(),
+                        to_file: operation.file_path.clone(),
+                        to_line: Some(operation.line),
+                        target: id.to_string(),
+                        confidence: 0.8,
+                    });
+                }
+            }
+        }
+    }
+
+    fn link_packages(from: &RootSurface, to: &RootSurface, edges: &mut Vec<CrossRootEdge>) {
+        for published in &to.manifests {
+            let Some(name) = &published.package_name else { continue };
+            for manifest in &from.manifests {
+                if manifest.dependencies.iter().any(|dependency| dependency.name == *name) {
+                    edges.push(CrossRootEdge {
+                        kind: "package".to_string(),
+                        from_root: from.label.clone(),
+                        from_file: manifest.path.replace('\\', "/"),
+                        from_line: None,
+                        to_root: to.label.clone(),
+                        to_file: published.path.replace('\\', "/"),
+                        to_line: None,
+                        target: name.clone(),
+                        confidence: 1.0,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Merge per-root results into one, keying concepts by their root's label
+    pub fn combine(roots: &[RootAnalysis]) -> CodebaseAnalysisResult {
+        let mut languages = BTreeSet::new();
+        let mut frameworks = BTreeSet::new();
+        let mut concepts = Vec::new();
+
+        for root in roots {
+            languages.extend(root.analysis.languages.iter().cloned());
+            frameworks.extend(root.analysis.frameworks.iter().cloned());
+            let ids: HashSet<&str> = root.analysis.concepts.iter().map(|c| c.id.as_str()).collect();
+            let prefix = |id: &str| format!("{}/{}", root.label, id);
+            for concept in &root.analysis.concepts {
+                let mut concept = concept.clone();
+                concept.id = prefix(&concept.id);
+                for target in concept.relationships.values_mut() {
+                    if ids.contains(target.as_str()) {
+                        *target = prefix(target);
+                    }
+                }
+                concept.metadata.insert("root".to_string(), root.label.clone());
+                concepts.push(concept);
+            }
+        }
+
+        sort_concepts(&mut concepts);
+        CodebaseAnalysisResult {
+            languages: languages.into_iter().collect(),
+            frameworks: frameworks.into_iter().collect(),
+            complexity: ComplexityAnalyzer::calculate_complexity(&concepts),
+            concepts,
+        }
+    }
+}
+
+/// Path segments, `None` for parameters (`:id`, `{id}`, `<id>`, `${id}`, `*`)
+fn route_segments(path: &str) -> Vec<Option<&str>> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let parameter = segment.starts_with([':', '{', '<', '*']) || segment.starts_with("${");
+            (!parameter).then_some(segment)
+        })
+        .collect()
+}
+
+/// Whether a requested path can be served by a route, treating parameters on
+/// either side as wildcards
+pub(crate) fn paths_match(request: &str, route: &str) -> bool {
+    let (request, route) = (route_segments(request), route_segments(route));
+    request.len() == route.len()
+        && request.iter().zip(&route).all(|(a, b)| match (a, b) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_calls_and_match_routes() {
+        let client = "const order = await fetch(`${API_URL}/api/orders/${id}?expand=1`);\n\
+                      await axios.post('https://shop.example.com/api/orders', body);\n\
+                      const r = requests.delete(f\"/api/orders/{order_id}\")\n\
+                      fetch('orders.json');\n";
+        let calls = CrossRootLinker::extract_calls("src/api.ts", client);
+        let found: Vec<(Option<&str>, &str, u32)> =
+            calls.iter().map(|c| (c.method.as_deref(), c.path.as_str(), c.line)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (None, "/api/orders/${id}", 1),
+                (Some("POST"), "/api/orders", 2),
+                (Some("DELETE"), "/api/orders/{order_id}", 3),
+            ]
+        );
+
+        assert!(paths_match("/api/orders/${id}", "/api/orders/:id"));
+        assert!(paths_match("/api/orders/42", "/api/orders/{orderId}"));
+        assert!(!paths_match("/api/orders", "/api/orders/:id"));
+        assert!(!paths_match("/api/users/1", "/api/orders/:id"));
+    }
+
+    #[test]
+    fn test_extract_openapi_operations() {
+        let spec = "openapi: 3.0.0\npaths:\n  /orders/{id}:\n    get:\n      operationId: getOrder\n    parameters: []\n";
+        let operations = CrossRootLinker::extract_operations("openapi.yaml", spec);
+        assert_eq!(operations.len(), 1);
+        assert_eq!(operations[0].operation_id.as_deref(), Some("getOrder"));
+        assert_eq!((operations[0].method.as_str(), operations[0].line), ("GET", 5));
+        assert!(CrossRootLinker::extract_operations("openapi.yaml", "paths: {}\n").is_empty());
+    }
+
+    #[test]
+    fn test_labels_are_relative_to_the_shared_directory() {
+        let roots = [PathBuf::from("/work/shop/frontend"), PathBuf::from("/work/shop/services/api")];
+        assert_eq!(CrossRootLinker::labels(&roots), vec!["frontend", "services/api"]);
+        let nested = [PathBuf::from("/work/shop"), PathBuf::from("/work/shop/web")];
+        assert_eq!(CrossRootLinker::labels(&nested), vec!["shop", "web"]);
+    }
+}
@@ -0,0 +1,5 @@
+//! Language server over stdio; editors start it with the workspace as the root
+
+fn main() -> std::io::Result<()> {
+    in_memoria_core::lsp::serve_stdio()
+}
@@ -0,0 +1,273 @@
+//! Change notifications for downstream stores
+//!
+//! Hosts mirror concepts and patterns into their own stores (SQLite, vector
+//! indexes). Rather than re-querying everything after each call, they
+//! subscribe to a [`SharedEngine`](crate::SharedEngine) and receive a
+//! [`ChangeSet`] listing what a learn, load or update call added, modified and
+//! removed. Change sets are computed by comparing snapshots taken before and
+//! after the call, and only while someone is subscribed.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::patterns::types::Pattern;
+use crate::types::SemanticConcept;
+use schemars::JsonSchema;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// What one engine call changed. Ids of removed items are listed on their own
+/// since the items are gone.
+#[derive(Debug, Clone, Default, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ChangeSet {
+    /// Increases by one with every change set the engine emits
+    pub sequence: u32,
+    /// Engine call that made the changes, e.g. `learn_from_codebase`
+    pub source: String,
+    pub concepts_added: Vec<SemanticConcept>,
+    pub concepts_modified: Vec<SemanticConcept>,
+    pub concepts_removed: Vec<String>,
+    pub patterns_added: Vec<Pattern>,
+    pub patterns_modified: Vec<Pattern>,
+    pub patterns_removed: Vec<String>,
+}
+
+impl ChangeSet {
+    pub fn is_empty(&self) -> bool {
+        self.concepts_added.is_empty()
+            && self.concepts_modified.is_empty()
+            && self.concepts_removed.is_empty()
+            && self.patterns_added.is_empty()
+            && self.patterns_modified.is_empty()
+            && self.patterns_removed.is_empty()
+    }
+}
+
+/// Concepts and patterns held by the engine at one point, keyed by id
+#[derive(Debug, Clone, Default)]
+pub struct EngineSnapshot {
+    concepts: BTreeMap<String, (String, SemanticConcept)>,
+    patterns: BTreeMap<String, (String, Pattern)>,
+}
+
+impl EngineSnapshot {
+    pub fn new<'a>(
+        concepts: impl IntoIterator<Item = &'a SemanticConcept>,
+        patterns: impl IntoIterator<Item = Pattern>,
+    ) -> Self {
+        EngineSnapshot {
+            concepts: concepts
+                .into_iter()
+                .map(|concept| (concept.id.clone(), (concept_signature(concept), concept.clone())))
+                .collect(),
+            patterns: patterns
+                .into_iter()
+                .map(|pattern| (pattern.id.clone(), (pattern_signature(&pattern), pattern)))
+                .collect(),
+        }
+    }
+
+    /// Concepts and patterns only in `after`, in both but different, and only
+    /// in `self`, each in id order
+    pub fn changes_to(&self, after: &EngineSnapshot, source: &str) -> ChangeSet {
+        let (concepts_added, concepts_modified, concepts_removed) = diff(&self.concepts, &after.concepts);
+        let (patterns_added, patterns_modified, patterns_removed) = diff(&self.patterns, &after.patterns);
+        ChangeSet {
+            sequence: 0,
+            source: source.to_string(),
+            concepts_added,
+            concepts_modified,
+            concepts_removed,
+            patterns_added,
+            patterns_modified,
+            patterns_removed,
+        }
+    }
+}
+
+fn diff<T: Clone>(
+    before: &BTreeMap<String, (String, T)>,
+    after: &BTreeMap<String, (String, T)>,
+) -> (Vec<T>, Vec<T>, Vec<String>) {
+    let mut added = Vec::new();
+    let mut modified = Vec::new();
+    for (id, (signature, item)) in after {
+        match before.get(id) {
+            None => added.push(item.clone()),
+            Some((previous, _)) if previous != signature => modified.push(item.clone()),
+            Some(_) => {}
+        }
+    }
+    let removed = before.keys().filter(|id| !after.contains_key(*id)).cloned().collect();
+    (added, modified, removed)
+}
+
+/// Everything a subscriber stores about a concept, with map entries sorted so
+/// equal concepts compare equal
+fn concept_signature(concept: &SemanticConcept) -> String {
+    fn sorted(map: &HashMap<String, String>) -> BTreeMap<&String, &String> {
+        map.iter().collect()
+    }
+    format!(
+        "{}|{}|{}|{}|{}-{}|{:?}|{:?}",
+        concept.name,
+        concept.concept_type,
+        concept.confidence,
+        concept.file_path,
+        concept.line_range.start,
+        concept.line_range.end,
+        sorted(&concept.relationships),
+        sorted(&concept.metadata)
+    )
+}
+
+fn pattern_signature(pattern: &Pattern) -> String {
+    serde_json::to_string(pattern).unwrap_or_default()
+}
+
+/// Receives the changes of every engine call that changed something. Called
+/// before the engine call returns, so implementations should hand work off
+/// rather than block.
+pub trait ChangeSubscriber: Send + Sync {
+    fn on_changes(&self, changes: &ChangeSet);
+}
+
+/// Registered subscribers and the sequence number of the last change set
+#[derive(Clone, Default)]
+pub struct Subscribers {
+    inner: Arc<Mutex<SubscriberList>>,
+}
+
+#[derive(Default)]
+struct SubscriberList {
+    next_id: u32,
+    sequence: u32,
+    entries: Vec<(u32, Arc<dyn ChangeSubscriber>)>,
+}
+
+impl Subscribers {
+    /// Register a subscriber; returns the id to unsubscribe it with
+    pub fn add(&self, subscriber: Arc<dyn ChangeSubscriber>) -> u32 {
+        let mut list = self.inner.lock().unwrap();
+        list.next_id += 1;
+        let id = list.next_id;
+        list.entries.push((id, subscriber));
+        id
+    }
+
+    pub fn remove(&self, id: u32) -> bool {
+        let mut list = self.inner.lock().unwrap();
+        let before = list.entries.len();
+        list.entries.retain(|(entry_id, _)| *entry_id != id);
+        list.entries.len() != before
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().unwrap().entries.is_empty()
+    }
+
+    /// Number and send a change set to every subscriber; empty change sets are dropped
+    pub fn publish(&self, mut changes: ChangeSet) {
+        if changes.is_empty() {
+            return;
+        }
+        let subscribers: Vec<Arc<dyn ChangeSubscriber>> = {
+            let mut list = self.inner.lock().unwrap();
+            list.sequence += 1;
+            changes.sequence = list.sequence;
+            list.entries.iter().map(|(_, subscriber)| subscriber.clone()).collect()
+        };
+        for subscriber in subscribers {
+            subscriber.on_changes(&changes);
+        }
+    }
+}
+
+/// Subscriber forwarding each change set to a JavaScript callback without
+/// waiting for it to run
+#[cfg(feature = "napi-bindings")]
+pub struct CallbackSubscriber {
+    callback: napi::threadsafe_function::ThreadsafeFunction<ChangeSet, (), ChangeSet, napi::Status, false>,
+}
+
+#[cfg(feature = "napi-bindings")]
+impl CallbackSubscriber {
+    pub fn new(
+        callback: napi::threadsafe_function::ThreadsafeFunction<ChangeSet, (), ChangeSet, napi::Status, false>,
+    ) -> Self {
+        CallbackSubscriber { callback }
+    }
+}
+
+#[cfg(feature = "napi-bindings")]
+impl ChangeSubscriber for CallbackSubscriber {
+    fn on_changes(&self, changes: &ChangeSet) {
+        self.callback.call(
+            changes.clone(),
+            napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LineRange;
+
+    fn concept(id: &str, name: &str) -> SemanticConcept {
+        SemanticConcept {
+            id: id.to_string(),
+            name: name.to_string(),
+            concept_type: "function".to_string(),
+            confidence: 0.8,
+            file_path: "src/app.ts".to_string(),
+            line_range: LineRange { start: 1, end: 3 },
+            relationships: HashMap::new(),
+            metadata: HashMap::from([("async".to_string(), "false".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_snapshots_diff_into_added_modified_and_removed() {
+        let before = EngineSnapshot::new(&[concept("a", "load"), concept("b", "save")], vec![]);
+        let mut renamed = concept("b", "store");
+        renamed.metadata.insert("exported".to_string(), "true".to_string());
+        let after = EngineSnapshot::new(&[renamed, concept("c", "drop")], vec![]);
+
+        let changes = before.changes_to(&after, "learn_from_codebase");
+        let ids = |concepts: &[SemanticConcept]| concepts.iter().map(|c| c.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&changes.concepts_added), vec!["c"]);
+        assert_eq!(ids(&changes.concepts_modified), vec!["b"]);
+        assert_eq!(changes.concepts_removed, vec!["a".to_string()]);
+        assert!(before.changes_to(&before.clone(), "load_project").is_empty());
+    }
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<u32>>);
+
+    impl ChangeSubscriber for Recorder {
+        fn on_changes(&self, changes: &ChangeSet) {
+            self.0.lock().unwrap().push(changes.sequence);
+        }
+    }
+
+    #[test]
+    fn test_publish_numbers_non_empty_change_sets() {
+        let subscribers = Subscribers::default();
+        let recorder = Arc::new(Recorder::default());
+        let id = subscribers.add(recorder.clone());
+
+        let changes = ChangeSet { patterns_removed: vec!["naming_camel".to_string()], ..Default::default() };
+        subscribers.publish(changes.clone());
+        subscribers.publish(ChangeSet::default());
+        subscribers.publish(changes.clone());
+        assert_eq!(*recorder.0.lock().unwrap(), vec![1, 2]);
+
+        assert!(subscribers.remove(id));
+        assert!(!subscribers.remove(id));
+        subscribers.publish(changes);
+        assert_eq!(recorder.0.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,322 @@
+//! Ranked context packs for agent prompts
+//!
+//! Given a task description and a token budget, [`ContextPackBuilder`] picks
+//! the stored concepts (documentation sections with their summaries), learned
+//! patterns, pattern examples and blueprint features most relevant to the
+//! task. Relevance is the share of the task's keywords (see
+//! [`KeywordExtractor`]) an item mentions, with matches in its name counting
+//! double, scaled by the item's confidence. Items are deduplicated
+//! (the same concept extracted twice, the same example code under two
+//! patterns), then taken best first while they fit the budget, so the pack
+//! is the same for the same inputs. Items are measured with
+//! [`TokenCounter`]; the first one that does not fit is cut to the room left
+//! when that room is worth filling.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::FeatureMap;
+use crate::patterns::types::Pattern;
+use crate::patterns::KeywordExtractor;
+use crate::tokens::TokenCounter;
+use crate::types::validation::{ErrorCode, ValidationError};
+use crate::types::{paths, ParseError, SemanticConcept};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Examples included per pattern at most
+const EXAMPLES_PER_PATTERN: usize = 2;
+
+/// Share of its pattern's relevance an example inherits
+const EXAMPLE_INHERITANCE: f64 = 0.8;
+
+/// Room left in the budget below which an item is not cut to fit
+const MIN_TRUNCATED_TOKENS: u32 = 32;
+
+/// One piece of context and why it was picked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ContextItem {
+    /// 'concept', 'pattern', 'example' or 'feature'
+    pub kind: String,
+    /// Concept or pattern id, or feature name
+    pub id: String,
+    /// Text to put in the prompt
+    pub content: String,
+    pub file_path: Option<String>,
+    /// 0.0 to 1.0
+    pub relevance: f64,
+    pub tokens: u32,
+    /// Whether `content` was cut to fit the budget
+    pub truncated: bool,
+}
+
+/// The context picked for a task within a token budget
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ContextPack {
+    pub task: String,
+    pub budget_tokens: u32,
+    pub used_tokens: u32,
+    /// Most relevant first
+    pub items: Vec<ContextItem>,
+    /// Relevant items left out because they did not fit the budget
+    pub omitted: u32,
+}
+
+/// Collects candidate context and packs the most relevant into a budget
+pub struct ContextPackBuilder<'a> {
+    task: String,
+    keywords: Vec<String>,
+    root: Option<&'a str>,
+    candidates: Vec<ContextItem>,
+    seen: HashSet<String>,
+}
+
+impl<'a> ContextPackBuilder<'a> {
+    /// A builder for `task`; fails when the task has no keywords to rank by
+    pub fn new(task: &str, root: Option<&'a str>) -> Result<Self, ParseError> {
+        let keywords = KeywordExtractor::extract(task);
+        if keywords.is_empty() {
+            return Err(ValidationError::new(
+                ErrorCode::InvalidArgument,
+                "task_description must describe the task, e.g. 'add retries to the payment client'",
+            )
+            .into());
+        }
+        Ok(ContextPackBuilder {
+            task: task.trim().to_string(),
+            keywords,
+            root,
+            candidates: Vec::new(),
+            seen: HashSet::new(),
+        })
+    }
+
+    pub fn concepts(mut self, concepts: impl IntoIterator<Item = &'a SemanticConcept>) -> Self {
+        for concept in concepts {
+            let file_path = paths::relative_key(&concept.file_path, self.root);
+            // Documentation sections carry the guidance itself
+            let summary = concept.metadata.get("summary").map_or("", String::as_str);
+            let relevance =
+                self.relevance(&concept.name, &[&concept.concept_type, &file_path, summary]) * concept.confidence;
+            let mut content = format!(
+                "{} {} ({}:{}-{})",
+                concept.concept_type, concept.name, file_path, concept.line_range.start, concept.line_range.end
+            );
+            if !summary.is_empty() {
+                content.push_str(&format!(": {}", summary));
+            }
+            let key = format!("concept:{}:{}:{}", concept.name, concept.concept_type, file_path);
+            self.push(key, "concept", &concept.id, content, Some(file_path), relevance);
+        }
+        self
+    }
+
+    /// Patterns and up to two examples of each
+    pub fn patterns(mut self, patterns: &[Pattern]) -> Self {
+        for pattern in patterns {
+            let relevance = self.relevance(&pattern.pattern_type, &[&pattern.description, &pattern.contexts.join(" ")])
+                * pattern.confidence;
+            let content = format!(
+                "{}: {} (seen {} times, confidence {:.2})",
+                pattern.pattern_type, pattern.description, pattern.frequency, pattern.confidence
+            );
+            self.push(format!("pattern:{}", pattern.id), "pattern", &pattern.id, content, None, relevance);
+
+            for example in pattern.examples.iter().take(EXAMPLES_PER_PATTERN) {
+                let file_path = paths::relative_key(&example.file_path, self.root);
+                let own = self.relevance("", &[&example.code, &file_path]);
+                let relevance = own.max(relevance * EXAMPLE_INHERITANCE);
+                let content = format!(
+                    "{} ({}:{}-{}):\n{}",
+                    pattern.pattern_type,
+                    file_path,
+                    example.line_range.start,
+                    example.line_range.end,
+                    example.code.trim()
+                );
+                let code: String = example.code.split_whitespace().collect::<Vec<_>>().join(" ");
+                self.push(format!("example:{}", code), "example", &pattern.id, content, Some(file_path), relevance);
+            }
+        }
+        self
+    }
+
+    /// Blueprint features, with their files, concepts and dependencies
+    pub fn features(mut self, features: &[FeatureMap]) -> Self {
+        for feature in features {
+            let files = feature.primary_files.join(" ");
+            let relevance = self.relevance(&feature.feature_name, &[&files, &feature.concepts.join(" ")]);
+            let mut content = format!("feature {}: files {}", feature.feature_name, feature.primary_files.join(", "));
+            if !feature.concepts.is_empty() {
+                content.push_str(&format!("; concepts {}", feature.concepts.join(", ")));
+            }
+            if !feature.dependencies.is_empty() {
+                content.push_str(&format!("; depends on {}", feature.dependencies.join(", ")));
+            }
+            let key = format!("feature:{}", feature.feature_name);
+            self.push(key, "feature", &feature.feature_name, content, None, relevance);
+        }
+        self
+    }
+
+    /// The most relevant candidates that fit in `budget_tokens`, best first
+    pub fn build(mut self, budget_tokens: u32) -> Result<ContextPack, ParseError> {
+        if budget_tokens == 0 {
+            return Err(ValidationError::new(ErrorCode::InvalidArgument, "budget_tokens must be greater than 0").into());
+        }
+        self.candidates.sort_by(|a, b| {
+            b.relevance
+                .total_cmp(&a.relevance)
+                .then_with(|| a.tokens.cmp(&b.tokens))
+                .then_with(|| a.kind.cmp(&b.kind))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+
+        let mut pack = ContextPack { task: self.task, budget_tokens, ..Default::default() };
+        for mut item in self.candidates {
+            let room = budget_tokens - pack.used_tokens;
+            if item.tokens > room && room >= MIN_TRUNCATED_TOKENS {
+                let cut = TokenCounter::truncate(&item.content, room);
+                item.content = cut.text;
+                item.tokens = cut.tokens;
+                item.truncated = true;
+            }
+            if item.tokens <= room {
+                pack.used_tokens += item.tokens;
+                pack.items.push(item);
+            } else {
+                pack.omitted += 1;
+            }
+        }
+        Ok(pack)
+    }
+
+    /// Share of the task's keywords found in `name` (counted twice) and `text`
+    fn relevance(&self, name: &str, text: &[&str]) -> f64 {
+        let name: HashSet<String> = KeywordExtractor::extract(name).into_iter().collect();
+        let text: HashSet<String> = text.iter().flat_map(|text| KeywordExtractor::extract(text)).collect();
+        let score: usize = self
+            .keywords
+            .iter()
+            .map(|keyword| if name.contains(keyword) { 2 } else { usize::from(text.contains(keyword)) })
+            .sum();
+        score as f64 / (2 * self.keywords.len()) as f64
+    }
+
+    /// Keep a relevant candidate unless one with the same key was kept
+    fn push(&mut self, key: String, kind: &str, id: &str, content: String, file_path: Option<String>, relevance: f64) {
+        if relevance <= 0.0 || !self.seen.insert(key) {
+            return;
+        }
+        self.candidates.push(ContextItem {
+            kind: kind.to_string(),
+            id: id.to_string(),
+            tokens: TokenCounter::count(&content),
+            content,
+            file_path,
+            relevance: relevance.min(1.0),
+            truncated: false,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::types::PatternExample;
+    use crate::types::LineRange;
+    use std::collections::HashMap;
+
+    fn concept(name: &str, file_path: &str) -> SemanticConcept {
+        SemanticConcept {
+            id: format!("{}::{}", file_path, name),
+            name: name.to_string(),
+            concept_type: "class".to_string(),
+            confidence: 0.9,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start: 1, end: 10 },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn retry_pattern(id: &str, code: &str) -> Pattern {
+        Pattern {
+            id: id.to_string(),
+            pattern_type: format!("implementation_{}", id),
+            description: "Payment calls are wrapped in a retry helper".to_string(),
+            frequency: 4,
+            confidence: 0.8,
+            examples: vec![PatternExample {
+                code: code.to_string(),
+                file_path: "/repo/src/payments/client.ts".to_string(),
+                line_range: LineRange { start: 3, end: 3 },
+            }],
+            contexts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_pack_ranks_and_deduplicates_relevant_context() {
+        let concepts = [
+            concept("PaymentClient", "/repo/src/payments/client.ts"),
+            concept("PaymentClient", "/repo/src/payments/client.ts"),
+            concept("UserProfile", "/repo/src/users/profile.ts"),
+        ];
+        let patterns = [
+            retry_pattern("retry", "retry(() => pay())"),
+            retry_pattern("retry_alias", "retry(()  =>  pay())"),
+        ];
+        let pack = ContextPackBuilder::new("Add retries to the payment client", Some("/repo"))
+            .unwrap()
+            .concepts(&concepts)
+            .patterns(&patterns)
+            .build(1000)
+            .unwrap();
+
+        assert_eq!(pack.items[0].kind, "concept");
+        assert_eq!(pack.items[0].file_path.as_deref(), Some("src/payments/client.ts"));
+        let count = |kind: &str| pack.items.iter().filter(|item| item.kind == kind).count();
+        assert_eq!(count("concept"), 1);
+        assert_eq!(count("pattern"), 2);
+        assert_eq!(count("example"), 1);
+        assert!(pack.items.windows(2).all(|pair| pair[0].relevance >= pair[1].relevance));
+        assert_eq!(pack.used_tokens, pack.items.iter().map(|item| item.tokens).sum::<u32>());
+    }
+
+    #[test]
+    fn test_pack_stays_within_budget() {
+        let concepts = [concept("PaymentClient", "/repo/src/payments/client.ts")];
+        let patterns = [retry_pattern("retry", "retry(() => pay())")];
+        let build = |budget| {
+            ContextPackBuilder::new("payment retries", None)
+                .unwrap()
+                .concepts(&concepts)
+                .patterns(&patterns)
+                .build(budget)
+                .unwrap()
+        };
+        let full = build(10_000);
+        let tight = build(full.items[0].tokens);
+        assert_eq!(tight.items.len(), 1);
+        assert!(tight.used_tokens <= tight.budget_tokens);
+        assert_eq!(tight.omitted as usize, full.items.len() - 1);
+
+        let long_code = "retry(() => pay(order, amount));\n".repeat(50);
+        let long = [retry_pattern("retry", &long_code)];
+        let pack = ContextPackBuilder::new("payment retries", None).unwrap().patterns(&long).build(80).unwrap();
+        let example = pack.items.iter().find(|item| item.kind == "example").unwrap();
+        assert!(example.truncated);
+        assert!(example.content.ends_with(crate::tokens::TRUNCATION_MARKER));
+        assert!(pack.used_tokens <= 80);
+
+        assert!(ContextPackBuilder::new("do this with that", None).is_err());
+        let zero = ContextPackBuilder::new("payment", None).unwrap().build(0);
+        assert!(zero.unwrap_err().to_string().contains("[INVALID_ARGUMENT]"));
+    }
+}
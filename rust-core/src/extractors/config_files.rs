@@ -0,0 +1,819 @@
+//! Concept extraction from project configuration files
+//!
+//! CI workflows, docker-compose files, Dockerfiles, Terraform, package scripts
+//! and build manifests describe how a project is built, tested, run and
+//! deployed. This extractor turns them into concepts:
+//! - Services (docker-compose services)
+//! - Container stages (Dockerfile `FROM` stages with their base image and ports)
+//! - Infrastructure (Terraform resources, data sources, modules and providers,
+//!   with the blocks each one references)
+//! - CI jobs (GitHub Actions and GitLab CI jobs)
+//! - Scripts (package.json and pyproject.toml scripts)
+//! - Build targets (tsconfig outputs, Cargo binaries and libraries)
+//! - Environment variables (compose, workflow and CI variables)
+
+use crate::extractors::hcl::HclReader;
+use crate::types::{LineRange, SemanticConcept};
+use serde_json::Value as JsonValue;
+use serde_yaml::Value as YamlValue;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use toml::Value as TomlValue;
+use walkdir::WalkDir;
+
+/// Directories never searched for configuration files
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "target", "dist", "build", "vendor", "venv", ".venv"];
+
+/// Configuration files are expected near the project root
+const MAX_CONFIG_DEPTH: usize = 4;
+
+/// Top-level `.gitlab-ci.yml` keys that are settings rather than jobs
+const GITLAB_RESERVED_KEYS: &[&str] = &[
+    "stages", "variables", "default", "include", "workflow", "image", "services",
+    "before_script", "after_script", "cache", "pages",
+];
+
+/// Recognized configuration file kinds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFileKind {
+    GithubWorkflow,
+    GitlabCi,
+    DockerCompose,
+    PackageJson,
+    TsConfig,
+    CargoManifest,
+    PyProject,
+    Dockerfile,
+    Terraform,
+}
+
+impl ConfigFileKind {
+    /// Detect the kind from a path, matching on file name and, for workflows,
+    /// the parent directories
+    pub fn detect(file_path: &str) -> Option<Self> {
+        let normalized = file_path.replace('\\', "/");
+        let file_name = normalized.rsplit('/').next().unwrap_or("");
+        let is_yaml = file_name.ends_with(".yml") || file_name.ends_with(".yaml");
+
+        if is_yaml && normalized.contains(".github/workflows/") {
+            return Some(Self::GithubWorkflow);
+        }
+        match file_name {
+            ".gitlab-ci.yml" | ".gitlab-ci.yaml" => Some(Self::GitlabCi),
+            "package.json" => Some(Self::PackageJson),
+            "Cargo.toml" => Some(Self::CargoManifest),
+            "pyproject.toml" => Some(Self::PyProject),
+            name if name == "Dockerfile" || name.starts_with("Dockerfile.") || name.ends_with(".Dockerfile") => {
+                Some(Self::Dockerfile)
+            }
+            name if name.ends_with(".tf") => Some(Self::Terraform),
+            name if is_yaml && (name.starts_with("docker-compose") || name.starts_with("compose.")) => {
+                Some(Self::DockerCompose)
+            }
+            name if name.starts_with("tsconfig") && name.ends_with(".json") => Some(Self::TsConfig),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::GithubWorkflow => "github_workflow",
+            Self::GitlabCi => "gitlab_ci",
+            Self::DockerCompose => "docker_compose",
+            Self::PackageJson => "package_json",
+            Self::TsConfig => "tsconfig",
+            Self::CargoManifest => "cargo_manifest",
+            Self::PyProject => "pyproject",
+            Self::Dockerfile => "dockerfile",
+            Self::Terraform => "terraform",
+        }
+    }
+}
+
+/// Extracts operational concepts from YAML, TOML, JSON, Dockerfile and HCL configuration
+pub struct ConfigFileExtractor;
+
+impl ConfigFileExtractor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn is_config_file(file_path: &str) -> bool {
+        ConfigFileKind::detect(file_path).is_some()
+    }
+
+    /// Extract concepts from one configuration file. Unrecognized or malformed
+    /// files yield no concepts.
+    pub fn extract_concepts(&self, file_path: &str, content: &str) -> Vec<SemanticConcept> {
+        let Some(kind) = ConfigFileKind::detect(file_path) else {
+            return Vec::new();
+        };
+        let mut builder = ConceptBuilder::new(file_path, content, kind);
+
+        match kind {
+            ConfigFileKind::GithubWorkflow => {
+                if let Ok(yaml) = serde_yaml::from_str::<YamlValue>(content) {
+                    Self::extract_github_workflow(&yaml, &mut builder);
+                }
+            }
+            ConfigFileKind::GitlabCi => {
+                if let Ok(yaml) = serde_yaml::from_str::<YamlValue>(content) {
+                    Self::extract_gitlab_ci(&yaml, &mut builder);
+                }
+            }
+            ConfigFileKind::DockerCompose => {
+                if let Ok(yaml) = serde_yaml::from_str::<YamlValue>(content) {
+                    Self::extract_docker_compose(&yaml, &mut builder);
+                }
+            }
+            ConfigFileKind::PackageJson => {
+                if let Ok(json) = serde_json::from_str::<JsonValue>(content) {
+                    Self::extract_package_scripts(&json, &mut builder);
+                }
+            }
+            ConfigFileKind::TsConfig => {
+                if let Ok(json) = serde_json::from_str::<JsonValue>(content) {
+                    Self::extract_tsconfig(&json, &mut builder);
+                }
+            }
+            ConfigFileKind::CargoManifest => {
+                if let Ok(toml) = toml::from_str::<toml::Table>(content) {
+                    Self::extract_cargo_targets(&toml, &mut builder);
+                }
+            }
+            ConfigFileKind::PyProject => {
+                if let Ok(toml) = toml::from_str::<toml::Table>(content) {
+                    Self::extract_pyproject_scripts(&toml, &mut builder);
+                }
+            }
+            ConfigFileKind::Dockerfile => Self::extract_dockerfile(content, &mut builder),
+            ConfigFileKind::Terraform => Self::extract_terraform(content, &mut builder),
+        }
+
+        builder.concepts
+    }
+
+    /// Extract concepts from every configuration file under `root`
+    pub fn extract_directory(&self, root: &Path, max_file_size: u64) -> Vec<SemanticConcept> {
+        let mut concepts = Vec::new();
+        for entry in WalkDir::new(root)
+            .max_depth(MAX_CONFIG_DEPTH)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            let path_str = path.to_string_lossy();
+            if !entry.file_type().is_file() || !Self::is_config_file(&path_str) {
+                continue;
+            }
+            if entry.metadata().map(|m| m.len() > max_file_size).unwrap_or(true) {
+                continue;
+            }
+            if let Ok(content) = fs::read_to_string(path) {
+                concepts.extend(self.extract_concepts(&path_str, &content));
+            }
+        }
+        concepts
+    }
+
+    fn extract_github_workflow(yaml: &YamlValue, builder: &mut ConceptBuilder) {
+        let workflow = yaml.get("name").and_then(YamlValue::as_str).map(str::to_string);
+        builder.env_vars(yaml.get("env"), "env", None);
+
+        let Some(jobs) = yaml.get("jobs").and_then(YamlValue::as_mapping) else {
+            return;
+        };
+        let jobs_line = builder.line_of("jobs", 1);
+        for (name, job) in jobs {
+            let Some(name) = name.as_str() else { continue };
+            let mut metadata = HashMap::new();
+            if let Some(workflow) = &workflow {
+                metadata.insert("workflow".to_string(), workflow.clone());
+            }
+            if let Some(runs_on) = job.get("runs-on").and_then(yaml_scalar) {
+                metadata.insert("runs_on".to_string(), runs_on);
+            }
+            if let Some(steps) = job.get("steps").and_then(YamlValue::as_sequence) {
+                metadata.insert("step_count".to_string(), steps.len().to_string());
+            }
+            let needs = yaml_strings(job.get("needs"));
+            if !needs.is_empty() {
+                metadata.insert("needs".to_string(), needs.join(","));
+            }
+
+            let line = builder.line_of(name, jobs_line);
+            builder.push(name, "ci_job", line, metadata);
+            builder.env_vars(job.get("env"), "env", Some(name));
+        }
+    }
+
+    fn extract_gitlab_ci(yaml: &YamlValue, builder: &mut ConceptBuilder) {
+        builder.env_vars(yaml.get("variables"), "variables", None);
+
+        let Some(root) = yaml.as_mapping() else {
+            return;
+        };
+        for (name, job) in root {
+            let Some(name) = name.as_str() else { continue };
+            // Hidden jobs (".template") are only used through `extends`
+            if name.starts_with('.') || GITLAB_RESERVED_KEYS.contains(&name) || !job.is_mapping() {
+                continue;
+            }
+
+            let mut metadata = HashMap::new();
+            if let Some(stage) = job.get("stage").and_then(yaml_scalar) {
+                metadata.insert("stage".to_string(), stage);
+            }
+            if let Some(image) = job.get("image").and_then(yaml_scalar) {
+                metadata.insert("image".to_string(), image);
+            }
+            let line = builder.line_of(name, 1);
+            builder.push(name, "ci_job", line, metadata);
+            builder.env_vars(job.get("variables"), "variables", Some(name));
+        }
+    }
+
+    fn extract_docker_compose(yaml: &YamlValue, builder: &mut ConceptBuilder) {
+        let Some(services) = yaml.get("services").and_then(YamlValue::as_mapping) else {
+            return;
+        };
+        let services_line = builder.line_of("services", 1);
+        for (name, service) in services {
+            let Some(name) = name.as_str() else { continue };
+            let mut metadata = HashMap::new();
+            if let Some(image) = service.get("image").and_then(yaml_scalar) {
+                metadata.insert("image".to_string(), image);
+            }
+            if let Some(build) = service.get("build") {
+                let context = yaml_scalar(build)
+                    .or_else(|| build.get("context").and_then(yaml_scalar))
+                    .unwrap_or_else(|| ".".to_string());
+                metadata.insert("build".to_string(), context);
+                if let Some(dockerfile) = build.get("dockerfile").and_then(yaml_scalar) {
+                    metadata.insert("dockerfile".to_string(), dockerfile);
+                }
+            }
+            let ports = yaml_strings(service.get("ports"));
+            if !ports.is_empty() {
+                metadata.insert("ports".to_string(), ports.join(","));
+            }
+            let depends_on = yaml_strings(service.get("depends_on"));
+            if !depends_on.is_empty() {
+                metadata.insert("depends_on".to_string(), depends_on.join(","));
+            }
+
+            let line = builder.line_of(name, services_line);
+            builder.push(name, "service", line, metadata);
+            builder.env_vars(service.get("environment"), "environment", Some(name));
+        }
+    }
+
+    fn extract_package_scripts(json: &JsonValue, builder: &mut ConceptBuilder) {
+        let Some(scripts) = json.get("scripts").and_then(JsonValue::as_object) else {
+            return;
+        };
+        let scripts_line = builder.line_of("scripts", 1);
+        for (name, command) in scripts {
+            let mut metadata = HashMap::new();
+            if let Some(command) = command.as_str() {
+                metadata.insert("command".to_string(), command.to_string());
+            }
+            let line = builder.line_of(name, scripts_line);
+            builder.push(name, "script", line, metadata);
+        }
+    }
+
+    fn extract_tsconfig(json: &JsonValue, builder: &mut ConceptBuilder) {
+        let options = json.get("compilerOptions");
+        let option = |key: &str| options.and_then(|o| o.get(key)).and_then(JsonValue::as_str).map(str::to_string);
+
+        let mut metadata = HashMap::new();
+        for (key, meta_key) in [("target", "target"), ("module", "module"), ("outDir", "out_dir"), ("rootDir", "root_dir")] {
+            if let Some(value) = option(key) {
+                metadata.insert(meta_key.to_string(), value);
+            }
+        }
+        if let Some(extends) = json.get("extends").and_then(JsonValue::as_str) {
+            metadata.insert("extends".to_string(), extends.to_string());
+        }
+        if metadata.is_empty() {
+            return;
+        }
+
+        let name = option("outDir").unwrap_or_else(|| builder.file_stem());
+        let line = builder.line_of("compilerOptions", 1);
+        builder.push(&name, "build_target", line, metadata);
+    }
+
+    fn extract_cargo_targets(toml: &toml::Table, builder: &mut ConceptBuilder) {
+        let package_name = toml
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(TomlValue::as_str);
+
+        if let Some(lib) = toml.get("lib") {
+            let name = lib
+                .get("name")
+                .and_then(TomlValue::as_str)
+                .or(package_name)
+                .unwrap_or("lib")
+                .to_string();
+            let mut metadata = HashMap::from([("target_kind".to_string(), "lib".to_string())]);
+            if let Some(crate_types) = lib.get("crate-type").and_then(TomlValue::as_array) {
+                let crate_types: Vec<&str> = crate_types.iter().filter_map(TomlValue::as_str).collect();
+                metadata.insert("crate_type".to_string(), crate_types.join(","));
+            }
+            let line = builder.line_of("[lib]", 1);
+            builder.push(&name, "build_target", line, metadata);
+        }
+
+        let mut bin_line = 1;
+        for bin in toml.get("bin").and_then(TomlValue::as_array).into_iter().flatten() {
+            let Some(name) = bin.get("name").and_then(TomlValue::as_str) else { continue };
+            let mut metadata = HashMap::from([("target_kind".to_string(), "bin".to_string())]);
+            if let Some(path) = bin.get("path").and_then(TomlValue::as_str) {
+                metadata.insert("path".to_string(), path.to_string());
+            }
+            bin_line = builder.line_of("[[bin]]", bin_line);
+            builder.push(name, "build_target", bin_line, metadata);
+            bin_line += 1;
+        }
+    }
+
+    fn extract_pyproject_scripts(toml: &toml::Table, builder: &mut ConceptBuilder) {
+        let project_scripts = toml.get("project").and_then(|p| p.get("scripts"));
+        let poetry_scripts = toml
+            .get("tool")
+            .and_then(|t| t.get("poetry"))
+            .and_then(|p| p.get("scripts"));
+
+        for scripts in [project_scripts, poetry_scripts].into_iter().flatten() {
+            let Some(scripts) = scripts.as_table() else { continue };
+            for (name, entry_point) in scripts {
+                let mut metadata = HashMap::new();
+                if let Some(entry_point) = entry_point.as_str() {
+                    metadata.insert("entry_point".to_string(), entry_point.to_string());
+                }
+                let line = builder.line_of(name, 1);
+                builder.push(name, "script", line, metadata);
+            }
+        }
+    }
+
+    /// One `container_stage` per `FROM`, named by its `AS` alias or its index
+    fn extract_dockerfile(content: &str, builder: &mut ConceptBuilder) {
+        struct Stage {
+            name: String,
+            line: u32,
+            metadata: HashMap<String, String>,
+            ports: Vec<String>,
+            copies_from: Vec<String>,
+            extends: Option<String>,
+        }
+
+        let mut stages: Vec<Stage> = Vec::new();
+        for (line, instruction) in dockerfile_instructions(content) {
+            let Some((keyword, argument)) = instruction.split_once(char::is_whitespace) else { continue };
+            let keyword = keyword.to_uppercase();
+            if keyword == "FROM" {
+                // `FROM [--platform=...] image [AS name]`
+                let words: Vec<&str> = argument.split_whitespace().filter(|w| !w.starts_with("--")).collect();
+                let Some(image) = words.first() else { continue };
+                let alias = match words.as_slice() {
+                    [_, as_keyword, alias, ..] if as_keyword.eq_ignore_ascii_case("as") => Some(alias.to_string()),
+                    _ => None,
+                };
+                let extends = stages.iter().find(|s| s.name == *image).map(|s| s.name.clone());
+                let mut metadata = HashMap::from([
+                    ("base_image".to_string(), image.to_string()),
+                    ("stage_index".to_string(), stages.len().to_string()),
+                ]);
+                if let Some(platform) = argument.split_whitespace().find_map(|w| w.strip_prefix("--platform=")) {
+                    metadata.insert("platform".to_string(), platform.to_string());
+                }
+                stages.push(Stage {
+                    name: alias.unwrap_or_else(|| format!("stage {}", stages.len())),
+                    line,
+                    metadata,
+                    ports: Vec::new(),
+                    copies_from: Vec::new(),
+                    extends,
+                });
+                continue;
+            }
+
+            let Some(stage) = stages.last_mut() else { continue };
+            match keyword.as_str() {
+                "EXPOSE" => stage.ports.extend(argument.split_whitespace().map(str::to_string)),
+                "COPY" | "ADD" => {
+                    if let Some(from) = argument.split_whitespace().find_map(|w| w.strip_prefix("--from=")) {
+                        if !stage.copies_from.iter().any(|f| f == from) {
+                            stage.copies_from.push(from.to_string());
+                        }
+                    }
+                }
+                "ENTRYPOINT" | "CMD" => {
+                    let command = serde_json::from_str::<Vec<String>>(argument.trim())
+                        .map(|parts| parts.join(" "))
+                        .unwrap_or_else(|_| argument.trim().to_string());
+                    stage.metadata.insert(keyword.to_lowercase(), command);
+                }
+                "WORKDIR" | "USER" => {
+                    stage.metadata.insert(keyword.to_lowercase(), argument.trim().to_string());
+                }
+                _ => {}
+            }
+        }
+
+        let final_stage = stages.len().saturating_sub(1);
+        for (index, mut stage) in stages.into_iter().enumerate() {
+            if !stage.ports.is_empty() {
+                stage.metadata.insert("ports".to_string(), stage.ports.join(","));
+            }
+            if !stage.copies_from.is_empty() {
+                stage.metadata.insert("copies_from".to_string(), stage.copies_from.join(","));
+            }
+            // The image that gets shipped
+            if index == final_stage {
+                stage.metadata.insert("final".to_string(), "true".to_string());
+            }
+            let concept = builder.push(&stage.name, "container_stage", stage.line, stage.metadata);
+            if let Some(extends) = stage.extends {
+                concept.relationships.insert("extends".to_string(), extends);
+            }
+            if !stage.copies_from.is_empty() {
+                concept.relationships.insert("copies_from".to_string(), stage.copies_from.join(","));
+            }
+        }
+    }
+
+    /// Resources, data sources, modules and providers, each with the blocks it references
+    fn extract_terraform(content: &str, builder: &mut ConceptBuilder) {
+        for block in HclReader::blocks(content) {
+            let attribute = |key: &str| block.attributes.get(key).cloned();
+            let mut metadata = HashMap::new();
+            let (name, concept_type) = match (block.keyword.as_str(), block.labels.as_slice()) {
+                ("resource", [kind, name]) => {
+                    // `aws_s3_bucket` belongs to the `aws` provider unless the block picks one
+                    let provider = attribute("provider")
+                        .unwrap_or_else(|| kind.split('_').next().unwrap_or(kind).to_string());
+                    metadata.insert("resource_type".to_string(), kind.clone());
+                    metadata.insert("provider".to_string(), provider);
+                    (format!("{}.{}", kind, name), "infra_resource")
+                }
+                ("data", [kind, name]) => {
+                    metadata.insert("resource_type".to_string(), kind.clone());
+                    (format!("data.{}.{}", kind, name), "infra_data_source")
+                }
+                ("module", [name]) => {
+                    metadata.extend(attribute("source").map(|source| ("module_source".to_string(), source)));
+                    metadata.extend(attribute("version").map(|version| ("version".to_string(), version)));
+                    (format!("module.{}", name), "infra_module")
+                }
+                ("provider", [name]) => {
+                    metadata.extend(attribute("region").map(|region| ("region".to_string(), region)));
+                    match attribute("alias") {
+                        Some(alias) => (format!("{}.{}", name, alias), "infra_provider"),
+                        None => (name.clone(), "infra_provider"),
+                    }
+                }
+                _ => continue,
+            };
+            for key in ["count", "for_each"] {
+                if block.attributes.contains_key(key) {
+                    metadata.insert("repeated_by".to_string(), key.to_string());
+                }
+            }
+
+            let references: Vec<&str> =
+                block.references.iter().map(String::as_str).filter(|reference| *reference != name).collect();
+            if !references.is_empty() {
+                metadata.insert("references".to_string(), references.join(","));
+            }
+            let concept = builder.push(&name, concept_type, block.line, metadata);
+            concept.line_range.end = block.end_line;
+            if !references.is_empty() {
+                concept.relationships.insert("references".to_string(), references.join(","));
+            }
+        }
+    }
+}
+
+impl Default for ConfigFileExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Accumulates concepts for one file and locates keys in its source text
+struct ConceptBuilder<'a> {
+    file_path: &'a str,
+    lines: Vec<&'a str>,
+    kind: ConfigFileKind,
+    concepts: Vec<SemanticConcept>,
+}
+
+impl<'a> ConceptBuilder<'a> {
+    fn new(file_path: &'a str, content: &'a str, kind: ConfigFileKind) -> Self {
+        ConceptBuilder {
+            file_path,
+            lines: content.lines().collect(),
+            kind,
+            concepts: Vec::new(),
+        }
+    }
+
+    fn push(
+        &mut self,
+        name: &str,
+        concept_type: &str,
+        line: u32,
+        mut metadata: HashMap<String, String>,
+    ) -> &mut SemanticConcept {
+        metadata.insert("config_kind".to_string(), self.kind.as_str().to_string());
+        metadata.insert("source".to_string(), "config_file".to_string());
+
+        let mut hasher = DefaultHasher::new();
+        (self.file_path, concept_type, name, line).hash(&mut hasher);
+
+        self.concepts.push(SemanticConcept {
+            id: format!("config_{:016x}", hasher.finish()),
+            name: name.to_string(),
+            concept_type: concept_type.to_string(),
+            // Declared in configuration rather than inferred from code
+            confidence: 0.9,
+            file_path: self.file_path.to_string(),
+            line_range: LineRange { start: line, end: line },
+            relationships: HashMap::new(),
+            metadata,
+        });
+        self.concepts.last_mut().expect("just pushed")
+    }
+
+    /// Environment variables from a `KEY: value` mapping or a `KEY=value` list
+    fn env_vars(&mut self, env: Option<&YamlValue>, section: &str, owner: Option<&str>) {
+        let names: Vec<String> = match env {
+            Some(YamlValue::Mapping(map)) => map.keys().filter_map(|k| k.as_str().map(str::to_string)).collect(),
+            Some(YamlValue::Sequence(items)) => items
+                .iter()
+                .filter_map(YamlValue::as_str)
+                .map(|item| item.split('=').next().unwrap_or(item).to_string())
+                .collect(),
+            _ => return,
+        };
+
+        let owner_line = owner.map_or(1, |owner| self.line_of(owner, 1));
+        let section_line = self.line_of(section, owner_line);
+        for name in names {
+            let mut metadata = HashMap::new();
+            if let Some(owner) = owner {
+                metadata.insert("owner".to_string(), owner.to_string());
+            }
+            let line = self.line_of(&name, section_line);
+            self.push(&name, "env_var", line, metadata);
+        }
+    }
+
+    /// First line at or after `from` (1-based) that declares `key`, or `from`
+    /// itself when the key can't be found
+    fn line_of(&self, key: &str, from: u32) -> u32 {
+        let start = from.saturating_sub(1) as usize;
+        self.lines
+            .iter()
+            .enumerate()
+            .skip(start)
+            .find(|(_, line)| {
+                let line = line.trim_start().trim_start_matches("- ");
+                let bare = line.starts_with(key)
+                    && (key.starts_with('[') || line[key.len()..].trim_start().starts_with([':', '=']));
+                bare || line.starts_with(&format!("\"{}\"", key)) || line.starts_with(&format!("'{}'", key))
+            })
+            .map_or(from.max(1), |(idx, _)| idx as u32 + 1)
+    }
+
+    fn file_stem(&self) -> String {
+        Path::new(self.file_path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "tsconfig".to_string())
+    }
+}
+
+/// Dockerfile instructions with their 1-based line, continuation lines joined
+/// and comments left out
+fn dockerfile_instructions(content: &str) -> Vec<(u32, String)> {
+    let mut instructions = Vec::new();
+    let mut current: Option<(u32, String)> = None;
+    for (idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') || (trimmed.is_empty() && current.is_none()) {
+            continue;
+        }
+        let (text, continues) = match trimmed.strip_suffix('\\') {
+            Some(text) => (text.trim_end(), true),
+            None => (trimmed, false),
+        };
+        let (_, instruction) = current.get_or_insert_with(|| (idx as u32 + 1, String::new()));
+        if !instruction.is_empty() && !text.is_empty() {
+            instruction.push(' ');
+        }
+        instruction.push_str(text);
+        if !continues {
+            instructions.extend(current.take());
+        }
+    }
+    instructions.extend(current);
+    instructions
+}
+
+/// A scalar YAML value as a string
+fn yaml_scalar(value: &YamlValue) -> Option<String> {
+    match value {
+        YamlValue::String(s) => Some(s.clone()),
+        YamlValue::Number(n) => Some(n.to_string()),
+        YamlValue::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// A scalar or a list of scalars; mapping keys for `depends_on: { db: ... }`
+fn yaml_strings(value: Option<&YamlValue>) -> Vec<String> {
+    match value {
+        Some(YamlValue::Sequence(items)) => items.iter().filter_map(yaml_scalar).collect(),
+        Some(YamlValue::Mapping(map)) => map.keys().filter_map(yaml_scalar).collect(),
+        Some(other) => yaml_scalar(other).into_iter().collect(),
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(concepts: &'a [SemanticConcept], name: &str, concept_type: &str) -> &'a SemanticConcept {
+        concepts
+            .iter()
+            .find(|c| c.name == name && c.concept_type == concept_type)
+            .unwrap_or_else(|| panic!("missing {} {}", concept_type, name))
+    }
+
+    #[test]
+    fn test_detect_kind() {
+        assert_eq!(ConfigFileKind::detect("repo/.github/workflows/ci.yml"), Some(ConfigFileKind::GithubWorkflow));
+        assert_eq!(ConfigFileKind::detect("docker-compose.prod.yaml"), Some(ConfigFileKind::DockerCompose));
+        assert_eq!(ConfigFileKind::detect("tsconfig.build.json"), Some(ConfigFileKind::TsConfig));
+        assert_eq!(ConfigFileKind::detect("deploy/Dockerfile.prod"), Some(ConfigFileKind::Dockerfile));
+        assert_eq!(ConfigFileKind::detect("infra/main.tf"), Some(ConfigFileKind::Terraform));
+        assert_eq!(ConfigFileKind::detect("config/settings.yml"), None);
+    }
+
+    #[test]
+    fn test_github_workflow_jobs_and_env() {
+        let content = "\
+name: CI
+env:
+  NODE_VERSION: 20
+jobs:
+  lint:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+  test:
+    needs: lint
+    runs-on: ubuntu-latest
+    env:
+      DATABASE_URL: postgres://localhost
+    steps:
+      - run: npm test
+";
+        let concepts = ConfigFileExtractor::new().extract_concepts(".github/workflows/ci.yml", content);
+
+        let test_job = find(&concepts, "test", "ci_job");
+        assert_eq!(test_job.line_range.start, 9);
+        assert_eq!(test_job.metadata["needs"], "lint");
+        assert_eq!(test_job.metadata["workflow"], "CI");
+        assert_eq!(find(&concepts, "lint", "ci_job").metadata["step_count"], "1");
+
+        assert_eq!(find(&concepts, "NODE_VERSION", "env_var").line_range.start, 3);
+        let database_url = find(&concepts, "DATABASE_URL", "env_var");
+        assert_eq!(database_url.metadata["owner"], "test");
+        assert_eq!(database_url.line_range.start, 13);
+    }
+
+    #[test]
+    fn test_docker_compose_services() {
+        let content = "\
+services:
+  api:
+    build: ./api
+    ports:
+      - \"8080:8080\"
+    depends_on:
+      - db
+    environment:
+      - DATABASE_URL=postgres://db/app
+  db:
+    image: postgres:16
+";
+        let concepts = ConfigFileExtractor::new().extract_concepts("docker-compose.yml", content);
+
+        let api = find(&concepts, "api", "service");
+        assert_eq!(api.metadata["build"], "./api");
+        assert_eq!(api.metadata["ports"], "8080:8080");
+        assert_eq!(api.metadata["depends_on"], "db");
+        assert_eq!(find(&concepts, "db", "service").metadata["image"], "postgres:16");
+        assert_eq!(find(&concepts, "DATABASE_URL", "env_var").line_range.start, 9);
+    }
+
+    #[test]
+    fn test_scripts_and_build_targets() {
+        let extractor = ConfigFileExtractor::new();
+
+        let package = r#"{ "name": "web", "scripts": { "build": "tsc -p .", "test": "vitest" } }"#;
+        let scripts = extractor.extract_concepts("package.json", package);
+        assert_eq!(find(&scripts, "build", "script").metadata["command"], "tsc -p .");
+
+        let tsconfig = r#"{ "compilerOptions": { "target": "ES2022", "outDir": "dist" } }"#;
+        let targets = extractor.extract_concepts("tsconfig.json", tsconfig);
+        assert_eq!(find(&targets, "dist", "build_target").metadata["target"], "ES2022");
+
+        let cargo = "[package]\nname = \"tool\"\n\n[lib]\ncrate-type = [\"cdylib\"]\n\n[[bin]]\nname = \"tool-cli\"\npath = \"src/main.rs\"\n";
+        let targets = extractor.extract_concepts("Cargo.toml", cargo);
+        assert_eq!(find(&targets, "tool", "build_target").metadata["crate_type"], "cdylib");
+        assert_eq!(find(&targets, "tool-cli", "build_target").line_range.start, 7);
+
+        assert!(extractor.extract_concepts("package.json", "{ not json").is_empty());
+    }
+
+    #[test]
+    fn test_dockerfile_stages() {
+        let content = "\
+# syntax=docker/dockerfile:1
+FROM --platform=linux/amd64 node:20 AS build
+WORKDIR /app
+RUN npm ci && \\
+    npm run build
+
+FROM build AS test
+RUN npm test
+
+FROM node:20-slim
+COPY --from=build /app/dist ./dist
+EXPOSE 8080 9229/tcp
+CMD [\"node\", \"dist/server.js\"]
+";
+        let concepts = ConfigFileExtractor::new().extract_concepts("Dockerfile", content);
+        let names: Vec<&str> = concepts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["build", "test", "stage 2"]);
+
+        let build = find(&concepts, "build", "container_stage");
+        assert_eq!(build.line_range.start, 2);
+        assert_eq!(build.metadata["base_image"], "node:20");
+        assert_eq!(build.metadata["platform"], "linux/amd64");
+        assert_eq!(find(&concepts, "test", "container_stage").relationships["extends"], "build");
+
+        let runtime = find(&concepts, "stage 2", "container_stage");
+        assert_eq!(runtime.line_range.start, 10);
+        assert_eq!(runtime.metadata["ports"], "8080,9229/tcp");
+        assert_eq!(runtime.metadata["cmd"], "node dist/server.js");
+        assert_eq!(runtime.metadata["final"], "true");
+        assert_eq!(runtime.relationships["copies_from"], "build");
+        assert!(!build.metadata.contains_key("final"));
+    }
+
+    #[test]
+    fn test_terraform_blocks() {
+        let content = r#"provider "aws" {
+  region = "eu-west-1"
+}
+
+module "vpc" {
+  source = "terraform-aws-modules/vpc/aws"
+}
+
+resource "aws_instance" "web" {
+  count     = 2
+  ami       = data.aws_ami.ubuntu.id
+  subnet_id = module.vpc.public_subnets[0]
+}
+
+variable "instances" {}
+"#;
+        let concepts = ConfigFileExtractor::new().extract_concepts("infra/main.tf", content);
+        assert_eq!(concepts.len(), 3);
+
+        assert_eq!(find(&concepts, "aws", "infra_provider").metadata["region"], "eu-west-1");
+        assert_eq!(find(&concepts, "module.vpc", "infra_module").metadata["module_source"], "terraform-aws-modules/vpc/aws");
+        let web = find(&concepts, "aws_instance.web", "infra_resource");
+        assert_eq!((web.line_range.start, web.line_range.end), (9, 13));
+        assert_eq!(web.metadata["provider"], "aws");
+        assert_eq!(web.metadata["repeated_by"], "count");
+        assert_eq!(web.relationships["references"], "data.aws_ami.ubuntu,module.vpc");
+        assert_eq!(web.metadata["config_kind"], "terraform");
+    }
+}
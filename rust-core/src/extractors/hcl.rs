@@ -0,0 +1,373 @@
+//! Minimal HCL reader for Terraform files
+//!
+//! Terraform configuration is a list of top-level blocks (`resource`, `data`,
+//! `module`, `provider`, ...) with labels, attributes and expressions that
+//! reference other blocks. This reader finds those blocks, their top-level
+//! attributes and the blocks they reference; it doesn't evaluate expressions.
+//!
+//! String literals, heredocs and comments are masked before braces are counted,
+//! so braces inside them don't end a block and words inside them aren't taken
+//! for references. Expressions inside `${...}` interpolations are kept.
+
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// Top-level keywords of references that point at values rather than blocks
+const NON_BLOCK_ROOTS: &[&str] = &["var", "local", "each", "count", "path", "self", "terraform"];
+
+/// One top-level block
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HclBlock {
+    /// e.g. `resource`
+    pub keyword: String,
+    /// e.g. `["aws_s3_bucket", "logs"]`
+    pub labels: Vec<String>,
+    /// 1-based line of the block header
+    pub line: u32,
+    pub end_line: u32,
+    /// Top-level attributes; string literals without their quotes, anything
+    /// else as written
+    pub attributes: BTreeMap<String, String>,
+    /// Resources (`aws_vpc.main`), data sources (`data.aws_ami.ubuntu`) and
+    /// modules (`module.vpc`) referenced from the block, in first-use order
+    pub references: Vec<String>,
+}
+
+pub struct HclReader;
+
+impl HclReader {
+    /// Every top-level block of a file, in source order
+    pub fn blocks(content: &str) -> Vec<HclBlock> {
+        let masked = mask(content);
+        let mut blocks = Vec::new();
+        let mut depth = 0u32;
+        // Byte offset of the header's line, of the opening brace, and its line
+        let mut open: Option<(usize, usize, u32)> = None;
+        let (mut line, mut line_start) = (1u32, 0usize);
+
+        for (idx, ch) in masked.char_indices() {
+            match ch {
+                '\n' => {
+                    line += 1;
+                    line_start = idx + 1;
+                }
+                '{' => {
+                    if depth == 0 {
+                        open = Some((line_start, idx, line));
+                    }
+                    depth += 1;
+                }
+                '}' if depth > 0 => {
+                    depth -= 1;
+                    if depth == 0 {
+                        if let Some((header_start, body_start, start_line)) = open.take() {
+                            let block = Self::block(content, &masked, header_start, body_start, idx, start_line, line);
+                            blocks.extend(block);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        blocks
+    }
+
+    fn block(
+        content: &str,
+        masked: &str,
+        header_start: usize,
+        body_start: usize,
+        body_end: usize,
+        line: u32,
+        end_line: u32,
+    ) -> Option<HclBlock> {
+        // `keyword "label" "label" {`; labels are the quoted parts of the header
+        let header = &content[header_start..body_start];
+        let keyword = header.split_whitespace().next()?;
+        if !keyword.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+        let labels = header.split('"').skip(1).step_by(2).map(str::to_string).collect();
+
+        let body = &content[body_start + 1..body_end];
+        let masked_body = &masked[body_start + 1..body_end];
+        Some(HclBlock {
+            keyword: keyword.to_string(),
+            labels,
+            line,
+            end_line,
+            attributes: Self::attributes(body, masked_body),
+            references: Self::references(masked_body),
+        })
+    }
+
+    /// `key = value` lines directly inside the block body
+    fn attributes(body: &str, masked_body: &str) -> BTreeMap<String, String> {
+        let mut attributes = BTreeMap::new();
+        let mut depth = 0i32;
+        for (line, masked_line) in body.lines().zip(masked_body.lines()) {
+            if depth == 0 {
+                if let Some(found) = patterns().attribute.captures(masked_line) {
+                    let key = &found[1];
+                    let value = line[found.get(2).map_or(0, |m| m.end())..].trim();
+                    let unquoted = value
+                        .strip_prefix('"')
+                        .and_then(|v| v.strip_suffix('"'))
+                        .filter(|v| !v.contains('"'));
+                    attributes.insert(key.to_string(), unquoted.unwrap_or(value).to_string());
+                }
+            }
+            for ch in masked_line.chars() {
+                match ch {
+                    '{' | '[' | '(' => depth += 1,
+                    '}' | ']' | ')' => depth -= 1,
+                    _ => {}
+                }
+            }
+        }
+        attributes
+    }
+
+    fn references(masked_body: &str) -> Vec<String> {
+        let mut references: Vec<String> = Vec::new();
+        for found in patterns().reference.find_iter(masked_body) {
+            // Skip attribute chains such as the `main.id` of `aws_vpc.main.id`
+            let preceded_by = masked_body[..found.start()].chars().next_back();
+            if preceded_by.is_some_and(|c| c == '.' || c == '_' || c == '-' || c.is_ascii_alphanumeric()) {
+                continue;
+            }
+            let reference = found.as_str();
+            let root = reference.split('.').next().unwrap_or(reference);
+            if NON_BLOCK_ROOTS.contains(&root) || references.iter().any(|r| r == reference) {
+                continue;
+            }
+            references.push(reference.to_string());
+        }
+        references
+    }
+}
+
+/// Where the masker is inside the file
+enum MaskState {
+    /// A string literal
+    String,
+    /// A `${...}` interpolation with this many unclosed braces of its own
+    Interpolation(u32),
+}
+
+/// The content with string literals, heredocs and comments blanked out but
+/// interpolated expressions kept; byte offsets and line breaks are preserved
+fn mask(content: &str) -> String {
+    let mut masked = String::with_capacity(content.len());
+    let mut stack: Vec<MaskState> = Vec::new();
+    let mut heredoc: Option<String> = None;
+    let mut chars = content.char_indices().peekable();
+    let blank = |masked: &mut String, ch: char| masked.push_str(&" ".repeat(ch.len_utf8()));
+
+    while let Some((idx, ch)) = chars.next() {
+        // Heredoc bodies are blanked line by line up to the closing marker
+        if let Some(marker) = &heredoc {
+            let line_end = content[idx..].find('\n').map_or(content.len(), |end| idx + end);
+            let line = &content[idx..line_end];
+            if line.trim() == marker {
+                heredoc = None;
+                masked.push_str(line);
+            } else {
+                masked.push_str(&" ".repeat(line.len()));
+            }
+            while chars.peek().is_some_and(|(next, _)| *next < line_end) {
+                chars.next();
+            }
+            if line_end < content.len() {
+                masked.push('\n');
+                // An empty line's break is the character already taken
+                if line_end > idx {
+                    chars.next();
+                }
+            }
+            continue;
+        }
+
+        match stack.last_mut() {
+            Some(MaskState::String) => match ch {
+                '\\' => {
+                    blank(&mut masked, ch);
+                    if let Some((_, escaped)) = chars.next() {
+                        blank(&mut masked, escaped);
+                    }
+                }
+                '"' => {
+                    stack.pop();
+                    masked.push(ch);
+                }
+                '$' if chars.peek().is_some_and(|(_, next)| *next == '{') => {
+                    chars.next();
+                    masked.push_str("  ");
+                    stack.push(MaskState::Interpolation(0));
+                }
+                '\n' => masked.push(ch),
+                _ => blank(&mut masked, ch),
+            },
+            state => {
+                match ch {
+                    '"' => {
+                        stack.push(MaskState::String);
+                        masked.push(ch);
+                    }
+                    '#' => skip_line_comment(&mut chars, &mut masked),
+                    '/' if chars.peek().is_some_and(|(_, next)| *next == '/') => {
+                        skip_line_comment(&mut chars, &mut masked)
+                    }
+                    '/' if chars.peek().is_some_and(|(_, next)| *next == '*') => {
+                        masked.push(' ');
+                        let mut previous = ' ';
+                        for (_, next) in chars.by_ref() {
+                            if next == '\n' {
+                                masked.push('\n');
+                            } else {
+                                blank(&mut masked, next);
+                            }
+                            if previous == '*' && next == '/' {
+                                break;
+                            }
+                            previous = next;
+                        }
+                    }
+                    '<' if content[idx..].starts_with("<<") => {
+                        let rest = &content[idx + 2..];
+                        let rest = rest.strip_prefix('-').unwrap_or(rest);
+                        let marker: String =
+                            rest.chars().take_while(|c| c.is_ascii_alphanumeric() || *c == '_').collect();
+                        if marker.is_empty() {
+                            masked.push(ch);
+                        } else {
+                            heredoc = Some(marker);
+                            // The rest of the opening line is blanked too
+                            masked.push(' ');
+                            while chars.peek().is_some_and(|(_, next)| *next != '\n') {
+                                let (_, next) = chars.next().expect("peeked");
+                                blank(&mut masked, next);
+                            }
+                            if chars.next().is_some() {
+                                masked.push('\n');
+                            }
+                        }
+                    }
+                    '{' => {
+                        if let Some(MaskState::Interpolation(depth)) = state {
+                            *depth += 1;
+                        }
+                        masked.push(ch);
+                    }
+                    '}' => match state {
+                        Some(MaskState::Interpolation(0)) => {
+                            stack.pop();
+                            masked.push(' ');
+                        }
+                        Some(MaskState::Interpolation(depth)) => {
+                            *depth -= 1;
+                            masked.push(ch);
+                        }
+                        _ => masked.push(ch),
+                    },
+                    _ => masked.push(ch),
+                }
+            }
+        }
+    }
+    masked
+}
+
+/// Blank a `#` or `//` comment up to, not including, the end of its line
+fn skip_line_comment(chars: &mut std::iter::Peekable<std::str::CharIndices>, masked: &mut String) {
+    masked.push(' ');
+    while chars.peek().is_some_and(|(_, next)| *next != '\n') {
+        let (_, next) = chars.next().expect("peeked");
+        masked.push_str(&" ".repeat(next.len_utf8()));
+    }
+}
+
+struct Patterns {
+    attribute: Regex,
+    reference: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let re = |p: &str| Regex::new(p).expect("valid HCL regex");
+        Patterns {
+            // `=` but not `==` or `=>`
+            attribute: re(r"^\s*([A-Za-z_][\w-]*)\s*(=)(?:[^=>]|$)"),
+            // `module.x`, `data.type.name` or `type.name` where the type has a
+            // provider prefix (`aws_vpc`), as resource types always do
+            reference: re(
+                r"\b(?:module\.[A-Za-z_][\w-]*|data\.[A-Za-z][\w-]*\.[A-Za-z_][\w-]*|[a-z][a-z0-9]*_[a-z0-9_]+\.[A-Za-z_][\w-]*)",
+            ),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_blocks_attributes_and_references() {
+        let content = r#"
+# Network { not a block }
+resource "aws_vpc" "main" {
+  cidr_block = "10.0.0.0/16"
+  tags = {
+    Name = "main.vpc_name"
+  }
+}
+
+resource "aws_instance" "web" {
+  ami           = data.aws_ami.ubuntu.id
+  subnet_id     = aws_subnet.public.id
+  user_data     = <<-EOF
+    #!/bin/bash
+
+    echo "} aws_fake.ref"
+  EOF
+  vpc_security_group_ids = ["${aws_security_group.web.id}"]
+  count         = var.instances
+  depends_on    = [module.vpc]
+}
+
+module "vpc" {
+  source  = "terraform-aws-modules/vpc/aws"
+  version = "5.1.0"
+}
+"#;
+        let blocks = HclReader::blocks(content);
+        let headers: Vec<(&str, Vec<&str>, u32)> = blocks
+            .iter()
+            .map(|b| (b.keyword.as_str(), b.labels.iter().map(String::as_str).collect(), b.line))
+            .collect();
+        assert_eq!(
+            headers,
+            vec![
+                ("resource", vec!["aws_vpc", "main"], 3),
+                ("resource", vec!["aws_instance", "web"], 10),
+                ("module", vec!["vpc"], 23),
+            ]
+        );
+
+        let vpc = &blocks[0];
+        assert_eq!(vpc.attributes["cidr_block"], "10.0.0.0/16");
+        assert!(!vpc.attributes.contains_key("Name"));
+        assert!(vpc.references.is_empty());
+
+        let web = &blocks[1];
+        assert_eq!(web.end_line, 21);
+        assert_eq!(web.attributes["count"], "var.instances");
+        assert_eq!(
+            web.references,
+            vec!["data.aws_ami.ubuntu", "aws_subnet.public", "aws_security_group.web", "module.vpc"]
+        );
+        assert_eq!(blocks[2].attributes["source"], "terraform-aws-modules/vpc/aws");
+    }
+}
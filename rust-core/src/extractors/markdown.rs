@@ -0,0 +1,504 @@
+//! Documentation structure from Markdown files
+//!
+//! READMEs, top-level guides (CONTRIBUTING.md, ARCHITECTURE.md) and the
+//! Markdown under `docs/` hold the team's written guidance. This extractor
+//! turns them into concepts:
+//! - Sections (`doc_section`): one per heading, spanning the lines up to the
+//!   next heading of the same or a higher level, with the section's first
+//!   paragraph as its summary
+//! - Code blocks (`doc_code_block`): fenced code with its language
+//! - Links (`doc_link`): links to other files and to URLs; in-page anchors
+//!   are left out
+//!
+//! Identifiers in code blocks and in inline code spans are recorded as the
+//! `documents` relationship, which the relationship graph resolves to the
+//! code concepts of those names.
+
+use crate::types::{paths, LineRange, SemanticConcept};
+use regex::Regex;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::OnceLock;
+use walkdir::WalkDir;
+
+/// Directories never searched for documentation
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", "target", "dist", "build", "vendor", "venv", ".venv"];
+
+/// Directories whose Markdown files are all documentation
+const DOC_DIRS: &[&str] = &["docs", "doc", "documentation"];
+
+const MAX_DOC_DEPTH: usize = 6;
+
+/// Longest section summary kept, in characters
+const MAX_SUMMARY_CHARS: usize = 240;
+
+/// Words that look like identifiers in code blocks but never name a concept
+const KEYWORDS: &[&str] = &[
+    "if", "else", "for", "while", "return", "self", "this", "None", "True", "False", "null", "true", "false",
+    "new", "let", "const", "var", "fn", "def", "class", "import", "from", "function", "async", "await", "pub",
+    "use", "mut", "struct", "impl", "print", "println", "console", "require",
+];
+
+pub struct MarkdownExtractor;
+
+impl MarkdownExtractor {
+    pub fn new() -> Self {
+        MarkdownExtractor
+    }
+
+    pub fn is_documentation(file_path: &str) -> bool {
+        Path::new(file_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "md" | "markdown" | "mdx"))
+    }
+
+    /// Extract concepts from every README, every Markdown file at the root and
+    /// every Markdown file inside a documentation directory
+    pub fn extract_directory(&self, root: &Path, max_file_size: u64) -> Vec<SemanticConcept> {
+        let mut concepts = Vec::new();
+        for entry in WalkDir::new(root)
+            .max_depth(MAX_DOC_DEPTH)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !IGNORED_DIRS.contains(&e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            let path_str = path.to_string_lossy();
+            if !entry.file_type().is_file() || !Self::is_documentation(&path_str) {
+                continue;
+            }
+            let is_readme = entry.file_name().to_string_lossy().to_lowercase().starts_with("readme");
+            let in_doc_dir = path
+                .strip_prefix(root)
+                .map(|relative| {
+                    relative.components().rev().skip(1).any(|dir| {
+                        DOC_DIRS.contains(&dir.as_os_str().to_string_lossy().to_lowercase().as_str())
+                    })
+                })
+                .unwrap_or(false);
+            if !(entry.depth() == 1 || is_readme || in_doc_dir) {
+                continue;
+            }
+            if entry.metadata().map(|m| m.len() > max_file_size).unwrap_or(true) {
+                continue;
+            }
+            if let Ok(content) = fs::read_to_string(path) {
+                concepts.extend(self.extract_concepts(&path_str, &content));
+            }
+        }
+        concepts
+    }
+
+    /// Sections, code blocks and links of one Markdown file
+    pub fn extract_concepts(&self, file_path: &str, content: &str) -> Vec<SemanticConcept> {
+        let lines: Vec<&str> = content.lines().collect();
+        let blocks = Self::blocks(&lines);
+        let headings: Vec<(usize, u32, &str)> = blocks
+            .iter()
+            .filter_map(|block| match block {
+                Block::Heading { line, level, text } => Some((*line, *level, *text)),
+                _ => None,
+            })
+            .collect();
+        // The innermost heading above a line
+        let section_of = |line: usize| headings.iter().rev().find(|(start, _, _)| *start < line).map(|h| h.2);
+        // Inline code spans outside code blocks, by line
+        let mut spans: Vec<Vec<&str>> = vec![Vec::new(); lines.len() + 1];
+        for block in &blocks {
+            if let Block::Text { start, end } = block {
+                for line in *start..=*end {
+                    spans[line] = inline_code(lines[line - 1]);
+                }
+            }
+        }
+
+        let mut concepts = Vec::new();
+        for (position, &(line, level, text)) in headings.iter().enumerate() {
+            let end = headings[position + 1..]
+                .iter()
+                .find(|(_, next_level, _)| *next_level <= level)
+                .map_or(lines.len(), |(next, _, _)| next - 1)
+                .max(line);
+            let mut metadata = HashMap::from([("level".to_string(), level.to_string())]);
+            let parent = headings[..position].iter().rev().find(|(_, outer, _)| *outer < level);
+            if let Some((_, _, parent)) = parent {
+                metadata.insert("parent".to_string(), parent.to_string());
+            }
+            if let Some(summary) = Self::summary(&lines, &blocks, line, end) {
+                metadata.insert("summary".to_string(), summary);
+            }
+            let documented = spans[line..=end]
+                .iter()
+                .flatten()
+                .map(|span| span.strip_suffix("()").unwrap_or(span))
+                .filter(|span| is_identifier(span))
+                .map(str::to_string)
+                .collect();
+            let concept = doc_concept(file_path, text, "doc_section", line, end, metadata);
+            concepts.push(with_documents(concept, documented));
+        }
+
+        let document = Path::new(file_path).file_stem().map_or_else(String::new, |s| s.to_string_lossy().to_string());
+        for block in &blocks {
+            match block {
+                Block::Code { start, end, language, code } => {
+                    let section = section_of(*start);
+                    let mut metadata = HashMap::new();
+                    if !language.is_empty() {
+                        metadata.insert("language".to_string(), language.to_string());
+                    }
+                    if let Some(section) = section {
+                        metadata.insert("section".to_string(), section.to_string());
+                    }
+                    let name = format!("{} example", section.unwrap_or(&document));
+                    let concept = doc_concept(file_path, &name, "doc_code_block", *start, *end, metadata);
+                    concepts.push(with_documents(concept, code_identifiers(code)));
+                }
+                Block::Text { start, end } => {
+                    for (offset, line) in lines[start - 1..*end].iter().enumerate() {
+                        let line_number = start + offset;
+                        for link in patterns().link.captures_iter(line) {
+                            let target = &link[2];
+                            let Some(kind) = link_kind(target) else { continue };
+                            let mut metadata = HashMap::from([
+                                ("target".to_string(), target.to_string()),
+                                ("link_kind".to_string(), kind.to_string()),
+                            ]);
+                            if kind == "file" {
+                                metadata.insert("path".to_string(), resolve(file_path, target));
+                            }
+                            if let Some(section) = section_of(line_number) {
+                                metadata.insert("section".to_string(), section.to_string());
+                            }
+                            let text = link[1].trim();
+                            concepts.push(doc_concept(file_path, text, "doc_link", line_number, line_number, metadata));
+                        }
+                    }
+                }
+                Block::Heading { .. } => {}
+            }
+        }
+        concepts
+    }
+
+    /// Headings, fenced code and runs of other lines, in order; front matter
+    /// is skipped. Line numbers are 1-based.
+    fn blocks<'a>(lines: &[&'a str]) -> Vec<Block<'a>> {
+        let mut blocks = Vec::new();
+        let mut index = 0;
+        if lines.first().is_some_and(|line| line.trim() == "---") {
+            if let Some(close) = lines[1..].iter().position(|line| line.trim() == "---") {
+                index = close + 2;
+            }
+        }
+
+        let mut text_start: Option<usize> = None;
+        while index < lines.len() {
+            let line = lines[index];
+            let number = index + 1;
+            let trimmed = line.trim_start();
+            let fence = ["```", "~~~"].into_iter().find(|fence| trimmed.starts_with(fence));
+            let heading = patterns().heading.captures(line);
+            if fence.is_none() && heading.is_none() {
+                text_start.get_or_insert(number);
+                index += 1;
+                continue;
+            }
+            if let Some(start) = text_start.take() {
+                blocks.push(Block::Text { start, end: number - 1 });
+            }
+
+            if let Some(fence) = fence {
+                let marker_len = trimmed.chars().take_while(|c| fence.starts_with(*c)).count();
+                let marker = &trimmed[..marker_len];
+                let language = trimmed[marker_len..].split_whitespace().next().unwrap_or("");
+                let close = lines[index + 1..].iter().position(|line| {
+                    let line = line.trim();
+                    line.starts_with(marker) && line.chars().all(|c| fence.starts_with(c))
+                });
+                let end = close.map_or(lines.len(), |close| index + 1 + close);
+                let code = lines[index + 1..end.min(lines.len())].join("\n");
+                blocks.push(Block::Code { start: number, end: (end + 1).min(lines.len()), language, code });
+                index = end + 1;
+            } else if let Some(heading) = heading {
+                let text = heading.get(2).map_or("", |m| m.as_str()).trim_end_matches('#').trim();
+                blocks.push(Block::Heading { line: number, level: heading[1].len() as u32, text });
+                index += 1;
+            }
+        }
+        if let Some(start) = text_start {
+            blocks.push(Block::Text { start, end: lines.len() });
+        }
+        blocks
+    }
+
+    /// The first paragraph after a heading, when it comes before the end of
+    /// the heading's section
+    fn summary(lines: &[&str], blocks: &[Block<'_>], heading_line: usize, end: usize) -> Option<String> {
+        let next = blocks.iter().find(|block| block.start() > heading_line)?;
+        let Block::Text { start, end: text_end } = next else {
+            return None;
+        };
+        if *start > end {
+            return None;
+        }
+        let paragraph: Vec<&str> = lines[start - 1..*text_end]
+            .iter()
+            .map(|line| line.trim())
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| !line.is_empty())
+            .collect();
+        let summary = paragraph.join(" ");
+        if summary.is_empty() {
+            return None;
+        }
+        Some(match summary.char_indices().nth(MAX_SUMMARY_CHARS) {
+            Some((cut, _)) => format!("{}...", summary[..cut].trim_end()),
+            None => summary,
+        })
+    }
+}
+
+impl Default for MarkdownExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Part of a Markdown file
+enum Block<'a> {
+    Heading { line: usize, level: u32, text: &'a str },
+    /// Fenced code; `start` and `end` are the fence lines
+    Code { start: usize, end: usize, language: &'a str, code: String },
+    Text { start: usize, end: usize },
+}
+
+impl Block<'_> {
+    fn start(&self) -> usize {
+        match self {
+            Block::Heading { line, .. } => *line,
+            Block::Code { start, .. } | Block::Text { start, .. } => *start,
+        }
+    }
+}
+
+fn doc_concept(
+    file_path: &str,
+    name: &str,
+    concept_type: &str,
+    start: usize,
+    end: usize,
+    mut metadata: HashMap<String, String>,
+) -> SemanticConcept {
+    metadata.insert("source".to_string(), "documentation".to_string());
+    let line = start as u32;
+
+    let mut hasher = DefaultHasher::new();
+    (file_path, concept_type, name, line).hash(&mut hasher);
+    SemanticConcept {
+        id: format!("doc_{:016x}", hasher.finish()),
+        name: name.to_string(),
+        concept_type: concept_type.to_string(),
+        // Written by the team rather than inferred from code
+        confidence: 0.9,
+        file_path: file_path.to_string(),
+        line_range: LineRange { start: line, end: end as u32 },
+        relationships: HashMap::new(),
+        metadata,
+    }
+}
+
+/// Record the identifiers a section or code block mentions
+fn with_documents(mut concept: SemanticConcept, names: BTreeSet<String>) -> SemanticConcept {
+    if !names.is_empty() {
+        let names: Vec<String> = names.into_iter().collect();
+        concept.relationships.insert("documents".to_string(), names.join(", "));
+    }
+    concept
+}
+
+/// Contents of the `inline code` spans of a line
+fn inline_code(line: &str) -> Vec<&str> {
+    patterns().inline_code.captures_iter(line).filter_map(|span| span.get(1)).map(|m| m.as_str().trim()).collect()
+}
+
+/// Whether an inline code span is a bare identifier or a path such as
+/// `client.send` or `payments::Client`
+fn is_identifier(span: &str) -> bool {
+    !span.is_empty()
+        && !KEYWORDS.contains(&span)
+        && span.split([':', '.']).filter(|part| !part.is_empty()).all(|part| {
+            part.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+                && part.chars().all(|c| c.is_alphanumeric() || c == '_')
+        })
+}
+
+/// Names in a code block that look like the code's own identifiers rather
+/// than words: those with an underscore or a capital letter, and those
+/// called or reached through a `.` or `::`
+fn code_identifiers(code: &str) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    for found in patterns().identifier.find_iter(code) {
+        let name = found.as_str();
+        if name.len() < 3 || KEYWORDS.contains(&name) || name.chars().all(|c| c == '_') {
+            continue;
+        }
+        let before = &code[..found.start()];
+        let after = code[found.end()..].trim_start();
+        let shaped = name.contains('_') || name.chars().any(char::is_uppercase);
+        let called = after.starts_with('(');
+        let member = before.ends_with('.') || before.ends_with("::");
+        if shaped || called || member {
+            names.insert(name.to_string());
+        }
+    }
+    names
+}
+
+/// 'url' or 'file'; `None` for in-page anchors, which only navigate the page
+fn link_kind(target: &str) -> Option<&'static str> {
+    if target.starts_with('#') {
+        None
+    } else if target.contains("://") || target.starts_with("mailto:") || target.starts_with("//") {
+        Some("url")
+    } else {
+        Some("file")
+    }
+}
+
+/// A relative link's target as a path next to the linking file
+fn resolve(file_path: &str, target: &str) -> String {
+    let target = target.split('#').next().unwrap_or(target);
+    let dir = Path::new(file_path).parent().unwrap_or(Path::new(""));
+    let mut segments: Vec<String> = Vec::new();
+    for segment in paths::normalize_path(&dir.join(target).to_string_lossy()).split('/') {
+        if segment == ".." && segments.last().is_some_and(|last| last != "..") {
+            segments.pop();
+        } else {
+            segments.push(segment.to_string());
+        }
+    }
+    segments.join("/")
+}
+
+struct Patterns {
+    heading: Regex,
+    link: Regex,
+    inline_code: Regex,
+    identifier: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let re = |p: &str| Regex::new(p).expect("valid Markdown regex");
+        Patterns {
+            heading: re(r"^ {0,3}(#{1,6})(?:\s+(.*))?$"),
+            // `[text](target "title")`, but not `![alt](image.png)`
+            link: re(r#"(?:^|[^!])\[([^\]]+)\]\(\s*<?([^)\s>]+)>?(?:\s+"[^"]*")?\s*\)"#),
+            inline_code: re(r"`([^`]+)`"),
+            identifier: re(r"[A-Za-z_][A-Za-z0-9_]*"),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const README: &str = r#"---
+title: Payments
+---
+# Payments
+
+Card payments go through `PaymentClient`, which retries
+failed calls with `retry_with_backoff()`.
+
+## Usage
+
+```ts
+const client = new PaymentClient({ retries: 3 });
+await client.charge(order);
+```
+
+See the [architecture notes](../docs/architecture.md#clients) and the
+[provider API](https://stripe.com/docs/api). Jump to [setup](#setup).
+
+![diagram](diagram.png)
+
+## Setup
+
+```bash
+npm install
+```
+"#;
+
+    #[test]
+    fn test_sections_code_blocks_and_links() {
+        assert!(MarkdownExtractor::is_documentation("docs/guide.MD"));
+        assert!(!MarkdownExtractor::is_documentation("src/app.ts"));
+
+        let concepts = MarkdownExtractor::new().extract_concepts("packages/payments/README.md", README);
+        let summary: Vec<(&str, &str, u32, u32)> = concepts
+            .iter()
+            .map(|c| (c.concept_type.as_str(), c.name.as_str(), c.line_range.start, c.line_range.end))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("doc_section", "Payments", 4, 25),
+                ("doc_section", "Usage", 9, 20),
+                ("doc_section", "Setup", 21, 25),
+                ("doc_code_block", "Usage example", 11, 14),
+                ("doc_link", "architecture notes", 16, 16),
+                ("doc_link", "provider API", 17, 17),
+                ("doc_code_block", "Setup example", 23, 25),
+            ]
+        );
+
+        let payments = &concepts[0];
+        assert_eq!(
+            payments.metadata["summary"],
+            "Card payments go through `PaymentClient`, which retries failed calls with `retry_with_backoff()`."
+        );
+        assert_eq!(payments.relationships["documents"], "PaymentClient, retry_with_backoff");
+        assert_eq!(concepts[1].metadata["parent"], "Payments");
+
+        let usage = &concepts[3];
+        assert_eq!(usage.metadata["language"], "ts");
+        assert_eq!(usage.relationships["documents"], "PaymentClient, charge");
+        assert!(!concepts[6].relationships.contains_key("documents"));
+
+        let notes = &concepts[4];
+        assert_eq!(notes.metadata["link_kind"], "file");
+        assert_eq!(notes.metadata["path"], "packages/docs/architecture.md");
+        assert_eq!(notes.metadata["section"], "Usage");
+        assert_eq!(concepts[5].metadata["link_kind"], "url");
+    }
+
+    #[test]
+    fn test_extract_directory_picks_documentation_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        for (path, content) in [
+            ("CONTRIBUTING.md", "# Contributing\n"),
+            ("docs/guides/deploy.md", "# Deploy\n"),
+            ("src/api/README.md", "# API\n"),
+            ("src/api/notes.md", "# Scratch\n"),
+            ("node_modules/lib/README.md", "# Dependency\n"),
+        ] {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let concepts = MarkdownExtractor::new().extract_directory(root, u64::MAX);
+        let names: Vec<&str> = concepts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Contributing", "Deploy", "API"]);
+    }
+}
@@ -9,6 +9,10 @@ pub mod cpp;
 pub mod csharp;
 pub mod svelte;
 pub mod generic;
+pub mod config_files;
+pub mod hcl;
+pub mod notebook;
+pub mod markdown;
 
 pub use typescript::*;
 pub use rust::*;
@@ -21,3 +25,7 @@ pub use cpp::*;
 pub use csharp::*;
 pub use svelte::*;
 pub use generic::*;
+pub use config_files::*;
+pub use hcl::*;
+pub use notebook::*;
+pub use markdown::*;
@@ -0,0 +1,316 @@
+//! Jupyter notebooks
+//!
+//! A notebook is JSON holding a list of markdown and code cells. Its code
+//! cells are laid end to end, in cell order, to give every line of code a
+//! line number within the notebook; concepts extracted from a cell are moved
+//! to those lines and record the cell they came from (`cell`, 0-based) and
+//! their line within it (`cell_line`, 1-based).
+//!
+//! IPython line magics (`%matplotlib inline`) and shell escapes (`!pip
+//! install`) are blanked before a cell is parsed. Cells run by a cell magic
+//! in another language (`%%bash`, `%%sql`, ...) are not parsed at all.
+
+use crate::types::{LineRange, ParseError, SemanticConcept};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Cell magics whose body is still Python
+const PYTHON_CELL_MAGICS: &[&str] = &["time", "timeit", "capture", "prun", "debug"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotebookCell {
+    /// Position among all of the notebook's cells
+    pub index: u32,
+    /// 'code', 'markdown' or 'raw'
+    pub cell_type: String,
+    pub source: String,
+    /// Line of the cell's first line of code; 0 for cells without code
+    pub first_line: u32,
+}
+
+impl NotebookCell {
+    /// The cell's code as Python, with magics and shell escapes blanked so
+    /// line numbers don't shift. `None` for cells in another language.
+    pub fn python_source(&self) -> Option<String> {
+        let mut lines = Vec::new();
+        for (index, line) in self.source.lines().enumerate() {
+            let trimmed = line.trim_start();
+            if index == 0 {
+                if let Some(magic) = trimmed.strip_prefix("%%") {
+                    let name = magic.split_whitespace().next().unwrap_or("");
+                    if !PYTHON_CELL_MAGICS.contains(&name) {
+                        return None;
+                    }
+                    lines.push("");
+                    continue;
+                }
+            }
+            let is_magic = trimmed.starts_with('%') || trimmed.starts_with('!');
+            lines.push(if is_magic { "" } else { line });
+        }
+        Some(lines.join("\n"))
+    }
+
+    fn line_count(&self) -> u32 {
+        self.source.lines().count() as u32
+    }
+
+    /// The text of a markdown heading cell and its level
+    fn heading(&self) -> Option<(usize, &str)> {
+        if self.cell_type != "markdown" {
+            return None;
+        }
+        let line = self.source.lines().map(str::trim).find(|line| !line.is_empty())?;
+        let level = line.chars().take_while(|c| *c == '#').count();
+        let text = line[level..].trim();
+        (level > 0 && !text.is_empty()).then_some((level, text))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notebook {
+    /// Kernel language, e.g. 'python'; 'python' when the notebook doesn't say
+    pub language: String,
+    /// Kernel display name, e.g. 'Python 3 (ipykernel)'
+    pub kernel: Option<String>,
+    pub cells: Vec<NotebookCell>,
+}
+
+impl Notebook {
+    pub fn is_notebook(file_path: &str) -> bool {
+        Path::new(file_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("ipynb"))
+    }
+
+    /// Read nbformat 4 notebooks, and nbformat 3 notebooks with their cells in
+    /// worksheets
+    pub fn parse(content: &str) -> Result<Self, ParseError> {
+        let json: JsonValue = serde_json::from_str(content)
+            .map_err(|e| ParseError::from_reason(format!("Invalid notebook JSON: {}", e)))?;
+
+        let cells: Vec<&JsonValue> = match json.get("cells").and_then(JsonValue::as_array) {
+            Some(cells) => cells.iter().collect(),
+            None => json
+                .get("worksheets")
+                .and_then(JsonValue::as_array)
+                .ok_or_else(|| ParseError::from_reason("Notebook has no cells".to_string()))?
+                .iter()
+                .filter_map(|sheet| sheet.get("cells").and_then(JsonValue::as_array))
+                .flatten()
+                .collect(),
+        };
+
+        let metadata = json.get("metadata");
+        let language = metadata
+            .and_then(|m| m.pointer("/kernelspec/language").or_else(|| m.pointer("/language_info/name")))
+            .and_then(JsonValue::as_str)
+            .unwrap_or("python")
+            .to_lowercase();
+        let kernel = metadata
+            .and_then(|m| m.pointer("/kernelspec/display_name"))
+            .and_then(JsonValue::as_str)
+            .map(str::to_string);
+
+        let mut next_line = 1;
+        let cells = cells
+            .into_iter()
+            .enumerate()
+            .map(|(index, cell)| {
+                let cell_type = cell.get("cell_type").and_then(JsonValue::as_str).unwrap_or("code");
+                // nbformat 3 code cells keep their code under `input`
+                let source = cell.get("source").or_else(|| cell.get("input")).map(source_text).unwrap_or_default();
+                let mut cell = NotebookCell {
+                    index: index as u32,
+                    cell_type: cell_type.to_string(),
+                    source,
+                    first_line: 0,
+                };
+                if cell.cell_type == "code" {
+                    cell.first_line = next_line;
+                    next_line += cell.line_count();
+                }
+                cell
+            })
+            .collect();
+
+        Ok(Notebook { language, kernel, cells })
+    }
+
+    pub fn is_python(&self) -> bool {
+        self.language.starts_with("python")
+    }
+
+    pub fn code_cells(&self) -> impl Iterator<Item = &NotebookCell> {
+        self.cells.iter().filter(|cell| cell.cell_type == "code")
+    }
+
+    /// The first markdown heading
+    pub fn title(&self) -> Option<&str> {
+        self.cells.iter().find_map(|cell| cell.heading()).map(|(_, text)| text)
+    }
+
+    /// Move concepts extracted from one cell to the cell's notebook lines and
+    /// record the cell and the markdown section they're in
+    pub fn place(&self, cell: &NotebookCell, concepts: &mut [SemanticConcept]) {
+        let section = self.cells[..cell.index as usize]
+            .iter()
+            .rev()
+            .find_map(|cell| cell.heading())
+            .map(|(_, text)| text.to_string());
+        let offset = cell.first_line.saturating_sub(1);
+        for concept in concepts {
+            concept.metadata.insert("cell".to_string(), cell.index.to_string());
+            concept.metadata.insert("cell_line".to_string(), concept.line_range.start.to_string());
+            if let Some(section) = &section {
+                concept.metadata.insert("section".to_string(), section.clone());
+            }
+            concept.line_range.start += offset;
+            concept.line_range.end += offset;
+        }
+    }
+
+    /// A `notebook` concept for the whole file and a `notebook_section` for
+    /// each markdown heading, spanning the code up to the next heading of the
+    /// same or a higher level
+    pub fn concepts(&self, file_path: &str) -> Vec<SemanticConcept> {
+        let code_cells: Vec<&NotebookCell> = self.code_cells().collect();
+        let code_lines: u32 = code_cells.iter().map(|cell| cell.line_count()).sum();
+        let name = Path::new(file_path)
+            .file_stem()
+            .map_or_else(|| file_path.to_string(), |stem| stem.to_string_lossy().to_string());
+
+        let mut metadata = HashMap::from([
+            ("language".to_string(), self.language.clone()),
+            ("code_cells".to_string(), code_cells.len().to_string()),
+            (
+                "markdown_cells".to_string(),
+                self.cells.iter().filter(|cell| cell.cell_type == "markdown").count().to_string(),
+            ),
+        ]);
+        if let Some(kernel) = &self.kernel {
+            metadata.insert("kernel".to_string(), kernel.clone());
+        }
+        if let Some(title) = self.title() {
+            metadata.insert("title".to_string(), title.to_string());
+        }
+        let mut concepts = vec![notebook_concept(&name, "notebook", file_path, 1, code_lines.max(1), metadata)];
+
+        for (position, cell) in self.cells.iter().enumerate() {
+            let Some((level, text)) = cell.heading() else { continue };
+            let rest = &self.cells[position + 1..];
+            let end = rest
+                .iter()
+                .position(|next| next.heading().is_some_and(|(next_level, _)| next_level <= level))
+                .unwrap_or(rest.len());
+            let section_code: Vec<&NotebookCell> =
+                rest[..end].iter().filter(|cell| cell.cell_type == "code").collect();
+            let (Some(first), Some(last)) = (section_code.first(), section_code.last()) else {
+                continue;
+            };
+            let metadata = HashMap::from([
+                ("cell".to_string(), cell.index.to_string()),
+                ("level".to_string(), level.to_string()),
+            ]);
+            let end_line = (last.first_line + last.line_count()).saturating_sub(1).max(first.first_line);
+            let mut section = notebook_concept(text, "notebook_section", file_path, first.first_line, end_line, metadata);
+            section.relationships.insert("notebook".to_string(), name.clone());
+            concepts.push(section);
+        }
+        concepts
+    }
+}
+
+fn notebook_concept(
+    name: &str,
+    concept_type: &str,
+    file_path: &str,
+    start: u32,
+    end: u32,
+    mut metadata: HashMap<String, String>,
+) -> SemanticConcept {
+    metadata.insert("source".to_string(), "notebook".to_string());
+    SemanticConcept {
+        id: String::new(),
+        name: name.to_string(),
+        concept_type: concept_type.to_string(),
+        // Read from the notebook's structure rather than inferred from code
+        confidence: 0.9,
+        file_path: file_path.to_string(),
+        line_range: LineRange { start, end },
+        relationships: HashMap::new(),
+        metadata,
+    }
+}
+
+/// A cell source, stored either as one string or as a list of lines
+fn source_text(source: &JsonValue) -> String {
+    match source {
+        JsonValue::String(text) => text.clone(),
+        JsonValue::Array(lines) => lines.iter().filter_map(JsonValue::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTEBOOK: &str = r###"{
+ "cells": [
+  { "cell_type": "markdown", "metadata": {}, "source": ["# Churn model\n", "Predicts churn."] },
+  { "cell_type": "code", "execution_count": 1, "metadata": {}, "outputs": [],
+    "source": ["%matplotlib inline\n", "import pandas as pd\n", "!pip install sklearn"] },
+  { "cell_type": "markdown", "metadata": {}, "source": "## Features" },
+  { "cell_type": "code", "execution_count": 2, "metadata": {}, "outputs": [],
+    "source": ["def load(path):\n", "    return pd.read_csv(path)"] },
+  { "cell_type": "code", "execution_count": 3, "metadata": {}, "outputs": [],
+    "source": ["%%bash\n", "ls data"] }
+ ],
+ "metadata": {
+  "kernelspec": { "display_name": "Python 3 (ipykernel)", "language": "python", "name": "python3" }
+ },
+ "nbformat": 4,
+ "nbformat_minor": 5
+}"###;
+
+    #[test]
+    fn test_parses_cells_and_maps_lines() {
+        let notebook = Notebook::parse(NOTEBOOK).unwrap();
+        assert!(Notebook::is_notebook("analysis/Churn.ipynb"));
+        assert!(notebook.is_python());
+        assert_eq!(notebook.title(), Some("Churn model"));
+
+        let code: Vec<(u32, u32)> = notebook.code_cells().map(|cell| (cell.index, cell.first_line)).collect();
+        assert_eq!(code, vec![(1, 1), (3, 4), (4, 6)]);
+        assert_eq!(notebook.cells[1].python_source().unwrap(), "\nimport pandas as pd\n");
+        assert_eq!(notebook.cells[4].python_source(), None);
+
+        // A function on the first line of the second code cell
+        let mut concepts = vec![notebook_concept("load", "function", "Churn.ipynb", 1, 2, HashMap::new())];
+        notebook.place(&notebook.cells[3], &mut concepts);
+        assert_eq!((concepts[0].line_range.start, concepts[0].line_range.end), (4, 5));
+        assert_eq!(concepts[0].metadata["cell"], "3");
+        assert_eq!(concepts[0].metadata["cell_line"], "1");
+        assert_eq!(concepts[0].metadata["section"], "Features");
+
+        let concepts = notebook.concepts("analysis/Churn.ipynb");
+        let summary: Vec<(&str, &str, u32, u32)> = concepts
+            .iter()
+            .map(|c| (c.concept_type.as_str(), c.name.as_str(), c.line_range.start, c.line_range.end))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("notebook", "Churn", 1, 7),
+                ("notebook_section", "Churn model", 1, 7),
+                ("notebook_section", "Features", 4, 7),
+            ]
+        );
+        assert_eq!(concepts[0].metadata["kernel"], "Python 3 (ipykernel)");
+        assert_eq!(concepts[0].metadata["code_cells"], "3");
+        assert!(Notebook::parse("{ not json").is_err());
+    }
+}
@@ -81,8 +81,9 @@ impl SqlExtractor {
                 }
             }
 
-            // Standalone column definitions
-            "column_definition" => {
+            // Standalone column definitions (ALTER TABLE ... ADD COLUMN); columns
+            // of a CREATE TABLE are already extracted with their table
+            "column_definition" if node.parent().is_none_or(|p| p.kind() != "column_definitions") => {
                 if let Some(concept) = self.extract_column(node, file_path, content)? {
                     concepts.push(concept);
                 }
@@ -169,36 +170,28 @@ impl SqlExtractor {
 
     /// Extract column definition with type and constraints
     fn extract_column(&self, node: Node<'_>, file_path: &str, content: &str) -> Result<Option<SemanticConcept>, ParseError> {
-        let mut column_name = String::new();
-        let mut _column_type = String::new();
         let mut metadata = HashMap::new();
         let mut relationships = HashMap::new();
 
-        let mut cursor = node.walk();
-        for child in node.children(&mut cursor) {
-            match child.kind() {
-                "_column" => {
-                    column_name = self.extract_identifier(child, content);
-                }
-                "_type" => {
-                    _column_type = self.extract_type_info(child, content);
-                    metadata.insert("data_type".to_string(), _column_type.clone());
-                }
-                "_column_constraint" => {
-                    let constraint_info = self.extract_constraint_info(child, content);
-                    for (key, value) in constraint_info {
-                        metadata.insert(key, value);
-                    }
-                }
-                _ => {}
-            }
+        // `_column` and `_type` are hidden rules, so they are reached through
+        // the `name` and `type` fields rather than by node kind
+        let column_name = node
+            .child_by_field_name("name")
+            .map(|name| self.extract_identifier(name, content))
+            .unwrap_or_default();
+        if let Some(type_node) = node.child_by_field_name("type") {
+            metadata.insert("data_type".to_string(), self.extract_type_info(type_node, content));
         }
+        metadata.extend(self.extract_constraint_info(node, content));
 
         if column_name.is_empty() {
             return Ok(None);
         }
 
         relationships.insert("sql_object_type".to_string(), "column".to_string());
+        if let Some(target) = metadata.get("references") {
+            relationships.insert("references".to_string(), target.clone());
+        }
 
         Ok(Some(SemanticConcept {
             id: format!("sql_column_{}_{}", file_path, column_name),
@@ -323,10 +316,8 @@ impl SqlExtractor {
                 "_if_not_exists" => {
                     metadata.insert("if_not_exists".to_string(), "true".to_string());
                 }
-                "_column" => {
-                    if index_name.is_empty() {
-                        index_name = self.extract_identifier(child, content);
-                    }
+                "_column" if index_name.is_empty() => {
+                    index_name = self.extract_identifier(child, content);
                 }
                 "object_reference" => {
                     let table_name = self.extract_identifier(child, content);
@@ -590,17 +581,22 @@ impl SqlExtractor {
         self.get_node_text(node, content)
     }
 
-    /// Extract constraint information from column constraint
-    fn extract_constraint_info(&self, node: Node<'_>, _content: &str) -> HashMap<String, String> {
+    /// Extract constraint information from a column definition
+    ///
+    /// Column constraints are hidden rules, so their keywords appear as direct
+    /// children of the column definition.
+    fn extract_constraint_info(&self, node: Node<'_>, content: &str) -> HashMap<String, String> {
         let mut constraints = HashMap::new();
-        
-        Self::walk_node_recursively(node, &mut |child| {
+        let mut previous = "";
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
             match child.kind() {
+                // `SET NULL` belongs to an ON DELETE/UPDATE action
+                "keyword_null" if previous == "keyword_set" => {}
                 "keyword_null" => {
-                    constraints.insert("nullable".to_string(), "true".to_string());
-                }
-                "_not_null" => {
-                    constraints.insert("nullable".to_string(), "false".to_string());
+                    let nullable = if previous == "keyword_not" { "false" } else { "true" };
+                    constraints.insert("nullable".to_string(), nullable.to_string());
                 }
                 "keyword_primary" => {
                     constraints.insert("primary_key".to_string(), "true".to_string());
@@ -611,12 +607,16 @@ impl SqlExtractor {
                 "keyword_references" => {
                     constraints.insert("foreign_key".to_string(), "true".to_string());
                 }
+                "object_reference" if previous == "keyword_references" => {
+                    constraints.insert("references".to_string(), self.extract_identifier(child, content));
+                }
                 "keyword_default" => {
                     constraints.insert("has_default".to_string(), "true".to_string());
                 }
                 _ => {}
             }
-        });
+            previous = child.kind();
+        }
 
         constraints
     }
@@ -916,6 +916,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_column_types_and_references() {
+        let extractor = SqlExtractor::new();
+        let sql = r#"
+            CREATE TABLE orders (
+                id INTEGER PRIMARY KEY,
+                note TEXT NULL,
+                customer_id INTEGER NOT NULL REFERENCES customers(id) ON DELETE SET NULL
+            );
+            ALTER TABLE orders ADD COLUMN total NUMERIC;
+        "#;
+
+        let tree = create_sql_tree(sql);
+        let concepts = extract_all_concepts(&extractor, &tree, "orders.sql", sql);
+        let column = |name: &str| concepts.iter().find(|c| c.concept_type == "column" && c.name == name).unwrap();
+
+        let customer_id = column("customer_id");
+        assert_eq!(customer_id.metadata["data_type"], "INTEGER");
+        assert_eq!(customer_id.metadata["nullable"], "false");
+        assert_eq!(customer_id.metadata["references"], "customers");
+        assert_eq!(customer_id.relationships["references"], "customers");
+        assert_eq!(column("note").metadata["nullable"], "true");
+
+        // Table columns are extracted once, with their table
+        assert_eq!(concepts.iter().filter(|c| c.name == "id").count(), 1);
+        assert!(!column("total").relationships.contains_key("parent_table"));
+    }
+
     #[test]
     fn test_line_number_accuracy() {
         let extractor = SqlExtractor::new();
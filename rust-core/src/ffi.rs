@@ -0,0 +1,358 @@
+//! Stable C ABI for embedding the engine outside of Node.js
+//!
+//! Every call takes and returns JSON so the surface stays small and stable
+//! across releases. Results are wrapped in an envelope:
+//! `{"ok": true, "data": ...}` on success, `{"ok": false, "error": "..."}` on failure.
+//! Strings returned by this module must be released with [`in_memoria_string_free`].
+
+use crate::analysis::SemanticAnalyzer;
+use crate::patterns::PatternLearner;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Opaque engine handle owning the analyzer, the pattern learner and the async runtime
+pub struct InMemoriaEngine {
+    analyzer: SemanticAnalyzer,
+    learner: PatternLearner,
+    runtime: tokio::runtime::Runtime,
+}
+
+#[derive(Deserialize)]
+struct AnalyzeFileRequest {
+    file_path: String,
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct PredictRequest {
+    problem_description: String,
+    #[serde(default)]
+    context: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct RelevantPatternsRequest {
+    problem_description: String,
+    #[serde(default)]
+    current_file: Option<String>,
+    #[serde(default)]
+    selected_code: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Envelope<T: Serialize> {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn into_c_string(json: String) -> *mut c_char {
+    // serde_json never emits interior NUL bytes, so this only fails on a logic error
+    CString::new(json)
+        .map(CString::into_raw)
+        .unwrap_or(std::ptr::null_mut())
+}
+
+fn ok_response<T: Serialize>(data: T) -> *mut c_char {
+    let envelope = Envelope { ok: true, data: Some(data), error: None };
+    match serde_json::to_string(&envelope) {
+        Ok(json) => into_c_string(json),
+        Err(e) => error_response(format!("Failed to serialize result: {}", e)),
+    }
+}
+
+fn error_response(message: String) -> *mut c_char {
+    let envelope: Envelope<()> = Envelope { ok: false, data: None, error: Some(message) };
+    into_c_string(serde_json::to_string(&envelope).unwrap_or_else(|_| {
+        r#"{"ok":false,"error":"unknown error"}"#.to_string()
+    }))
+}
+
+/// Read a borrowed C string into an owned Rust string
+///
+/// # Safety
+/// `ptr` must be null or point to a valid NUL-terminated string.
+unsafe fn read_c_str(ptr: *const c_char, name: &str) -> Result<String, String> {
+    if ptr.is_null() {
+        return Err(format!("{} must not be null", name));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map(|s| s.to_string())
+        .map_err(|_| format!("{} is not valid UTF-8", name))
+}
+
+/// Run `f` against the engine, converting errors and panics into an error envelope
+///
+/// # Safety
+/// `engine` must be null or a pointer obtained from [`in_memoria_engine_new`].
+unsafe fn with_engine<F>(engine: *mut InMemoriaEngine, f: F) -> *mut c_char
+where
+    F: FnOnce(&mut InMemoriaEngine) -> Result<*mut c_char, String>,
+{
+    let Some(engine) = engine.as_mut() else {
+        return error_response("engine must not be null".to_string());
+    };
+
+    match catch_unwind(AssertUnwindSafe(|| f(engine))) {
+        Ok(Ok(response)) => response,
+        Ok(Err(message)) => error_response(message),
+        Err(_) => error_response("internal panic in in-memoria core".to_string()),
+    }
+}
+
+/// Create a new engine. Returns null if the parsers or runtime fail to initialize.
+#[no_mangle]
+pub extern "C" fn in_memoria_engine_new() -> *mut InMemoriaEngine {
+    let engine = catch_unwind(|| {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .ok()?;
+        let analyzer = SemanticAnalyzer::new().ok()?;
+        Some(InMemoriaEngine {
+            analyzer,
+            learner: PatternLearner::new(),
+            runtime,
+        })
+    });
+
+    match engine {
+        Ok(Some(engine)) => Box::into_raw(Box::new(engine)),
+        _ => std::ptr::null_mut(),
+    }
+}
+
+/// Destroy an engine created by [`in_memoria_engine_new`]
+///
+/// # Safety
+/// `engine` must be null or a pointer obtained from [`in_memoria_engine_new`]
+/// that has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn in_memoria_engine_free(engine: *mut InMemoriaEngine) {
+    if !engine.is_null() {
+        drop(Box::from_raw(engine));
+    }
+}
+
+/// Release a string returned by any `in_memoria_*` function
+///
+/// # Safety
+/// `s` must be null or a pointer returned by this library that has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn in_memoria_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Analyze a codebase directory. `data` is a `CodebaseAnalysisResult`.
+///
+/// # Safety
+/// `engine` must come from [`in_memoria_engine_new`]; `path` must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn in_memoria_analyze_codebase(
+    engine: *mut InMemoriaEngine,
+    path: *const c_char,
+) -> *mut c_char {
+    with_engine(engine, |engine| {
+        let path = read_c_str(path, "path")?;
+        let result = engine
+            .runtime
+            .block_on(unsafe { engine.analyzer.analyze_codebase(path) })
+            .map_err(|e| e.to_string())?;
+        Ok(ok_response(result))
+    })
+}
+
+/// Analyze a single file. `request_json` is `{"file_path": ..., "content": ...}`;
+/// `data` is an array of `SemanticConcept`.
+///
+/// # Safety
+/// `engine` must come from [`in_memoria_engine_new`]; `request_json` must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn in_memoria_analyze_file(
+    engine: *mut InMemoriaEngine,
+    request_json: *const c_char,
+) -> *mut c_char {
+    with_engine(engine, |engine| {
+        let request: AnalyzeFileRequest = serde_json::from_str(&read_c_str(request_json, "request_json")?)
+            .map_err(|e| format!("Invalid request: {}", e))?;
+        let concepts = engine
+            .runtime
+            .block_on(unsafe { engine.analyzer.analyze_file_content(request.file_path, request.content) })
+            .map_err(|e| e.to_string())?;
+        Ok(ok_response(concepts))
+    })
+}
+
+/// Learn semantic concepts from a codebase. `data` is an array of `SemanticConcept`.
+///
+/// # Safety
+/// `engine` must come from [`in_memoria_engine_new`]; `path` must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn in_memoria_learn_concepts(
+    engine: *mut InMemoriaEngine,
+    path: *const c_char,
+) -> *mut c_char {
+    with_engine(engine, |engine| {
+        let path = read_c_str(path, "path")?;
+        let concepts = engine
+            .runtime
+            .block_on(unsafe { engine.analyzer.learn_from_codebase(path) })
+            .map_err(|e| e.to_string())?;
+        Ok(ok_response(concepts))
+    })
+}
+
+/// Learn coding patterns from a codebase. `data` is an array of `Pattern`.
+///
+/// # Safety
+/// `engine` must come from [`in_memoria_engine_new`]; `path` must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn in_memoria_learn_patterns(
+    engine: *mut InMemoriaEngine,
+    path: *const c_char,
+) -> *mut c_char {
+    with_engine(engine, |engine| {
+        let path = read_c_str(path, "path")?;
+        let patterns = engine
+            .runtime
+            .block_on(unsafe { engine.learner.learn_from_codebase(path) })
+            .map_err(|e| e.to_string())?;
+        Ok(ok_response(patterns))
+    })
+}
+
+/// Predict an approach. `request_json` is `{"problem_description": ..., "context": {...}}`;
+/// `data` is an `ApproachPrediction`.
+///
+/// # Safety
+/// `engine` must come from [`in_memoria_engine_new`]; `request_json` must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn in_memoria_predict_approach(
+    engine: *mut InMemoriaEngine,
+    request_json: *const c_char,
+) -> *mut c_char {
+    with_engine(engine, |engine| {
+        let request: PredictRequest = serde_json::from_str(&read_c_str(request_json, "request_json")?)
+            .map_err(|e| format!("Invalid request: {}", e))?;
+        let prediction = engine
+            .runtime
+            .block_on(engine.learner.predict_approach(request.problem_description, request.context))
+            .map_err(|e| e.to_string())?;
+        Ok(ok_response(prediction))
+    })
+}
+
+/// Find learned patterns relevant to a problem. `request_json` is
+/// `{"problem_description": ..., "current_file": ..., "selected_code": ...}`;
+/// `data` is an array of `Pattern`.
+///
+/// # Safety
+/// `engine` must come from [`in_memoria_engine_new`]; `request_json` must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn in_memoria_find_relevant_patterns(
+    engine: *mut InMemoriaEngine,
+    request_json: *const c_char,
+) -> *mut c_char {
+    with_engine(engine, |engine| {
+        let request: RelevantPatternsRequest =
+            serde_json::from_str(&read_c_str(request_json, "request_json")?)
+                .map_err(|e| format!("Invalid request: {}", e))?;
+        let patterns = engine
+            .runtime
+            .block_on(engine.learner.find_relevant_patterns(
+                request.problem_description,
+                request.current_file,
+                request.selected_code,
+            ))
+            .map_err(|e| e.to_string())?;
+        Ok(ok_response(patterns))
+    })
+}
+
+/// List every pattern learned so far. `data` is an array of `Pattern`.
+///
+/// # Safety
+/// `engine` must come from [`in_memoria_engine_new`].
+#[no_mangle]
+pub unsafe extern "C" fn in_memoria_get_learned_patterns(engine: *mut InMemoriaEngine) -> *mut c_char {
+    with_engine(engine, |engine| Ok(ok_response(engine.learner.get_learned_patterns())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn take_json(ptr: *mut c_char) -> serde_json::Value {
+        assert!(!ptr.is_null());
+        let json = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string();
+        unsafe { in_memoria_string_free(ptr) };
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_engine_lifecycle() {
+        let engine = in_memoria_engine_new();
+        assert!(!engine.is_null());
+        unsafe { in_memoria_engine_free(engine) };
+    }
+
+    #[test]
+    fn test_null_engine_returns_error_envelope() {
+        let path = CString::new(".").unwrap();
+        let response = take_json(unsafe { in_memoria_analyze_codebase(std::ptr::null_mut(), path.as_ptr()) });
+        assert_eq!(response["ok"], false);
+        assert!(response["error"].as_str().unwrap().contains("engine"));
+    }
+
+    #[test]
+    fn test_analyze_file_returns_concepts() {
+        let engine = in_memoria_engine_new();
+        let request = CString::new(
+            r#"{"file_path": "test.ts", "content": "function greet() { return 1; }"}"#,
+        )
+        .unwrap();
+
+        let response = take_json(unsafe { in_memoria_analyze_file(engine, request.as_ptr()) });
+        assert_eq!(response["ok"], true);
+        assert!(response["data"].is_array());
+
+        unsafe { in_memoria_engine_free(engine) };
+    }
+
+    #[test]
+    fn test_invalid_request_json() {
+        let engine = in_memoria_engine_new();
+        let request = CString::new("not json").unwrap();
+
+        let response = take_json(unsafe { in_memoria_predict_approach(engine, request.as_ptr()) });
+        assert_eq!(response["ok"], false);
+        assert!(response["error"].as_str().unwrap().starts_with("Invalid request"));
+
+        unsafe { in_memoria_engine_free(engine) };
+    }
+
+    #[test]
+    fn test_predict_and_query_patterns() {
+        let engine = in_memoria_engine_new();
+        let request = CString::new(r#"{"problem_description": "add caching layer"}"#).unwrap();
+
+        let prediction = take_json(unsafe { in_memoria_predict_approach(engine, request.as_ptr()) });
+        assert_eq!(prediction["ok"], true);
+        assert!(prediction["data"]["approach"].is_string());
+
+        let patterns = take_json(unsafe { in_memoria_get_learned_patterns(engine) });
+        assert_eq!(patterns["ok"], true);
+        assert!(patterns["data"].as_array().unwrap().is_empty());
+
+        unsafe { in_memoria_engine_free(engine) };
+    }
+}
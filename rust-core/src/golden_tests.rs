@@ -0,0 +1,129 @@
+//! End-to-end snapshot tests over the sample projects in `fixtures/`
+//!
+//! Concept ids are time-based and HashMap iteration order is unstable, so each
+//! snapshot renders a sorted, id-free projection of the output.
+
+use crate::analysis::{BlueprintAnalyzer, FrameworkInfo, SemanticAnalyzer};
+use crate::patterns::NamingPatternAnalyzer;
+use crate::types::SemanticConcept;
+use std::path::{Path, PathBuf};
+
+fn fixture_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures").join("polyglot")
+}
+
+/// A copy of the polyglot fixture in a temporary directory, for tests that
+/// learn from it and so save state under the project root
+pub(crate) fn polyglot_copy() -> tempfile::TempDir {
+    fn copy_dir(from: &Path, to: &Path) {
+        std::fs::create_dir_all(to).unwrap();
+        for entry in std::fs::read_dir(from).unwrap() {
+            let entry = entry.unwrap();
+            let target = to.join(entry.file_name());
+            if entry.file_type().unwrap().is_dir() {
+                copy_dir(&entry.path(), &target);
+            } else {
+                std::fs::copy(entry.path(), target).unwrap();
+            }
+        }
+    }
+
+    let dir = tempfile::TempDir::new().unwrap();
+    copy_dir(&fixture_root(), dir.path());
+    dir
+}
+
+fn extract_fixture(relative_path: &str) -> Vec<SemanticConcept> {
+    let content = std::fs::read_to_string(fixture_root().join(relative_path))
+        .expect("fixture file should exist");
+    let mut analyzer = SemanticAnalyzer::new().unwrap();
+    analyzer.extract_file_concepts_sync(relative_path, &content)
+}
+
+fn render_concepts(concepts: &[SemanticConcept]) -> String {
+    let mut lines: Vec<String> = concepts
+        .iter()
+        .map(|c| format!("{} {} L{}-{}", c.concept_type, c.name, c.line_range.start, c.line_range.end))
+        .collect();
+    lines.sort();
+    lines.join("\n")
+}
+
+fn framework(name: &str) -> FrameworkInfo {
+    FrameworkInfo {
+        name: name.to_string(),
+        version: None,
+        confidence: 1.0,
+        evidence: vec![],
+    }
+}
+
+#[test]
+fn snapshot_typescript_concepts() {
+    let concepts = extract_fixture("src/index.ts");
+    insta::assert_snapshot!("typescript_concepts", render_concepts(&concepts));
+}
+
+#[test]
+fn snapshot_python_concepts() {
+    let concepts = extract_fixture("src/services/user_repository.py");
+    insta::assert_snapshot!("python_concepts", render_concepts(&concepts));
+}
+
+#[test]
+fn snapshot_rust_concepts() {
+    let concepts = extract_fixture("src/lib.rs");
+    insta::assert_snapshot!("rust_concepts", render_concepts(&concepts));
+}
+
+#[test]
+fn snapshot_typescript_naming_patterns() {
+    let concepts = extract_fixture("src/index.ts");
+    let mut analyzer = NamingPatternAnalyzer::new();
+    let patterns = analyzer.analyze_concepts(&concepts, "typescript").unwrap();
+
+    let mut lines: Vec<String> = patterns
+        .iter()
+        .map(|p| format!("{} ({:.2}): {}", p.id, p.confidence, p.description))
+        .collect();
+    lines.sort();
+    insta::assert_snapshot!("typescript_naming_patterns", lines.join("\n"));
+}
+
+#[tokio::test]
+async fn snapshot_blueprint() {
+    let root = fixture_root().to_string_lossy().to_string();
+
+    let entry_points = BlueprintAnalyzer::detect_entry_points(
+        root.clone(),
+        vec![framework("express"), framework("rust")],
+    )
+    .await
+    .unwrap();
+    let key_dirs = BlueprintAnalyzer::map_key_directories(root.clone()).await.unwrap();
+    let features = BlueprintAnalyzer::build_feature_map(root).await.unwrap();
+
+    let mut out = vec!["entry_points:".to_string()];
+    for entry in &entry_points {
+        out.push(format!(
+            "  {} {} framework={} confidence={:.2}",
+            entry.entry_type,
+            entry.file_path,
+            entry.framework.as_deref().unwrap_or("-"),
+            entry.confidence
+        ));
+    }
+    out.push("key_directories:".to_string());
+    for dir in &key_dirs {
+        out.push(format!("  {} {} files={}", dir.path, dir.dir_type, dir.file_count));
+    }
+    out.push("features:".to_string());
+    for feature in &features {
+        out.push(format!(
+            "  {} primary={:?} related={:?}",
+            feature.feature_name, feature.primary_files, feature.related_files
+        ));
+    }
+
+    insta::assert_snapshot!("blueprint", out.join("\n"));
+}
@@ -0,0 +1,513 @@
+//! Insights contributed by agents, linked to concepts and patterns
+//!
+//! An agent that learned something about the codebase (a recurring bug, an
+//! optimization, a refactoring worth doing, a practice to keep) reports it as
+//! an insight document. [`InsightValidator`] checks the document field by
+//! field and names every missing or invalid field in one error, rather than
+//! failing on the first. Accepted insights are linked to the stored concepts
+//! and learned patterns they mention or whose files they touch, and saved to
+//! `.in-memoria/insights.json`. Concepts are linked by name and
+//! project-relative file, like tags, because concept ids change between runs.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::patterns::types::Pattern;
+use crate::types::validation::{self, ErrorCode, ValidationError};
+use crate::types::{paths, ParseError, SemanticConcept, CONFIG_DIR};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// File holding contributed insights, inside the project's config directory
+pub const INSIGHTS_FILE: &str = "insights.json";
+
+const INSIGHTS_VERSION: u32 = 1;
+
+/// Kinds of insight an agent can contribute
+pub const INSIGHT_TYPES: &[&str] = &["bug_pattern", "optimization", "refactor_suggestion", "best_practice"];
+
+/// Top-level fields of an insight document; `source_agent` and
+/// `session_update` are accepted for `sourceAgent` and `sessionUpdate`
+const INSIGHT_FIELDS: &[&str] = &["type", "content", "confidence", "sourceAgent", "sessionUpdate", "impactPrediction"];
+
+/// What an insight says, with the code elements it is about
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default, rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct InsightContent {
+    pub description: String,
+    pub reasoning: Option<String>,
+    pub code: Option<String>,
+    /// Concept names or ids the insight is about
+    pub concepts: Vec<String>,
+    /// Pattern ids the insight is about
+    pub patterns: Vec<String>,
+    pub files: Vec<String>,
+}
+
+/// Work the agent did in the session that produced the insight
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default, rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct SessionUpdate {
+    pub feature: Option<String>,
+    pub tasks: Vec<String>,
+    pub files: Vec<String>,
+    pub decisions: Vec<String>,
+}
+
+/// A stored concept an insight is linked to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ConceptLink {
+    pub name: String,
+    /// Relative to the project root
+    pub file_path: String,
+}
+
+/// An accepted insight and what it is linked to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct Insight {
+    pub id: String,
+    /// One of [`INSIGHT_TYPES`]
+    pub insight_type: String,
+    pub content: InsightContent,
+    pub confidence: f64,
+    pub source_agent: String,
+    #[serde(default)]
+    pub session_update: Option<SessionUpdate>,
+    /// The document's `impactPrediction`, kept as JSON
+    #[serde(default)]
+    pub impact_prediction: Option<String>,
+    pub related_concepts: Vec<ConceptLink>,
+    pub related_patterns: Vec<String>,
+    /// RFC 3339 time the insight was accepted
+    #[serde(default)]
+    pub recorded_at: Option<String>,
+}
+
+/// A validated insight document, before it is linked and stored
+#[derive(Debug, Clone, PartialEq)]
+pub struct InsightInput {
+    pub insight_type: String,
+    pub content: InsightContent,
+    pub confidence: f64,
+    pub source_agent: String,
+    pub session_update: Option<SessionUpdate>,
+    pub impact_prediction: Option<String>,
+}
+
+/// The insight document [`InsightValidator::parse`] accepts, in its
+/// canonical spelling (the snake_case aliases are left out)
+impl JsonSchema for InsightInput {
+    fn schema_name() -> Cow<'static, str> {
+        "InsightDocument".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let content = generator.subschema_for::<InsightContent>();
+        let session_update = generator.subschema_for::<SessionUpdate>();
+        json_schema!({
+            "type": "object",
+            "properties": {
+                "type": {"type": "string", "enum": INSIGHT_TYPES},
+                "content": {"anyOf": [{"type": "string", "minLength": 1}, content]},
+                "confidence": {"type": "number", "minimum": 0.0, "maximum": 1.0},
+                "sourceAgent": {"type": "string", "minLength": 1},
+                "sessionUpdate": {"anyOf": [session_update, {"type": "null"}]},
+                "impactPrediction": {"type": ["object", "null"]},
+            },
+            "required": ["type", "content", "confidence", "sourceAgent"],
+            "additionalProperties": false,
+        })
+    }
+}
+
+pub struct InsightValidator;
+
+impl InsightValidator {
+    /// Parse an insight document, reporting every missing or invalid field
+    pub fn parse(input: &str) -> Result<InsightInput, ValidationError> {
+        let value: Value = validation::parse_json(input, "insight")?;
+        let Value::Object(document) = value else {
+            return Err(ValidationError::new(ErrorCode::SchemaMismatch, "Invalid insight: expected a JSON object"));
+        };
+
+        let mut problems = Vec::new();
+        for key in document.keys() {
+            if !INSIGHT_FIELDS.contains(&key.as_str()) && key != "source_agent" && key != "session_update" {
+                problems.push(format!("unknown field '{}'", key));
+            }
+        }
+
+        let insight_type = match document.get("type") {
+            None | Some(Value::Null) => {
+                problems.push("type is required".to_string());
+                String::new()
+            }
+            Some(Value::String(kind)) if INSIGHT_TYPES.contains(&kind.as_str()) => kind.clone(),
+            Some(other) => {
+                problems.push(format!("type must be one of {}, got {}", INSIGHT_TYPES.join(", "), other));
+                String::new()
+            }
+        };
+        let content = Self::content(document.get("content"), &mut problems);
+        let confidence = match document.get("confidence") {
+            None | Some(Value::Null) => {
+                problems.push("confidence is required".to_string());
+                0.0
+            }
+            Some(Value::Number(number)) if number.as_f64().is_some_and(|c| (0.0..=1.0).contains(&c)) => {
+                number.as_f64().unwrap_or_default()
+            }
+            Some(other) => {
+                problems.push(format!("confidence must be a number between 0 and 1, got {}", other));
+                0.0
+            }
+        };
+        let source_agent = match document.get("sourceAgent").or_else(|| document.get("source_agent")) {
+            Some(Value::String(agent)) if !agent.trim().is_empty() => agent.trim().to_string(),
+            None | Some(Value::Null) => {
+                problems.push("sourceAgent is required".to_string());
+                String::new()
+            }
+            Some(other) => {
+                problems.push(format!("sourceAgent must be a non-empty string, got {}", other));
+                String::new()
+            }
+        };
+        let session_update = match document.get("sessionUpdate").or_else(|| document.get("session_update")) {
+            None | Some(Value::Null) => None,
+            Some(Value::Object(update)) => Some(Self::session_update(update, &mut problems)),
+            Some(other) => {
+                problems.push(format!("sessionUpdate must be an object, got {}", other));
+                None
+            }
+        };
+        let impact_prediction = match document.get("impactPrediction") {
+            None | Some(Value::Null) => None,
+            Some(impact @ Value::Object(_)) => Some(impact.to_string()),
+            Some(other) => {
+                problems.push(format!("impactPrediction must be an object, got {}", other));
+                None
+            }
+        };
+
+        if !problems.is_empty() {
+            return Err(ValidationError::new(
+                ErrorCode::SchemaMismatch,
+                format!("Invalid insight: {}", problems.join("; ")),
+            ));
+        }
+        Ok(InsightInput {
+            insight_type,
+            content,
+            confidence,
+            source_agent,
+            session_update,
+            impact_prediction,
+        })
+    }
+
+    /// `content` is a description, or an object with a description and the
+    /// code elements it is about
+    fn content(value: Option<&Value>, problems: &mut Vec<String>) -> InsightContent {
+        let content = match value {
+            None | Some(Value::Null) => {
+                problems.push("content is required".to_string());
+                return InsightContent::default();
+            }
+            Some(Value::String(text)) => {
+                if text.trim().is_empty() {
+                    problems.push("content must not be empty".to_string());
+                }
+                return InsightContent { description: text.trim().to_string(), ..Default::default() };
+            }
+            Some(Value::Object(content)) => content,
+            Some(other) => {
+                problems.push(format!("content must be a string or an object, got {}", other));
+                return InsightContent::default();
+            }
+        };
+
+        let description = match content.get("description") {
+            Some(Value::String(text)) if !text.trim().is_empty() => text.trim().to_string(),
+            None | Some(Value::Null) => {
+                problems.push("content.description is required".to_string());
+                String::new()
+            }
+            Some(other) => {
+                problems.push(format!("content.description must be a non-empty string, got {}", other));
+                String::new()
+            }
+        };
+        InsightContent {
+            description,
+            reasoning: Self::optional_string(content, "content", "reasoning", problems),
+            code: Self::optional_string(content, "content", "code", problems),
+            concepts: Self::strings(content, "content", "concepts", problems),
+            patterns: Self::strings(content, "content", "patterns", problems),
+            files: Self::strings(content, "content", "files", problems),
+        }
+    }
+
+    fn session_update(update: &Map<String, Value>, problems: &mut Vec<String>) -> SessionUpdate {
+        SessionUpdate {
+            feature: Self::optional_string(update, "sessionUpdate", "feature", problems),
+            tasks: Self::strings(update, "sessionUpdate", "tasks", problems),
+            files: Self::strings(update, "sessionUpdate", "files", problems),
+            decisions: Self::strings(update, "sessionUpdate", "decisions", problems),
+        }
+    }
+
+    fn optional_string(object: &Map<String, Value>, parent: &str, field: &str, problems: &mut Vec<String>) -> Option<String> {
+        match object.get(field) {
+            None | Some(Value::Null) => None,
+            Some(Value::String(text)) => Some(text.clone()),
+            Some(other) => {
+                problems.push(format!("{}.{} must be a string, got {}", parent, field, other));
+                None
+            }
+        }
+    }
+
+    fn strings(object: &Map<String, Value>, parent: &str, field: &str, problems: &mut Vec<String>) -> Vec<String> {
+        match object.get(field) {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Array(items)) => items
+                .iter()
+                .enumerate()
+                .filter_map(|(index, item)| match item {
+                    Value::String(text) => Some(text.clone()),
+                    other => {
+                        problems.push(format!("{}.{}[{}] must be a string, got {}", parent, field, index, other));
+                        None
+                    }
+                })
+                .collect(),
+            Some(other) => {
+                problems.push(format!("{}.{} must be an array of strings, got {}", parent, field, other));
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// All insights contributed to a project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct InsightStore {
+    pub version: u32,
+    pub insights: Vec<Insight>,
+}
+
+impl InsightStore {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(CONFIG_DIR).join(INSIGHTS_FILE)
+    }
+
+    /// Read a project's insights; `None` when none were saved
+    pub fn load(project_root: &Path) -> Result<Option<Self>, ParseError> {
+        let path = Self::path(project_root);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            ParseError::from_reason(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        Ok(Some(validation::parse_json(&content, INSIGHTS_FILE)?))
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<(), ParseError> {
+        let mut store = self.clone();
+        store.version = INSIGHTS_VERSION;
+        let path = Self::path(project_root);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                ParseError::from_reason(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+        let content = serde_json::to_string_pretty(&store)
+            .map_err(|e| ParseError::from_reason(format!("Failed to serialize insights: {}", e)))?;
+        std::fs::write(&path, content).map_err(|e| {
+            ParseError::from_reason(format!("Failed to write {}: {}", path.display(), e))
+        })
+    }
+
+    /// Link a validated insight to the concepts and patterns it is about and
+    /// store it
+    ///
+    /// A concept is related when the insight names it (by name or id) or
+    /// touches its file; a pattern when the insight names its id or touches a
+    /// file it has examples in.
+    pub fn add<'a>(
+        &mut self,
+        input: InsightInput,
+        concepts: impl IntoIterator<Item = &'a SemanticConcept>,
+        patterns: &[Pattern],
+        root: Option<&str>,
+        recorded_at: Option<String>,
+    ) -> Insight {
+        let files: Vec<&String> = input
+            .content
+            .files
+            .iter()
+            .chain(input.session_update.iter().flat_map(|update| update.files.iter()))
+            .collect();
+        let touches = |file_path: &str| files.iter().any(|file| paths::same_file(file_path, file));
+
+        let mut related_concepts: Vec<ConceptLink> = concepts
+            .into_iter()
+            .filter(|concept| {
+                input.content.concepts.iter().any(|named| *named == concept.name || *named == concept.id)
+                    || touches(&concept.file_path)
+            })
+            .map(|concept| ConceptLink {
+                name: concept.name.clone(),
+                file_path: paths::relative_key(&concept.file_path, root),
+            })
+            .collect();
+        related_concepts.sort_by(|a, b| (&a.file_path, &a.name).cmp(&(&b.file_path, &b.name)));
+        related_concepts.dedup();
+
+        let mut related_patterns: Vec<String> = patterns
+            .iter()
+            .filter(|pattern| {
+                input.content.patterns.contains(&pattern.id)
+                    || pattern.examples.iter().any(|example| touches(&example.file_path))
+            })
+            .map(|pattern| pattern.id.clone())
+            .collect();
+        related_patterns.sort();
+
+        let insight = Insight {
+            id: format!("insight_{}", uuid::Uuid::new_v4().simple()),
+            insight_type: input.insight_type,
+            content: input.content,
+            confidence: input.confidence,
+            source_agent: input.source_agent,
+            session_update: input.session_update,
+            impact_prediction: input.impact_prediction,
+            related_concepts,
+            related_patterns,
+            recorded_at,
+        };
+        self.insights.push(insight.clone());
+        insight
+    }
+
+    /// Insights linked to a concept, newest first
+    pub fn for_concept(&self, concept: &SemanticConcept) -> Vec<Insight> {
+        self.newest_first(|insight| {
+            insight
+                .related_concepts
+                .iter()
+                .any(|link| link.name == concept.name && paths::same_file(&concept.file_path, &link.file_path))
+        })
+    }
+
+    /// Insights linked to a pattern, newest first
+    pub fn for_pattern(&self, pattern_id: &str) -> Vec<Insight> {
+        self.newest_first(|insight| insight.related_patterns.iter().any(|id| id == pattern_id))
+    }
+
+    fn newest_first(&self, keep: impl Fn(&Insight) -> bool) -> Vec<Insight> {
+        self.insights.iter().rev().filter(|insight| keep(insight)).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::types::PatternExample;
+    use crate::types::LineRange;
+    use std::collections::HashMap;
+
+    fn concept(name: &str, file_path: &str) -> SemanticConcept {
+        SemanticConcept {
+            id: format!("{}::{}", file_path, name),
+            name: name.to_string(),
+            concept_type: "class".to_string(),
+            confidence: 0.9,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start: 1, end: 10 },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_every_invalid_field_is_named() {
+        let error = InsightValidator::parse(r#"{"type": "hunch", "confidence": 1.4, "extra": true}"#).unwrap_err();
+        assert_eq!(error.code, ErrorCode::SchemaMismatch);
+        for expected in ["unknown field 'extra'", "type must be one of", "content is required", "confidence must be a number between 0 and 1, got 1.4", "sourceAgent is required"] {
+            assert!(error.message.contains(expected), "{} missing from {}", expected, error.message);
+        }
+
+        let error = InsightValidator::parse(
+            r#"{"type": "bug_pattern", "content": {"files": ["a.ts", 3]}, "confidence": 0.8, "sourceAgent": "agent", "sessionUpdate": {"tasks": "fix"}}"#,
+        )
+        .unwrap_err();
+        assert!(error.message.contains("content.description is required"));
+        assert!(error.message.contains("content.files[1] must be a string"));
+        assert!(error.message.contains("sessionUpdate.tasks must be an array of strings"));
+
+        assert_eq!(InsightValidator::parse("[1]").unwrap_err().code, ErrorCode::SchemaMismatch);
+        assert_eq!(InsightValidator::parse("{").unwrap_err().code, ErrorCode::InvalidJson);
+
+        // The published schema lists exactly the fields the validator accepts
+        let schema = schemars::schema_for!(InsightInput);
+        let properties = schema.get("properties").and_then(Value::as_object).unwrap();
+        assert_eq!(properties.len(), INSIGHT_FIELDS.len());
+        assert!(INSIGHT_FIELDS.iter().all(|field| properties.contains_key(*field)));
+    }
+
+    #[test]
+    fn test_insights_are_linked_to_concepts_and_patterns() {
+        let input = InsightValidator::parse(
+            r#"{"type": "bug_pattern", "content": {"description": "Retries are not idempotent", "concepts": ["PaymentClient"]},
+                "confidence": 0.8, "source_agent": "reviewer", "sessionUpdate": {"feature": "payments", "files": ["src/orders.ts"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(input.session_update.as_ref().and_then(|u| u.feature.as_deref()), Some("payments"));
+
+        let concepts = [
+            concept("PaymentClient", "/repo/src/payments.ts"),
+            concept("OrderService", "/repo/src/orders.ts"),
+            concept("UserService", "/repo/src/users.ts"),
+        ];
+        let pattern = Pattern {
+            id: "implementation_retry".to_string(),
+            pattern_type: "implementation_retry".to_string(),
+            description: "Retry wrapper".to_string(),
+            frequency: 2,
+            confidence: 0.7,
+            examples: vec![PatternExample {
+                code: "retry(() => submit())".to_string(),
+                file_path: "/repo/src/orders.ts".to_string(),
+                line_range: LineRange { start: 4, end: 4 },
+            }],
+            contexts: Vec::new(),
+        };
+
+        let mut store = InsightStore::default();
+        let insight = store.add(input, &concepts, &[pattern], Some("/repo"), None);
+        let linked: Vec<&str> = insight.related_concepts.iter().map(|link| link.file_path.as_str()).collect();
+        assert_eq!(linked, vec!["src/orders.ts", "src/payments.ts"]);
+        assert_eq!(insight.related_patterns, vec!["implementation_retry"]);
+        assert_eq!(store.for_concept(&concepts[0]).len(), 1);
+        assert!(store.for_concept(&concepts[2]).is_empty());
+        assert_eq!(store.for_pattern("implementation_retry")[0].id, insight.id);
+
+        let dir = tempfile::tempdir().unwrap();
+        store.save(dir.path()).unwrap();
+        let loaded = InsightStore::load(dir.path()).unwrap().unwrap();
+        assert_eq!(loaded.insights, store.insights);
+    }
+}
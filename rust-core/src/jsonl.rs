@@ -0,0 +1,185 @@
+//! JSON Lines output for concepts, patterns and violations
+//!
+//! One JSON document of a large repository's results has to be built whole
+//! before its first byte can be read. [`JsonLinesWriter`] writes results as
+//! JSON Lines instead: one compact object per line, tagged with the `kind` of
+//! record it is, so a stream can be piped through
+//! `jq -c 'select(.kind == "pattern")'`, read by the Node layer a line at a
+//! time, and exported straight from the engine's own data without holding a
+//! copy of the whole document. Records have the fields of the saved learning
+//! state (`file_path`, `line_range`) with `kind` in front.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::types::validation::{ErrorCode, ValidationError};
+use crate::types::ParseError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// Records in one page of JSON Lines when no limit is given
+pub const DEFAULT_JSON_LINES_PAGE: u32 = 500;
+
+/// What a JSON Lines record holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RecordKind {
+    Concept,
+    Pattern,
+    Violation,
+}
+
+impl RecordKind {
+    pub const ALL: [RecordKind; 3] = [RecordKind::Concept, RecordKind::Pattern, RecordKind::Violation];
+
+    /// Value of the record's `kind` field
+    pub fn name(&self) -> &'static str {
+        match self {
+            RecordKind::Concept => "concept",
+            RecordKind::Pattern => "pattern",
+            RecordKind::Violation => "violation",
+        }
+    }
+
+    /// 'concept' or 'concepts', and likewise for patterns and violations
+    pub fn parse(name: &str) -> Result<Self, ParseError> {
+        RecordKind::ALL
+            .into_iter()
+            .find(|kind| name == kind.name() || name.strip_suffix('s') == Some(kind.name()))
+            .ok_or_else(|| {
+                ValidationError::new(
+                    ErrorCode::InvalidArgument,
+                    format!("Unknown record kind '{}'; expected concepts, patterns or violations", name),
+                )
+                .into()
+            })
+    }
+
+    /// The kinds `names` lists, in output order; every kind when absent
+    pub fn parse_all(names: Option<&[String]>) -> Result<Vec<Self>, ParseError> {
+        let Some(names) = names else {
+            return Ok(RecordKind::ALL.to_vec());
+        };
+        let mut kinds = names.iter().map(|name| Self::parse(name)).collect::<Result<Vec<_>, _>>()?;
+        kinds.sort();
+        kinds.dedup();
+        Ok(kinds)
+    }
+}
+
+/// Records of each kind written by one JSON Lines export
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct JsonLinesExport {
+    pub path: String,
+    pub concept_count: u32,
+    pub pattern_count: u32,
+    pub violation_count: u32,
+}
+
+#[derive(Serialize)]
+struct Record<'a, T> {
+    kind: &'static str,
+    #[serde(flatten)]
+    record: &'a T,
+}
+
+/// Writes one record per line to any `Write`
+pub struct JsonLinesWriter<W: Write> {
+    writer: W,
+    lines: u32,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    pub fn new(writer: W) -> Self {
+        JsonLinesWriter { writer, lines: 0 }
+    }
+
+    /// Write `record` as one line; strings are escaped, so it never spans two
+    pub fn write<T: Serialize>(&mut self, kind: RecordKind, record: &T) -> Result<(), ParseError> {
+        serde_json::to_writer(&mut self.writer, &Record { kind: kind.name(), record })
+            .map_err(|e| ParseError::from_reason(format!("Failed to serialize {}: {}", kind.name(), e)))?;
+        self.writer
+            .write_all(b"\n")
+            .map_err(|e| ParseError::from_reason(format!("Failed to write JSON Lines: {}", e)))?;
+        self.lines += 1;
+        Ok(())
+    }
+
+    /// Write each of `records`; returns how many were written
+    pub fn write_all<'a, T: Serialize + 'a>(
+        &mut self,
+        kind: RecordKind,
+        records: impl IntoIterator<Item = &'a T>,
+    ) -> Result<u32, ParseError> {
+        let before = self.lines;
+        for record in records {
+            self.write(kind, record)?;
+        }
+        Ok(self.lines - before)
+    }
+
+    /// Lines written so far
+    pub fn lines(&self) -> u32 {
+        self.lines
+    }
+
+    /// Flush and hand back the underlying writer
+    pub fn finish(mut self) -> Result<W, ParseError> {
+        self.writer
+            .flush()
+            .map_err(|e| ParseError::from_reason(format!("Failed to write JSON Lines: {}", e)))?;
+        Ok(self.writer)
+    }
+}
+
+/// `records` as a JSON Lines string, for results small enough to hold at once
+pub fn to_json_lines<'a, T: Serialize + 'a>(
+    kind: RecordKind,
+    records: impl IntoIterator<Item = &'a T>,
+) -> Result<String, ParseError> {
+    let mut writer = JsonLinesWriter::new(Vec::new());
+    writer.write_all(kind, records)?;
+    let bytes = writer.finish()?;
+    String::from_utf8(bytes).map_err(|e| ParseError::from_reason(format!("Failed to write JSON Lines: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{LineRange, SemanticConcept};
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_each_record_is_one_tagged_line() {
+        let concept = SemanticConcept {
+            id: "c1".to_string(),
+            name: "parse".to_string(),
+            concept_type: "function".to_string(),
+            confidence: 0.9,
+            file_path: "src/parse.rs".to_string(),
+            line_range: LineRange { start: 3, end: 9 },
+            relationships: HashMap::new(),
+            metadata: HashMap::from([("doc".to_string(), "Parses\nlines".to_string())]),
+        };
+        let text = to_json_lines(RecordKind::Concept, [&concept, &concept]).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let record: Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(record["kind"], "concept");
+        assert_eq!(record["file_path"], "src/parse.rs");
+        assert_eq!(record["metadata"]["doc"], "Parses\nlines");
+
+        let mut writer = JsonLinesWriter::new(Vec::new());
+        writer.write(RecordKind::Violation, &serde_json::json!({ "rule_id": "no-console-log" })).unwrap();
+        assert_eq!(writer.lines(), 1);
+        assert_eq!(writer.finish().unwrap(), b"{\"kind\":\"violation\",\"rule_id\":\"no-console-log\"}\n");
+
+        let names = ["violations".to_string(), "concept".to_string(), "concepts".to_string()];
+        assert_eq!(RecordKind::parse_all(Some(&names)).unwrap(), [RecordKind::Concept, RecordKind::Violation]);
+        assert_eq!(RecordKind::parse_all(None).unwrap(), RecordKind::ALL);
+        assert!(RecordKind::parse("rows").unwrap_err().to_string().contains("[INVALID_ARGUMENT]"));
+    }
+}
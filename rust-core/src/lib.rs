@@ -12,12 +12,71 @@ pub mod extractors;
 pub mod analysis;
 pub mod patterns;
 
-// Legacy modules (will be removed in future versions)
-// pattern_learning has been fully ported to the patterns module
+// C ABI for embedding outside Node.js
+#[cfg(feature = "c-ffi")]
+pub mod ffi;
+
+// WebAssembly bindings over host-provided file contents
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub mod wasm;
+
+// SQLite persistence for concepts, patterns and relationships
+#[cfg(feature = "storage")]
+pub mod storage;
+
+// Language Server Protocol facade: symbols, references and diagnostics
+#[cfg(feature = "lsp")]
+pub mod lsp;
+
+// Arc<RwLock>-based handle for serving concurrent requests
+pub mod shared;
+
+// Change sets sent to subscribers after learn, load and update calls
+pub mod changes;
+
+// One shared engine per open project root
+pub mod registry;
+
+// Per-project caps on concepts and patterns, with eviction
+pub mod quotas;
+
+// .in-memoria bootstrap and project manifest
+pub mod project;
+
+// Agent-contributed insights linked to concepts and patterns
+pub mod insights;
+
+// Agent work sessions: tasks, touched files and involved concepts over time
+pub mod sessions;
+
+// Ranked, budgeted context for agent prompts
+pub mod context_pack;
+
+// Approximate token counts and budget-aware truncation
+pub mod tokens;
+
+// JSON Schemas of the public result and input types
+pub mod schemas;
+
+// JSON Lines output of concepts, patterns and violations
+pub mod jsonl;
+
+// Snapshot tests over fixtures/ (see fixtures/README.md)
+#[cfg(test)]
+mod golden_tests;
+
+// The monolithic pattern_learning/semantic modules have been removed; the
+// legacy PatternLearner API lives on as a façade in patterns::legacy
 
 // Re-export core types and main structs for easy access
 pub use types::*;
 pub use analysis::{SemanticAnalyzer, ComplexityAnalyzer, RelationshipLearner, FrameworkDetector, BlueprintAnalyzer};
+pub use shared::SharedEngine;
+pub use changes::{ChangeSet, ChangeSubscriber};
+pub use registry::{ProjectInfo, ProjectRegistry};
+pub use quotas::{ProjectQuota, ResourceUsage};
+pub use jsonl::{JsonLinesExport, JsonLinesWriter};
+pub use project::{init_project, InitReport, ProjectManifest};
 pub use parsing::{ParserManager, TreeWalker, FallbackExtractor};
 pub use patterns::{
     PatternLearningEngine, NamingPatternAnalyzer, StructuralPatternAnalyzer, 
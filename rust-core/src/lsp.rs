@@ -0,0 +1,437 @@
+//! Language Server Protocol facade over the analysis engine
+//!
+//! Editors speak LSP, so [`LspServer`] lets them use the engine without the
+//! MCP server in between. It answers the requests the engine has data for:
+//!
+//! - `initialize` loads what the workspace root has saved, or learns it;
+//! - `textDocument/documentSymbol` lists the stored concepts of a file;
+//! - `workspace/symbol` searches concepts by name and tag;
+//! - `textDocument/references` follows incoming relationship edges of the
+//!   concept under the cursor;
+//! - `textDocument/didOpen` and `didSave` publish the file's violations as
+//!   diagnostics.
+//!
+//! Messages are JSON-RPC with `Content-Length` framing, read and written as
+//! `serde_json` values; [`serve`] runs the loop over any reader and writer,
+//! and [`serve_stdio`] over standard input and output. Concept lines are
+//! 1-based and LSP lines 0-based, so positions are shifted at the boundary.
+
+use crate::shared::SharedEngine;
+use crate::types::{ParseError, SemanticConcept};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// Symbols returned for one `workspace/symbol` query at most
+const WORKSPACE_SYMBOL_LIMIT: u32 = 100;
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// An error answered to a request instead of a result
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn invalid_params(message: impl Into<String>) -> Self {
+        RpcError { code: INVALID_PARAMS, message: message.into() }
+    }
+}
+
+impl From<ParseError> for RpcError {
+    fn from(error: ParseError) -> Self {
+        RpcError { code: INTERNAL_ERROR, message: error.to_string() }
+    }
+}
+
+/// Answers LSP messages from one engine
+pub struct LspServer {
+    engine: SharedEngine,
+    runtime: tokio::runtime::Runtime,
+    root: Option<PathBuf>,
+    exited: bool,
+}
+
+impl LspServer {
+    pub fn new() -> Result<Self, ParseError> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| ParseError::from_reason(format!("Failed to start runtime: {}", e)))?;
+        Ok(LspServer { engine: SharedEngine::new()?, runtime, root: None, exited: false })
+    }
+
+    /// Whether the client sent `exit`
+    pub fn has_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Handle one message; returns the response to a request and any
+    /// notifications to send, in order
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message.get("method").and_then(Value::as_str).unwrap_or_default();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let Some(id) = message.get("id").cloned() else {
+            return self.notify(method, &params);
+        };
+
+        let result = match method {
+            "initialize" => self.initialize(&params),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/documentSymbol" => self.document_symbols(&params),
+            "workspace/symbol" => self.workspace_symbols(&params),
+            "textDocument/references" => self.references(&params),
+            _ => Err(RpcError { code: METHOD_NOT_FOUND, message: format!("Unsupported method: {}", method) }),
+        };
+        vec![match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(error) => json!({"jsonrpc": "2.0", "id": id, "error": {"code": error.code, "message": error.message}}),
+        }]
+    }
+
+    fn notify(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        match method {
+            "exit" => {
+                self.exited = true;
+                Vec::new()
+            }
+            "textDocument/didOpen" | "textDocument/didSave" => {
+                let Some(uri) = params.pointer("/textDocument/uri").and_then(Value::as_str) else {
+                    return Vec::new();
+                };
+                vec![self.diagnostics(uri)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn initialize(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let root = params
+            .get("rootUri")
+            .and_then(Value::as_str)
+            .or_else(|| params.pointer("/workspaceFolders/0/uri").and_then(Value::as_str))
+            .map(uri_to_path)
+            .or_else(|| params.get("rootPath").and_then(Value::as_str).map(PathBuf::from));
+        if let Some(root) = &root {
+            let path = root.to_string_lossy().to_string();
+            let engine = &self.engine;
+            self.runtime.block_on(async {
+                if !engine.load_project(path.clone()).await? {
+                    engine.learn_from_codebase(path).await?;
+                }
+                Ok::<_, ParseError>(())
+            })?;
+        }
+        self.root = root;
+
+        Ok(json!({
+            "capabilities": {
+                "textDocumentSync": {"openClose": true, "save": true},
+                "documentSymbolProvider": true,
+                "workspaceSymbolProvider": true,
+                "referencesProvider": true,
+            },
+            "serverInfo": {"name": "in-memoria", "version": env!("CARGO_PKG_VERSION")},
+        }))
+    }
+
+    fn document_symbols(&self, params: &Value) -> Result<Value, RpcError> {
+        let path = self.document_path(params)?;
+        let concepts = self.runtime.block_on(self.engine.get_file_concepts(path.to_string_lossy().to_string()));
+        Ok(Value::Array(concepts.iter().map(|concept| self.symbol(concept)).collect()))
+    }
+
+    fn workspace_symbols(&self, params: &Value) -> Result<Value, RpcError> {
+        let query = params.get("query").and_then(Value::as_str).unwrap_or_default().to_string();
+        let concepts = self.runtime.block_on(self.engine.search_concepts(query, Some(WORKSPACE_SYMBOL_LIMIT)));
+        Ok(Value::Array(concepts.iter().map(|concept| self.symbol(concept)).collect()))
+    }
+
+    /// Concepts with an edge into the concept under the cursor, and that
+    /// concept itself when the client asks for the declaration
+    fn references(&self, params: &Value) -> Result<Value, RpcError> {
+        let path = self.document_path(params)?;
+        let line = params
+            .pointer("/position/line")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| RpcError::invalid_params("position.line is required"))? as u32
+            + 1;
+        let include_declaration = params
+            .pointer("/context/includeDeclaration")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        let engine = &self.engine;
+        let locations = self.runtime.block_on(async {
+            let concepts = engine.get_file_concepts(path.to_string_lossy().to_string()).await;
+            let Some(target) = concepts
+                .iter()
+                .filter(|concept| concept.line_range.start <= line && line <= concept.line_range.end)
+                .min_by_key(|concept| concept.line_range.end.saturating_sub(concept.line_range.start))
+            else {
+                return Ok::<_, ParseError>(Vec::new());
+            };
+
+            let mut referencing = Vec::new();
+            if include_declaration {
+                referencing.push(target.clone());
+            }
+            let edges = engine.get_relationship_edges(target.id.clone(), None, Some("incoming".to_string())).await?;
+            for edge in edges.iter().filter(|edge| edge.kind != "contains") {
+                if let Some(concept) = engine.get_concept(edge.from.clone()).await {
+                    if !referencing.iter().any(|known: &SemanticConcept| known.id == concept.id) {
+                        referencing.push(concept);
+                    }
+                }
+            }
+            Ok(referencing)
+        })?;
+        Ok(Value::Array(locations.iter().map(|concept| self.location(concept)).collect()))
+    }
+
+    /// `textDocument/publishDiagnostics` with the file's violations
+    fn diagnostics(&self, uri: &str) -> Value {
+        let path = uri_to_path(uri);
+        let violations = self.runtime.block_on(self.engine.get_violations());
+        let diagnostics: Vec<Value> = violations
+            .iter()
+            .filter(|violation| crate::types::paths::same_file(&violation.file_path, &path.to_string_lossy()))
+            .map(|violation| {
+                let severity = match violation.severity.as_str() {
+                    "error" => 1,
+                    "warning" => 2,
+                    _ => 3,
+                };
+                json!({
+                    "range": range(violation.line_range.start, violation.line_range.end),
+                    "severity": severity,
+                    "code": violation.rule_id,
+                    "source": "in-memoria",
+                    "message": violation.message,
+                })
+            })
+            .collect();
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {"uri": uri, "diagnostics": diagnostics},
+        })
+    }
+
+    fn document_path(&self, params: &Value) -> Result<PathBuf, RpcError> {
+        params
+            .pointer("/textDocument/uri")
+            .and_then(Value::as_str)
+            .map(uri_to_path)
+            .ok_or_else(|| RpcError::invalid_params("textDocument.uri is required"))
+    }
+
+    /// A `SymbolInformation` for a concept
+    fn symbol(&self, concept: &SemanticConcept) -> Value {
+        json!({
+            "name": concept.name,
+            "kind": symbol_kind(&concept.concept_type),
+            "location": self.location(concept),
+        })
+    }
+
+    fn location(&self, concept: &SemanticConcept) -> Value {
+        let path = Path::new(&concept.file_path);
+        let path = match &self.root {
+            Some(root) if path.is_relative() => root.join(path),
+            _ => path.to_path_buf(),
+        };
+        json!({
+            "uri": path_to_uri(&path),
+            "range": range(concept.line_range.start, concept.line_range.end),
+        })
+    }
+}
+
+/// An LSP range over whole 1-based lines
+fn range(start: u32, end: u32) -> Value {
+    json!({
+        "start": {"line": start.saturating_sub(1), "character": 0},
+        "end": {"line": end.saturating_sub(1), "character": u32::MAX},
+    })
+}
+
+/// LSP `SymbolKind` of a concept type
+fn symbol_kind(concept_type: &str) -> u32 {
+    match concept_type {
+        "module" => 2,
+        "namespace" => 3,
+        "class" => 5,
+        "method" => 6,
+        "property" | "attribute" => 7,
+        "field" | "column" => 8,
+        "constructor" => 9,
+        "enum" => 10,
+        "interface" | "trait" | "type" | "type_alias" => 11,
+        "function" | "arrow_function" | "procedure" => 12,
+        "constant" => 14,
+        "struct" | "record" => 23,
+        _ => 13,
+    }
+}
+
+/// The path of a `file://` URI, with percent-escapes decoded
+pub fn uri_to_path(uri: &str) -> PathBuf {
+    let path = uri.strip_prefix("file://").unwrap_or(uri);
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        // Hex digits are checked as bytes: slicing `path` after a stray '%'
+        // could split a multi-byte char
+        let escape = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| bytes[i] == b'%' && hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escape {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    let path = String::from_utf8_lossy(&decoded).to_string();
+    // file:///C:/src -> C:/src
+    let is_drive = path.len() > 2 && path.as_bytes()[2] == b':' && path.as_bytes()[1].is_ascii_alphabetic();
+    PathBuf::from(if path.starts_with('/') && is_drive { &path[1..] } else { &path })
+}
+
+/// A `file://` URI for a path, escaping what URIs cannot hold
+pub fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from(if path.starts_with('/') { "file://" } else { "file:///" });
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~:".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+/// Read one `Content-Length` framed message; `None` at end of input
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let length = length.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header"))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    serde_json::from_slice(&body).map(Some).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write one message with `Content-Length` framing
+pub fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+/// Answer messages from `input` on `output` until the client exits or the
+/// input ends
+pub fn serve(mut input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut server = LspServer::new().map_err(|e| io::Error::other(e.to_string()))?;
+    while let Some(message) = read_message(&mut input)? {
+        for reply in server.handle(&message) {
+            write_message(&mut output, &reply)?;
+        }
+        if server.has_exited() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// [`serve`] over standard input and output
+pub fn serve_stdio() -> io::Result<()> {
+    serve(io::stdin().lock(), io::stdout().lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uris_round_trip_and_messages_are_framed() {
+        let path = Path::new("/home/dev/my project/src/app.ts");
+        let uri = path_to_uri(path);
+        assert_eq!(uri, "file:///home/dev/my%20project/src/app.ts");
+        assert_eq!(uri_to_path(&uri), path);
+        assert_eq!(uri_to_path("file:///C:/src/app.ts"), PathBuf::from("C:/src/app.ts"));
+        let unicode = Path::new("/home/dév/100%é/ünï 日本.ts");
+        assert_eq!(uri_to_path(&path_to_uri(unicode)), unicode);
+        assert_eq!(uri_to_path("file:///tmp/%é%+1%4"), PathBuf::from("/tmp/%é%+1%4"));
+
+        let mut framed = Vec::new();
+        write_message(&mut framed, &json!({"jsonrpc": "2.0", "method": "exit"})).unwrap();
+        let mut input = io::Cursor::new(framed);
+        assert_eq!(read_message(&mut input).unwrap().unwrap()["method"], "exit");
+        assert!(read_message(&mut input).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_server_answers_symbols_and_references() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("orders.ts");
+        std::fs::write(
+            &file,
+            "export function total() {\n  return 1;\n}\n\nexport function checkout() {\n  return total();\n}\n",
+        )
+        .unwrap();
+
+        let mut server = LspServer::new().unwrap();
+        let initialized = server.handle(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": {"rootUri": path_to_uri(dir.path())}}));
+        assert_eq!(initialized[0]["result"]["capabilities"]["referencesProvider"], true);
+
+        let uri = path_to_uri(&file);
+        let symbols = server.handle(&json!({"jsonrpc": "2.0", "id": 2, "method": "textDocument/documentSymbol",
+            "params": {"textDocument": {"uri": uri}}}));
+        let names: Vec<&str> =
+            symbols[0]["result"].as_array().unwrap().iter().filter_map(|symbol| symbol["name"].as_str()).collect();
+        assert!(names.contains(&"total") && names.contains(&"checkout"), "{:?}", names);
+
+        let references = server.handle(&json!({"jsonrpc": "2.0", "id": 3, "method": "textDocument/references",
+            "params": {"textDocument": {"uri": uri}, "position": {"line": 0, "character": 17}}}));
+        let lines: Vec<u64> = references[0]["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|location| location["range"]["start"]["line"].as_u64())
+            .collect();
+        assert!(lines.contains(&4) && !lines.contains(&0), "{:?}", lines);
+
+        let unknown = server.handle(&json!({"jsonrpc": "2.0", "id": 4, "method": "textDocument/hover", "params": {}}));
+        assert_eq!(unknown[0]["error"]["code"], METHOD_NOT_FOUND);
+        let diagnostics = server.handle(&json!({"jsonrpc": "2.0", "method": "textDocument/didOpen",
+            "params": {"textDocument": {"uri": uri}}}));
+        assert_eq!(diagnostics[0]["method"], "textDocument/publishDiagnostics");
+
+        assert!(server.handle(&json!({"jsonrpc": "2.0", "method": "exit"})).is_empty());
+        assert!(server.has_exited());
+    }
+}
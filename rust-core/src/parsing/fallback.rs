@@ -1,5 +1,6 @@
 //! Fallback pattern-based extraction when tree-sitter parsing fails
 
+use crate::parsing::NameExtractor;
 use crate::types::{SemanticConcept, LineRange};
 use std::collections::HashMap;
 use std::path::Path;
@@ -115,63 +116,32 @@ impl FallbackExtractor {
     /// Extract function names using regex patterns
     fn extract_function_name(&self, line: &str) -> Option<String> {
         // TypeScript/JavaScript function patterns
-        if line.contains("function ") {
-            if let Some(start) = line.find("function ") {
-                let after_function = &line[start + 9..];
-                if let Some(end) = after_function.find('(') {
-                    let name = after_function[..end].trim();
-                    if !name.is_empty() && self.is_valid_identifier(name) {
-                        return Some(name.to_string());
-                    }
-                }
-            }
+        if let Some(name) = NameExtractor::identifier_between(line, "function ", '(') {
+            return Some(name);
         }
 
         // Arrow function patterns: const funcName = () =>
         if line.contains("=>") {
-            if let Some(equals_pos) = line.find('=') {
-                let before_equals = &line[..equals_pos].trim();
-                if let Some(name_start) = before_equals.rfind(char::is_whitespace) {
-                    let name = before_equals[name_start..].trim();
-                    if !name.is_empty() && self.is_valid_identifier(name) {
-                        return Some(name.to_string());
-                    }
-                } else {
-                    // Handle case like "const funcName ="
-                    if let Some(const_pos) = before_equals.find("const ") {
-                        let name = before_equals[const_pos + 6..].trim();
-                        if !name.is_empty() && self.is_valid_identifier(name) {
-                            return Some(name.to_string());
-                        }
-                    }
+            if let Some((before_equals, _)) = line.split_once('=') {
+                let before_equals = before_equals.trim();
+                let name = before_equals
+                    .rsplit_once(char::is_whitespace)
+                    .map_or(before_equals, |(_, name)| name)
+                    .trim();
+                if !name.is_empty() && self.is_valid_identifier(name) {
+                    return Some(name.to_string());
                 }
             }
         }
 
         // Rust function patterns
-        if line.contains("fn ") {
-            if let Some(start) = line.find("fn ") {
-                let after_fn = &line[start + 3..];
-                if let Some(end) = after_fn.find('(') {
-                    let name = after_fn[..end].trim();
-                    if !name.is_empty() && self.is_valid_identifier(name) {
-                        return Some(name.to_string());
-                    }
-                }
-            }
+        if let Some(name) = NameExtractor::identifier_between(line, "fn ", '(') {
+            return Some(name);
         }
 
         // Python function patterns
         if line.trim_start().starts_with("def ") {
-            if let Some(start) = line.find("def ") {
-                let after_def = &line[start + 4..];
-                if let Some(end) = after_def.find('(') {
-                    let name = after_def[..end].trim();
-                    if !name.is_empty() && self.is_valid_identifier(name) {
-                        return Some(name.to_string());
-                    }
-                }
-            }
+            return NameExtractor::identifier_between(line, "def ", '(');
         }
 
         None
@@ -179,34 +149,18 @@ impl FallbackExtractor {
 
     /// Extract class names using regex patterns
     fn extract_class_name(&self, line: &str) -> Option<String> {
-        if line.contains("class ") {
-            if let Some(start) = line.find("class ") {
-                let after_class = &line[start + 6..];
-                let end = after_class
-                    .find(char::is_whitespace)
-                    .or_else(|| after_class.find('{'))
-                    .or_else(|| after_class.find('('))
-                    .unwrap_or(after_class.len());
-                let name = after_class[..end].trim();
-                if !name.is_empty() && self.is_valid_identifier(name) {
-                    return Some(name.to_string());
-                }
+        if let Some(after_class) = NameExtractor::text_after(line, "class ") {
+            let name = self.leading_name(after_class, &['{', '(']);
+            if !name.is_empty() && self.is_valid_identifier(name) {
+                return Some(name.to_string());
             }
         }
 
         // Rust struct patterns
-        if line.contains("struct ") {
-            if let Some(start) = line.find("struct ") {
-                let after_struct = &line[start + 7..];
-                let end = after_struct
-                    .find(char::is_whitespace)
-                    .or_else(|| after_struct.find('{'))
-                    .or_else(|| after_struct.find('<'))
-                    .unwrap_or(after_struct.len());
-                let name = after_struct[..end].trim();
-                if !name.is_empty() && self.is_valid_identifier(name) {
-                    return Some(name.to_string());
-                }
+        if let Some(after_struct) = NameExtractor::text_after(line, "struct ") {
+            let name = self.leading_name(after_struct, &['{', '<']);
+            if !name.is_empty() && self.is_valid_identifier(name) {
+                return Some(name.to_string());
             }
         }
 
@@ -215,24 +169,28 @@ impl FallbackExtractor {
 
     /// Extract interface names using regex patterns
     fn extract_interface_name(&self, line: &str) -> Option<String> {
-        if line.contains("interface ") {
-            if let Some(start) = line.find("interface ") {
-                let after_interface = &line[start + 10..];
-                let end = after_interface
-                    .find(char::is_whitespace)
-                    .or_else(|| after_interface.find('{'))
-                    .or_else(|| after_interface.find('<'))
-                    .unwrap_or(after_interface.len());
-                let name = after_interface[..end].trim();
-                if !name.is_empty() && self.is_valid_identifier(name) {
-                    return Some(name.to_string());
-                }
+        if let Some(after_interface) = NameExtractor::text_after(line, "interface ") {
+            let name = self.leading_name(after_interface, &['{', '<']);
+            if !name.is_empty() && self.is_valid_identifier(name) {
+                return Some(name.to_string());
             }
         }
 
         None
     }
 
+    /// Leading name of `text`, ended by whitespace or else by the first delimiter found (in priority order)
+    fn leading_name<'a>(&self, text: &'a str, delimiters: &[char]) -> &'a str {
+        if text.contains(char::is_whitespace) {
+            return NameExtractor::text_until(text, char::is_whitespace).trim();
+        }
+        delimiters
+            .iter()
+            .find_map(|d| text.split_once(*d).map(|(name, _)| name))
+            .unwrap_or(text)
+            .trim()
+    }
+
     /// Check if a string is a valid programming language identifier
     fn is_valid_identifier(&self, name: &str) -> bool {
         !name.is_empty()
@@ -471,4 +429,42 @@ function third() {}
         assert_eq!(string_concepts.len(), 1);
         assert_eq!(string_concepts[0].name, "actualFunction");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_multibyte_identifiers() {
+        let extractor = FallbackExtractor::new();
+
+        let code = "function größe() {}\nclass Ünïcode {}\nconst 名前 = () => 1;";
+        let concepts = extractor.extract_concepts("test.js", code);
+
+        let names: Vec<&str> = concepts.iter().map(|c| c.name.as_str()).collect();
+        assert!(names.contains(&"größe"));
+        assert!(names.contains(&"Ünïcode"));
+        assert!(names.contains(&"名前"));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_extract_concepts_never_panics(content in "\\PC*") {
+            let concepts = FallbackExtractor::new().extract_concepts("fuzz.ts", &content);
+            // The file-level concept guarantees at least one result
+            proptest::prop_assert!(!concepts.is_empty());
+        }
+
+        #[test]
+        fn prop_keyword_lines_never_panic(
+            prefix in "\\PC{0,8}",
+            keyword in proptest::sample::select(vec![
+                "function ", "fn ", "def ", "class ", "struct ", "interface ", "const ", "=>", "=",
+            ]),
+            suffix in "\\PC{0,16}",
+        ) {
+            let line = format!("{}{}{}", prefix, keyword, suffix);
+            let concepts = FallbackExtractor::new().extract_concepts("fuzz.rs", &line);
+            for concept in concepts {
+                proptest::prop_assert!(!concept.name.is_empty());
+            }
+        }
+    }
+}
+
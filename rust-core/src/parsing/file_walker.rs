@@ -0,0 +1,353 @@
+//! One way to list the files an analyzer looks at
+//!
+//! [`FileWalker`] walks a project in path order, never descends into the
+//! dependency, build and editor directories in [`IGNORED_DIRECTORIES`], and
+//! keeps the files passing its depth, extension, size and caller filters, up
+//! to an optional file limit. Each walk reports what it left out in
+//! [`WalkStats`]. [`FileWalker::map`] runs a function over the kept files,
+//! spreading them over threads when the walker is parallel.
+
+use std::path::{Path, PathBuf};
+use walkdir::{DirEntry, WalkDir};
+
+/// Directories no analysis descends into: dependencies, build output, caches
+/// and editor settings
+pub const IGNORED_DIRECTORIES: &[&str] = &[
+    "node_modules",
+    ".git",
+    "target",
+    "dist",
+    "build",
+    ".next",
+    "__pycache__",
+    "coverage",
+    ".vscode",
+    ".idea",
+];
+
+/// What one walk visited and left out
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WalkStats {
+    /// Directories walked, the root included
+    pub directories: usize,
+    /// Ignored directories the walk did not descend into
+    pub ignored_directories: usize,
+    /// Files found in the walked directories
+    pub files_seen: usize,
+    pub files_kept: usize,
+    /// Files without one of the walker's extensions
+    pub skipped_extension: usize,
+    /// Files over the walker's size limit
+    pub skipped_size: usize,
+    /// Files the caller's filter rejected
+    pub skipped_filter: usize,
+    /// Entries that could not be read
+    pub errors: usize,
+    /// True when files were left out because of the file limit
+    pub truncated: bool,
+}
+
+/// A directory reached by a walk, with its depth below the root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkedDirectory {
+    pub path: PathBuf,
+    pub depth: usize,
+}
+
+/// Files kept by a walk, in path order, and the walk's statistics
+#[derive(Debug, Default)]
+pub struct FileWalk {
+    pub files: Vec<PathBuf>,
+    pub stats: WalkStats,
+}
+
+type PathFilter<'a> = Box<dyn Fn(&Path) -> bool + 'a>;
+
+pub struct FileWalker<'a> {
+    max_depth: Option<usize>,
+    max_files: Option<usize>,
+    max_file_size: Option<u64>,
+    extensions: Option<Vec<String>>,
+    skip_ignored_directories: bool,
+    filter: Option<PathFilter<'a>>,
+    parallel: bool,
+}
+
+impl<'a> FileWalker<'a> {
+    /// A walker keeping every file outside the ignored directories
+    pub fn new() -> Self {
+        FileWalker {
+            max_depth: None,
+            max_files: None,
+            max_file_size: None,
+            extensions: None,
+            skip_ignored_directories: true,
+            filter: None,
+            parallel: false,
+        }
+    }
+
+    /// Whether a directory of this name is one walks do not descend into
+    pub fn is_ignored_directory(name: &str) -> bool {
+        IGNORED_DIRECTORIES.contains(&name)
+    }
+
+    /// Descend at most `depth` levels below the root; files directly in the
+    /// root are at depth 1
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Keep at most `limit` files, the first in path order
+    pub fn max_files(mut self, limit: usize) -> Self {
+        self.max_files = Some(limit);
+        self
+    }
+
+    /// Leave out files larger than `bytes`
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Keep only files with one of these extensions, compared case-insensitively
+    pub fn extensions(mut self, extensions: &[&str]) -> Self {
+        self.extensions = Some(extensions.iter().map(|extension| extension.to_lowercase()).collect());
+        self
+    }
+
+    /// Descend into the ignored directories too
+    pub fn include_ignored_directories(mut self) -> Self {
+        self.skip_ignored_directories = false;
+        self
+    }
+
+    /// Keep only files `filter` accepts, after the other filters
+    pub fn filter(mut self, filter: impl Fn(&Path) -> bool + 'a) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Run [`map`](Self::map) over several threads
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Files under `root` the walker keeps, in path order
+    pub fn walk(&self, root: &Path) -> FileWalk {
+        let mut walk = FileWalk::default();
+        for entry in self.entries(root, &mut walk.stats) {
+            if entry.file_type().is_dir() {
+                walk.stats.directories += 1;
+                continue;
+            }
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            walk.stats.files_seen += 1;
+            if !self.keeps_extension(entry.path()) {
+                walk.stats.skipped_extension += 1;
+            } else if !self.keeps_size(entry.path()) {
+                walk.stats.skipped_size += 1;
+            } else if !self.filter.as_ref().is_none_or(|filter| filter(entry.path())) {
+                walk.stats.skipped_filter += 1;
+            } else {
+                walk.files.push(entry.into_path());
+            }
+        }
+
+        walk.files.sort();
+        if let Some(limit) = self.max_files {
+            if walk.files.len() > limit {
+                walk.files.truncate(limit);
+                walk.stats.truncated = true;
+            }
+        }
+        walk.stats.files_kept = walk.files.len();
+        walk
+    }
+
+    /// Directories under `root`, the root included, in path order; the file
+    /// filters do not apply
+    pub fn directories(&self, root: &Path) -> Vec<WalkedDirectory> {
+        let mut stats = WalkStats::default();
+        self.entries(root, &mut stats)
+            .into_iter()
+            .filter(|entry| entry.file_type().is_dir())
+            .map(|entry| WalkedDirectory { depth: entry.depth(), path: entry.into_path() })
+            .collect()
+    }
+
+    /// Whether the walker would keep `path`, a file under `root` that need not
+    /// exist on disk (e.g. an overlaid file)
+    pub fn keeps(&self, root: &Path, path: &Path) -> bool {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let components: Vec<_> = relative.components().collect();
+        if self.max_depth.is_some_and(|depth| components.len() > depth) {
+            return false;
+        }
+        let in_ignored = components[..components.len().saturating_sub(1)]
+            .iter()
+            .any(|component| Self::is_ignored_directory(&component.as_os_str().to_string_lossy()));
+        !(self.skip_ignored_directories && in_ignored)
+            && self.keeps_extension(path)
+            && self.filter.as_ref().is_none_or(|filter| filter(path))
+    }
+
+    /// `f` applied to each kept file under `root`, in path order, leaving out
+    /// files for which it returns `None`
+    pub fn map<T, F>(&self, root: &Path, f: F) -> (Vec<T>, WalkStats)
+    where
+        T: Send,
+        F: Fn(&Path) -> Option<T> + Sync,
+    {
+        let walk = self.walk(root);
+        let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        if !self.parallel || threads < 2 || walk.files.len() < 2 || cfg!(target_arch = "wasm32") {
+            let results = walk.files.iter().filter_map(|path| f(path)).collect();
+            return (results, walk.stats);
+        }
+
+        // Contiguous chunks, joined in order, keep the results in path order
+        let chunk_size = walk.files.len().div_ceil(threads);
+        let results = std::thread::scope(|scope| {
+            let handles: Vec<_> = walk
+                .files
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let f = &f;
+                    scope.spawn(move || chunk.iter().filter_map(|path| f(path)).collect::<Vec<T>>())
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_default())
+                .collect()
+        });
+        (results, walk.stats)
+    }
+
+    /// Entries under `root` in name order, without descending into ignored
+    /// directories
+    fn entries(&self, root: &Path, stats: &mut WalkStats) -> Vec<DirEntry> {
+        let mut walker = WalkDir::new(root).sort_by_file_name();
+        if let Some(depth) = self.max_depth {
+            walker = walker.max_depth(depth);
+        }
+
+        let mut ignored = 0;
+        let mut entries = Vec::new();
+        let walker = walker.into_iter().filter_entry(|entry| {
+            let skip = self.skip_ignored_directories
+                && entry.depth() > 0
+                && entry.file_type().is_dir()
+                && Self::is_ignored_directory(&entry.file_name().to_string_lossy());
+            if skip {
+                ignored += 1;
+            }
+            !skip
+        });
+        for entry in walker {
+            match entry {
+                Ok(entry) => entries.push(entry),
+                Err(_) => stats.errors += 1,
+            }
+        }
+        stats.ignored_directories += ignored;
+        entries
+    }
+
+    fn keeps_extension(&self, path: &Path) -> bool {
+        let Some(extensions) = &self.extensions else {
+            return true;
+        };
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extensions.iter().any(|kept| kept.eq_ignore_ascii_case(extension)))
+    }
+
+    fn keeps_size(&self, path: &Path) -> bool {
+        match self.max_file_size {
+            Some(limit) => path.metadata().map(|metadata| metadata.len() <= limit).unwrap_or(true),
+            None => true,
+        }
+    }
+}
+
+impl Default for FileWalker<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for path in ["src/app.ts", "src/util.rs", "src/deep/nested/mod.rs", "README.md", "node_modules/pkg/index.ts", "build/out.ts"] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "content").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_walk_skips_ignored_directories_and_filters_files() {
+        let dir = project();
+        let walk = FileWalker::new().extensions(&["ts", "RS"]).walk(dir.path());
+        let relative: Vec<_> = walk.files.iter().map(|path| path.strip_prefix(dir.path()).unwrap().to_path_buf()).collect();
+        assert_eq!(
+            relative,
+            vec![PathBuf::from("src/app.ts"), PathBuf::from("src/deep/nested/mod.rs"), PathBuf::from("src/util.rs")]
+        );
+        assert_eq!(walk.stats.ignored_directories, 2);
+        assert_eq!(walk.stats.skipped_extension, 1);
+        assert_eq!(walk.stats.files_kept, 3);
+        assert!(!walk.stats.truncated);
+    }
+
+    #[test]
+    fn test_limits_and_caller_filter() {
+        let dir = project();
+        let walk = FileWalker::new().max_depth(2).walk(dir.path());
+        assert_eq!(walk.files.len(), 3);
+
+        let walk = FileWalker::new().max_files(1).filter(|path| !path.ends_with("README.md")).walk(dir.path());
+        assert_eq!(walk.files, vec![dir.path().join("src/app.ts")]);
+        assert_eq!(walk.stats.skipped_filter, 1);
+        assert!(walk.stats.truncated);
+
+        let walk = FileWalker::new().max_file_size(3).walk(dir.path());
+        assert!(walk.files.is_empty());
+        assert_eq!(walk.stats.skipped_size, 4);
+    }
+
+    #[test]
+    fn test_keeps_applies_the_path_filters_to_files_not_on_disk() {
+        let root = Path::new("/project");
+        let walker = FileWalker::new().max_depth(3).extensions(&["ts"]);
+        assert!(walker.keeps(root, Path::new("/project/src/new.ts")));
+        assert!(!walker.keeps(root, Path::new("/project/node_modules/pkg/new.ts")));
+        assert!(!walker.keeps(root, Path::new("/project/src/new.rs")));
+        assert!(!walker.keeps(root, Path::new("/project/a/b/c/new.ts")));
+    }
+
+    #[test]
+    fn test_parallel_map_keeps_path_order() {
+        let dir = project();
+        let sequential = FileWalker::new().map(dir.path(), |path| Some(path.to_path_buf())).0;
+        let (parallel, stats) = FileWalker::new().parallel(true).map(dir.path(), |path| Some(path.to_path_buf()));
+        assert_eq!(parallel, sequential);
+        assert_eq!(stats.files_kept, 4);
+
+        let directories = FileWalker::new().directories(dir.path());
+        assert_eq!(directories[0].depth, 0);
+        assert!(directories.iter().all(|d| !d.path.ends_with("node_modules")));
+    }
+}
@@ -191,6 +191,11 @@ impl ParserManager {
 
     /// Get tree-sitter language object for a given language string
     pub fn get_tree_sitter_language(&self, language: &str) -> Result<Language, ParseError> {
+        Self::tree_sitter_language(language)
+    }
+
+    /// Tree-sitter language object for a language string, without a manager
+    pub fn tree_sitter_language(language: &str) -> Result<Language, ParseError> {
         match language {
             "typescript" => Ok(tree_sitter_typescript.into()),
             "javascript" => Ok(tree_sitter_javascript.into()),
@@ -0,0 +1,158 @@
+//! Comment and string-literal masking for text heuristics
+//!
+//! Regex and keyword detectors read source as text, so `create` in a log
+//! message or `getInstance()` in a doc comment used to count as code.
+//! [`SourceMasker`] parses a file with tree-sitter and blanks the bytes of its
+//! comments and string literals, keeping line breaks and byte offsets, so a
+//! match in the masked text is at the same place in the original. Interpolated
+//! expressions, such as `${user.name}` in a template string or `{total}` in an
+//! f-string, are code and stay. A language without a grammar, or a file that
+//! doesn't parse, is returned unchanged.
+
+use crate::parsing::ParserManager;
+use std::ops::Range;
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+/// Blanks comments and string literals out of source text
+pub struct SourceMasker;
+
+impl SourceMasker {
+    /// `code` in `language` (e.g. 'typescript' or 'rust') with comments and
+    /// string literals blanked
+    pub fn mask(code: &str, language: &str) -> String {
+        let Ok(grammar) = ParserManager::tree_sitter_language(language) else {
+            return code.to_string();
+        };
+        let mut parser = Parser::new();
+        if parser.set_language(&grammar).is_err() {
+            return code.to_string();
+        }
+        match parser.parse(code, None) {
+            Some(tree) => Self::mask_tree(tree.root_node(), code),
+            None => code.to_string(),
+        }
+    }
+
+    /// The file's content with comments and string literals blanked, the
+    /// language taken from its extension
+    pub fn mask_file(file_path: &str, code: &str) -> String {
+        match Self::language_for_path(file_path) {
+            Some(language) => Self::mask(code, language),
+            None => code.to_string(),
+        }
+    }
+
+    /// `code` with the comments and string literals of its parsed `root` blanked
+    pub fn mask_tree(root: Node<'_>, code: &str) -> String {
+        let mut masked = code.as_bytes().to_vec();
+        // Parents come before their children, so a string inside an
+        // interpolation is blanked after the interpolation is kept
+        let mut cursor = root.walk();
+        loop {
+            let node = cursor.node();
+            if node.is_named() && Self::is_comment(node.kind()) {
+                Self::blank(&mut masked, node.byte_range());
+            } else if node.is_named() && Self::is_string(node.kind()) {
+                let mut start = node.start_byte();
+                let mut children = node.walk();
+                for child in node.named_children(&mut children) {
+                    if Self::is_interpolation(child.kind()) {
+                        Self::blank(&mut masked, start..child.start_byte());
+                        start = child.end_byte();
+                    }
+                }
+                Self::blank(&mut masked, start..node.end_byte());
+            }
+
+            if cursor.goto_first_child() || cursor.goto_next_sibling() {
+                continue;
+            }
+            loop {
+                if !cursor.goto_parent() {
+                    // Only whole characters were blanked, so this never falls back
+                    return String::from_utf8(masked).unwrap_or_else(|_| code.to_string());
+                }
+                if cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+        }
+    }
+
+    fn blank(masked: &mut [u8], range: Range<usize>) {
+        for byte in masked.get_mut(range).unwrap_or_default() {
+            if *byte != b'\n' {
+                *byte = b' ';
+            }
+        }
+    }
+
+    /// `comment`, and the line, block and doc comments of some grammars
+    fn is_comment(kind: &str) -> bool {
+        kind.ends_with("comment")
+    }
+
+    fn is_string(kind: &str) -> bool {
+        matches!(
+            kind,
+            "string"
+                | "string_literal"
+                | "raw_string_literal"
+                | "template_string"
+                | "interpreted_string_literal"
+                | "verbatim_string_literal"
+                | "interpolated_string_expression"
+                | "encapsed_string"
+                | "heredoc"
+                | "text_block"
+                | "char_literal"
+                | "character_literal"
+                | "rune_literal"
+        )
+    }
+
+    /// Code embedded in a string literal
+    fn is_interpolation(kind: &str) -> bool {
+        matches!(kind, "template_substitution" | "interpolation")
+    }
+
+    fn language_for_path(file_path: &str) -> Option<&'static str> {
+        let extension = Path::new(file_path).extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "ts" | "tsx" | "mts" | "cts" => Some("typescript"),
+            "js" | "jsx" | "mjs" | "cjs" => Some("javascript"),
+            "rs" => Some("rust"),
+            "py" => Some("python"),
+            "go" => Some("go"),
+            "java" => Some("java"),
+            "c" | "h" => Some("c"),
+            "cpp" | "cc" | "cxx" | "hpp" => Some("cpp"),
+            "cs" => Some("csharp"),
+            "php" => Some("php"),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_masks_comments_and_strings_but_keeps_interpolations() {
+        let code = "// createUser() helper\nlog(`made ${makeId(\"x\")} ok`, 'Factory');\n/* é */ build();\n";
+        let masked = SourceMasker::mask(code, "typescript");
+        assert_eq!(masked.len(), code.len());
+        assert_eq!(masked.lines().count(), 3);
+        assert!(masked.contains("makeId(") && masked.contains("build();"));
+        assert!(!masked.contains("createUser") && !masked.contains("made") && !masked.contains("Factory"));
+        assert!(!masked.contains('x') && !masked.contains('é'));
+
+        let python = SourceMasker::mask_file("report.py", "# create\nprint(f\"total {make_total()}\")\n");
+        assert_eq!(python.lines().next().unwrap().trim(), "");
+        assert!(python.contains("make_total()") && !python.contains("total {"));
+
+        assert_eq!(SourceMasker::mask_file("notes.txt", "// kept"), "// kept");
+    }
+}
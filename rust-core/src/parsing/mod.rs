@@ -2,8 +2,14 @@ pub mod manager;
 pub mod tree_walker;
 pub mod fallback;
 pub mod utils;
+pub mod queries;
+pub mod file_walker;
+pub mod masking;
 
 pub use manager::*;
 pub use tree_walker::*;
 pub use fallback::*;
-pub use utils::*;
\ No newline at end of file
+pub use utils::*;
+pub use queries::*;
+pub use file_walker::*;
+pub use masking::*;
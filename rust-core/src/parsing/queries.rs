@@ -0,0 +1,287 @@
+//! Declarative tree-sitter query extraction
+//!
+//! Languages can describe their concepts with a `.scm` query instead of a
+//! node-kind matcher in Rust. Each pattern captures the declaration node as
+//! `@definition.<concept_type>` and its identifier as `@name`:
+//!
+//! ```scheme
+//! (function_declaration name: (identifier) @name) @definition.function
+//! ```
+//!
+//! Queries for several languages are bundled from `queries/`; callers can
+//! register their own to override them or to cover languages without one.
+
+use crate::parsing::ParserManager;
+use crate::types::{LineRange, ParseError, SemanticConcept};
+use std::collections::HashMap;
+use tree_sitter::{Node, Query, QueryCursor, StreamingIterator};
+
+const DEFINITION_CAPTURE_PREFIX: &str = "definition.";
+const NAME_CAPTURE: &str = "name";
+
+/// Queries shipped with the crate, keyed by language
+const BUNDLED_QUERIES: &[(&str, &str)] = &[
+    ("go", include_str!("../../queries/go.scm")),
+    ("java", include_str!("../../queries/java.scm")),
+    ("c", include_str!("../../queries/c.scm")),
+    ("cpp", include_str!("../../queries/cpp.scm")),
+    ("csharp", include_str!("../../queries/csharp.scm")),
+];
+
+/// Loads extraction queries and caches them once compiled
+#[derive(Default)]
+pub struct QueryRegistry {
+    /// User-registered query sources, which take precedence over bundled ones
+    custom_sources: HashMap<String, String>,
+    /// Compiled queries; `None` records a bundled query that failed to compile
+    compiled: HashMap<String, Option<Query>>,
+}
+
+impl QueryRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Languages with a bundled or registered query
+    pub fn languages(&self) -> Vec<String> {
+        let mut languages: Vec<String> = BUNDLED_QUERIES
+            .iter()
+            .map(|(language, _)| language.to_string())
+            .chain(self.custom_sources.keys().cloned())
+            .collect();
+        languages.sort();
+        languages.dedup();
+        languages
+    }
+
+    /// Register a query for a language, replacing any bundled or earlier query
+    pub fn register(
+        &mut self,
+        language: &str,
+        source: &str,
+        parser_manager: &ParserManager,
+    ) -> Result<(), ParseError> {
+        let query = Self::compile(language, source, parser_manager)?;
+        self.custom_sources
+            .insert(language.to_string(), source.to_string());
+        self.compiled.insert(language.to_string(), Some(query));
+        Ok(())
+    }
+
+    /// Register every `<language>.scm` file in a directory. Returns the number loaded.
+    pub fn load_dir(&mut self, dir: &str, parser_manager: &ParserManager) -> Result<u32, ParseError> {
+        let entries = std::fs::read_dir(dir).map_err(|e| {
+            ParseError::from_reason(format!("Failed to read query directory {}: {}", dir, e))
+        })?;
+
+        let mut loaded = 0;
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("scm") {
+                continue;
+            }
+            let Some(language) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let source = std::fs::read_to_string(&path).map_err(|e| {
+                ParseError::from_reason(format!("Failed to read {}: {}", path.display(), e))
+            })?;
+            self.register(language, &source, parser_manager)?;
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
+    /// Compiled query for a language, compiling and caching it on first use
+    pub fn get(&mut self, language: &str, parser_manager: &ParserManager) -> Option<&Query> {
+        if !self.compiled.contains_key(language) {
+            let query = Self::source_for(&self.custom_sources, language).and_then(|source| {
+                Self::compile(language, source, parser_manager)
+                    .map_err(|e| eprintln!("Ignoring extraction query for {}: {}", language, e))
+                    .ok()
+            });
+            self.compiled.insert(language.to_string(), query);
+        }
+        self.compiled.get(language).and_then(|q| q.as_ref())
+    }
+
+    fn source_for<'a>(custom_sources: &'a HashMap<String, String>, language: &str) -> Option<&'a str> {
+        custom_sources.get(language).map(|s| s.as_str()).or_else(|| {
+            BUNDLED_QUERIES
+                .iter()
+                .find(|(lang, _)| *lang == language)
+                .map(|(_, source)| *source)
+        })
+    }
+
+    fn compile(language: &str, source: &str, parser_manager: &ParserManager) -> Result<Query, ParseError> {
+        let ts_language = parser_manager.get_tree_sitter_language(language)?;
+        let query = Query::new(&ts_language, source).map_err(|e| {
+            ParseError::from_reason(format!("Invalid {} extraction query: {}", language, e))
+        })?;
+
+        let captures = query.capture_names();
+        if !captures.contains(&NAME_CAPTURE)
+            || !captures.iter().any(|c| c.starts_with(DEFINITION_CAPTURE_PREFIX))
+        {
+            return Err(ParseError::from_reason(format!(
+                "{} extraction query must capture @name and @definition.<type>",
+                language
+            )));
+        }
+        Ok(query)
+    }
+}
+
+/// Turns query matches into semantic concepts
+pub struct QueryExtractor;
+
+impl QueryExtractor {
+    pub fn extract(query: &Query, root: Node<'_>, file_path: &str, content: &str) -> Vec<SemanticConcept> {
+        let capture_names = query.capture_names();
+        let mut concepts = Vec::new();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(query, root, content.as_bytes());
+
+        while let Some(m) = matches.next() {
+            let mut definition = None;
+            let mut name = None;
+            for capture in m.captures {
+                let capture_name = capture_names[capture.index as usize];
+                if let Some(concept_type) = capture_name.strip_prefix(DEFINITION_CAPTURE_PREFIX) {
+                    definition = Some((concept_type, capture.node));
+                } else if capture_name == NAME_CAPTURE {
+                    name = capture.node.utf8_text(content.as_bytes()).ok();
+                }
+            }
+
+            let (Some((concept_type, node)), Some(name)) = (definition, name) else {
+                continue;
+            };
+            if name.is_empty() {
+                continue;
+            }
+
+            let mut metadata = HashMap::new();
+            metadata.insert("extractor".to_string(), "query".to_string());
+
+            concepts.push(SemanticConcept {
+                id: format!(
+                    "concept_{}",
+                    std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|d| d.as_millis())
+                        .unwrap_or(0)
+                ),
+                name: name.to_string(),
+                concept_type: concept_type.to_string(),
+                confidence: 0.8,
+                file_path: file_path.to_string(),
+                line_range: LineRange {
+                    start: node.start_position().row as u32 + 1,
+                    end: node.end_position().row as u32 + 1,
+                },
+                relationships: HashMap::new(),
+                metadata,
+            });
+        }
+
+        concepts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(registry: &mut QueryRegistry, manager: &mut ParserManager, language: &str, code: &str) -> Vec<SemanticConcept> {
+        let tree = manager.parse(code, language).unwrap();
+        let query = registry.get(language, manager).expect("query should compile");
+        QueryExtractor::extract(query, tree.root_node(), "test", code)
+    }
+
+    fn names(concepts: &[SemanticConcept]) -> Vec<String> {
+        let mut names: Vec<String> = concepts
+            .iter()
+            .map(|c| format!("{} {}", c.concept_type, c.name))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_bundled_queries_compile() {
+        let manager = ParserManager::new().unwrap();
+        let mut registry = QueryRegistry::new();
+        for (language, _) in BUNDLED_QUERIES {
+            assert!(registry.get(language, &manager).is_some(), "{} query failed to compile", language);
+        }
+    }
+
+    #[test]
+    fn test_go_query_extraction() {
+        let mut manager = ParserManager::new().unwrap();
+        let mut registry = QueryRegistry::new();
+        let code = "package main\n\ntype Server struct {}\n\nfunc (s *Server) Start() {}\n\nfunc main() {\n}\n\nvar port = 8080\n";
+
+        let concepts = extract(&mut registry, &mut manager, "go", code);
+        assert_eq!(
+            names(&concepts),
+            vec!["function Start", "function main", "struct Server", "variable port"]
+        );
+        let main = concepts.iter().find(|c| c.name == "main").unwrap();
+        assert_eq!((main.line_range.start, main.line_range.end), (7, 8));
+        assert_eq!(main.metadata.get("extractor"), Some(&"query".to_string()));
+    }
+
+    #[test]
+    fn test_java_query_extraction() {
+        let mut manager = ParserManager::new().unwrap();
+        let mut registry = QueryRegistry::new();
+        let code = "public class Greeter {\n  private String name;\n  public Greeter() {}\n  public void greet() {}\n}\n";
+
+        let concepts = extract(&mut registry, &mut manager, "java", code);
+        assert_eq!(
+            names(&concepts),
+            vec!["class Greeter", "function Greeter", "function greet", "variable name"]
+        );
+    }
+
+    #[test]
+    fn test_registered_query_overrides_bundled() {
+        let mut manager = ParserManager::new().unwrap();
+        let mut registry = QueryRegistry::new();
+        registry
+            .register("go", "(function_declaration name: (identifier) @name) @definition.entrypoint", &manager)
+            .unwrap();
+
+        let concepts = extract(&mut registry, &mut manager, "go", "package main\nfunc main() {}\ntype T struct {}\n");
+        assert_eq!(names(&concepts), vec!["entrypoint main"]);
+    }
+
+    #[test]
+    fn test_register_rejects_invalid_queries() {
+        let manager = ParserManager::new().unwrap();
+        let mut registry = QueryRegistry::new();
+
+        assert!(registry.register("go", "(not_a_node) @name", &manager).is_err());
+        assert!(registry.register("go", "(function_declaration) @definition.function", &manager).is_err());
+        assert!(registry.register("klingon", "(x) @name", &manager).is_err());
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let manager = ParserManager::new().unwrap();
+        let mut registry = QueryRegistry::new();
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("rust.scm"),
+            "(function_item name: (identifier) @name) @definition.function",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.md"), "not a query").unwrap();
+
+        assert_eq!(registry.load_dir(dir.path().to_str().unwrap(), &manager).unwrap(), 1);
+        assert!(registry.languages().contains(&"rust".to_string()));
+    }
+}
@@ -152,6 +152,24 @@ impl NameExtractor {
             .filter(|c| c.is_alphanumeric() || *c == '_')
             .collect()
     }
+
+    /// Text following the first occurrence of `keyword`, split on a char boundary
+    pub fn text_after<'a>(text: &'a str, keyword: &str) -> Option<&'a str> {
+        text.split_once(keyword).map(|(_, rest)| rest)
+    }
+
+    /// Text up to the first character matching `delimiter`, or the whole text if none matches
+    pub fn text_until(text: &str, delimiter: impl Fn(char) -> bool) -> &str {
+        text.split(delimiter).next().unwrap_or(text)
+    }
+
+    /// Identifier following `keyword` and terminated by `terminator`, e.g. `fn name(`
+    pub fn identifier_between(text: &str, keyword: &str, terminator: char) -> Option<String> {
+        let rest = Self::text_after(text, keyword)?;
+        let (candidate, _) = rest.split_once(terminator)?;
+        let name = candidate.trim();
+        Self::is_valid_identifier(name).then(|| name.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -384,4 +402,16 @@ mod tests {
         assert!(identifiers.contains(&"UserService".to_string()));
         assert!(identifiers.contains(&"getName".to_string()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_text_helpers_respect_char_boundaries() {
+        assert_eq!(NameExtractor::text_after("fn ünïcødé(x)", "fn "), Some("ünïcødé(x)"));
+        assert_eq!(NameExtractor::text_after("héllo", "fn "), None);
+        assert_eq!(NameExtractor::text_until("Ωmega {", char::is_whitespace), "Ωmega");
+        assert_eq!(
+            NameExtractor::identifier_between("function größe() {}", "function ", '('),
+            Some("größe".to_string())
+        );
+        assert_eq!(NameExtractor::identifier_between("function 日本 語(", "function ", '('), None);
+    }
+}
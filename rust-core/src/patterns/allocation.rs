@@ -0,0 +1,391 @@
+//! Allocation-heavy Rust code
+//!
+//! A line-based pass over Rust sources that flags code which allocates more
+//! than it needs to, and turns each kind of finding into a performance
+//! recommendation:
+//! - `.clone()` inside a loop body
+//! - a `Vec` built with `collect()` whose only use is being iterated again
+//! - large arrays placed on the stack
+//! - `Box`/`Rc`/`Arc` allocated on every loop iteration
+//!
+//! `#[cfg(test)]` modules are skipped, since test code trades allocations
+//! for readability on purpose.
+
+use crate::patterns::types::{Pattern, PatternExample};
+use crate::patterns::violations::Suppressions;
+use crate::types::LineRange;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// Maximum number of examples kept per allocation pattern
+const MAX_ALLOCATION_EXAMPLES: usize = 5;
+
+/// Stack arrays at or above this many bytes are reported
+const LARGE_ARRAY_BYTES: u64 = 16 * 1024;
+
+/// How far past a `let` line a multi-line `collect()` chain is followed
+const MAX_STATEMENT_LINES: usize = 12;
+
+/// Prefix of the ids of patterns produced by this pass
+const PATTERN_PREFIX: &str = "performance_allocation_";
+
+/// One allocation-heavy spot
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllocationFinding {
+    /// 'clone_in_loop', 'collect_then_iterate', 'large_stack_array' or 'smart_pointer_in_loop'
+    pub kind: String,
+    pub file_path: String,
+    pub line: u32,
+    pub code: String,
+}
+
+/// A `for`/`while`/`loop`/`for_each` body
+struct LoopScope {
+    /// Brace depth the loop header was found at
+    level: usize,
+    /// The body's opening brace has been seen
+    opened: bool,
+}
+
+pub struct AllocationAnalyzer;
+
+impl AllocationAnalyzer {
+    /// Whether files with this extension are read by the pass
+    pub fn supports_extension(extension: &str) -> bool {
+        extension == "rs"
+    }
+
+    /// Allocation-heavy spots in one Rust file, minus those silenced with an
+    /// `in-memoria-disable-next-line` comment
+    pub fn analyze_file(file_path: &str, content: &str) -> Vec<AllocationFinding> {
+        let p = patterns();
+        let lines: Vec<&str> = content.lines().collect();
+        let mut findings = Vec::new();
+        let mut loops: Vec<LoopScope> = Vec::new();
+        let mut depth = 0usize;
+        let mut pending_test_module = false;
+        let mut test_module: Option<usize> = None;
+
+        for (idx, line) in lines.iter().enumerate() {
+            let trimmed = line.trim_start();
+            let opens = line.matches('{').count();
+            let closes = line.matches('}').count();
+
+            if trimmed.starts_with("#[cfg(test)]") {
+                pending_test_module = true;
+            } else if pending_test_module && p.module.is_match(line) {
+                pending_test_module = false;
+                test_module = Some(depth);
+            } else if !trimmed.starts_with("#[") && !trimmed.is_empty() {
+                pending_test_module = false;
+            }
+
+            let skip = test_module.is_some() || trimmed.starts_with("//");
+            if !skip {
+                // Lines of a loop header run once, so only opened bodies count
+                let in_loop = loops.iter().any(|l| l.opened);
+                if in_loop {
+                    if p.clone.is_match(line) && !p.shared_clone.is_match(line) {
+                        findings.push(Self::found("clone_in_loop", file_path, idx, line));
+                    }
+                    if p.smart_pointer.is_match(line) {
+                        findings.push(Self::found("smart_pointer_in_loop", file_path, idx, line));
+                    }
+                }
+                if Self::has_large_array(line) {
+                    findings.push(Self::found("large_stack_array", file_path, idx, line));
+                }
+                if Self::collect_then_iterate(&lines, idx) {
+                    findings.push(Self::found("collect_then_iterate", file_path, idx, line));
+                }
+                if p.loop_header.is_match(line) {
+                    loops.push(LoopScope { level: depth, opened: false });
+                }
+            }
+
+            depth = (depth + opens).saturating_sub(closes);
+
+            if test_module.is_some_and(|level| depth <= level && closes > 0) {
+                test_module = None;
+            }
+            while let Some(scope) = loops.last_mut() {
+                if !scope.opened && depth > scope.level {
+                    scope.opened = true;
+                }
+                if scope.opened && depth <= scope.level {
+                    loops.pop();
+                } else if !scope.opened && (opens > 0 || line.trim_end().ends_with(';')) {
+                    // Body closed on the header line, or a one-line `for_each`
+                    loops.pop();
+                } else {
+                    break;
+                }
+            }
+        }
+
+        let suppressions = Suppressions::parse(content);
+        if !suppressions.is_empty() {
+            findings.retain(|f| !suppressions.is_suppressed(f.line, &f.kind));
+        }
+        findings
+    }
+
+    /// One `performance_violation` pattern per kind of finding
+    pub fn to_patterns(findings: &[AllocationFinding]) -> Vec<Pattern> {
+        let mut groups: BTreeMap<&str, Vec<&AllocationFinding>> = BTreeMap::new();
+        for finding in findings {
+            groups.entry(finding.kind.as_str()).or_default().push(finding);
+        }
+
+        groups
+            .into_iter()
+            .map(|(kind, matches)| Pattern {
+                id: format!("{}{}", PATTERN_PREFIX, kind),
+                pattern_type: "performance_violation".to_string(),
+                description: format!("[info] {}", Self::describe(kind)),
+                frequency: matches.len() as u32,
+                // Found by line heuristics rather than type information
+                confidence: 0.7,
+                examples: matches
+                    .iter()
+                    .take(MAX_ALLOCATION_EXAMPLES)
+                    .map(|m| PatternExample {
+                        code: m.code.clone(),
+                        file_path: m.file_path.clone(),
+                        line_range: LineRange { start: m.line, end: m.line },
+                    })
+                    .collect(),
+                contexts: vec!["performance".to_string(), "severity:info".to_string()],
+            })
+            .collect()
+    }
+
+    /// Performance advice for a pattern produced by this pass, pointing at its first example
+    pub fn recommendation(pattern: &Pattern) -> Option<String> {
+        let advice = Self::advice(pattern.id.strip_prefix(PATTERN_PREFIX)?)?;
+        let location = pattern
+            .examples
+            .first()
+            .map(|e| format!(" (e.g. {}:{})", e.file_path, e.line_range.start))
+            .unwrap_or_default();
+        Some(format!("{}{} - {} occurrences", advice, location, pattern.frequency))
+    }
+
+    /// How to fix one kind of finding
+    pub fn advice(kind: &str) -> Option<&'static str> {
+        match kind {
+            "clone_in_loop" => Some("Hoist clones out of loops or borrow instead of cloning on every iteration"),
+            "collect_then_iterate" => {
+                Some("Iterate the iterator directly instead of collecting into a Vec that is only iterated again")
+            }
+            "large_stack_array" => {
+                Some("Move large arrays to the heap (vec! or Box<[T]>) to avoid stack overflows and costly moves")
+            }
+            "smart_pointer_in_loop" => {
+                Some("Allocate Box/Rc/Arc values once outside the loop, or reuse them, to reduce allocator churn")
+            }
+            _ => None,
+        }
+    }
+
+    fn describe(kind: &str) -> &'static str {
+        match kind {
+            "clone_in_loop" => "clone() inside a loop",
+            "collect_then_iterate" => "Vec collected only to be iterated again",
+            "large_stack_array" => "large array on the stack",
+            "smart_pointer_in_loop" => "Box/Rc/Arc allocated inside a loop",
+            _ => "allocation-heavy code",
+        }
+    }
+
+    fn found(kind: &str, file_path: &str, idx: usize, line: &str) -> AllocationFinding {
+        AllocationFinding {
+            kind: kind.to_string(),
+            file_path: file_path.to_string(),
+            line: idx as u32 + 1,
+            code: line.trim().to_string(),
+        }
+    }
+
+    /// An array repeat expression or type whose size reaches `LARGE_ARRAY_BYTES`;
+    /// `const`/`static` items and `vec![...]` live elsewhere and are ignored
+    fn has_large_array(line: &str) -> bool {
+        let p = patterns();
+        if p.static_item.is_match(line) || line.contains("vec![") {
+            return false;
+        }
+        p.array.captures_iter(line).any(|c| {
+            let len: u64 = c[2].replace('_', "").parse().unwrap_or(0);
+            len.saturating_mul(Self::element_size(c[1].trim())) >= LARGE_ARRAY_BYTES
+        })
+    }
+
+    /// Byte size of a primitive element type or suffixed literal, 8 when unknown
+    fn element_size(element: &str) -> u64 {
+        let ty = element.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.' || c == '_' || c == '-');
+        match ty {
+            "u8" | "i8" | "bool" => 1,
+            "u16" | "i16" => 2,
+            "u32" | "i32" | "f32" | "char" => 4,
+            "u128" | "i128" => 16,
+            _ => 8,
+        }
+    }
+
+    /// A `let` binding collected into a `Vec` whose only later use is a loop or `.iter()`
+    fn collect_then_iterate(lines: &[&str], idx: usize) -> bool {
+        let p = patterns();
+        let Some(c) = p.binding.captures(lines[idx]) else {
+            return false;
+        };
+        let name = &c[1];
+        let annotated_vec = c.get(2).is_some_and(|t| t.as_str().trim_start().starts_with("Vec"));
+
+        // Follow the statement to its `;`
+        let mut end = idx;
+        let mut statement = lines[idx].to_string();
+        while !statement.trim_end().ends_with(';') && end + 1 < lines.len() && end - idx < MAX_STATEMENT_LINES {
+            end += 1;
+            statement.push_str(lines[end]);
+        }
+        let collects_vec = statement.contains(".collect::<Vec") || (annotated_vec && statement.contains(".collect()"));
+        if !collects_vec {
+            return false;
+        }
+
+        let usage = Regex::new(&format!(r"\b{}\b", regex::escape(name))).expect("valid allocation regex");
+        let iteration = Regex::new(&format!(
+            r"\bin\s+(?:&(?:mut\s+)?)?{0}\s*\{{|\b{0}\.(?:iter|iter_mut|into_iter)\(\)",
+            regex::escape(name)
+        ))
+        .expect("valid allocation regex");
+
+        // Uses until the block the binding lives in closes
+        let mut depth = 0i64;
+        let mut uses = Vec::new();
+        for line in &lines[end + 1..] {
+            if usage.is_match(line) {
+                uses.push(*line);
+            }
+            depth += line.matches('{').count() as i64 - line.matches('}').count() as i64;
+            if depth < 0 {
+                break;
+            }
+        }
+        uses.len() == 1 && iteration.is_match(uses[0])
+    }
+}
+
+struct Patterns {
+    loop_header: Regex,
+    clone: Regex,
+    shared_clone: Regex,
+    smart_pointer: Regex,
+    array: Regex,
+    static_item: Regex,
+    binding: Regex,
+    module: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let re = |pattern: &str| Regex::new(pattern).expect("valid allocation regex");
+        Patterns {
+            loop_header: re(r"^\s*(?:'\w+:\s*)?(?:for\s.+\sin\s|while\s|loop\s*\{)|\.for_each\(\s*(?:move\s+)?\|"),
+            clone: re(r"\.clone\(\)"),
+            // Reference-count bumps are cheap and usually intentional
+            shared_clone: re(r"\b(?:Rc|Arc)::clone\("),
+            smart_pointer: re(r"\b(?:Box|Rc|Arc)::new\("),
+            array: re(r"\[\s*([^\[\];]+?)\s*;\s*(\d[\d_]*)\s*\]"),
+            static_item: re(r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:const|static)\s"),
+            binding: re(r"^\s*let\s+(?:mut\s+)?(\w+)\s*(?::\s*([^=]+))?="),
+            module: re(r"^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+\w+\s*\{"),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(findings: &[AllocationFinding]) -> Vec<(&str, u32)> {
+        findings.iter().map(|f| (f.kind.as_str(), f.line)).collect()
+    }
+
+    #[test]
+    fn test_loop_allocations() {
+        let source = "\
+fn render(items: &[Item], config: &Config) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    for item in items.clone() {
+        let settings = config.clone();
+        let shared = Arc::clone(&settings.theme);
+        nodes.push(Box::new(Node::new(item, settings)));
+    }
+    let owned = config.clone();
+    nodes
+}
+";
+        let findings = AllocationAnalyzer::analyze_file("src/render.rs", source);
+        assert_eq!(kinds(&findings), vec![("clone_in_loop", 4), ("smart_pointer_in_loop", 6)]);
+    }
+
+    #[test]
+    fn test_collect_and_stack_arrays() {
+        let source = "\
+fn total(values: &[u32]) -> u32 {
+    let doubled: Vec<u32> = values
+        .iter()
+        .map(|v| v * 2)
+        .collect();
+    let mut sum = 0;
+    for v in &doubled {
+        sum += v;
+    }
+    let kept = values.iter().copied().collect::<Vec<_>>();
+    println!(\"{}\", kept.len());
+    for v in kept.iter() {
+        sum += v;
+    }
+    let buffer = [0u8; 65536];
+    let small = [0u64; 16];
+    sum
+}
+
+const TABLE: [u8; 65536] = [0; 65536];
+
+#[cfg(test)]
+mod tests {
+    fn fixture() {
+        for _ in 0..3 {
+            let copy = VALUES.clone();
+        }
+    }
+}
+";
+        let findings = AllocationAnalyzer::analyze_file("src/total.rs", source);
+        assert_eq!(kinds(&findings), vec![("collect_then_iterate", 2), ("large_stack_array", 15)]);
+    }
+
+    #[test]
+    fn test_patterns_and_recommendations() {
+        let source = "fn run(jobs: &[Job]) {\n    loop {\n        let job = jobs[0].clone();\n    }\n}\n";
+        let findings = AllocationAnalyzer::analyze_file("src/run.rs", source);
+        let patterns = AllocationAnalyzer::to_patterns(&findings);
+
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].id, "performance_allocation_clone_in_loop");
+        assert_eq!(patterns[0].pattern_type, "performance_violation");
+        assert_eq!(patterns[0].description, "[info] clone() inside a loop");
+
+        let advice = AllocationAnalyzer::recommendation(&patterns[0]).unwrap();
+        assert!(advice.starts_with("Hoist clones out of loops"));
+        assert!(advice.contains("src/run.rs:3"));
+        assert!(!AllocationAnalyzer::supports_extension("py"));
+
+        let suppressed = source.replace("        let job", "        // in-memoria-disable-next-line clone_in_loop\n        let job");
+        assert!(AllocationAnalyzer::analyze_file("src/run.rs", &suppressed).is_empty());
+    }
+}
@@ -0,0 +1,475 @@
+//! Complexity budgets
+//!
+//! Teams set upper bounds for cyclomatic complexity, cognitive complexity and
+//! function and file length, with tighter or looser budgets for some
+//! directories. Functions are measured on their tree-sitter tree; a nested
+//! named function is measured on its own rather than adding to the function
+//! around it. Overruns are reported as [`Violation`]s, so they can be
+//! silenced with `in-memoria-disable-next-line` comments and disabled with
+//! `rules.disabled` like any other finding.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::patterns::violations::{Suppressions, Violation};
+use crate::types::validation::{ErrorCode, ValidationError};
+use crate::types::{LineRange, ParseError};
+use tree_sitter::{Node, Tree};
+
+/// Rule id of functions over the cyclomatic complexity budget
+pub const CYCLOMATIC_RULE: &str = "max_cyclomatic_complexity";
+/// Rule id of functions over the cognitive complexity budget
+pub const COGNITIVE_RULE: &str = "max_cognitive_complexity";
+/// Rule id of functions longer than the budget
+pub const FUNCTION_LINES_RULE: &str = "max_function_lines";
+/// Rule id of files longer than the budget
+pub const FILE_LINES_RULE: &str = "max_file_lines";
+
+const SEVERITIES: &[&str] = &["info", "warning", "error"];
+
+/// Named function and method declarations across the supported grammars
+const FUNCTION_KINDS: &[&str] = &[
+    "function_declaration",
+    "generator_function_declaration",
+    "method_definition",
+    "function_item",
+    "function_definition",
+    "method_declaration",
+    "constructor_declaration",
+];
+
+/// Function values, measured on their own when bound to a name
+const FUNCTION_VALUE_KINDS: &[&str] = &["arrow_function", "function_expression", "function"];
+
+/// Branches and loops: one path each for cyclomatic complexity, and an
+/// increment growing with nesting for cognitive complexity
+const BRANCH_KINDS: &[&str] = &[
+    "if_statement",
+    "if_expression",
+    "while_statement",
+    "while_expression",
+    "do_statement",
+    "for_statement",
+    "for_in_statement",
+    "for_expression",
+    "enhanced_for_statement",
+    "catch_clause",
+    "except_clause",
+    "ternary_expression",
+    "conditional_expression",
+];
+
+/// Each case is one more path; the switch itself adds one to cognitive complexity
+const CASE_KINDS: &[&str] = &["switch_case", "case_clause", "match_arm", "switch_block_statement_group", "expression_case"];
+const SWITCH_KINDS: &[&str] = &["switch_statement", "match_expression", "expression_switch_statement"];
+
+/// Upper bounds; unset fields are not enforced
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ComplexityBudget {
+    pub max_cyclomatic: Option<u32>,
+    pub max_cognitive: Option<u32>,
+    pub max_function_lines: Option<u32>,
+    pub max_file_lines: Option<u32>,
+}
+
+impl ComplexityBudget {
+    /// This budget with the fields `other` sets replaced
+    fn overridden_by(&self, other: &ComplexityBudget) -> ComplexityBudget {
+        ComplexityBudget {
+            max_cyclomatic: other.max_cyclomatic.or(self.max_cyclomatic),
+            max_cognitive: other.max_cognitive.or(self.max_cognitive),
+            max_function_lines: other.max_function_lines.or(self.max_function_lines),
+            max_file_lines: other.max_file_lines.or(self.max_file_lines),
+        }
+    }
+
+    fn validate(&self, scope: &str) -> Result<(), ParseError> {
+        let limits = [
+            ("maxCyclomatic", self.max_cyclomatic),
+            ("maxCognitive", self.max_cognitive),
+            ("maxFunctionLines", self.max_function_lines),
+            ("maxFileLines", self.max_file_lines),
+        ];
+        match limits.iter().find(|(_, limit)| *limit == Some(0)) {
+            Some((name, _)) => Err(invalid(format!("{} of {} must be at least 1", name, scope))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A budget replacing some limits for the files under a directory
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct DirectoryBudget {
+    /// Directory relative to the project root, e.g. `src/legacy`
+    pub directory: String,
+    pub budget: ComplexityBudget,
+}
+
+/// Budgets enforced by `check_complexity_budgets`
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ComplexityThresholds {
+    /// Limits for the whole project
+    pub defaults: ComplexityBudget,
+    /// Limits for directories; the deepest directory containing a file wins
+    pub overrides: Option<Vec<DirectoryBudget>>,
+    /// Severity of the violations: 'info', 'warning' (default) or 'error'
+    pub severity: Option<String>,
+}
+
+impl ComplexityThresholds {
+    pub fn validate(&self) -> Result<(), ParseError> {
+        self.defaults.validate("the defaults")?;
+        for directory in self.overrides.iter().flatten() {
+            let normalized = Self::normalize_directory(&directory.directory);
+            if normalized.is_empty()
+                || directory.directory.starts_with('/')
+                || normalized.split('/').any(|part| part == "..")
+            {
+                return Err(invalid(format!(
+                    "Override directory '{}' must be a directory inside the project",
+                    directory.directory
+                )));
+            }
+            directory.budget.validate(&format!("'{}'", directory.directory))?;
+        }
+        if let Some(severity) = &self.severity {
+            if !SEVERITIES.contains(&severity.as_str()) {
+                return Err(invalid(format!(
+                    "Unknown severity '{}'; expected info, warning or error",
+                    severity
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Budget for a file, from the defaults and the overrides of the
+    /// directories containing it, shallowest first
+    pub fn budget_for(&self, relative_path: &str) -> ComplexityBudget {
+        let path = relative_path.replace('\\', "/");
+        let mut matching: Vec<(usize, &DirectoryBudget)> = self
+            .overrides
+            .iter()
+            .flatten()
+            .filter_map(|directory| {
+                let normalized = Self::normalize_directory(&directory.directory);
+                let inside = path.strip_prefix(&normalized).is_some_and(|rest| rest.starts_with('/'));
+                inside.then(|| (normalized.split('/').count(), directory))
+            })
+            .collect();
+        matching.sort_by_key(|(depth, _)| *depth);
+        matching
+            .into_iter()
+            .fold(self.defaults.clone(), |budget, (_, directory)| budget.overridden_by(&directory.budget))
+    }
+
+    fn severity(&self) -> String {
+        self.severity.clone().unwrap_or_else(|| "warning".to_string())
+    }
+
+    fn normalize_directory(directory: &str) -> String {
+        directory.replace('\\', "/").trim_start_matches("./").trim_matches('/').to_string()
+    }
+}
+
+/// Size and complexity of one function
+#[derive(Debug, Clone)]
+pub struct FunctionMetrics {
+    pub name: String,
+    pub line_range: LineRange,
+    pub cyclomatic: u32,
+    pub cognitive: u32,
+    pub lines: u32,
+}
+
+pub struct ComplexityBudgetChecker;
+
+impl ComplexityBudgetChecker {
+    /// Every named function of a parsed file, in source order
+    pub fn measure_functions(tree: &Tree, content: &str) -> Vec<FunctionMetrics> {
+        let mut functions = Vec::new();
+        Self::collect_functions(tree.root_node(), content, &mut functions);
+        functions
+    }
+
+    /// Violations of the file's budget, minus those silenced with an
+    /// `in-memoria-disable-next-line` comment
+    pub fn check_file(
+        thresholds: &ComplexityThresholds,
+        file_path: &str,
+        relative_path: &str,
+        content: &str,
+        functions: &[FunctionMetrics],
+    ) -> Vec<Violation> {
+        let budget = thresholds.budget_for(relative_path);
+        let severity = thresholds.severity();
+        let mut violations = Vec::new();
+        let mut report = |rule_id: &str, line_range: LineRange, message: String| {
+            violations.push(Violation {
+                rule_id: rule_id.to_string(),
+                severity: severity.clone(),
+                file_path: file_path.to_string(),
+                line_range,
+                message,
+            });
+        };
+
+        let file_lines = content.lines().count() as u32;
+        if let Some(max) = budget.max_file_lines.filter(|max| file_lines > *max) {
+            report(
+                FILE_LINES_RULE,
+                LineRange { start: 1, end: file_lines },
+                format!("File has {} lines (budget {})", file_lines, max),
+            );
+        }
+
+        for function in functions {
+            let checks = [
+                (CYCLOMATIC_RULE, "cyclomatic complexity", function.cyclomatic, budget.max_cyclomatic),
+                (COGNITIVE_RULE, "cognitive complexity", function.cognitive, budget.max_cognitive),
+                (FUNCTION_LINES_RULE, "lines", function.lines, budget.max_function_lines),
+            ];
+            for (rule_id, measure, value, max) in checks {
+                if let Some(max) = max.filter(|max| value > *max) {
+                    let message = match rule_id {
+                        FUNCTION_LINES_RULE => format!("'{}' has {} lines (budget {})", function.name, value, max),
+                        _ => format!("'{}' has {} {} (budget {})", function.name, measure, value, max),
+                    };
+                    report(rule_id, function.line_range.clone(), message);
+                }
+            }
+        }
+
+        let suppressions = Suppressions::parse(content);
+        violations.retain(|v| !suppressions.is_suppressed(v.line_range.start, &v.rule_id));
+        violations
+    }
+
+    /// How to bring a function or file back under one of the budgets
+    pub fn advice(rule_id: &str) -> Option<&'static str> {
+        match rule_id {
+            CYCLOMATIC_RULE | COGNITIVE_RULE => {
+                Some("Extract branches into well-named helper functions or replace conditionals with early returns")
+            }
+            FUNCTION_LINES_RULE => Some("Split the function into smaller functions with a single responsibility"),
+            FILE_LINES_RULE => Some("Split the file into modules along its responsibilities"),
+            _ => None,
+        }
+    }
+
+    fn collect_functions(node: Node, content: &str, functions: &mut Vec<FunctionMetrics>) {
+        if let Some(name) = Self::function_name(node, content) {
+            let mut cyclomatic = 1;
+            let mut cognitive = 0;
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                Self::score(child, content, 0, &mut cyclomatic, &mut cognitive);
+            }
+            functions.push(FunctionMetrics {
+                name,
+                line_range: LineRange {
+                    start: node.start_position().row as u32 + 1,
+                    end: node.end_position().row as u32 + 1,
+                },
+                cyclomatic,
+                cognitive,
+                lines: (node.end_position().row - node.start_position().row) as u32 + 1,
+            });
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            Self::collect_functions(child, content, functions);
+        }
+    }
+
+    /// Add a node's paths and cognitive increments, stopping at nested named
+    /// functions since they are measured on their own
+    fn score(node: Node, content: &str, nesting: u32, cyclomatic: &mut u32, cognitive: &mut u32) {
+        if Self::function_name(node, content).is_some() {
+            return;
+        }
+
+        let kind = node.kind();
+        let mut child_nesting = nesting;
+        if BRANCH_KINDS.contains(&kind) {
+            *cyclomatic += 1;
+            // `else if` continues the chain rather than nesting in it
+            let chained = node.parent().is_some_and(|parent| parent.kind() == "else_clause");
+            if chained {
+                *cognitive += 1;
+            } else {
+                *cognitive += 1 + nesting;
+                child_nesting = nesting + 1;
+            }
+        } else if CASE_KINDS.contains(&kind) {
+            *cyclomatic += 1;
+        } else if SWITCH_KINDS.contains(&kind) {
+            *cognitive += 1 + nesting;
+            child_nesting = nesting + 1;
+        } else if matches!(kind, "elif_clause" | "else_clause") {
+            *cognitive += 1;
+            if kind == "elif_clause" {
+                *cyclomatic += 1;
+            }
+        } else if Self::is_logical_operator(node, content) {
+            *cyclomatic += 1;
+            *cognitive += 1;
+        } else if FUNCTION_VALUE_KINDS.contains(&kind) || kind == "closure_expression" || kind == "lambda" {
+            child_nesting = nesting + 1;
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            Self::score(child, content, child_nesting, cyclomatic, cognitive);
+        }
+    }
+
+    fn is_logical_operator(node: Node, content: &str) -> bool {
+        match node.kind() {
+            "boolean_operator" => true,
+            "binary_expression" => node
+                .child_by_field_name("operator")
+                .and_then(|operator| operator.utf8_text(content.as_bytes()).ok())
+                .is_some_and(|operator| matches!(operator, "&&" | "||" | "??")),
+            _ => false,
+        }
+    }
+
+    /// Name of a function declaration, or of a function value bound to a
+    /// variable, property or field; `None` for other nodes
+    fn function_name(node: Node, content: &str) -> Option<String> {
+        let kind = node.kind();
+        let named = if FUNCTION_KINDS.contains(&kind) {
+            node.child_by_field_name("name").or_else(|| Self::declarator_name(node))
+        } else if FUNCTION_VALUE_KINDS.contains(&kind) {
+            let parent = node.parent()?;
+            match parent.kind() {
+                "variable_declarator" => parent.child_by_field_name("name"),
+                "pair" => parent.child_by_field_name("key"),
+                "public_field_definition" | "field_definition" => parent
+                    .child_by_field_name("name")
+                    .or_else(|| parent.child_by_field_name("property")),
+                _ => None,
+            }
+        } else {
+            None
+        }?;
+        named.utf8_text(content.as_bytes()).ok().map(str::to_string)
+    }
+
+    /// Identifier inside a C/C++ style `declarator` chain
+    fn declarator_name(node: Node) -> Option<Node> {
+        let mut current = node.child_by_field_name("declarator")?;
+        while let Some(inner) = current.child_by_field_name("declarator") {
+            current = inner;
+        }
+        Some(current)
+    }
+}
+
+fn invalid(message: String) -> ParseError {
+    ValidationError::new(ErrorCode::InvalidArgument, message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_typescript(source: &str) -> Tree {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    const SOURCE: &str = "\
+export function route(req, res) {
+  if (req.user && req.user.admin) {
+    for (const item of req.items) {
+      if (item.ok) {
+        res.push(item);
+      } else if (item.retry) {
+        res.retry(item);
+      }
+    }
+  }
+  const pick = (x) => x > 1 ? x : 1;
+  return res;
+}
+
+function small() {
+  return 1;
+}
+";
+
+    #[test]
+    fn test_measures_each_named_function() {
+        let functions = ComplexityBudgetChecker::measure_functions(&parse_typescript(SOURCE), SOURCE);
+        let summary: Vec<(&str, u32, u32, u32)> = functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.cyclomatic, f.cognitive, f.lines))
+            .collect();
+        // route: if, &&, for, if, else if -> 6 paths; cognitive 1 + 1 + 2 + 3 + 1 (else) + 1 (else if)
+        // pick is measured on its own: one ternary
+        assert_eq!(summary, vec![("route", 6, 9, 13), ("pick", 2, 1, 1), ("small", 1, 0, 3)]);
+    }
+
+    #[test]
+    fn test_directory_overrides_and_violations() {
+        let thresholds = ComplexityThresholds {
+            defaults: ComplexityBudget { max_cyclomatic: Some(5), max_function_lines: Some(20), ..Default::default() },
+            overrides: Some(vec![
+                DirectoryBudget {
+                    directory: "src/legacy".to_string(),
+                    budget: ComplexityBudget { max_cyclomatic: Some(10), ..Default::default() },
+                },
+                DirectoryBudget {
+                    directory: "./src/legacy/core/".to_string(),
+                    budget: ComplexityBudget { max_function_lines: Some(5), ..Default::default() },
+                },
+            ]),
+            severity: Some("error".to_string()),
+        };
+        thresholds.validate().unwrap();
+        let core = thresholds.budget_for("src/legacy/core/router.ts");
+        assert_eq!((core.max_cyclomatic, core.max_function_lines), (Some(10), Some(5)));
+        assert_eq!(thresholds.budget_for("src/legacyish/a.ts").max_cyclomatic, Some(5));
+
+        let functions = ComplexityBudgetChecker::measure_functions(&parse_typescript(SOURCE), SOURCE);
+        let violations =
+            ComplexityBudgetChecker::check_file(&thresholds, "/repo/src/app.ts", "src/app.ts", SOURCE, &functions);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule_id, CYCLOMATIC_RULE);
+        assert_eq!(violations[0].severity, "error");
+        assert_eq!(violations[0].message, "'route' has cyclomatic complexity 6 (budget 5)");
+
+        let violations =
+            ComplexityBudgetChecker::check_file(&thresholds, "core/router.ts", "src/legacy/core/router.ts", SOURCE, &functions);
+        assert_eq!(violations.iter().map(|v| v.rule_id.as_str()).collect::<Vec<_>>(), vec![FUNCTION_LINES_RULE]);
+
+        let suppressed = format!("// in-memoria-disable-next-line {}\n{}", CYCLOMATIC_RULE, SOURCE);
+        let functions = ComplexityBudgetChecker::measure_functions(&parse_typescript(&suppressed), &suppressed);
+        assert!(ComplexityBudgetChecker::check_file(&thresholds, "a.ts", "src/app.ts", &suppressed, &functions).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_thresholds_are_rejected() {
+        let zero = ComplexityThresholds {
+            defaults: ComplexityBudget { max_cognitive: Some(0), ..Default::default() },
+            ..Default::default()
+        };
+        assert!(zero.validate().is_err());
+
+        let outside = ComplexityThresholds {
+            overrides: Some(vec![DirectoryBudget { directory: "../shared".to_string(), budget: ComplexityBudget::default() }]),
+            ..Default::default()
+        };
+        assert!(outside.validate().unwrap_err().to_string().contains("INVALID_ARGUMENT"));
+
+        let severity = ComplexityThresholds { severity: Some("fatal".to_string()), ..Default::default() };
+        assert!(severity.validate().is_err());
+    }
+}
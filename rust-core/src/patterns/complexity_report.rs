@@ -0,0 +1,227 @@
+//! Complexity distribution of a codebase
+//!
+//! An average hides the few functions that make code hard to change, and two
+//! averages say little about what a refactoring did. The report built here
+//! gives the median, 90th percentile and maximum of cyclomatic complexity,
+//! cognitive complexity and function length, for the whole codebase and per
+//! language and directory, plus the most complex functions. Functions are
+//! measured as for complexity budgets (see
+//! [`ComplexityBudgetChecker::measure_functions`](crate::patterns::ComplexityBudgetChecker::measure_functions)).
+//!
+//! Percentiles use the nearest-rank method, so each is the measure of an
+//! actual function and reports from before and after a change compare
+//! directly.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::patterns::budgets::FunctionMetrics;
+use crate::types::LineRange;
+use std::collections::BTreeMap;
+
+/// Functions listed in [`ComplexityReport::top_offenders`]
+const TOP_OFFENDER_LIMIT: usize = 20;
+
+/// Percentiles of one measure over a set of functions
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct MetricDistribution {
+    pub p50: u32,
+    pub p90: u32,
+    pub max: u32,
+    pub mean: f64,
+}
+
+impl MetricDistribution {
+    fn of(mut values: Vec<u32>) -> Self {
+        if values.is_empty() {
+            return MetricDistribution::default();
+        }
+        values.sort_unstable();
+        let rank = |percentile: usize| values[(values.len() * percentile).div_ceil(100).max(1) - 1];
+        MetricDistribution {
+            p50: rank(50),
+            p90: rank(90),
+            max: values[values.len() - 1],
+            mean: values.iter().map(|value| *value as f64).sum::<f64>() / values.len() as f64,
+        }
+    }
+}
+
+/// Complexity distribution of the functions of a language, a directory or
+/// the whole codebase
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ComplexityGroup {
+    /// Language, directory relative to the project root ("." for the root
+    /// itself), or "all"
+    pub key: String,
+    pub file_count: u32,
+    pub function_count: u32,
+    pub cyclomatic: MetricDistribution,
+    pub cognitive: MetricDistribution,
+    pub function_lines: MetricDistribution,
+}
+
+/// One of the most complex functions
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ComplexityOffender {
+    pub name: String,
+    /// Relative to the project root
+    pub file_path: String,
+    pub line_range: LineRange,
+    pub language: String,
+    pub cyclomatic: u32,
+    pub cognitive: u32,
+    pub lines: u32,
+}
+
+/// Complexity distributions of a codebase and its most complex functions
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ComplexityReport {
+    pub overall: ComplexityGroup,
+    /// In language order
+    pub languages: Vec<ComplexityGroup>,
+    /// In path order
+    pub directories: Vec<ComplexityGroup>,
+    /// Highest cyclomatic complexity first, then highest cognitive
+    /// complexity, then longest
+    pub top_offenders: Vec<ComplexityOffender>,
+}
+
+/// Collects measured files into a [`ComplexityReport`]
+#[derive(Debug, Default)]
+pub struct ComplexityReportBuilder {
+    functions: Vec<ComplexityOffender>,
+    /// Files per language and per directory
+    language_files: BTreeMap<String, u32>,
+    directory_files: BTreeMap<String, u32>,
+}
+
+impl ComplexityReportBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the functions of one file; `relative_path` is relative to the
+    /// project root
+    pub fn add_file(&mut self, relative_path: &str, language: &str, functions: &[FunctionMetrics]) {
+        let relative_path = relative_path.replace('\\', "/");
+        *self.language_files.entry(language.to_string()).or_default() += 1;
+        *self.directory_files.entry(Self::directory(&relative_path)).or_default() += 1;
+        self.functions.extend(functions.iter().map(|function| ComplexityOffender {
+            name: function.name.clone(),
+            file_path: relative_path.clone(),
+            line_range: function.line_range.clone(),
+            language: language.to_string(),
+            cyclomatic: function.cyclomatic,
+            cognitive: function.cognitive,
+            lines: function.lines,
+        }));
+    }
+
+    pub fn build(mut self) -> ComplexityReport {
+        let group = |key: String, file_count: u32, functions: &[&ComplexityOffender]| ComplexityGroup {
+            key,
+            file_count,
+            function_count: functions.len() as u32,
+            cyclomatic: MetricDistribution::of(functions.iter().map(|f| f.cyclomatic).collect()),
+            cognitive: MetricDistribution::of(functions.iter().map(|f| f.cognitive).collect()),
+            function_lines: MetricDistribution::of(functions.iter().map(|f| f.lines).collect()),
+        };
+
+        let mut by_language: BTreeMap<&str, Vec<&ComplexityOffender>> = BTreeMap::new();
+        let mut by_directory: BTreeMap<String, Vec<&ComplexityOffender>> = BTreeMap::new();
+        for function in &self.functions {
+            by_language.entry(&function.language).or_default().push(function);
+            by_directory.entry(Self::directory(&function.file_path)).or_default().push(function);
+        }
+        let languages = self
+            .language_files
+            .iter()
+            .map(|(language, files)| {
+                group(language.clone(), *files, by_language.get(language.as_str()).map_or(&[], Vec::as_slice))
+            })
+            .collect();
+        let directories = self
+            .directory_files
+            .iter()
+            .map(|(directory, files)| {
+                group(directory.clone(), *files, by_directory.get(directory).map_or(&[], Vec::as_slice))
+            })
+            .collect();
+        let all: Vec<&ComplexityOffender> = self.functions.iter().collect();
+        let overall = group("all".to_string(), self.language_files.values().sum(), &all);
+
+        self.functions.sort_by(|a, b| {
+            (b.cyclomatic, b.cognitive, b.lines)
+                .cmp(&(a.cyclomatic, a.cognitive, a.lines))
+                .then_with(|| (&a.file_path, a.line_range.start).cmp(&(&b.file_path, b.line_range.start)))
+        });
+        self.functions.truncate(TOP_OFFENDER_LIMIT);
+
+        ComplexityReport {
+            overall,
+            languages,
+            directories,
+            top_offenders: self.functions,
+        }
+    }
+
+    fn directory(relative_path: &str) -> String {
+        relative_path.rsplit_once('/').map_or(".", |(directory, _)| directory).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(name: &str, start: u32, cyclomatic: u32, cognitive: u32, lines: u32) -> FunctionMetrics {
+        FunctionMetrics {
+            name: name.to_string(),
+            line_range: LineRange { start, end: start + lines - 1 },
+            cyclomatic,
+            cognitive,
+            lines,
+        }
+    }
+
+    #[test]
+    fn test_report_percentiles_groups_and_offenders() {
+        let mut builder = ComplexityReportBuilder::new();
+        let orders: Vec<FunctionMetrics> =
+            (1..=10).map(|n| function(&format!("order{}", n), n * 10, n, n * 2, n * 3)).collect();
+        builder.add_file("src/orders.ts", "typescript", &orders);
+        builder.add_file("src\\billing\\charge.py", "python", &[function("charge", 1, 12, 20, 40)]);
+        builder.add_file("main.py", "python", &[]);
+        let report = builder.build();
+
+        assert_eq!(report.overall.file_count, 3);
+        assert_eq!(report.overall.function_count, 11);
+        assert_eq!(
+            report.overall.cyclomatic,
+            MetricDistribution { p50: 6, p90: 10, max: 12, mean: 67.0 / 11.0 }
+        );
+
+        let languages: Vec<(&str, u32, u32, u32)> = report
+            .languages
+            .iter()
+            .map(|g| (g.key.as_str(), g.file_count, g.function_count, g.cyclomatic.p90))
+            .collect();
+        assert_eq!(languages, vec![("python", 2, 1, 12), ("typescript", 1, 10, 9)]);
+        let typescript = &report.languages[1];
+        assert_eq!((typescript.cyclomatic.p50, typescript.function_lines.max), (5, 30));
+
+        let directories: Vec<(&str, u32)> =
+            report.directories.iter().map(|g| (g.key.as_str(), g.function_count)).collect();
+        assert_eq!(directories, vec![(".", 0), ("src", 10), ("src/billing", 1)]);
+        assert_eq!(report.directories[0].cyclomatic, MetricDistribution::default());
+
+        let offenders: Vec<&str> = report.top_offenders.iter().take(3).map(|f| f.name.as_str()).collect();
+        assert_eq!(offenders, vec!["charge", "order10", "order9"]);
+        assert_eq!(report.top_offenders[0].file_path, "src/billing/charge.py");
+    }
+}
@@ -0,0 +1,552 @@
+//! Concurrency primitives and risky async patterns
+//!
+//! A line-based pass over source files that records which concurrency tools a
+//! codebase relies on (task spawning, threads, locks, atomics, channels,
+//! `Promise.all`, `asyncio.gather`, goroutines) and flags two risky patterns:
+//! - blocking calls made directly inside async code
+//! - a synchronous lock guard that is still held at an `.await`
+
+use crate::patterns::types::{Pattern, PatternExample};
+use crate::patterns::violations::Suppressions;
+use crate::types::LineRange;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// Maximum number of examples kept per concurrency pattern
+const MAX_CONCURRENCY_EXAMPLES: usize = 5;
+
+/// One use of a primitive, or one risky spot
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConcurrencyMatch {
+    /// Primitive id such as 'task_spawn', or risk kind such as 'blocking_in_async'
+    pub name: String,
+    pub file_path: String,
+    pub line: u32,
+    pub code: String,
+}
+
+/// Everything the pass found in one file
+#[derive(Debug, Clone, Default)]
+pub struct ConcurrencyReport {
+    pub usages: Vec<ConcurrencyMatch>,
+    pub risks: Vec<ConcurrencyMatch>,
+}
+
+/// How source files of a language group are read
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Syntax {
+    Rust,
+    JavaScript,
+    Python,
+    Go,
+    Java,
+    CSharp,
+    Cpp,
+}
+
+/// A function or closure scope that is (or explicitly is not) async
+struct Scope {
+    is_async: bool,
+    /// Brace depth (or indentation for Python) the scope was opened at
+    level: usize,
+    /// The body's opening brace has been seen
+    opened: bool,
+    header_line: usize,
+}
+
+/// A lock guard bound inside async Rust code
+struct Guard {
+    name: String,
+    depth: usize,
+    line: usize,
+    reported: bool,
+}
+
+pub struct ConcurrencyAnalyzer;
+
+impl ConcurrencyAnalyzer {
+    /// Whether files with this extension are read by the pass
+    pub fn supports_extension(extension: &str) -> bool {
+        Self::syntax(extension).is_some()
+    }
+
+    /// Primitive usages and risky spots in one file; risks silenced with an
+    /// `in-memoria-disable-next-line` comment are left out
+    pub fn analyze_file(file_path: &str, content: &str) -> ConcurrencyReport {
+        let extension = file_path.rsplit('.').next().unwrap_or("").to_lowercase();
+        let Some(syntax) = Self::syntax(&extension) else {
+            return ConcurrencyReport::default();
+        };
+
+        let lines: Vec<&str> = content.lines().collect();
+        let mut report = ConcurrencyReport::default();
+        for (idx, line) in lines.iter().enumerate() {
+            if Self::is_comment(line) {
+                continue;
+            }
+            for (name, pattern) in primitives(syntax) {
+                if pattern.is_match(line) {
+                    report.usages.push(Self::found(name, file_path, idx, line));
+                }
+            }
+        }
+
+        match syntax {
+            Syntax::Python => Self::python_risks(file_path, &lines, &mut report.risks),
+            Syntax::Rust | Syntax::JavaScript => Self::brace_risks(syntax, file_path, &lines, &mut report.risks),
+            _ => {}
+        }
+
+        let suppressions = Suppressions::parse(content);
+        if !suppressions.is_empty() {
+            report.risks.retain(|r| !suppressions.is_suppressed(r.line, &r.name));
+        }
+        report
+    }
+
+    /// One `implementation` pattern per primitive in use, and one
+    /// `concurrency_violation` pattern per kind of risk found
+    pub fn to_patterns(usages: &[ConcurrencyMatch], risks: &[ConcurrencyMatch]) -> Vec<Pattern> {
+        let mut patterns = Vec::new();
+
+        for (name, matches) in Self::group(usages) {
+            patterns.push(Pattern {
+                id: format!("implementation_concurrency_{}", name),
+                pattern_type: "implementation".to_string(),
+                description: format!("Concurrency: {}", Self::describe(name)),
+                frequency: matches.len() as u32,
+                confidence: 0.9,
+                examples: Self::examples(&matches),
+                contexts: vec!["concurrency".to_string()],
+            });
+        }
+
+        for (name, matches) in Self::group(risks) {
+            patterns.push(Pattern {
+                id: format!("implementation_concurrency_risk_{}", name),
+                pattern_type: "concurrency_violation".to_string(),
+                description: format!("[warning] {}", Self::describe(name)),
+                frequency: matches.len() as u32,
+                // Found by line heuristics rather than type information
+                confidence: 0.75,
+                examples: Self::examples(&matches),
+                contexts: vec!["concurrency".to_string(), "severity:warning".to_string()],
+            });
+        }
+
+        patterns
+    }
+
+    fn syntax(extension: &str) -> Option<Syntax> {
+        match extension {
+            "rs" => Some(Syntax::Rust),
+            "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" => Some(Syntax::JavaScript),
+            "py" => Some(Syntax::Python),
+            "go" => Some(Syntax::Go),
+            "java" | "kt" => Some(Syntax::Java),
+            "cs" => Some(Syntax::CSharp),
+            "cpp" | "cc" | "cxx" | "hpp" | "h" => Some(Syntax::Cpp),
+            _ => None,
+        }
+    }
+
+    /// How to fix one kind of risk
+    pub fn advice(name: &str) -> Option<&'static str> {
+        match name {
+            "blocking_in_async" => {
+                Some("Use the async variant of the call, or move it to a blocking thread pool (e.g. spawn_blocking)")
+            }
+            "lock_across_await" => Some("Release the guard before awaiting, or use an async-aware lock"),
+            _ => None,
+        }
+    }
+
+    fn describe(name: &str) -> &'static str {
+        match name {
+            "task_spawn" => "async task spawning",
+            "thread_spawn" => "OS threads",
+            "lock" => "mutexes and locks",
+            "atomic" => "atomic variables",
+            "channel" => "channels",
+            "promise_combinator" => "Promise.all/allSettled/race/any",
+            "asyncio_gather" => "asyncio.gather/wait/create_task",
+            "goroutine" => "goroutines",
+            "executor" => "thread pools and executors",
+            "blocking_in_async" => "blocking call inside async code",
+            "lock_across_await" => "lock held across an await point",
+            _ => "concurrency",
+        }
+    }
+
+    fn is_comment(line: &str) -> bool {
+        let trimmed = line.trim_start();
+        trimmed.starts_with("//") || trimmed.starts_with('#') || trimmed.starts_with('*')
+    }
+
+    fn found(name: &str, file_path: &str, idx: usize, line: &str) -> ConcurrencyMatch {
+        ConcurrencyMatch {
+            name: name.to_string(),
+            file_path: file_path.to_string(),
+            line: idx as u32 + 1,
+            code: line.trim().to_string(),
+        }
+    }
+
+    fn group(matches: &[ConcurrencyMatch]) -> BTreeMap<&str, Vec<&ConcurrencyMatch>> {
+        let mut groups: BTreeMap<&str, Vec<&ConcurrencyMatch>> = BTreeMap::new();
+        for m in matches {
+            groups.entry(m.name.as_str()).or_default().push(m);
+        }
+        groups
+    }
+
+    fn examples(matches: &[&ConcurrencyMatch]) -> Vec<PatternExample> {
+        matches
+            .iter()
+            .take(MAX_CONCURRENCY_EXAMPLES)
+            .map(|m| PatternExample {
+                code: m.code.clone(),
+                file_path: m.file_path.clone(),
+                line_range: LineRange { start: m.line, end: m.line },
+            })
+            .collect()
+    }
+
+    /// Risks in Rust and JavaScript, scoping functions by brace depth
+    fn brace_risks(syntax: Syntax, file_path: &str, lines: &[&str], risks: &mut Vec<ConcurrencyMatch>) {
+        let p = risk_patterns();
+        let (async_header, sync_header, blocking) = if syntax == Syntax::Rust {
+            (&p.rust_async, &p.rust_sync_scope, &p.rust_blocking)
+        } else {
+            (&p.js_async, &p.js_sync_scope, &p.js_blocking)
+        };
+
+        let mut scopes: Vec<Scope> = Vec::new();
+        let mut guards: Vec<Guard> = Vec::new();
+        let mut depth = 0usize;
+
+        for (idx, line) in lines.iter().enumerate() {
+            if Self::is_comment(line) {
+                continue;
+            }
+
+            if async_header.is_match(line) {
+                scopes.push(Scope { is_async: true, level: depth, opened: false, header_line: idx });
+            } else if sync_header.is_match(line) {
+                scopes.push(Scope { is_async: false, level: depth, opened: false, header_line: idx });
+            }
+
+            let in_async = scopes.last().is_some_and(|s| s.is_async);
+            if in_async {
+                if blocking.is_match(line) {
+                    risks.push(Self::found("blocking_in_async", file_path, idx, line));
+                }
+
+                if syntax == Syntax::Rust {
+                    if line.contains(".await") {
+                        for guard in guards.iter_mut().filter(|g| !g.reported && g.line != idx) {
+                            guard.reported = true;
+                            risks.push(Self::found("lock_across_await", file_path, guard.line, lines[guard.line]));
+                        }
+                    }
+                    if let Some(c) = p.rust_guard.captures(line) {
+                        guards.push(Guard { name: c[1].to_string(), depth, line: idx, reported: false });
+                    }
+                    if let Some(c) = p.rust_drop.captures(line) {
+                        guards.retain(|g| g.name != c[1]);
+                    }
+                }
+            }
+
+            let opens = line.matches('{').count();
+            let closes = line.matches('}').count();
+            depth = (depth + opens).saturating_sub(closes);
+
+            // Guards live until the block they were bound in closes
+            guards.retain(|g| g.depth <= depth);
+            while let Some(scope) = scopes.last_mut() {
+                if !scope.opened && depth > scope.level {
+                    scope.opened = true;
+                }
+                // Bodies without braces (`async () => fetch(url)`) end with their line
+                let ended = if scope.opened { depth <= scope.level } else { idx > scope.header_line };
+                if !ended {
+                    break;
+                }
+                scopes.pop();
+                if !scopes.iter().any(|s| s.is_async) {
+                    guards.clear();
+                }
+            }
+        }
+    }
+
+    /// Risks in Python, scoping functions and `with` blocks by indentation
+    fn python_risks(file_path: &str, lines: &[&str], risks: &mut Vec<ConcurrencyMatch>) {
+        let p = risk_patterns();
+        let mut scopes: Vec<Scope> = Vec::new();
+        // Indentation and line of a `with <lock>:` block inside async code
+        let mut lock_block: Option<(usize, usize, bool)> = None;
+
+        for (idx, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() || Self::is_comment(line) {
+                continue;
+            }
+            let indent = line.len() - line.trim_start().len();
+            while scopes.last().is_some_and(|s| indent <= s.level) {
+                scopes.pop();
+            }
+            if lock_block.is_some_and(|(level, _, _)| indent <= level) {
+                lock_block = None;
+            }
+
+            if let Some(c) = p.python_def.captures(line) {
+                let is_async = c.get(1).is_some();
+                scopes.push(Scope { is_async, level: indent, opened: true, header_line: idx });
+                continue;
+            }
+
+            if !scopes.last().is_some_and(|s| s.is_async) {
+                continue;
+            }
+            if p.python_blocking.is_match(line) {
+                risks.push(Self::found("blocking_in_async", file_path, idx, line));
+            }
+            if let Some((_, lock_line, reported)) = lock_block.as_mut() {
+                if !*reported && line.contains("await ") {
+                    *reported = true;
+                    risks.push(Self::found("lock_across_await", file_path, *lock_line, lines[*lock_line]));
+                }
+            } else if p.python_sync_lock.is_match(line) {
+                lock_block = Some((indent, idx, false));
+            }
+        }
+    }
+}
+
+fn primitives(syntax: Syntax) -> &'static [(&'static str, Regex)] {
+    static RUST: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    static JAVASCRIPT: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    static PYTHON: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    static GO: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    static JAVA: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    static CSHARP: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    static CPP: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+
+    let compile = |table: &[(&'static str, &str)]| {
+        table
+            .iter()
+            .map(|(name, pattern)| (*name, Regex::new(pattern).expect("valid concurrency regex")))
+            .collect::<Vec<_>>()
+    };
+
+    match syntax {
+        Syntax::Rust => RUST.get_or_init(|| {
+            compile(&[
+                ("task_spawn", r"\b(?:tokio::|task::)spawn(?:_blocking|_local)?\(|\btokio::task::spawn"),
+                ("thread_spawn", r"\bthread::(?:spawn|scope)\(|\bthread::Builder::new\("),
+                ("lock", r"\b(?:Mutex|RwLock)(?:::new\(|<)"),
+                ("atomic", r"\bAtomic(?:Bool|Usize|Isize|U8|U16|U32|U64|I8|I16|I32|I64|Ptr)\b"),
+                ("channel", r"\b(?:mpsc|oneshot|broadcast|watch|crossbeam_channel|flume)::(?:channel|sync_channel|unbounded|bounded)\b"),
+            ])
+        }),
+        Syntax::JavaScript => JAVASCRIPT.get_or_init(|| {
+            compile(&[
+                ("promise_combinator", r"\bPromise\.(?:all|allSettled|race|any)\("),
+                ("thread_spawn", r"\bnew Worker\("),
+                ("atomic", r"\bAtomics\.\w+\("),
+            ])
+        }),
+        Syntax::Python => PYTHON.get_or_init(|| {
+            compile(&[
+                ("asyncio_gather", r"\basyncio\.(?:gather|wait|create_task|TaskGroup)\b"),
+                ("thread_spawn", r"\bthreading\.Thread\(|\bmultiprocessing\.Process\("),
+                ("lock", r"\b(?:threading|asyncio|multiprocessing)\.(?:Lock|RLock|Semaphore|Condition)\("),
+                ("channel", r"\b(?:queue\.Queue|asyncio\.Queue|multiprocessing\.Queue)\("),
+                ("executor", r"\b(?:ThreadPoolExecutor|ProcessPoolExecutor)\("),
+            ])
+        }),
+        Syntax::Go => GO.get_or_init(|| {
+            compile(&[
+                ("goroutine", r"^\s*go\s+(?:func\b|[\w.]+\()"),
+                ("channel", r"\bmake\(\s*(?:chan|<-chan|chan<-)\b|\bselect\s*\{"),
+                ("lock", r"\bsync\.(?:Mutex|RWMutex|WaitGroup|Once)\b"),
+                ("atomic", r"\batomic\.(?:Add|Load|Store|CompareAndSwap|Swap)\w*\(|\batomic\.(?:Int32|Int64|Uint32|Uint64|Bool|Value|Pointer)\b"),
+            ])
+        }),
+        Syntax::Java => JAVA.get_or_init(|| {
+            compile(&[
+                ("thread_spawn", r"\bnew Thread\(|\bThread\.(?:ofVirtual|startVirtualThread)\b"),
+                ("lock", r"\bsynchronized\b|\b(?:ReentrantLock|ReentrantReadWriteLock|Semaphore)\b"),
+                ("atomic", r"\bAtomic(?:Integer|Long|Boolean|Reference)\b"),
+                ("executor", r"\bExecutors\.\w+\(|\bExecutorService\b|\bCompletableFuture\.\w+\("),
+            ])
+        }),
+        Syntax::CSharp => CSHARP.get_or_init(|| {
+            compile(&[
+                ("task_spawn", r"\bTask\.(?:Run|Factory\.StartNew)\("),
+                ("promise_combinator", r"\bTask\.(?:WhenAll|WhenAny)\("),
+                ("thread_spawn", r"\bnew Thread\("),
+                ("lock", r"\block\s*\(|\b(?:SemaphoreSlim|Mutex|ReaderWriterLockSlim)\b"),
+                ("atomic", r"\bInterlocked\.\w+\("),
+                ("channel", r"\bChannel\.Create(?:Bounded|Unbounded)\b"),
+            ])
+        }),
+        Syntax::Cpp => CPP.get_or_init(|| {
+            compile(&[
+                ("thread_spawn", r"\bstd::(?:thread|jthread)\b|\bpthread_create\("),
+                ("task_spawn", r"\bstd::async\("),
+                ("lock", r"\bstd::(?:mutex|shared_mutex|lock_guard|unique_lock|scoped_lock)\b"),
+                ("atomic", r"\bstd::atomic\b"),
+            ])
+        }),
+    }
+}
+
+struct RiskPatterns {
+    rust_async: Regex,
+    rust_sync_scope: Regex,
+    rust_blocking: Regex,
+    rust_guard: Regex,
+    rust_drop: Regex,
+    js_async: Regex,
+    js_sync_scope: Regex,
+    js_blocking: Regex,
+    python_def: Regex,
+    python_blocking: Regex,
+    python_sync_lock: Regex,
+}
+
+fn risk_patterns() -> &'static RiskPatterns {
+    static PATTERNS: OnceLock<RiskPatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let re = |pattern: &str| Regex::new(pattern).expect("valid concurrency regex");
+        RiskPatterns {
+            rust_async: re(r"\basync\s+(?:move\s+)?(?:fn\b|\{|\|)"),
+            // Work handed to a blocking pool or thread may block freely
+            rust_sync_scope: re(r"\bspawn_blocking\(|\bthread::spawn\(|\bblock_in_place\(|^\s*(?:pub(?:\([^)]*\))?\s+)?fn\s"),
+            rust_blocking: re(
+                r"\bstd::thread::sleep\(|\bthread::sleep\(|\bstd::fs::\w+\(|\bstd::net::TcpStream::connect\(|\breqwest::blocking::|\.block_on\(",
+            ),
+            rust_guard: re(r"\blet\s+(?:mut\s+)?(\w+)\s*=\s*.*\.(?:lock|read|write)\(\)(?:\.unwrap\(\)|\.expect\([^)]*\)|\?)\s*;"),
+            rust_drop: re(r"\bdrop\(\s*(\w+)\s*\)"),
+            js_async: re(r"\basync\s+(?:function\b|\(|[\w$]+\s*=>|[\w$]+\s*\()"),
+            js_sync_scope: re(r"^\s*(?:export\s+)?function\b"),
+            js_blocking: re(r"\b\w+Sync\(|\bAtomics\.wait\("),
+            python_def: re(r"^\s*(async\s+)?def\s+\w+"),
+            python_blocking: re(
+                r"\btime\.sleep\(|\brequests\.(?:get|post|put|patch|delete|head|request)\(|\burllib\.request\.urlopen\(|\bsubprocess\.(?:run|call|check_call|check_output)\(",
+            ),
+            python_sync_lock: re(r"^\s*with\s+[^:]*\b\w*[Ll]ock\w*\b[^:]*:"),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(matches: &[ConcurrencyMatch]) -> Vec<(&str, u32)> {
+        matches.iter().map(|m| (m.name.as_str(), m.line)).collect()
+    }
+
+    #[test]
+    fn test_rust_primitives_and_risks() {
+        let source = "\
+use std::sync::{Arc, Mutex};
+
+async fn refresh(cache: Arc<Mutex<Vec<u8>>>) {
+    let mut data = cache.lock().unwrap();
+    let body = fetch().await;
+    data.extend(body);
+    std::thread::sleep(Duration::from_millis(10));
+}
+
+async fn safe(cache: Arc<Mutex<Vec<u8>>>) {
+    {
+        let data = cache.lock().unwrap();
+        data.len();
+    }
+    fetch().await;
+    tokio::task::spawn_blocking(move || {
+        std::thread::sleep(Duration::from_millis(10));
+    });
+}
+
+fn main() {
+    let counter = AtomicUsize::new(0);
+    std::thread::sleep(Duration::from_millis(10));
+    let (tx, rx) = mpsc::channel();
+    tokio::spawn(refresh(cache));
+}
+";
+        let report = ConcurrencyAnalyzer::analyze_file("src/cache.rs", source);
+        assert_eq!(names(&report.risks), vec![("lock_across_await", 4), ("blocking_in_async", 7)]);
+
+        let usages: Vec<&str> = report.usages.iter().map(|m| m.name.as_str()).collect();
+        assert!(usages.contains(&"task_spawn"));
+        assert!(usages.contains(&"atomic"));
+        assert!(usages.contains(&"channel"));
+    }
+
+    #[test]
+    fn test_javascript_and_python_risks() {
+        let javascript = "\
+export async function load(paths) {
+  const config = fs.readFileSync('config.json');
+  return Promise.all(paths.map((p) => read(p)));
+}
+
+function build() {
+  fs.writeFileSync('out.json', '{}');
+}
+";
+        let report = ConcurrencyAnalyzer::analyze_file("src/load.ts", javascript);
+        assert_eq!(names(&report.risks), vec![("blocking_in_async", 2)]);
+        assert_eq!(names(&report.usages), vec![("promise_combinator", 3)]);
+
+        let python = "\
+lock = threading.Lock()
+
+async def sync_all(urls):
+    results = await asyncio.gather(*[fetch(u) for u in urls])
+    time.sleep(1)
+    with lock:
+        await save(results)
+
+def report():
+    time.sleep(1)
+";
+        let report = ConcurrencyAnalyzer::analyze_file("app/sync.py", python);
+        assert_eq!(names(&report.risks), vec![("blocking_in_async", 5), ("lock_across_await", 6)]);
+        assert_eq!(names(&report.usages), vec![("lock", 1), ("asyncio_gather", 4)]);
+    }
+
+    #[test]
+    fn test_go_usages_become_patterns() {
+        let source = "\
+func serve(jobs []Job) {
+    var mu sync.Mutex
+    results := make(chan Result)
+    go worker(jobs, results)
+    go func() { mu.Lock() }()
+}
+";
+        let report = ConcurrencyAnalyzer::analyze_file("cmd/serve.go", source);
+        assert!(report.risks.is_empty());
+
+        let patterns = ConcurrencyAnalyzer::to_patterns(&report.usages, &report.risks);
+        let goroutines = patterns.iter().find(|p| p.id == "implementation_concurrency_goroutine").unwrap();
+        assert_eq!(goroutines.frequency, 2);
+        assert_eq!(goroutines.pattern_type, "implementation");
+        assert!(patterns.iter().any(|p| p.id == "implementation_concurrency_channel"));
+        assert!(patterns.iter().any(|p| p.id == "implementation_concurrency_lock"));
+
+        let risky = ConcurrencyAnalyzer::analyze_file("src/load.js", "async function f() {\n  execSync('ls');\n}\n");
+        let patterns = ConcurrencyAnalyzer::to_patterns(&risky.usages, &risky.risks);
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].pattern_type, "concurrency_violation");
+        assert_eq!(patterns[0].description, "[warning] blocking call inside async code");
+    }
+}
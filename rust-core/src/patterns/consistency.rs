@@ -0,0 +1,219 @@
+//! Cross-language naming consistency
+//!
+//! In a polyglot repository one domain entity shows up in several languages:
+//! `user_id` in a Rust struct, `userId` in a TypeScript DTO, `UserID` in Go.
+//! Names are reduced to their lower-cased words, so those three become the
+//! entity `user_id`; an entity found in two or more languages under more than
+//! one spelling is reported as drift, with an example of each spelling.
+//!
+//! Single-word names are left out: `User` the class and `user` the variable
+//! are usually different things, not drift.
+
+use crate::patterns::naming::NamingPatternAnalyzer;
+use crate::patterns::types::{Pattern, PatternExample};
+use crate::types::SemanticConcept;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Prefix of the ids of patterns produced by this check; the rest is the entity
+const PATTERN_PREFIX: &str = "naming_consistency_";
+
+/// Spellings kept as examples per entity
+const MAX_SPELLINGS: usize = 5;
+
+/// One way an entity is spelled, and where
+#[derive(Debug, Clone)]
+pub struct NamingVariant {
+    pub spelling: String,
+    /// Languages using this spelling, sorted
+    pub languages: Vec<String>,
+    /// First occurrence of the spelling
+    pub example: PatternExample,
+    pub occurrences: u32,
+}
+
+/// An entity spelled differently across languages
+#[derive(Debug, Clone)]
+pub struct NamingDrift {
+    /// Lower-cased words joined by `_`, e.g. `user_id`
+    pub entity: String,
+    /// Spellings, most used first
+    pub variants: Vec<NamingVariant>,
+}
+
+/// Spelling -> (languages, first example, occurrences) of one entity
+type Spellings<'a> = BTreeMap<&'a str, (BTreeSet<String>, PatternExample, u32)>;
+
+pub struct NamingConsistencyChecker;
+
+impl NamingConsistencyChecker {
+    /// Entities spelled more than one way across two or more languages;
+    /// `language_of` maps a file path to its language, "unknown" is ignored
+    pub fn find_drift(concepts: &[SemanticConcept], language_of: impl Fn(&str) -> String) -> Vec<NamingDrift> {
+        let mut entities: BTreeMap<String, Spellings> = BTreeMap::new();
+        for concept in concepts {
+            let language = language_of(&concept.file_path);
+            if language == "unknown" {
+                continue;
+            }
+            let Some(entity) = Self::entity_key(concept) else {
+                continue;
+            };
+            let spelling = entities
+                .entry(entity)
+                .or_default()
+                .entry(concept.name.as_str())
+                .or_insert_with(|| {
+                    (
+                        BTreeSet::new(),
+                        PatternExample {
+                            code: concept.name.clone(),
+                            file_path: concept.file_path.clone(),
+                            line_range: concept.line_range.clone(),
+                        },
+                        0,
+                    )
+                });
+            spelling.0.insert(language);
+            spelling.2 += 1;
+        }
+
+        entities
+            .into_iter()
+            .filter_map(|(entity, spellings)| {
+                let languages: BTreeSet<&String> = spellings.values().flat_map(|(languages, _, _)| languages).collect();
+                if spellings.len() < 2 || languages.len() < 2 {
+                    return None;
+                }
+                let mut variants: Vec<NamingVariant> = spellings
+                    .into_iter()
+                    .map(|(spelling, (languages, example, occurrences))| NamingVariant {
+                        spelling: spelling.to_string(),
+                        languages: languages.into_iter().collect(),
+                        example,
+                        occurrences,
+                    })
+                    .collect();
+                variants.sort_by(|a, b| b.occurrences.cmp(&a.occurrences).then_with(|| a.spelling.cmp(&b.spelling)));
+                Some(NamingDrift { entity, variants })
+            })
+            .collect()
+    }
+
+    /// One `naming_consistency_violation` pattern per drifting entity; its rule
+    /// id is the pattern id, e.g. `naming_consistency_user_id`
+    pub fn to_patterns(drifts: &[NamingDrift]) -> Vec<Pattern> {
+        drifts
+            .iter()
+            .map(|drift| {
+                let spellings: Vec<String> = drift
+                    .variants
+                    .iter()
+                    .map(|v| format!("{} ({})", v.spelling, v.languages.join(", ")))
+                    .collect();
+                let languages: BTreeSet<&String> = drift.variants.iter().flat_map(|v| &v.languages).collect();
+                let mut contexts = vec!["naming".to_string(), "severity:info".to_string()];
+                contexts.extend(languages.into_iter().map(|language| format!("language:{}", language)));
+
+                Pattern {
+                    id: format!("{}{}", PATTERN_PREFIX, drift.entity),
+                    pattern_type: "naming_consistency_violation".to_string(),
+                    description: format!(
+                        "[info] '{}' is named inconsistently across languages: {}",
+                        drift.entity.replace('_', " "),
+                        spellings.join(", ")
+                    ),
+                    frequency: drift.variants.iter().map(|v| v.occurrences).sum(),
+                    confidence: 0.75,
+                    examples: drift
+                        .variants
+                        .iter()
+                        .take(MAX_SPELLINGS)
+                        .map(|v| v.example.clone())
+                        .collect(),
+                    contexts,
+                }
+            })
+            .collect()
+    }
+
+    /// Lower-cased words of a multi-word name joined by `_`; `None` for single words
+    fn entity_key(concept: &SemanticConcept) -> Option<String> {
+        let words = NamingPatternAnalyzer::concept_tokens(concept);
+        (words.len() >= 2).then(|| words.join("_"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::violations::Violation;
+    use crate::types::LineRange;
+    use std::collections::HashMap;
+
+    fn concept(name: &str, file_path: &str) -> SemanticConcept {
+        SemanticConcept {
+            id: format!("{}_{}", file_path, name),
+            name: name.to_string(),
+            concept_type: "variable".to_string(),
+            confidence: 0.9,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start: 3, end: 3 },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn language_of(path: &str) -> String {
+        match path.rsplit('.').next() {
+            Some("rs") => "rust",
+            Some("ts") => "typescript",
+            Some("go") => "go",
+            Some("sql") => "sql",
+            _ => "unknown",
+        }
+        .to_string()
+    }
+
+    #[test]
+    fn test_entity_spelled_differently_across_languages_is_drift() {
+        let concepts = vec![
+            concept("user_id", "src/models/user.rs"),
+            concept("user_id", "db/schema.sql"),
+            concept("userId", "web/api/user.ts"),
+            concept("UserID", "svc/user.go"),
+            // Same spelling everywhere: consistent
+            concept("order_total", "src/orders.rs"),
+            concept("order_total", "db/orders.sql"),
+            // Different spellings within one language only
+            concept("createdAt", "web/a.ts"),
+            concept("CreatedAt", "web/b.ts"),
+            // Single words and unknown languages are not compared
+            concept("User", "src/models/user.rs"),
+            concept("user", "web/api/user.ts"),
+            concept("UserId", "docs/notes.txt"),
+        ];
+
+        let drifts = NamingConsistencyChecker::find_drift(&concepts, language_of);
+        assert_eq!(drifts.len(), 1);
+        let drift = &drifts[0];
+        assert_eq!(drift.entity, "user_id");
+        let spellings: Vec<&str> = drift.variants.iter().map(|v| v.spelling.as_str()).collect();
+        assert_eq!(spellings, vec!["user_id", "UserID", "userId"]);
+        assert_eq!(drift.variants[0].languages, vec!["rust".to_string(), "sql".to_string()]);
+    }
+
+    #[test]
+    fn test_drift_is_reported_as_violations_with_examples() {
+        let concepts = vec![concept("user_id", "src/user.rs"), concept("userId", "web/user.ts")];
+        let patterns = NamingConsistencyChecker::to_patterns(&NamingConsistencyChecker::find_drift(&concepts, language_of));
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].id, "naming_consistency_user_id");
+        assert!(patterns[0].description.contains("userId (typescript)"));
+
+        let violations = Violation::from_pattern(&patterns[0]);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].severity, "info");
+        assert_eq!(violations[0].rule_id, "naming_consistency_user_id");
+        assert!(violations.iter().any(|v| v.file_path == "web/user.ts"));
+    }
+}
@@ -0,0 +1,408 @@
+//! Grouping of candidate patterns that describe the same convention
+//!
+//! Learning produces overlapping candidates, e.g. one camelCase pattern per
+//! analyzer pass. Before they are stored, candidates in the same group are
+//! merged into one pattern. How groups are formed is a
+//! [`ConsolidationStrategy`]:
+//! - `description`: same type and first three words of the description (the default)
+//! - `type-context`: same type and the same contexts
+//! - `example-similarity`: same type and examples sharing most of their identifiers
+//! - `embedding`: same type and close bag-of-words embeddings of description,
+//!   contexts and examples, clustered around centroids
+//!
+//! Candidates of different types are never merged.
+
+use crate::patterns::keywords::KeywordExtractor;
+use crate::patterns::types::Pattern;
+use crate::types::validation::{ErrorCode, ValidationError};
+use crate::types::ParseError;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::OnceLock;
+
+/// Share of identifiers two candidates' examples must have in common
+const EXAMPLE_SIMILARITY_THRESHOLD: f64 = 0.5;
+
+/// Cosine similarity a candidate needs to join a cluster
+const EMBEDDING_SIMILARITY_THRESHOLD: f64 = 0.7;
+
+/// Dimensions of the hashed embeddings
+const EMBEDDING_DIMENSIONS: usize = 256;
+
+/// How candidate patterns are grouped before merging
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConsolidationStrategy {
+    /// Same type and first three words of the description
+    #[default]
+    Description,
+    /// Same type and the same set of contexts
+    TypeContext,
+    /// Same type and examples with mostly the same identifiers; candidates
+    /// without examples compare description keywords instead
+    ExampleSimilarity,
+    /// Same type and close embeddings of description, contexts and examples
+    Embedding,
+}
+
+impl ConsolidationStrategy {
+    pub fn parse(name: &str) -> Result<Self, ParseError> {
+        match name.trim().to_lowercase().replace('_', "-").as_str() {
+            "description" => Ok(ConsolidationStrategy::Description),
+            "type-context" => Ok(ConsolidationStrategy::TypeContext),
+            "example-similarity" => Ok(ConsolidationStrategy::ExampleSimilarity),
+            "embedding" => Ok(ConsolidationStrategy::Embedding),
+            _ => Err(ValidationError::new(
+                ErrorCode::InvalidArgument,
+                format!(
+                    "Unknown consolidation strategy '{}'; expected description, type-context, example-similarity or embedding",
+                    name
+                ),
+            )
+            .into()),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConsolidationStrategy::Description => "description",
+            ConsolidationStrategy::TypeContext => "type-context",
+            ConsolidationStrategy::ExampleSimilarity => "example-similarity",
+            ConsolidationStrategy::Embedding => "embedding",
+        }
+    }
+
+    /// Indices of the candidates to merge together, every candidate in exactly
+    /// one group; groups and their members keep the input order
+    pub fn group(&self, patterns: &[Pattern]) -> Vec<Vec<usize>> {
+        match self {
+            ConsolidationStrategy::Description => group_by_key(patterns.iter().map(|pattern| {
+                format!("{}_{}", pattern.pattern_type, description_key(&pattern.description))
+            })),
+            ConsolidationStrategy::TypeContext => group_by_key(patterns.iter().map(|pattern| {
+                let contexts: BTreeSet<&str> = pattern.contexts.iter().map(String::as_str).collect();
+                (pattern.pattern_type.as_str(), contexts)
+            })),
+            ConsolidationStrategy::ExampleSimilarity => group_by_example_similarity(patterns),
+            ConsolidationStrategy::Embedding => group_by_embedding(patterns),
+        }
+    }
+}
+
+/// First three words of a description, lowercased without punctuation
+pub fn description_key(description: &str) -> String {
+    description
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .take(3)
+        .collect::<Vec<&str>>()
+        .join("_")
+}
+
+/// Indices sharing a key, in order of each key's first appearance
+fn group_by_key<K: Ord>(keys: impl IntoIterator<Item = K>) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut positions: BTreeMap<K, usize> = BTreeMap::new();
+    for (index, key) in keys.into_iter().enumerate() {
+        let position = *positions.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[position].push(index);
+    }
+    groups
+}
+
+/// Link candidates whose identifier sets overlap enough; groups are the
+/// connected components
+fn group_by_example_similarity(patterns: &[Pattern]) -> Vec<Vec<usize>> {
+    let tokens: Vec<HashSet<String>> = patterns.iter().map(example_tokens).collect();
+    let mut parents: Vec<usize> = (0..patterns.len()).collect();
+    for i in 0..patterns.len() {
+        for j in (i + 1)..patterns.len() {
+            if patterns[i].pattern_type == patterns[j].pattern_type
+                && jaccard(&tokens[i], &tokens[j]) >= EXAMPLE_SIMILARITY_THRESHOLD
+            {
+                let (a, b) = (find_root(&mut parents, i), find_root(&mut parents, j));
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+    }
+    let roots: Vec<usize> = (0..patterns.len()).map(|index| find_root(&mut parents, index)).collect();
+    group_by_key(roots)
+}
+
+/// Identifiers of the examples as written, so `getUser` and `get_user` stay
+/// apart; description keywords for candidates without examples
+fn example_tokens(pattern: &Pattern) -> HashSet<String> {
+    if pattern.examples.is_empty() {
+        return KeywordExtractor::extract(&pattern.description).into_iter().collect();
+    }
+    pattern
+        .examples
+        .iter()
+        .flat_map(|example| identifier_pattern().find_iter(&example.code))
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        0.0
+    } else {
+        a.intersection(b).count() as f64 / union as f64
+    }
+}
+
+fn find_root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+/// Each candidate joins the most similar cluster of its type, or starts one
+/// when no centroid is close enough
+fn group_by_embedding(patterns: &[Pattern]) -> Vec<Vec<usize>> {
+    // (pattern type, centroid, members)
+    let mut clusters: Vec<(&str, Vec<f64>, Vec<usize>)> = Vec::new();
+    for (index, pattern) in patterns.iter().enumerate() {
+        let vector = embed(pattern);
+        let best = clusters
+            .iter()
+            .enumerate()
+            .filter(|(_, (pattern_type, _, _))| *pattern_type == pattern.pattern_type)
+            .map(|(position, (_, centroid, _))| (position, cosine(centroid, &vector)))
+            .filter(|(_, similarity)| *similarity >= EMBEDDING_SIMILARITY_THRESHOLD)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match best {
+            Some((position, _)) => {
+                let (_, centroid, members) = &mut clusters[position];
+                let count = members.len() as f64;
+                for (value, added) in centroid.iter_mut().zip(&vector) {
+                    *value = (*value * count + added) / (count + 1.0);
+                }
+                members.push(index);
+            }
+            None => clusters.push((&pattern.pattern_type, vector, vec![index])),
+        }
+    }
+    clusters.into_iter().map(|(_, _, members)| members).collect()
+}
+
+/// Hashed bag-of-words embedding over description and context keywords, the
+/// words of example identifiers and their casing, normalized to unit length.
+/// Computed locally, so consolidation needs no model.
+fn embed(pattern: &Pattern) -> Vec<f64> {
+    let mut vector = vec![0.0; EMBEDDING_DIMENSIONS];
+    let mut add = |feature: &str, weight: f64| {
+        vector[(fnv1a(feature) % EMBEDDING_DIMENSIONS as u64) as usize] += weight;
+    };
+    // The description says what the convention is; examples say where it was seen
+    for keyword in KeywordExtractor::extract(&pattern.description) {
+        add(&keyword, 2.0);
+    }
+    for keyword in KeywordExtractor::extract(&pattern.contexts.join(" ")) {
+        add(&keyword, 1.0);
+    }
+    for example in &pattern.examples {
+        for keyword in KeywordExtractor::extract(&example.code) {
+            add(&keyword, 1.0);
+        }
+        // Keeps `getUserName` and `get_user_name` apart even though their words match
+        for identifier in identifier_pattern().find_iter(&example.code) {
+            if let Some(casing) = casing(identifier.as_str()) {
+                add(casing, 2.0);
+            }
+        }
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+/// Casing of a multi-word identifier; `None` for single lowercase words
+fn casing(identifier: &str) -> Option<&'static str> {
+    let has_lower = identifier.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = identifier.chars().any(|c| c.is_ascii_uppercase());
+    let trimmed = identifier.trim_matches('_');
+    if trimmed.contains('_') {
+        Some(if has_lower { "casing:snake_case" } else { "casing:SCREAMING_CASE" })
+    } else if trimmed.starts_with(|c: char| c.is_ascii_uppercase()) && has_lower {
+        Some("casing:PascalCase")
+    } else if has_lower && has_upper {
+        Some("casing:camelCase")
+    } else {
+        None
+    }
+}
+
+fn cosine(a: &[f64], b: &[f64]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norms = a.iter().map(|v| v * v).sum::<f64>().sqrt() * b.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+/// Stable across runs and platforms, unlike `DefaultHasher`
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn identifier_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"[A-Za-z_][A-Za-z0-9_]*").expect("valid identifier regex"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::types::PatternExample;
+    use crate::types::LineRange;
+
+    /// Candidates labelled with the convention they describe
+    fn labelled_candidates() -> Vec<(Pattern, &'static str)> {
+        let candidate = |id: &str, pattern_type: &str, description: &str, contexts: &[&str], examples: &[&str]| Pattern {
+            id: id.to_string(),
+            pattern_type: pattern_type.to_string(),
+            description: description.to_string(),
+            frequency: 3,
+            confidence: 0.8,
+            examples: examples
+                .iter()
+                .map(|code| PatternExample {
+                    code: code.to_string(),
+                    file_path: "src/app.ts".to_string(),
+                    line_range: LineRange { start: 1, end: 1 },
+                })
+                .collect(),
+            contexts: contexts.iter().map(|c| c.to_string()).collect(),
+        };
+        vec![
+            (
+                candidate("try_catch_1", "implementation", "Error handling with try/catch blocks", &["typescript", "error_handling"],
+                    &["try { await save(user) } catch (error) { logger.error(error) }"]),
+                "try_catch",
+            ),
+            (
+                candidate("try_catch_2", "implementation", "Error handling with try/catch around awaits", &["error_handling", "typescript"],
+                    &["try { await load(order) } catch (error) { logger.error(error) }"]),
+                "try_catch",
+            ),
+            (
+                candidate("result_1", "implementation", "Error handling with Result types", &["rust", "error_handling"],
+                    &["fn find(id: u64) -> Result<Order, Error> { let order = repo.get(id)?; Ok(order) }"]),
+                "result",
+            ),
+            (
+                candidate("repository_1", "implementation", "Repository pattern for data access", &["typescript", "data_access"],
+                    &["class UserRepository { async findById(id) { return this.db.users.find(id) } }"]),
+                "repository",
+            ),
+            (
+                candidate("repository_2", "implementation", "Data access goes through repositories", &["typescript", "data_access"],
+                    &["class OrderRepository { async findById(id) { return this.db.orders.find(id) } }"]),
+                "repository",
+            ),
+            (
+                candidate("camel_1", "naming", "Functions use camelCase", &["typescript", "function"],
+                    &["function getUserName() {", "export function loadOrders() {"]),
+                "camel",
+            ),
+            (
+                candidate("camel_2", "naming", "Functions use camelCase in services", &["typescript", "function"],
+                    &["function getUserName() {", "export function loadOrders() {", "function saveOrder() {"]),
+                "camel",
+            ),
+            (
+                candidate("snake_1", "naming", "Functions use snake_case", &["python", "function"],
+                    &["def get_user_name():", "def load_orders():"]),
+                "snake",
+            ),
+        ]
+    }
+
+    /// Share of merged pairs that describe the same convention, and share of
+    /// such pairs that were merged
+    fn precision_and_recall(strategy: ConsolidationStrategy) -> (f64, f64) {
+        let candidates = labelled_candidates();
+        let patterns: Vec<Pattern> = candidates.iter().map(|(pattern, _)| pattern.clone()).collect();
+        let groups = strategy.group(&patterns);
+
+        let mut group_of = vec![0; patterns.len()];
+        for (group, members) in groups.iter().enumerate() {
+            for &member in members {
+                group_of[member] = group;
+            }
+        }
+        let (mut merged, mut correct, mut related) = (0, 0, 0);
+        for i in 0..patterns.len() {
+            for j in (i + 1)..patterns.len() {
+                let same_label = candidates[i].1 == candidates[j].1;
+                let same_group = group_of[i] == group_of[j];
+                merged += same_group as u32;
+                correct += (same_group && same_label) as u32;
+                related += same_label as u32;
+            }
+        }
+        let precision = if merged == 0 { 1.0 } else { correct as f64 / merged as f64 };
+        (precision, correct as f64 / related as f64)
+    }
+
+    #[test]
+    fn test_strategies_compared_on_labelled_candidates() {
+        let (description_precision, description_recall) = precision_and_recall(ConsolidationStrategy::Description);
+        // "Error handling with ..." merges try/catch with Result types, and the
+        // two repository descriptions are split
+        assert_eq!(description_precision, 0.5);
+        assert!((description_recall - 2.0 / 3.0).abs() < 1e-9);
+
+        for strategy in [
+            ConsolidationStrategy::TypeContext,
+            ConsolidationStrategy::ExampleSimilarity,
+            ConsolidationStrategy::Embedding,
+        ] {
+            let (precision, recall) = precision_and_recall(strategy);
+            assert_eq!(precision, 1.0, "{} precision", strategy.as_str());
+            assert!(recall >= description_recall, "{} recall {}", strategy.as_str(), recall);
+        }
+    }
+
+    #[test]
+    fn test_groups_cover_every_candidate_once_and_keep_types_apart() {
+        let patterns: Vec<Pattern> = labelled_candidates().into_iter().map(|(pattern, _)| pattern).collect();
+        for strategy in [
+            ConsolidationStrategy::Description,
+            ConsolidationStrategy::TypeContext,
+            ConsolidationStrategy::ExampleSimilarity,
+            ConsolidationStrategy::Embedding,
+        ] {
+            let groups = strategy.group(&patterns);
+            let mut members: Vec<usize> = groups.iter().flatten().copied().collect();
+            members.sort();
+            assert_eq!(members, (0..patterns.len()).collect::<Vec<_>>());
+            for group in &groups {
+                assert!(group.iter().all(|&i| patterns[i].pattern_type == patterns[group[0]].pattern_type));
+            }
+            assert_eq!(ConsolidationStrategy::parse(strategy.as_str()).unwrap(), strategy);
+        }
+        assert_eq!(ConsolidationStrategy::parse("Type_Context").unwrap(), ConsolidationStrategy::TypeContext);
+        assert!(ConsolidationStrategy::parse("kmeans").is_err());
+    }
+
+    #[test]
+    fn test_description_key() {
+        // Should normalize to similar keys for grouping
+        assert_eq!(description_key("CamelCase naming pattern for functions"), "camelcase_naming_pattern");
+        assert_eq!(description_key("camelCase naming pattern in JavaScript"), "camelcase_naming_pattern");
+    }
+}
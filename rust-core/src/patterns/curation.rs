@@ -0,0 +1,174 @@
+//! Manual curation of learned patterns
+//!
+//! Agents and users correct the engine by listing patterns, editing their
+//! description or confidence, deleting bogus ones and pinning the ones they
+//! vouch for. A pinned pattern stays exactly as curated: decay, confidence
+//! boosts, consolidation and relearning leave it alone until it is unpinned.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::patterns::sharing::parse_namespace;
+use crate::patterns::taxonomy::PatternNamespace;
+use crate::patterns::types::Pattern;
+use crate::types::validation::{ErrorCode, ValidationError};
+use crate::types::ParseError;
+use serde::{Deserialize, Serialize};
+
+/// Which patterns to list; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct PatternFilter {
+    /// 'naming', 'structural', 'implementation' or 'violation'
+    pub namespace: Option<String>,
+    pub pattern_types: Option<Vec<String>>,
+    pub min_confidence: Option<f64>,
+    /// Case-insensitive text the id or description contains
+    pub search: Option<String>,
+    /// Only pinned (true) or only unpinned (false) patterns
+    pub pinned: Option<bool>,
+}
+
+impl PatternFilter {
+    pub fn validate(&self) -> Result<(), ParseError> {
+        if let Some(namespace) = &self.namespace {
+            parse_namespace(namespace)?;
+        }
+        if let Some(min) = self.min_confidence {
+            check_confidence("min_confidence", min)?;
+        }
+        Ok(())
+    }
+
+    pub fn matches(&self, pattern: &Pattern, pinned: bool) -> bool {
+        let namespace = PatternNamespace::of(&pattern.pattern_type);
+        let search = self.search.as_deref().map(str::to_lowercase);
+        namespace != PatternNamespace::Activity
+            && self.namespace.as_deref().is_none_or(|name| namespace.as_str() == name)
+            && self
+                .pattern_types
+                .as_ref()
+                .is_none_or(|types| types.contains(&pattern.pattern_type))
+            && self.min_confidence.is_none_or(|min| pattern.confidence >= min)
+            && search.is_none_or(|text| {
+                pattern.id.to_lowercase().contains(&text) || pattern.description.to_lowercase().contains(&text)
+            })
+            && self.pinned.is_none_or(|wanted| wanted == pinned)
+    }
+}
+
+/// Fields of a pattern to replace; unset fields are left as they are
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct PatternChanges {
+    pub description: Option<String>,
+    pub confidence: Option<f64>,
+    pub contexts: Option<Vec<String>>,
+}
+
+impl PatternChanges {
+    pub fn validate(&self) -> Result<(), ParseError> {
+        if self.description.is_none() && self.confidence.is_none() && self.contexts.is_none() {
+            return Err(ValidationError::new(
+                ErrorCode::InvalidArgument,
+                "changes must set at least one of description, confidence or contexts",
+            )
+            .into());
+        }
+        if self.description.as_deref().is_some_and(|description| description.trim().is_empty()) {
+            return Err(ValidationError::new(ErrorCode::InvalidArgument, "description must not be empty").into());
+        }
+        if let Some(confidence) = self.confidence {
+            check_confidence("confidence", confidence)?;
+        }
+        Ok(())
+    }
+
+    /// Apply the changes to `pattern`
+    pub fn apply(self, pattern: &mut Pattern) {
+        if let Some(description) = self.description {
+            pattern.description = description.trim().to_string();
+        }
+        if let Some(confidence) = self.confidence {
+            pattern.confidence = confidence;
+        }
+        if let Some(contexts) = self.contexts {
+            pattern.contexts = contexts;
+        }
+    }
+}
+
+/// A learned pattern and whether it is pinned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct PatternRecord {
+    pub pattern: Pattern,
+    pub pinned: bool,
+}
+
+/// The error for a pattern id the engine does not know
+pub fn unknown_pattern(pattern_id: &str) -> ParseError {
+    ValidationError::new(ErrorCode::InvalidArgument, format!("Unknown pattern: {}", pattern_id)).into()
+}
+
+fn check_confidence(field: &str, value: f64) -> Result<(), ParseError> {
+    if !(0.0..=1.0).contains(&value) {
+        return Err(ValidationError::new(
+            ErrorCode::InvalidArgument,
+            format!("{} must be between 0 and 1, got {}", field, value),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(id: &str, pattern_type: &str, confidence: f64) -> Pattern {
+        Pattern {
+            id: id.to_string(),
+            pattern_type: pattern_type.to_string(),
+            description: format!("{} convention", id),
+            frequency: 3,
+            confidence,
+            examples: Vec::new(),
+            contexts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_filter_matches_text_and_pin_state() {
+        let filter = PatternFilter {
+            search: Some("CAMEL".to_string()),
+            pinned: Some(true),
+            ..Default::default()
+        };
+        let camel = pattern("naming_camel", "naming_function_camelCase", 0.8);
+        assert!(filter.matches(&camel, true));
+        assert!(!filter.matches(&camel, false));
+        assert!(!filter.matches(&pattern("naming_snake", "naming_function_snake_case", 0.8), true));
+        assert!(!PatternFilter::default().matches(&pattern("hour", "change_time_hour_14", 0.5), false));
+    }
+
+    #[test]
+    fn test_changes_are_validated_and_applied() {
+        assert!(PatternChanges::default().validate().is_err());
+        let out_of_range = PatternChanges { confidence: Some(1.5), ..Default::default() };
+        assert!(out_of_range.validate().unwrap_err().to_string().contains("[INVALID_ARGUMENT]"));
+        let blank = PatternChanges { description: Some("  ".to_string()), ..Default::default() };
+        assert!(blank.validate().is_err());
+
+        let changes = PatternChanges {
+            description: Some(" Services are singletons ".to_string()),
+            confidence: Some(0.95),
+            contexts: None,
+        };
+        changes.validate().unwrap();
+        let mut singleton = pattern("impl_singleton", "implementation_singleton", 0.4);
+        changes.apply(&mut singleton);
+        assert_eq!(singleton.description, "Services are singletons");
+        assert_eq!(singleton.confidence, 0.95);
+    }
+}
@@ -0,0 +1,193 @@
+//! Architectural drift between two git refs
+//!
+//! `PatternLearningEngine::compare_refs` reads the files that differ between a
+//! base and a head ref from git and reports, for the head relative to the base:
+//! - concepts added, removed, renamed and moved
+//! - functions whose cyclomatic or cognitive complexity changed
+//! - violations of rules, concurrency, allocation, secret and complexity budget
+//!   checks that the head introduces or resolves
+//!
+//! Violations are matched by rule, file, message and the text of the flagged
+//! line rather than by line number, so code moving within a file is neither
+//! introduced nor resolved.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::FileConceptDiff;
+use crate::patterns::budgets::FunctionMetrics;
+use crate::patterns::violations::Violation;
+use std::collections::HashMap;
+
+/// Complexity of one function on each ref
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ComplexityChange {
+    pub file_path: String,
+    pub function: String,
+    /// Unset for functions only on the head ref
+    pub base_cyclomatic: Option<u32>,
+    /// Unset for functions removed on the head ref
+    pub head_cyclomatic: Option<u32>,
+    pub base_cognitive: Option<u32>,
+    pub head_cognitive: Option<u32>,
+}
+
+impl ComplexityChange {
+    /// Head minus base cyclomatic complexity, counting a missing side as 0
+    pub fn cyclomatic_delta(&self) -> i64 {
+        self.head_cyclomatic.unwrap_or(0) as i64 - self.base_cyclomatic.unwrap_or(0) as i64
+    }
+}
+
+/// What the head ref changes relative to the base ref
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct RefComparison {
+    pub base_ref: String,
+    pub head_ref: String,
+    pub base_commit: String,
+    pub head_commit: String,
+    pub files_added: Vec<String>,
+    pub files_removed: Vec<String>,
+    pub files_modified: Vec<String>,
+    /// Concept changes of each analyzed file with any
+    pub concept_diffs: Vec<FileConceptDiff>,
+    pub concepts_added: u32,
+    pub concepts_removed: u32,
+    /// Functions whose complexity changed, largest cyclomatic increase first
+    pub complexity_changes: Vec<ComplexityChange>,
+    /// Total cyclomatic complexity of the changed files, head minus base
+    pub cyclomatic_delta: i64,
+    pub cognitive_delta: i64,
+    pub introduced_violations: Vec<Violation>,
+    pub resolved_violations: Vec<Violation>,
+}
+
+/// A violation with the trimmed text of the line it points at
+pub type LocatedViolation = (Violation, String);
+
+/// Functions of one file whose complexity differs between two versions.
+/// Functions are matched by name, in order when a name repeats.
+pub fn complexity_changes(
+    file_path: &str,
+    base: &[FunctionMetrics],
+    head: &[FunctionMetrics],
+) -> Vec<ComplexityChange> {
+    let mut unmatched: HashMap<&str, Vec<&FunctionMetrics>> = HashMap::new();
+    for function in base.iter().rev() {
+        unmatched.entry(function.name.as_str()).or_default().push(function);
+    }
+
+    let mut changes = Vec::new();
+    for function in head {
+        let before = unmatched.get_mut(function.name.as_str()).and_then(Vec::pop);
+        if before.is_some_and(|b| (b.cyclomatic, b.cognitive) == (function.cyclomatic, function.cognitive)) {
+            continue;
+        }
+        changes.push(ComplexityChange {
+            file_path: file_path.to_string(),
+            function: function.name.clone(),
+            base_cyclomatic: before.map(|b| b.cyclomatic),
+            head_cyclomatic: Some(function.cyclomatic),
+            base_cognitive: before.map(|b| b.cognitive),
+            head_cognitive: Some(function.cognitive),
+        });
+    }
+    // Whatever is left of the base was removed on the head
+    let mut removed: Vec<&FunctionMetrics> = unmatched.into_values().flatten().collect();
+    removed.sort_by_key(|function| function.line_range.start);
+    changes.extend(removed.into_iter().map(|function| ComplexityChange {
+        file_path: file_path.to_string(),
+        function: function.name.clone(),
+        base_cyclomatic: Some(function.cyclomatic),
+        head_cyclomatic: None,
+        base_cognitive: Some(function.cognitive),
+        head_cognitive: None,
+    }));
+    changes
+}
+
+/// Violations in `after` beyond those in `before`
+pub fn new_violations(before: &[LocatedViolation], after: &[LocatedViolation]) -> Vec<Violation> {
+    let key = |(violation, line): &LocatedViolation| {
+        (violation.rule_id.clone(), violation.file_path.clone(), violation.message.clone(), line.clone())
+    };
+    let mut remaining: HashMap<_, usize> = HashMap::new();
+    for located in before {
+        *remaining.entry(key(located)).or_default() += 1;
+    }
+    after
+        .iter()
+        .filter(|located| match remaining.get_mut(&key(located)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .map(|(violation, _)| violation.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LineRange;
+
+    fn function(name: &str, cyclomatic: u32, cognitive: u32) -> FunctionMetrics {
+        FunctionMetrics {
+            name: name.to_string(),
+            line_range: LineRange { start: 1, end: 1 },
+            cyclomatic,
+            cognitive,
+            lines: 1,
+        }
+    }
+
+    fn located(rule_id: &str, line: u32, text: &str) -> LocatedViolation {
+        let violation = Violation {
+            rule_id: rule_id.to_string(),
+            severity: "warning".to_string(),
+            file_path: "src/app.ts".to_string(),
+            line_range: LineRange { start: line, end: line },
+            message: "No console.log".to_string(),
+        };
+        (violation, text.to_string())
+    }
+
+    #[test]
+    fn test_complexity_changes_match_functions_by_name() {
+        let base = [function("parse", 3, 2), function("render", 5, 4), function("legacy", 2, 1)];
+        let head = [function("parse", 3, 2), function("render", 9, 12), function("validate", 4, 3)];
+
+        let changes = complexity_changes("src/app.ts", &base, &head);
+        let summary: Vec<(&str, Option<u32>, Option<u32>, i64)> = changes
+            .iter()
+            .map(|c| (c.function.as_str(), c.base_cyclomatic, c.head_cyclomatic, c.cyclomatic_delta()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("render", Some(5), Some(9), 4),
+                ("validate", None, Some(4), 4),
+                ("legacy", Some(2), None, -2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_new_violations_ignore_moved_lines() {
+        let base = [located("no-console-log", 10, "console.log(state);")];
+        let head = [
+            // The same call, pushed down by an added import
+            located("no-console-log", 11, "console.log(state);"),
+            located("no-console-log", 20, "console.log(user);"),
+        ];
+
+        let introduced = new_violations(&base, &head);
+        assert_eq!(introduced.len(), 1);
+        assert_eq!(introduced[0].line_range.start, 20);
+        assert!(new_violations(&head, &base).is_empty());
+    }
+}
@@ -3,11 +3,14 @@
 #[cfg(feature = "napi-bindings")]
 use napi_derive::napi;
 
+use crate::patterns::allocation::AllocationAnalyzer;
+use crate::patterns::concurrency::ConcurrencyAnalyzer;
+use crate::patterns::secrets::SecretScanner;
+use crate::parsing::{FileWalker, SourceMasker};
 use crate::patterns::types::{Pattern, PatternExample, ImplementationPattern, PatternExtractor};
 use crate::types::{ParseError, LineRange, SemanticConcept};
 use std::collections::HashMap;
 use regex::Regex;
-use walkdir::WalkDir;
 use std::fs;
 
 /// Analyzer for detecting implementation patterns (design patterns)
@@ -263,26 +266,77 @@ impl ImplementationPatternAnalyzer {
     }
 
     /// Analyze code files for pattern signatures
+    ///
+    /// Also runs the concurrency pass, which reports the concurrency primitives
+    /// in use as patterns and risky async code as `concurrency_violation` patterns,
+    /// the allocation pass, which reports allocation-heavy Rust code as
+    /// `performance_violation` patterns, and the secret pass, which reports
+    /// committed credentials as `security_violation` patterns.
     pub fn analyze_code_files(&mut self, path: &str) -> Result<Vec<Pattern>, ParseError> {
+        self.analyze_code_files_where(path, |_| true)
+    }
+
+    /// [`analyze_code_files`](Self::analyze_code_files) reading only the files `keep` accepts
+    pub fn analyze_code_files_where(
+        &mut self,
+        path: &str,
+        keep: impl Fn(&std::path::Path) -> bool,
+    ) -> Result<Vec<Pattern>, ParseError> {
         let mut detected_patterns = Vec::new();
+        let mut concurrency_usages = Vec::new();
+        let mut concurrency_risks = Vec::new();
+        let mut allocation_findings = Vec::new();
+        let mut secret_findings = Vec::new();
+        let env_secrets = SecretScanner::env_secrets(std::path::Path::new(path));
         
-        for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                let file_path = entry.path();
-                if let Some(extension) = file_path.extension().and_then(|s| s.to_str()) {
-                    if matches!(extension.to_lowercase().as_str(), "js" | "ts" | "jsx" | "tsx" | "rs" | "py" | "java" | "cs" | "cpp" | "c") {
-                        if let Ok(content) = fs::read_to_string(file_path) {
-                            let patterns = self.detect_patterns_in_code(&content, file_path.to_string_lossy().as_ref())?;
-                            detected_patterns.extend(patterns);
-                        }
-                    }
-                }
+        // Files are read and scanned in parallel; the passes share nothing mutable
+        let walker = FileWalker::new()
+            .filter(|file_path| Self::passes_for(file_path) != (false, false, false, false) && keep(file_path))
+            .parallel(true);
+        let (results, _) = walker.map(std::path::Path::new(path), |file_path| {
+            let (design_patterns, concurrency, allocation, secrets) = Self::passes_for(file_path);
+            let content = fs::read_to_string(file_path).ok()?;
+            let file_path = file_path.to_string_lossy();
+            Some((
+                design_patterns.then(|| self.detect_patterns_in_code(&content, file_path.as_ref())),
+                concurrency.then(|| ConcurrencyAnalyzer::analyze_file(file_path.as_ref(), &content)),
+                allocation.then(|| AllocationAnalyzer::analyze_file(file_path.as_ref(), &content)),
+                secrets.then(|| SecretScanner::analyze_file(file_path.as_ref(), &content, &env_secrets)),
+            ))
+        });
+        for (patterns, report, findings, secrets) in results {
+            if let Some(patterns) = patterns {
+                detected_patterns.extend(patterns?);
             }
+            if let Some(report) = report {
+                concurrency_usages.extend(report.usages);
+                concurrency_risks.extend(report.risks);
+            }
+            allocation_findings.extend(findings.unwrap_or_default());
+            secret_findings.extend(secrets.unwrap_or_default());
         }
+
+        detected_patterns.extend(ConcurrencyAnalyzer::to_patterns(&concurrency_usages, &concurrency_risks));
+        detected_patterns.extend(AllocationAnalyzer::to_patterns(&allocation_findings));
+        detected_patterns.extend(SecretScanner::to_patterns(&secret_findings));
         
         Ok(detected_patterns)
     }
 
+    /// Which of the design pattern, concurrency, allocation and secret passes read a file
+    fn passes_for(file_path: &std::path::Path) -> (bool, bool, bool, bool) {
+        let Some(extension) = file_path.extension().and_then(|s| s.to_str()) else {
+            return (false, false, false, false);
+        };
+        let extension = extension.to_lowercase();
+        (
+            matches!(extension.as_str(), "js" | "ts" | "jsx" | "tsx" | "rs" | "py" | "java" | "cs" | "cpp" | "c"),
+            ConcurrencyAnalyzer::supports_extension(&extension),
+            AllocationAnalyzer::supports_extension(&extension),
+            SecretScanner::supports_extension(&extension),
+        )
+    }
+
     /// Detect patterns in concepts using semantic analysis
     fn detect_patterns_in_concepts(&self, concepts: &[SemanticConcept]) -> Result<Vec<PatternMatch>, ParseError> {
         let mut pattern_matches = Vec::new();
@@ -341,9 +395,12 @@ impl ImplementationPatternAnalyzer {
         Ok(pattern_matches)
     }
 
-    /// Detect patterns in code using regex patterns
+    /// Detect patterns in code using regex patterns, ignoring what comments
+    /// and string literals say
     fn detect_patterns_in_code(&self, code: &str, file_path: &str) -> Result<Vec<Pattern>, ParseError> {
         let mut detected_patterns = Vec::new();
+        let code = SourceMasker::mask_file(file_path, code);
+        let code = code.as_str();
         
         for (pattern_name, signature) in &self.pattern_signatures {
             let mut evidence = Vec::new();
@@ -911,4 +968,83 @@ mod tests {
         let pattern_names: Vec<String> = patterns.iter().map(|p| p.id.clone()).collect();
         assert!(pattern_names.iter().any(|name| name.contains("singleton") || name.contains("factory") || name.contains("observer")));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_code_patterns_ignore_comments_and_strings() {
+        let analyzer = ImplementationPatternAnalyzer::new();
+        let logged = "// TODO: make this a Factory\nconsole.log(\"createUser() failed, build and make it again\");\n";
+        let patterns = analyzer.detect_patterns_in_code(logged, "src/users.ts").unwrap();
+        assert!(patterns.is_empty());
+
+        let factory = "export class UserFactory {\n  createUser() { return new User(); }\n}\n";
+        let patterns = analyzer.detect_patterns_in_code(factory, "src/users.ts").unwrap();
+        assert!(patterns.iter().any(|p| p.id == "implementation_factory"));
+    }
+
+    #[test]
+    fn test_code_files_include_concurrency_pass() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("worker.go"),
+            "func run() {\n    go process()\n    results := make(chan int)\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("sync.py"),
+            "async def poll():\n    time.sleep(5)\n",
+        )
+        .unwrap();
+
+        let mut analyzer = ImplementationPatternAnalyzer::new();
+        let patterns = analyzer.analyze_code_files(dir.path().to_str().unwrap()).unwrap();
+
+        assert!(patterns.iter().any(|p| p.id == "implementation_concurrency_goroutine"));
+        assert!(patterns.iter().any(|p| p.id == "implementation_concurrency_channel"));
+        let risk = patterns
+            .iter()
+            .find(|p| p.pattern_type == "concurrency_violation")
+            .unwrap();
+        assert_eq!(risk.examples[0].line_range.start, 2);
+    }
+
+    #[test]
+    fn test_code_files_include_allocation_pass() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("lib.rs"),
+            "fn fill(names: &[String]) {\n    for name in names {\n        let owned = name.clone();\n    }\n}\n",
+        )
+        .unwrap();
+
+        let mut analyzer = ImplementationPatternAnalyzer::new();
+        let patterns = analyzer.analyze_code_files(dir.path().to_str().unwrap()).unwrap();
+
+        let finding = patterns
+            .iter()
+            .find(|p| p.id == "performance_allocation_clone_in_loop")
+            .unwrap();
+        assert_eq!(finding.pattern_type, "performance_violation");
+        assert_eq!(finding.examples[0].line_range.start, 3);
+    }
+
+    #[test]
+    fn test_code_files_include_secret_pass() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(dir.path().join(".env"), "SESSION_SECRET=s3cr3t-session-value\n").unwrap();
+        fs::write(
+            dir.path().join("settings.yaml"),
+            "aws:\n  access_key_id: AKIA2E0A8F3B244C9986\n  session: s3cr3t-session-value\n",
+        )
+        .unwrap();
+
+        let mut analyzer = ImplementationPatternAnalyzer::new();
+        let patterns = analyzer.analyze_code_files(dir.path().to_str().unwrap()).unwrap();
+
+        let secrets: Vec<&Pattern> = patterns.iter().filter(|p| p.pattern_type == "security_violation").collect();
+        let ids: Vec<&str> = secrets.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["security_secret_aws_access_key", "security_secret_env_value_in_source"]);
+        assert_eq!(secrets[0].examples[0].code, "access_key_id: <redacted>");
+        assert_eq!(secrets[1].examples[0].line_range.start, 3);
+        assert!(secrets.iter().all(|p| !p.examples[0].code.contains("s3cr3t")));
+    }
+}
@@ -0,0 +1,178 @@
+//! Keyword extraction for matching problem descriptions against patterns
+//!
+//! Splitting on whitespace let filler words ("with", "this", "that") drive
+//! relevance and never matched `getUserById` against "user". Text is split into
+//! words, identifiers are split on camelCase and snake_case boundaries, stop
+//! words are dropped, programming synonyms are mapped to one term ("endpoint"
+//! and "route" both become `api`) and the rest is reduced to a stem, so
+//! "caching", "cached" and "cache" all match. Both sides of a comparison must
+//! go through [`KeywordExtractor::extract`].
+
+use crate::patterns::naming::NamingPatternAnalyzer;
+use std::collections::HashSet;
+
+/// English and request filler that says nothing about the code
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "been", "before",
+    "but", "by", "can", "could", "do", "does", "for", "from", "get", "had", "has", "have", "how", "i",
+    "if", "in", "into", "is", "it", "its", "just", "let", "like", "make", "me", "more", "my", "need",
+    "new", "no", "not", "now", "of", "on", "one", "only", "or", "other", "our", "out", "please", "should",
+    "so", "some", "such", "than", "that", "the", "their", "them", "then", "there", "these", "they",
+    "this", "those", "to", "up", "us", "use", "used", "using", "via", "want", "was", "way", "we", "were",
+    "what", "when", "where", "which", "while", "who", "will", "with", "within", "without", "would",
+    "you", "your",
+];
+
+/// Programming terms mapped to one canonical keyword
+const SYNONYMS: &[(&str, &[&str])] = &[
+    ("api", &["endpoint", "endpoints", "rest", "restful", "route", "routes", "routing", "http"]),
+    ("auth", &["authentication", "authenticate", "authorization", "authorize", "login", "logout", "signin", "signup", "oauth", "jwt"]),
+    ("async", &["asynchronous", "await", "promise", "promises", "future", "futures", "concurrent", "concurrency"]),
+    ("cache", &["caching", "cached", "caches", "memoize", "memoization", "memoized"]),
+    ("config", &["configuration", "configure", "settings", "setting", "env", "environment"]),
+    ("db", &["database", "databases", "sql", "query", "queries", "persistence", "orm"]),
+    ("error", &["errors", "exception", "exceptions", "failure", "failures"]),
+    ("function", &["func", "fn", "method", "methods", "functions", "def"]),
+    ("repository", &["repo", "repositories", "dao"]),
+    ("test", &["tests", "testing", "spec", "specs", "unittest"]),
+    ("ui", &["frontend", "gui", "interface"]),
+    ("validate", &["validation", "validator", "validators", "sanitize", "sanitization"]),
+];
+
+/// Short words kept even though they are under the minimum length
+const SHORT_TERMS: &[&str] = &["ai", "db", "io", "ui", "ux", "id", "ci", "cd", "js", "ts", "go", "py", "ml", "os"];
+
+/// Suffixes removed by [`KeywordExtractor::stem`], longest first
+const SUFFIXES: &[&str] = &["ations", "ation", "ings", "ing", "ers", "ed", "er", "es", "s"];
+
+pub struct KeywordExtractor;
+
+impl KeywordExtractor {
+    /// Keywords of a text or identifier, in order of first appearance
+    pub fn extract(text: &str) -> Vec<String> {
+        let mut seen = HashSet::new();
+        text.split(|c: char| !c.is_alphanumeric() && c != '_')
+            .flat_map(NamingPatternAnalyzer::split_words)
+            .filter_map(|word| Self::normalize(&word))
+            .filter(|keyword| seen.insert(keyword.clone()))
+            .collect()
+    }
+
+    /// Keyword of a single word; `None` for stop words, numbers and short noise
+    pub fn normalize(word: &str) -> Option<String> {
+        let word = word.to_lowercase();
+        if word.chars().all(|c| c.is_ascii_digit()) || STOP_WORDS.contains(&word.as_str()) {
+            return None;
+        }
+        if let Some((canonical, _)) = SYNONYMS
+            .iter()
+            .find(|(canonical, variants)| *canonical == word || variants.contains(&word.as_str()))
+        {
+            return Some(canonical.to_string());
+        }
+        if word.len() < 3 && !SHORT_TERMS.contains(&word.as_str()) {
+            return None;
+        }
+        Some(Self::stem(&word))
+    }
+
+    /// Light suffix stripping: "caching", "cached", "caches" -> "cach"
+    pub fn stem(word: &str) -> String {
+        if word.len() <= 3 {
+            return word.to_string();
+        }
+        if let Some(base) = word.strip_suffix("ies") {
+            return format!("{}y", base);
+        }
+
+        let mut stem = word;
+        for suffix in SUFFIXES {
+            if let Some(base) = word.strip_suffix(suffix) {
+                // "class", "status", "analysis" are not plurals
+                let not_plural = *suffix == "s" && (base.ends_with('s') || base.ends_with('u') || base.ends_with('i'));
+                if base.len() >= 3 && !not_plural {
+                    stem = base;
+                    break;
+                }
+            }
+        }
+
+        let mut stem = stem.to_string();
+        if stem.len() > 3 && stem.ends_with('e') {
+            stem.pop();
+        }
+        // "mapping" -> "mapp" -> "map"; compared by char, as two bytes of one
+        // multi-byte char can be equal
+        let mut tail = stem.chars().rev();
+        if let (Some(last), Some(before)) = (tail.next(), tail.next()) {
+            if stem.chars().count() > 3 && last == before && !"aeiouls".contains(last) {
+                stem.pop();
+            }
+        }
+        stem
+    }
+
+    /// Share of keywords two texts have in common (Jaccard index), 0.0 to 1.0
+    pub fn similarity(a: &str, b: &str) -> f64 {
+        let a: HashSet<String> = Self::extract(a).into_iter().collect();
+        let b: HashSet<String> = Self::extract(b).into_iter().collect();
+        let union = a.union(&b).count();
+        if union == 0 {
+            0.0
+        } else {
+            a.intersection(&b).count() as f64 / union as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stop_words_dropped_and_identifiers_split() {
+        let keywords = KeywordExtractor::extract("Add this with the getUserById handler and user_profile");
+        assert_eq!(keywords, vec!["add", "user", "id", "handl", "profil"]);
+
+        assert!(KeywordExtractor::extract("the with this that from 2024").is_empty());
+    }
+
+    #[test]
+    fn test_stems_and_synonyms_match_variants() {
+        for word in ["cache", "caching", "cached", "memoize"] {
+            assert_eq!(KeywordExtractor::normalize(word).as_deref(), Some("cache"), "{}", word);
+        }
+        assert_eq!(KeywordExtractor::normalize("Endpoints").as_deref(), Some("api"));
+        assert_eq!(KeywordExtractor::normalize("services"), KeywordExtractor::normalize("service"));
+        assert_eq!(KeywordExtractor::normalize("mapping"), KeywordExtractor::normalize("maps"));
+        assert_eq!(KeywordExtractor::normalize("status").as_deref(), Some("status"));
+        assert_eq!(KeywordExtractor::normalize("class").as_deref(), Some("class"));
+        // U+0820 is E0 A0 A0: its last two bytes are equal but it is not a doubled letter
+        assert_eq!(KeywordExtractor::stem("abc\u{820}"), "abc\u{820}");
+        assert_eq!(KeywordExtractor::stem("größenn"), "größen");
+    }
+
+    #[test]
+    fn test_similarity_ignores_filler() {
+        let similar = KeywordExtractor::similarity(
+            "Add caching to the product lookup",
+            "cache product lookups",
+        );
+        assert!(similar > 0.6, "{}", similar);
+
+        let filler_only = KeywordExtractor::similarity("do this with that", "this is about that");
+        assert_eq!(filler_only, 0.0);
+    }
+
+    #[test]
+    fn test_request_keywords_collapse_synonyms() {
+        let keywords = KeywordExtractor::extract("Build a REST API endpoint using Express");
+
+        assert!(keywords.contains(&"build".to_string()));
+        assert!(keywords.contains(&"api".to_string()));
+        assert!(keywords.contains(&"express".to_string()));
+        // Filler is dropped and REST/API/endpoint collapse into one keyword
+        assert!(!keywords.contains(&"using".to_string()));
+        assert_eq!(keywords.len(), 3);
+    }
+}
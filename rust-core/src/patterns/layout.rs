@@ -0,0 +1,345 @@
+//! Recurring directory layouts
+//!
+//! Sibling directories often share a layout: every feature under `features/`
+//! holds `components/`, `hooks/` and `api/`, every package under `packages/`
+//! holds `src/` and `tests/`. A parent's template is the set of child
+//! directory names that most of its children have; templates are learned
+//! wherever they occur, at any depth, and children missing part of their
+//! parent's template are reported as deviations.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::patterns::types::{Pattern, PatternExample};
+use crate::types::{LineRange, ParseError};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Fewest conforming children for a layout to count as a template
+const MIN_CONFORMING: usize = 2;
+
+/// Child directory names shared by most subdirectories of `parent`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct DirectoryTemplate {
+    /// Parent directory relative to the analyzed root; `.` for the root itself
+    pub parent: String,
+    /// Sorted child directory names making up the template
+    pub entries: Vec<String>,
+    /// Subdirectories of `parent` that contain every entry
+    pub conforming: Vec<String>,
+    /// Number of subdirectories of `parent` the template was learned from
+    pub sibling_count: u32,
+    /// Share of the subdirectories that conform
+    pub coverage: f64,
+}
+
+/// A subdirectory whose layout departs from its parent's template
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct TemplateDeviation {
+    /// The deviating directory, relative to the analyzed root
+    pub directory: String,
+    /// Parent whose template it departs from
+    pub parent: String,
+    /// Template entries the directory lacks
+    pub missing: Vec<String>,
+    /// Child directories it has that aren't part of the template
+    pub extra: Vec<String>,
+}
+
+/// Templates and deviations found under a root, ordered by path
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct DirectoryLayoutReport {
+    pub templates: Vec<DirectoryTemplate>,
+    pub deviations: Vec<TemplateDeviation>,
+}
+
+pub struct DirectoryLayoutLearner;
+
+impl DirectoryLayoutLearner {
+    /// Learn the templates under `root`, skipping hidden directories and the
+    /// ones `is_ignored` rejects by name
+    pub fn learn(root: &str, is_ignored: impl Fn(&str) -> bool) -> Result<DirectoryLayoutReport, ParseError> {
+        let root_path = Path::new(root);
+        if !root_path.is_dir() {
+            return Err(ParseError::from_reason(format!("Not a directory: {}", root)));
+        }
+
+        // Every kept directory, with the names of its kept children
+        let mut children: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let walker = WalkDir::new(root_path).into_iter().filter_entry(|entry| {
+            entry.depth() == 0 || {
+                let name = entry.file_name().to_string_lossy();
+                !name.starts_with('.') && !is_ignored(&name)
+            }
+        });
+        for entry in walker.filter_map(|e| e.ok()) {
+            if !entry.file_type().is_dir() {
+                continue;
+            }
+            let relative = Self::relative(entry.path(), root_path);
+            children.entry(relative.clone()).or_default();
+            if entry.depth() > 0 {
+                let parent = entry
+                    .path()
+                    .parent()
+                    .map(|parent| Self::relative(parent, root_path))
+                    .unwrap_or_else(|| ".".to_string());
+                let name = entry.file_name().to_string_lossy().to_string();
+                children.entry(parent).or_default().insert(name);
+            }
+        }
+
+        Ok(Self::learn_from_tree(&children))
+    }
+
+    /// Learn templates from a directory tree given as each directory's child
+    /// names, keyed by relative path
+    pub fn learn_from_tree(children: &BTreeMap<String, BTreeSet<String>>) -> DirectoryLayoutReport {
+        let empty = BTreeSet::new();
+        let mut report = DirectoryLayoutReport::default();
+
+        for (parent, names) in children {
+            if names.len() < MIN_CONFORMING {
+                continue;
+            }
+            let siblings: Vec<(String, &BTreeSet<String>)> = names
+                .iter()
+                .map(|name| {
+                    let path = Self::join(parent, name);
+                    let layout = children.get(&path).unwrap_or(&empty);
+                    (path, layout)
+                })
+                .collect();
+
+            // Names held by a strict majority of the siblings
+            let mut counts: BTreeMap<&String, usize> = BTreeMap::new();
+            for (_, layout) in &siblings {
+                for name in layout.iter() {
+                    *counts.entry(name).or_insert(0) += 1;
+                }
+            }
+            let entries: BTreeSet<String> = counts
+                .into_iter()
+                .filter(|(_, count)| count * 2 > siblings.len())
+                .map(|(name, _)| name.clone())
+                .collect();
+            if entries.is_empty() {
+                continue;
+            }
+
+            let conforming: Vec<String> = siblings
+                .iter()
+                .filter(|(_, layout)| entries.is_subset(layout))
+                .map(|(path, _)| path.clone())
+                .collect();
+            if conforming.len() < MIN_CONFORMING {
+                continue;
+            }
+
+            for (path, layout) in &siblings {
+                let missing: Vec<String> = entries.difference(layout).cloned().collect();
+                if missing.is_empty() {
+                    continue;
+                }
+                report.deviations.push(TemplateDeviation {
+                    directory: path.clone(),
+                    parent: parent.clone(),
+                    missing,
+                    extra: layout.difference(&entries).cloned().collect(),
+                });
+            }
+            report.templates.push(DirectoryTemplate {
+                parent: parent.clone(),
+                entries: entries.into_iter().collect(),
+                coverage: conforming.len() as f64 / siblings.len() as f64,
+                conforming,
+                sibling_count: siblings.len() as u32,
+            });
+        }
+
+        report
+    }
+
+    /// One `structure_directory_template` pattern per template and one
+    /// `structure_template_deviation` pattern per deviation
+    pub fn to_patterns(report: &DirectoryLayoutReport, id_for: impl Fn(&str) -> String) -> Vec<Pattern> {
+        let example = |directory: &str, code: String| PatternExample {
+            code,
+            file_path: directory.to_string(),
+            line_range: LineRange { start: 0, end: 0 },
+        };
+
+        let mut patterns = Vec::new();
+        for template in &report.templates {
+            let layout = Self::describe(&template.entries);
+            patterns.push(Pattern {
+                id: format!("struct_template_{}", id_for(&format!("{}:{}", template.parent, layout))),
+                pattern_type: "structure_directory_template".to_string(),
+                description: format!(
+                    "Directories under {} share the layout {} ({} of {})",
+                    template.parent,
+                    layout,
+                    template.conforming.len(),
+                    template.sibling_count
+                ),
+                frequency: template.conforming.len() as u32,
+                confidence: template.coverage,
+                examples: template
+                    .conforming
+                    .iter()
+                    .map(|directory| example(directory, layout.clone()))
+                    .collect(),
+                contexts: vec!["architecture".to_string(), "organization".to_string()],
+            });
+        }
+        for deviation in &report.deviations {
+            patterns.push(Pattern {
+                id: format!("struct_deviation_{}", id_for(&deviation.directory)),
+                pattern_type: "structure_template_deviation".to_string(),
+                description: format!(
+                    "{} lacks {} found in the other directories under {}",
+                    deviation.directory,
+                    Self::describe(&deviation.missing),
+                    deviation.parent
+                ),
+                frequency: 1,
+                confidence: 1.0,
+                examples: vec![example(&deviation.directory, Self::describe(&deviation.missing))],
+                contexts: vec!["architecture".to_string(), "deviation".to_string()],
+            });
+        }
+        patterns
+    }
+
+    fn describe(names: &[String]) -> String {
+        names.iter().map(|name| format!("{}/", name)).collect::<Vec<_>>().join(", ")
+    }
+
+    fn join(parent: &str, name: &str) -> String {
+        if parent == "." {
+            name.to_string()
+        } else {
+            format!("{}/{}", parent, name)
+        }
+    }
+
+    fn relative(path: &Path, root: &Path) -> String {
+        let relative = path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        if relative.is_empty() {
+            ".".to_string()
+        } else {
+            relative
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn mkdirs(root: &Path, dirs: &[&str]) {
+        for dir in dirs {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_templates_are_learned_below_depth_three() {
+        let dir = TempDir::new().unwrap();
+        mkdirs(
+            dir.path(),
+            &[
+                "packages/web/src/app/features/auth/components",
+                "packages/web/src/app/features/auth/hooks",
+                "packages/web/src/app/features/auth/api",
+                "packages/web/src/app/features/billing/components",
+                "packages/web/src/app/features/billing/hooks",
+                "packages/web/src/app/features/billing/api",
+                "packages/web/src/app/features/billing/charts",
+                "packages/web/src/app/features/profile/components",
+                "packages/web/src/app/features/profile/hooks",
+                "packages/web/node_modules/dep/lib",
+            ],
+        );
+
+        let report = DirectoryLayoutLearner::learn(dir.path().to_str().unwrap(), |name| name == "node_modules").unwrap();
+
+        let template = report
+            .templates
+            .iter()
+            .find(|t| t.parent == "packages/web/src/app/features")
+            .expect("feature template");
+        assert_eq!(template.entries, vec!["api", "components", "hooks"]);
+        assert_eq!(
+            template.conforming,
+            vec!["packages/web/src/app/features/auth", "packages/web/src/app/features/billing"]
+        );
+        assert_eq!(template.sibling_count, 3);
+
+        assert_eq!(
+            report.deviations,
+            vec![TemplateDeviation {
+                directory: "packages/web/src/app/features/profile".to_string(),
+                parent: "packages/web/src/app/features".to_string(),
+                missing: vec!["api".to_string()],
+                extra: vec![],
+            }]
+        );
+        assert!(report.templates.iter().all(|t| !t.parent.contains("node_modules")));
+    }
+
+    #[test]
+    fn test_siblings_without_a_shared_layout_have_no_template() {
+        let mut children: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let set = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<BTreeSet<_>>();
+        children.insert(".".to_string(), set(&["src", "docs", "scripts"]));
+        children.insert("src".to_string(), set(&["models"]));
+        children.insert("docs".to_string(), set(&["images"]));
+        children.insert("scripts".to_string(), set(&[]));
+
+        let report = DirectoryLayoutLearner::learn_from_tree(&children);
+        assert!(report.templates.is_empty());
+        assert!(report.deviations.is_empty());
+    }
+
+    #[test]
+    fn test_patterns_for_templates_and_deviations() {
+        let report = DirectoryLayoutReport {
+            templates: vec![DirectoryTemplate {
+                parent: "services".to_string(),
+                entries: vec!["handlers".to_string(), "models".to_string()],
+                conforming: vec!["services/orders".to_string(), "services/users".to_string()],
+                sibling_count: 3,
+                coverage: 2.0 / 3.0,
+            }],
+            deviations: vec![TemplateDeviation {
+                directory: "services/billing".to_string(),
+                parent: "services".to_string(),
+                missing: vec!["models".to_string()],
+                extra: vec!["tasks".to_string()],
+            }],
+        };
+
+        let patterns = DirectoryLayoutLearner::to_patterns(&report, |key| key.replace('/', "_"));
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(patterns[0].pattern_type, "structure_directory_template");
+        assert_eq!(patterns[0].frequency, 2);
+        assert_eq!(patterns[0].examples.len(), 2);
+        assert!(patterns[0].description.contains("handlers/, models/"));
+        assert_eq!(patterns[1].pattern_type, "structure_template_deviation");
+        assert_eq!(patterns[1].id, "struct_deviation_services_billing");
+        assert!(patterns[1].description.contains("lacks models/"));
+    }
+}
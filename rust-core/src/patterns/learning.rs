@@ -3,18 +3,56 @@
 #[cfg(feature = "napi-bindings")]
 use napi_derive::napi;
 
+use crate::patterns::allocation::AllocationAnalyzer;
+use crate::patterns::budgets::{ComplexityBudgetChecker, ComplexityThresholds, FunctionMetrics};
+use crate::patterns::complexity_report::{ComplexityReport, ComplexityReportBuilder};
+use crate::patterns::concurrency::ConcurrencyAnalyzer;
+use crate::patterns::consistency::NamingConsistencyChecker;
+use crate::patterns::consolidation::ConsolidationStrategy;
+use crate::patterns::curation::{unknown_pattern, PatternChanges, PatternFilter, PatternRecord};
+use crate::patterns::drift::{self, LocatedViolation, RefComparison};
 use crate::patterns::implementation::ImplementationPatternAnalyzer;
+use crate::patterns::keywords::KeywordExtractor;
+use crate::patterns::layout::{DirectoryLayoutLearner, DirectoryLayoutReport};
 use crate::patterns::naming::NamingPatternAnalyzer;
-use crate::patterns::prediction::ApproachPredictor;
+use crate::patterns::prediction::{ApproachPredictor, SurroundingCode};
+use crate::patterns::provenance::ProvenanceLog;
+use crate::patterns::renames::{DeletionOutcome, FileFingerprint, RenameTracker};
+use crate::patterns::review::{
+    enclosing_concept, hunk_concepts, suggestion_for, DiffReview, ReviewComment, ReviewedHunk, UnifiedDiff,
+    NAMING_CONVENTION_RULE,
+};
+use crate::patterns::rules::RuleEngine;
+use crate::patterns::secrets::SecretScanner;
+use crate::patterns::sharing::{self, MergeStrategy, PatternExport, PatternExportFilter, PatternImportReport};
+use crate::patterns::store::{self, LearningStore};
 use crate::patterns::structural::StructuralPatternAnalyzer;
+use crate::patterns::taxonomy::{ActivityCounter, ActivityCounters, PatternNamespace};
+use crate::patterns::violations::Violation;
 use crate::patterns::types::{
-    Pattern, PatternAnalysisResult, PatternLearner as PatternLearnerTrait,
+    ApproachQuery, HistoricalApproach, LearningScope, NamingFix, Pattern, PatternAnalysisResult, PatternExplanation,
+    PatternLearner as PatternLearnerTrait, sort_patterns,
+};
+use crate::analysis::{
+    nice_level, ConceptChange, FileConceptDiff, FileSampler, FrameworkDetector, Pacer, SampleCandidate, SamplingReport,
+    noop_telemetry, FileOverlay, SemanticAnalyzer, SharedTelemetry, TestInventoryScanner, Tombstone, TombstoneStore,
+    DEFAULT_NICE,
 };
-use crate::types::{ParseError, SemanticConcept};
-use serde_json::{from_str, Value};
-use std::collections::{HashMap, HashSet};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::analysis::{GitRepository, GitRevision};
+use crate::parsing::{FileWalker, NameExtractor, ParserManager};
+use crate::quotas::EvictionPolicy;
+use crate::types::validation::{self, AnalysisInput, ChangeInput, ConceptInput};
+use crate::types::{
+    mark_generated, paths, AnalysisConfig, FileClass, FileClassifier, ParseError, PathScope, ProjectConfig,
+    SemanticConcept,
+};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
-use walkdir::WalkDir;
+
+/// Most patterns returned by `find_relevant_patterns`
+const MAX_RELEVANT_PATTERNS: usize = 5;
 
 /// Core learning engine that orchestrates pattern discovery across all domains
 #[cfg_attr(feature = "napi-bindings", napi)]
@@ -23,9 +61,38 @@ pub struct PatternLearningEngine {
     structural_analyzer: StructuralPatternAnalyzer,
     implementation_analyzer: ImplementationPatternAnalyzer,
     approach_predictor: ApproachPredictor,
+    rule_engine: RuleEngine,
     learned_patterns: HashMap<String, Pattern>,
+    /// Change and file activity seen by incremental learning, kept out of the patterns
+    activity: ActivityCounters,
+    provenance: ProvenanceLog,
     learning_metrics: LearningMetrics,
     confidence_threshold: f64,
+    deterministic: bool,
+    /// Filters and limits from the project's `.in-memoria` configuration, if any
+    project_config: Option<AnalysisConfig>,
+    /// Parses file versions for concept diffs during incremental learning, created on first use
+    concept_analyzer: Option<SemanticAnalyzer>,
+    /// Pairs watcher delete/create events that are really a moved file
+    renames: RenameTracker,
+    /// Coverage of the last codebase walk that hit the file limit
+    sampling_report: Option<SamplingReport>,
+    /// Nice level set by the host, taking precedence over the project's
+    nice: Option<u8>,
+    /// Consolidation strategy set by the host, taking precedence over the project's
+    consolidation: Option<ConsolidationStrategy>,
+    /// Receives learned-pattern and timeout events; a no-op unless the host sets one
+    telemetry: SharedTelemetry,
+    /// Contents read instead of the files on disk during codebase learning
+    overlay: FileOverlay,
+    /// Budgets enforced by `check_complexity_budgets`, once the host sets them
+    complexity_thresholds: Option<ComplexityThresholds>,
+    /// Ids of patterns curated by hand; learning, decay and consolidation leave them alone
+    pinned: BTreeSet<String>,
+    /// Removed files whose examples were subtracted from the patterns holding them
+    tombstones: TombstoneStore,
+    /// Example files found on disk by earlier runs; only these can be found removed
+    walked_files: BTreeSet<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -72,7 +139,10 @@ impl PatternLearningEngine {
             structural_analyzer: StructuralPatternAnalyzer::new(),
             implementation_analyzer: ImplementationPatternAnalyzer::new(),
             approach_predictor: ApproachPredictor::new(),
+            rule_engine: RuleEngine::new(),
             learned_patterns: HashMap::new(),
+            activity: ActivityCounters::default(),
+            provenance: ProvenanceLog::new(),
             learning_metrics: LearningMetrics {
                 total_patterns_learned: 0,
                 confidence_distribution: HashMap::new(),
@@ -81,9 +151,179 @@ impl PatternLearningEngine {
                 last_learning_timestamp: None,
             },
             confidence_threshold: 0.5,
+            deterministic: false,
+            project_config: None,
+            concept_analyzer: None,
+            renames: RenameTracker::new(),
+            sampling_report: None,
+            nice: None,
+            consolidation: None,
+            telemetry: noop_telemetry(),
+            overlay: FileOverlay::default(),
+            complexity_thresholds: None,
+            pinned: BTreeSet::new(),
+            tombstones: TombstoneStore::default(),
+            walked_files: BTreeSet::new(),
+        }
+    }
+
+    /// Enable or disable deterministic learning
+    ///
+    /// In deterministic mode two runs over the same input produce identical output:
+    /// pattern IDs are derived from content instead of the clock and time-of-day
+    /// patterns are skipped. Files are visited in sorted order and results are
+    /// returned sorted by ID in either mode.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn set_deterministic(&mut self, enabled: bool) {
+        self.deterministic = enabled;
+    }
+
+    /// Whether deterministic learning is enabled
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// Set how often codebase learning yields to other work on the runtime:
+    /// 0 never yields, 4 yields after every file. Overrides the project's
+    /// `analysis.nice` setting.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn set_nice(&mut self, level: u32) -> Result<(), ParseError> {
+        self.nice = Some(nice_level(level)?);
+        Ok(())
+    }
+
+    /// Choose how candidate patterns are grouped before they are merged:
+    /// `description` (same first three words, the default), `type-context`,
+    /// `example-similarity` or `embedding`
+    ///
+    /// Takes precedence over `analysis.consolidation` in the project configuration.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn set_consolidation_strategy(&mut self, strategy: String) -> Result<(), ParseError> {
+        self.consolidation = Some(ConsolidationStrategy::parse(&strategy)?);
+        Ok(())
+    }
+
+    /// Name of the consolidation strategy learning uses
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_consolidation_strategy(&self) -> String {
+        self.consolidation_strategy().as_str().to_string()
+    }
+
+    /// Learn from these contents instead of the files on disk, e.g. unsaved
+    /// editor buffers, until the overlay is cleared or replaced
+    ///
+    /// Keys are absolute or project-relative paths. Overlaid files missing on
+    /// disk are learned from as if they existed. Returns the number of
+    /// overlaid files.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn set_file_overlay(&mut self, files: HashMap<String, String>) -> u32 {
+        self.overlay = FileOverlay::new(files);
+        self.overlay.len() as u32
+    }
+
+    /// Go back to reading every file from disk
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn clear_file_overlay(&mut self) {
+        self.overlay = FileOverlay::default();
+    }
+
+    /// Call `callback` with a `TelemetryEvent` for every learned pattern and
+    /// learning timeout. The callback runs without blocking learning.
+    #[cfg(feature = "napi-bindings")]
+    #[napi]
+    pub fn set_telemetry_callback(
+        &mut self,
+        callback: napi::threadsafe_function::ThreadsafeFunction<
+            crate::analysis::TelemetryEvent,
+            (),
+            crate::analysis::TelemetryEvent,
+            napi::Status,
+            false,
+        >,
+    ) {
+        self.telemetry = std::sync::Arc::new(crate::analysis::CallbackTelemetry::new(callback));
+    }
+
+    /// Apply the `.in-memoria` configuration found in a project root
+    ///
+    /// Returns false and keeps the current settings when the project has no
+    /// configuration file. `learn_from_codebase` calls this for the analyzed path.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn load_project_config(&mut self, project_path: String) -> Result<bool, ParseError> {
+        let root = std::path::Path::new(&project_path);
+        match ProjectConfig::load(root)? {
+            Some(project) => {
+                if let Some(strategy) = &project.analysis.consolidation {
+                    ConsolidationStrategy::parse(strategy)?;
+                }
+                self.project_config = Some(AnalysisConfig::from_project_config(&project, root)?);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Let predictions for testing tasks name the project's test framework
+    fn detect_test_frameworks(&mut self, project_path: &str) {
+        // Without an inventory predictions stay generic
+        if let Ok(inventory) = TestInventoryScanner::scan(std::path::Path::new(project_path)) {
+            self.approach_predictor.set_test_frameworks(inventory.frameworks);
+        }
+    }
+
+    /// Let predictions favor approaches built on the project's frameworks
+    fn detect_installed_frameworks(&mut self, project_path: &str) {
+        // Unreadable manifests leave every approach unpenalized
+        if let Ok(frameworks) = FrameworkDetector::detect(project_path) {
+            self.approach_predictor.set_installed_frameworks(&frameworks);
         }
     }
 
+    /// Which files the last `learn_from_codebase` sampled and skipped, if it hit the file limit
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_sampling_report(&self) -> Option<SamplingReport> {
+        self.sampling_report.clone()
+    }
+
+    /// Load user-defined pattern rules from a JSON or YAML string
+    ///
+    /// Rules are evaluated alongside learned patterns during `learn_from_codebase`.
+    /// Returns the number of rules loaded; rules with an existing name replace it.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn load_rules(&mut self, definitions: String) -> Result<u32, ParseError> {
+        self.rule_engine.load(&definitions).map(|count| count as u32)
+    }
+
+    /// Load user-defined pattern rules from a `.json`, `.yaml` or `.yml` file
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn load_rules_from_file(&mut self, path: String) -> Result<u32, ParseError> {
+        self.rule_engine.load_file(&path).map(|count| count as u32)
+    }
+
+    /// Add approach templates from a JSON or YAML string
+    ///
+    /// Templates extend the built-in set used by approach prediction; a
+    /// template with a built-in id replaces it. Returns the number loaded.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn load_approach_templates(&mut self, definitions: String) -> Result<u32, ParseError> {
+        self.approach_predictor.load_templates(&definitions).map(|count| count as u32)
+    }
+
+    /// Add approach templates from a `.json`, `.yaml` or `.yml` file
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn load_approach_templates_from_file(&mut self, path: String) -> Result<u32, ParseError> {
+        self.approach_predictor.load_templates_file(&path).map(|count| count as u32)
+    }
+
+    /// Evaluate user-defined rules against a single file's content
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn evaluate_rules(&mut self, file_path: String, content: String) -> Vec<Pattern> {
+        let language = self.detect_language_from_path(&file_path);
+        let matches = self.rule_engine.evaluate_file(&file_path, &content, &language);
+        self.rule_engine.to_patterns(&matches)
+    }
+
     /// Learn patterns from an entire codebase
     ///
     /// # Safety
@@ -94,15 +334,64 @@ impl PatternLearningEngine {
         &mut self,
         path: String,
     ) -> Result<Vec<Pattern>, ParseError> {
+        self.learn_in_scope(path, PathScope::default()).await
+    }
+
+    /// Learn patterns from the files of a codebase selected by include and
+    /// exclude globs or by paths, e.g. just `src/payments/**` after a feature
+    /// change. Other files are neither parsed nor checked against rules.
+    ///
+    /// # Safety
+    /// This function is marked unsafe for NAPI compatibility. It performs file system operations
+    /// and pattern analysis that are inherently safe but marked unsafe for JavaScript interop.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async unsafe fn learn_from_codebase_scoped(
+        &mut self,
+        path: String,
+        scope: LearningScope,
+    ) -> Result<Vec<Pattern>, ParseError> {
+        let scope = PathScope::new(
+            scope.include.as_deref().unwrap_or_default(),
+            scope.exclude.as_deref().unwrap_or_default(),
+            scope.paths.as_deref().unwrap_or_default(),
+        )?;
+        self.learn_in_scope(path, scope).await
+    }
+
+    /// Learn patterns from the given directories and files of a codebase,
+    /// relative to its root
+    ///
+    /// # Safety
+    /// This function is marked unsafe for NAPI compatibility. It performs file system operations
+    /// and pattern analysis that are inherently safe but marked unsafe for JavaScript interop.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async unsafe fn learn_from_paths(
+        &mut self,
+        path: String,
+        paths: Vec<String>,
+    ) -> Result<Vec<Pattern>, ParseError> {
+        if paths.iter().all(|path| path.trim().is_empty()) {
+            return Err(validation::ValidationError::new(
+                validation::ErrorCode::InvalidArgument,
+                "paths must name at least one directory or file",
+            )
+            .into());
+        }
+        let scope = PathScope::new(&[], &[], &paths)?;
+        self.learn_in_scope(path, scope).await
+    }
+
+    async fn learn_in_scope(&mut self, path: String, scope: PathScope) -> Result<Vec<Pattern>, ParseError> {
+        self.overlay.require_root(&path)?;
+        self.load_project_config(path.clone())?;
+        self.approach_predictor
+            .set_project_path(Some(store::project_key(&path)));
+        self.detect_test_frameworks(&path);
+        self.detect_installed_frameworks(&path);
+
         let session_start = std::time::Instant::now();
         let mut session = LearningSession {
-            session_id: format!(
-                "session_{}",
-                std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs()
-            ),
+            session_id: format!("session_{}", self.generate_pattern_id(&path)),
             patterns_discovered: Vec::new(),
             analysis_duration_ms: 0,
             files_analyzed: 0,
@@ -110,7 +399,7 @@ impl PatternLearningEngine {
         };
 
         // Phase 1: Collect semantic concepts from the codebase
-        let concepts = self.extract_semantic_concepts(&path).await?;
+        let concepts = self.extract_semantic_concepts(&path, &scope).await?;
         session.concepts_analyzed = concepts.len();
 
         // Count unique files analyzed
@@ -118,16 +407,29 @@ impl PatternLearningEngine {
             concepts.iter().map(|c| &c.file_path).collect();
         session.files_analyzed = unique_files.len();
 
+        // Track which analyzer produced each candidate for explain_pattern
+        let mut origins: HashMap<String, &'static str> = HashMap::new();
+
+        let mut pacer = self.pacer();
+        pacer.pause().await;
+
         // Phase 2: Learn naming patterns
         let naming_patterns = self.learn_naming_patterns(&concepts, &path).await?;
+        Self::note_origins(&mut origins, &naming_patterns, "naming");
         session.patterns_discovered.extend(naming_patterns);
 
+        pacer.pause().await;
+
         // Phase 3: Learn structural patterns
-        let structural_patterns = self.learn_structural_patterns(&concepts, &path).await?;
+        let structural_patterns = self.learn_structural_patterns(&concepts, &path, &scope).await?;
+        Self::note_origins(&mut origins, &structural_patterns, "structural");
         session.patterns_discovered.extend(structural_patterns);
 
+        pacer.pause().await;
+
         // Phase 4: Learn implementation patterns
-        let implementation_patterns = self.learn_implementation_patterns(&concepts, &path).await?;
+        let implementation_patterns = self.learn_implementation_patterns(&concepts, &path, &scope).await?;
+        Self::note_origins(&mut origins, &implementation_patterns, "implementation");
         session.patterns_discovered.extend(implementation_patterns);
 
         // Phase 5: Update approach predictor with new patterns
@@ -136,21 +438,71 @@ impl PatternLearningEngine {
 
         // Phase 6: Consolidate and validate patterns
         let patterns_for_validation = session.patterns_discovered.clone();
-        let validated_patterns = self.validate_and_consolidate_patterns(patterns_for_validation)?;
+        let mut validated_patterns = self.validate_and_consolidate_patterns(patterns_for_validation)?;
+        self.record_consolidations(&session.patterns_discovered);
+
+        pacer.pause().await;
+
+        // Phase 6b: Evaluate user-defined rules (declarative, so they skip the confidence threshold)
+        let rule_patterns = self.evaluate_rules_in_path(&path, &scope);
+        Self::note_origins(&mut origins, &rule_patterns, "rule_engine");
+        validated_patterns.extend(rule_patterns);
+
+        // Phase 6c: Entities named differently across languages, reported like rule findings
+        let drifts = NamingConsistencyChecker::find_drift(&concepts, |file| self.detect_language_from_path(file));
+        let consistency_patterns = NamingConsistencyChecker::to_patterns(&drifts);
+        Self::note_origins(&mut origins, &consistency_patterns, "naming_consistency");
+        validated_patterns.extend(consistency_patterns);
+        sort_patterns(&mut validated_patterns);
 
         // Phase 7: Update learning metrics
         session.analysis_duration_ms = session_start.elapsed().as_millis() as u64;
         self.update_learning_metrics(&validated_patterns, &session);
 
         // Store learned patterns
+        let timestamp = self.provenance_timestamp();
         for pattern in &validated_patterns {
-            self.learned_patterns
-                .insert(pattern.id.clone(), pattern.clone());
+            let analyzer = origins.get(&pattern.id).copied().unwrap_or("unknown");
+            self.store_learned(pattern.clone(), analyzer, timestamp.clone());
         }
 
+        // Phase 8: Stop counting the examples of files removed since earlier runs
+        self.bury_removed_files(&path, &chrono::Utc::now().to_rfc3339());
+
         Ok(validated_patterns)
     }
 
+    /// Suggest concrete renames for identifiers that break the dominant naming
+    /// convention of their language, with edits for every reference
+    ///
+    /// # Safety
+    /// This function is marked unsafe for NAPI compatibility. It performs file system operations
+    /// and pattern analysis that are inherently safe but marked unsafe for JavaScript interop.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async unsafe fn suggest_naming_fixes(&mut self, path: String) -> Result<Vec<NamingFix>, ParseError> {
+        validation::require_directory(&path)?;
+        self.load_project_config(path.clone())?;
+        let concepts = self.extract_semantic_concepts(&path, &PathScope::default()).await?;
+        self.learn_naming_patterns(&concepts, &path).await?;
+
+        let mut language_groups: BTreeMap<String, Vec<SemanticConcept>> = BTreeMap::new();
+        for concept in concepts {
+            let language = self.detect_language_from_path(&concept.file_path);
+            language_groups.entry(language).or_default().push(concept);
+        }
+
+        let mut fixes = Vec::new();
+        for (language, group) in language_groups {
+            let files: std::collections::BTreeSet<&str> = group.iter().map(|c| c.file_path.as_str()).collect();
+            let sources: Vec<(String, String)> = files
+                .into_iter()
+                .filter_map(|file| fs::read_to_string(file).ok().map(|content| (file.to_string(), content)))
+                .collect();
+            fixes.extend(self.naming_analyzer.suggest_fixes(&group, &language, &sources));
+        }
+        Ok(fixes)
+    }
+
     /// Learn from file changes (incremental learning)
     ///
     /// # Safety
@@ -164,30 +516,43 @@ impl PatternLearningEngine {
         file_path: String,
         language: String,
     ) -> Result<Vec<Pattern>, ParseError> {
+        validation::require_non_empty("file_path", &file_path)?;
         let mut new_patterns = Vec::new();
 
         // Learn naming pattern changes
         let naming_changes =
             self.naming_analyzer
                 .learn_from_changes(&old_content, &new_content, &language)?;
+        let mut origins: HashMap<String, &'static str> = HashMap::new();
+        Self::note_origins(&mut origins, &naming_changes, "naming");
         new_patterns.extend(naming_changes);
 
-        // Learn structural changes (simplified - would need AST diff in practice)
-        if self.has_structural_changes(&old_content, &new_content) {
-            let structural_changes = self
-                .learn_structural_changes(&old_content, &new_content, &file_path)
-                .await?;
-            new_patterns.extend(structural_changes);
+        // Learn structural changes from the concept diff between the two versions
+        let diff = self.diff_concepts(&old_content, &new_content, &file_path);
+        match &diff {
+            Some(diff) if diff.has_changes() => {
+                let structural_changes = self.learn_structural_changes(diff, &file_path).await?;
+                Self::note_origins(&mut origins, &structural_changes, "structural");
+                new_patterns.extend(structural_changes);
+            }
+            None if self.has_structural_changes(&old_content, &new_content) => {
+                eprintln!("Concept diff unavailable for {}, skipping structural learning", file_path);
+            }
+            _ => {}
         }
 
         // Update internal state
+        let timestamp = self.provenance_timestamp();
         for pattern in &new_patterns {
-            self.learned_patterns
-                .insert(pattern.id.clone(), pattern.clone());
+            let analyzer = origins.get(&pattern.id).copied().unwrap_or("unknown");
+            self.store_learned(pattern.clone(), analyzer, timestamp.clone());
         }
 
         // Use helper methods for additional learning
-        let change_type = self.detect_change_type(&old_content, &new_content);
+        let change_type = match &diff {
+            Some(diff) => diff.change_kind().to_string(),
+            None => self.detect_change_type(&old_content, &new_content),
+        };
         self.learn_from_change_type(&change_type).await?;
         self.learn_from_file_context(&file_path).await?;
 
@@ -214,18 +579,17 @@ impl PatternLearningEngine {
         &mut self,
         analysis_data: String,
     ) -> Result<bool, ParseError> {
-        let data: Value = from_str(&analysis_data).map_err(|e| {
-            ParseError::from_reason(format!("Failed to parse analysis data: {}", e))
-        })?;
+        let data: AnalysisInput = validation::parse_json(&analysis_data, "analysis data")?;
 
-        // Extract concepts from analysis data
-        let concepts = self.parse_concepts_from_analysis(&data)?;
+        let concepts: Vec<SemanticConcept> =
+            data.concepts.into_iter().map(ConceptInput::into_concept).collect();
 
         // Also try to parse any existing patterns in the data
-        if let Some(patterns_array) = data.get("patterns").and_then(|p| p.as_array()) {
+        if let Some(patterns_array) = data.patterns.as_ref().and_then(|p| p.as_array()) {
             for pattern_json in patterns_array {
                 if let Ok(pattern) = self.parse_pattern_from_json(pattern_json) {
-                    self.learned_patterns.insert(pattern.id.clone(), pattern);
+                    let timestamp = self.provenance_timestamp();
+                    self.store_learned(pattern, "analysis_import", timestamp);
                 }
             }
         }
@@ -246,21 +610,27 @@ impl PatternLearningEngine {
                 .unwrap_or_default();
 
             // Combine all patterns
+            let mut origins: HashMap<String, &'static str> = HashMap::new();
+            Self::note_origins(&mut origins, &naming_patterns, "naming");
+            Self::note_origins(&mut origins, &implementation_patterns, "implementation");
+            Self::note_origins(&mut origins, &structural_patterns, "structural");
             let mut all_patterns = naming_patterns;
             all_patterns.append(&mut implementation_patterns);
             all_patterns.extend(structural_patterns);
 
             // Store patterns that meet confidence threshold
             let mut learned_count = 0;
+            let timestamp = self.provenance_timestamp();
             for pattern in all_patterns {
                 if pattern.confidence >= self.confidence_threshold {
-                    self.learned_patterns.insert(pattern.id.clone(), pattern);
+                    let analyzer = origins.get(&pattern.id).copied().unwrap_or("unknown");
+                    self.store_learned(pattern, analyzer, timestamp.clone());
                     learned_count += 1;
                 }
             }
 
             // Update predictor with historical approach data if available
-            if let Some(approaches) = data.get("approaches") {
+            if let Some(approaches) = &data.approaches {
                 if let Ok(approach_data) = serde_json::to_string(approaches) {
                     let _ = self
                         .approach_predictor
@@ -316,8 +686,29 @@ impl PatternLearningEngine {
             .generate_recommendations(&concepts);
         recommendations.extend(implementation_recommendations);
 
+        // User-defined rule violations, risky concurrency, committed secrets and
+        // cross-language naming drift
+        for pattern in self.patterns_by_id() {
+            if self.is_rule_disabled(pattern) {
+                continue;
+            }
+            if matches!(
+                pattern.pattern_type.as_str(),
+                "custom_rule_violation" | "concurrency_violation" | "security_violation" | "naming_consistency_violation"
+            ) {
+                violations.push(format!("{} ({} occurrences)", pattern.description, pattern.frequency));
+            }
+        }
+
+        // Allocation-heavy Rust code becomes performance advice
+        for pattern in self.patterns_by_id() {
+            if pattern.pattern_type == "performance_violation" && !self.is_rule_disabled(pattern) {
+                recommendations.extend(AllocationAnalyzer::recommendation(pattern));
+            }
+        }
+
         // Detected patterns
-        for pattern in self.learned_patterns.values() {
+        for pattern in self.patterns_by_id() {
             detected.push(format!(
                 "{}: {} (confidence: {:.2})",
                 pattern.pattern_type, pattern.description, pattern.confidence
@@ -328,10 +719,121 @@ impl PatternLearningEngine {
             detected,
             violations,
             recommendations,
-            learned: Some(self.learned_patterns.values().cloned().collect()),
+            learned: Some(self.get_learned_patterns()),
+        })
+    }
+
+    /// Check one changed file against the learned patterns without learning from it
+    ///
+    /// The change's content (or the file on disk when it has none) is parsed
+    /// into concepts; `detected` lists the patterns they follow and
+    /// `violations` where they break the conventions learned so far.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn analyze_file_change(&self, change_data: String) -> Result<PatternAnalysisResult, ParseError> {
+        let change: ChangeInput = validation::parse_json(&change_data, "change event")?;
+        let path = change.path.as_deref().unwrap_or_default();
+        let content = match change.content {
+            Some(content) => content,
+            None => fs::read_to_string(path).unwrap_or_default(),
+        };
+        let language = change.language.unwrap_or_else(|| self.detect_language_from_path(path));
+
+        let concepts = if content.trim().is_empty() {
+            Vec::new()
+        } else {
+            SemanticAnalyzer::new()?.extract_unstored(path, &content)
+        };
+
+        let mut detected: Vec<String> = NamingPatternAnalyzer::new()
+            .analyze_concepts(&concepts, &language)?
+            .into_iter()
+            .chain(ImplementationPatternAnalyzer::new().analyze_concepts(&concepts)?)
+            .map(|pattern| pattern.id)
+            .collect();
+        detected.sort();
+        detected.dedup();
+
+        let mut violations = self.naming_analyzer.detect_violations(&concepts, &language);
+        violations.extend(self.structural_analyzer.detect_structural_violations(&concepts));
+        violations.extend(self.implementation_analyzer.detect_antipatterns(&concepts));
+
+        let mut recommendations = self.naming_analyzer.generate_recommendations(&language);
+        recommendations.extend(self.structural_analyzer.generate_structural_recommendations(&concepts));
+        recommendations.extend(self.implementation_analyzer.generate_recommendations(&concepts));
+
+        Ok(PatternAnalysisResult {
+            detected,
+            violations,
+            recommendations,
+            learned: None,
         })
     }
 
+    /// Learned patterns relevant to a problem, at most five, the most
+    /// confident and frequent first
+    ///
+    /// A pattern is relevant when the keywords of the problem and the selected
+    /// code appear in its description or type, weighed with its confidence and
+    /// frequency; examples in the current file's directory count in its favor.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn find_relevant_patterns(
+        &self,
+        problem_description: String,
+        current_file: Option<String>,
+        selected_code: Option<String>,
+    ) -> Result<Vec<Pattern>, ParseError> {
+        let mut keywords = KeywordExtractor::extract(&problem_description);
+        keywords.extend(selected_code.as_deref().map(KeywordExtractor::extract).unwrap_or_default());
+        keywords.sort();
+        keywords.dedup();
+        let module_dir = current_file.as_deref().and_then(|file| std::path::Path::new(file).parent());
+
+        let mut relevant: Vec<&Pattern> = self
+            .patterns_by_id()
+            .into_iter()
+            .filter(|pattern| Self::pattern_relevance(pattern, &keywords, module_dir) > 0.5)
+            .collect();
+        relevant.sort_by(|a, b| {
+            let score_a = a.confidence * a.frequency as f64;
+            let score_b = b.confidence * b.frequency as f64;
+            score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        Ok(relevant.into_iter().take(MAX_RELEVANT_PATTERNS).cloned().collect())
+    }
+
+    /// How relevant a pattern is to the keywords of a problem, from 0 to 1
+    fn pattern_relevance(pattern: &Pattern, keywords: &[String], module_dir: Option<&std::path::Path>) -> f64 {
+        let mut relevance = 0.0;
+
+        let description = KeywordExtractor::extract(&pattern.description);
+        let pattern_type = KeywordExtractor::extract(&pattern.pattern_type);
+        for keyword in keywords {
+            if description.contains(keyword) {
+                relevance += 0.2;
+            }
+            if pattern_type.contains(keyword) {
+                relevance += 0.3;
+            }
+        }
+
+        let in_module = module_dir.is_some_and(|dir| {
+            pattern
+                .examples
+                .iter()
+                .any(|example| std::path::Path::new(&example.file_path).parent() == Some(dir))
+        });
+        if in_module {
+            relevance += 0.2;
+        }
+
+        // Factor in pattern confidence and frequency
+        relevance += pattern.confidence * 0.3;
+        relevance += (pattern.frequency as f64 / 100.0) * 0.2;
+
+        relevance.min(1.0)
+    }
+
     /// Predict best approach for a problem
     #[cfg_attr(feature = "napi-bindings", napi)]
     pub fn predict_approach(
@@ -343,6 +845,168 @@ impl PatternLearningEngine {
             .predict_approach(problem_description, context)
     }
 
+    /// Predict best approach for a problem in the file or code the user is working on
+    ///
+    /// The current file and selected code are resolved to their concepts and to
+    /// learned patterns with examples in the same module, and candidates that fit
+    /// those patterns are preferred.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn predict_approach_in_context(
+        &mut self,
+        problem_description: String,
+        context: Option<String>,
+        current_file: Option<String>,
+        selected_code: Option<String>,
+    ) -> Result<crate::patterns::types::ApproachPrediction, ParseError> {
+        validation::require_non_empty("problem_description", &problem_description)?;
+        if let Some(file) = &current_file {
+            validation::require_path(file)?;
+        }
+        let surrounding = self.resolve_surrounding_code(current_file, selected_code);
+        self.approach_predictor
+            .predict_approach_in_context(problem_description, context, &surrounding)
+    }
+
+    /// Write learned patterns and this project's historical approaches to
+    /// `.in-memoria/learned-state.json` under the project root
+    ///
+    /// Generic approaches (recorded without a project) are saved too. Returns
+    /// the number of approaches written.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn save_learning_state(&self, project_path: String) -> Result<u32, ParseError> {
+        validation::require_directory(&project_path)?;
+        let mut store = LearningStore::new(&project_path);
+        store.patterns = self.learned_patterns.values().cloned().collect();
+        store.patterns.sort_by(|a, b| a.id.cmp(&b.id));
+        store.activity = self.activity.clone();
+        store.pinned = self.pinned.iter().cloned().collect();
+        store.tombstones = self.tombstones.list();
+        store.walked_files = self.walked_files.iter().cloned().collect();
+        store.approaches = self
+            .approach_predictor
+            .historical_approaches()
+            .iter()
+            .filter(|a| a.project_path.as_ref().is_none_or(|p| *p == store.project_path))
+            .cloned()
+            .collect();
+        store.save()?;
+        Ok(store.approaches.len() as u32)
+    }
+
+    /// Restore the state saved by `save_learning_state` and prefer the project's
+    /// approaches in later predictions
+    ///
+    /// Patterns already learned in this session are kept over saved ones.
+    /// Returns false when the project has no saved state.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn load_learning_state(&mut self, project_path: String) -> Result<bool, ParseError> {
+        validation::require_directory(&project_path)?;
+        self.approach_predictor
+            .set_project_path(Some(store::project_key(&project_path)));
+        self.detect_test_frameworks(&project_path);
+        self.detect_installed_frameworks(&project_path);
+        let Some(store) = LearningStore::load(&project_path)? else {
+            return Ok(false);
+        };
+
+        for tombstone in store.tombstones {
+            if !self.tombstones.is_buried(&tombstone.file_path) {
+                self.tombstones.bury(&tombstone.file_path, tombstone.ids, &tombstone.removed_at);
+            }
+        }
+        self.walked_files.extend(store.walked_files);
+        let timestamp = self.provenance_timestamp();
+        for pattern in store.patterns {
+            if !self.learned_patterns.contains_key(&pattern.id) {
+                self.store_learned(pattern, "store", timestamp.clone());
+            }
+        }
+        // Pinned only now: storing skips patterns that are already pinned
+        self.pinned.extend(store.pinned);
+        self.activity.merge(&store.activity);
+        self.approach_predictor.restore_approaches(store.approaches);
+        Ok(true)
+    }
+
+    /// Serialize learned patterns for another repository's `import_patterns`,
+    /// recording the project they were learned in and when they were exported
+    ///
+    /// Activity counts are never exported; `filter` narrows the patterns by
+    /// namespace, type and minimum confidence.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn export_patterns(&self, filter: Option<PatternExportFilter>) -> Result<String, ParseError> {
+        let filter = filter.unwrap_or_default();
+        filter.validate()?;
+        let patterns: Vec<Pattern> = self
+            .get_learned_patterns()
+            .into_iter()
+            .filter(|pattern| filter.matches(pattern))
+            .collect();
+        let source_repo = self.approach_predictor.project_path().unwrap_or_default().to_string();
+        PatternExport::new(source_repo, self.provenance_timestamp(), patterns).to_json()
+    }
+
+    /// Add patterns exported from another repository, e.g. organizational
+    /// conventions to seed a new project with
+    ///
+    /// `merge_strategy` decides what happens to patterns known on both sides:
+    /// 'prefer-local' (the default) keeps the local pattern, 'prefer-imported'
+    /// replaces it and 'average-confidence' keeps it at the mean confidence.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn import_patterns(
+        &mut self,
+        data: String,
+        merge_strategy: Option<String>,
+    ) -> Result<PatternImportReport, ParseError> {
+        let strategy = match merge_strategy {
+            Some(name) => MergeStrategy::parse(&name)?,
+            None => MergeStrategy::default(),
+        };
+        let export = PatternExport::parse(&data)?;
+        let mut report = PatternImportReport {
+            source_repo: export.source_repo.clone(),
+            exported_at: export.exported_at.clone(),
+            ..Default::default()
+        };
+
+        let timestamp = self.provenance_timestamp();
+        let mut imported = Vec::new();
+        for pattern in export.patterns {
+            let Some(local) = self.learned_patterns.get(&pattern.id) else {
+                report.added += 1;
+                imported.push(pattern.clone());
+                self.store_learned(pattern, "import", timestamp.clone());
+                continue;
+            };
+            if self.pinned.contains(&pattern.id) {
+                report.kept_local += 1;
+                continue;
+            }
+            match strategy.merge(local, pattern) {
+                Some(merged) => {
+                    report.merged += 1;
+                    let reason = format!("merged with pattern imported from {}", export.source_repo);
+                    self.provenance
+                        .record_adjustment(&merged.id, merged.confidence, &reason, timestamp.clone());
+                    imported.push(merged.clone());
+                    self.learned_patterns.insert(merged.id.clone(), merged);
+                }
+                None => report.kept_local += 1,
+            }
+        }
+        self.approach_predictor.update_patterns(imported);
+        Ok(report)
+    }
+
+    /// Recorded historical approaches filtered by domain, complexity, success
+    /// rating and project, best rated first
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn query_approaches(&self, query: ApproachQuery) -> Vec<HistoricalApproach> {
+        let mut query = query;
+        query.project_path = query.project_path.map(|p| store::project_key(&p));
+        self.approach_predictor.query_approaches(&query)
+    }
+
     /// Get learning metrics and statistics
     pub fn get_learning_metrics(&self) -> &LearningMetrics {
         &self.learning_metrics
@@ -353,14 +1017,27 @@ impl PatternLearningEngine {
         self.confidence_threshold = threshold.clamp(0.0, 1.0);
     }
 
+    /// Explain why a pattern was learned: the analyzer that produced it, the files
+    /// its examples come from, patterns merged into it, and its confidence history
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn explain_pattern(&self, pattern_id: String) -> Option<PatternExplanation> {
+        self.learned_patterns
+            .get(&pattern_id)
+            .map(|pattern| self.provenance.explain(pattern))
+    }
+
     /// Get pattern evolution data
     pub fn get_pattern_evolution(&self, pattern_id: &str) -> Option<PatternEvolution> {
-        // This would track pattern changes over time
-        // Simplified implementation for now
+        // Frequency history and trend detection are not tracked yet
         if self.learned_patterns.contains_key(pattern_id) {
             Some(PatternEvolution {
                 pattern_id: pattern_id.to_string(),
-                confidence_history: Vec::new(),
+                confidence_history: self
+                    .provenance
+                    .history(pattern_id)
+                    .into_iter()
+                    .map(|adjustment| (adjustment.timestamp.unwrap_or_default(), adjustment.confidence))
+                    .collect(),
                 frequency_history: Vec::new(),
                 evolution_trend: EvolutionTrend::Stable,
             })
@@ -369,81 +1046,770 @@ impl PatternLearningEngine {
         }
     }
 
-    /// Get all learned patterns (for legacy compatibility)
-    pub fn get_learned_patterns(&self) -> Vec<Pattern> {
-        self.learned_patterns.values().cloned().collect()
+    /// Violations of user-defined rules, concurrency, allocation, secret and
+    /// cross-language naming checks with their rule id, severity and location, ordered by file and line
+    ///
+    /// Findings silenced by `in-memoria-disable-next-line` comments are dropped
+    /// while learning; rules listed under `rules.disabled` in the project
+    /// configuration are dropped here.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_violations(&self) -> Vec<Violation> {
+        let mut violations: Vec<Violation> = self
+            .learned_patterns
+            .values()
+            .filter(|pattern| !self.is_rule_disabled(pattern))
+            .flat_map(Violation::from_pattern)
+            .collect();
+        violations.sort_by(|a, b| {
+            (&a.file_path, a.line_range.start, &a.rule_id).cmp(&(&b.file_path, b.line_range.start, &b.rule_id))
+        });
+        violations
     }
 
-    /// Insert a pattern (for external use and testing)
-    pub fn insert_pattern(&mut self, id: String, pattern: Pattern) {
-        self.learned_patterns.insert(id, pattern);
+    /// Set the cyclomatic complexity, cognitive complexity and function and
+    /// file length budgets `check_complexity_budgets` enforces, with
+    /// per-directory overrides
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn set_complexity_thresholds(&mut self, config: ComplexityThresholds) -> Result<(), ParseError> {
+        config.validate()?;
+        self.complexity_thresholds = Some(config);
+        Ok(())
     }
 
-    /// Get a specific pattern by ID
-    pub fn get_pattern(&self, id: &str) -> Option<&Pattern> {
-        self.learned_patterns.get(id)
-    }
+    /// Functions and files under `path` over their complexity budget, ordered
+    /// by file and line
+    ///
+    /// Rule ids are `max_cyclomatic_complexity`, `max_cognitive_complexity`,
+    /// `max_function_lines` and `max_file_lines`; they can be silenced with
+    /// `in-memoria-disable-next-line` comments or listed under `rules.disabled`.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn check_complexity_budgets(&mut self, path: String) -> Result<Vec<Violation>, ParseError> {
+        self.overlay.require_root(&path)?;
+        let Some(thresholds) = self.complexity_thresholds.clone() else {
+            return Err(validation::ValidationError::new(
+                validation::ErrorCode::InvalidArgument,
+                "No complexity thresholds set; call set_complexity_thresholds first",
+            )
+            .into());
+        };
+        self.load_project_config(path.clone())?;
 
-    /// Check if a pattern exists
-    pub fn has_pattern(&self, id: &str) -> bool {
-        self.learned_patterns.contains_key(id)
+        let root = std::path::Path::new(&path);
+        let mut parsers = ParserManager::new()?;
+        let mut violations = Vec::new();
+        for file_path in self.source_files(&path, &PathScope::default()) {
+            let Ok(content) = self.overlay.read_to_string(&file_path, root) else {
+                continue;
+            };
+            let file_path = file_path.to_string_lossy();
+            let language = self.detect_language_from_path(&file_path);
+            let functions = parsers
+                .parse(&content, &language)
+                .map(|tree| ComplexityBudgetChecker::measure_functions(&tree, &content))
+                .unwrap_or_default();
+            let relative = paths::relative_to(&file_path, root);
+            violations.extend(ComplexityBudgetChecker::check_file(
+                &thresholds,
+                &file_path,
+                &relative,
+                &content,
+                &functions,
+            ));
+        }
+
+        if let Some(config) = &self.project_config {
+            violations.retain(|violation| !config.disabled_rules.contains(&violation.rule_id));
+        }
+        violations.sort_by(|a, b| {
+            (&a.file_path, a.line_range.start, &a.rule_id).cmp(&(&b.file_path, b.line_range.start, &b.rule_id))
+        });
+        Ok(violations)
     }
 
-    /// Updates patterns based on file changes (from original implementation)
+    /// Median, 90th percentile and maximum cyclomatic complexity, cognitive
+    /// complexity and function length of the functions under `path`, overall
+    /// and per language and directory, with the most complex functions
     ///
-    /// # Safety
-    /// This function uses unsafe because it needs to interact with the Node.js runtime
-    /// through N-API bindings. The caller must ensure the change data is valid JSON.
+    /// Functions are measured as for `check_complexity_budgets`, which needs
+    /// no thresholds set here.
     #[cfg_attr(feature = "napi-bindings", napi)]
-    pub async unsafe fn update_from_change(
-        &mut self,
-        change_data: String,
-    ) -> Result<bool, ParseError> {
-        self.update_from_change_internal(change_data).await
+    pub fn analyze_complexity_report(&mut self, path: String) -> Result<ComplexityReport, ParseError> {
+        self.overlay.require_root(&path)?;
+        self.load_project_config(path.clone())?;
+
+        let root = std::path::Path::new(&path);
+        let mut parsers = ParserManager::new()?;
+        let mut report = ComplexityReportBuilder::new();
+        for file_path in self.source_files(&path, &PathScope::default()) {
+            let Ok(content) = self.overlay.read_to_string(&file_path, root) else {
+                continue;
+            };
+            let file_path = file_path.to_string_lossy();
+            let language = self.detect_language_from_path(&file_path);
+            let functions = parsers
+                .parse(&content, &language)
+                .map(|tree| ComplexityBudgetChecker::measure_functions(&tree, &content))
+                .unwrap_or_default();
+            report.add_file(&paths::relative_to(&file_path, root), &language, &functions);
+        }
+        Ok(report.build())
     }
 
-    /// Internal implementation for updating patterns from file changes (from original implementation)
-    pub async fn update_from_change_internal(
+    /// Architectural drift of `head_ref` relative to `base_ref` in the git
+    /// repository containing `path`: concepts added and removed, complexity
+    /// changes and the violations the head introduces or resolves
+    ///
+    /// Both refs are read from the object database, so the working tree is
+    /// neither checked out nor read. Only files under `path` that differ between
+    /// the refs are analyzed; their paths are relative to `path`. Violations come
+    /// from user rules, the concurrency, allocation and secret passes, and
+    /// complexity budgets once thresholds are set, less rules the project
+    /// configuration disables.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn compare_refs(
         &mut self,
-        change_data: String,
-    ) -> Result<bool, ParseError> {
-        // Parse the change data JSON
-        let change: Value = match from_str(&change_data) {
-            Ok(data) => data,
-            Err(e) => {
-                eprintln!("Failed to parse change data: {}", e);
-                return Ok(false);
-            }
+        path: String,
+        base_ref: String,
+        head_ref: String,
+    ) -> Result<RefComparison, ParseError> {
+        validation::require_directory(&path)?;
+        self.load_project_config(path.clone())?;
+        let repository = GitRepository::open(std::path::Path::new(&path))?;
+        let base = repository.revision(&base_ref)?;
+        let head = repository.revision(&head_ref)?;
+
+        let mut comparison = RefComparison {
+            base_ref,
+            head_ref,
+            base_commit: base.commit.clone(),
+            head_commit: head.commit.clone(),
+            ..Default::default()
         };
+        let changed = base.changed_files(&head);
+        for file_path in &changed {
+            let list = match (base.files.contains_key(*file_path), head.files.contains_key(*file_path)) {
+                (false, _) => &mut comparison.files_added,
+                (_, false) => &mut comparison.files_removed,
+                _ => &mut comparison.files_modified,
+            };
+            list.push(file_path.to_string());
+        }
 
-        let mut patterns_updated = false;
+        let analyzed: Vec<&str> = changed
+            .into_iter()
+            .filter(|file_path| {
+                std::path::Path::new(file_path)
+                    .extension()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(|extension| self.is_supported_extension(extension))
+            })
+            .collect();
+        let ids: Vec<&str> = analyzed
+            .iter()
+            .flat_map(|file_path| [base.files.get(*file_path), head.files.get(*file_path)])
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        let blobs = repository.read_blobs(&ids)?;
+        let content_of = |revision: &GitRevision, file_path: &str| {
+            revision.files.get(file_path).and_then(|id| blobs.get(id)).cloned().unwrap_or_default()
+        };
 
-        // Extract change information
-        let change_type = change
-            .get("type")
-            .and_then(|t| t.as_str())
-            .unwrap_or("unknown");
-        let file_path = change.get("path").and_then(|p| p.as_str());
-        let content = change.get("content").and_then(|c| c.as_str());
-        let language = change.get("language").and_then(|l| l.as_str());
+        let mut parsers = ParserManager::new()?;
+        let mut base_violations = Vec::new();
+        let mut head_violations = Vec::new();
+        for file_path in analyzed {
+            let (old_content, new_content) = (content_of(&base, file_path), content_of(&head, file_path));
+            if let Some(diff) = self.diff_concepts(&old_content, &new_content, file_path) {
+                if diff.has_changes() {
+                    comparison.concepts_added += diff.added.len() as u32;
+                    comparison.concepts_removed += diff.removed.len() as u32;
+                    comparison.concept_diffs.push(diff);
+                }
+            }
+
+            let language = self.detect_language_from_path(file_path);
+            let mut measure = |content: &str| {
+                parsers
+                    .parse(content, &language)
+                    .map(|tree| ComplexityBudgetChecker::measure_functions(&tree, content))
+                    .unwrap_or_default()
+            };
+            let (old_functions, new_functions) = (measure(&old_content), measure(&new_content));
+            let total = |functions: &[FunctionMetrics]| {
+                functions.iter().fold((0i64, 0i64), |(cyclomatic, cognitive), f| {
+                    (cyclomatic + f.cyclomatic as i64, cognitive + f.cognitive as i64)
+                })
+            };
+            let ((old_cyclomatic, old_cognitive), (new_cyclomatic, new_cognitive)) =
+                (total(&old_functions), total(&new_functions));
+            comparison.cyclomatic_delta += new_cyclomatic - old_cyclomatic;
+            comparison.cognitive_delta += new_cognitive - old_cognitive;
+            comparison
+                .complexity_changes
+                .extend(drift::complexity_changes(file_path, &old_functions, &new_functions));
+
+            base_violations.extend(self.content_violations(file_path, &old_content, &old_functions));
+            head_violations.extend(self.content_violations(file_path, &new_content, &new_functions));
+        }
+
+        comparison.complexity_changes.sort_by_key(|change| {
+            (std::cmp::Reverse(change.cyclomatic_delta()), change.file_path.clone(), change.function.clone())
+        });
+        comparison.introduced_violations = drift::new_violations(&base_violations, &head_violations);
+        comparison.resolved_violations = drift::new_violations(&head_violations, &base_violations);
+        for violations in [&mut comparison.introduced_violations, &mut comparison.resolved_violations] {
+            violations.sort_by(|a, b| {
+                (&a.file_path, a.line_range.start, &a.rule_id).cmp(&(&b.file_path, b.line_range.start, &b.rule_id))
+            });
+        }
+        Ok(comparison)
+    }
+
+    /// Inline review comments on a unified diff such as `git diff` output
+    ///
+    /// Comments come from user rules, the concurrency, allocation and secret
+    /// passes, complexity budgets once thresholds are set, and the naming
+    /// conventions learned so far. Only findings on added lines are reported,
+    /// each with the concept around it. With `path`, changed files are read from
+    /// that project root (or the file overlay) as the diff leaves them and its
+    /// configuration applies; without it, files are rebuilt from the hunks alone.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn review_diff(&mut self, diff_text: String, path: Option<String>) -> Result<DiffReview, ParseError> {
+        let files = UnifiedDiff::parse(&diff_text)?;
+        if let Some(path) = &path {
+            self.overlay.require_root(path)?;
+            self.load_project_config(path.clone())?;
+        }
+
+        let mut parsers = ParserManager::new()?;
+        let mut review = DiffReview::default();
+        for file in files {
+            let supported = std::path::Path::new(&file.path)
+                .extension()
+                .and_then(|s| s.to_str())
+                .is_some_and(|extension| self.is_supported_extension(extension));
+            if !supported {
+                continue;
+            }
+            let content = path
+                .as_ref()
+                .and_then(|root| {
+                    let root = std::path::Path::new(root);
+                    self.overlay.read_to_string(&root.join(&file.path), root).ok()
+                })
+                .unwrap_or_else(|| file.sparse_content());
+
+            let concepts = self.file_concepts(&file.path, &content);
+            review.hunks.extend(file.hunks.iter().map(|hunk| ReviewedHunk {
+                file_path: file.path.clone(),
+                start_line: hunk.new_start,
+                end_line: hunk.new_start + hunk.new_count.saturating_sub(1),
+                added_lines: hunk.added,
+                removed_lines: hunk.removed,
+                concepts: hunk_concepts(&concepts, hunk),
+            }));
+
+            let language = self.detect_language_from_path(&file.path);
+            let functions = parsers
+                .parse(&content, &language)
+                .map(|tree| ComplexityBudgetChecker::measure_functions(&tree, &content))
+                .unwrap_or_default();
+            for (violation, _) in self.content_violations(&file.path, &content, &functions) {
+                // Function and file budgets point at the first line the diff adds to them
+                let Some(line) = file.first_added_in(&violation.line_range) else {
+                    continue;
+                };
+                review.comments.push(ReviewComment {
+                    file_path: file.path.clone(),
+                    line,
+                    suggestion: suggestion_for(&violation.rule_id),
+                    concept: enclosing_concept(&concepts, line),
+                    rule_id: violation.rule_id,
+                    severity: violation.severity,
+                    message: violation.message,
+                });
+            }
+
+            let sources = [(file.path.clone(), content.clone())];
+            for fix in self.naming_analyzer.suggest_fixes(&concepts, &language, &sources) {
+                let line = fix.line_range.start;
+                if !file.added_lines.contains(&line) {
+                    continue;
+                }
+                review.comments.push(ReviewComment {
+                    file_path: file.path.clone(),
+                    line,
+                    rule_id: NAMING_CONVENTION_RULE.to_string(),
+                    severity: "info".to_string(),
+                    message: format!(
+                        "{} '{}' does not follow the project's {} convention",
+                        fix.concept_type, fix.old_name, fix.convention
+                    ),
+                    suggestion: Some(format!("Rename '{}' to '{}'", fix.old_name, fix.new_name)),
+                    concept: enclosing_concept(&concepts, line),
+                });
+            }
+            review.files_reviewed.push(file.path);
+        }
+
+        if let Some(config) = &self.project_config {
+            review.comments.retain(|comment| !config.disabled_rules.contains(&comment.rule_id));
+        }
+        review
+            .comments
+            .sort_by(|a, b| (&a.file_path, a.line, &a.rule_id).cmp(&(&b.file_path, b.line, &b.rule_id)));
+        Ok(review)
+    }
+
+    /// Violations in one version of a file, each with the text of its line
+    fn content_violations(
+        &mut self,
+        file_path: &str,
+        content: &str,
+        functions: &[FunctionMetrics],
+    ) -> Vec<LocatedViolation> {
+        if content.is_empty() {
+            return Vec::new();
+        }
+        let extension = std::path::Path::new(file_path)
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        // One finding at a time, so no finding is cut as a surplus example
+        let mut patterns = Vec::new();
+        if ConcurrencyAnalyzer::supports_extension(&extension) {
+            let report = ConcurrencyAnalyzer::analyze_file(file_path, content);
+            for risk in &report.risks {
+                patterns.extend(ConcurrencyAnalyzer::to_patterns(&[], std::slice::from_ref(risk)));
+            }
+        }
+        if AllocationAnalyzer::supports_extension(&extension) {
+            for finding in AllocationAnalyzer::analyze_file(file_path, content) {
+                patterns.extend(AllocationAnalyzer::to_patterns(&[finding]));
+            }
+        }
+        if SecretScanner::supports_extension(&extension) {
+            for finding in SecretScanner::analyze_file(file_path, content, &[]) {
+                patterns.extend(SecretScanner::to_patterns(&[finding]));
+            }
+        }
+        let language = self.detect_language_from_path(file_path);
+        for rule_match in self.rule_engine.evaluate_file(file_path, content, &language) {
+            patterns.extend(self.rule_engine.to_patterns(&[rule_match]));
+        }
+
+        let mut violations: Vec<Violation> = patterns
+            .iter()
+            .filter(|pattern| !self.is_rule_disabled(pattern))
+            .flat_map(Violation::from_pattern)
+            .collect();
+        if let Some(thresholds) = &self.complexity_thresholds {
+            let budget_violations =
+                ComplexityBudgetChecker::check_file(thresholds, file_path, file_path, content, functions);
+            violations.extend(budget_violations.into_iter().filter(|violation| {
+                !self
+                    .project_config
+                    .as_ref()
+                    .is_some_and(|config| config.disabled_rules.contains(&violation.rule_id))
+            }));
+        }
+
+        let lines: Vec<&str> = content.lines().collect();
+        violations
+            .into_iter()
+            .map(|violation| {
+                let line = (violation.line_range.start as usize)
+                    .checked_sub(1)
+                    .and_then(|idx| lines.get(idx))
+                    .map(|line| line.trim().to_string())
+                    .unwrap_or_default();
+                (violation, line)
+            })
+            .collect()
+    }
+
+    /// Directory layouts shared by sibling directories anywhere under `path`,
+    /// and the siblings that depart from them
+    ///
+    /// Learning a codebase records the same templates and deviations as
+    /// structural patterns.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn analyze_directory_layout(&self, path: String) -> Result<DirectoryLayoutReport, ParseError> {
+        validation::require_directory(&path)?;
+        DirectoryLayoutLearner::learn(&path, FileWalker::is_ignored_directory)
+    }
+
+    /// Entities named differently across languages among the given concepts,
+    /// e.g. the semantic analyzer's stored concepts of a polyglot project
+    ///
+    /// Each spelling of a drifting entity is one violation pointing at its first
+    /// occurrence; learning a codebase records the same findings.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn check_naming_consistency(&self, concepts: Vec<SemanticConcept>) -> Vec<Violation> {
+        let drifts = NamingConsistencyChecker::find_drift(&concepts, |file| self.detect_language_from_path(file));
+        NamingConsistencyChecker::to_patterns(&drifts)
+            .iter()
+            .filter(|pattern| !self.is_rule_disabled(pattern))
+            .flat_map(Violation::from_pattern)
+            .collect()
+    }
+
+    /// Whether the project configuration disables the rule behind a violation pattern
+    fn is_rule_disabled(&self, pattern: &Pattern) -> bool {
+        let Some(config) = &self.project_config else {
+            return false;
+        };
+        let rule_id = Violation::rule_id(pattern);
+        config
+            .disabled_rules
+            .iter()
+            .any(|disabled| *disabled == rule_id || *disabled == pattern.id)
+    }
+
+    /// Get all learned patterns (for legacy compatibility)
+    ///
+    /// Activity counts are not patterns and are never included; see
+    /// `get_activity_counters`.
+    pub fn get_learned_patterns(&self) -> Vec<Pattern> {
+        let mut patterns: Vec<Pattern> = self
+            .learned_patterns
+            .values()
+            .filter(|pattern| PatternNamespace::of(&pattern.pattern_type) != PatternNamespace::Activity)
+            .cloned()
+            .collect();
+        sort_patterns(&mut patterns);
+        patterns
+    }
+
+    /// Learned patterns in one namespace: 'naming', 'structural',
+    /// 'implementation' or 'violation'
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_patterns_in_namespace(&self, namespace: String) -> Result<Vec<Pattern>, ParseError> {
+        let namespace = sharing::parse_namespace(&namespace)?;
+        let mut patterns: Vec<Pattern> = self
+            .get_learned_patterns()
+            .into_iter()
+            .filter(|pattern| PatternNamespace::of(&pattern.pattern_type) == namespace)
+            .collect();
+        patterns.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(patterns)
+    }
+
+    /// Learned patterns matching `filter`, with whether each is pinned, in id order
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn list_patterns(&self, filter: Option<PatternFilter>) -> Result<Vec<PatternRecord>, ParseError> {
+        let filter = filter.unwrap_or_default();
+        filter.validate()?;
+        Ok(self
+            .patterns_by_id()
+            .into_iter()
+            .filter(|pattern| filter.matches(pattern, self.pinned.contains(&pattern.id)))
+            .map(|pattern| PatternRecord {
+                pattern: pattern.clone(),
+                pinned: self.pinned.contains(&pattern.id),
+            })
+            .collect())
+    }
+
+    /// Replace a pattern's description, confidence or contexts, e.g. to
+    /// correct a misleading description; returns the updated pattern
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn update_pattern(&mut self, id: String, changes: PatternChanges) -> Result<Pattern, ParseError> {
+        changes.validate()?;
+        let timestamp = self.provenance_timestamp();
+        let pattern = self.learned_patterns.get_mut(&id).ok_or_else(|| unknown_pattern(&id))?;
+        changes.apply(pattern);
+        self.provenance
+            .record_adjustment(&pattern.id, pattern.confidence, "edited by hand", timestamp);
+        Ok(pattern.clone())
+    }
+
+    /// Remove a pattern, pinned or not; learning the codebase again may find
+    /// it again. Returns false when no pattern has the id.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn delete_pattern(&mut self, id: String) -> bool {
+        self.pinned.remove(&id);
+        self.provenance.forget(&id);
+        self.learned_patterns.remove(&id).is_some()
+    }
+
+    /// Keep a pattern exactly as it is: later learning, decay, confidence
+    /// boosts and consolidation leave it unchanged until `unpin_pattern`
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn pin_pattern(&mut self, id: String) -> Result<(), ParseError> {
+        if !self.learned_patterns.contains_key(&id) {
+            return Err(unknown_pattern(&id));
+        }
+        self.pinned.insert(id);
+        Ok(())
+    }
+
+    /// Let learning adjust a pinned pattern again; returns false when it was not pinned
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn unpin_pattern(&mut self, id: String) -> bool {
+        self.pinned.remove(&id)
+    }
+
+    /// Drop learned patterns until `max` remain, those `policy` ranks lowest
+    /// first; pinned patterns stay and count toward `max`. Returns how many went.
+    pub fn evict_patterns(&mut self, max: usize, policy: EvictionPolicy) -> usize {
+        if self.learned_patterns.len() <= max {
+            return 0;
+        }
+        let pinned = self.learned_patterns.keys().filter(|id| self.pinned.contains(*id)).count();
+        let evicted = policy.patterns_to_evict(
+            self.learned_patterns.values().filter(|pattern| !self.pinned.contains(&pattern.id)),
+            max.saturating_sub(pinned),
+        );
+        for id in &evicted {
+            self.learned_patterns.remove(id);
+            self.provenance.forget(id);
+        }
+        evicted.len()
+    }
+
+    /// Change and file activity counted by incremental learning (change types,
+    /// hours, file types, directories), highest count first; `family` limits
+    /// them to one kind such as 'change' or 'change_time_hour'
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_activity_counters(&self, family: Option<String>) -> Vec<ActivityCounter> {
+        self.activity.counters(family.as_deref())
+    }
+
+    /// Every stored pattern, activity counts included, in id order
+    fn patterns_by_id(&self) -> Vec<&Pattern> {
+        let mut patterns: Vec<&Pattern> = self.learned_patterns.values().collect();
+        patterns.sort_by(|a, b| a.id.cmp(&b.id));
+        patterns
+    }
+
+    /// Add a pattern to the learned set, recording which analyzer or store it
+    /// came from; a pinned pattern keeps its curated form
+    fn store_learned(&mut self, pattern: Pattern, source: &str, timestamp: Option<String>) {
+        if self.pinned.contains(&pattern.id) {
+            return;
+        }
+        self.provenance.record_learned(&pattern, source, timestamp);
+        self.telemetry.on_pattern_learned(&pattern, source);
+        self.learned_patterns.insert(pattern.id.clone(), pattern);
+    }
+
+    /// Subtract the examples of files that no longer exist from the patterns
+    /// holding them, leaving a tombstone per file; a pattern left without
+    /// examples stops counting altogether
+    ///
+    /// Only files an earlier run found on disk count as removed, so examples
+    /// standing for no single file ("multiple_files") are never buried.
+    /// Tombstones of files that exist again are forgotten, and those older
+    /// than the grace period are forgotten with the patterns they left
+    /// without occurrences. Pinned patterns are left alone.
+    fn bury_removed_files(&mut self, root: &str, now: &str) {
+        let root = std::path::Path::new(root);
+        let files: BTreeSet<&str> = self
+            .learned_patterns
+            .values()
+            .filter(|pattern| !self.pinned.contains(&pattern.id))
+            .flat_map(|pattern| pattern.examples.iter().map(|example| example.file_path.as_str()))
+            .filter(|file_path| !file_path.is_empty())
+            .collect();
+        let (present, removed): (BTreeSet<&str>, BTreeSet<&str>) =
+            files.into_iter().partition(|file_path| self.file_exists(root, file_path));
+        let removed: BTreeSet<String> = removed
+            .into_iter()
+            .filter(|file_path| self.walked_files.contains(*file_path))
+            .map(str::to_string)
+            .collect();
+        let present: Vec<String> = present.into_iter().map(str::to_string).collect();
+        self.walked_files.extend(present);
+
+        let mut buried: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for pattern in self.learned_patterns.values_mut() {
+            if self.pinned.contains(&pattern.id) {
+                continue;
+            }
+            let dropped: BTreeSet<&String> = pattern
+                .examples
+                .iter()
+                .map(|example| &example.file_path)
+                .filter(|file_path| removed.contains(*file_path))
+                .collect();
+            if dropped.is_empty() {
+                continue;
+            }
+            for file_path in dropped {
+                buried.entry(file_path.clone()).or_default().push(pattern.id.clone());
+            }
+            let before = pattern.examples.len();
+            pattern.examples.retain(|example| !removed.contains(&example.file_path));
+            pattern.frequency = if pattern.examples.is_empty() {
+                0
+            } else {
+                pattern.frequency.saturating_sub((before - pattern.examples.len()) as u32)
+            };
+        }
+        for (file_path, ids) in buried {
+            self.tombstones.bury(&file_path, ids, now);
+        }
+
+        for tombstone in self.tombstones.list() {
+            if self.file_exists(root, &tombstone.file_path) {
+                self.tombstones.revive(&tombstone.file_path);
+            }
+        }
+        let grace_secs = self
+            .project_config
+            .as_ref()
+            .map_or(AnalysisConfig::default().tombstone_grace_secs, |config| config.tombstone_grace_secs);
+        for tombstone in self.tombstones.collect_garbage(now, grace_secs) {
+            for id in tombstone.ids {
+                let unused = !self.pinned.contains(&id)
+                    && self.learned_patterns.get(&id).is_some_and(|pattern| pattern.frequency == 0);
+                if unused {
+                    self.learned_patterns.remove(&id);
+                    self.provenance.forget(&id);
+                }
+            }
+            self.walked_files.remove(&tombstone.file_path);
+        }
+    }
+
+    /// Whether a file of the project at `root` exists, on disk or in the overlay
+    fn file_exists(&self, root: &std::path::Path, file_path: &str) -> bool {
+        let path = root.join(file_path);
+        path.is_file() || self.overlay.get(&path, root).is_some()
+    }
+
+    /// Files whose examples learning subtracted from patterns because the
+    /// file was removed, in path order, until the grace period passes
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_tombstones(&self) -> Vec<Tombstone> {
+        self.tombstones.list()
+    }
+
+    /// Send learned-pattern and timeout events to a host-side telemetry implementation
+    pub fn set_telemetry(&mut self, telemetry: SharedTelemetry) {
+        self.telemetry = telemetry;
+    }
+
+    /// Insert a pattern (for external use and testing)
+    pub fn insert_pattern(&mut self, id: String, pattern: Pattern) {
+        let timestamp = self.provenance_timestamp();
+        self.provenance.record_learned(&pattern, "external", timestamp);
+        self.telemetry.on_pattern_learned(&pattern, "external");
+        self.learned_patterns.insert(id, pattern);
+    }
+
+    /// Get a specific pattern by ID
+    pub fn get_pattern(&self, id: &str) -> Option<&Pattern> {
+        self.learned_patterns.get(id)
+    }
+
+    /// Check if a pattern exists
+    pub fn has_pattern(&self, id: &str) -> bool {
+        self.learned_patterns.contains_key(id)
+    }
+
+    /// Updates patterns based on file changes (from original implementation)
+    ///
+    /// # Safety
+    /// This function uses unsafe because it needs to interact with the Node.js runtime
+    /// through N-API bindings. The caller must ensure the change data is valid JSON.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async unsafe fn update_from_change(
+        &mut self,
+        change_data: String,
+    ) -> Result<bool, ParseError> {
+        self.update_from_change_internal(change_data).await
+    }
+
+    /// Apply every deletion still waiting for a matching create as a real deletion
+    ///
+    /// Deletions are normally held for a few seconds in case the file reappears
+    /// elsewhere; hosts can call this when a batch of watcher events is complete.
+    ///
+    /// # Safety
+    /// This function is marked unsafe for NAPI compatibility. It performs pattern updates
+    /// that are inherently safe but marked unsafe for JavaScript interop.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async unsafe fn flush_pending_deletions(&mut self) -> Result<u32, ParseError> {
+        let pending = self.renames.drain_pending();
+        let count = pending.len() as u32;
+        self.apply_deletions(pending).await?;
+        Ok(count)
+    }
+
+    /// Internal implementation for updating patterns from file changes (from original implementation)
+    pub async fn update_from_change_internal(
+        &mut self,
+        change_data: String,
+    ) -> Result<bool, ParseError> {
+        let change: ChangeInput = validation::parse_json(&change_data, "change event")?;
+
+        // Deletions whose rename window passed without a matching create are real
+        let expired = self.renames.expire(std::time::Instant::now());
+        let mut patterns_updated = self.apply_deletions(expired).await?;
 
-        // Update patterns based on change type
+        // Extract change information
+        let change_type = change.change_type.as_str();
+        let file_path = change.path.as_deref();
+        let content = change.content.as_deref();
+        let language = change.language.as_deref();
+
+        // Update patterns based on change type. A delete/create pair for the same
+        // content is a move, so it is learned as a rename rather than two events.
+        let mut change_type = change_type;
         match change_type {
             "add" | "create" => {
-                patterns_updated |= self
-                    .handle_file_addition(file_path, content, language)
-                    .await?;
+                let moved_from = file_path.and_then(|path| {
+                    let fingerprint = Self::fingerprint_file(path, content)?;
+                    self.renames
+                        .on_added(path, fingerprint, std::time::Instant::now())
+                });
+                match (moved_from, file_path) {
+                    (Some(old_path), Some(new_path)) => {
+                        patterns_updated |= self.apply_file_rename(&old_path, new_path).await?;
+                        change_type = "rename";
+                    }
+                    _ => {
+                        patterns_updated |= self
+                            .handle_file_addition(file_path, content, language)
+                            .await?;
+                    }
+                }
             }
             "modify" | "change" => {
+                if let (Some(path), Some(content_str)) = (file_path, content) {
+                    self.renames
+                        .record(path, FileFingerprint::from_content(content_str));
+                }
                 patterns_updated |= self
                     .handle_file_modification(file_path, content, language)
                     .await?;
             }
-            "delete" | "remove" => {
-                patterns_updated |= self.handle_file_deletion(file_path).await?;
+            "delete" | "remove" | "unlink" => {
+                let outcome = file_path
+                    .map(|path| self.renames.on_deleted(path, std::time::Instant::now()))
+                    .unwrap_or(DeletionOutcome::Unknown);
+                match (outcome, file_path) {
+                    (DeletionOutcome::Renamed(new_path), Some(old_path)) => {
+                        patterns_updated |= self.apply_file_rename(old_path, &new_path).await?;
+                        change_type = "rename";
+                    }
+                    // Wait for a matching create before decaying anything
+                    (DeletionOutcome::Pending, _) => return Ok(patterns_updated),
+                    _ => {
+                        patterns_updated |= self.handle_file_deletion(file_path).await?;
+                    }
+                }
             }
             "rename" | "move" => {
-                patterns_updated |= self.handle_file_rename(file_path, &change).await?;
+                patterns_updated |= self
+                    .handle_file_rename(file_path, change.old_path.as_deref())
+                    .await?;
             }
             _ => {
                 // Handle unknown change types by treating as modification
@@ -472,15 +1838,39 @@ impl PatternLearningEngine {
         pattern_type: &str,
         increment: u32,
     ) -> Result<bool, ParseError> {
-        if let Some(pattern) = self.learned_patterns.get_mut(pattern_type) {
+        // Activity is counted, not learned as a pattern
+        if self.activity.record(pattern_type, increment) {
+            return Ok(true);
+        }
+
+        let timestamp = self.provenance_timestamp();
+        let existing = if self.learned_patterns.contains_key(pattern_type) {
+            Some(pattern_type.to_string())
+        } else {
+            // Patterns created here are keyed by a generated id; find them by type
+            self.learned_patterns
+                .values()
+                .find(|p| p.pattern_type == pattern_type && p.id.starts_with("learned_"))
+                .map(|p| p.id.clone())
+        };
+        if let Some(pattern) = existing.and_then(|id| self.learned_patterns.get_mut(&id)) {
             pattern.frequency += increment;
+            if self.pinned.contains(&pattern.id) {
+                return Ok(true);
+            }
             // Adjust confidence based on increased usage
             pattern.confidence = (pattern.confidence + 0.05).min(0.95);
+            self.provenance.record_adjustment(
+                &pattern.id,
+                pattern.confidence,
+                "usage frequency increased",
+                timestamp,
+            );
             Ok(true)
         } else {
             // Create a new pattern if it doesn't exist
             let new_pattern = Pattern {
-                id: format!("learned_{}_{}", pattern_type, self.generate_pattern_id()),
+                id: format!("learned_{}_{}", pattern_type, self.generate_pattern_id(pattern_type)),
                 pattern_type: pattern_type.to_string(),
                 description: format!("Pattern learned from analysis: {}", pattern_type),
                 frequency: increment,
@@ -488,8 +1878,7 @@ impl PatternLearningEngine {
                 examples: vec![],
                 contexts: vec!["learned".to_string()],
             };
-            self.learned_patterns
-                .insert(new_pattern.id.clone(), new_pattern);
+            self.store_learned(new_pattern, "change_tracking", timestamp);
             Ok(true)
         }
     }
@@ -501,8 +1890,13 @@ impl PatternLearningEngine {
         boost: f64,
     ) -> Result<bool, ParseError> {
         let mut updated = false;
+        let timestamp = self.provenance_timestamp();
+        let reason = format!("boosted by related change to '{}'", concept);
 
         for pattern in self.learned_patterns.values_mut() {
+            if self.pinned.contains(&pattern.id) {
+                continue;
+            }
             // Check if pattern is related to the concept
             if pattern
                 .description
@@ -518,6 +1912,12 @@ impl PatternLearningEngine {
                     .any(|c| c.to_lowercase().contains(&concept.to_lowercase()))
             {
                 pattern.confidence = (pattern.confidence + boost).min(0.95);
+                self.provenance.record_adjustment(
+                    &pattern.id,
+                    pattern.confidence,
+                    &reason,
+                    timestamp.clone(),
+                );
                 updated = true;
             }
         }
@@ -558,12 +1958,6 @@ impl PatternLearningEngine {
     /// Helper method to parse pattern from JSON (from original implementation)
     fn parse_pattern_from_json(&self, json: &Value) -> Result<Pattern, serde_json::Error> {
         // Extract pattern fields from JSON
-        let id = json
-            .get("id")
-            .and_then(|v| v.as_str())
-            .unwrap_or(&format!("parsed_{}", self.generate_pattern_id()))
-            .to_string();
-
         let pattern_type = json
             .get("type")
             .or_else(|| json.get("patternType"))
@@ -571,6 +1965,12 @@ impl PatternLearningEngine {
             .unwrap_or("unknown")
             .to_string();
 
+        let id = json
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("parsed_{}", self.generate_pattern_id(&pattern_type)));
+
         let description = json
             .get("description")
             .and_then(|v| v.as_str())
@@ -647,7 +2047,13 @@ impl PatternLearningEngine {
     }
 
     /// Generate unique pattern ID (from original implementation)
-    fn generate_pattern_id(&self) -> String {
+    ///
+    /// In deterministic mode the ID is a stable hash of `key` instead of the current time.
+    fn generate_pattern_id(&self, key: &str) -> String {
+        if self.deterministic {
+            return format!("{:016x}", Self::stable_hash(key));
+        }
+
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -655,6 +2061,13 @@ impl PatternLearningEngine {
             .to_string()
     }
 
+    /// FNV-1a hash, stable across runs, platforms and Rust versions
+    fn stable_hash(key: &str) -> u64 {
+        key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
     fn detect_change_type(&self, old_content: &str, new_content: &str) -> String {
         if old_content.len() > new_content.len() {
             "deletion".to_string()
@@ -673,22 +2086,89 @@ impl PatternLearningEngine {
     }
 
     /// Private helper methods
+    /// Walk the codebase and evaluate user-defined rules against every analyzable file
+    fn evaluate_rules_in_path(&mut self, path: &str, scope: &PathScope) -> Vec<Pattern> {
+        if self.rule_engine.is_empty() {
+            return Vec::new();
+        }
+
+        let root = std::path::Path::new(path);
+        let mut matches = Vec::new();
+        for file_path in self.source_files(path, scope) {
+            if let Ok(content) = self.overlay.read_to_string(&file_path, root) {
+                let file_path = file_path.to_string_lossy();
+                let language = self.detect_language_from_path(&file_path);
+                matches.extend(self.rule_engine.evaluate_file(&file_path, &content, &language));
+            }
+        }
+
+        self.rule_engine.to_patterns(&matches)
+    }
+
+    /// Files under `path` worth analyzing and in scope, walked to a limited
+    /// depth, plus overlaid files, in path order
+    fn source_files(&self, path: &str, scope: &PathScope) -> Vec<std::path::PathBuf> {
+        let root = std::path::Path::new(path);
+        let walker = FileWalker::new()
+            .max_depth(5) // Limit directory traversal depth
+            .filter(|file_path| {
+                self.should_analyze_file(file_path)
+                    && (scope.is_empty() || scope.matches(&paths::relative_to(&file_path.to_string_lossy(), root)))
+            });
+
+        let mut files = walker.walk(root).files;
+        files.extend(self.overlay.files_under(root).into_iter().filter(|file_path| walker.keeps(root, file_path)));
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    fn pacer(&self) -> Pacer {
+        let project_nice = self.project_config.as_ref().map(|config| config.nice);
+        Pacer::new(self.nice.or(project_nice).unwrap_or(DEFAULT_NICE))
+    }
+
     async fn extract_semantic_concepts(
-        &self,
+        &mut self,
         path: &str,
+        scope: &PathScope,
     ) -> Result<Vec<SemanticConcept>, ParseError> {
         let mut concepts = Vec::new();
         let mut file_count = 0;
         let start_time = std::time::Instant::now();
         let timeout = std::time::Duration::from_secs(60); // 60 second timeout
+        let max_files = self
+            .project_config
+            .as_ref()
+            .map(|config| config.max_files)
+            .unwrap_or(100); // Reduced limit for performance unless configured
+
+        let mut candidates = Vec::new();
+        for file_path in self.source_files(path, scope) {
+            let supported = file_path
+                .extension()
+                .and_then(|s| s.to_str())
+                .is_some_and(|extension| self.is_supported_extension(extension));
+            if supported {
+                candidates.push(SampleCandidate {
+                    language: self.detect_language_from_path(&file_path.to_string_lossy()),
+                    path: file_path,
+                });
+            }
+        }
 
-        for entry in WalkDir::new(path)
-            .max_depth(5) // Limit directory traversal depth
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
+        // Sample across directories and languages rather than taking the first files walked
+        let (files, report) = FileSampler::sample(std::path::Path::new(path), candidates, max_files);
+        if let Some(report) = &report {
+            eprintln!("Reached file limit for pattern learning. {}", report.summary());
+        }
+        self.sampling_report = report;
+
+        let mut pacer = self.pacer();
+        for file_path in files {
             // Check timeout
             if start_time.elapsed() > timeout {
+                self.telemetry.on_timeout("learning", None, start_time.elapsed().as_secs_f64() * 1000.0);
                 eprintln!(
                     "Timeout reached during concept extraction after {} files",
                     file_count
@@ -696,29 +2176,24 @@ impl PatternLearningEngine {
                 break;
             }
 
-            if entry.file_type().is_file() && file_count < 100 {
-                // Reduced limit for performance
-                let file_path = entry.path();
-
-                // Add proper file filtering
-                if !self.should_analyze_file(file_path) {
-                    continue;
-                }
-
-                if let Some(extension) = file_path.extension().and_then(|s| s.to_str()) {
-                    if self.is_supported_extension(extension) {
-                        if let Ok(content) = fs::read_to_string(file_path) {
-                            let file_concepts = self.extract_concepts_from_file(
-                                &content,
-                                file_path.to_string_lossy().as_ref(),
-                                extension,
-                            )?;
-                            concepts.extend(file_concepts);
-                            file_count += 1;
-                        }
+            let Some(extension) = file_path.extension().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if let Ok(content) = self.overlay.read_to_string(&file_path, std::path::Path::new(path)) {
+                let file_path = file_path.to_string_lossy();
+                let mut file_concepts =
+                    self.extract_concepts_from_file(&content, &file_path, extension)?;
+                if self.includes_generated() {
+                    if let Some(detail) = FileClassifier::generated_by(std::path::Path::new(file_path.as_ref()), &content) {
+                        mark_generated(&mut file_concepts, &detail);
                     }
                 }
+                concepts.extend(file_concepts);
+                self.renames
+                    .record(&file_path, FileFingerprint::from_content(&content));
+                file_count += 1;
             }
+            pacer.tick().await;
         }
 
         Ok(concepts)
@@ -793,7 +2268,7 @@ impl PatternLearningEngine {
                 if let Some(captures) = regex.captures(trimmed) {
                     if let Some(name) = captures.get(1) {
                         return Some(SemanticConcept {
-                            id: format!("{}_{}", file_path, name.as_str()),
+                            id: format!("{}_{}", paths::normalize_path(file_path), name.as_str()),
                             name: name.as_str().to_string(),
                             concept_type: "function".to_string(),
                             confidence: 0.8,
@@ -816,7 +2291,7 @@ impl PatternLearningEngine {
                 if let Some(captures) = regex.captures(trimmed) {
                     if let Some(name) = captures.get(1) {
                         return Some(SemanticConcept {
-                            id: format!("{}_{}", file_path, name.as_str()),
+                            id: format!("{}_{}", paths::normalize_path(file_path), name.as_str()),
                             name: name.as_str().to_string(),
                             concept_type: "class".to_string(),
                             confidence: 0.9,
@@ -854,45 +2329,33 @@ impl PatternLearningEngine {
         )
     }
 
+    /// Whether a file the walk reached is worth learning from; the walk
+    /// already left out dependency and build directories
     fn should_analyze_file(&self, file_path: &std::path::Path) -> bool {
-        // Skip common non-source directories
-        let path_str = file_path.to_string_lossy();
-        if path_str.contains("node_modules")
-            || path_str.contains(".git")
-            || path_str.contains("target")
-            || path_str.contains("dist")
-            || path_str.contains("build")
-            || path_str.contains(".next")
-            || path_str.contains("__pycache__")
-            || path_str.contains("coverage")
-            || path_str.contains(".vscode")
-            || path_str.contains(".idea")
-        {
-            return false;
+        if let Some(config) = &self.project_config {
+            if !config.matches_project_filters(file_path) {
+                return false;
+            }
         }
 
         // Check if file extension is supported
-        if let Some(extension) = file_path.extension().and_then(|s| s.to_str()) {
-            self.is_supported_extension(extension)
-        } else {
-            false
-        }
+        let supported = file_path
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|extension| self.is_supported_extension(extension));
+
+        // Generated, minified, vendored and binary files don't reflect the team's
+        // conventions; generated code only counts when the project opts in
+        supported
+            && match FileClassifier::classify(file_path).class {
+                FileClass::Source => true,
+                FileClass::Generated => self.includes_generated(),
+                _ => false,
+            }
     }
 
-    fn is_ignored_directory(&self, dir_name: &str) -> bool {
-        matches!(
-            dir_name,
-            "node_modules"
-                | ".git"
-                | "target"
-                | "dist"
-                | "build"
-                | ".next"
-                | "__pycache__"
-                | "coverage"
-                | ".vscode"
-                | ".idea"
-        )
+    fn includes_generated(&self) -> bool {
+        self.project_config.as_ref().is_some_and(|config| config.include_generated)
     }
 
     async fn learn_naming_patterns(
@@ -908,6 +2371,9 @@ impl PatternLearningEngine {
             language_groups.entry(language).or_default().push(concept);
         }
 
+        let mut language_groups: Vec<_> = language_groups.into_iter().collect();
+        language_groups.sort_by(|a, b| a.0.cmp(&b.0));
+
         let mut all_patterns = Vec::new();
         for (language, group_concepts) in language_groups {
             let concept_refs: Vec<_> = group_concepts.into_iter().cloned().collect();
@@ -924,16 +2390,21 @@ impl PatternLearningEngine {
         &mut self,
         concepts: &[SemanticConcept],
         path: &str,
+        scope: &PathScope,
     ) -> Result<Vec<Pattern>, ParseError> {
         let mut patterns = Vec::new();
 
-        // Analyze directory structure (from backup implementation)
-        let directory_structure = self.analyze_directory_structure(path)?;
-        patterns.extend(directory_structure);
+        // Directory layout and architecture describe the whole tree, so a
+        // scoped run learns structure from its own files' concepts only
+        if scope.is_empty() {
+            // Analyze directory structure (from backup implementation)
+            let directory_structure = self.analyze_directory_structure(path)?;
+            patterns.extend(directory_structure);
 
-        // Learn from codebase structure
-        let structure_patterns = self.structural_analyzer.analyze_codebase_structure(path)?;
-        patterns.extend(structure_patterns);
+            // Learn from codebase structure
+            let structure_patterns = self.structural_analyzer.analyze_codebase_structure(path)?;
+            patterns.extend(structure_patterns);
+        }
 
         // Learn from concept relationships
         let concept_patterns = self
@@ -945,64 +2416,19 @@ impl PatternLearningEngine {
     }
 
     fn analyze_directory_structure(&self, path: &str) -> Result<Vec<Pattern>, ParseError> {
-        let mut patterns = Vec::new();
-        let mut directory_stats = std::collections::HashMap::new();
-
-        // Analyze directory structure with depth limit
-        for entry in WalkDir::new(path)
-            .max_depth(3)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            if entry.file_type().is_dir() {
-                let dir_name = entry.file_name().to_string_lossy();
-                if !self.is_ignored_directory(&dir_name) {
-                    *directory_stats.entry(dir_name.to_string()).or_insert(0) += 1;
-                }
-            }
-        }
-
-        // Common patterns from backup
-        let common_dirs = vec![
-            "src",
-            "lib",
-            "components",
-            "utils",
-            "services",
-            "types",
-            "models",
-            "controllers",
-        ];
-        let mut found_patterns = Vec::new();
-
-        for dir in common_dirs {
-            if directory_stats.contains_key(dir) {
-                found_patterns.push(dir.to_string());
-            }
+        // A root made only of overlaid files has no directories on disk
+        if !std::path::Path::new(path).is_dir() {
+            return Ok(Vec::new());
         }
-
-        if found_patterns.len() >= 2 {
-            patterns.push(Pattern {
-                id: format!("struct_dirs_{}", self.generate_pattern_id()),
-                pattern_type: "structure_organized_directories".to_string(),
-                description: format!(
-                    "Organized directory structure with: {}",
-                    found_patterns.join(", ")
-                ),
-                frequency: found_patterns.len() as u32,
-                confidence: 0.8,
-                examples: vec![],
-                contexts: vec!["architecture".to_string(), "organization".to_string()],
-            });
-        }
-
-        Ok(patterns)
+        let report = DirectoryLayoutLearner::learn(path, FileWalker::is_ignored_directory)?;
+        Ok(DirectoryLayoutLearner::to_patterns(&report, |key| self.generate_pattern_id(key)))
     }
 
     async fn learn_implementation_patterns(
         &mut self,
         concepts: &[SemanticConcept],
         path: &str,
+        scope: &PathScope,
     ) -> Result<Vec<Pattern>, ParseError> {
         let mut patterns = Vec::new();
 
@@ -1010,8 +2436,11 @@ impl PatternLearningEngine {
         let concept_patterns = self.implementation_analyzer.analyze_concepts(concepts)?;
         patterns.extend(concept_patterns);
 
-        // Learn from code files
-        let code_patterns = self.implementation_analyzer.analyze_code_files(path)?;
+        // Learn from code files in scope
+        let root = std::path::Path::new(path);
+        let code_patterns = self.implementation_analyzer.analyze_code_files_where(path, |file_path| {
+            scope.is_empty() || scope.matches(&paths::relative_to(&file_path.to_string_lossy(), root))
+        })?;
         patterns.extend(code_patterns);
 
         Ok(patterns)
@@ -1021,53 +2450,92 @@ impl PatternLearningEngine {
         &self,
         patterns: Vec<Pattern>,
     ) -> Result<Vec<Pattern>, ParseError> {
-        let mut consolidated: HashMap<String, Pattern> = HashMap::new();
-        let mut pattern_groups: HashMap<String, Vec<Pattern>> = HashMap::new();
+        let mut validated: Vec<Pattern> = self
+            .consolidation_groups(patterns)
+            .into_iter()
+            .map(|mut group_patterns| {
+                // The merged pattern takes the first ID, so fix the order first
+                sort_patterns(&mut group_patterns);
+                if group_patterns.len() == 1 {
+                    group_patterns.into_iter().next().unwrap()
+                } else {
+                    // Merge patterns in the group
+                    self.merge_similar_patterns(group_patterns)
+                }
+            })
+            .collect();
+        sort_patterns(&mut validated);
 
-        // Group similar patterns with quality thresholds
-        for pattern in patterns {
-            // Apply quality thresholds from old implementation
-            let min_frequency = if pattern.pattern_type.contains("naming") {
-                3
-            } else {
-                2
-            };
+        Ok(validated)
+    }
 
-            if pattern.confidence >= self.confidence_threshold && pattern.frequency >= min_frequency
-            {
-                let group_key = format!(
-                    "{}_{}",
-                    pattern.pattern_type,
-                    self.normalize_description(&pattern.description)
-                );
-                pattern_groups.entry(group_key).or_default().push(pattern);
-            }
-        }
+    /// Candidates that pass the quality thresholds, grouped by the consolidation
+    /// strategy; pinned patterns are kept as curated rather than merged with new candidates
+    fn consolidation_groups(&self, patterns: Vec<Pattern>) -> Vec<Vec<Pattern>> {
+        let mut candidates: Vec<Pattern> = patterns
+            .into_iter()
+            .filter(|pattern| self.passes_quality_threshold(pattern) && !self.pinned.contains(&pattern.id))
+            .collect();
+        // Similarity strategies depend on the order candidates are seen in
+        sort_patterns(&mut candidates);
+
+        let groups = self.consolidation_strategy().group(&candidates);
+        let mut candidates: Vec<Option<Pattern>> = candidates.into_iter().map(Some).collect();
+        groups
+            .into_iter()
+            .map(|members| members.into_iter().filter_map(|index| candidates[index].take()).collect())
+            .collect()
+    }
 
-        // Consolidate each group
-        for (group_key, group_patterns) in pattern_groups {
-            if group_patterns.len() == 1 {
-                consolidated.insert(group_key, group_patterns.into_iter().next().unwrap());
-            } else {
-                // Merge patterns in the group
-                let merged = self.merge_similar_patterns(group_patterns);
-                consolidated.insert(group_key, merged);
+    fn consolidation_strategy(&self) -> ConsolidationStrategy {
+        let project_strategy = self
+            .project_config
+            .as_ref()
+            .and_then(|config| config.consolidation.as_deref())
+            .and_then(|name| ConsolidationStrategy::parse(name).ok());
+        self.consolidation.or(project_strategy).unwrap_or_default()
+    }
+
+    /// Apply quality thresholds from old implementation
+    fn passes_quality_threshold(&self, pattern: &Pattern) -> bool {
+        // A single risky spot is worth reporting
+        let min_frequency = if pattern.pattern_type.contains("naming") {
+            3
+        } else if pattern.pattern_type.ends_with("_violation") {
+            1
+        } else {
+            2
+        };
+        pattern.confidence >= self.confidence_threshold && pattern.frequency >= min_frequency
+    }
+
+    /// Record which candidates `validate_and_consolidate_patterns` merged together.
+    /// The merged pattern keeps the first ID of its group.
+    fn record_consolidations(&mut self, candidates: &[Pattern]) {
+        for group in self.consolidation_groups(candidates.to_vec()) {
+            if group.len() < 2 {
+                continue;
             }
+            let mut ids: Vec<String> = group.into_iter().map(|pattern| pattern.id).collect();
+            ids.sort();
+            let merged_id = ids.remove(0);
+            self.provenance.record_merge(&merged_id, ids);
         }
+    }
 
-        Ok(consolidated.into_values().collect())
+    fn note_origins(origins: &mut HashMap<String, &'static str>, patterns: &[Pattern], analyzer: &'static str) {
+        for pattern in patterns {
+            origins.entry(pattern.id.clone()).or_insert(analyzer);
+        }
     }
 
-    fn normalize_description(&self, description: &str) -> String {
-        description
-            .to_lowercase()
-            .chars()
-            .filter(|c| c.is_alphanumeric() || c.is_whitespace())
-            .collect::<String>()
-            .split_whitespace()
-            .take(3) // Take first 3 words for grouping
-            .collect::<Vec<&str>>()
-            .join("_")
+    /// Wall-clock time for provenance entries, omitted in deterministic mode
+    fn provenance_timestamp(&self) -> Option<String> {
+        if self.deterministic {
+            None
+        } else {
+            Some(chrono::Utc::now().to_rfc3339())
+        }
     }
 
     fn merge_similar_patterns(&self, patterns: Vec<Pattern>) -> Pattern {
@@ -1101,7 +2569,11 @@ impl PatternLearningEngine {
             frequency: total_frequency,
             confidence: avg_confidence,
             examples: all_examples,
-            contexts: all_contexts.into_iter().collect(),
+            contexts: {
+                let mut contexts: Vec<String> = all_contexts.into_iter().collect();
+                contexts.sort();
+                contexts
+            },
         }
     }
 
@@ -1142,8 +2614,10 @@ impl PatternLearningEngine {
                 .or_insert(0) += 1;
         }
 
-        // Update timestamp
-        self.learning_metrics.last_learning_timestamp = Some(chrono::Utc::now().to_rfc3339());
+        // Update timestamp (left unset in deterministic mode so metrics are reproducible)
+        if !self.deterministic {
+            self.learning_metrics.last_learning_timestamp = Some(chrono::Utc::now().to_rfc3339());
+        }
 
         // Calculate learning accuracy (simplified)
         let high_confidence_patterns = patterns.iter().filter(|p| p.confidence >= 0.8).count();
@@ -1175,12 +2649,14 @@ impl PatternLearningEngine {
                 "js" | "jsx" => "javascript",
                 "ts" | "tsx" => "typescript",
                 "rs" => "rust",
-                "py" => "python",
+                "py" | "ipynb" => "python",
                 "java" => "java",
                 "cpp" | "cc" | "cxx" => "cpp",
                 "c" => "c",
                 "cs" => "csharp",
                 "go" => "go",
+                "php" => "php",
+                "sql" => "sql",
                 _ => "unknown",
             }
             .to_string()
@@ -1202,64 +2678,160 @@ impl PatternLearningEngine {
             || new_content.contains("function ") != old_content.contains("function ")
     }
 
-    async fn learn_structural_changes(
-        &self,
-        _old_content: &str,
-        _new_content: &str,
-        _file_path: &str,
-    ) -> Result<Vec<Pattern>, ParseError> {
-        // Simplified implementation - would need proper AST diffing
-        Ok(Vec::new())
+    /// Diff the concepts in two versions of a file
+    fn diff_concepts(
+        &mut self,
+        old_content: &str,
+        new_content: &str,
+        file_path: &str,
+    ) -> Option<FileConceptDiff> {
+        self.concept_analyzer().map(|analyzer| {
+            analyzer.diff_file_concepts(
+                old_content.to_string(),
+                new_content.to_string(),
+                file_path.to_string(),
+            )
+        })
     }
 
-    fn parse_concepts_from_analysis(
-        &self,
-        data: &Value,
-    ) -> Result<Vec<SemanticConcept>, ParseError> {
-        let mut concepts = Vec::new();
+    /// Concepts declared in one version of a file, without storing them
+    fn file_concepts(&mut self, file_path: &str, content: &str) -> Vec<SemanticConcept> {
+        self.concept_analyzer()
+            .map(|analyzer| analyzer.extract_unstored(file_path, content))
+            .unwrap_or_default()
+    }
+
+    /// The analyzer behind concept diffs, creating the parser on first use
+    fn concept_analyzer(&mut self) -> Option<&mut SemanticAnalyzer> {
+        if self.concept_analyzer.is_none() {
+            self.concept_analyzer = SemanticAnalyzer::new().ok();
+        }
+        self.concept_analyzer.as_mut()
+    }
 
-        if let Some(concepts_array) = data.get("concepts").and_then(|v| v.as_array()) {
-            for concept_value in concepts_array {
-                if let Ok(concept) = self.parse_concept_from_value(concept_value) {
-                    concepts.push(concept);
+    /// Concepts declared in the current file and selection, and learned patterns
+    /// with examples in the same directory or inside the selection
+    fn resolve_surrounding_code(
+        &mut self,
+        current_file: Option<String>,
+        selected_code: Option<String>,
+    ) -> SurroundingCode {
+        let mut surrounding = SurroundingCode {
+            file_path: current_file.clone(),
+            ..Default::default()
+        };
+        if current_file.is_none() && selected_code.is_none() {
+            return surrounding;
+        }
+
+        if self.concept_analyzer.is_none() {
+            self.concept_analyzer = SemanticAnalyzer::new().ok();
+        }
+        if let (Some(analyzer), Some(file)) = (self.concept_analyzer.as_mut(), current_file.as_deref()) {
+            if let Ok(content) = fs::read_to_string(file) {
+                surrounding.concepts = analyzer.extract_unstored(file, &content);
+            }
+            if let Some(selection) = selected_code.as_deref() {
+                for concept in analyzer.extract_unstored(file, selection) {
+                    if !surrounding.concepts.iter().any(|c| c.name == concept.name) {
+                        surrounding.concepts.push(concept);
+                    }
                 }
             }
         }
 
-        Ok(concepts)
+        let module_dir = current_file
+            .as_deref()
+            .and_then(|file| std::path::Path::new(file).parent().map(|p| p.to_path_buf()));
+        let selection = selected_code.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        let mut patterns: Vec<Pattern> = self
+            .learned_patterns
+            .values()
+            .filter(|pattern| {
+                pattern.examples.iter().any(|example| {
+                    let in_module = module_dir.as_deref().is_some_and(|dir| {
+                        std::path::Path::new(&example.file_path).parent() == Some(dir)
+                    });
+                    let in_selection = selection.is_some_and(|code| {
+                        let snippet = example.code.trim();
+                        !snippet.is_empty() && code.contains(snippet)
+                    });
+                    in_module || in_selection
+                })
+            })
+            .cloned()
+            .collect();
+        patterns.sort_by(|a, b| a.id.cmp(&b.id));
+        surrounding.patterns = patterns;
+        surrounding
     }
 
-    fn parse_concept_from_value(&self, value: &Value) -> Result<SemanticConcept, ParseError> {
-        let name = value
-            .get("name")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown")
-            .to_string();
-        let concept_type = value
-            .get("type")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown")
-            .to_string();
-        let file_path = value
-            .get("file")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown")
-            .to_string();
-        let confidence = value
-            .get("confidence")
-            .and_then(|v| v.as_f64())
-            .unwrap_or(0.5);
+    /// One pattern per kind of change and concept type in the diff
+    async fn learn_structural_changes(
+        &self,
+        diff: &FileConceptDiff,
+        file_path: &str,
+    ) -> Result<Vec<Pattern>, ParseError> {
+        let categories: [(&str, &str, &[ConceptChange]); 4] = [
+            ("added", "Added", &diff.added),
+            ("removed", "Removed", &diff.removed),
+            ("renamed", "Renamed", &diff.renamed),
+            ("moved", "Moved", &diff.moved),
+        ];
 
-        Ok(SemanticConcept {
-            id: format!("{}_{}", file_path, name),
-            name,
-            concept_type,
-            confidence,
-            file_path,
-            line_range: crate::types::LineRange { start: 1, end: 1 },
-            relationships: HashMap::new(),
-            metadata: HashMap::new(),
-        })
+        let mut patterns = Vec::new();
+        for (category, verb, changes) in categories {
+            let mut by_type: BTreeMap<&str, Vec<&ConceptChange>> = BTreeMap::new();
+            for change in changes {
+                by_type
+                    .entry(change.concept_type.as_str())
+                    .or_default()
+                    .push(change);
+            }
+
+            for (concept_type, changes) in by_type {
+                let examples = changes
+                    .iter()
+                    .take(5)
+                    .map(|change| crate::patterns::types::PatternExample {
+                        code: match &change.previous_name {
+                            Some(previous) => format!("{} -> {}", previous, change.name),
+                            None => change.name.clone(),
+                        },
+                        file_path: file_path.to_string(),
+                        line_range: change
+                            .new_range
+                            .clone()
+                            .or_else(|| change.old_range.clone())
+                            .unwrap_or(crate::types::LineRange { start: 1, end: 1 }),
+                    })
+                    .collect();
+
+                let key = format!("{}:{}:{}", file_path, category, concept_type);
+                patterns.push(Pattern {
+                    id: format!(
+                        "change_{}_{}_{}",
+                        category,
+                        concept_type,
+                        self.generate_pattern_id(&key)
+                    ),
+                    pattern_type: format!("structural_{}", category),
+                    description: format!(
+                        "{} {} {}(s) in {}",
+                        verb,
+                        changes.len(),
+                        concept_type,
+                        file_path
+                    ),
+                    frequency: changes.len() as u32,
+                    confidence: 0.6,
+                    examples,
+                    contexts: vec![concept_type.to_string(), "incremental".to_string()],
+                });
+            }
+        }
+
+        Ok(patterns)
     }
 
     // File change handling methods (from original implementation)
@@ -1360,49 +2932,87 @@ impl PatternLearningEngine {
     async fn handle_file_rename(
         &mut self,
         file_path: Option<&str>,
-        change: &Value,
+        old_path: Option<&str>,
     ) -> Result<bool, ParseError> {
-        let mut updated = false;
-
-        if let Some(old_path) = change.get("oldPath").and_then(|p| p.as_str()) {
-            let new_path = file_path.unwrap_or("unknown");
+        match old_path {
+            Some(old_path) => {
+                self.apply_file_rename(old_path, file_path.unwrap_or("unknown"))
+                    .await
+            }
+            None => Ok(false),
+        }
+    }
 
-            // Learn from file movement patterns
-            let old_dir = std::path::Path::new(old_path)
-                .parent()
-                .and_then(|p| p.file_name())
-                .and_then(|s| s.to_str())
-                .unwrap_or("root");
-            let new_dir = std::path::Path::new(new_path)
-                .parent()
-                .and_then(|p| p.file_name())
-                .and_then(|s| s.to_str())
-                .unwrap_or("root");
+    /// Move a file's pattern contributions to its new path and learn from the move
+    async fn apply_file_rename(&mut self, old_path: &str, new_path: &str) -> Result<bool, ParseError> {
+        self.renames.rename(old_path, new_path);
 
-            if old_dir != new_dir {
-                let pattern_type = format!("file_movement_{}_{}", old_dir, new_dir);
-                updated |= self.update_pattern_frequency(&pattern_type, 1).await?;
+        // Examples keep pointing at the file under its new name instead of being re-learned
+        let mut updated = false;
+        for pattern in self.learned_patterns.values_mut() {
+            for example in pattern.examples.iter_mut().filter(|e| e.file_path == old_path) {
+                example.file_path = new_path.to_string();
+                updated = true;
             }
+        }
 
-            // Learn from renaming patterns
-            let old_name = std::path::Path::new(old_path)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("unknown");
-            let new_name = std::path::Path::new(new_path)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("unknown");
+        // Learn from file movement patterns
+        let old_dir = std::path::Path::new(old_path)
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|s| s.to_str())
+            .unwrap_or("root");
+        let new_dir = std::path::Path::new(new_path)
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|s| s.to_str())
+            .unwrap_or("root");
 
-            if old_name != new_name {
-                let pattern_type = "file_renaming".to_string();
-                updated |= self.update_pattern_frequency(&pattern_type, 1).await?;
-            }
+        if old_dir != new_dir {
+            let pattern_type = format!("file_movement_{}_{}", old_dir, new_dir);
+            updated |= self.update_pattern_frequency(&pattern_type, 1).await?;
+        }
+
+        // Learn from renaming patterns
+        let old_name = std::path::Path::new(old_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown");
+        let new_name = std::path::Path::new(new_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown");
+
+        if old_name != new_name {
+            let pattern_type = "file_renaming".to_string();
+            updated |= self.update_pattern_frequency(&pattern_type, 1).await?;
         }
 
         Ok(updated)
     }
 
+    /// Treat deletions that never matched a create as real deletions
+    async fn apply_deletions(&mut self, paths: Vec<String>) -> Result<bool, ParseError> {
+        let mut updated = false;
+        for path in paths {
+            updated |= self.handle_file_deletion(Some(&path)).await?;
+            updated |= self
+                .learn_from_change_pattern("delete", Some(&path), None)
+                .await?;
+        }
+        Ok(updated)
+    }
+
+    /// Fingerprint a file from the event content, or from disk when the event has none
+    fn fingerprint_file(path: &str, content: Option<&str>) -> Option<FileFingerprint> {
+        match content {
+            Some(content) => Some(FileFingerprint::from_content(content)),
+            None => fs::read_to_string(path)
+                .ok()
+                .map(|content| FileFingerprint::from_content(&content)),
+        }
+    }
+
     /// Learn from change patterns (from original implementation)
     async fn learn_from_change_pattern(
         &mut self,
@@ -1422,12 +3032,15 @@ impl PatternLearningEngine {
             updated |= self.update_pattern_frequency(&lang_pattern, 1).await?;
         }
 
-        // Time-based patterns (hour of day, day of week)
-        let now = std::time::SystemTime::now();
-        if let Ok(duration) = now.duration_since(std::time::UNIX_EPOCH) {
-            let hour = (duration.as_secs() / 3600) % 24;
-            let time_pattern = format!("change_time_hour_{}", hour);
-            updated |= self.update_pattern_frequency(&time_pattern, 1).await?;
+        // Time-based patterns (hour of day, day of week). These depend on the wall
+        // clock, so deterministic mode skips them
+        if !self.deterministic {
+            let now = std::time::SystemTime::now();
+            if let Ok(duration) = now.duration_since(std::time::UNIX_EPOCH) {
+                let hour = (duration.as_secs() / 3600) % 24;
+                let time_pattern = format!("change_time_hour_{}", hour);
+                updated |= self.update_pattern_frequency(&time_pattern, 1).await?;
+            }
         }
 
         Ok(updated)
@@ -1599,12 +3212,21 @@ impl PatternLearningEngine {
             .and_then(|s| s.to_str())
         {
             // Find patterns related to this file type and decrease their confidence slightly
+            let timestamp = self.provenance_timestamp();
+            let reason = format!("related file deleted: {}", file_path);
             for pattern in self.learned_patterns.values_mut() {
                 if (pattern.pattern_type.contains(extension)
                     || pattern.contexts.contains(&extension.to_string()))
                     && pattern.confidence > 0.1
+                    && !self.pinned.contains(&pattern.id)
                 {
                     pattern.confidence = (pattern.confidence - 0.02).max(0.1);
+                    self.provenance.record_adjustment(
+                        &pattern.id,
+                        pattern.confidence,
+                        &reason,
+                        timestamp.clone(),
+                    );
                     updated = true;
                 }
             }
@@ -1619,24 +3241,17 @@ impl PatternLearningEngine {
         let mut names = Vec::new();
 
         // TypeScript/JavaScript function patterns
-        if line.contains("function ") {
-            if let Some(start) = line.find("function ") {
-                let after_function = &line[start + 9..];
-                if let Some(end) = after_function.find('(') {
-                    let name = after_function[..end].trim();
-                    if !name.is_empty() && self.is_valid_identifier(name) {
-                        names.push(name.to_string());
-                    }
-                }
-            }
+        if let Some(name) = NameExtractor::identifier_between(line, "function ", '(')
+            .filter(|name| self.is_valid_identifier(name))
+        {
+            names.push(name);
         }
 
         // Arrow function patterns
-        if line.contains(" = ") && line.contains("=>") {
-            if let Some(equal_pos) = line.find(" = ") {
-                let before_equal = &line[..equal_pos];
-                if let Some(start) = before_equal.rfind(char::is_whitespace) {
-                    let name = before_equal[start..].trim();
+        if line.contains("=>") {
+            if let Some((before_equal, _)) = line.split_once(" = ") {
+                if let Some((_, name)) = before_equal.rsplit_once(char::is_whitespace) {
+                    let name = name.trim();
                     if !name.is_empty() && self.is_valid_identifier(name) {
                         names.push(name.to_string());
                     }
@@ -1646,14 +3261,10 @@ impl PatternLearningEngine {
 
         // Python function patterns
         if line.trim_start().starts_with("def ") {
-            if let Some(start) = line.find("def ") {
-                let after_def = &line[start + 4..];
-                if let Some(end) = after_def.find('(') {
-                    let name = after_def[..end].trim();
-                    if !name.is_empty() && self.is_valid_identifier(name) {
-                        names.push(name.to_string());
-                    }
-                }
+            if let Some(name) = NameExtractor::identifier_between(line, "def ", '(')
+                .filter(|name| self.is_valid_identifier(name))
+            {
+                names.push(name);
             }
         }
 
@@ -1667,18 +3278,15 @@ impl PatternLearningEngine {
     fn extract_class_names(&self, line: &str) -> Option<Vec<String>> {
         let mut names = Vec::new();
 
-        if line.contains("class ") {
-            if let Some(start) = line.find("class ") {
-                let after_class = &line[start + 6..];
-                let end = after_class
-                    .find(char::is_whitespace)
-                    .or_else(|| after_class.find('{'))
-                    .or_else(|| after_class.find('('))
-                    .unwrap_or(after_class.len());
-                let name = after_class[..end].trim();
-                if !name.is_empty() && self.is_valid_identifier(name) {
-                    names.push(name.to_string());
-                }
+        if let Some(after_class) = NameExtractor::text_after(line, "class ") {
+            let name = if after_class.contains(char::is_whitespace) {
+                NameExtractor::text_until(after_class, char::is_whitespace)
+            } else {
+                NameExtractor::text_until(after_class, |c| c == '{' || c == '(')
+            }
+            .trim();
+            if !name.is_empty() && self.is_valid_identifier(name) {
+                names.push(name.to_string());
             }
         }
 
@@ -1695,16 +3303,10 @@ impl PatternLearningEngine {
         // TypeScript/JavaScript variable patterns
         let patterns = vec!["const ", "let ", "var "];
         for pattern in patterns {
-            if line.contains(pattern) {
-                if let Some(start) = line.find(pattern) {
-                    let after_keyword = &line[start + pattern.len()..];
-                    if let Some(equal_pos) = after_keyword.find('=') {
-                        let name = after_keyword[..equal_pos].trim();
-                        if !name.is_empty() && self.is_valid_identifier(name) {
-                            names.push(name.to_string());
-                        }
-                    }
-                }
+            if let Some(name) = NameExtractor::identifier_between(line, pattern, '=')
+                .filter(|name| self.is_valid_identifier(name))
+            {
+                names.push(name);
             }
         }
 
@@ -1721,57 +3323,23 @@ impl PatternLearningEngine {
             && name.chars().all(|c| c.is_alphanumeric() || c == '_')
     }
 
+    /// Convention of an identifier (see `NamingPatternAnalyzer::classify_convention`)
     fn classify_naming_pattern(&self, name: &str, _context: &str) -> String {
-        if self.is_camel_case(name) {
-            "camelCase".to_string()
-        } else if self.is_pascal_case(name) {
-            "PascalCase".to_string()
-        } else if self.is_snake_case(name) {
-            "snake_case".to_string()
-        } else if self.is_kebab_case(name) {
-            "kebab-case".to_string()
-        } else if self.is_upper_case(name) {
-            "UPPER_CASE".to_string()
-        } else {
-            "mixed".to_string()
-        }
-    }
-
-    fn is_camel_case(&self, name: &str) -> bool {
-        name.chars().next().is_some_and(|c| c.is_lowercase())
-            && name.contains(char::is_uppercase)
-            && !name.contains('_')
-            && !name.contains('-')
-    }
-
-    fn is_pascal_case(&self, name: &str) -> bool {
-        name.chars().next().is_some_and(|c| c.is_uppercase())
-            && !name.contains('_')
-            && !name.contains('-')
-    }
-
-    fn is_snake_case(&self, name: &str) -> bool {
-        name.chars().all(|c| c.is_lowercase() || c == '_') && name.contains('_')
-    }
-
-    fn is_kebab_case(&self, name: &str) -> bool {
-        name.chars().all(|c| c.is_lowercase() || c == '-') && name.contains('-')
-    }
-
-    fn is_upper_case(&self, name: &str) -> bool {
-        name.chars().all(|c| c.is_uppercase() || c == '_')
+        NamingPatternAnalyzer::classify_convention(name).to_string()
     }
 }
 
 impl PatternLearnerTrait for PatternLearningEngine {
     fn learn_from_data(&mut self, data: &str) -> Result<Vec<Pattern>, ParseError> {
         // Synchronous version of learning from data
-        let runtime = tokio::runtime::Runtime::new()
+        // Current-thread runtime so this also works where threads are unavailable (wasm32)
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
             .map_err(|e| ParseError::from_reason(format!("Failed to create runtime: {}", e)))?;
 
         runtime.block_on(async { unsafe { self.learn_from_analysis(data.to_string()).await } })?;
 
-        Ok(self.learned_patterns.values().cloned().collect())
+        Ok(self.get_learned_patterns())
     }
 }
 
@@ -1897,6 +3465,53 @@ mod tests {
         assert_eq!(consolidated[0].frequency, 8); // 5 + 3
     }
 
+    #[test]
+    fn test_consolidation_strategy_from_config_and_host() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_string_lossy().to_string();
+        let candidate = |id: &str, description: &str, context: &str| Pattern {
+            id: id.to_string(),
+            pattern_type: "implementation".to_string(),
+            description: description.to_string(),
+            frequency: 3,
+            confidence: 0.8,
+            examples: vec![],
+            contexts: vec![context.to_string()],
+        };
+        let candidates = || {
+            vec![
+                candidate("try_catch", "Error handling with try/catch", "typescript"),
+                candidate("result", "Error handling with Result types", "rust"),
+            ]
+        };
+
+        let mut engine = PatternLearningEngine::new();
+        assert_eq!(engine.get_consolidation_strategy(), "description");
+        assert_eq!(engine.validate_and_consolidate_patterns(candidates()).unwrap().len(), 1);
+
+        fs::create_dir_all(temp_dir.path().join(".in-memoria")).unwrap();
+        fs::write(
+            temp_dir.path().join(".in-memoria/config.json"),
+            r#"{ "analysis": { "consolidation": "type-context" } }"#,
+        )
+        .unwrap();
+        engine.load_project_config(root.clone()).unwrap();
+        assert_eq!(engine.get_consolidation_strategy(), "type-context");
+        assert_eq!(engine.validate_and_consolidate_patterns(candidates()).unwrap().len(), 2);
+
+        // The host's choice wins over the project's
+        engine.set_consolidation_strategy("description".to_string()).unwrap();
+        assert_eq!(engine.validate_and_consolidate_patterns(candidates()).unwrap().len(), 1);
+        assert!(engine.set_consolidation_strategy("kmeans".to_string()).is_err());
+
+        fs::write(
+            temp_dir.path().join(".in-memoria/config.json"),
+            r#"{ "analysis": { "consolidation": "kmeans" } }"#,
+        )
+        .unwrap();
+        assert!(engine.load_project_config(root).is_err());
+    }
+
     #[tokio::test]
     async fn test_confidence_threshold() {
         let mut engine = PatternLearningEngine::new();
@@ -2017,6 +3632,174 @@ class UserService {
         assert!(!engine.learned_patterns.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_telemetry_reports_each_learned_pattern() {
+        let telemetry = std::sync::Arc::new(crate::analysis::RecordingTelemetry::default());
+        let mut engine = PatternLearningEngine::new();
+        engine.set_telemetry(telemetry.clone());
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("users.js"),
+            "function getUserName(user) {\n  return user.name;\n}\n\nfunction getUserEmail(user) {\n  return user.email;\n}\n",
+        )
+        .unwrap();
+        let patterns = unsafe { engine.learn_from_codebase(temp_dir.path().to_string_lossy().to_string()).await }.unwrap();
+
+        let events = telemetry.events.lock().unwrap();
+        assert!(!patterns.is_empty());
+        assert!(events.iter().all(|event| event.kind == "pattern_learned" && event.detail.is_some()));
+        assert!(patterns.iter().all(|p| events.iter().any(|e| e.pattern_id.as_deref() == Some(p.id.as_str()))));
+    }
+
+    #[test]
+    fn test_export_and_import_patterns_between_engines() {
+        let mut source = PatternLearningEngine::new();
+        source.approach_predictor.set_project_path(Some("/work/payments".to_string()));
+        source.insert_pattern("naming_camel".to_string(), Pattern {
+            id: "naming_camel".to_string(),
+            pattern_type: "naming_function_camelCase".to_string(),
+            description: "camelCase functions".to_string(),
+            frequency: 12,
+            confidence: 0.9,
+            examples: vec![],
+            contexts: vec!["typescript".to_string()],
+        });
+        source.insert_pattern("repository".to_string(), Pattern {
+            id: "repository".to_string(),
+            pattern_type: "structural_repository".to_string(),
+            description: "Repository classes".to_string(),
+            frequency: 4,
+            confidence: 0.4,
+            examples: vec![],
+            contexts: vec![],
+        });
+
+        let filter = PatternExportFilter { min_confidence: Some(0.5), ..Default::default() };
+        let data = source.export_patterns(Some(filter)).unwrap();
+        assert!(data.contains("/work/payments"));
+
+        let mut target = PatternLearningEngine::new();
+        let mut local = source.get_pattern("naming_camel").unwrap().clone();
+        local.confidence = 0.5;
+        target.insert_pattern(local.id.clone(), local);
+
+        let report = target.import_patterns(data.clone(), None).unwrap();
+        assert_eq!((report.added, report.merged, report.kept_local), (0, 0, 1));
+        assert_eq!(report.source_repo, "/work/payments");
+        assert!(!target.has_pattern("repository"));
+
+        let report = target.import_patterns(data, Some("average-confidence".to_string())).unwrap();
+        assert_eq!(report.merged, 1);
+        assert!((target.get_pattern("naming_camel").unwrap().confidence - 0.7).abs() < 1e-9);
+        let history = target.explain_pattern("naming_camel".to_string()).unwrap().confidence_history;
+        assert!(history.last().unwrap().reason.contains("/work/payments"));
+
+        assert!(target.import_patterns("{}".to_string(), Some("newest".to_string())).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scoped_learning_reads_only_files_in_scope() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_string_lossy().to_string();
+        fs::create_dir_all(temp_dir.path().join("src/payments")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("src/users")).unwrap();
+        fs::write(
+            temp_dir.path().join("src/payments/charges.js"),
+            "function chargeCard(card) {\n  return card.id;\n}\n\nfunction refundCharge(charge) {\n  return charge.id;\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("src/users/accounts.js"),
+            "function loadAccount(id) {\n  return id;\n}\n\nfunction saveAccount(account) {\n  return account;\n}\n",
+        )
+        .unwrap();
+        // Examples summarizing several files name no file of their own
+        let example_files = |patterns: &[Pattern]| -> HashSet<String> {
+            patterns
+                .iter()
+                .flat_map(|p| p.examples.iter().map(|e| e.file_path.clone()))
+                .filter(|file| file != "multiple_files")
+                .collect()
+        };
+
+        let mut engine = PatternLearningEngine::new();
+        let patterns = unsafe { engine.learn_from_paths(root.clone(), vec!["src/payments".to_string()]).await }.unwrap();
+        assert!(!patterns.is_empty());
+        assert!(example_files(&patterns).iter().all(|file| file.contains("payments")));
+
+        let scope = LearningScope {
+            include: Some(vec!["src/**".to_string()]),
+            exclude: Some(vec!["src/payments/**".to_string()]),
+            paths: None,
+        };
+        let mut engine = PatternLearningEngine::new();
+        let patterns = unsafe { engine.learn_from_codebase_scoped(root.clone(), scope).await }.unwrap();
+        assert!(!patterns.is_empty());
+        assert!(example_files(&patterns).iter().all(|file| file.contains("users")));
+
+        assert!(unsafe { engine.learn_from_paths(root.clone(), vec![" ".to_string()]).await }.is_err());
+        assert!(unsafe { engine.learn_from_paths(root, vec!["../outside".to_string()]).await }.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_generated_code_is_left_out_of_learning_unless_included() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_string_lossy().to_string();
+        fs::create_dir_all(temp_dir.path().join("src/api")).unwrap();
+        fs::write(
+            temp_dir.path().join("src/users.js"),
+            "function loadAccount(id) {\n  return id;\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("src/api/orders_pb.js"),
+            "function get_order_id(msg) {\n  return msg.id;\n}\n",
+        )
+        .unwrap();
+        let generated = |concepts: &[SemanticConcept]| -> Vec<String> {
+            concepts
+                .iter()
+                .filter(|c| c.metadata.get("generated").map(String::as_str) == Some("true"))
+                .map(|c| c.name.clone())
+                .collect()
+        };
+
+        let mut engine = PatternLearningEngine::new();
+        engine.load_project_config(root.clone()).unwrap();
+        let concepts = engine.extract_semantic_concepts(&root, &PathScope::default()).await.unwrap();
+        assert!(concepts.iter().all(|c| !c.file_path.ends_with("orders_pb.js")));
+
+        fs::create_dir_all(temp_dir.path().join(".in-memoria")).unwrap();
+        fs::write(
+            temp_dir.path().join(".in-memoria/config.json"),
+            r#"{ "analysis": { "includeGenerated": true } }"#,
+        )
+        .unwrap();
+        engine.load_project_config(root.clone()).unwrap();
+        let concepts = engine.extract_semantic_concepts(&root, &PathScope::default()).await.unwrap();
+        assert_eq!(generated(&concepts), vec!["get_order_id".to_string()]);
+        let tagged = concepts.iter().find(|c| c.name == "get_order_id").unwrap();
+        assert_eq!(tagged.metadata.get("generated_by").map(String::as_str), Some("'*_pb.js' file name"));
+    }
+
+    #[tokio::test]
+    async fn test_learns_from_overlaid_files_without_touching_disk() {
+        let temp_dir = TempDir::new().unwrap();
+        let virtual_root = temp_dir.path().join("virtual").to_string_lossy().to_string();
+
+        let mut engine = PatternLearningEngine::new();
+        assert!(unsafe { engine.learn_from_codebase(virtual_root.clone()).await }.is_err());
+
+        engine.set_file_overlay(HashMap::from([(
+            "src/users.js".to_string(),
+            "function getUserName(user) {\n  return user.name;\n}\n\nfunction getUserEmail(user) {\n  return user.email;\n}\n".to_string(),
+        )]));
+        let patterns = unsafe { engine.learn_from_codebase(virtual_root.clone()).await }.unwrap();
+        assert!(!patterns.is_empty());
+        assert!(!std::path::Path::new(&virtual_root).exists());
+    }
+
     #[tokio::test]
     async fn test_learn_from_analysis_data() {
         let mut engine = PatternLearningEngine::new();
@@ -2050,21 +3833,6 @@ class UserService {
         assert!(!engine.learned_patterns.is_empty());
     }
 
-    #[test]
-    fn test_description_normalization() {
-        let engine = PatternLearningEngine::new();
-
-        let desc1 = "CamelCase naming pattern for functions";
-        let desc2 = "camelCase naming pattern in JavaScript";
-
-        let norm1 = engine.normalize_description(desc1);
-        let norm2 = engine.normalize_description(desc2);
-
-        // Should normalize to similar keys for grouping
-        assert_eq!(norm1, "camelcase_naming_pattern");
-        assert_eq!(norm2, "camelcase_naming_pattern");
-    }
-
     #[test]
     fn test_pattern_merge() {
         let engine = PatternLearningEngine::new();
@@ -2096,4 +3864,801 @@ class UserService {
         assert_eq!(merged.confidence, 0.7); // (0.8 + 0.6) / 2
         assert_eq!(merged.contexts.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_deterministic_learning_is_reproducible() {
+        let dir = crate::golden_tests::polyglot_copy();
+        let fixture = dir.path().to_string_lossy();
+
+        let mut runs = Vec::new();
+        for _ in 0..2 {
+            let mut engine = PatternLearningEngine::new();
+            engine.set_deterministic(true);
+            let patterns = unsafe { engine.learn_from_codebase(fixture.to_string()).await }.unwrap();
+            assert!(engine.get_learning_metrics().last_learning_timestamp.is_none());
+            runs.push(serde_json::to_string(&patterns).unwrap());
+        }
+
+        assert_eq!(runs[0], runs[1]);
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_mode_skips_time_patterns() {
+        let mut engine = PatternLearningEngine::new();
+        engine.set_deterministic(true);
+
+        engine
+            .learn_from_change_pattern("modification", Some("src/app.ts"), Some("typescript"))
+            .await
+            .unwrap();
+
+        assert!(engine.get_activity_counters(Some("change_time_hour".to_string())).is_empty());
+        assert_eq!(engine.activity.get("change", "modification"), 1);
+
+        engine.update_pattern_frequency("naming_function_camelCase", 1).await.unwrap();
+        assert!(engine.has_pattern(&format!(
+            "learned_naming_function_camelCase_{:016x}",
+            PatternLearningEngine::stable_hash("naming_function_camelCase")
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_activity_is_counted_apart_from_patterns() {
+        let mut engine = PatternLearningEngine::new();
+        for _ in 0..3 {
+            engine
+                .learn_from_change_pattern("modification", Some("src/app.ts"), Some("typescript"))
+                .await
+                .unwrap();
+            engine.update_language_usage_patterns("src/app.js", "javascript").await.unwrap();
+            engine.update_pattern_frequency("naming_function_camelCase", 1).await.unwrap();
+        }
+
+        // Only the coding pattern is returned to agents, once, with its frequency aggregated
+        let patterns = engine.get_learned_patterns();
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].pattern_type, "naming_function_camelCase");
+        assert_eq!(patterns[0].frequency, 3);
+        assert_eq!(engine.get_patterns_in_namespace("naming".to_string()).unwrap().len(), 1);
+        assert!(engine.get_patterns_in_namespace("activity".to_string()).unwrap().is_empty());
+        let error = engine.get_patterns_in_namespace("telemetry".to_string()).unwrap_err();
+        assert!(error.to_string().contains("[INVALID_ARGUMENT]"));
+
+        assert_eq!(engine.activity.get("change", "modification"), 3);
+        assert_eq!(engine.activity.get("directory_language", "src_javascript"), 3);
+        let hours = engine.get_activity_counters(Some("change_time_hour".to_string()));
+        assert_eq!(hours.iter().map(|c| c.count).sum::<u32>(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_user_rules_evaluated_alongside_learned_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("app.ts"),
+            "function loadUser() {\n  console.log('loading');\n}\n",
+        )
+        .unwrap();
+
+        let mut engine = PatternLearningEngine::new();
+        let loaded = engine
+            .load_rules(
+                r#"[{"name": "no-console", "regex": "console\\.log", "languages": ["typescript"], "severity": "error", "mode": "forbid"}]"#
+                    .to_string(),
+            )
+            .unwrap();
+        assert_eq!(loaded, 1);
+
+        let patterns = unsafe {
+            engine
+                .learn_from_codebase(temp_dir.path().to_string_lossy().to_string())
+                .await
+        }
+        .unwrap();
+        let rule_pattern = patterns.iter().find(|p| p.id == "rule_no-console").unwrap();
+        assert_eq!(rule_pattern.pattern_type, "custom_rule_violation");
+        assert_eq!(rule_pattern.frequency, 1);
+        assert!(engine.has_pattern("rule_no-console"));
+
+        let analysis = engine.analyze_patterns(Vec::new()).unwrap();
+        assert!(analysis.violations.iter().any(|v| v.contains("no-console")));
+
+        let single = engine.evaluate_rules("other.ts".to_string(), "console.log(1)".to_string());
+        assert_eq!(single.len(), 1);
+        assert!(engine.evaluate_rules("other.py".to_string(), "console.log(1)".to_string()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_explain_pattern_reports_evidence_trail() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["user_service.ts", "order_service.ts", "cart_service.ts"] {
+            fs::write(
+                temp_dir.path().join(name),
+                "function getUser() {}\nfunction getOrder() {}\nfunction getCart() {}\nfunction saveItem() {}\n",
+            )
+            .unwrap();
+        }
+
+        let mut engine = PatternLearningEngine::new();
+        engine.set_deterministic(true);
+        let patterns = unsafe {
+            engine
+                .learn_from_codebase(temp_dir.path().to_string_lossy().to_string())
+                .await
+        }
+        .unwrap();
+        assert!(!patterns.is_empty());
+
+        for pattern in &patterns {
+            let explanation = engine.explain_pattern(pattern.id.clone()).unwrap();
+            assert_eq!(explanation.pattern_id, pattern.id);
+            assert_ne!(explanation.analyzer, "unknown");
+            assert_eq!(explanation.confidence_history.len(), 1);
+            assert!(explanation.confidence_history[0].timestamp.is_none());
+            let example_total: u32 = explanation.contributing_files.iter().map(|f| f.example_count).sum();
+            assert_eq!(example_total as usize, pattern.examples.len());
+        }
+
+        assert!(engine.explain_pattern("missing".to_string()).is_none());
+    }
+
+    #[test]
+    fn test_pattern_relevance_calculation() {
+        let pattern = Pattern {
+            id: "test".to_string(),
+            pattern_type: "function".to_string(),
+            description: "Function pattern for JavaScript development".to_string(),
+            frequency: 10,
+            confidence: 0.8,
+            examples: vec![crate::patterns::types::PatternExample {
+                code: "function getUser() {}".to_string(),
+                file_path: "src/users/service.js".to_string(),
+                line_range: crate::types::LineRange { start: 1, end: 1 },
+            }],
+            contexts: vec!["javascript".to_string()],
+        };
+
+        let keywords = vec!["function".to_string(), "javascript".to_string()];
+        assert!(PatternLearningEngine::pattern_relevance(&pattern, &keywords, None) > 0.5);
+
+        // Examples next to the current file make up for missing keywords
+        let unrelated = PatternLearningEngine::pattern_relevance(&pattern, &[], None);
+        let in_module = PatternLearningEngine::pattern_relevance(&pattern, &[], Some(std::path::Path::new("src/users")));
+        assert!((in_module - unrelated - 0.2).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_explain_pattern_tracks_confidence_adjustments() {
+        let mut engine = PatternLearningEngine::new();
+        engine.insert_pattern(
+            "factory".to_string(),
+            Pattern {
+                id: "factory".to_string(),
+                pattern_type: "implementation_factory".to_string(),
+                description: "Factory pattern".to_string(),
+                frequency: 4,
+                confidence: 0.6,
+                examples: vec![],
+                contexts: vec!["factory".to_string()],
+            },
+        );
+
+        engine
+            .boost_related_pattern_confidence("factory", 0.1)
+            .await
+            .unwrap();
+
+        let explanation = engine.explain_pattern("factory".to_string()).unwrap();
+        assert_eq!(explanation.analyzer, "external");
+        assert_eq!(explanation.confidence_history.len(), 2);
+        assert!((explanation.confidence_history[1].confidence - 0.7).abs() < 1e-9);
+        assert!(explanation.confidence_history[1].reason.contains("factory"));
+        assert_eq!(engine.get_pattern_evolution("factory").unwrap().confidence_history.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_moved_file_keeps_its_pattern_contributions() {
+        let mut engine = PatternLearningEngine::new();
+        engine.set_deterministic(true);
+        engine.insert_pattern(
+            "ts_functions".to_string(),
+            Pattern {
+                id: "ts_functions".to_string(),
+                pattern_type: "naming_functions".to_string(),
+                description: "camelCase functions".to_string(),
+                frequency: 1,
+                confidence: 0.8,
+                examples: vec![crate::patterns::types::PatternExample {
+                    code: "loadUser".to_string(),
+                    file_path: "src/users.ts".to_string(),
+                    line_range: crate::types::LineRange { start: 1, end: 3 },
+                }],
+                contexts: vec!["ts".to_string()],
+            },
+        );
+        let content = "export function loadUser(id) {\n  return db.find(id);\n}\n";
+        let event = |change_type: &str, path: &str, content: Option<&str>| {
+            serde_json::json!({"type": change_type, "path": path, "content": content, "language": "typescript"})
+                .to_string()
+        };
+        let confidence = |engine: &PatternLearningEngine| engine.get_pattern("ts_functions").unwrap().confidence;
+
+        unsafe { engine.update_from_change(event("change", "src/users.ts", Some(content))).await }.unwrap();
+        let before = confidence(&engine);
+
+        // The deletion is held back, then matched by the create of the same content
+        unsafe { engine.update_from_change(event("unlink", "src/users.ts", None)).await }.unwrap();
+        assert_eq!(confidence(&engine), before);
+        unsafe { engine.update_from_change(event("add", "lib/users.ts", Some(content))).await }.unwrap();
+
+        let pattern = engine.get_pattern("ts_functions").unwrap();
+        assert_eq!(pattern.examples[0].file_path, "lib/users.ts");
+        assert_eq!(pattern.confidence, before);
+        assert!(engine.activity.get("change", "rename") > 0);
+        assert_eq!(unsafe { engine.flush_pending_deletions().await }.unwrap(), 0);
+
+        // A deletion with no matching create decays related patterns once flushed
+        unsafe { engine.update_from_change(event("change", "src/orders.ts", Some("const orders = [];\n"))).await }.unwrap();
+        unsafe { engine.update_from_change(event("unlink", "src/orders.ts", None)).await }.unwrap();
+        assert_eq!(unsafe { engine.flush_pending_deletions().await }.unwrap(), 1);
+        assert!(confidence(&engine) < before);
+    }
+
+    #[tokio::test]
+    async fn test_pinned_patterns_are_curated_by_hand() {
+        let mut engine = PatternLearningEngine::new();
+        engine.set_deterministic(true);
+        let pattern = |id: &str, confidence: f64| Pattern {
+            id: id.to_string(),
+            pattern_type: "naming_functions".to_string(),
+            description: "camelCase functions".to_string(),
+            frequency: 4,
+            confidence,
+            examples: Vec::new(),
+            contexts: vec!["ts".to_string()],
+        };
+        engine.insert_pattern("ts_functions".to_string(), pattern("ts_functions", 0.6));
+        engine.insert_pattern("bogus".to_string(), pattern("bogus", 0.7));
+
+        let changes = PatternChanges { description: Some("Functions are camelCase".to_string()), confidence: Some(0.9), contexts: None };
+        let updated = engine.update_pattern("ts_functions".to_string(), changes).unwrap();
+        assert_eq!(updated.description, "Functions are camelCase");
+        engine.pin_pattern("ts_functions".to_string()).unwrap();
+        assert!(engine.pin_pattern("missing".to_string()).unwrap_err().to_string().contains("Unknown pattern"));
+
+        // Relearning and decay leave the pinned pattern as curated
+        let data = serde_json::json!({"concepts": [], "patterns": [{"id": "ts_functions", "type": "naming_functions", "description": "relearned", "confidence": 0.2}]});
+        unsafe { engine.learn_from_analysis(data.to_string()).await }.unwrap();
+        engine.boost_related_pattern_confidence("naming", 0.05).await.unwrap();
+        let pinned = engine.get_pattern("ts_functions").unwrap();
+        assert_eq!((pinned.description.as_str(), pinned.confidence), ("Functions are camelCase", 0.9));
+
+        // A pinned pattern is not merged with new candidates of the same kind
+        let consolidated = engine.validate_and_consolidate_patterns(vec![pattern("ts_functions", 0.8), pattern("ts_functions_2", 0.8)]).unwrap();
+        assert_eq!(consolidated.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(), vec!["ts_functions_2"]);
+
+        let filter = PatternFilter { pinned: Some(true), ..Default::default() };
+        let listed = engine.list_patterns(Some(filter.clone())).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].pattern.id, "ts_functions");
+
+        // Saved and reloaded still pinned and as curated
+        let dir = TempDir::new().unwrap();
+        let project = dir.path().to_string_lossy().to_string();
+        engine.save_learning_state(project.clone()).unwrap();
+        let mut reloaded = PatternLearningEngine::new();
+        assert!(reloaded.load_learning_state(project).unwrap());
+        let listed = reloaded.list_patterns(Some(filter)).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].pattern.description, "Functions are camelCase");
+
+        assert!(engine.delete_pattern("bogus".to_string()));
+        assert!(!engine.delete_pattern("bogus".to_string()));
+        assert!(engine.unpin_pattern("ts_functions".to_string()));
+        assert_eq!(engine.list_patterns(None).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_learn_from_changes_uses_concept_diff() {
+        let mut engine = PatternLearningEngine::new();
+        engine.set_deterministic(true);
+
+        let old_code = "function getUser(id) {\n  return db.find(id);\n}\n";
+        let new_code = "function fetchUser(id) {\n  return db.find(id);\n}\n\nfunction deleteUser(id) {\n  db.remove(id);\n}\n";
+        let patterns = unsafe {
+            engine
+                .learn_from_changes(
+                    old_code.to_string(),
+                    new_code.to_string(),
+                    "users.js".to_string(),
+                    "javascript".to_string(),
+                )
+                .await
+        }
+        .unwrap();
+
+        let renamed = patterns
+            .iter()
+            .find(|p| p.pattern_type == "structural_renamed")
+            .unwrap();
+        assert_eq!(renamed.examples[0].code, "getUser -> fetchUser");
+        let added = patterns
+            .iter()
+            .find(|p| p.pattern_type == "structural_added")
+            .unwrap();
+        assert_eq!(added.examples[0].code, "deleteUser");
+        assert_eq!(added.examples[0].line_range.start, 5);
+        assert!(engine.activity.get("change", "addition") > 0);
+    }
+
+    #[tokio::test]
+    async fn test_file_limit_samples_every_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join(".in-memoria")).unwrap();
+        fs::write(
+            temp_dir.path().join(".in-memoria/config.json"),
+            r#"{"analysis": {"maxFiles": 4}}"#,
+        )
+        .unwrap();
+        for dir in ["alpha", "beta"] {
+            fs::create_dir_all(temp_dir.path().join(dir)).unwrap();
+        }
+        for i in 0..10 {
+            fs::write(
+                temp_dir.path().join(format!("alpha/file{}.ts", i)),
+                format!("function alpha{}() {{}}\n", i),
+            )
+            .unwrap();
+        }
+        fs::write(temp_dir.path().join("beta/tool.py"), "def beta_tool():\n    pass\n").unwrap();
+
+        let mut engine = PatternLearningEngine::new();
+        unsafe {
+            engine
+                .learn_from_codebase(temp_dir.path().to_string_lossy().to_string())
+                .await
+        }
+        .unwrap();
+
+        let report = engine.get_sampling_report().unwrap();
+        assert_eq!(report.total_files, 11);
+        assert_eq!(report.sampled_files, 4);
+        assert_eq!(report.skipped_files, 7);
+        let beta = report.strata.iter().find(|s| s.directory == "beta").unwrap();
+        assert_eq!(beta.sampled_files, 1);
+    }
+
+    #[tokio::test]
+    async fn test_project_config_filters_learning_input() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join(".in-memoria")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("generated")).unwrap();
+        fs::write(
+            temp_dir.path().join(".in-memoria/config.json"),
+            r#"{"project": {"languages": ["typescript"]}, "watching": {"patterns": ["**/*.ts"], "ignored": ["**/generated/**"]}}"#,
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("app.ts"), "console.log('app');\n").unwrap();
+        fs::write(temp_dir.path().join("generated/api.ts"), "console.log('api');\n").unwrap();
+        fs::write(temp_dir.path().join("script.py"), "print('script')\n").unwrap();
+
+        let mut engine = PatternLearningEngine::new();
+        engine
+            .load_rules(r#"[{"name": "no-print", "regex": "console\\.log|print\\(", "mode": "forbid"}]"#.to_string())
+            .unwrap();
+
+        let patterns = unsafe {
+            engine
+                .learn_from_codebase(temp_dir.path().to_string_lossy().to_string())
+                .await
+        }
+        .unwrap();
+        let rule_pattern = patterns.iter().find(|p| p.id == "rule_no-print").unwrap();
+        assert_eq!(rule_pattern.frequency, 1);
+        assert!(rule_pattern.examples[0].file_path.ends_with("app.ts"));
+    }
+
+    #[tokio::test]
+    async fn test_structured_violations_respect_disabled_rules() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("app.ts"),
+            "console.log('a');\n// in-memoria-disable-next-line no-console\nconsole.log('b');\neval(code);\n",
+        )
+        .unwrap();
+
+        let mut engine = PatternLearningEngine::new();
+        engine
+            .load_rules(
+                r#"[{"name": "no-console", "regex": "console\\.log", "mode": "forbid", "severity": "warning"},
+                    {"name": "no-eval", "regex": "eval\\(", "mode": "forbid", "severity": "error"}]"#
+                    .to_string(),
+            )
+            .unwrap();
+        let path = temp_dir.path().to_string_lossy().to_string();
+        unsafe { engine.learn_from_codebase(path.clone()).await }.unwrap();
+
+        let violations = engine.get_violations();
+        let found: Vec<(&str, &str, u32)> = violations
+            .iter()
+            .map(|v| (v.rule_id.as_str(), v.severity.as_str(), v.line_range.start))
+            .collect();
+        assert_eq!(found, vec![("no-console", "warning", 1), ("no-eval", "error", 4)]);
+        assert!(violations[1].message.contains("no-eval"));
+
+        fs::create_dir_all(temp_dir.path().join(".in-memoria")).unwrap();
+        fs::write(
+            temp_dir.path().join(".in-memoria/config.json"),
+            r#"{"rules": {"disabled": ["no-console"]}}"#,
+        )
+        .unwrap();
+        engine.load_project_config(path).unwrap();
+
+        let rule_ids: Vec<String> = engine.get_violations().into_iter().map(|v| v.rule_id).collect();
+        assert_eq!(rule_ids, vec!["no-eval".to_string()]);
+        let analysis = engine.analyze_patterns(Vec::new()).unwrap();
+        assert!(!analysis.violations.iter().any(|v| v.contains("no-console")));
+    }
+
+    #[test]
+    fn test_complexity_budgets_with_directory_overrides() {
+        use crate::patterns::budgets::{ComplexityBudget, DirectoryBudget};
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("src/legacy")).unwrap();
+        let branchy = "export function route(a, b) {\n  if (a) { return 1; }\n  if (b) { return 2; }\n  return a || b;\n}\n";
+        fs::write(temp_dir.path().join("src/app.ts"), branchy).unwrap();
+        fs::write(temp_dir.path().join("src/legacy/old.ts"), branchy).unwrap();
+        let path = temp_dir.path().to_string_lossy().to_string();
+
+        let mut engine = PatternLearningEngine::new();
+        assert!(engine.check_complexity_budgets(path.clone()).is_err());
+        engine
+            .set_complexity_thresholds(ComplexityThresholds {
+                defaults: ComplexityBudget { max_cyclomatic: Some(3), ..Default::default() },
+                overrides: Some(vec![DirectoryBudget {
+                    directory: "src/legacy".to_string(),
+                    budget: ComplexityBudget { max_cyclomatic: Some(10), ..Default::default() },
+                }]),
+                severity: None,
+            })
+            .unwrap();
+
+        let violations = engine.check_complexity_budgets(path.clone()).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].file_path.ends_with("app.ts"));
+        assert_eq!(violations[0].rule_id, "max_cyclomatic_complexity");
+        assert_eq!(violations[0].message, "'route' has cyclomatic complexity 4 (budget 3)");
+
+        fs::create_dir_all(temp_dir.path().join(".in-memoria")).unwrap();
+        fs::write(
+            temp_dir.path().join(".in-memoria/config.json"),
+            r#"{"rules": {"disabled": ["max_cyclomatic_complexity"]}}"#,
+        )
+        .unwrap();
+        assert!(engine.check_complexity_budgets(path).unwrap().is_empty());
+        let invalid = ComplexityThresholds { severity: Some("fatal".to_string()), ..Default::default() };
+        assert!(engine.set_complexity_thresholds(invalid).is_err());
+    }
+
+    #[test]
+    fn test_compare_refs_reports_drift_between_branches() {
+        use std::process::Command;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .arg("-C")
+                .arg(root)
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+                .is_ok_and(|output| output.status.success())
+        };
+        if !git(&["init", "-q", "-b", "main"]) {
+            return; // git not available
+        }
+        fs::write(root.join("app.ts"), "export function start() {\n  console.log('start');\n}\n").unwrap();
+        fs::write(root.join("README.md"), "# App\n").unwrap();
+        assert!(git(&["add", "-A"]) && git(&["commit", "-q", "-m", "base"]));
+        assert!(git(&["checkout", "-q", "-b", "feature"]));
+        fs::write(
+            root.join("app.ts"),
+            "import './setup';\nexport function start() {\n  console.log('start');\n}\n\
+             export function route(a, b) {\n  if (a) { console.log(a); }\n  if (b) { return 2; }\n  return 0;\n}\n",
+        )
+        .unwrap();
+        fs::write(root.join("README.md"), "# App\n\nNow routes.\n").unwrap();
+        assert!(git(&["add", "-A"]) && git(&["commit", "-q", "-m", "feature"]));
+        assert!(git(&["checkout", "-q", "main"]));
+
+        let mut engine = PatternLearningEngine::new();
+        engine
+            .load_rules(r#"[{"name": "no-console", "regex": "console\\.log", "mode": "forbid"}]"#.to_string())
+            .unwrap();
+        let path = root.to_string_lossy().to_string();
+        let comparison = engine.compare_refs(path.clone(), "main".to_string(), "feature".to_string()).unwrap();
+
+        assert_eq!(comparison.files_modified, vec!["README.md".to_string(), "app.ts".to_string()]);
+        assert!(comparison.files_added.is_empty() && comparison.files_removed.is_empty());
+        assert!(comparison.concept_diffs.iter().any(|diff| diff.added.iter().any(|c| c.name == "route")));
+        assert!(comparison.cyclomatic_delta > 0);
+        assert_eq!(comparison.complexity_changes[0].function, "route");
+        assert_eq!(comparison.complexity_changes[0].base_cyclomatic, None);
+        // The existing console.log moved down a line and is not reported again
+        let introduced: Vec<(&str, u32)> = comparison
+            .introduced_violations
+            .iter()
+            .map(|v| (v.rule_id.as_str(), v.line_range.start))
+            .collect();
+        assert_eq!(introduced, vec![("no-console", 6)]);
+        assert!(comparison.resolved_violations.is_empty());
+
+        assert!(engine.compare_refs(path, "main".to_string(), "missing".to_string()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_review_diff_comments_on_added_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("users.ts"),
+            "export function getUserName() {}\nexport function setUserName() {}\nexport function listUsers() {}\n",
+        )
+        .unwrap();
+        let mut engine = PatternLearningEngine::new();
+        engine
+            .load_rules(r#"[{"name": "no-console", "regex": "console\\.log", "mode": "forbid"}]"#.to_string())
+            .unwrap();
+        unsafe { engine.learn_from_codebase(temp_dir.path().to_string_lossy().to_string()).await }.unwrap();
+
+        let diff = "\
+diff --git a/src/app.ts b/src/app.ts
+--- a/src/app.ts
++++ b/src/app.ts
+@@ -1,3 +1,7 @@
+ export function start() {
+   console.log('start');
+ }
++
++export function load_profile() {
++  console.log('loading');
++}
+diff --git a/README.md b/README.md
+--- a/README.md
++++ b/README.md
+@@ -1 +1 @@
+-# App
++# App console.log
+";
+        let review = engine.review_diff(diff.to_string(), None).unwrap();
+
+        assert_eq!(review.files_reviewed, vec!["src/app.ts".to_string()]);
+        assert_eq!(review.hunks.len(), 1);
+        assert!(review.hunks[0].concepts.contains(&"load_profile".to_string()));
+        // The console.log on the unchanged context line is not commented on
+        let comments: Vec<(u32, &str, Option<&str>)> = review
+            .comments
+            .iter()
+            .map(|c| (c.line, c.rule_id.as_str(), c.concept.as_deref()))
+            .collect();
+        assert_eq!(
+            comments,
+            vec![(5, "naming_convention", Some("load_profile")), (6, "no-console", Some("load_profile"))]
+        );
+        assert_eq!(review.comments[0].suggestion.as_deref(), Some("Rename 'load_profile' to 'loadProfile'"));
+
+        assert!(engine.review_diff("not a diff".to_string(), None).is_err());
+    }
+
+    #[test]
+    fn test_naming_consistency_over_concept_store() {
+        let concepts = vec![
+            create_test_concept("user_id", "variable", "src/user.rs"),
+            create_test_concept("user_id", "variable", "db/schema.sql"),
+            create_test_concept("userId", "variable", "web/user.ts"),
+        ];
+
+        let mut engine = PatternLearningEngine::new();
+        let violations = engine.check_naming_consistency(concepts.clone());
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().all(|v| v.rule_id == "naming_consistency_user_id"));
+        assert!(violations[0].message.contains("user_id (rust, sql)"));
+
+        engine.project_config = Some(AnalysisConfig {
+            disabled_rules: vec!["naming_consistency_user_id".to_string()],
+            ..AnalysisConfig::default()
+        });
+        assert!(engine.check_naming_consistency(concepts).is_empty());
+    }
+
+    #[test]
+    fn test_prediction_uses_current_file_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let module = temp_dir.path().join("products");
+        fs::create_dir_all(&module).unwrap();
+        let current_file = module.join("ProductController.ts");
+        fs::write(
+            &current_file,
+            "export class ProductController {\n  constructor(private service: ProductService) {}\n}\n",
+        )
+        .unwrap();
+
+        let mut engine = PatternLearningEngine::new();
+        engine.insert_pattern(
+            "structural_MVC".to_string(),
+            Pattern {
+                id: "structural_MVC".to_string(),
+                pattern_type: "structural".to_string(),
+                description: "MVC architecture".to_string(),
+                frequency: 3,
+                confidence: 0.8,
+                examples: vec![crate::patterns::types::PatternExample {
+                    code: "class ProductRepository".to_string(),
+                    file_path: module.join("ProductRepository.ts").to_string_lossy().to_string(),
+                    line_range: crate::types::LineRange { start: 1, end: 1 },
+                }],
+                contexts: vec![],
+            },
+        );
+
+        let surrounding = engine.resolve_surrounding_code(
+            Some(current_file.to_string_lossy().to_string()),
+            Some("const repo = new ProductRepository();".to_string()),
+        );
+        assert_eq!(surrounding.patterns.len(), 1);
+
+        let prediction = engine
+            .predict_approach_in_context(
+                "Add a form to edit the product price".to_string(),
+                None,
+                Some(current_file.to_string_lossy().to_string()),
+                None,
+            )
+            .unwrap();
+        assert!(prediction.reasoning.contains("mvc"));
+
+        let unrelated = engine.resolve_surrounding_code(Some("/elsewhere/index.ts".to_string()), None);
+        assert!(unrelated.patterns.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_requests_fail_before_learning() {
+        let mut engine = PatternLearningEngine::new();
+
+        let missing = unsafe { engine.learn_from_codebase("/no/such/project".to_string()).await };
+        assert!(missing.unwrap_err().to_string().contains("[PATH_NOT_FOUND]"));
+
+        let malformed = unsafe { engine.update_from_change("{\"type\": \"add\"".to_string()).await };
+        assert!(malformed.unwrap_err().to_string().contains("[INVALID_JSON]"));
+        let untyped = unsafe { engine.update_from_change(r#"{"path": "a.ts"}"#.to_string()).await };
+        assert!(untyped.unwrap_err().to_string().contains("[SCHEMA_MISMATCH]"));
+
+        let wrong_concepts = unsafe { engine.learn_from_analysis(r#"{"concepts": [42]}"#.to_string()).await };
+        assert!(wrong_concepts.unwrap_err().to_string().contains("[SCHEMA_MISMATCH]"));
+        assert!(engine.get_learned_patterns().is_empty());
+    }
+
+    #[test]
+    fn test_learning_state_persists_approaches() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().to_string_lossy().to_string();
+
+        let mut engine = PatternLearningEngine::new();
+        assert!(!engine.load_learning_state(path.clone()).unwrap());
+        engine
+            .approach_predictor
+            .learn_from_approaches(
+                r#"[{"problem": "paginate the orders endpoint", "approach": "Cursor pagination on the orders query",
+                     "success": 0.85, "complexity": "low", "domain": "api"}]"#,
+            )
+            .unwrap();
+        engine.insert_pattern(
+            "naming_function_camelCase".to_string(),
+            Pattern {
+                id: "naming_function_camelCase".to_string(),
+                pattern_type: "naming".to_string(),
+                description: "Functions use camelCase".to_string(),
+                frequency: 5,
+                confidence: 0.8,
+                examples: vec![],
+                contexts: vec!["function".to_string()],
+            },
+        );
+        assert_eq!(engine.save_learning_state(path.clone()).unwrap(), 1);
+
+        let mut restarted = PatternLearningEngine::new();
+        assert!(restarted.load_learning_state(path.clone()).unwrap());
+        assert!(restarted.has_pattern("naming_function_camelCase"));
+        let stored = restarted.query_approaches(ApproachQuery {
+            domain: Some("api".to_string()),
+            project_path: Some(path),
+            ..Default::default()
+        });
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].approach_taken, "Cursor pagination on the orders query");
+
+        let prediction = restarted
+            .predict_approach("paginate the orders endpoint".to_string(), None)
+            .unwrap();
+        assert_eq!(prediction.approach, "Cursor pagination on the orders query");
+    }
+
+    #[test]
+    fn test_relearn_tombstones_removed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().to_string_lossy().to_string();
+        fs::write(temp_dir.path().join("kept.ts"), "export function getUser() {}").unwrap();
+        fs::write(temp_dir.path().join("gone.ts"), "export function getUser() {}").unwrap();
+        let pattern = |id: &str, frequency: u32, files: &[&str]| Pattern {
+            id: id.to_string(),
+            pattern_type: "naming".to_string(),
+            description: id.to_string(),
+            frequency,
+            confidence: 0.8,
+            examples: files
+                .iter()
+                .map(|file_path| crate::patterns::types::PatternExample {
+                    code: "function getUser() {}".to_string(),
+                    file_path: file_path.to_string(),
+                    line_range: crate::types::LineRange { start: 1, end: 1 },
+                })
+                .collect(),
+            contexts: vec![],
+        };
+
+        let mut engine = PatternLearningEngine::new();
+        engine.insert_pattern("mixed".to_string(), pattern("mixed", 5, &["kept.ts", "gone.ts"]));
+        engine.insert_pattern("only_gone".to_string(), pattern("only_gone", 3, &["gone.ts", "gone.ts"]));
+        engine.insert_pattern("pinned".to_string(), pattern("pinned", 2, &["gone.ts"]));
+        engine.insert_pattern("spread".to_string(), pattern("spread", 2, &["multiple_files", "never.ts"]));
+        engine.pin_pattern("pinned".to_string()).unwrap();
+
+        // Files never seen on disk are not removed ones
+        engine.bury_removed_files(&path, "2026-02-28T10:00:00Z");
+        assert!(engine.get_tombstones().is_empty());
+        fs::remove_file(temp_dir.path().join("gone.ts")).unwrap();
+
+        engine.bury_removed_files(&path, "2026-03-01T10:00:00Z");
+        // Relearning again subtracts nothing more and keeps the first time
+        engine.bury_removed_files(&path, "2026-03-02T10:00:00Z");
+        let frequency = |engine: &PatternLearningEngine, id: &str| engine.get_pattern(id).map(|p| p.frequency);
+        assert_eq!(frequency(&engine, "mixed"), Some(4));
+        assert_eq!(frequency(&engine, "only_gone"), Some(0));
+        assert_eq!(frequency(&engine, "pinned"), Some(2));
+        assert_eq!(frequency(&engine, "spread"), Some(2));
+        let tombstones = engine.get_tombstones();
+        assert_eq!(tombstones.len(), 1);
+        assert_eq!(tombstones[0].file_path, "gone.ts");
+        assert_eq!(tombstones[0].removed_at, "2026-03-01T10:00:00Z");
+        assert_eq!(tombstones[0].ids, vec!["mixed", "only_gone"]);
+
+        engine.save_learning_state(path.clone()).unwrap();
+        let mut restarted = PatternLearningEngine::new();
+        restarted.load_learning_state(path.clone()).unwrap();
+        assert_eq!(restarted.get_tombstones(), tombstones);
+
+        // A week later the pattern left without occurrences goes with the tombstone
+        restarted.bury_removed_files(&path, "2026-03-08T10:00:00Z");
+        assert!(restarted.get_tombstones().is_empty());
+        assert!(!restarted.has_pattern("only_gone"));
+        assert!(restarted.has_pattern("mixed") && restarted.has_pattern("pinned"));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_name_extraction_never_panics(line in "\\PC{0,64}") {
+            let engine = PatternLearningEngine::new();
+            for names in [
+                engine.extract_function_names(&line),
+                engine.extract_class_names(&line),
+                engine.extract_variable_names(&line),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                proptest::prop_assert!(names.iter().all(|n| engine.is_valid_identifier(n)));
+            }
+        }
+    }
 }
+
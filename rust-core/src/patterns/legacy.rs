@@ -0,0 +1,509 @@
+//! Legacy `PatternLearner` façade
+//!
+//! The original monolithic `pattern_learning.rs` has been fully ported to the
+//! modular analyzers in this directory. This type keeps the NAPI surface that
+//! existing JavaScript consumers depend on and delegates to `PatternLearningEngine`.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::patterns::learning::PatternLearningEngine;
+use crate::types::validation;
+use crate::patterns::types::*;
+use crate::patterns::{ImplementationPatternAnalyzer, NamingPatternAnalyzer, StructuralPatternAnalyzer};
+
+/// Legacy PatternLearner for backwards compatibility
+#[derive(Default)]
+#[cfg_attr(feature = "napi-bindings", napi)]
+pub struct PatternLearner {
+    engine: PatternLearningEngine,
+}
+
+#[cfg_attr(feature = "napi-bindings", napi)]
+impl PatternLearner {
+    #[cfg_attr(feature = "napi-bindings", napi(constructor))]
+    pub fn new() -> Self {
+        PatternLearner {
+            engine: PatternLearningEngine::new(),
+        }
+    }
+
+    /// Learn patterns from an entire codebase
+    /// 
+    /// # Safety
+    /// This function is marked unsafe for NAPI compatibility. It performs file system operations
+    /// and pattern analysis that are inherently safe but marked unsafe for JavaScript interop.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async unsafe fn learn_from_codebase(&mut self, path: String) -> Result<Vec<Pattern>, crate::types::ParseError> {
+        self.engine.learn_from_codebase(path).await
+    }
+
+    /// Extract patterns from a specific path
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn extract_patterns(&self, path: String) -> Result<Vec<Pattern>, crate::types::ParseError> {
+        validation::require_directory(&path)?;
+
+        // Use the learning engine to extract patterns
+        let naming_analyzer = NamingPatternAnalyzer::new();
+        let structural_analyzer = StructuralPatternAnalyzer::new();
+        let implementation_analyzer = ImplementationPatternAnalyzer::new();
+        
+        let mut all_patterns = Vec::new();
+        
+        // Extract patterns from each analyzer
+        all_patterns.extend(naming_analyzer.extract_patterns(&path)?);
+        all_patterns.extend(structural_analyzer.extract_patterns(&path)?);
+        all_patterns.extend(implementation_analyzer.extract_patterns(&path)?);
+        
+        Ok(all_patterns)
+    }
+
+    /// Analyze file changes to identify patterns (original signature)
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn analyze_file_change(
+        &self,
+        change_data: String,
+    ) -> Result<PatternAnalysisResult, crate::types::ParseError> {
+        self.engine.analyze_file_change(change_data)
+    }
+
+    /// Find patterns relevant to a given problem description (original signature)
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn find_relevant_patterns(
+        &self,
+        problem_description: String,
+        current_file: Option<String>,
+        selected_code: Option<String>,
+    ) -> Result<Vec<Pattern>, crate::types::ParseError> {
+        self.engine
+            .find_relevant_patterns(problem_description, current_file, selected_code)
+    }
+
+    /// Predict coding approach based on problem description and context (original signature)
+    ///
+    /// The context map becomes the predictor's JSON context; `existing_patterns`
+    /// and `technologies` are read as comma-separated lists.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn predict_approach(
+        &self,
+        problem_description: String,
+        context: std::collections::HashMap<String, String>,
+    ) -> Result<ApproachPrediction, crate::types::ParseError> {
+        self.engine
+            .predict_approach(problem_description, Self::context_json(&context))
+    }
+
+    /// Learn from analysis data
+    /// 
+    /// # Safety
+    /// This function is marked unsafe for NAPI compatibility. It performs data parsing and
+    /// learning operations that are inherently safe but marked unsafe for JavaScript interop.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async unsafe fn learn_from_analysis(&mut self, analysis_data: String) -> Result<bool, crate::types::ParseError> {
+        self.engine.learn_from_analysis(analysis_data).await
+    }
+
+    /// Update pattern learner from change data (from original implementation)
+    /// 
+    /// # Safety
+    /// This function is marked unsafe for NAPI compatibility. It performs data parsing and
+    /// pattern update operations that are inherently safe but marked unsafe for JavaScript interop.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async unsafe fn update_from_change(&mut self, change_data: String) -> Result<bool, crate::types::ParseError> {
+        self.engine.update_from_change(change_data).await
+    }
+
+    /// Enable deterministic learning (stable IDs, no wall-clock patterns, sorted output)
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn set_deterministic(&mut self, enabled: bool) {
+        self.engine.set_deterministic(enabled);
+    }
+
+    /// Get all patterns learned so far
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_learned_patterns(&self) -> Vec<Pattern> {
+        self.engine.get_learned_patterns()
+    }
+
+    /// The legacy context map as the predictor's JSON context
+    fn context_json(context: &std::collections::HashMap<String, String>) -> Option<String> {
+        if context.is_empty() {
+            return None;
+        }
+        let object: serde_json::Map<String, serde_json::Value> = context
+            .iter()
+            .map(|(key, value)| {
+                let value = match key.as_str() {
+                    "existing_patterns" | "technologies" => value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|item| !item.is_empty())
+                        .map(|item| serde_json::Value::String(item.to_string()))
+                        .collect(),
+                    _ => serde_json::Value::String(value.clone()),
+                };
+                (key.clone(), value)
+            })
+            .collect();
+        Some(serde_json::Value::Object(object).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::ApproachPredictor;
+    use crate::types::LineRange;
+
+    #[test]
+    fn test_pattern_learner_creation() {
+        let learner = PatternLearner::new();
+        assert!(learner.engine.get_learning_metrics().total_patterns_learned == 0);
+    }
+
+    #[test]
+    fn test_pattern_creation() {
+        let pattern = Pattern {
+            id: "test_pattern".to_string(),
+            pattern_type: "naming".to_string(),
+            description: "Test pattern".to_string(),
+            frequency: 5,
+            confidence: 0.8,
+            examples: vec![],
+            contexts: vec!["test".to_string()],
+        };
+
+        assert_eq!(pattern.id, "test_pattern");
+        assert_eq!(pattern.pattern_type, "naming");
+        assert_eq!(pattern.frequency, 5);
+        assert_eq!(pattern.confidence, 0.8);
+    }
+
+    #[test]
+    fn test_pattern_analysis_result() {
+        let result = PatternAnalysisResult {
+            detected: vec!["pattern1".to_string()],
+            violations: vec!["violation1".to_string()],
+            recommendations: vec!["Use consistent naming".to_string()],
+            learned: None,
+        };
+
+        assert_eq!(result.detected.len(), 1);
+        assert_eq!(result.violations.len(), 1);
+        assert_eq!(result.recommendations.len(), 1);
+        assert!(result.learned.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_extract_patterns_internal() {
+        let learner = PatternLearner::new();
+        let missing = learner.extract_patterns("/test/path".to_string()).await;
+        assert!(missing.unwrap_err().to_string().contains("[PATH_NOT_FOUND]"));
+
+        let fixture = crate::golden_tests::polyglot_copy();
+        let result = learner.extract_patterns(fixture.path().to_string_lossy().to_string()).await;
+        
+        assert!(result.is_ok());
+        let patterns = result.unwrap();
+        // Should have some patterns from the analyzers
+        assert!(!patterns.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_analyze_file_change() {
+        let learner = PatternLearner::new();
+        let change_data = r#"{
+            "type": "modify",
+            "path": "test.ts",
+            "content": "function getUser() {}\nfunction saveUser() {}\n",
+            "language": "typescript"
+        }"#.to_string();
+        
+        let result = learner.analyze_file_change(change_data).await;
+        
+        assert!(result.is_ok());
+        let analysis = result.unwrap();
+        assert!(analysis.detected.contains(&"naming_camelCase_function".to_string()));
+        assert!(analysis.violations.is_empty());
+        assert!(learner.analyze_file_change("{}".to_string()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_find_relevant_patterns() {
+        let mut learner = PatternLearner::new();
+        
+        // Add a test pattern to the engine first
+        let pattern = Pattern {
+            id: "test_function".to_string(),
+            pattern_type: "function".to_string(),
+            description: "Function pattern for testing".to_string(),
+            frequency: 10,
+            confidence: 0.9,
+            examples: vec![PatternExample {
+                code: "function test() {}".to_string(),
+                file_path: "test.ts".to_string(),
+                line_range: LineRange { start: 1, end: 1 },
+            }],
+            contexts: vec!["typescript".to_string()],
+        };
+        learner.engine.insert_pattern("test_function".to_string(), pattern);
+        
+        let result = learner.find_relevant_patterns(
+            "I need to create a function".to_string(),
+            Some("test.ts".to_string()),
+            None,
+        ).await;
+        
+        assert!(result.is_ok());
+        let patterns = result.unwrap();
+        assert!(!patterns.is_empty());
+        assert_eq!(patterns[0].pattern_type, "function");
+    }
+
+    #[tokio::test]
+    async fn test_predict_approach() {
+        let mut learner = PatternLearner::new();
+        
+        // Add test patterns
+        let pattern = Pattern {
+            id: "api_pattern".to_string(),
+            pattern_type: "api".to_string(),
+            description: "REST API pattern".to_string(),
+            frequency: 15,
+            confidence: 0.85,
+            examples: vec![PatternExample {
+                code: "app.get('/api', handler)".to_string(),
+                file_path: "server.js".to_string(),
+                line_range: LineRange { start: 10, end: 10 },
+            }],
+            contexts: vec!["express".to_string()],
+        };
+        learner.engine.insert_pattern("api_pattern".to_string(), pattern);
+        
+        let mut context = std::collections::HashMap::new();
+        context.insert("framework".to_string(), "express".to_string());
+        context.insert("language".to_string(), "javascript".to_string());
+        
+        let result = learner.predict_approach(
+            "Build a REST API endpoint".to_string(),
+            context,
+        ).await;
+        
+        assert!(result.is_ok());
+        let prediction = result.unwrap();
+        assert!(prediction.confidence > 0.0);
+        assert!(!prediction.reasoning.is_empty());
+        assert!(!prediction.approach.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_learn_from_analysis() {
+        let mut learner = PatternLearner::new();
+        let analysis_data = r#"{
+            "patterns": {
+                "detected": ["service_pattern", "dependency_injection"],
+                "learned": []
+            },
+            "concepts": [
+                {
+                    "name": "UserService",
+                    "type": "class",
+                    "patterns": ["service", "dependency_injection"]
+                }
+            ]
+        }"#.to_string();
+        
+        let result = unsafe { learner.learn_from_analysis(analysis_data).await };
+        assert!(result.is_ok());
+        let updated = result.unwrap();
+        assert!(updated); // Should return true since patterns were updated
+    }
+
+    #[tokio::test]
+    async fn test_update_from_change() {
+        let mut learner = PatternLearner::new();
+        
+        let change_data = r#"{
+            "type": "modify",
+            "path": "test.ts",
+            "content": "function newName() {}",
+            "language": "typescript"
+        }"#.to_string();
+        
+        let result = unsafe { learner.update_from_change(change_data).await };
+        assert!(result.is_ok());
+        assert!(result.unwrap());
+    }
+
+    #[test]
+    fn test_module_exports() {
+        // Test that all main types are accessible
+        let _naming = NamingPatternAnalyzer::new();
+        let _structural = StructuralPatternAnalyzer::new();
+        let _implementation = ImplementationPatternAnalyzer::new();
+        let _predictor = ApproachPredictor::new();
+        let _engine = PatternLearningEngine::new();
+        
+        // Test legacy compatibility
+        let _legacy = PatternLearner::new();
+    }
+
+    #[test]
+    fn test_approach_prediction_types() {
+        let prediction = ApproachPrediction {
+            approach: "Use modular architecture".to_string(),
+            confidence: 0.85,
+            reasoning: "Based on complexity analysis".to_string(),
+            patterns: vec!["modular".to_string()],
+            complexity: "medium".to_string(),
+        };
+
+        assert_eq!(prediction.approach, "Use modular architecture");
+        assert_eq!(prediction.confidence, 0.85);
+        assert!(!prediction.reasoning.is_empty());
+        assert!(!prediction.patterns.is_empty());
+        assert_eq!(prediction.complexity, "medium");
+    }
+
+    #[test]
+    fn test_pattern_example_creation() {
+        let example = PatternExample {
+            code: "function calculateTotal() { return 42; }".to_string(),
+            file_path: "utils.ts".to_string(),
+            line_range: LineRange { start: 15, end: 15 },
+        };
+
+        assert!(example.code.contains("function"));
+        assert!(example.code.contains("calculateTotal"));
+        assert_eq!(example.file_path, "utils.ts");
+        assert_eq!(example.line_range.start, 15);
+        assert_eq!(example.line_range.end, 15);
+    }
+
+    #[tokio::test]
+    async fn test_facade_matches_engine_learning() {
+        let dir = crate::golden_tests::polyglot_copy();
+        let fixture = dir.path().to_string_lossy().to_string();
+
+        let mut learner = PatternLearner::new();
+        learner.set_deterministic(true);
+        let via_facade = unsafe { learner.learn_from_codebase(fixture.clone()).await }.unwrap();
+
+        let mut engine = PatternLearningEngine::new();
+        engine.set_deterministic(true);
+        let via_engine = unsafe { engine.learn_from_codebase(fixture).await }.unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&via_facade).unwrap(),
+            serde_json::to_string(&via_engine).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_string(&learner.get_learned_patterns()).unwrap(),
+            serde_json::to_string(&engine.get_learned_patterns()).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_facade_matches_engine_analysis_update() {
+        let analysis = r#"{"patterns": [{"id": "p1", "type": "naming", "description": "camelCase functions", "frequency": 4, "confidence": 0.9}]}"#;
+
+        let mut learner = PatternLearner::new();
+        let facade_result = unsafe { learner.learn_from_analysis(analysis.to_string()).await }.unwrap();
+
+        let mut engine = PatternLearningEngine::new();
+        let engine_result = unsafe { engine.learn_from_analysis(analysis.to_string()).await }.unwrap();
+
+        assert_eq!(facade_result, engine_result);
+        assert_eq!(learner.get_learned_patterns().len(), engine.get_learned_patterns().len());
+    }
+
+    #[tokio::test]
+    async fn test_facade_matches_engine_file_change_analysis() {
+        let change = r#"{"type": "modify", "path": "src/users.ts", "content": "export class UserService {\n  getUser() {}\n}\nfunction save_user() {}\n"}"#;
+
+        let learner = PatternLearner::new();
+        let engine = PatternLearningEngine::new();
+        let via_facade = learner.analyze_file_change(change.to_string()).await.unwrap();
+        let via_engine = engine.analyze_file_change(change.to_string()).unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&via_facade).unwrap(),
+            serde_json::to_string(&via_engine).unwrap()
+        );
+        assert!(!via_engine.detected.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_facade_matches_engine_relevant_patterns() {
+        let pattern = |id: &str, pattern_type: &str, description: &str, frequency: u32| Pattern {
+            id: id.to_string(),
+            pattern_type: pattern_type.to_string(),
+            description: description.to_string(),
+            frequency,
+            confidence: 0.9,
+            examples: vec![PatternExample {
+                code: "function getUser() {}".to_string(),
+                file_path: "src/users/service.ts".to_string(),
+                line_range: LineRange { start: 1, end: 1 },
+            }],
+            contexts: vec![],
+        };
+        let mut learner = PatternLearner::new();
+        let mut engine = PatternLearningEngine::new();
+        for (id, pattern_type, description, frequency) in [
+            ("user_cache", "caching", "Cache user lookups", 12),
+            ("user_repository", "repository", "Repository for user records", 30),
+            ("error_handling", "error", "Wrap errors with context", 5),
+        ] {
+            learner.engine.insert_pattern(id.to_string(), pattern(id, pattern_type, description, frequency));
+            engine.insert_pattern(id.to_string(), pattern(id, pattern_type, description, frequency));
+        }
+
+        let via_facade = learner
+            .find_relevant_patterns(
+                "Cache the user repository".to_string(),
+                Some("src/users/handler.ts".to_string()),
+                Some("getUser(id)".to_string()),
+            )
+            .await
+            .unwrap();
+        let via_engine = engine
+            .find_relevant_patterns(
+                "Cache the user repository".to_string(),
+                Some("src/users/handler.ts".to_string()),
+                Some("getUser(id)".to_string()),
+            )
+            .unwrap();
+
+        let ids = |patterns: &[Pattern]| patterns.iter().map(|p| p.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&via_facade), ids(&via_engine));
+        assert_eq!(ids(&via_engine), vec!["user_repository", "user_cache"]);
+    }
+
+    #[tokio::test]
+    async fn test_facade_matches_engine_prediction() {
+        let mut context = std::collections::HashMap::new();
+        context.insert("domain".to_string(), "ecommerce".to_string());
+        context.insert("technologies".to_string(), "express, redis".to_string());
+
+        let learner = PatternLearner::new();
+        let via_facade = learner.predict_approach("Build a REST API for orders".to_string(), context).await.unwrap();
+
+        let engine = PatternLearningEngine::new();
+        let context_json = r#"{"domain": "ecommerce", "technologies": ["express", "redis"]}"#;
+        let via_engine = engine
+            .predict_approach("Build a REST API for orders".to_string(), Some(context_json.to_string()))
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&via_facade).unwrap(),
+            serde_json::to_string(&via_engine).unwrap()
+        );
+        let without_context = learner
+            .predict_approach("Build a REST API for orders".to_string(), Default::default())
+            .await
+            .unwrap();
+        let engine_without_context = engine.predict_approach("Build a REST API for orders".to_string(), None).unwrap();
+        assert_eq!(without_context.approach, engine_without_context.approach);
+    }
+}
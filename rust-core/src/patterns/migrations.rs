@@ -0,0 +1,159 @@
+//! Upgrades for learning state written by older releases
+//!
+//! Persisted state carries a `version`. On load the raw JSON is passed through
+//! each migration from its version up to [`STATE_VERSION`], so files written by
+//! an older release keep working after an upgrade. Files from a newer release
+//! are rejected with an `UNSUPPORTED_VERSION` error instead of being misread.
+
+use crate::patterns::store::STATE_VERSION;
+use crate::patterns::taxonomy::{ActivityCounters, PatternNamespace};
+use crate::types::validation::{ErrorCode, ValidationError};
+use serde_json::{Map, Value};
+
+/// Upgrades a state object from the version it is registered under to the next
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+/// One migration per version below [`STATE_VERSION`], in order
+const MIGRATIONS: &[(u32, Migration)] = &[(0, unversioned_to_v1), (1, activity_patterns_to_counters)];
+
+/// Upgrade raw state to the current version in place, returning the version
+/// it was written with
+pub fn migrate(state: &mut Value, file: &str) -> Result<u32, ValidationError> {
+    let object = state.as_object_mut().ok_or_else(|| {
+        ValidationError::new(ErrorCode::SchemaMismatch, format!("Invalid {}: expected an object", file))
+    })?;
+
+    let from = match object.get("version") {
+        None | Some(Value::Null) => 0,
+        Some(version) => version.as_u64().map(|v| v as u32).ok_or_else(|| {
+            ValidationError::new(
+                ErrorCode::SchemaMismatch,
+                format!("Invalid {}: version must be a non-negative integer", file),
+            )
+        })?,
+    };
+
+    if from > STATE_VERSION {
+        return Err(ValidationError::new(
+            ErrorCode::UnsupportedVersion,
+            format!(
+                "{} was written by a newer release (version {}; this release reads up to {}). Upgrade In Memoria or delete the file to relearn.",
+                file, from, STATE_VERSION
+            ),
+        ));
+    }
+
+    for (version, migration) in MIGRATIONS.iter().filter(|(version, _)| *version >= from) {
+        migration(object).map_err(|reason| {
+            ValidationError::new(
+                ErrorCode::SchemaMismatch,
+                format!("Failed to upgrade {} from version {}: {}", file, version, reason),
+            )
+        })?;
+        object.insert("version".to_string(), Value::from(version + 1));
+    }
+
+    Ok(from)
+}
+
+/// Before versioning, patterns were written as the engine's map of id to pattern
+fn unversioned_to_v1(state: &mut Map<String, Value>) -> Result<(), String> {
+    if let Some(Value::Object(patterns)) = state.remove("patterns") {
+        let mut patterns: Vec<(String, Value)> = patterns.into_iter().collect();
+        patterns.sort_by(|a, b| a.0.cmp(&b.0));
+        state.insert(
+            "patterns".to_string(),
+            Value::Array(patterns.into_iter().map(|(_, pattern)| pattern).collect()),
+        );
+    }
+    if let Some(approaches) = state.get("approaches") {
+        if !approaches.is_array() {
+            return Err("approaches must be a list".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Version 1 stored activity such as `change_time_hour_14` as patterns; fold
+/// those into the activity counters
+fn activity_patterns_to_counters(state: &mut Map<String, Value>) -> Result<(), String> {
+    let mut activity: ActivityCounters = match state.remove("activity") {
+        Some(value) => serde_json::from_value(value).map_err(|e| format!("invalid activity: {}", e))?,
+        None => ActivityCounters::default(),
+    };
+
+    if let Some(Value::Array(patterns)) = state.get_mut("patterns") {
+        patterns.retain(|pattern| {
+            let pattern_type = pattern.get("pattern_type").and_then(Value::as_str).unwrap_or("");
+            if PatternNamespace::of(pattern_type) != PatternNamespace::Activity {
+                return true;
+            }
+            let frequency = pattern.get("frequency").and_then(Value::as_u64).unwrap_or(1);
+            activity.record(pattern_type, frequency.min(u32::MAX as u64) as u32);
+            false
+        });
+    }
+
+    let activity = serde_json::to_value(&activity).map_err(|e| e.to_string())?;
+    state.insert("activity".to_string(), activity);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrations_cover_every_older_version() {
+        let versions: Vec<u32> = MIGRATIONS.iter().map(|(version, _)| *version).collect();
+        assert_eq!(versions, (0..STATE_VERSION).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_unversioned_state_is_upgraded() {
+        let mut state = json!({
+            "patterns": { "b": { "id": "b" }, "a": { "id": "a" } },
+            "approaches": []
+        });
+        assert_eq!(migrate(&mut state, "learned-state.json").unwrap(), 0);
+        assert_eq!(state["version"], json!(STATE_VERSION));
+        assert_eq!(state["patterns"], json!([{ "id": "a" }, { "id": "b" }]));
+
+        let mut current = json!({ "version": STATE_VERSION, "patterns": [] });
+        assert_eq!(migrate(&mut current, "learned-state.json").unwrap(), STATE_VERSION);
+    }
+
+    #[test]
+    fn test_activity_patterns_become_counters() {
+        let pattern = |id: &str, pattern_type: &str, frequency: u32| {
+            json!({ "id": id, "pattern_type": pattern_type, "frequency": frequency })
+        };
+        let mut state = json!({
+            "version": 1,
+            "patterns": [
+                pattern("learned_change_time_hour_14_1", "change_time_hour_14", 1),
+                pattern("learned_change_time_hour_14_2", "change_time_hour_14", 2),
+                pattern("learned_directory_language_src_js_3", "directory_language_src_js", 1),
+                pattern("naming_camel", "naming", 4)
+            ]
+        });
+        assert_eq!(migrate(&mut state, "learned-state.json").unwrap(), 1);
+        assert_eq!(state["patterns"], json!([pattern("naming_camel", "naming", 4)]));
+        assert_eq!(state["activity"]["change_time_hour"]["14"], json!(3));
+        assert_eq!(state["activity"]["directory_language"]["src_js"], json!(1));
+    }
+
+    #[test]
+    fn test_future_and_malformed_versions_are_rejected() {
+        let mut future = json!({ "version": STATE_VERSION + 1 });
+        let error = migrate(&mut future, "learned-state.json").unwrap_err();
+        assert_eq!(error.code, ErrorCode::UnsupportedVersion);
+        assert!(error.message.contains("newer release"));
+
+        let mut malformed = json!({ "version": "1.0" });
+        assert_eq!(migrate(&mut malformed, "learned-state.json").unwrap_err().code, ErrorCode::SchemaMismatch);
+        let mut bad_approaches = json!({ "approaches": {} });
+        assert_eq!(migrate(&mut bad_approaches, "learned-state.json").unwrap_err().code, ErrorCode::SchemaMismatch);
+    }
+}
@@ -3,18 +3,25 @@
 #[cfg(feature = "napi-bindings")]
 use napi_derive::napi;
 
-use crate::patterns::types::{Pattern, PatternExample, NamingPattern, PatternExtractor};
+use crate::parsing::FileWalker;
+use crate::patterns::types::{Pattern, PatternExample, NamingEdit, NamingFix, NamingPattern, PatternExtractor};
 use crate::types::{ParseError, LineRange, SemanticConcept};
-use std::collections::HashMap;
-use walkdir::WalkDir;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::path::Path;
 use regex::Regex;
 
+/// Concept metadata key holding the lower-cased words of the concept's name,
+/// comma-separated, e.g. `parse,html,file` for `parseHTMLFile`
+pub const NAME_TOKENS_METADATA_KEY: &str = "name_tokens";
+
 /// Analyzer for detecting and learning naming conventions
 #[cfg_attr(feature = "napi-bindings", napi)]
 pub struct NamingPatternAnalyzer {
     patterns: HashMap<String, NamingPattern>,
     naming_rules: HashMap<String, Vec<NamingRule>>,
+    /// Languages each pattern key was learned from
+    pattern_languages: HashMap<String, HashSet<String>>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +38,7 @@ impl NamingPatternAnalyzer {
         let mut analyzer = NamingPatternAnalyzer {
             patterns: HashMap::new(),
             naming_rules: HashMap::new(),
+            pattern_languages: HashMap::new(),
         };
         analyzer.initialize_rules();
         analyzer
@@ -142,6 +150,10 @@ impl NamingPatternAnalyzer {
                             contexts: vec![self.get_context_type(&concept.concept_type)],
                             confidence: rule.confidence_weight,
                         };
+                        self.pattern_languages
+                            .entry(pattern_key.clone())
+                            .or_default()
+                            .insert(language.to_string());
                         self.patterns.insert(pattern_key, naming_pattern);
                         break;
                     }
@@ -203,6 +215,67 @@ impl NamingPatternAnalyzer {
         violations
     }
 
+    /// Suggest a rename for every concept that breaks the dominant convention
+    /// of its context, with edits for the declaration and each whole-word
+    /// reference found in `sources` (file path, content)
+    ///
+    /// Renames that would collide with an existing concept name are skipped.
+    pub fn suggest_fixes(
+        &self,
+        concepts: &[SemanticConcept],
+        language: &str,
+        sources: &[(String, String)],
+    ) -> Vec<NamingFix> {
+        let dominant_patterns = self.get_dominant_patterns(language);
+        let existing_names: HashSet<&str> = concepts.iter().map(|c| c.name.as_str()).collect();
+        let mut seen = HashSet::new();
+        let mut fixes = Vec::new();
+
+        for concept in concepts {
+            let context = self.get_context_type(&concept.concept_type);
+            let Some(pattern) = dominant_patterns.get(&context) else {
+                continue;
+            };
+            if self.matches_pattern(&concept.name, &pattern.pattern_type) {
+                continue;
+            }
+            let Some(new_name) = Self::convert_name(&concept.name, &pattern.pattern_type) else {
+                continue;
+            };
+            if new_name == concept.name
+                || existing_names.contains(new_name.as_str())
+                || !seen.insert((concept.file_path.clone(), concept.name.clone()))
+            {
+                continue;
+            }
+
+            let referenced_by = concepts
+                .iter()
+                .filter(|other| {
+                    other.id != concept.id
+                        && other
+                            .relationships
+                            .values()
+                            .any(|names| names.split(',').any(|name| name.trim() == concept.name))
+                })
+                .map(|other| other.name.clone())
+                .collect();
+
+            fixes.push(NamingFix {
+                old_name: concept.name.clone(),
+                new_name: new_name.clone(),
+                convention: pattern.pattern_type.clone(),
+                concept_type: concept.concept_type.clone(),
+                file_path: concept.file_path.clone(),
+                line_range: concept.line_range.clone(),
+                referenced_by,
+                edits: Self::reference_edits(&concept.name, &new_name, sources),
+            });
+        }
+
+        fixes
+    }
+
     /// Generate naming recommendations based on learned patterns
     pub fn generate_recommendations(&self, language: &str) -> Vec<String> {
         let mut recommendations = Vec::new();
@@ -269,7 +342,11 @@ impl NamingPatternAnalyzer {
         let mut dominant: HashMap<String, &NamingPattern> = HashMap::new();
         
         for (key, pattern) in &self.patterns {
-            if key.contains(language) || pattern.contexts.contains(&language.to_string()) {
+            let learned_for_language = self
+                .pattern_languages
+                .get(key)
+                .is_some_and(|languages| languages.contains(language));
+            if learned_for_language || key.contains(language) || pattern.contexts.contains(&language.to_string()) {
                 let parts: Vec<&str> = key.split('_').collect();
                 if parts.len() >= 2 {
                     let context = parts[parts.len() - 1];
@@ -291,6 +368,159 @@ impl NamingPatternAnalyzer {
         dominant
     }
 
+    /// Rewrite an identifier in another naming convention, keeping leading
+    /// underscores; `None` for unknown conventions
+    fn convert_name(name: &str, convention: &str) -> Option<String> {
+        let body = name.trim_start_matches('_');
+        let prefix = &name[..name.len() - body.len()];
+        let words = Self::split_words(body);
+        if words.is_empty() {
+            return None;
+        }
+
+        let capitalize = |word: &str| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect::<String>())
+                .unwrap_or_default()
+        };
+        let converted = match convention {
+            "camelCase" => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| if i == 0 { word.to_lowercase() } else { capitalize(word) })
+                .collect::<String>(),
+            "PascalCase" => words.iter().map(|word| capitalize(word)).collect::<String>(),
+            "snake_case" => words.iter().map(|word| word.to_lowercase()).collect::<Vec<_>>().join("_"),
+            "CONSTANT_CASE" | "SCREAMING_SNAKE_CASE" => {
+                words.iter().map(|word| word.to_uppercase()).collect::<Vec<_>>().join("_")
+            }
+            _ => return None,
+        };
+        Some(format!("{}{}", prefix, converted))
+    }
+
+    /// Words of an identifier in any convention: `parseHTTPResponse_v2` -> parse, HTTP, Response, v2
+    ///
+    /// Acronyms stay whole (`IOError` -> IO, Error), including a plural `s`
+    /// (`userIDs` -> user, IDs), and digits stay with the word before them
+    /// (`getHTML5Parser` -> get, HTML5, Parser).
+    pub fn split_words(name: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        for part in name.split(['_', '-', '.', '$']).filter(|p| !p.is_empty()) {
+            let chars: Vec<char> = part.chars().collect();
+            let mut current = String::new();
+            for (i, &c) in chars.iter().enumerate() {
+                let acronym_end = chars.get(i + 1).is_some_and(|next| next.is_lowercase())
+                    && !(chars[i + 1] == 's' && chars.get(i + 2).is_none_or(|after| !after.is_lowercase()));
+                let boundary = i > 0
+                    && c.is_uppercase()
+                    && (chars[i - 1].is_lowercase()
+                        || chars[i - 1].is_ascii_digit()
+                        || (chars[i - 1].is_uppercase() && acronym_end));
+                if boundary && !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+                current.push(c);
+            }
+            if !current.is_empty() {
+                words.push(current);
+            }
+        }
+        words
+    }
+
+    /// Lower-cased words of an identifier: `parseHTMLFile` -> parse, html, file
+    pub fn name_tokens(name: &str) -> Vec<String> {
+        Self::split_words(name)
+            .into_iter()
+            .map(|word| word.to_lowercase())
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .collect()
+    }
+
+    /// Words of a concept's name, from its [`NAME_TOKENS_METADATA_KEY`]
+    /// metadata when it was annotated
+    pub fn concept_tokens(concept: &SemanticConcept) -> Vec<String> {
+        match concept.metadata.get(NAME_TOKENS_METADATA_KEY) {
+            Some(tokens) => tokens.split(',').filter(|token| !token.is_empty()).map(str::to_string).collect(),
+            None => Self::name_tokens(&concept.name),
+        }
+    }
+
+    /// Store the words of each concept's name in its metadata
+    pub fn annotate_name_tokens<'a>(concepts: impl IntoIterator<Item = &'a mut SemanticConcept>) {
+        for concept in concepts {
+            let tokens = Self::name_tokens(&concept.name);
+            if !tokens.is_empty() {
+                concept.metadata.insert(NAME_TOKENS_METADATA_KEY.to_string(), tokens.join(","));
+            }
+        }
+    }
+
+    /// Convention an identifier is written in, judged by its words rather than
+    /// its characters so acronyms and digits don't make it `mixed`
+    ///
+    /// One of camelCase, PascalCase, snake_case, kebab-case, UPPER_CASE,
+    /// lowercase (a single lower-case word, which fits camelCase and
+    /// snake_case alike) or mixed. Leading and trailing `_` and `$` are
+    /// ignored, so `_privateName` is camelCase and `__init__` lowercase.
+    pub fn classify_convention(name: &str) -> &'static str {
+        let body = name.trim_matches(['_', '$']);
+        let underscore = body.contains('_');
+        let dash = body.contains('-');
+        if !body.chars().next().is_some_and(char::is_alphabetic)
+            || !body.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+            || (underscore && dash)
+        {
+            return "mixed";
+        }
+
+        let words = Self::split_words(body);
+        let all_lower = words.iter().all(|word| !word.chars().any(char::is_uppercase));
+        let all_upper = words.iter().all(|word| !word.chars().any(char::is_lowercase));
+        if dash {
+            return if all_lower { "kebab-case" } else { "mixed" };
+        }
+        if underscore {
+            return match (all_lower, all_upper) {
+                (true, _) => "snake_case",
+                (_, true) => "UPPER_CASE",
+                _ => "mixed",
+            };
+        }
+        match words.as_slice() {
+            [_] if all_lower => "lowercase",
+            // A lone capital is a type parameter, not a constant
+            [word] if all_upper && word.chars().filter(|c| c.is_alphabetic()).count() > 1 => "UPPER_CASE",
+            [first, ..] if first.starts_with(char::is_lowercase) => "camelCase",
+            _ => "PascalCase",
+        }
+    }
+
+    /// Whole-word occurrences of `old_name` in the sources, as edits to `new_name`
+    fn reference_edits(old_name: &str, new_name: &str, sources: &[(String, String)]) -> Vec<NamingEdit> {
+        let Ok(word) = Regex::new(&format!(r"\b{}\b", regex::escape(old_name))) else {
+            return Vec::new();
+        };
+        let mut edits = Vec::new();
+        for (file_path, content) in sources {
+            for (idx, line) in content.lines().enumerate() {
+                for found in word.find_iter(line) {
+                    edits.push(NamingEdit {
+                        file_path: file_path.clone(),
+                        line: idx as u32 + 1,
+                        column: line[..found.start()].chars().count() as u32 + 1,
+                        old_text: old_name.to_string(),
+                        new_text: new_name.to_string(),
+                    });
+                }
+            }
+        }
+        edits
+    }
+
     /// Check if a name matches a pattern type
     fn matches_pattern(&self, name: &str, pattern_type: &str) -> bool {
         match pattern_type {
@@ -401,37 +631,34 @@ impl PatternExtractor for NamingPatternAnalyzer {
     fn extract_patterns(&self, path: &str) -> Result<Vec<Pattern>, ParseError> {
         let mut all_patterns = Vec::new();
         
-        for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                let file_path = entry.path();
-                if let Some(extension) = file_path.extension().and_then(|s| s.to_str()) {
-                    let language = match extension.to_lowercase().as_str() {
-                        "js" | "jsx" => "javascript",
-                        "ts" | "tsx" => "typescript", 
-                        "rs" => "rust",
-                        "py" => "python",
-                        _ => continue,
-                    };
+        for file_path in FileWalker::new().extensions(&["js", "jsx", "ts", "tsx", "rs", "py"]).walk(Path::new(path)).files {
+            if let Some(extension) = file_path.extension().and_then(|s| s.to_str()) {
+                let language = match extension.to_lowercase().as_str() {
+                    "js" | "jsx" => "javascript",
+                    "ts" | "tsx" => "typescript", 
+                    "rs" => "rust",
+                    "py" => "python",
+                    _ => continue,
+                };
+                
+                if let Ok(content) = fs::read_to_string(&file_path) {
+                    let names = self.extract_names_from_code(&content, language);
                     
-                    if let Ok(content) = fs::read_to_string(file_path) {
-                        let names = self.extract_names_from_code(&content, language);
-                        
-                        for name in names {
-                            if let Some(pattern_type) = self.classify_name(&name, language) {
-                                all_patterns.push(Pattern {
-                                    id: format!("naming_{}_{}", pattern_type, name),
-                                    pattern_type: "naming".to_string(),
-                                    description: format!("{} naming pattern", pattern_type),
-                                    frequency: 1,
-                                    confidence: 0.7,
-                                    examples: vec![PatternExample {
-                                        code: name,
-                                        file_path: file_path.to_string_lossy().to_string(),
-                                        line_range: LineRange { start: 1, end: 1 },
-                                    }],
-                                    contexts: vec![language.to_string()],
-                                });
-                            }
+                    for name in names {
+                        if let Some(pattern_type) = self.classify_name(&name, language) {
+                            all_patterns.push(Pattern {
+                                id: format!("naming_{}_{}", pattern_type, name),
+                                pattern_type: "naming".to_string(),
+                                description: format!("{} naming pattern", pattern_type),
+                                frequency: 1,
+                                confidence: 0.7,
+                                examples: vec![PatternExample {
+                                    code: name,
+                                    file_path: file_path.to_string_lossy().to_string(),
+                                    line_range: LineRange { start: 1, end: 1 },
+                                }],
+                                contexts: vec![language.to_string()],
+                            });
                         }
                     }
                 }
@@ -545,6 +772,84 @@ mod tests {
         assert!(!violations.is_empty());
     }
 
+    #[test]
+    fn test_naming_fix_suggestions() {
+        let mut analyzer = NamingPatternAnalyzer::new();
+        let established = vec![
+            create_test_concept("getUserName", "function", "src/users.js"),
+            create_test_concept("setUserName", "function", "src/users.js"),
+        ];
+        analyzer.analyze_concepts(&established, "javascript").unwrap();
+
+        let mut offender = create_test_concept("load_user_profile", "function", "src/users.js");
+        offender.line_range = LineRange { start: 3, end: 5 };
+        let mut caller = create_test_concept("renderProfile", "function", "src/view.js");
+        caller.relationships.insert("calls".to_string(), "load_user_profile".to_string());
+
+        let sources = vec![
+            (
+                "src/users.js".to_string(),
+                "function getUserName() {}\n\nfunction load_user_profile(id) {\n  return load_user_profile_cache(id);\n}\n".to_string(),
+            ),
+            ("src/view.js".to_string(), "const profile = load_user_profile(1);\n".to_string()),
+        ];
+        let fixes = analyzer.suggest_fixes(&[offender, caller], "javascript", &sources);
+
+        assert_eq!(fixes.len(), 1);
+        let fix = &fixes[0];
+        assert_eq!(fix.new_name, "loadUserProfile");
+        assert_eq!(fix.convention, "camelCase");
+        assert_eq!(fix.referenced_by, vec!["renderProfile".to_string()]);
+        let edits: Vec<(&str, u32, u32)> = fix.edits.iter().map(|e| (e.file_path.as_str(), e.line, e.column)).collect();
+        assert_eq!(edits, vec![("src/users.js", 3, 10), ("src/view.js", 1, 17)]);
+    }
+
+    #[test]
+    fn test_name_conversion() {
+        assert_eq!(
+            NamingPatternAnalyzer::convert_name("parseHTTPResponse_v2", "snake_case").as_deref(),
+            Some("parse_http_response_v2")
+        );
+        assert_eq!(NamingPatternAnalyzer::convert_name("MAX_RETRIES", "camelCase").as_deref(), Some("maxRetries"));
+        assert_eq!(NamingPatternAnalyzer::convert_name("_private_name", "camelCase").as_deref(), Some("_privateName"));
+        assert_eq!(NamingPatternAnalyzer::convert_name("user_service", "PascalCase").as_deref(), Some("UserService"));
+        assert_eq!(NamingPatternAnalyzer::convert_name("retryCount", "SCREAMING_SNAKE_CASE").as_deref(), Some("RETRY_COUNT"));
+        assert_eq!(NamingPatternAnalyzer::convert_name("name", "kebab-case"), None);
+    }
+
+    #[test]
+    fn test_convention_classification_with_acronyms_and_digits() {
+        let cases = [
+            ("parseHTMLFile", "camelCase"),
+            ("userIDs", "camelCase"),
+            ("_privateName", "camelCase"),
+            ("IOError", "PascalCase"),
+            ("HTML5Parser", "PascalCase"),
+            ("T", "PascalCase"),
+            ("HTTP", "UPPER_CASE"),
+            ("MAX_RETRIES_3", "UPPER_CASE"),
+            ("utf8_decode_v2", "snake_case"),
+            ("__init__", "lowercase"),
+            ("render", "lowercase"),
+            ("data-table", "kebab-case"),
+            ("Mixed_Case", "mixed"),
+            ("snake_and-kebab", "mixed"),
+        ];
+        for (name, convention) in cases {
+            assert_eq!(NamingPatternAnalyzer::classify_convention(name), convention, "{}", name);
+        }
+
+        assert_eq!(NamingPatternAnalyzer::split_words("getHTML5Parser"), vec!["get", "HTML5", "Parser"]);
+        assert_eq!(NamingPatternAnalyzer::split_words("userIDsToFetch"), vec!["user", "IDs", "To", "Fetch"]);
+        assert_eq!(NamingPatternAnalyzer::name_tokens("IOError"), vec!["io", "error"]);
+
+        let mut concepts = [create_test_concept("parseHTMLFile", "function", "src/parse.ts")];
+        NamingPatternAnalyzer::annotate_name_tokens(concepts.iter_mut());
+        assert_eq!(concepts[0].metadata.get(NAME_TOKENS_METADATA_KEY).map(String::as_str), Some("parse,html,file"));
+        concepts[0].name = "renamedWithoutAnnotation".to_string();
+        assert_eq!(NamingPatternAnalyzer::concept_tokens(&concepts[0]), vec!["parse", "html", "file"]);
+    }
+
     #[test]
     fn test_recommendations_generation() {
         let mut analyzer = NamingPatternAnalyzer::new();
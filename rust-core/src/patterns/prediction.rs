@@ -3,10 +3,21 @@
 #[cfg(feature = "napi-bindings")]
 use napi_derive::napi;
 
-use crate::patterns::types::{ApproachPrediction, ProblemComplexity, GeneratedApproach, Pattern};
+use crate::analysis::{FrameworkInfo, TestFramework};
+use crate::patterns::keywords::KeywordExtractor;
+use crate::patterns::technologies::InstalledTechnologies;
+use crate::patterns::types::{
+    ApproachPrediction, ApproachQuery, GeneratedApproach, HistoricalApproach, Pattern, ProblemComplexity,
+};
+use crate::types::validation;
 use crate::types::{ParseError, SemanticConcept};
+use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
-use serde_json::{Value, from_str};
+
+/// Templates every predictor starts with
+const DEFAULT_TEMPLATES: &str = include_str!("../../data/approach-templates.json");
+
+const COMPLEXITY_LABELS: &[&str] = &["low", "medium", "high"];
 
 /// Predictor for suggesting coding approaches based on patterns and context
 #[cfg_attr(feature = "napi-bindings", napi)]
@@ -15,6 +26,13 @@ pub struct ApproachPredictor {
     approach_templates: HashMap<String, ApproachTemplate>,
     context_weights: HashMap<String, f64>,
     historical_approaches: Vec<HistoricalApproach>,
+    /// Project whose recorded approaches outrank generic templates and other projects
+    project_path: Option<String>,
+    /// Test frameworks of the project, most used first
+    test_frameworks: Vec<TestFramework>,
+    /// Frameworks detected in the project; until some are, no template is
+    /// penalized for technologies the project lacks
+    installed: Option<InstalledTechnologies>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,14 +49,75 @@ struct ApproachTemplate {
     patterns: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
-struct HistoricalApproach {
-    problem_description: String,
-    approach_taken: String,
-    patterns_used: Vec<String>,
-    success_rating: f64,
-    complexity: ProblemComplexity,
-    context: HashMap<String, String>,
+/// One entry of an approach template file
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApproachTemplateDefinition {
+    /// Key the template is stored under; a later definition with the same id replaces it
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Problem complexities the approach suits: 'low', 'medium' and/or 'high'
+    pub complexity: Vec<String>,
+    /// Patterns the approach depends on; confidence scales with how many are present
+    #[serde(default)]
+    pub required_patterns: Vec<String>,
+    /// Patterns that raise confidence when present
+    #[serde(default)]
+    pub preferred_patterns: Vec<String>,
+    #[serde(default)]
+    pub technologies: Vec<String>,
+    /// Base confidence, above 0 and at most 1
+    pub confidence: f64,
+}
+
+impl ApproachTemplateDefinition {
+    fn into_template(self) -> Result<ApproachTemplate, ParseError> {
+        if self.id.trim().is_empty() {
+            return Err(ParseError::from_reason("Approach template is missing an id"));
+        }
+        if self.name.trim().is_empty() {
+            return Err(ParseError::from_reason(format!("Approach template '{}' is missing a name", self.id)));
+        }
+        if self.complexity.is_empty() {
+            return Err(ParseError::from_reason(format!(
+                "Approach template '{}' must list at least one complexity",
+                self.id
+            )));
+        }
+        if let Some(label) = self.complexity.iter().find(|label| !COMPLEXITY_LABELS.contains(&label.to_lowercase().as_str())) {
+            return Err(ParseError::from_reason(format!(
+                "Approach template '{}' has unknown complexity '{}'; expected low, medium or high",
+                self.id, label
+            )));
+        }
+        if !(self.confidence > 0.0 && self.confidence <= 1.0) {
+            return Err(ParseError::from_reason(format!(
+                "Approach template '{}' needs a confidence above 0 and at most 1, got {}",
+                self.id, self.confidence
+            )));
+        }
+
+        Ok(ApproachTemplate {
+            name: self.name,
+            description: self.description,
+            complexity_suitability: self.complexity.iter().map(|label| ProblemComplexity::from_label(label)).collect(),
+            patterns: self.required_patterns.clone(),
+            required_patterns: self.required_patterns,
+            preferred_patterns: self.preferred_patterns,
+            technologies: self.technologies,
+            confidence_base: self.confidence,
+            confidence: self.confidence,
+        })
+    }
+}
+
+/// Template files may be a bare list or an object with a `templates` key
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TemplateDocument {
+    List(Vec<ApproachTemplateDefinition>),
+    Set { templates: Vec<ApproachTemplateDefinition> },
 }
 
 #[derive(Debug, Clone)]
@@ -51,6 +130,77 @@ struct ProblemContext {
     timeline: String,
     existing_patterns: Vec<String>,
     technologies: Vec<String>,
+    /// Patterns used in the module around the current file or selection
+    module_patterns: Vec<String>,
+}
+
+/// JSON context accepted by `predict_approach`
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct ContextInput {
+    domain: String,
+    scale: String,
+    performance: String,
+    maintainability: String,
+    team_size: String,
+    timeline: String,
+    existing_patterns: Vec<String>,
+    technologies: Vec<String>,
+}
+
+impl Default for ContextInput {
+    fn default() -> Self {
+        ContextInput {
+            domain: "general".to_string(),
+            scale: "medium".to_string(),
+            performance: "standard".to_string(),
+            maintainability: "high".to_string(),
+            team_size: "small".to_string(),
+            timeline: "months".to_string(),
+            existing_patterns: Vec::new(),
+            technologies: Vec::new(),
+        }
+    }
+}
+
+/// One entry of the JSON array accepted by `learn_from_approaches`
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct ApproachInput {
+    problem: String,
+    approach: String,
+    success: f64,
+    complexity: String,
+    domain: Option<String>,
+    patterns: Vec<String>,
+    context: HashMap<String, String>,
+    /// Project the approach was taken in; defaults to the predictor's project
+    project: Option<String>,
+}
+
+impl Default for ApproachInput {
+    fn default() -> Self {
+        ApproachInput {
+            problem: String::new(),
+            approach: String::new(),
+            success: 0.5,
+            complexity: "medium".to_string(),
+            domain: None,
+            patterns: Vec::new(),
+            context: HashMap::new(),
+            project: None,
+        }
+    }
+}
+
+/// Code around the point where a prediction was requested
+#[derive(Debug, Clone, Default)]
+pub struct SurroundingCode {
+    pub file_path: Option<String>,
+    /// Concepts declared in the current file and the selected code
+    pub concepts: Vec<SemanticConcept>,
+    /// Learned patterns with examples in the current module or the selection
+    pub patterns: Vec<Pattern>,
 }
 
 #[cfg_attr(feature = "napi-bindings", napi)]
@@ -62,6 +212,9 @@ impl ApproachPredictor {
             approach_templates: HashMap::new(),
             context_weights: HashMap::new(),
             historical_approaches: Vec::new(),
+            project_path: None,
+            test_frameworks: Vec::new(),
+            installed: None,
         };
         predictor.initialize_approach_templates();
         predictor.initialize_context_weights();
@@ -78,33 +231,183 @@ impl ApproachPredictor {
         let candidates = self.generate_approach_candidates(&problem_description, &complexity, &context, &available_patterns);
         let best_approach = self.select_best_approach(candidates, &context);
         
-        Ok(ApproachPrediction {
+        let mut prediction = ApproachPrediction {
             approach: best_approach.description.clone(),
             confidence: best_approach.confidence,
             reasoning: self.generate_reasoning(&best_approach, &complexity, &context),
             patterns: self.extract_recommended_patterns(&best_approach),
             complexity: complexity.to_string(),
-        })
+        };
+        self.apply_test_framework(&problem_description, &mut prediction);
+        Ok(prediction)
+    }
+
+    /// Predict the best approach for a problem, favoring approaches that fit the
+    /// patterns of the file or selection the user is working in
+    pub fn predict_approach_in_context(
+        &self,
+        problem_description: String,
+        context_data: Option<String>,
+        surrounding: &SurroundingCode,
+    ) -> Result<ApproachPrediction, ParseError> {
+        let complexity = self.analyze_problem_complexity(&problem_description);
+        let mut context = self.parse_context_data(context_data.as_deref())?;
+        context.module_patterns = self.identify_module_patterns(surrounding);
+        context.existing_patterns.extend(context.module_patterns.iter().cloned());
+        let available_patterns = self.extract_available_patterns(&context);
+
+        let candidates = self.generate_approach_candidates(&problem_description, &complexity, &context, &available_patterns);
+        let best_approach = self.select_best_approach(candidates, &context);
+
+        let mut reasoning = self.generate_reasoning(&best_approach, &complexity, &context);
+        if !context.module_patterns.is_empty() {
+            let location = surrounding.file_path.as_deref().unwrap_or("the selected code");
+            reasoning.push_str(&format!(
+                ". Patterns around {}: {}",
+                location,
+                context.module_patterns.join(", ")
+            ));
+        }
+
+        let mut prediction = ApproachPrediction {
+            approach: best_approach.description.clone(),
+            confidence: best_approach.confidence,
+            reasoning,
+            patterns: self.extract_recommended_patterns(&best_approach),
+            complexity: complexity.to_string(),
+        };
+        self.apply_test_framework(&problem_description, &mut prediction);
+        Ok(prediction)
     }
 
     /// Learn from historical approach data
     pub fn learn_from_approaches(&mut self, approach_data: &str) -> Result<bool, ParseError> {
-        let historical_data: Value = from_str(approach_data)
-            .map_err(|e| ParseError::from_reason(format!("Failed to parse approach data: {}", e)))?;
-        
-        if let Some(approaches) = historical_data.as_array() {
-            for approach_value in approaches {
-                if let Ok(historical_approach) = self.parse_historical_approach(approach_value) {
-                    self.historical_approaches.push(historical_approach);
-                    
-                    // Update approach templates based on successful patterns
-                    self.update_templates_from_history();
-                }
-            }
-            Ok(true)
+        let approaches: Vec<ApproachInput> = validation::parse_json(approach_data, "approach data")?;
+        for input in approaches {
+            let historical_approach = self.to_historical_approach(input);
+            self.historical_approaches.push(historical_approach);
+
+            // Update approach templates based on successful patterns
+            self.update_templates_from_history();
+        }
+        Ok(true)
+    }
+
+    /// Add approach templates from a JSON or YAML string, replacing templates
+    /// with the same id
+    ///
+    /// Returns the number of templates loaded. Nothing is loaded if any
+    /// template is invalid or an id appears twice.
+    pub fn load_templates(&mut self, source: &str) -> Result<usize, ParseError> {
+        let trimmed = source.trim_start();
+        let document: TemplateDocument = if trimmed.starts_with('{') || trimmed.starts_with('[') {
+            serde_json::from_str(trimmed)
+                .map_err(|e| ParseError::from_reason(format!("Invalid JSON approach templates: {}", e)))?
         } else {
-            Ok(false)
+            serde_yaml::from_str(source)
+                .map_err(|e| ParseError::from_reason(format!("Invalid YAML approach templates: {}", e)))?
+        };
+        let definitions = match document {
+            TemplateDocument::List(templates) | TemplateDocument::Set { templates } => templates,
+        };
+
+        let mut templates = Vec::with_capacity(definitions.len());
+        for definition in definitions {
+            if templates.iter().any(|(id, _)| *id == definition.id) {
+                return Err(ParseError::from_reason(format!(
+                    "Approach template '{}' is defined more than once",
+                    definition.id
+                )));
+            }
+            let id = definition.id.clone();
+            templates.push((id, definition.into_template()?));
+        }
+
+        let count = templates.len();
+        self.approach_templates.extend(templates);
+        Ok(count)
+    }
+
+    /// Add approach templates from a `.json`, `.yaml` or `.yml` file
+    pub fn load_templates_file(&mut self, path: &str) -> Result<usize, ParseError> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| ParseError::from_reason(format!("Failed to read approach template file {}: {}", path, e)))?;
+        self.load_templates(&source)
+    }
+
+    /// Set the project whose recorded approaches are preferred during prediction
+    ///
+    /// Approaches learned afterwards without an explicit project are recorded against it.
+    pub fn set_project_path(&mut self, project_path: Option<String>) {
+        self.project_path = project_path;
+    }
+
+    /// The project set by `set_project_path`
+    pub fn project_path(&self) -> Option<&str> {
+        self.project_path.as_deref()
+    }
+
+    /// Set the project's test frameworks, most used first, so predictions for
+    /// testing tasks name the one in use
+    pub fn set_test_frameworks(&mut self, frameworks: Vec<TestFramework>) {
+        self.test_frameworks = frameworks;
+    }
+
+    /// Set the frameworks detected in the project, most confident first, so
+    /// approaches are ranked and explained by what it already uses
+    pub fn set_installed_frameworks(&mut self, frameworks: &[FrameworkInfo]) {
+        let installed = InstalledTechnologies::from_frameworks(frameworks);
+        self.installed = (!installed.is_empty()).then_some(installed);
+    }
+
+    /// All recorded historical approaches, oldest first
+    pub fn historical_approaches(&self) -> &[HistoricalApproach] {
+        &self.historical_approaches
+    }
+
+    /// Add previously persisted approaches, skipping ones already recorded
+    ///
+    /// Returns the number of approaches added.
+    pub fn restore_approaches(&mut self, approaches: Vec<HistoricalApproach>) -> usize {
+        let mut added = 0;
+        for approach in approaches {
+            let known = self.historical_approaches.iter().any(|existing| {
+                existing.project_path == approach.project_path
+                    && existing.problem_description == approach.problem_description
+                    && existing.approach_taken == approach.approach_taken
+            });
+            if !known {
+                self.historical_approaches.push(approach);
+                added += 1;
+            }
+        }
+        if added > 0 {
+            self.update_templates_from_history();
         }
+        added
+    }
+
+    /// Recorded approaches matching every filter set in the query, best rated first
+    pub fn query_approaches(&self, query: &ApproachQuery) -> Vec<HistoricalApproach> {
+        let mut matches: Vec<HistoricalApproach> = self
+            .historical_approaches
+            .iter()
+            .filter(|a| {
+                query.domain.as_ref().is_none_or(|d| a.domain.eq_ignore_ascii_case(d))
+                    && query.complexity.as_ref().is_none_or(|c| {
+                        ProblemComplexity::from_label(&a.complexity) == ProblemComplexity::from_label(c)
+                    })
+                    && query.min_success_rating.is_none_or(|min| a.success_rating >= min)
+                    && query.project_path.as_ref().is_none_or(|p| a.project_path.as_ref() == Some(p))
+            })
+            .cloned()
+            .collect();
+        matches.sort_by(|a, b| {
+            b.success_rating
+                .partial_cmp(&a.success_rating)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        matches
     }
 
     /// Update predictor with new pattern information
@@ -161,85 +464,10 @@ impl ApproachPredictor {
         alternatives
     }
 
-    /// Initialize approach templates
+    /// Load the templates shipped with the crate
     fn initialize_approach_templates(&mut self) {
-        // Microservices Architecture
-        self.approach_templates.insert("microservices".to_string(), ApproachTemplate {
-            name: "Microservices Architecture".to_string(),
-            description: "Decompose into loosely coupled, independently deployable services".to_string(),
-            complexity_suitability: vec![ProblemComplexity::Medium, ProblemComplexity::High],
-            required_patterns: vec!["service_boundaries".to_string(), "api_gateway".to_string()],
-            preferred_patterns: vec!["event_driven".to_string(), "database_per_service".to_string()],
-            technologies: vec!["docker".to_string(), "kubernetes".to_string(), "rest_api".to_string()],
-            confidence_base: 0.8,
-            confidence: 0.8,
-            patterns: vec!["service_boundaries".to_string(), "api_gateway".to_string()],
-        });
-
-        // Monolithic Architecture  
-        self.approach_templates.insert("monolith".to_string(), ApproachTemplate {
-            name: "Modular Monolith".to_string(),
-            description: "Single deployable unit with clear internal module boundaries".to_string(),
-            complexity_suitability: vec![ProblemComplexity::Low, ProblemComplexity::Medium],
-            required_patterns: vec!["layered_architecture".to_string()],
-            preferred_patterns: vec!["dependency_injection".to_string(), "domain_driven_design".to_string()],
-            technologies: vec!["mvc".to_string(), "orm".to_string()],
-            confidence_base: 0.7,
-            confidence: 0.7,
-            patterns: vec!["layered_architecture".to_string()],
-        });
-
-        // Event-Driven Architecture
-        self.approach_templates.insert("event_driven".to_string(), ApproachTemplate {
-            name: "Event-Driven Architecture".to_string(),
-            description: "Asynchronous communication through events and message queues".to_string(),
-            complexity_suitability: vec![ProblemComplexity::Medium, ProblemComplexity::High],
-            required_patterns: vec!["event_sourcing".to_string(), "publisher_subscriber".to_string()],
-            preferred_patterns: vec!["saga_pattern".to_string(), "cqrs".to_string()],
-            technologies: vec!["message_queue".to_string(), "event_store".to_string()],
-            confidence_base: 0.75,
-            confidence: 0.75,
-            patterns: vec!["event_sourcing".to_string(), "publisher_subscriber".to_string()],
-        });
-
-        // Serverless Architecture
-        self.approach_templates.insert("serverless".to_string(), ApproachTemplate {
-            name: "Serverless Architecture".to_string(),
-            description: "Function-based architecture with managed infrastructure".to_string(),
-            complexity_suitability: vec![ProblemComplexity::Low, ProblemComplexity::Medium],
-            required_patterns: vec!["function_as_service".to_string()],
-            preferred_patterns: vec!["api_gateway".to_string(), "event_triggers".to_string()],
-            technologies: vec!["aws_lambda".to_string(), "azure_functions".to_string(), "api_gateway".to_string()],
-            confidence_base: 0.6,
-            confidence: 0.6,
-            patterns: vec!["function_as_service".to_string()],
-        });
-
-        // Clean Architecture
-        self.approach_templates.insert("clean_architecture".to_string(), ApproachTemplate {
-            name: "Clean Architecture".to_string(),
-            description: "Dependency inversion with clear separation of concerns".to_string(),
-            complexity_suitability: vec![ProblemComplexity::Medium, ProblemComplexity::High],
-            required_patterns: vec!["dependency_inversion".to_string(), "use_cases".to_string()],
-            preferred_patterns: vec!["repository_pattern".to_string(), "domain_entities".to_string()],
-            technologies: vec!["dependency_injection".to_string(), "testing_framework".to_string()],
-            confidence_base: 0.85,
-            confidence: 0.85,
-            patterns: vec!["dependency_inversion".to_string(), "use_cases".to_string()],
-        });
-
-        // CRUD Application
-        self.approach_templates.insert("crud".to_string(), ApproachTemplate {
-            name: "CRUD Application".to_string(),
-            description: "Simple Create, Read, Update, Delete operations with standard patterns".to_string(),
-            complexity_suitability: vec![ProblemComplexity::Low],
-            required_patterns: vec!["mvc".to_string(), "repository".to_string()],
-            preferred_patterns: vec!["validation".to_string(), "orm".to_string()],
-            technologies: vec!["database".to_string(), "web_framework".to_string()],
-            confidence_base: 0.9,
-            confidence: 0.9,
-            patterns: vec!["mvc".to_string(), "repository".to_string()],
-        });
+        self.load_templates(DEFAULT_TEMPLATES)
+            .expect("embedded approach templates are valid");
     }
 
     /// Initialize context weights for decision making
@@ -286,40 +514,22 @@ impl ApproachPredictor {
 
     /// Parse context data from JSON string
     fn parse_context_data(&self, context_data: Option<&str>) -> Result<ProblemContext, ParseError> {
-        let default_context = ProblemContext {
-            domain: "general".to_string(),
-            scale: "medium".to_string(),
-            performance_requirements: "standard".to_string(),
-            maintainability_requirements: "high".to_string(),
-            team_size: "small".to_string(),
-            timeline: "months".to_string(),
-            existing_patterns: Vec::new(),
-            technologies: Vec::new(),
+        let input: ContextInput = match context_data {
+            Some(data) => validation::parse_json(data, "prediction context")?,
+            None => ContextInput::default(),
         };
-        
-        if let Some(data) = context_data {
-            let parsed: Value = from_str(data)
-                .map_err(|e| ParseError::from_reason(format!("Failed to parse context: {}", e)))?;
-            
-            Ok(ProblemContext {
-                domain: parsed.get("domain").and_then(|v| v.as_str()).unwrap_or("general").to_string(),
-                scale: parsed.get("scale").and_then(|v| v.as_str()).unwrap_or("medium").to_string(),
-                performance_requirements: parsed.get("performance").and_then(|v| v.as_str()).unwrap_or("standard").to_string(),
-                maintainability_requirements: parsed.get("maintainability").and_then(|v| v.as_str()).unwrap_or("high").to_string(),
-                team_size: parsed.get("team_size").and_then(|v| v.as_str()).unwrap_or("small").to_string(),
-                timeline: parsed.get("timeline").and_then(|v| v.as_str()).unwrap_or("months").to_string(),
-                existing_patterns: parsed.get("existing_patterns")
-                    .and_then(|v| v.as_array())
-                    .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
-                    .unwrap_or_default(),
-                technologies: parsed.get("technologies")
-                    .and_then(|v| v.as_array())
-                    .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
-                    .unwrap_or_default(),
-            })
-        } else {
-            Ok(default_context)
-        }
+
+        Ok(ProblemContext {
+            domain: input.domain,
+            scale: input.scale,
+            performance_requirements: input.performance,
+            maintainability_requirements: input.maintainability,
+            team_size: input.team_size,
+            timeline: input.timeline,
+            existing_patterns: input.existing_patterns,
+            technologies: input.technologies,
+            module_patterns: Vec::new(),
+        })
     }
 
     /// Extract available patterns from context
@@ -403,6 +613,19 @@ impl ApproachPredictor {
         
         // Adjust for context factors
         confidence *= self.calculate_context_multiplier(template, context);
+
+        // Approaches needing technologies the project lacks rank lower
+        if self.installed.is_some() && !template.technologies.is_empty() {
+            let present = template.technologies.iter().filter(|t| self.is_installed(t, context)).count();
+            confidence *= 0.7 + 0.3 * present as f64 / template.technologies.len() as f64;
+        }
+
+        // Boost for fitting the surrounding module
+        let module_matches = Self::module_pattern_matches(
+            template.required_patterns.iter().chain(&template.preferred_patterns),
+            &context.module_patterns,
+        );
+        confidence += 0.1 * module_matches.min(3) as f64;
         
         confidence.min(1.0)
     }
@@ -519,6 +742,22 @@ impl ApproachPredictor {
         reasoning.join(". ")
     }
 
+    /// Point a prediction for a testing task at the project's main test framework
+    fn apply_test_framework(&self, problem_description: &str, prediction: &mut ApproachPrediction) {
+        let Some(framework) = self.test_frameworks.first() else { return };
+        if !KeywordExtractor::extract(problem_description).iter().any(|keyword| keyword == "test") {
+            return;
+        }
+        prediction.approach.push_str(&format!("; write the tests with {}", framework.name));
+        if let Some(example) = &framework.example_file {
+            prediction.approach.push_str(&format!(" following {}", example));
+        }
+        prediction.reasoning.push_str(&format!(
+            ". The project's tests use {} ({} cases in {} suites), run with `{}`",
+            framework.name, framework.case_count, framework.suite_count, framework.command
+        ));
+    }
+
     /// Generate contextual reasoning based on existing codebase
     fn generate_contextual_reasoning(&self, approach: &GeneratedApproach, existing_patterns: &[String], context: &ProblemContext) -> String {
         let mut reasoning = vec![
@@ -547,6 +786,13 @@ impl ApproachPredictor {
                 break;
             }
         }
+
+        // Approaches recalled from history carry the patterns they were built with
+        for historical in &self.historical_approaches {
+            if historical.approach_taken == approach.description {
+                patterns.extend(historical.patterns_used.clone());
+            }
+        }
         
         patterns.sort();
         patterns.dedup();
@@ -554,26 +800,22 @@ impl ApproachPredictor {
     }
 
     /// Additional helper methods for historical data and codebase analysis
-    fn parse_historical_approach(&self, value: &Value) -> Result<HistoricalApproach, ParseError> {
-        let problem = value.get("problem").and_then(|v| v.as_str()).unwrap_or("").to_string();
-        let approach = value.get("approach").and_then(|v| v.as_str()).unwrap_or("").to_string();
-        let success = value.get("success").and_then(|v| v.as_f64()).unwrap_or(0.5);
-        let complexity_str = value.get("complexity").and_then(|v| v.as_str()).unwrap_or("medium");
-        
-        let complexity = match complexity_str {
-            "low" => ProblemComplexity::Low,
-            "high" => ProblemComplexity::High,
-            _ => ProblemComplexity::Medium,
-        };
-        
-        Ok(HistoricalApproach {
-            problem_description: problem,
-            approach_taken: approach,
-            patterns_used: Vec::new(),
-            success_rating: success,
-            complexity,
-            context: HashMap::new(),
-        })
+    fn to_historical_approach(&self, input: ApproachInput) -> HistoricalApproach {
+        let domain = input
+            .domain
+            .or_else(|| input.context.get("domain").cloned())
+            .unwrap_or_else(|| "general".to_string());
+
+        HistoricalApproach {
+            problem_description: input.problem,
+            approach_taken: input.approach,
+            patterns_used: input.patterns,
+            success_rating: input.success.clamp(0.0, 1.0),
+            complexity: ProblemComplexity::from_label(&input.complexity).to_string(),
+            domain,
+            project_path: input.project.or_else(|| self.project_path.clone()),
+            context: input.context,
+        }
     }
 
     fn update_templates_from_history(&mut self) {
@@ -599,7 +841,8 @@ impl ApproachPredictor {
                 if total_similarity > 0.6 {  // Similar approaches
                     if let Some(template) = self.approach_templates.get_mut(template_name) {
                         // Check if template complexity matches historical complexity
-                        let complexity_match = template.complexity_suitability.contains(&historical.complexity);
+                        let complexity = ProblemComplexity::from_label(&historical.complexity);
+                        let complexity_match = template.complexity_suitability.contains(&complexity);
                         let complexity_bonus = if complexity_match { 0.1 } else { -0.05 };
                         
                         // Adjust confidence based on historical success and complexity matching
@@ -622,16 +865,7 @@ impl ApproachPredictor {
     }
 
     fn calculate_approach_similarity(&self, approach1: &str, approach2: &str) -> f64 {
-        // Simple similarity calculation based on common words
-        let approach1_lower = approach1.to_lowercase();
-        let approach2_lower = approach2.to_lowercase();
-        let words1: HashSet<&str> = approach1_lower.split_whitespace().collect();
-        let words2: HashSet<&str> = approach2_lower.split_whitespace().collect();
-        
-        let intersection = words1.intersection(&words2).count();
-        let union = words1.union(&words2).count();
-        
-        if union == 0 { 0.0 } else { intersection as f64 / union as f64 }
+        KeywordExtractor::similarity(approach1, approach2)
     }
 
     fn recalculate_template_confidence(&mut self) {
@@ -696,6 +930,7 @@ impl ApproachPredictor {
             timeline,
             existing_patterns: patterns.into_iter().collect(),
             technologies: technologies.into_iter().collect(),
+            module_patterns: Vec::new(),
         }
     }
 
@@ -732,12 +967,101 @@ impl ApproachPredictor {
 
     fn generate_historical_candidates(
         &self,
-        _problem_description: &str,
-        _complexity: &ProblemComplexity,
-        _context: &ProblemContext,
+        problem_description: &str,
+        complexity: &ProblemComplexity,
+        context: &ProblemContext,
     ) -> Vec<GeneratedApproach> {
-        // Generate candidates based on historical success patterns
-        Vec::new() // Simplified for now
+        let mut candidates: Vec<GeneratedApproach> = Vec::new();
+
+        for historical in &self.historical_approaches {
+            if historical.success_rating < 0.6 || historical.approach_taken.is_empty() {
+                continue;
+            }
+            let similarity = self.calculate_approach_similarity(problem_description, &historical.problem_description);
+            if similarity < 0.2 {
+                continue;
+            }
+
+            let mut confidence = historical.success_rating * (0.4 + 0.4 * similarity);
+            if ProblemComplexity::from_label(&historical.complexity) == *complexity {
+                confidence += 0.05;
+            }
+            if historical.domain.eq_ignore_ascii_case(&context.domain) {
+                confidence += 0.05;
+            }
+            let module_matches = Self::module_pattern_matches(&historical.patterns_used, &context.module_patterns);
+            confidence += 0.1 * module_matches.min(2) as f64;
+
+            // Approaches that worked in this project beat generic templates
+            let same_project = self.project_path.is_some() && historical.project_path == self.project_path;
+            if same_project {
+                confidence += 0.25;
+            }
+            let confidence = confidence.min(0.95);
+
+            if candidates.iter().any(|c| c.description == historical.approach_taken && c.confidence >= confidence) {
+                continue;
+            }
+            candidates.retain(|c| c.description != historical.approach_taken);
+
+            let source = if same_project { "this project" } else { "a previous project" };
+            let mut reasoning = format!(
+                "Worked for a similar problem in {} ({:.0}% success)",
+                source,
+                historical.success_rating * 100.0
+            );
+            if !historical.patterns_used.is_empty() {
+                reasoning.push_str(&format!(", using {}", historical.patterns_used.join(", ")));
+            }
+            candidates.push(GeneratedApproach {
+                description: historical.approach_taken.clone(),
+                confidence,
+                reasoning,
+            });
+        }
+
+        candidates
+    }
+
+    /// Pattern tags for the code around a prediction: architecture roles of its
+    /// concepts plus the structural and implementation patterns learned there
+    fn identify_module_patterns(&self, surrounding: &SurroundingCode) -> Vec<String> {
+        let mut patterns = self.identify_existing_patterns(&surrounding.concepts);
+        for pattern in &surrounding.patterns {
+            let tag = ["structural_", "implementation_"]
+                .iter()
+                .find_map(|prefix| pattern.id.strip_prefix(prefix));
+            if let Some(tag) = tag {
+                if !pattern.pattern_type.ends_with("_violation") {
+                    patterns.push(tag.to_lowercase());
+                }
+            }
+        }
+        patterns.sort();
+        patterns.dedup();
+        patterns
+    }
+
+    /// How many of `patterns` the module uses; tags match ignoring underscores and
+    /// a trailing qualifier, so 'layered' matches 'layered_architecture'
+    fn module_pattern_matches<'a>(patterns: impl IntoIterator<Item = &'a String>, module_patterns: &[String]) -> usize {
+        let normalize = |tag: &str| tag.replace('_', "").to_lowercase();
+        let module: Vec<String> = module_patterns.iter().map(|p| normalize(p)).collect();
+        patterns
+            .into_iter()
+            .map(|p| normalize(p))
+            .filter(|p| {
+                module
+                    .iter()
+                    .any(|m| m.len() >= 3 && p.len() >= 3 && (p.starts_with(m.as_str()) || m.starts_with(p.as_str())))
+            })
+            .count()
+    }
+
+    /// Whether the project's frameworks or the request's context provide `technology`
+    fn is_installed(&self, technology: &str, context: &ProblemContext) -> bool {
+        context.technologies.iter().any(|t| t == technology)
+            || self.installed.as_ref().is_some_and(|installed| !installed.providers(technology).is_empty())
     }
 
     fn generate_template_reasoning(&self, template: &ApproachTemplate, context: &ProblemContext, available_patterns: &[String]) -> String {
@@ -754,6 +1078,36 @@ impl ApproachPredictor {
         if template.technologies.iter().any(|t| context.technologies.contains(t)) {
             reasoning.push("Aligns with existing technology stack".to_string());
         }
+        if let Some(installed) = &self.installed {
+            let mut providers: Vec<&str> = Vec::new();
+            for technology in &template.technologies {
+                if let Some(name) = installed.providers(technology).first() {
+                    if !providers.contains(&name.as_str()) {
+                        providers.push(name);
+                    }
+                }
+            }
+            if !providers.is_empty() {
+                reasoning.push(format!("uses your existing {} setup", providers.join(" and ")));
+            }
+            let missing: Vec<&str> = template
+                .technologies
+                .iter()
+                .filter(|t| !self.is_installed(t, context))
+                .map(String::as_str)
+                .collect();
+            if !missing.is_empty() {
+                reasoning.push(format!("would add {}, not found in the project", missing.join(", ")));
+            }
+        }
+
+        let module_matches = Self::module_pattern_matches(
+            template.required_patterns.iter().chain(&template.preferred_patterns),
+            &context.module_patterns,
+        );
+        if module_matches > 0 {
+            reasoning.push(format!("Fits {} patterns of the surrounding module", module_matches));
+        }
         
         reasoning.join(", ")
     }
@@ -804,6 +1158,34 @@ mod tests {
         assert_eq!(prediction.complexity, "low");
     }
 
+    #[test]
+    fn test_installed_frameworks_rank_and_explain_templates() {
+        let mut predictor = ApproachPredictor::new();
+        let context = predictor.parse_context_data(None).unwrap();
+        let available = vec!["mvc".to_string(), "repository".to_string()];
+        let crud = predictor.approach_templates["crud"].clone();
+        let unknown = predictor.calculate_template_confidence(&crud, &context, &available);
+
+        predictor.set_installed_frameworks(&[FrameworkInfo {
+            name: "Express".to_string(),
+            version: Some("4.18.2".to_string()),
+            confidence: 0.9,
+            evidence: vec![],
+        }]);
+        // Provides web_framework but not database
+        let partial = predictor.calculate_template_confidence(&crud, &context, &available);
+        assert!((partial - unknown * 0.85).abs() < 1e-9);
+        let reasoning = predictor.generate_template_reasoning(&crud, &context, &available);
+        assert!(reasoning.contains("uses your existing Express setup"));
+        assert!(reasoning.contains("would add database, not found in the project"));
+
+        // Technologies named in the request's context count as present
+        let mut with_database = context.clone();
+        with_database.technologies.push("database".to_string());
+        let full = predictor.calculate_template_confidence(&crud, &with_database, &available);
+        assert!((full - unknown).abs() < 1e-9);
+    }
+
     #[test]
     fn test_context_data_parsing() {
         let predictor = ApproachPredictor::new();
@@ -851,6 +1233,7 @@ mod tests {
             timeline: "months".to_string(),
             existing_patterns: vec!["mvc".to_string()],
             technologies: vec!["react".to_string(), "express".to_string()],
+            module_patterns: Vec::new(),
         };
         
         let patterns = predictor.extract_available_patterns(&context);
@@ -872,6 +1255,7 @@ mod tests {
             timeline: "months".to_string(),
             existing_patterns: vec!["service_boundaries".to_string()],
             technologies: vec!["docker".to_string()],
+            module_patterns: Vec::new(),
         };
         let available_patterns = vec!["service_boundaries".to_string(), "api_gateway".to_string()];
         
@@ -980,4 +1364,178 @@ mod tests {
         assert!(prediction.confidence > 0.0);
         assert!(!prediction.patterns.is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_testing_tasks_name_the_project_framework() {
+        let mut predictor = ApproachPredictor::new();
+        let unrelated = predictor.predict_approach("add caching to the catalog".to_string(), None).unwrap();
+        predictor.set_test_frameworks(vec![TestFramework {
+            name: "vitest".to_string(),
+            language: "typescript".to_string(),
+            command: "npx vitest run".to_string(),
+            evidence: vec!["package.json: vitest".to_string()],
+            suite_count: 4,
+            case_count: 31,
+            example_file: Some("src/cart.test.ts".to_string()),
+        }]);
+
+        let prediction = predictor.predict_approach("Add a test for the checkout flow".to_string(), None).unwrap();
+        assert!(prediction.approach.ends_with("; write the tests with vitest following src/cart.test.ts"));
+        assert!(prediction.reasoning.contains("use vitest (31 cases in 4 suites), run with `npx vitest run`"));
+
+        let prediction = predictor.predict_approach("add caching to the catalog".to_string(), None).unwrap();
+        assert_eq!(prediction.approach, unrelated.approach);
+    }
+
+    #[test]
+    fn test_project_approaches_outrank_templates() {
+        let mut predictor = ApproachPredictor::new();
+        predictor.set_project_path(Some("/work/shop".to_string()));
+        predictor
+            .learn_from_approaches(r#"[
+                {"problem": "add caching to the product catalog lookup", "approach": "Read-through cache in front of the catalog repository",
+                 "success": 0.9, "complexity": "medium", "domain": "web", "patterns": ["repository"]},
+                {"problem": "add caching to the product catalog lookup", "approach": "Memoize catalog queries in the controller",
+                 "success": 0.9, "complexity": "medium", "domain": "web", "project": "/work/other"},
+                {"problem": "add caching to the product catalog lookup", "approach": "Cache entire pages at the proxy",
+                 "success": 0.2, "complexity": "low", "domain": "ops"}
+            ]"#)
+            .unwrap();
+
+        let prediction = predictor
+            .predict_approach("add caching to the product catalog lookup".to_string(), Some(r#"{"domain": "web"}"#.to_string()))
+            .unwrap();
+        assert_eq!(prediction.approach, "Read-through cache in front of the catalog repository");
+        assert!(prediction.reasoning.contains("this project"));
+        assert!(prediction.patterns.contains(&"repository".to_string()));
+
+        let web = predictor.query_approaches(&ApproachQuery { domain: Some("web".to_string()), ..Default::default() });
+        assert_eq!(web.len(), 2);
+        let successful = predictor.query_approaches(&ApproachQuery { min_success_rating: Some(0.5), ..Default::default() });
+        assert_eq!(successful.len(), 2);
+        let low = predictor.query_approaches(&ApproachQuery { complexity: Some("low".to_string()), ..Default::default() });
+        assert_eq!(low[0].approach_taken, "Cache entire pages at the proxy");
+        let other = predictor.query_approaches(&ApproachQuery { project_path: Some("/work/other".to_string()), ..Default::default() });
+        assert_eq!(other.len(), 1);
+
+        // Restoring the same approaches again adds nothing
+        let existing = predictor.historical_approaches().to_vec();
+        assert_eq!(predictor.restore_approaches(existing), 0);
+    }
+
+    #[test]
+    fn test_prediction_biased_by_surrounding_module() {
+        let predictor = ApproachPredictor::new();
+        let concept = |id: &str, name: &str| SemanticConcept {
+            id: id.to_string(),
+            name: name.to_string(),
+            concept_type: "class".to_string(),
+            confidence: 0.8,
+            file_path: "src/products/ProductController.ts".to_string(),
+            line_range: crate::types::LineRange { start: 1, end: 20 },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        };
+        let surrounding = SurroundingCode {
+            file_path: Some("src/products/ProductController.ts".to_string()),
+            concepts: vec![
+                concept("1", "ProductController"),
+                concept("2", "ProductService"),
+                concept("3", "ProductRepository"),
+            ],
+            patterns: vec![Pattern {
+                id: "structural_MVC".to_string(),
+                pattern_type: "structural".to_string(),
+                description: "MVC architecture".to_string(),
+                frequency: 3,
+                confidence: 0.8,
+                examples: Vec::new(),
+                contexts: Vec::new(),
+            }],
+        };
+        assert_eq!(
+            predictor.identify_module_patterns(&surrounding),
+            vec!["mvc".to_string(), "repository_pattern".to_string(), "service_layer".to_string()]
+        );
+
+        let problem = "Add a form to edit the product price";
+        let plain = predictor.predict_approach(problem.to_string(), None).unwrap();
+        let in_context = predictor
+            .predict_approach_in_context(problem.to_string(), None, &surrounding)
+            .unwrap();
+        assert!(in_context.approach.starts_with("CRUD Application"));
+        assert!(!plain.approach.starts_with("CRUD Application"));
+        assert!(in_context.confidence > plain.confidence);
+        assert!(in_context.reasoning.contains("Patterns around src/products/ProductController.ts: mvc"));
+        assert!(in_context.reasoning.contains("surrounding module"));
+    }
+
+    #[test]
+    fn test_module_pattern_matching() {
+        let module = vec!["layered".to_string(), "dependencyinjection".to_string()];
+        let template = vec!["layered_architecture".to_string(), "dependency_injection".to_string(), "cqrs".to_string()];
+        assert_eq!(ApproachPredictor::module_pattern_matches(&template, &module), 2);
+        assert_eq!(ApproachPredictor::module_pattern_matches(&template, &[]), 0);
+    }
+
+    #[test]
+    fn test_default_templates_load_from_embedded_data() {
+        let predictor = ApproachPredictor::new();
+        assert_eq!(predictor.approach_templates.len(), 6);
+        let crud = &predictor.approach_templates["crud"];
+        assert_eq!(crud.name, "CRUD Application");
+        assert_eq!(crud.complexity_suitability, vec![ProblemComplexity::Low]);
+        assert_eq!(crud.required_patterns, vec!["mvc", "repository"]);
+        assert_eq!(crud.technologies, vec!["database", "web_framework"]);
+        assert_eq!(crud.confidence_base, 0.9);
+    }
+
+    #[test]
+    fn test_user_templates_extend_and_replace_defaults() {
+        let mut predictor = ApproachPredictor::new();
+        let loaded = predictor
+            .load_templates(
+                r#"
+templates:
+  - id: hexagonal
+    name: Hexagonal Architecture
+    description: Ports and adapters around the domain
+    complexity: [medium, high]
+    required_patterns: [ports_and_adapters]
+    technologies: [dependency_injection]
+    confidence: 0.8
+  - id: crud
+    name: CRUD Application
+    description: Generated admin screens
+    complexity: [low, medium]
+    confidence: 0.5
+"#,
+            )
+            .unwrap();
+
+        assert_eq!(loaded, 2);
+        assert_eq!(predictor.approach_templates.len(), 7);
+        assert_eq!(predictor.approach_templates["hexagonal"].required_patterns, vec!["ports_and_adapters"]);
+        assert_eq!(predictor.approach_templates["crud"].description, "Generated admin screens");
+        assert!(predictor.approach_templates["crud"].required_patterns.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_templates_load_nothing() {
+        let mut predictor = ApproachPredictor::new();
+        let cases = [
+            r#"[{"id": "a", "name": "A", "complexity": ["huge"], "confidence": 0.5}]"#,
+            r#"[{"id": "a", "name": "A", "complexity": [], "confidence": 0.5}]"#,
+            r#"[{"id": "a", "name": "A", "complexity": ["low"], "confidence": 1.5}]"#,
+            r#"[{"id": "", "name": "A", "complexity": ["low"], "confidence": 0.5}]"#,
+            r#"[{"id": "a", "name": "A", "complexity": ["low"], "confidence": 0.5},
+                {"id": "a", "name": "B", "complexity": ["low"], "confidence": 0.5}]"#,
+            r#"[{"name": "A", "complexity": ["low"], "confidence": 0.5}]"#,
+        ];
+        for case in cases {
+            assert!(predictor.load_templates(case).is_err(), "accepted {}", case);
+        }
+        assert_eq!(predictor.approach_templates.len(), 6);
+        assert!(!predictor.approach_templates.contains_key("a"));
+    }
+}
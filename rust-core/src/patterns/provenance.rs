@@ -0,0 +1,182 @@
+//! Provenance tracking for learned patterns
+//!
+//! Records which analyzer produced each pattern, which candidates were merged
+//! into it, and every confidence change, so `explain_pattern` can show the
+//! evidence behind a claim like "this repo uses the factory pattern (0.9)".
+
+use crate::patterns::types::{ConfidenceAdjustment, Pattern, PatternExplanation, PatternFileEvidence};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+struct PatternProvenance {
+    analyzer: String,
+    merged_from: Vec<String>,
+    confidence_history: Vec<ConfidenceAdjustment>,
+}
+
+/// Evidence trail for every pattern the engine has stored
+#[derive(Debug, Clone, Default)]
+pub struct ProvenanceLog {
+    entries: HashMap<String, PatternProvenance>,
+}
+
+impl ProvenanceLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `analyzer` produced `pattern` at its current confidence
+    pub fn record_learned(&mut self, pattern: &Pattern, analyzer: &str, timestamp: Option<String>) {
+        let entry = self.entries.entry(pattern.id.clone()).or_default();
+        entry.analyzer = analyzer.to_string();
+        entry.confidence_history.push(ConfidenceAdjustment {
+            timestamp,
+            confidence: pattern.confidence,
+            reason: format!("learned by {} analyzer", analyzer),
+        });
+    }
+
+    /// Record the IDs of candidates consolidated into `pattern_id`
+    pub fn record_merge(&mut self, pattern_id: &str, merged_ids: Vec<String>) {
+        let entry = self.entries.entry(pattern_id.to_string()).or_default();
+        for id in merged_ids {
+            if !entry.merged_from.contains(&id) {
+                entry.merged_from.push(id);
+            }
+        }
+    }
+
+    /// Record a confidence change, skipping no-op adjustments
+    pub fn record_adjustment(
+        &mut self,
+        pattern_id: &str,
+        confidence: f64,
+        reason: &str,
+        timestamp: Option<String>,
+    ) {
+        let entry = self.entries.entry(pattern_id.to_string()).or_default();
+        let unchanged = entry
+            .confidence_history
+            .last()
+            .is_some_and(|last| (last.confidence - confidence).abs() < f64::EPSILON);
+        if !unchanged {
+            entry.confidence_history.push(ConfidenceAdjustment {
+                timestamp,
+                confidence,
+                reason: reason.to_string(),
+            });
+        }
+    }
+
+    /// Drop everything recorded about a deleted pattern
+    pub fn forget(&mut self, pattern_id: &str) {
+        self.entries.remove(pattern_id);
+    }
+
+    pub fn history(&self, pattern_id: &str) -> Vec<ConfidenceAdjustment> {
+        self.entries
+            .get(pattern_id)
+            .map(|entry| entry.confidence_history.clone())
+            .unwrap_or_default()
+    }
+
+    /// Build the explanation for a stored pattern
+    pub fn explain(&self, pattern: &Pattern) -> PatternExplanation {
+        let entry = self.entries.get(&pattern.id);
+
+        let mut per_file: HashMap<&str, u32> = HashMap::new();
+        for example in &pattern.examples {
+            *per_file.entry(example.file_path.as_str()).or_insert(0) += 1;
+        }
+        let mut contributing_files: Vec<PatternFileEvidence> = per_file
+            .into_iter()
+            .map(|(file_path, example_count)| PatternFileEvidence {
+                file_path: file_path.to_string(),
+                example_count,
+            })
+            .collect();
+        contributing_files.sort_by(|a, b| {
+            b.example_count
+                .cmp(&a.example_count)
+                .then_with(|| a.file_path.cmp(&b.file_path))
+        });
+
+        PatternExplanation {
+            pattern_id: pattern.id.clone(),
+            pattern_type: pattern.pattern_type.clone(),
+            description: pattern.description.clone(),
+            confidence: pattern.confidence,
+            frequency: pattern.frequency,
+            analyzer: entry
+                .map(|e| e.analyzer.clone())
+                .filter(|a| !a.is_empty())
+                .unwrap_or_else(|| "unknown".to_string()),
+            contributing_files,
+            confidence_history: entry
+                .map(|e| e.confidence_history.clone())
+                .unwrap_or_default(),
+            merged_from: entry.map(|e| e.merged_from.clone()).unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::types::PatternExample;
+    use crate::types::LineRange;
+
+    fn example(file_path: &str) -> PatternExample {
+        PatternExample {
+            code: "fn x() {}".to_string(),
+            file_path: file_path.to_string(),
+            line_range: LineRange { start: 1, end: 1 },
+        }
+    }
+
+    fn pattern() -> Pattern {
+        Pattern {
+            id: "p1".to_string(),
+            pattern_type: "implementation_factory".to_string(),
+            description: "Factory pattern".to_string(),
+            frequency: 3,
+            confidence: 0.8,
+            examples: vec![example("b.rs"), example("a.rs"), example("b.rs")],
+            contexts: vec![],
+        }
+    }
+
+    #[test]
+    fn test_explain_counts_examples_per_file() {
+        let log = ProvenanceLog::new();
+        let explanation = log.explain(&pattern());
+
+        assert_eq!(explanation.analyzer, "unknown");
+        assert_eq!(explanation.contributing_files.len(), 2);
+        assert_eq!(explanation.contributing_files[0].file_path, "b.rs");
+        assert_eq!(explanation.contributing_files[0].example_count, 2);
+        assert_eq!(explanation.contributing_files[1].file_path, "a.rs");
+    }
+
+    #[test]
+    fn test_history_records_learning_and_adjustments() {
+        let mut log = ProvenanceLog::new();
+        let p = pattern();
+        log.record_learned(&p, "implementation", None);
+        log.record_adjustment("p1", 0.8, "no change", None);
+        log.record_adjustment("p1", 0.85, "boosted", Some("2024-01-01T00:00:00Z".to_string()));
+        log.record_merge("p1", vec!["p2".to_string(), "p2".to_string()]);
+
+        let explanation = log.explain(&p);
+        assert_eq!(explanation.analyzer, "implementation");
+        assert_eq!(explanation.merged_from, vec!["p2".to_string()]);
+
+        let reasons: Vec<&str> = explanation
+            .confidence_history
+            .iter()
+            .map(|a| a.reason.as_str())
+            .collect();
+        assert_eq!(reasons, vec!["learned by implementation analyzer", "boosted"]);
+        assert_eq!(log.history("p1").len(), 2);
+    }
+}
@@ -0,0 +1,255 @@
+//! Rename detection for file watcher events
+//!
+//! Watchers report a moved file as a delete plus a create, in either order. The
+//! tracker keeps a fingerprint of every file the engine has seen and holds
+//! deletions back for a short window, so a matching create elsewhere can be
+//! treated as a rename instead of a deletion followed by an unrelated addition.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long a deletion or addition waits for its counterpart
+const RENAME_WINDOW: Duration = Duration::from_secs(5);
+
+/// Minimum line overlap for two files to count as the same file moved
+const RENAME_SIMILARITY: f64 = 0.8;
+
+/// Content hash plus per-line hashes for similarity matching
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileFingerprint {
+    content_hash: u64,
+    /// Sorted hashes of trimmed, non-blank lines
+    line_hashes: Vec<u64>,
+}
+
+impl FileFingerprint {
+    pub fn from_content(content: &str) -> Self {
+        let mut line_hashes: Vec<u64> = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(fnv1a)
+            .collect();
+        line_hashes.sort_unstable();
+
+        FileFingerprint {
+            content_hash: fnv1a(content),
+            line_hashes,
+        }
+    }
+
+    /// 1.0 for identical content, otherwise the multiset overlap of lines
+    pub fn similarity(&self, other: &FileFingerprint) -> f64 {
+        if self.content_hash == other.content_hash {
+            return 1.0;
+        }
+        if self.line_hashes.is_empty() || other.line_hashes.is_empty() {
+            return 0.0;
+        }
+
+        let (mut i, mut j, mut shared) = (0, 0, 0);
+        while i < self.line_hashes.len() && j < other.line_hashes.len() {
+            match self.line_hashes[i].cmp(&other.line_hashes[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    shared += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        shared as f64 / self.line_hashes.len().max(other.line_hashes.len()) as f64
+    }
+}
+
+/// What the tracker concluded about a deletion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeletionOutcome {
+    /// The file reappeared at this path just before being deleted
+    Renamed(String),
+    /// Held back in case a matching create follows
+    Pending,
+    /// Nothing is known about the file, so it cannot be matched
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+struct RecentEvent {
+    path: String,
+    fingerprint: FileFingerprint,
+    at: Instant,
+}
+
+/// Pairs delete and create events that describe the same file
+#[derive(Debug, Clone)]
+pub struct RenameTracker {
+    fingerprints: HashMap<String, FileFingerprint>,
+    pending_deletions: Vec<RecentEvent>,
+    recent_additions: Vec<RecentEvent>,
+    window: Duration,
+}
+
+impl Default for RenameTracker {
+    fn default() -> Self {
+        RenameTracker {
+            fingerprints: HashMap::new(),
+            pending_deletions: Vec::new(),
+            recent_additions: Vec::new(),
+            window: RENAME_WINDOW,
+        }
+    }
+}
+
+impl RenameTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember the current content of a file
+    pub fn record(&mut self, path: &str, fingerprint: FileFingerprint) {
+        self.fingerprints.insert(path.to_string(), fingerprint);
+    }
+
+    /// Carry a file's fingerprint over to its new path
+    pub fn rename(&mut self, old_path: &str, new_path: &str) {
+        if let Some(fingerprint) = self.fingerprints.remove(old_path) {
+            self.fingerprints.insert(new_path.to_string(), fingerprint);
+        }
+    }
+
+    /// Handle a create event. Returns the old path when it matches a pending deletion.
+    pub fn on_added(&mut self, path: &str, fingerprint: FileFingerprint, now: Instant) -> Option<String> {
+        let matched = Self::best_match(&self.pending_deletions, &fingerprint)
+            .map(|idx| self.pending_deletions.remove(idx).path);
+
+        self.fingerprints.insert(path.to_string(), fingerprint.clone());
+        if matched.is_none() {
+            self.recent_additions.push(RecentEvent {
+                path: path.to_string(),
+                fingerprint,
+                at: now,
+            });
+        }
+        matched
+    }
+
+    /// Handle a delete event, matching it against files created just before
+    pub fn on_deleted(&mut self, path: &str, now: Instant) -> DeletionOutcome {
+        let Some(fingerprint) = self.fingerprints.remove(path) else {
+            return DeletionOutcome::Unknown;
+        };
+
+        if let Some(idx) = Self::best_match(&self.recent_additions, &fingerprint) {
+            return DeletionOutcome::Renamed(self.recent_additions.remove(idx).path);
+        }
+
+        self.pending_deletions.push(RecentEvent {
+            path: path.to_string(),
+            fingerprint,
+            at: now,
+        });
+        DeletionOutcome::Pending
+    }
+
+    /// Drop events older than the rename window, returning deletions that never
+    /// found a matching create and should now be treated as real deletions
+    pub fn expire(&mut self, now: Instant) -> Vec<String> {
+        let window = self.window;
+        let is_expired = |event: &RecentEvent| now.saturating_duration_since(event.at) > window;
+
+        self.recent_additions.retain(|event| !is_expired(event));
+        let (expired, pending): (Vec<RecentEvent>, Vec<RecentEvent>) =
+            self.pending_deletions.drain(..).partition(is_expired);
+        self.pending_deletions = pending;
+        expired.into_iter().map(|event| event.path).collect()
+    }
+
+    /// Give up on every pending deletion, returning their paths
+    pub fn drain_pending(&mut self) -> Vec<String> {
+        self.recent_additions.clear();
+        self.pending_deletions.drain(..).map(|event| event.path).collect()
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending_deletions.len()
+    }
+
+    fn best_match(events: &[RecentEvent], fingerprint: &FileFingerprint) -> Option<usize> {
+        events
+            .iter()
+            .enumerate()
+            .map(|(idx, event)| (idx, event.fingerprint.similarity(fingerprint)))
+            .filter(|(_, similarity)| *similarity >= RENAME_SIMILARITY)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(idx, _)| idx)
+    }
+}
+
+/// FNV-1a hash, stable across runs and platforms
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "export function a() {\n  return 1;\n}\n\nexport function b() {\n  return 2;\n}\n";
+
+    #[test]
+    fn test_similarity() {
+        let original = FileFingerprint::from_content(SOURCE);
+        let edited = FileFingerprint::from_content(&SOURCE.replace("return 2", "return 3"));
+        let unrelated = FileFingerprint::from_content("const x = 1;\n");
+
+        assert_eq!(original.similarity(&original), 1.0);
+        assert!(original.similarity(&edited) >= RENAME_SIMILARITY);
+        assert_eq!(original.similarity(&unrelated), 0.0);
+    }
+
+    #[test]
+    fn test_delete_then_create_is_a_rename() {
+        let mut tracker = RenameTracker::new();
+        let now = Instant::now();
+        tracker.record("src/old.ts", FileFingerprint::from_content(SOURCE));
+
+        assert_eq!(tracker.on_deleted("src/old.ts", now), DeletionOutcome::Pending);
+        assert_eq!(
+            tracker.on_added("lib/new.ts", FileFingerprint::from_content(SOURCE), now),
+            Some("src/old.ts".to_string())
+        );
+        assert_eq!(tracker.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_create_then_delete_is_a_rename() {
+        let mut tracker = RenameTracker::new();
+        let now = Instant::now();
+        tracker.record("src/old.ts", FileFingerprint::from_content(SOURCE));
+
+        assert_eq!(tracker.on_added("lib/new.ts", FileFingerprint::from_content(SOURCE), now), None);
+        assert_eq!(
+            tracker.on_deleted("src/old.ts", now),
+            DeletionOutcome::Renamed("lib/new.ts".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unmatched_deletions_expire() {
+        let mut tracker = RenameTracker::new();
+        let start = Instant::now();
+        tracker.record("src/old.ts", FileFingerprint::from_content(SOURCE));
+
+        assert_eq!(tracker.on_deleted("src/unknown.ts", start), DeletionOutcome::Unknown);
+        assert_eq!(tracker.on_deleted("src/old.ts", start), DeletionOutcome::Pending);
+        assert!(tracker.expire(start).is_empty());
+        assert_eq!(
+            tracker.expire(start + RENAME_WINDOW + Duration::from_secs(1)),
+            vec!["src/old.ts".to_string()]
+        );
+        assert_eq!(tracker.on_added("lib/new.ts", FileFingerprint::from_content(SOURCE), start), None);
+    }
+}
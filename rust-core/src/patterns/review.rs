@@ -0,0 +1,319 @@
+//! Review of a unified diff against learned patterns
+//!
+//! `PatternLearningEngine::review_diff` parses `git diff`/`diff -u` output,
+//! runs the violation passes over each changed file and keeps only findings on
+//! added lines, so a review comments on what the diff changes rather than on
+//! what it merely touches. Each comment names the concept (function, class,
+//! ...) its line belongs to.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::patterns::allocation::AllocationAnalyzer;
+use crate::patterns::budgets::ComplexityBudgetChecker;
+use crate::patterns::concurrency::ConcurrencyAnalyzer;
+use crate::patterns::secrets::SecretScanner;
+use crate::types::validation::{ErrorCode, ValidationError};
+use crate::types::{LineRange, ParseError, SemanticConcept};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Rule id of comments on declarations that break a learned naming convention
+pub const NAMING_CONVENTION_RULE: &str = "naming_convention";
+
+/// One inline review comment, anchored to an added line
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ReviewComment {
+    pub file_path: String,
+    /// Line in the new version of the file
+    pub line: u32,
+    pub rule_id: String,
+    pub severity: String,
+    pub message: String,
+    pub suggestion: Option<String>,
+    /// Innermost concept around the line, when there is one
+    pub concept: Option<String>,
+}
+
+/// A hunk's range in the new version of its file and the concepts it touches
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ReviewedHunk {
+    pub file_path: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub added_lines: u32,
+    pub removed_lines: u32,
+    pub concepts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct DiffReview {
+    /// Changed files in a supported language, in diff order
+    pub files_reviewed: Vec<String>,
+    pub hunks: Vec<ReviewedHunk>,
+    /// Ordered by file and line
+    pub comments: Vec<ReviewComment>,
+}
+
+/// One `@@` hunk of a file diff
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiffHunk {
+    /// First line of the hunk in the new version
+    pub new_start: u32,
+    pub new_count: u32,
+    pub added: u32,
+    pub removed: u32,
+}
+
+/// The changes a unified diff makes to one file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileDiff {
+    /// Path in the new version, without the `b/` prefix
+    pub path: String,
+    pub hunks: Vec<DiffHunk>,
+    /// Context and added lines of the new version, by line number
+    pub new_lines: BTreeMap<u32, String>,
+    pub added_lines: BTreeSet<u32>,
+}
+
+impl FileDiff {
+    /// The new version with every line outside the hunks left blank, so line
+    /// numbers match the real file
+    pub fn sparse_content(&self) -> String {
+        let last = self.new_lines.keys().next_back().copied().unwrap_or(0);
+        let mut content = String::new();
+        for line in 1..=last {
+            content.push_str(self.new_lines.get(&line).map(String::as_str).unwrap_or(""));
+            content.push('\n');
+        }
+        content
+    }
+
+    /// First added line within a range
+    pub fn first_added_in(&self, range: &LineRange) -> Option<u32> {
+        self.added_lines.range(range.start..=range.end.max(range.start)).next().copied()
+    }
+}
+
+pub struct UnifiedDiff;
+
+impl UnifiedDiff {
+    /// Files changed by a unified diff; deleted and binary files are left out
+    pub fn parse(diff_text: &str) -> Result<Vec<FileDiff>, ParseError> {
+        let mut files = Vec::new();
+        let mut current: Option<FileDiff> = None;
+        // Old and new lines still expected in the current hunk
+        let (mut old_remaining, mut new_remaining) = (0u32, 0u32);
+        let mut next_line = 0u32;
+
+        for line in diff_text.lines() {
+            if old_remaining > 0 || new_remaining > 0 {
+                let Some(file) = current.as_mut() else { break };
+                let hunk = file.hunks.last_mut().expect("hunk started");
+                match line.chars().next() {
+                    Some('+') => {
+                        file.new_lines.insert(next_line, line[1..].to_string());
+                        file.added_lines.insert(next_line);
+                        hunk.added += 1;
+                        next_line += 1;
+                        new_remaining = new_remaining.saturating_sub(1);
+                    }
+                    Some('-') => {
+                        hunk.removed += 1;
+                        old_remaining = old_remaining.saturating_sub(1);
+                    }
+                    Some('\\') => {}
+                    // Context, including blank context lines some tools strip to nothing
+                    _ => {
+                        file.new_lines.insert(next_line, line.get(1..).unwrap_or("").to_string());
+                        next_line += 1;
+                        old_remaining = old_remaining.saturating_sub(1);
+                        new_remaining = new_remaining.saturating_sub(1);
+                    }
+                }
+                continue;
+            }
+
+            if line.starts_with("diff --git ") || line.starts_with("--- ") {
+                if line.starts_with("--- ") && current.as_ref().is_some_and(|file| file.hunks.is_empty()) {
+                    // The `---` line of a file whose `diff --git` header was just read
+                    continue;
+                }
+                files.extend(current.take());
+                current = Some(FileDiff::default());
+            } else if let Some(path) = line.strip_prefix("+++ ") {
+                let file = current.get_or_insert_with(FileDiff::default);
+                file.path = Self::clean_path(path);
+            } else if let Some(header) = line.strip_prefix("@@ ") {
+                let Some(file) = current.as_mut().filter(|file| !file.path.is_empty()) else {
+                    return Err(Self::invalid(format!("Hunk without a file header: '{}'", line)));
+                };
+                let (old_range, new_range) = Self::hunk_ranges(header)
+                    .ok_or_else(|| Self::invalid(format!("Malformed hunk header: '{}'", line)))?;
+                (old_remaining, new_remaining) = (old_range.1, new_range.1);
+                next_line = new_range.0;
+                file.hunks.push(DiffHunk { new_start: new_range.0, new_count: new_range.1, ..Default::default() });
+            }
+        }
+        files.extend(current);
+
+        // `/dev/null` targets are deletions; files without hunks are binary or mode-only changes
+        files.retain(|file| file.path != "/dev/null" && !file.path.is_empty() && !file.hunks.is_empty());
+        if files.is_empty() {
+            return Err(Self::invalid("The diff changes no text files".to_string()));
+        }
+        Ok(files)
+    }
+
+    /// `(start, count)` of the old and new ranges of `-a,b +c,d @@ ...`
+    fn hunk_ranges(header: &str) -> Option<((u32, u32), (u32, u32))> {
+        let mut fields = header.split_whitespace();
+        let old = fields.next()?.strip_prefix('-')?;
+        let new = fields.next()?.strip_prefix('+')?;
+        let range = |range: &str| -> Option<(u32, u32)> {
+            match range.split_once(',') {
+                Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+                None => Some((range.parse().ok()?, 1)),
+            }
+        };
+        Some((range(old)?, range(new)?))
+    }
+
+    /// Path of a `+++` line without the `b/` prefix or a trailing timestamp
+    fn clean_path(path: &str) -> String {
+        let path = path.split('\t').next().unwrap_or(path).trim();
+        let path = path.strip_prefix('"').and_then(|p| p.strip_suffix('"')).unwrap_or(path);
+        path.strip_prefix("b/").unwrap_or(path).to_string()
+    }
+
+    fn invalid(message: String) -> ParseError {
+        ValidationError::new(ErrorCode::InvalidArgument, message).into()
+    }
+}
+
+/// How to fix a finding, by rule id, for the built-in passes
+pub fn suggestion_for(rule_id: &str) -> Option<String> {
+    ConcurrencyAnalyzer::advice(rule_id)
+        .or_else(|| AllocationAnalyzer::advice(rule_id))
+        .or_else(|| SecretScanner::advice(rule_id))
+        .or_else(|| ComplexityBudgetChecker::advice(rule_id))
+        .map(str::to_string)
+}
+
+/// Name of the innermost concept whose range contains a line
+pub fn enclosing_concept(concepts: &[SemanticConcept], line: u32) -> Option<String> {
+    concepts
+        .iter()
+        .filter(|concept| concept.line_range.start <= line && line <= concept.line_range.end)
+        .min_by_key(|concept| {
+            let span = concept.line_range.end.saturating_sub(concept.line_range.start);
+            (span, std::cmp::Reverse(concept.line_range.start))
+        })
+        .map(|concept| concept.name.clone())
+}
+
+/// Names of the concepts overlapping a hunk, in source order
+pub fn hunk_concepts(concepts: &[SemanticConcept], hunk: &DiffHunk) -> Vec<String> {
+    let end = hunk.new_start + hunk.new_count.saturating_sub(1);
+    let mut overlapping: Vec<&SemanticConcept> = concepts
+        .iter()
+        .filter(|concept| concept.line_range.start <= end && hunk.new_start <= concept.line_range.end)
+        .collect();
+    overlapping.sort_by_key(|concept| concept.line_range.start);
+    let mut names: Vec<String> = Vec::new();
+    for concept in overlapping {
+        if !names.contains(&concept.name) {
+            names.push(concept.name.clone());
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/app.ts b/src/app.ts
+index 1111111..2222222 100644
+--- a/src/app.ts
++++ b/src/app.ts
+@@ -1,4 +1,6 @@
+ export function start() {
+-  init();
++  init(config);
++  console.log('started');
++
+   return true;
+ }
+@@ -10,2 +12,3 @@ export function stop() {
+   cleanup();
++  --counter;
+ }
+diff --git a/assets/logo.png b/assets/logo.png
+Binary files a/assets/logo.png and b/assets/logo.png differ
+diff --git a/old.ts b/old.ts
+deleted file mode 100644
+--- a/old.ts
++++ /dev/null
+@@ -1 +0,0 @@
+-export const old = 1;
+";
+
+    #[test]
+    fn test_parses_hunks_into_new_line_numbers() {
+        let files = UnifiedDiff::parse(DIFF).unwrap();
+        assert_eq!(files.len(), 1);
+        let file = &files[0];
+        assert_eq!(file.path, "src/app.ts");
+        assert_eq!(file.added_lines.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4, 13]);
+        assert_eq!(file.new_lines[&3], "  console.log('started');");
+        // `--counter;` is an added line, not a file header
+        assert_eq!(file.new_lines[&13], "  --counter;");
+        assert_eq!(
+            file.hunks,
+            vec![
+                DiffHunk { new_start: 1, new_count: 6, added: 3, removed: 1 },
+                DiffHunk { new_start: 12, new_count: 3, added: 1, removed: 0 },
+            ]
+        );
+
+        let sparse = file.sparse_content();
+        assert_eq!(sparse.lines().count(), 14);
+        assert_eq!(sparse.lines().nth(12), Some("  --counter;"));
+        assert_eq!(file.first_added_in(&LineRange { start: 5, end: 14 }), Some(13));
+
+        assert!(UnifiedDiff::parse("").is_err());
+        assert!(UnifiedDiff::parse("+++ b/a.ts\n@@ nonsense @@\n").is_err());
+        let plain = "--- a.py\t2024-01-01\n+++ a.py\t2024-01-02\n@@ -1 +1 @@\n-x = 1\n+x = 2\n";
+        let plain = UnifiedDiff::parse(plain).unwrap();
+        assert_eq!((plain[0].path.as_str(), plain[0].added_lines.len()), ("a.py", 1));
+    }
+
+    #[test]
+    fn test_maps_lines_to_enclosing_concepts() {
+        let concept = |name: &str, start: u32, end: u32| SemanticConcept {
+            id: name.to_string(),
+            name: name.to_string(),
+            concept_type: "function".to_string(),
+            confidence: 1.0,
+            file_path: "src/app.ts".to_string(),
+            line_range: LineRange { start, end },
+            relationships: Default::default(),
+            metadata: Default::default(),
+        };
+        let concepts = vec![concept("Server", 1, 20), concept("start", 2, 6), concept("stop", 12, 15)];
+
+        assert_eq!(enclosing_concept(&concepts, 3).as_deref(), Some("start"));
+        assert_eq!(enclosing_concept(&concepts, 9).as_deref(), Some("Server"));
+        assert_eq!(enclosing_concept(&concepts, 30), None);
+        let hunk = DiffHunk { new_start: 5, new_count: 8, added: 2, removed: 0 };
+        assert_eq!(hunk_concepts(&concepts, &hunk), vec!["Server", "start", "stop"]);
+
+        assert!(suggestion_for("lock_across_await").is_some());
+        assert!(suggestion_for("max_cyclomatic_complexity").is_some());
+        assert_eq!(suggestion_for("no-console"), None);
+    }
+}
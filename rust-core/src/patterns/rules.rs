@@ -0,0 +1,475 @@
+//! User-defined pattern rules
+//!
+//! Teams can declare patterns in JSON or YAML instead of hard-coding them in Rust.
+//! Each rule matches with either a tree-sitter query or a regex, and matches are
+//! reported as ordinary `Pattern`s so they flow through the same APIs as learned
+//! patterns.
+//!
+//! ```yaml
+//! rules:
+//!   - name: no-console-log
+//!     description: Use the project logger instead of console.log
+//!     regex: 'console\.log\('
+//!     languages: [typescript, javascript]
+//!     severity: warning
+//!     mode: forbid
+//!   - name: async-functions
+//!     query: '(function_declaration "async" name: (identifier) @name)'
+//!     languages: [typescript]
+//! ```
+
+use crate::parsing::ParserManager;
+use crate::patterns::types::{Pattern, PatternExample};
+use crate::patterns::violations::Suppressions;
+use crate::types::{LineRange, ParseError};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tree_sitter::{Query, QueryCursor, StreamingIterator};
+
+/// Maximum number of examples kept per rule pattern
+const MAX_RULE_EXAMPLES: usize = 5;
+
+/// How serious a rule match is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleSeverity {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+impl RuleSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleSeverity::Info => "info",
+            RuleSeverity::Warning => "warning",
+            RuleSeverity::Error => "error",
+        }
+    }
+}
+
+/// Whether matches are detections of a wanted pattern or violations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleMode {
+    #[default]
+    Detect,
+    Forbid,
+}
+
+/// A single declarative pattern definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternRule {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Tree-sitter query; must contain at least one capture
+    #[serde(default)]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub regex: Option<String>,
+    /// Languages the rule applies to; empty means every language (regex rules only)
+    #[serde(default)]
+    pub languages: Vec<String>,
+    #[serde(default)]
+    pub severity: RuleSeverity,
+    #[serde(default)]
+    pub mode: RuleMode,
+}
+
+impl PatternRule {
+    fn applies_to(&self, language: &str) -> bool {
+        self.languages.is_empty() || self.languages.iter().any(|l| l == language)
+    }
+
+    fn pattern_id(&self) -> String {
+        format!("rule_{}", self.name)
+    }
+}
+
+/// Rule files may be a bare list or an object with a `rules` key
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RuleDocument {
+    List(Vec<PatternRule>),
+    Set { rules: Vec<PatternRule> },
+}
+
+impl RuleDocument {
+    fn into_rules(self) -> Vec<PatternRule> {
+        match self {
+            RuleDocument::List(rules) | RuleDocument::Set { rules } => rules,
+        }
+    }
+}
+
+/// A location where a rule matched
+#[derive(Debug, Clone)]
+pub struct RuleMatch {
+    pub rule_name: String,
+    pub file_path: String,
+    pub line_range: LineRange,
+    pub code: String,
+}
+
+enum Matcher {
+    Regex(Regex),
+    /// Compiled query per language
+    Query(HashMap<String, Query>),
+}
+
+struct CompiledRule {
+    rule: PatternRule,
+    matcher: Matcher,
+}
+
+/// Loads rule definitions and evaluates them against source files
+#[derive(Default)]
+pub struct RuleEngine {
+    rules: Vec<CompiledRule>,
+    parser_manager: Option<ParserManager>,
+}
+
+impl RuleEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse rule definitions from JSON or YAML without compiling them
+    pub fn parse_definitions(source: &str) -> Result<Vec<PatternRule>, ParseError> {
+        let trimmed = source.trim_start();
+        let document: RuleDocument = if trimmed.starts_with('{') || trimmed.starts_with('[') {
+            serde_json::from_str(trimmed).map_err(|e| {
+                ParseError::from_reason(format!("Invalid JSON rule definitions: {}", e))
+            })?
+        } else {
+            serde_yaml::from_str(source).map_err(|e| {
+                ParseError::from_reason(format!("Invalid YAML rule definitions: {}", e))
+            })?
+        };
+        Ok(document.into_rules())
+    }
+
+    /// Load rules from a JSON or YAML string, replacing existing rules with the same name.
+    /// Returns the number of rules loaded. Nothing is loaded if any rule is invalid.
+    pub fn load(&mut self, source: &str) -> Result<usize, ParseError> {
+        let rules = Self::parse_definitions(source)?;
+        let compiled = rules
+            .into_iter()
+            .map(|rule| self.compile(rule))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let count = compiled.len();
+        for rule in compiled {
+            self.rules.retain(|existing| existing.rule.name != rule.rule.name);
+            self.rules.push(rule);
+        }
+        Ok(count)
+    }
+
+    /// Load rules from a `.json`, `.yaml` or `.yml` file
+    pub fn load_file(&mut self, path: &str) -> Result<usize, ParseError> {
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            ParseError::from_reason(format!("Failed to read rule file {}: {}", path, e))
+        })?;
+        self.load(&source)
+    }
+
+    /// Loaded rule definitions in load order
+    pub fn rules(&self) -> Vec<&PatternRule> {
+        self.rules.iter().map(|compiled| &compiled.rule).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    fn compile(&mut self, rule: PatternRule) -> Result<CompiledRule, ParseError> {
+        if rule.name.trim().is_empty() {
+            return Err(ParseError::from_reason("Rule is missing a name"));
+        }
+
+        let matcher = match (&rule.query, &rule.regex) {
+            (Some(_), Some(_)) => {
+                return Err(ParseError::from_reason(format!(
+                    "Rule '{}' must define either `query` or `regex`, not both",
+                    rule.name
+                )))
+            }
+            (None, None) => {
+                return Err(ParseError::from_reason(format!(
+                    "Rule '{}' must define `query` or `regex`",
+                    rule.name
+                )))
+            }
+            (None, Some(pattern)) => Matcher::Regex(Regex::new(pattern).map_err(|e| {
+                ParseError::from_reason(format!("Rule '{}' has an invalid regex: {}", rule.name, e))
+            })?),
+            (Some(source), None) => {
+                if rule.languages.is_empty() {
+                    return Err(ParseError::from_reason(format!(
+                        "Rule '{}' uses a tree-sitter query and must list its languages",
+                        rule.name
+                    )));
+                }
+
+                let parser_manager = match self.parser_manager.take() {
+                    Some(manager) => manager,
+                    None => ParserManager::new()?,
+                };
+                let mut queries = HashMap::new();
+                let compiled = rule.languages.iter().try_for_each(|language| {
+                    let ts_language = parser_manager.get_tree_sitter_language(language)?;
+                    let query = Query::new(&ts_language, source).map_err(|e| {
+                        ParseError::from_reason(format!(
+                            "Rule '{}' has an invalid {} query: {}",
+                            rule.name, language, e
+                        ))
+                    })?;
+                    if query.capture_names().is_empty() {
+                        return Err(ParseError::from_reason(format!(
+                            "Rule '{}' query must contain at least one @capture",
+                            rule.name
+                        )));
+                    }
+                    queries.insert(language.clone(), query);
+                    Ok(())
+                });
+                self.parser_manager = Some(parser_manager);
+                compiled?;
+
+                Matcher::Query(queries)
+            }
+        };
+
+        Ok(CompiledRule { rule, matcher })
+    }
+
+    /// Evaluate every applicable rule against one file
+    ///
+    /// Matches silenced by an `in-memoria-disable-next-line` comment are dropped.
+    pub fn evaluate_file(&mut self, file_path: &str, content: &str, language: &str) -> Vec<RuleMatch> {
+        let mut matches = Vec::new();
+        let mut tree = None;
+
+        for compiled in &self.rules {
+            if !compiled.rule.applies_to(language) {
+                continue;
+            }
+
+            match &compiled.matcher {
+                Matcher::Regex(regex) => {
+                    for found in regex.find_iter(content) {
+                        matches.push(RuleMatch {
+                            rule_name: compiled.rule.name.clone(),
+                            file_path: file_path.to_string(),
+                            line_range: LineRange {
+                                start: line_at(content, found.start()),
+                                end: line_at(content, found.end()),
+                            },
+                            code: first_line(found.as_str()),
+                        });
+                    }
+                }
+                Matcher::Query(queries) => {
+                    let Some(query) = queries.get(language) else {
+                        continue;
+                    };
+                    if tree.is_none() {
+                        tree = self
+                            .parser_manager
+                            .as_mut()
+                            .and_then(|manager| manager.parse(content, language).ok());
+                    }
+                    let Some(tree) = tree.as_ref() else {
+                        continue;
+                    };
+
+                    let mut cursor = QueryCursor::new();
+                    let mut query_matches = cursor.matches(query, tree.root_node(), content.as_bytes());
+                    while let Some(m) = query_matches.next() {
+                        if let Some(capture) = m.captures.first() {
+                            let node = capture.node;
+                            matches.push(RuleMatch {
+                                rule_name: compiled.rule.name.clone(),
+                                file_path: file_path.to_string(),
+                                line_range: LineRange {
+                                    start: node.start_position().row as u32 + 1,
+                                    end: node.end_position().row as u32 + 1,
+                                },
+                                code: first_line(node.utf8_text(content.as_bytes()).unwrap_or("")),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        let suppressions = Suppressions::parse(content);
+        if !suppressions.is_empty() {
+            matches.retain(|m| !suppressions.is_suppressed(m.line_range.start, &m.rule_name));
+        }
+        matches
+    }
+
+    /// Aggregate matches into one `Pattern` per rule that matched
+    ///
+    /// Detect rules produce `custom_rule` patterns and forbid rules produce
+    /// `custom_rule_violation` patterns. Rules are declarative, so confidence is 1.0.
+    pub fn to_patterns(&self, matches: &[RuleMatch]) -> Vec<Pattern> {
+        self.rules
+            .iter()
+            .filter_map(|compiled| {
+                let rule = &compiled.rule;
+                let rule_matches: Vec<&RuleMatch> =
+                    matches.iter().filter(|m| m.rule_name == rule.name).collect();
+                if rule_matches.is_empty() {
+                    return None;
+                }
+
+                let description = rule.description.clone().unwrap_or_else(|| rule.name.clone());
+                let (pattern_type, description) = match rule.mode {
+                    RuleMode::Detect => ("custom_rule", description),
+                    RuleMode::Forbid => (
+                        "custom_rule_violation",
+                        format!(
+                            "[{}] rule '{}' violated: {}",
+                            rule.severity.as_str(),
+                            rule.name,
+                            description
+                        ),
+                    ),
+                };
+
+                let mut contexts = vec![format!("severity:{}", rule.severity.as_str())];
+                contexts.extend(rule.languages.iter().cloned());
+
+                Some(Pattern {
+                    id: rule.pattern_id(),
+                    pattern_type: pattern_type.to_string(),
+                    description,
+                    frequency: rule_matches.len() as u32,
+                    confidence: 1.0,
+                    examples: rule_matches
+                        .iter()
+                        .take(MAX_RULE_EXAMPLES)
+                        .map(|m| PatternExample {
+                            code: m.code.clone(),
+                            file_path: m.file_path.clone(),
+                            line_range: m.line_range.clone(),
+                        })
+                        .collect(),
+                    contexts,
+                })
+            })
+            .collect()
+    }
+}
+
+fn line_at(content: &str, byte_offset: usize) -> u32 {
+    content.as_bytes()[..byte_offset]
+        .iter()
+        .filter(|&&b| b == b'\n')
+        .count() as u32
+        + 1
+}
+
+fn first_line(text: &str) -> String {
+    text.lines().next().unwrap_or("").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML_RULES: &str = r#"
+rules:
+  - name: no-console-log
+    description: Use the project logger instead of console.log
+    regex: 'console\.log\('
+    languages: [typescript, javascript]
+    severity: warning
+    mode: forbid
+  - name: ts-functions
+    query: '(function_declaration name: (identifier) @name)'
+    languages: [typescript]
+"#;
+
+    const TS_SOURCE: &str = "function greet(name: string) {\n  console.log(name);\n}\n\nfunction farewell() {\n  console.log('bye');\n}\n";
+
+    #[test]
+    fn test_load_yaml_and_json() {
+        let mut engine = RuleEngine::new();
+        assert_eq!(engine.load(YAML_RULES).unwrap(), 2);
+        assert_eq!(engine.len(), 2);
+
+        let json = r#"[{"name": "todo", "regex": "TODO", "severity": "info"}]"#;
+        assert_eq!(engine.load(json).unwrap(), 1);
+        assert_eq!(engine.len(), 3);
+
+        // Reloading a rule by name replaces it
+        engine.load(json).unwrap();
+        assert_eq!(engine.len(), 3);
+        assert_eq!(engine.rules()[2].severity, RuleSeverity::Info);
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        let mut engine = RuleEngine::new();
+        assert!(engine.load(r#"[{"name": "both", "regex": "a", "query": "(x) @x", "languages": ["rust"]}]"#).is_err());
+        assert!(engine.load(r#"[{"name": "neither"}]"#).is_err());
+        assert!(engine.load(r#"[{"name": "bad", "regex": "("}]"#).is_err());
+        assert!(engine.load(r#"[{"name": "no-lang", "query": "(identifier) @id"}]"#).is_err());
+        assert!(engine.load(r#"[{"name": "no-capture", "query": "(identifier)", "languages": ["rust"]}]"#).is_err());
+        assert!(engine.is_empty());
+    }
+
+    #[test]
+    fn test_regex_and_query_rules_emit_patterns() {
+        let mut engine = RuleEngine::new();
+        engine.load(YAML_RULES).unwrap();
+
+        let matches = engine.evaluate_file("src/app.ts", TS_SOURCE, "typescript");
+        let patterns = engine.to_patterns(&matches);
+        assert_eq!(patterns.len(), 2);
+
+        let violation = patterns.iter().find(|p| p.id == "rule_no-console-log").unwrap();
+        assert_eq!(violation.pattern_type, "custom_rule_violation");
+        assert_eq!(violation.frequency, 2);
+        assert_eq!(violation.examples[0].line_range.start, 2);
+        assert!(violation.description.starts_with("[warning]"));
+        assert!(violation.contexts.contains(&"severity:warning".to_string()));
+
+        let detection = patterns.iter().find(|p| p.id == "rule_ts-functions").unwrap();
+        assert_eq!(detection.pattern_type, "custom_rule");
+        assert_eq!(detection.frequency, 2);
+        assert_eq!(detection.examples[0].code, "greet");
+        assert_eq!(detection.confidence, 1.0);
+    }
+
+    #[test]
+    fn test_suppressed_matches_are_dropped() {
+        let mut engine = RuleEngine::new();
+        engine.load(YAML_RULES).unwrap();
+
+        let source = "// in-memoria-disable-next-line no-console-log\nconsole.log(a);\n// in-memoria-disable-next-line other-rule\nconsole.log(b);\n";
+        let matches = engine.evaluate_file("src/app.ts", source, "typescript");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line_range.start, 4);
+    }
+
+    #[test]
+    fn test_rules_respect_languages() {
+        let mut engine = RuleEngine::new();
+        engine.load(YAML_RULES).unwrap();
+
+        let matches = engine.evaluate_file("script.py", "console.log('x')\n", "python");
+        assert!(matches.is_empty());
+    }
+}
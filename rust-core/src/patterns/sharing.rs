@@ -0,0 +1,260 @@
+//! Sharing learned patterns across repositories
+//!
+//! A team can seed a new repository with conventions learned elsewhere: one
+//! engine exports its patterns as a JSON document recording where they came
+//! from, another imports it and reconciles patterns both sides know with a
+//! [`MergeStrategy`].
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::patterns::taxonomy::PatternNamespace;
+use crate::patterns::types::Pattern;
+use crate::types::validation::{self, ErrorCode, ValidationError};
+use crate::types::ParseError;
+use serde::{Deserialize, Serialize};
+
+/// Name of the document in validation errors
+const EXPORT_DOCUMENT: &str = "pattern export";
+
+/// Version of the export layout written by this release
+pub const PATTERN_EXPORT_VERSION: u32 = 1;
+
+/// Learned patterns with the repository they were learned in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternExport {
+    pub version: u32,
+    /// Canonical path of the project the patterns were learned in
+    pub source_repo: String,
+    /// RFC 3339 time of the export; omitted in deterministic mode
+    #[serde(default)]
+    pub exported_at: Option<String>,
+    pub core_version: String,
+    pub patterns: Vec<Pattern>,
+}
+
+impl PatternExport {
+    pub fn new(source_repo: String, exported_at: Option<String>, patterns: Vec<Pattern>) -> Self {
+        PatternExport {
+            version: PATTERN_EXPORT_VERSION,
+            source_repo,
+            exported_at,
+            core_version: env!("CARGO_PKG_VERSION").to_string(),
+            patterns,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, ParseError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| ParseError::from_reason(format!("Failed to serialize patterns: {}", e)))
+    }
+
+    /// Read an export, rejecting documents written by a newer release
+    pub fn parse(data: &str) -> Result<Self, ParseError> {
+        let export: PatternExport = validation::parse_json(data, EXPORT_DOCUMENT)?;
+        if export.version > PATTERN_EXPORT_VERSION {
+            return Err(ValidationError::new(
+                ErrorCode::UnsupportedVersion,
+                format!(
+                    "The {} was written by a newer release (version {}; this release reads up to {})",
+                    EXPORT_DOCUMENT, export.version, PATTERN_EXPORT_VERSION
+                ),
+            )
+            .into());
+        }
+        Ok(export)
+    }
+}
+
+/// Which learned patterns to export; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct PatternExportFilter {
+    /// 'naming', 'structural', 'implementation' or 'violation'
+    pub namespace: Option<String>,
+    pub pattern_types: Option<Vec<String>>,
+    pub min_confidence: Option<f64>,
+}
+
+impl PatternExportFilter {
+    /// Check the filter's namespace before matching with it
+    pub fn validate(&self) -> Result<(), ParseError> {
+        if let Some(namespace) = &self.namespace {
+            parse_namespace(namespace)?;
+        }
+        Ok(())
+    }
+
+    pub fn matches(&self, pattern: &Pattern) -> bool {
+        let namespace = PatternNamespace::of(&pattern.pattern_type);
+        namespace != PatternNamespace::Activity
+            && self.namespace.as_deref().is_none_or(|name| namespace.as_str() == name)
+            && self
+                .pattern_types
+                .as_ref()
+                .is_none_or(|types| types.contains(&pattern.pattern_type))
+            && self.min_confidence.is_none_or(|min| pattern.confidence >= min)
+    }
+}
+
+/// A namespace name accepted by the pattern APIs
+pub fn parse_namespace(namespace: &str) -> Result<PatternNamespace, ParseError> {
+    PatternNamespace::parse(namespace).ok_or_else(|| {
+        ValidationError::new(
+            ErrorCode::InvalidArgument,
+            format!(
+                "Unknown pattern namespace '{}'; expected one of naming, structural, implementation, violation",
+                namespace
+            ),
+        )
+        .into()
+    })
+}
+
+/// How an imported pattern is reconciled with a local pattern of the same id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// Keep the local pattern; only patterns missing locally are added
+    #[default]
+    PreferLocal,
+    /// Replace the local pattern with the imported one
+    PreferImported,
+    /// Keep the local pattern at the mean of both confidences, adding the
+    /// imported examples and contexts it lacks
+    AverageConfidence,
+}
+
+impl MergeStrategy {
+    pub fn parse(name: &str) -> Result<Self, ParseError> {
+        match name.trim().to_lowercase().replace('_', "-").as_str() {
+            "prefer-local" => Ok(MergeStrategy::PreferLocal),
+            "prefer-imported" => Ok(MergeStrategy::PreferImported),
+            "average-confidence" => Ok(MergeStrategy::AverageConfidence),
+            _ => Err(ValidationError::new(
+                ErrorCode::InvalidArgument,
+                format!(
+                    "Unknown merge strategy '{}'; expected prefer-local, prefer-imported or average-confidence",
+                    name
+                ),
+            )
+            .into()),
+        }
+    }
+
+    /// The pattern to keep for an id both sides know, or `None` to keep the
+    /// local one unchanged
+    pub fn merge(&self, local: &Pattern, imported: Pattern) -> Option<Pattern> {
+        match self {
+            MergeStrategy::PreferLocal => None,
+            MergeStrategy::PreferImported => Some(imported),
+            MergeStrategy::AverageConfidence => {
+                let mut merged = local.clone();
+                merged.confidence = (local.confidence + imported.confidence) / 2.0;
+                for example in imported.examples {
+                    let known = merged.examples.iter().any(|e| {
+                        e.file_path == example.file_path && e.line_range.start == example.line_range.start
+                    });
+                    if !known {
+                        merged.examples.push(example);
+                    }
+                }
+                for context in imported.contexts {
+                    if !merged.contexts.contains(&context) {
+                        merged.contexts.push(context);
+                    }
+                }
+                Some(merged)
+            }
+        }
+    }
+}
+
+/// What `import_patterns` did with each pattern of an export
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct PatternImportReport {
+    pub source_repo: String,
+    pub exported_at: Option<String>,
+    /// Patterns not known locally
+    pub added: u32,
+    /// Local patterns replaced by or merged with the imported ones
+    pub merged: u32,
+    /// Local patterns kept unchanged
+    pub kept_local: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::types::PatternExample;
+    use crate::types::LineRange;
+
+    fn pattern(id: &str, pattern_type: &str, confidence: f64, file: &str) -> Pattern {
+        Pattern {
+            id: id.to_string(),
+            pattern_type: pattern_type.to_string(),
+            description: format!("{} pattern", pattern_type),
+            frequency: 3,
+            confidence,
+            examples: vec![PatternExample {
+                code: "fn example() {}".to_string(),
+                file_path: file.to_string(),
+                line_range: LineRange { start: 1, end: 1 },
+            }],
+            contexts: vec![file.to_string()],
+        }
+    }
+
+    #[test]
+    fn test_merge_strategies() {
+        let local = pattern("naming_snake", "naming", 0.6, "src/local.rs");
+        let imported = pattern("naming_snake", "naming", 0.9, "src/imported.rs");
+
+        assert!(MergeStrategy::parse("prefer-local").unwrap().merge(&local, imported.clone()).is_none());
+        let replaced = MergeStrategy::parse("prefer_imported").unwrap().merge(&local, imported.clone()).unwrap();
+        assert_eq!(replaced.confidence, 0.9);
+
+        let averaged = MergeStrategy::AverageConfidence.merge(&local, imported).unwrap();
+        assert!((averaged.confidence - 0.75).abs() < 1e-9);
+        assert_eq!(averaged.examples.len(), 2);
+        assert_eq!(averaged.contexts, vec!["src/local.rs".to_string(), "src/imported.rs".to_string()]);
+
+        assert!(MergeStrategy::parse("newest").is_err());
+    }
+
+    #[test]
+    fn test_export_round_trip_and_version_check() {
+        let export = PatternExport::new(
+            "/work/payments".to_string(),
+            Some("2026-01-01T00:00:00+00:00".to_string()),
+            vec![pattern("naming_camel", "naming", 0.8, "src/app.ts")],
+        );
+        let parsed = PatternExport::parse(&export.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.source_repo, "/work/payments");
+        assert_eq!(parsed.patterns.len(), 1);
+
+        let newer = export.to_json().unwrap().replace("\"version\": 1", "\"version\": 99");
+        let error = PatternExport::parse(&newer).unwrap_err();
+        assert!(error.to_string().contains("UNSUPPORTED_VERSION"));
+        assert!(PatternExport::parse("{\"patterns\": []}").is_err());
+    }
+
+    #[test]
+    fn test_filter_by_namespace_type_and_confidence() {
+        let naming = pattern("naming_camel", "naming", 0.8, "src/app.ts");
+        let weak = pattern("naming_snake", "naming", 0.3, "src/app.py");
+
+        let filter = PatternExportFilter {
+            namespace: Some("naming".to_string()),
+            min_confidence: Some(0.5),
+            ..Default::default()
+        };
+        assert!(filter.matches(&naming));
+        assert!(!filter.matches(&weak));
+        assert!(PatternExportFilter::default().matches(&weak));
+
+        let unknown = PatternExportFilter { namespace: Some("telemetry".to_string()), ..Default::default() };
+        assert!(unknown.validate().is_err());
+    }
+}
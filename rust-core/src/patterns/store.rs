@@ -0,0 +1,192 @@
+//! On-disk learning state for a project
+//!
+//! Learned patterns, activity counters and historical approaches are written to
+//! `.in-memoria/learned-state.json` under the project root, so approaches
+//! recorded in one session still inform predictions after a restart. The file
+//! records the project it belongs to; approaches are keyed by that path.
+//!
+//! Files written by older releases are upgraded in place on load (see
+//! [`migrations`](crate::patterns::migrations)); the original is kept next to it
+//! as `learned-state.json.v<version>.bak`.
+
+use crate::analysis::Tombstone;
+use crate::patterns::migrations;
+use crate::patterns::taxonomy::ActivityCounters;
+use crate::patterns::types::{HistoricalApproach, Pattern};
+use crate::types::validation::{self, ErrorCode, ValidationError};
+use crate::types::{ParseError, CONFIG_DIR};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File holding the learning state, inside the project's config directory
+pub const STATE_FILE: &str = "learned-state.json";
+
+/// Version of the state layout written by this release
+pub const STATE_VERSION: u32 = 2;
+
+/// Patterns and approaches persisted for one project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LearningStore {
+    pub version: u32,
+    pub project_path: String,
+    pub patterns: Vec<Pattern>,
+    /// Change and file activity, kept apart from the patterns
+    pub activity: ActivityCounters,
+    pub approaches: Vec<HistoricalApproach>,
+    /// Ids of the patterns pinned by hand
+    pub pinned: Vec<String>,
+    /// Removed files whose examples were subtracted from patterns
+    pub tombstones: Vec<Tombstone>,
+    /// Example files found on disk by earlier runs
+    pub walked_files: Vec<String>,
+}
+
+impl LearningStore {
+    pub fn new(project_path: &str) -> Self {
+        LearningStore {
+            version: STATE_VERSION,
+            project_path: project_key(project_path),
+            patterns: Vec::new(),
+            activity: ActivityCounters::default(),
+            approaches: Vec::new(),
+            pinned: Vec::new(),
+            tombstones: Vec::new(),
+            walked_files: Vec::new(),
+        }
+    }
+
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(CONFIG_DIR).join(STATE_FILE)
+    }
+
+    /// Read the state saved for a project; `None` when nothing was saved yet
+    ///
+    /// Approaches saved without a project are attributed to this one.
+    pub fn load(project_path: &str) -> Result<Option<Self>, ParseError> {
+        let path = Self::path(Path::new(project_path));
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            ParseError::from_reason(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let mut state: serde_json::Value = validation::parse_json(&content, STATE_FILE)?;
+        let written_version = migrations::migrate(&mut state, STATE_FILE)?;
+        let mut store: LearningStore = serde_json::from_value(state).map_err(|e| {
+            ValidationError::new(ErrorCode::SchemaMismatch, format!("Invalid {}: {}", STATE_FILE, e))
+        })?;
+
+        store.project_path = project_key(project_path);
+        for approach in &mut store.approaches {
+            if approach.project_path.is_none() {
+                approach.project_path = Some(store.project_path.clone());
+            }
+        }
+
+        if written_version < STATE_VERSION {
+            let backup = path.with_extension(format!("json.v{}.bak", written_version));
+            std::fs::copy(&path, &backup).map_err(|e| {
+                ParseError::from_reason(format!("Failed to back up {}: {}", path.display(), e))
+            })?;
+            store.save()?;
+        }
+        Ok(Some(store))
+    }
+
+    pub fn save(&self) -> Result<(), ParseError> {
+        let path = Self::path(Path::new(&self.project_path));
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                ParseError::from_reason(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| ParseError::from_reason(format!("Failed to serialize learning state: {}", e)))?;
+        std::fs::write(&path, content).map_err(|e| {
+            ParseError::from_reason(format!("Failed to write {}: {}", path.display(), e))
+        })
+    }
+}
+
+/// Canonical form of a project path, used to key approaches by project
+pub fn project_key(project_path: &str) -> String {
+    std::fs::canonicalize(project_path)
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| project_path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_string_lossy().into_owned();
+        assert!(LearningStore::load(&root).unwrap().is_none());
+
+        let mut store = LearningStore::new(&root);
+        store.approaches.push(HistoricalApproach {
+            problem_description: "add caching to the user lookup".to_string(),
+            approach_taken: "Wrap the repository in a read-through cache".to_string(),
+            patterns_used: vec!["repository".to_string()],
+            success_rating: 0.9,
+            complexity: "medium".to_string(),
+            domain: "web".to_string(),
+            project_path: None,
+            context: Default::default(),
+        });
+        store.save().unwrap();
+        assert!(dir.path().join(CONFIG_DIR).join(STATE_FILE).is_file());
+
+        let loaded = LearningStore::load(&root).unwrap().unwrap();
+        assert_eq!(loaded.version, STATE_VERSION);
+        assert_eq!(loaded.approaches.len(), 1);
+        assert_eq!(loaded.approaches[0].patterns_used, vec!["repository".to_string()]);
+        assert_eq!(loaded.approaches[0].project_path.as_deref(), Some(project_key(&root).as_str()));
+    }
+
+    #[test]
+    fn test_old_state_is_upgraded_in_place() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_string_lossy().into_owned();
+        let path = LearningStore::path(dir.path());
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let legacy = r#"{
+            "patterns": {
+                "naming_camel": {
+                    "id": "naming_camel", "pattern_type": "naming", "description": "camelCase functions",
+                    "frequency": 4, "confidence": 0.8, "examples": [], "contexts": ["typescript"]
+                }
+            },
+            "approaches": [{ "problemDescription": "cache lookups", "approachTaken": "memoize", "successRating": 0.8 }]
+        }"#;
+        std::fs::write(&path, legacy).unwrap();
+
+        let store = LearningStore::load(&root).unwrap().unwrap();
+        assert_eq!(store.version, STATE_VERSION);
+        assert_eq!(store.patterns[0].id, "naming_camel");
+        assert_eq!(store.approaches[0].approach_taken, "memoize");
+
+        // Rewritten in the current layout, with the original kept as a backup
+        let rewritten: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(rewritten["version"], serde_json::json!(STATE_VERSION));
+        assert_eq!(std::fs::read_to_string(path.with_extension("json.v0.bak")).unwrap(), legacy);
+    }
+
+    #[test]
+    fn test_newer_state_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let path = LearningStore::path(dir.path());
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, format!(r#"{{"version": {}, "patterns": []}}"#, STATE_VERSION + 1)).unwrap();
+
+        let error = LearningStore::load(&dir.path().to_string_lossy()).unwrap_err();
+        assert!(error.to_string().contains("[UNSUPPORTED_VERSION]"));
+        assert!(path.is_file());
+    }
+}
@@ -3,10 +3,11 @@
 #[cfg(feature = "napi-bindings")]
 use napi_derive::napi;
 
+use crate::analysis::{EventChannel, EventGraph, EventScanner};
+use crate::parsing::FileWalker;
 use crate::patterns::types::{Pattern, PatternExample, StructuralPattern, PatternExtractor};
 use crate::types::{ParseError, SemanticConcept, LineRange};
 use std::collections::{HashMap, HashSet};
-use walkdir::WalkDir;
 use std::fs;
 use std::path::Path;
 
@@ -148,10 +149,20 @@ impl StructuralPatternAnalyzer {
                 self.patterns.insert(pattern_key.clone(), structural_pattern);
             }
         }
+
+        // Messaging shows in client calls rather than in directory names
+        detected_patterns.extend(self.analyze_event_topology(path)?);
         
         Ok(detected_patterns)
     }
 
+    /// Detect message queue, pub/sub and event emitter patterns from the
+    /// producers and consumers found in code
+    pub fn analyze_event_topology(&mut self, path: &str) -> Result<Vec<Pattern>, ParseError> {
+        let graph = EventScanner::scan(Path::new(path))?;
+        Ok(self.event_topology_patterns(path, &graph))
+    }
+
     /// Analyze concepts for structural relationships
     pub fn analyze_concept_structures(&mut self, concepts: &[SemanticConcept]) -> Result<Vec<Pattern>, ParseError> {
         let mut detected_patterns = Vec::new();
@@ -164,10 +175,14 @@ impl StructuralPatternAnalyzer {
         
         // Analyze naming structure patterns
         let naming_structure = self.analyze_naming_structure_patterns(concepts);
+
+        // Analyze pub/sub naming
+        let event_naming = self.analyze_event_naming(concepts);
         
         detected_patterns.extend(file_organization);
         detected_patterns.extend(dependency_patterns);
         detected_patterns.extend(naming_structure);
+        detected_patterns.extend(event_naming);
         
         Ok(detected_patterns)
     }
@@ -237,31 +252,29 @@ impl StructuralPatternAnalyzer {
     fn analyze_directory_structure(&self, path: &str) -> Result<Vec<DirectoryAnalysis>, ParseError> {
         let mut analyses = Vec::new();
         
-        for entry in WalkDir::new(path).max_depth(5).into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_dir() {
-                let dir_path = entry.path();
-                let mut analysis = DirectoryAnalysis {
-                    path: dir_path.to_string_lossy().to_string(),
-                    subdirectories: Vec::new(),
-                    file_types: HashMap::new(),
-                    depth: entry.depth(),
-                };
-                
-                // Analyze immediate children
-                if let Ok(entries) = fs::read_dir(dir_path) {
-                    for child_entry in entries.filter_map(|e| e.ok()) {
-                        if child_entry.file_type().ok().is_some_and(|ft| ft.is_dir()) {
-                            if let Some(name) = child_entry.file_name().to_str() {
-                                analysis.subdirectories.push(name.to_string());
-                            }
-                        } else if let Some(extension) = child_entry.path().extension().and_then(|s| s.to_str()) {
-                            *analysis.file_types.entry(extension.to_string()).or_insert(0) += 1;
+        for directory in FileWalker::new().max_depth(5).directories(Path::new(path)) {
+            let dir_path = directory.path.as_path();
+            let mut analysis = DirectoryAnalysis {
+                path: dir_path.to_string_lossy().to_string(),
+                subdirectories: Vec::new(),
+                file_types: HashMap::new(),
+                depth: directory.depth,
+            };
+            
+            // Analyze immediate children
+            if let Ok(entries) = fs::read_dir(dir_path) {
+                for child_entry in entries.filter_map(|e| e.ok()) {
+                    if child_entry.file_type().ok().is_some_and(|ft| ft.is_dir()) {
+                        if let Some(name) = child_entry.file_name().to_str() {
+                            analysis.subdirectories.push(name.to_string());
                         }
+                    } else if let Some(extension) = child_entry.path().extension().and_then(|s| s.to_str()) {
+                        *analysis.file_types.entry(extension.to_string()).or_insert(0) += 1;
                     }
                 }
-                
-                analyses.push(analysis);
             }
+            
+            analyses.push(analysis);
         }
         
         Ok(analyses)
@@ -271,29 +284,26 @@ impl StructuralPatternAnalyzer {
     fn analyze_file_patterns(&self, path: &str) -> Result<HashMap<String, Vec<String>>, ParseError> {
         let mut file_patterns: HashMap<String, Vec<String>> = HashMap::new();
         
-        for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                let file_path = entry.path();
-                if let Some(file_name) = file_path.file_name().and_then(|n| n.to_str()) {
-                    // Categorize files by patterns
-                    if file_name.contains("Controller") {
-                        file_patterns.entry("controller".to_string()).or_default().push(file_name.to_string());
-                    }
-                    if file_name.contains("Model") {
-                        file_patterns.entry("model".to_string()).or_default().push(file_name.to_string());
-                    }
-                    if file_name.contains("View") {
-                        file_patterns.entry("view".to_string()).or_default().push(file_name.to_string());
-                    }
-                    if file_name.contains("Service") {
-                        file_patterns.entry("service".to_string()).or_default().push(file_name.to_string());
-                    }
-                    if file_name.contains("Repository") {
-                        file_patterns.entry("repository".to_string()).or_default().push(file_name.to_string());
-                    }
-                    if file_name.contains("Handler") {
-                        file_patterns.entry("handler".to_string()).or_default().push(file_name.to_string());
-                    }
+        for file_path in FileWalker::new().walk(Path::new(path)).files {
+            if let Some(file_name) = file_path.file_name().and_then(|n| n.to_str()) {
+                // Categorize files by patterns
+                if file_name.contains("Controller") {
+                    file_patterns.entry("controller".to_string()).or_default().push(file_name.to_string());
+                }
+                if file_name.contains("Model") {
+                    file_patterns.entry("model".to_string()).or_default().push(file_name.to_string());
+                }
+                if file_name.contains("View") {
+                    file_patterns.entry("view".to_string()).or_default().push(file_name.to_string());
+                }
+                if file_name.contains("Service") {
+                    file_patterns.entry("service".to_string()).or_default().push(file_name.to_string());
+                }
+                if file_name.contains("Repository") {
+                    file_patterns.entry("repository".to_string()).or_default().push(file_name.to_string());
+                }
+                if file_name.contains("Handler") {
+                    file_patterns.entry("handler".to_string()).or_default().push(file_name.to_string());
                 }
             }
         }
@@ -371,20 +381,18 @@ impl StructuralPatternAnalyzer {
     fn collect_structure_examples(&self, path: &str, signature: &ArchitectureSignature) -> Result<Vec<PatternExample>, ParseError> {
         let mut examples = Vec::new();
         
-        for entry in WalkDir::new(path).max_depth(3).into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_dir() {
-                let dir_name = entry.file_name().to_string_lossy().to_string();
-                
-                // Check if directory name matches required components
-                for component in &signature.required_components {
-                    if dir_name.to_lowercase().contains(&component.to_lowercase()) {
-                        examples.push(PatternExample {
-                            code: format!("Directory: {}", dir_name),
-                            file_path: entry.path().to_string_lossy().to_string(),
-                            line_range: LineRange { start: 1, end: 1 },
-                        });
-                        break;
-                    }
+        for directory in FileWalker::new().max_depth(3).directories(Path::new(path)) {
+            let dir_name = directory.path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            
+            // Check if directory name matches required components
+            for component in &signature.required_components {
+                if dir_name.to_lowercase().contains(&component.to_lowercase()) {
+                    examples.push(PatternExample {
+                        code: format!("Directory: {}", dir_name),
+                        file_path: directory.path.to_string_lossy().to_string(),
+                        line_range: LineRange { start: 1, end: 1 },
+                    });
+                    break;
                 }
             }
         }
@@ -394,6 +402,75 @@ impl StructuralPatternAnalyzer {
         Ok(examples)
     }
 
+    /// One pattern per messaging transport of the event graph, more confident
+    /// the more channels have both their producers and consumers in the project
+    fn event_topology_patterns(&mut self, path: &str, graph: &EventGraph) -> Vec<Pattern> {
+        let mut patterns = Vec::new();
+
+        for transport in &graph.transports {
+            let channels: Vec<&EventChannel> = graph.channels_of(transport).collect();
+            let producer_count: usize = channels.iter().map(|c| c.producers.len()).sum();
+            let consumer_count: usize = channels.iter().map(|c| c.consumers.len()).sum();
+            let connected = channels.iter().filter(|c| !c.producers.is_empty() && !c.consumers.is_empty()).count();
+            let confidence = (0.5 + 0.15 * connected as f64 + 0.05 * channels.len() as f64).min(0.95);
+            let pattern_name = Self::transport_pattern_name(transport);
+            let frequency = (producer_count + consumer_count) as u32;
+
+            let examples = channels
+                .iter()
+                .flat_map(|channel| {
+                    let produced = channel.producers.iter().map(move |client| (client, "produces", channel));
+                    let consumed = channel.consumers.iter().map(move |client| (client, "consumes", channel));
+                    produced.chain(consumed)
+                })
+                .take(10)
+                .map(|(client, role, channel)| PatternExample {
+                    code: format!("{} {} '{}'", client.symbol.as_deref().unwrap_or("module"), role, channel.name),
+                    file_path: Path::new(path).join(&client.file_path).to_string_lossy().to_string(),
+                    line_range: LineRange { start: client.line, end: client.line },
+                })
+                .collect();
+
+            let key = format!("events_{}", transport.replace('-', "_"));
+            patterns.push(Pattern {
+                id: format!("structural_{}", key),
+                pattern_type: "structural".to_string(),
+                description: format!(
+                    "{} across {} channels ({} producers, {} consumers)",
+                    pattern_name,
+                    channels.len(),
+                    producer_count,
+                    consumer_count
+                ),
+                frequency,
+                confidence,
+                examples,
+                contexts: vec!["architecture".to_string(), "events".to_string()],
+            });
+
+            self.patterns.insert(key, StructuralPattern {
+                pattern_type: pattern_name.to_string(),
+                frequency,
+                characteristics: channels.iter().take(10).map(|c| c.name.clone()).collect(),
+                confidence,
+            });
+        }
+
+        patterns
+    }
+
+    fn transport_pattern_name(transport: &str) -> &'static str {
+        match transport {
+            "kafka" => "Kafka messaging",
+            "sqs" => "SQS queues",
+            "rabbitmq" => "RabbitMQ messaging",
+            "redis" => "Redis pub/sub",
+            "nats" => "NATS messaging",
+            "event-emitter" => "Event emitter topology",
+            _ => "Publish/subscribe messaging",
+        }
+    }
+
     /// Analyze file organization patterns from concepts
     fn analyze_file_organization(&self, concepts: &[SemanticConcept]) -> Vec<Pattern> {
         let mut patterns = Vec::new();
@@ -442,9 +519,12 @@ impl StructuralPatternAnalyzer {
                 .and_then(|s| s.to_str())
                 .unwrap_or(&concept.file_path);
                 
-            for (relationship_type, target) in &concept.relationships {
+            for (relationship_type, targets) in &concept.relationships {
                 if relationship_type.contains("import") || relationship_type.contains("depends") {
-                    dependencies.entry(concept_file.to_string()).or_default().insert(target.clone());
+                    // Recorded references list several names joined by ", "
+                    for target in targets.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+                        dependencies.entry(concept_file.to_string()).or_default().insert(target.to_string());
+                    }
                 }
             }
         }
@@ -509,6 +589,47 @@ impl StructuralPatternAnalyzer {
         patterns
     }
 
+    /// Detect pub/sub naming: event and message types alongside the
+    /// publishers, subscribers and handlers named after them
+    fn analyze_event_naming(&self, concepts: &[SemanticConcept]) -> Vec<Pattern> {
+        const MESSAGE_SUFFIXES: &[&str] = &["Event", "Message", "Command"];
+        const PARTICIPANT_SUFFIXES: &[&str] =
+            &["Publisher", "Subscriber", "Producer", "Consumer", "Listener", "EventHandler", "MessageHandler"];
+        let named = |concept: &&SemanticConcept, suffixes: &[&str]| {
+            suffixes.iter().any(|suffix| concept.name.len() > suffix.len() && concept.name.ends_with(suffix))
+        };
+
+        let messages: Vec<&SemanticConcept> = concepts.iter().filter(|c| named(c, MESSAGE_SUFFIXES)).collect();
+        let participants: Vec<&SemanticConcept> = concepts.iter().filter(|c| named(c, PARTICIPANT_SUFFIXES)).collect();
+        let total = messages.len() + participants.len();
+        if messages.is_empty() || participants.is_empty() || total < 3 {
+            return Vec::new();
+        }
+
+        vec![Pattern {
+            id: "structural_event_naming".to_string(),
+            pattern_type: "structural".to_string(),
+            description: format!(
+                "Publish/subscribe naming detected ({} message types, {} publishers and subscribers)",
+                messages.len(),
+                participants.len()
+            ),
+            frequency: total as u32,
+            confidence: (0.6 + 0.05 * total as f64).min(0.9),
+            examples: messages
+                .iter()
+                .take(5)
+                .chain(participants.iter().take(5))
+                .map(|concept| PatternExample {
+                    code: format!("{} {}", concept.concept_type, concept.name),
+                    file_path: concept.file_path.clone(),
+                    line_range: concept.line_range.clone(),
+                })
+                .collect(),
+            contexts: vec!["naming".to_string(), "events".to_string()],
+        }]
+    }
+
     /// Detect God Object violations
     fn detect_god_object_violations(&self, concepts: &[SemanticConcept]) -> Vec<String> {
         let mut violations = Vec::new();
@@ -547,9 +668,11 @@ impl StructuralPatternAnalyzer {
         
         // Build dependency graph
         for concept in concepts {
-            for (rel_type, target) in &concept.relationships {
+            for (rel_type, targets) in &concept.relationships {
                 if rel_type.contains("depends") || rel_type.contains("import") {
-                    dependencies.entry(concept.name.clone()).or_default().insert(target.clone());
+                    for target in targets.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+                        dependencies.entry(concept.name.clone()).or_default().insert(target.to_string());
+                    }
                 }
             }
         }
@@ -583,10 +706,13 @@ impl StructuralPatternAnalyzer {
         for concept in concepts {
             let concept_layer = self.determine_layer(&concept.file_path, &layer_hierarchy);
             
-            for (rel_type, target) in &concept.relationships {
-                if rel_type.contains("depends") || rel_type.contains("import") {
+            for (rel_type, targets) in &concept.relationships {
+                if !(rel_type.contains("depends") || rel_type.contains("import")) {
+                    continue;
+                }
+                for target in targets.split(',').map(str::trim).filter(|t| !t.is_empty()) {
                     // Find target concept to determine its layer
-                    if let Some(target_concept) = concepts.iter().find(|c| c.name == *target) {
+                    if let Some(target_concept) = concepts.iter().find(|c| c.name == target) {
                         let target_layer = self.determine_layer(&target_concept.file_path, &layer_hierarchy);
                         
                         // Check for violations (higher layer depending on lower layer)
@@ -847,6 +973,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_event_topology_detection() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        fs::create_dir_all(base_path.join("src")).unwrap();
+        fs::write(
+            base_path.join("src/orders.ts"),
+            "import { Kafka } from 'kafkajs';\n\
+             export async function placeOrder() {\n  await producer.send({ topic: 'orders', messages: [] });\n}\n\
+             export async function startBilling() {\n  await consumer.subscribe({ topic: 'orders' });\n}\n",
+        )
+        .unwrap();
+
+        let mut analyzer = StructuralPatternAnalyzer::new();
+        let patterns = analyzer.analyze_codebase_structure(base_path.to_str().unwrap()).unwrap();
+
+        let kafka = patterns.iter().find(|p| p.id == "structural_events_kafka").unwrap();
+        assert_eq!(kafka.frequency, 2);
+        assert!(kafka.description.contains("1 producers, 1 consumers"));
+        assert!(kafka.examples.iter().any(|e| e.code == "placeOrder produces 'orders'" && e.line_range.start == 3));
+        assert!(kafka.confidence > 0.7);
+    }
+
+    #[test]
+    fn test_event_naming_detection() {
+        let concepts = vec![
+            create_test_concept("OrderPlacedEvent", "class", "events/OrderPlacedEvent.ts", 1, 10),
+            create_test_concept("PaymentFailedEvent", "class", "events/PaymentFailedEvent.ts", 1, 10),
+            create_test_concept("OrderPlacedListener", "class", "billing/OrderPlacedListener.ts", 1, 20),
+            create_test_concept("Event", "class", "events/Event.ts", 1, 5),
+        ];
+
+        let analyzer = StructuralPatternAnalyzer::new();
+        let patterns = analyzer.analyze_event_naming(&concepts);
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].frequency, 3);
+        assert!(analyzer.analyze_event_naming(&concepts[..2]).is_empty());
+    }
+
     #[test]
     fn test_god_object_detection() {
         let mut concepts = Vec::new();
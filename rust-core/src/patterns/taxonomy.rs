@@ -0,0 +1,223 @@
+//! Namespaces of pattern types, and counters for activity telemetry
+//!
+//! Incremental learning used to mint a pattern for every change event it saw:
+//! `change_time_hour_14`, `directory_language_src_js`, `file_type_json`. Those
+//! are counts of what happened in the repository, not ways the code is written,
+//! and they crowded genuine patterns out of the store and out of the context
+//! handed to agents. Every pattern type now belongs to a [`PatternNamespace`];
+//! types in the `activity` namespace are tallied in [`ActivityCounters`]
+//! grouped by family (`change_time_hour` -> `14`) and never become patterns.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Families of activity pattern types, longest first so the most specific matches
+const ACTIVITY_FAMILIES: &[&str] = &[
+    "new_file_with_exports",
+    "new_file_with_imports",
+    "file_with_functions",
+    "directory_language",
+    "file_modification",
+    "file_with_classes",
+    "file_with_imports",
+    "change_time_hour",
+    "directory_usage",
+    "file_creation",
+    "file_deletion",
+    "file_movement",
+    "new_file_size",
+    "language_usage",
+    "file_type",
+    "directory",
+    "change",
+];
+
+/// Broad kind of a pattern type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternNamespace {
+    Naming,
+    Structural,
+    Implementation,
+    /// Findings of rules and checks, `*_violation`
+    Violation,
+    /// Counts of change events, file types and directories
+    Activity,
+}
+
+impl PatternNamespace {
+    pub const ALL: [PatternNamespace; 5] = [
+        PatternNamespace::Naming,
+        PatternNamespace::Structural,
+        PatternNamespace::Implementation,
+        PatternNamespace::Violation,
+        PatternNamespace::Activity,
+    ];
+
+    /// Namespace of a pattern type, e.g. `naming_function_camelCase` -> naming
+    pub fn of(pattern_type: &str) -> Self {
+        if Self::activity_family(pattern_type).is_some() {
+            PatternNamespace::Activity
+        } else if pattern_type.ends_with("_violation") {
+            PatternNamespace::Violation
+        } else if pattern_type.starts_with("naming") {
+            PatternNamespace::Naming
+        } else if pattern_type.starts_with("structur") {
+            PatternNamespace::Structural
+        } else {
+            PatternNamespace::Implementation
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|namespace| namespace.as_str() == name)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PatternNamespace::Naming => "naming",
+            PatternNamespace::Structural => "structural",
+            PatternNamespace::Implementation => "implementation",
+            PatternNamespace::Violation => "violation",
+            PatternNamespace::Activity => "activity",
+        }
+    }
+
+    /// Family and bucket of an activity pattern type:
+    /// `change_time_hour_14` -> (`change_time_hour`, `14`)
+    pub fn activity_family(pattern_type: &str) -> Option<(&'static str, &str)> {
+        ACTIVITY_FAMILIES.iter().find_map(|family| {
+            if pattern_type == *family {
+                Some((*family, ""))
+            } else {
+                pattern_type
+                    .strip_prefix(family)
+                    .and_then(|rest| rest.strip_prefix('_'))
+                    .map(|bucket| (*family, bucket))
+            }
+        })
+    }
+}
+
+/// One activity count, e.g. family `change`, bucket `rename`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ActivityCounter {
+    pub family: String,
+    pub bucket: String,
+    pub count: u32,
+}
+
+/// Activity counts by family and bucket
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ActivityCounters {
+    counters: BTreeMap<String, BTreeMap<String, u32>>,
+}
+
+impl ActivityCounters {
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+    }
+
+    /// Count an activity pattern type; false for types outside the activity namespace
+    pub fn record(&mut self, pattern_type: &str, increment: u32) -> bool {
+        let Some((family, bucket)) = PatternNamespace::activity_family(pattern_type) else {
+            return false;
+        };
+        let count = self
+            .counters
+            .entry(family.to_string())
+            .or_default()
+            .entry(bucket.to_string())
+            .or_insert(0);
+        *count = count.saturating_add(increment);
+        true
+    }
+
+    pub fn merge(&mut self, other: &ActivityCounters) {
+        for (family, buckets) in &other.counters {
+            let merged = self.counters.entry(family.clone()).or_default();
+            for (bucket, count) in buckets {
+                let total = merged.entry(bucket.clone()).or_insert(0);
+                *total = total.saturating_add(*count);
+            }
+        }
+    }
+
+    /// Counters of one family, or of all families, highest count first
+    pub fn counters(&self, family: Option<&str>) -> Vec<ActivityCounter> {
+        let mut counters: Vec<ActivityCounter> = self
+            .counters
+            .iter()
+            .filter(|(name, _)| family.is_none_or(|family| family == name.as_str()))
+            .flat_map(|(family, buckets)| {
+                buckets.iter().map(move |(bucket, count)| ActivityCounter {
+                    family: family.clone(),
+                    bucket: bucket.clone(),
+                    count: *count,
+                })
+            })
+            .collect();
+        counters.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| (&a.family, &a.bucket).cmp(&(&b.family, &b.bucket)))
+        });
+        counters
+    }
+
+    pub fn get(&self, family: &str, bucket: &str) -> u32 {
+        self.counters
+            .get(family)
+            .and_then(|buckets| buckets.get(bucket))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_types_fall_into_namespaces() {
+        assert_eq!(PatternNamespace::of("change_time_hour_14"), PatternNamespace::Activity);
+        assert_eq!(PatternNamespace::of("directory_language_src_js"), PatternNamespace::Activity);
+        assert_eq!(PatternNamespace::of("naming_function_camelCase"), PatternNamespace::Naming);
+        assert_eq!(PatternNamespace::of("structural_renamed"), PatternNamespace::Structural);
+        assert_eq!(PatternNamespace::of("custom_rule_violation"), PatternNamespace::Violation);
+        assert_eq!(PatternNamespace::of("implementation_factory"), PatternNamespace::Implementation);
+        // A prefix must end at a word boundary
+        assert_eq!(PatternNamespace::of("changelog_format"), PatternNamespace::Implementation);
+        assert_eq!(PatternNamespace::parse("activity"), Some(PatternNamespace::Activity));
+        assert_eq!(PatternNamespace::parse("telemetry"), None);
+    }
+
+    #[test]
+    fn test_activity_is_aggregated_by_family() {
+        let mut counters = ActivityCounters::default();
+        assert!(counters.record("change_time_hour_14", 1));
+        assert!(counters.record("change_time_hour_14", 2));
+        assert!(counters.record("change_time_hour_9", 1));
+        assert!(counters.record("change_rename", 1));
+        assert!(!counters.record("naming_function_camelCase", 1));
+
+        assert_eq!(counters.get("change_time_hour", "14"), 3);
+        let hours = counters.counters(Some("change_time_hour"));
+        assert_eq!(hours.len(), 2);
+        assert_eq!(hours[0].bucket, "14");
+
+        let mut other = ActivityCounters::default();
+        other.record("change_rename", 4);
+        counters.merge(&other);
+        assert_eq!(counters.counters(None)[0], ActivityCounter {
+            family: "change".to_string(),
+            bucket: "rename".to_string(),
+            count: 5,
+        });
+        assert_eq!(serde_json::to_value(&counters).unwrap()["change"]["rename"], 5);
+    }
+}
@@ -0,0 +1,103 @@
+//! Technologies a project already has, for approach prediction
+//!
+//! Approach templates name the technologies they rely on in general terms
+//! (`web_framework`, `orm`, `message_queue`), while [`FrameworkDetector`]
+//! reports concrete frameworks (Express, Diesel). [`InstalledTechnologies`]
+//! maps one to the other so the predictor can prefer approaches the project
+//! is already set up for, say which framework provides what ("uses your
+//! existing Express setup"), and rank approaches needing technologies the
+//! project lacks lower. A template may also name a framework itself, e.g.
+//! `express` or `actix_web`.
+//!
+//! [`FrameworkDetector`]: crate::analysis::FrameworkDetector
+
+use crate::analysis::FrameworkInfo;
+use std::collections::BTreeMap;
+
+/// Template technologies each detected framework provides
+const FRAMEWORK_TECHNOLOGIES: &[(&str, &[&str])] = &[
+    ("Express", &["web_framework", "rest_api", "mvc"]),
+    ("Next.js", &["web_framework", "rest_api"]),
+    ("Angular", &["mvc", "dependency_injection"]),
+    ("Jest", &["testing_framework"]),
+    ("Vitest", &["testing_framework"]),
+    ("Actix Web", &["web_framework", "rest_api"]),
+    ("Rocket", &["web_framework", "rest_api"]),
+    ("Diesel", &["orm", "database"]),
+    ("SQLx", &["database"]),
+    ("Django", &["web_framework", "rest_api", "mvc", "orm", "database"]),
+    ("Flask", &["web_framework", "rest_api"]),
+    ("FastAPI", &["web_framework", "rest_api", "dependency_injection"]),
+    ("SQLAlchemy", &["orm", "database"]),
+    ("Gin", &["web_framework", "rest_api"]),
+    ("Echo", &["web_framework", "rest_api"]),
+    ("Fiber", &["web_framework", "rest_api"]),
+    ("GORM", &["orm", "database"]),
+];
+
+/// Detected frameworks by the template technologies they provide
+#[derive(Debug, Clone, Default)]
+pub struct InstalledTechnologies {
+    /// Technology to the names of the frameworks providing it, most confident first
+    providers: BTreeMap<String, Vec<String>>,
+}
+
+impl InstalledTechnologies {
+    /// `frameworks` as detected, most confident first
+    pub fn from_frameworks(frameworks: &[FrameworkInfo]) -> Self {
+        let mut providers: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for framework in frameworks {
+            let provided = FRAMEWORK_TECHNOLOGIES
+                .iter()
+                .find(|(name, _)| *name == framework.name)
+                .map_or(&[][..], |(_, technologies)| *technologies);
+            for technology in provided.iter().map(|t| t.to_string()).chain([Self::key(&framework.name)]) {
+                let names = providers.entry(technology).or_default();
+                if !names.contains(&framework.name) {
+                    names.push(framework.name.clone());
+                }
+            }
+        }
+        InstalledTechnologies { providers }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    /// Frameworks of the project providing `technology`, most confident first
+    pub fn providers(&self, technology: &str) -> &[String] {
+        self.providers.get(&Self::key(technology)).map_or(&[], Vec::as_slice)
+    }
+
+    /// `Actix Web` and `actix-web` both become `actix_web`
+    fn key(name: &str) -> String {
+        name.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frameworks_provide_template_technologies() {
+        let framework = |name: &str| FrameworkInfo {
+            name: name.to_string(),
+            version: None,
+            confidence: 0.9,
+            evidence: vec![],
+        };
+        let installed = InstalledTechnologies::from_frameworks(&[framework("Express"), framework("Actix Web")]);
+        assert_eq!(installed.providers("web_framework"), ["Express", "Actix Web"]);
+        assert_eq!(installed.providers("mvc"), ["Express"]);
+        assert_eq!(installed.providers("actix-web"), ["Actix Web"]);
+        assert_eq!(installed.providers("Express"), ["Express"]);
+        assert!(installed.providers("orm").is_empty());
+        assert!(InstalledTechnologies::from_frameworks(&[]).is_empty());
+    }
+}
@@ -2,8 +2,10 @@
 
 #[cfg(feature = "napi-bindings")]
 use napi_derive::napi;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use crate::types::{LineRange, ParseError};
+use std::collections::HashMap;
 
 // Simple error type for when napi is not available (from original implementation)
 #[derive(Debug)]
@@ -34,27 +36,9 @@ pub type ApiResult<T> = napi::Result<T>;
 #[cfg(not(feature = "napi-bindings"))]
 pub type ApiResult<T> = Result<T, SimpleError>;
 
-/// Core pattern representation
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "napi-bindings", napi(object))]
-pub struct Pattern {
-    pub id: String,
-    pub pattern_type: String,
-    pub description: String,
-    pub frequency: u32,
-    pub confidence: f64,
-    pub examples: Vec<PatternExample>,
-    pub contexts: Vec<String>,
-}
-
-/// Example of a pattern occurrence
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "napi-bindings", napi(object))]
-pub struct PatternExample {
-    pub code: String,
-    pub file_path: String,
-    pub line_range: LineRange,
-}
+// Pattern and its examples are defined next to LineRange; re-exported here for
+// the analyzers that have always imported them from this module
+pub use crate::types::{sort_patterns, Pattern, PatternExample};
 
 /// Result of pattern analysis
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,7 +51,8 @@ pub struct PatternAnalysisResult {
 }
 
 /// Prediction of coding approach based on patterns
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
 #[cfg_attr(feature = "napi-bindings", napi(object))]
 pub struct ApproachPrediction {
     pub approach: String,
@@ -77,6 +62,119 @@ pub struct ApproachPrediction {
     pub complexity: String,
 }
 
+/// An approach taken for a past problem and how well it worked out
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default, rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct HistoricalApproach {
+    pub problem_description: String,
+    pub approach_taken: String,
+    pub patterns_used: Vec<String>,
+    /// 0.0 (failed) to 1.0 (worked well)
+    pub success_rating: f64,
+    /// 'low', 'medium' or 'high'
+    pub complexity: String,
+    pub domain: String,
+    /// Root of the project the approach was recorded in; `None` for generic approaches
+    pub project_path: Option<String>,
+    pub context: HashMap<String, String>,
+}
+
+/// Filters for stored historical approaches; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ApproachQuery {
+    pub domain: Option<String>,
+    pub complexity: Option<String>,
+    pub min_success_rating: Option<f64>,
+    pub project_path: Option<String>,
+}
+
+/// Part of a codebase to learn from; unset fields don't narrow it
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct LearningScope {
+    /// Globs relative to the project root, e.g. `src/payments/**`; when set a
+    /// file must match one of them
+    pub include: Option<Vec<String>>,
+    /// Globs for files to leave out even when included
+    pub exclude: Option<Vec<String>>,
+    /// Directories or files relative to the root, each covering everything below it
+    pub paths: Option<Vec<String>>,
+}
+
+/// A recorded change to a pattern's confidence
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ConfidenceAdjustment {
+    /// RFC 3339 time of the change; omitted in deterministic mode
+    pub timestamp: Option<String>,
+    pub confidence: f64,
+    pub reason: String,
+}
+
+/// How many stored examples of a pattern come from one file
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct PatternFileEvidence {
+    pub file_path: String,
+    pub example_count: u32,
+}
+
+/// Evidence trail explaining why a pattern was learned
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct PatternExplanation {
+    pub pattern_id: String,
+    pub pattern_type: String,
+    pub description: String,
+    pub confidence: f64,
+    pub frequency: u32,
+    /// Analyzer that produced the pattern (naming, structural, implementation, ...)
+    pub analyzer: String,
+    pub contributing_files: Vec<PatternFileEvidence>,
+    pub confidence_history: Vec<ConfidenceAdjustment>,
+    /// IDs of similar patterns consolidated into this one
+    pub merged_from: Vec<String>,
+}
+
+/// A single text replacement that applies part of a fix
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct NamingEdit {
+    pub file_path: String,
+    /// 1-based line of the identifier
+    pub line: u32,
+    /// 1-based character column where the identifier starts
+    pub column: u32,
+    pub old_text: String,
+    pub new_text: String,
+}
+
+/// Suggested rename for an identifier that breaks the dominant naming convention
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct NamingFix {
+    pub old_name: String,
+    pub new_name: String,
+    /// Convention the new name follows, e.g. camelCase
+    pub convention: String,
+    pub concept_type: String,
+    pub file_path: String,
+    pub line_range: LineRange,
+    /// Names of concepts whose relationships point at the identifier
+    pub referenced_by: Vec<String>,
+    /// The declaration and every whole-word reference in the analyzed sources
+    pub edits: Vec<NamingEdit>,
+}
+
 /// Naming pattern information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NamingPattern {
@@ -112,6 +210,17 @@ pub enum ProblemComplexity {
     High,
 }
 
+impl ProblemComplexity {
+    /// Parse 'low', 'medium' or 'high'; anything else is medium
+    pub fn from_label(label: &str) -> Self {
+        match label.to_lowercase().as_str() {
+            "low" => ProblemComplexity::Low,
+            "high" => ProblemComplexity::High,
+            _ => ProblemComplexity::Medium,
+        }
+    }
+}
+
 impl std::fmt::Display for ProblemComplexity {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -0,0 +1,213 @@
+//! Structured violations and inline suppression comments
+//!
+//! Violation patterns (`custom_rule_violation`, `concurrency_violation`,
+//! `performance_violation`, `security_violation`, `naming_consistency_violation`)
+//! are flattened into one [`Violation`] per example.
+//! A single finding can be silenced with a comment on the line above it:
+//!
+//! ```text
+//! // in-memoria-disable-next-line no-console-log
+//! console.log(debugState);
+//! ```
+//!
+//! Several rule ids may be listed, separated by commas or spaces; without any
+//! id every rule is silenced for that line.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::patterns::types::Pattern;
+use crate::types::LineRange;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Comment marker that silences findings on the following line
+pub const SUPPRESSION_MARKER: &str = "in-memoria-disable-next-line";
+
+/// Prefixes of violation pattern ids, stripped to get the rule id
+const RULE_ID_PREFIXES: &[&str] =
+    &["rule_", "implementation_concurrency_risk_", "performance_allocation_", "security_secret_"];
+
+/// One reported finding of a rule
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct Violation {
+    /// The id used to suppress or disable the rule, e.g. 'no-console-log' or 'lock_across_await'
+    pub rule_id: String,
+    /// 'info', 'warning' or 'error'
+    pub severity: String,
+    pub file_path: String,
+    pub line_range: LineRange,
+    pub message: String,
+}
+
+impl Violation {
+    /// One violation per example of a `*_violation` pattern; nothing for other patterns
+    pub fn from_pattern(pattern: &Pattern) -> Vec<Violation> {
+        if !pattern.pattern_type.ends_with("_violation") {
+            return Vec::new();
+        }
+
+        let rule_id = Self::rule_id(pattern);
+        let (severity, message) = Self::split_severity(pattern);
+        pattern
+            .examples
+            .iter()
+            .map(|example| Violation {
+                rule_id: rule_id.clone(),
+                severity: severity.clone(),
+                file_path: example.file_path.clone(),
+                line_range: example.line_range.clone(),
+                message: message.clone(),
+            })
+            .collect()
+    }
+
+    /// Rule id of a violation pattern: the rule name or risk kind without the pattern id prefix
+    pub fn rule_id(pattern: &Pattern) -> String {
+        RULE_ID_PREFIXES
+            .iter()
+            .find_map(|prefix| pattern.id.strip_prefix(prefix))
+            .unwrap_or(&pattern.id)
+            .to_string()
+    }
+
+    /// Severity from the `severity:` context or the `[severity]` description
+    /// prefix, and the description without that prefix
+    fn split_severity(pattern: &Pattern) -> (String, String) {
+        let from_context = pattern
+            .contexts
+            .iter()
+            .find_map(|c| c.strip_prefix("severity:"))
+            .map(str::to_string);
+
+        let (from_description, message) = match pattern
+            .description
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once("] "))
+        {
+            Some((severity, message)) => (Some(severity.to_string()), message.to_string()),
+            None => (None, pattern.description.clone()),
+        };
+
+        let severity = from_context
+            .or(from_description)
+            .unwrap_or_else(|| "warning".to_string());
+        (severity, message)
+    }
+}
+
+/// Lines silenced by `in-memoria-disable-next-line` comments in one file
+#[derive(Debug, Clone, Default)]
+pub struct Suppressions {
+    /// 1-based line to the rule ids silenced on it; empty means every rule
+    lines: HashMap<u32, Vec<String>>,
+}
+
+impl Suppressions {
+    pub fn parse(content: &str) -> Self {
+        let mut lines = HashMap::new();
+        for (idx, line) in content.lines().enumerate() {
+            let Some(position) = line.find(SUPPRESSION_MARKER) else {
+                continue;
+            };
+            let rest = &line[position + SUPPRESSION_MARKER.len()..];
+            // Stop at the end of block comments (`*/`, `-->`)
+            let rest = rest.split("*/").next().unwrap_or("").split("-->").next().unwrap_or("");
+            let rule_ids: Vec<String> = rest
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .collect();
+            // The comment sits on line idx + 1, so it silences idx + 2
+            lines.insert(idx as u32 + 2, rule_ids);
+        }
+        Suppressions { lines }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Whether findings of `rule_id` on the 1-based `line` are silenced
+    pub fn is_suppressed(&self, line: u32, rule_id: &str) -> bool {
+        self.lines
+            .get(&line)
+            .is_some_and(|ids| ids.is_empty() || ids.iter().any(|id| id == rule_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::types::PatternExample;
+
+    fn violation_pattern(id: &str, description: &str, contexts: Vec<String>) -> Pattern {
+        Pattern {
+            id: id.to_string(),
+            pattern_type: "concurrency_violation".to_string(),
+            description: description.to_string(),
+            frequency: 2,
+            confidence: 0.75,
+            examples: vec![
+                PatternExample {
+                    code: "let guard = lock.lock().unwrap();".to_string(),
+                    file_path: "src/a.rs".to_string(),
+                    line_range: LineRange { start: 4, end: 4 },
+                },
+                PatternExample {
+                    code: "let guard = lock.lock().unwrap();".to_string(),
+                    file_path: "src/b.rs".to_string(),
+                    line_range: LineRange { start: 9, end: 9 },
+                },
+            ],
+            contexts,
+        }
+    }
+
+    #[test]
+    fn test_suppression_comments() {
+        let source = "\
+// in-memoria-disable-next-line no-console-log
+console.log(a);
+/* in-memoria-disable-next-line no-console-log, no-debugger */
+debugger;
+# in-memoria-disable-next-line
+time.sleep(1)
+console.log(b);
+";
+        let suppressions = Suppressions::parse(source);
+        assert!(suppressions.is_suppressed(2, "no-console-log"));
+        assert!(!suppressions.is_suppressed(2, "no-debugger"));
+        assert!(suppressions.is_suppressed(4, "no-debugger"));
+        assert!(suppressions.is_suppressed(6, "blocking_in_async"));
+        assert!(!suppressions.is_suppressed(7, "no-console-log"));
+        assert!(Suppressions::parse("let a = 1;\n").is_empty());
+    }
+
+    #[test]
+    fn test_violations_from_patterns() {
+        let pattern = violation_pattern(
+            "implementation_concurrency_risk_lock_across_await",
+            "[warning] lock held across an await point",
+            vec!["concurrency".to_string(), "severity:warning".to_string()],
+        );
+        let violations = Violation::from_pattern(&pattern);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].rule_id, "lock_across_await");
+        assert_eq!(violations[0].severity, "warning");
+        assert_eq!(violations[0].message, "lock held across an await point");
+        assert_eq!(violations[1].file_path, "src/b.rs");
+        assert_eq!(violations[1].line_range.start, 9);
+
+        let rule = violation_pattern("rule_no-eval", "[error] rule 'no-eval' violated: eval is unsafe", Vec::new());
+        assert_eq!(Violation::from_pattern(&rule)[0].severity, "error");
+        assert_eq!(Violation::rule_id(&rule), "no-eval");
+
+        let mut detection = rule.clone();
+        detection.pattern_type = "custom_rule".to_string();
+        assert!(Violation::from_pattern(&detection).is_empty());
+    }
+}
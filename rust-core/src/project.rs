@@ -0,0 +1,228 @@
+//! Project bootstrap
+//!
+//! Every saved artifact (learning state, concept graph, tags, checkpoints)
+//! lives in `.in-memoria/` under the project root. [`init_project`] creates
+//! that directory up front and records a `manifest.json` describing the
+//! project, so tools can set a project up once before learning instead of
+//! each writer discovering a missing or misplaced directory on its own.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::SourceWalker;
+use crate::types::validation::{self, ErrorCode, ValidationError};
+use crate::types::{paths, AnalysisConfig, ParseError, CONFIG_DIR};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Manifest file inside the project's config directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Version of the manifest layout written by this release
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Number of analyzable files of one language
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct LanguageSummary {
+    pub language: String,
+    pub file_count: u32,
+}
+
+/// Contents of `.in-memoria/manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ProjectManifest {
+    pub version: u32,
+    /// Release of the core that last wrote the manifest
+    pub core_version: String,
+    /// RFC 3339 time of the first initialization; kept when re-initializing
+    pub created_at: String,
+    /// Languages of the files analysis would pick up, most files first
+    pub languages: Vec<LanguageSummary>,
+}
+
+/// What `init_project` found and wrote
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct InitReport {
+    /// Canonical project root, with forward slashes
+    pub project_root: String,
+    pub data_dir: String,
+    pub manifest_path: String,
+    /// False when the data directory already existed
+    pub created: bool,
+    pub manifest: ProjectManifest,
+}
+
+/// Validate a project root, create its `.in-memoria` directory and write the
+/// manifest. Safe to call again: existing state is left alone and the
+/// language summary is refreshed.
+#[cfg_attr(feature = "napi-bindings", napi)]
+pub fn init_project(path: String) -> Result<InitReport, ParseError> {
+    validation::require_directory(&path)?;
+    let root = SourceWalker::canonical_root(Path::new(&path));
+
+    let data_dir = root.join(CONFIG_DIR);
+    if data_dir.exists() && !data_dir.is_dir() {
+        return Err(ValidationError::new(
+            ErrorCode::NotADirectory,
+            format!("{} exists but is not a directory", data_dir.display()),
+        )
+        .into());
+    }
+    let created = !data_dir.exists();
+    std::fs::create_dir_all(&data_dir).map_err(|e| {
+        ParseError::from_reason(format!("Failed to create {}: {}", data_dir.display(), e))
+    })?;
+
+    let manifest_path = data_dir.join(MANIFEST_FILE);
+    let created_at = match load_manifest(&manifest_path)? {
+        Some(existing) => existing.created_at,
+        None => chrono::Utc::now().to_rfc3339(),
+    };
+    let manifest = ProjectManifest {
+        version: MANIFEST_VERSION,
+        core_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at,
+        languages: language_summary(&root)?,
+    };
+
+    let content = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| ParseError::from_reason(format!("Failed to serialize manifest: {}", e)))?;
+    std::fs::write(&manifest_path, content).map_err(|e| {
+        ParseError::from_reason(format!("Failed to write {}: {}", manifest_path.display(), e))
+    })?;
+
+    Ok(InitReport {
+        project_root: display_path(&root),
+        data_dir: display_path(&data_dir),
+        manifest_path: display_path(&manifest_path),
+        created,
+        manifest,
+    })
+}
+
+/// Read a project's manifest; `None` when it was never initialized
+pub fn load_manifest(path: &Path) -> Result<Option<ProjectManifest>, ParseError> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| ParseError::from_reason(format!("Failed to read {}: {}", path.display(), e)))?;
+    let manifest: ProjectManifest = validation::parse_json(&content, MANIFEST_FILE)?;
+    if manifest.version > MANIFEST_VERSION {
+        return Err(ValidationError::new(
+            ErrorCode::UnsupportedVersion,
+            format!(
+                "{} was written by a newer release (version {}; this release reads up to {})",
+                MANIFEST_FILE, manifest.version, MANIFEST_VERSION
+            ),
+        )
+        .into());
+    }
+    Ok(Some(manifest))
+}
+
+/// Count the files analysis would pick up, by language, honoring the
+/// project's configured filters
+fn language_summary(root: &Path) -> Result<Vec<LanguageSummary>, ParseError> {
+    let mut config = AnalysisConfig::load(root)?;
+    config.project_root = Some(root.to_path_buf());
+
+    let mut counts: BTreeMap<String, u32> = BTreeMap::new();
+    for file in SourceWalker::new(config.follow_symlinks).walk(root, |_| true).files {
+        if config.exclusion(&file).is_none() {
+            let language = config.detect_file_language(&file);
+            *counts.entry(language).or_default() += 1;
+        }
+    }
+
+    let mut languages: Vec<LanguageSummary> = counts
+        .into_iter()
+        .map(|(language, file_count)| LanguageSummary { language, file_count })
+        .collect();
+    languages.sort_by(|a, b| b.file_count.cmp(&a.file_count).then_with(|| a.language.cmp(&b.language)));
+    Ok(languages)
+}
+
+fn display_path(path: &Path) -> String {
+    paths::normalize_path(&path.to_string_lossy())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> tempfile::TempDir {
+        tempfile::TempDir::new().unwrap()
+    }
+
+    #[test]
+    fn test_init_creates_data_dir_and_manifest() {
+        let dir = project();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/app.ts"), "export const app = 1;\n").unwrap();
+        std::fs::write(dir.path().join("src/user.ts"), "export class User {}\n").unwrap();
+        std::fs::write(dir.path().join("src/tool.py"), "def tool():\n    pass\n").unwrap();
+        std::fs::write(dir.path().join("README.md"), "# Demo\n").unwrap();
+
+        let report = init_project(dir.path().to_string_lossy().to_string()).unwrap();
+        assert!(report.created);
+        assert!(report.data_dir.ends_with("/.in-memoria"));
+        assert!(dir.path().join(CONFIG_DIR).join(MANIFEST_FILE).is_file());
+        assert_eq!(report.manifest.version, MANIFEST_VERSION);
+        assert_eq!(
+            report.manifest.languages,
+            vec![
+                LanguageSummary { language: "typescript".to_string(), file_count: 2 },
+                LanguageSummary { language: "python".to_string(), file_count: 1 },
+            ]
+        );
+
+        let saved = load_manifest(Path::new(&report.manifest_path)).unwrap().unwrap();
+        assert_eq!(saved.created_at, report.manifest.created_at);
+    }
+
+    #[test]
+    fn test_reinit_keeps_state_and_creation_time() {
+        let dir = project();
+        let path = dir.path().to_string_lossy().to_string();
+        let first = init_project(path.clone()).unwrap();
+        assert!(first.manifest.languages.is_empty());
+
+        let state = dir.path().join(CONFIG_DIR).join("learned-state.json");
+        std::fs::write(&state, "{}").unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "pub fn lib() {}\n").unwrap();
+
+        let again = init_project(path).unwrap();
+        assert!(!again.created);
+        assert_eq!(again.manifest.created_at, first.manifest.created_at);
+        assert_eq!(again.manifest.languages[0].language, "rust");
+        assert_eq!(std::fs::read_to_string(state).unwrap(), "{}");
+    }
+
+    #[test]
+    fn test_init_rejects_bad_roots_and_newer_manifests() {
+        let missing = init_project("/no/such/project".to_string());
+        assert!(missing.unwrap_err().to_string().contains("[PATH_NOT_FOUND]"));
+
+        let dir = project();
+        std::fs::write(dir.path().join(CONFIG_DIR), "").unwrap();
+        let blocked = init_project(dir.path().to_string_lossy().to_string());
+        assert!(blocked.unwrap_err().to_string().contains("[NOT_A_DIRECTORY]"));
+
+        std::fs::remove_file(dir.path().join(CONFIG_DIR)).unwrap();
+        std::fs::create_dir_all(dir.path().join(CONFIG_DIR)).unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_DIR).join(MANIFEST_FILE),
+            r#"{"version": 99, "coreVersion": "9.0.0", "createdAt": "2030-01-01T00:00:00Z", "languages": []}"#,
+        )
+        .unwrap();
+        let newer = init_project(dir.path().to_string_lossy().to_string());
+        assert!(newer.unwrap_err().to_string().contains("[UNSUPPORTED_VERSION]"));
+    }
+}
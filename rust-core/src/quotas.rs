@@ -0,0 +1,214 @@
+//! Per-project resource quotas
+//!
+//! A host serving many repositories from one process keeps one engine per
+//! project (see [`registry`](crate::registry)), and without limits a single
+//! giant repository holds as many concepts and patterns as it yields. A
+//! [`ProjectQuota`] caps what one engine keeps: after every learn, load or
+//! update call the concepts and patterns over the caps are evicted, those the
+//! quota's [`EvictionPolicy`] ranks lowest first, so each project is bounded
+//! by its own quota instead of by what the others left over. Patterns pinned
+//! by hand are never evicted. Parse trees are not cached between calls, so
+//! concepts and patterns are all a quota has to bound.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::patterns::types::Pattern;
+use crate::types::validation::{ErrorCode, ValidationError};
+use crate::types::{ParseError, SemanticConcept};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Caps on what one project's engine keeps; no cap where a limit is absent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ProjectQuota {
+    #[serde(default)]
+    pub max_concepts: Option<u32>,
+    #[serde(default)]
+    pub max_patterns: Option<u32>,
+    /// 'lowest-confidence' (the default) or 'least-used'
+    #[serde(default)]
+    pub eviction: Option<String>,
+}
+
+/// What one project's engine holds, against its quota
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ResourceUsage {
+    /// Root of the project loaded or learned, if any
+    pub project_root: Option<String>,
+    pub concept_count: u32,
+    pub pattern_count: u32,
+    pub max_concepts: Option<u32>,
+    pub max_patterns: Option<u32>,
+    pub eviction: String,
+    /// Evicted since the quota was set
+    pub evicted_concepts: u32,
+    pub evicted_patterns: u32,
+}
+
+/// Which concepts and patterns over a quota go first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Least confident first, then the least used
+    #[default]
+    LowestConfidence,
+    /// Concepts with the fewest relationships and patterns with the fewest
+    /// occurrences first, then the least confident
+    LeastUsed,
+}
+
+impl EvictionPolicy {
+    pub fn parse(name: Option<&str>) -> Result<Self, ParseError> {
+        match name {
+            None | Some("lowest-confidence") => Ok(EvictionPolicy::LowestConfidence),
+            Some("least-used") => Ok(EvictionPolicy::LeastUsed),
+            Some(other) => Err(ValidationError::new(
+                ErrorCode::InvalidArgument,
+                format!("Unknown eviction policy '{}'; expected lowest-confidence or least-used", other),
+            )
+            .into()),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EvictionPolicy::LowestConfidence => "lowest-confidence",
+            EvictionPolicy::LeastUsed => "least-used",
+        }
+    }
+
+    /// Ids of the concepts to evict so that `keep` remain
+    pub fn concepts_to_evict<'a>(
+        &self,
+        concepts: impl IntoIterator<Item = &'a SemanticConcept>,
+        keep: usize,
+    ) -> Vec<String> {
+        let ranked = concepts
+            .into_iter()
+            .map(|concept| (concept.confidence, concept.relationships.len() as f64, concept.id.clone()));
+        self.lowest(ranked, keep)
+    }
+
+    /// Ids of the patterns to evict so that `keep` remain
+    pub fn patterns_to_evict<'a>(&self, patterns: impl IntoIterator<Item = &'a Pattern>, keep: usize) -> Vec<String> {
+        let ranked = patterns
+            .into_iter()
+            .map(|pattern| (pattern.confidence, pattern.frequency as f64, pattern.id.clone()));
+        self.lowest(ranked, keep)
+    }
+
+    /// Ids of all but the `keep` highest ranked of (confidence, use, id)
+    fn lowest(&self, ranked: impl Iterator<Item = (f64, f64, String)>, keep: usize) -> Vec<String> {
+        let mut ranked: Vec<(f64, f64, String)> = match self {
+            EvictionPolicy::LowestConfidence => ranked.collect(),
+            EvictionPolicy::LeastUsed => ranked.map(|(confidence, used, id)| (used, confidence, id)).collect(),
+        };
+        let excess = ranked.len().saturating_sub(keep);
+        ranked.sort_by(|(a, a_next, a_id), (b, b_next, b_id)| {
+            a.partial_cmp(b)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a_next.partial_cmp(b_next).unwrap_or(Ordering::Equal))
+                .then_with(|| a_id.cmp(b_id))
+        });
+        ranked.into_iter().take(excess).map(|(_, _, id)| id).collect()
+    }
+}
+
+/// A validated quota and what it has evicted so far
+#[derive(Debug, Clone, Default)]
+pub struct QuotaLedger {
+    quota: ProjectQuota,
+    policy: EvictionPolicy,
+    evicted_concepts: u32,
+    evicted_patterns: u32,
+}
+
+impl QuotaLedger {
+    pub fn new(quota: ProjectQuota) -> Result<Self, ParseError> {
+        let policy = EvictionPolicy::parse(quota.eviction.as_deref())?;
+        for (name, limit) in [("maxConcepts", quota.max_concepts), ("maxPatterns", quota.max_patterns)] {
+            if limit == Some(0) {
+                return Err(
+                    ValidationError::new(ErrorCode::InvalidArgument, format!("{} must be at least 1", name)).into()
+                );
+            }
+        }
+        Ok(QuotaLedger { quota, policy, evicted_concepts: 0, evicted_patterns: 0 })
+    }
+
+    pub fn max_concepts(&self) -> Option<usize> {
+        self.quota.max_concepts.map(|max| max as usize)
+    }
+
+    pub fn max_patterns(&self) -> Option<usize> {
+        self.quota.max_patterns.map(|max| max as usize)
+    }
+
+    pub fn policy(&self) -> EvictionPolicy {
+        self.policy
+    }
+
+    pub fn record(&mut self, concepts: usize, patterns: usize) {
+        self.evicted_concepts = self.evicted_concepts.saturating_add(concepts as u32);
+        self.evicted_patterns = self.evicted_patterns.saturating_add(patterns as u32);
+    }
+
+    pub fn usage(&self, project_root: Option<String>, concept_count: usize, pattern_count: usize) -> ResourceUsage {
+        ResourceUsage {
+            project_root,
+            concept_count: concept_count as u32,
+            pattern_count: pattern_count as u32,
+            max_concepts: self.quota.max_concepts,
+            max_patterns: self.quota.max_patterns,
+            eviction: self.policy.name().to_string(),
+            evicted_concepts: self.evicted_concepts,
+            evicted_patterns: self.evicted_patterns,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LineRange;
+    use std::collections::HashMap;
+
+    fn concept(id: &str, confidence: f64, relationships: usize) -> SemanticConcept {
+        SemanticConcept {
+            id: id.to_string(),
+            name: id.to_string(),
+            concept_type: "function".to_string(),
+            confidence,
+            file_path: "src/app.ts".to_string(),
+            line_range: LineRange { start: 1, end: 1 },
+            relationships: (0..relationships).map(|i| (format!("r{}", i), "calls".to_string())).collect(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_policies_rank_what_goes_first() {
+        let concepts = [concept("a", 0.9, 0), concept("b", 0.5, 4), concept("c", 0.9, 2), concept("d", 0.5, 1)];
+        let lowest = EvictionPolicy::parse(None).unwrap();
+        assert_eq!(lowest.concepts_to_evict(&concepts, 2), vec!["d", "b"]);
+        let least_used = EvictionPolicy::parse(Some("least-used")).unwrap();
+        assert_eq!(least_used.concepts_to_evict(&concepts, 2), vec!["a", "d"]);
+        assert!(least_used.concepts_to_evict(&concepts, 4).is_empty());
+
+        let error = QuotaLedger::new(ProjectQuota { eviction: Some("random".to_string()), ..Default::default() });
+        assert!(error.unwrap_err().to_string().contains("[INVALID_ARGUMENT]"));
+        let error = QuotaLedger::new(ProjectQuota { max_patterns: Some(0), ..Default::default() });
+        assert!(error.unwrap_err().to_string().contains("maxPatterns must be at least 1"));
+
+        let mut ledger = QuotaLedger::new(ProjectQuota { max_concepts: Some(2), ..Default::default() }).unwrap();
+        ledger.record(2, 0);
+        let usage = ledger.usage(None, 2, 7);
+        assert_eq!((usage.evicted_concepts, usage.max_concepts, usage.max_patterns), (2, Some(2), None));
+        assert_eq!(usage.eviction, "lowest-confidence");
+    }
+}
@@ -0,0 +1,257 @@
+//! Isolated engines for each project a host serves
+//!
+//! A [`SharedEngine`] holds one project's concepts and patterns. An MCP server
+//! answering for several repositories keeps one engine per project in a
+//! [`ProjectRegistry`], keyed by the canonical project root, so learning one
+//! repository never leaks concepts or patterns into another. Opening a project
+//! loads its configuration and saved state; closing it drops the engine.
+//! Each engine can be given a [`ProjectQuota`], or the registry's default
+//! one when it opens, so one giant repository can't grow without bound
+//! while the others share the process.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::SourceWalker;
+use crate::quotas::{ProjectQuota, QuotaLedger, ResourceUsage};
+use crate::shared::SharedEngine;
+use crate::types::paths;
+use crate::types::validation::{self, ErrorCode, ValidationError};
+use crate::types::ParseError;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// An open project and the size of what its engine holds
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ProjectInfo {
+    /// Canonical project root, with forward slashes
+    pub root: String,
+    pub name: String,
+    pub concept_count: u32,
+    pub pattern_count: u32,
+}
+
+/// Cloneable handle to the engines of every open project
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "napi-bindings", napi)]
+pub struct ProjectRegistry {
+    projects: Arc<RwLock<BTreeMap<String, SharedEngine>>>,
+    /// Quota given to projects as they open, if any
+    default_quota: Arc<RwLock<Option<ProjectQuota>>>,
+}
+
+#[cfg_attr(feature = "napi-bindings", napi)]
+impl ProjectRegistry {
+    #[cfg_attr(feature = "napi-bindings", napi(constructor))]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a project, loading its configuration and saved state. Opening a
+    /// project that is already open, under any spelling of its root, returns it
+    /// unchanged.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn open_project(&self, path: String) -> Result<ProjectInfo, ParseError> {
+        validation::require_directory(&path)?;
+        let root = Self::root_key(&path);
+        if let Some(engine) = self.projects.read().await.get(&root).cloned() {
+            return Ok(Self::info(&root, &engine).await);
+        }
+
+        // Load outside the registry lock so other projects stay available
+        let engine = SharedEngine::new()?;
+        if let Some(quota) = self.default_quota.read().await.clone() {
+            engine.set_quota(quota).await?;
+        }
+        engine.load_project(root.clone()).await?;
+
+        let engine = self.projects.write().await.entry(root.clone()).or_insert(engine).clone();
+        Ok(Self::info(&root, &engine).await)
+    }
+
+    /// Drop a project's engine. Returns false when it was not open.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn close_project(&self, path: String) -> bool {
+        self.projects.write().await.remove(&Self::root_key(&path)).is_some()
+    }
+
+    /// Open projects in root order
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn list_projects(&self) -> Vec<ProjectInfo> {
+        let projects: Vec<(String, SharedEngine)> =
+            self.projects.read().await.iter().map(|(root, engine)| (root.clone(), engine.clone())).collect();
+
+        let mut infos = Vec::with_capacity(projects.len());
+        for (root, engine) in &projects {
+            infos.push(Self::info(root, engine).await);
+        }
+        infos
+    }
+
+    /// Quota given to every project opened from now on; `None` opens them
+    /// without one. Projects already open keep theirs.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn set_default_quota(&self, quota: Option<ProjectQuota>) -> Result<(), ParseError> {
+        if let Some(quota) = &quota {
+            QuotaLedger::new(quota.clone())?;
+        }
+        *self.default_quota.write().await = quota;
+        Ok(())
+    }
+
+    /// Replace the quota of the open project containing `path`, evicting
+    /// what is over it now
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn set_project_quota(&self, path: String, quota: ProjectQuota) -> Result<ResourceUsage, ParseError> {
+        self.get_project(path).await?.set_quota(quota).await
+    }
+
+    /// What each open project holds against its quota, in root order
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_resource_usage(&self) -> Vec<ResourceUsage> {
+        let projects: Vec<(String, SharedEngine)> =
+            self.projects.read().await.iter().map(|(root, engine)| (root.clone(), engine.clone())).collect();
+
+        let mut usage = Vec::with_capacity(projects.len());
+        for (root, engine) in &projects {
+            let mut project = engine.get_resource_usage().await;
+            project.project_root = Some(root.clone());
+            usage.push(project);
+        }
+        usage
+    }
+
+    /// The engine of the open project containing `path`, a project root or
+    /// any file or directory inside one; nested projects take precedence
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_project(&self, path: String) -> Result<SharedEngine, ParseError> {
+        let key = Self::root_key(&path);
+        let projects = self.projects.read().await;
+        projects
+            .iter()
+            .filter(|(root, _)| Path::new(&key).starts_with(root.as_str()))
+            .max_by_key(|(root, _)| root.len())
+            .map(|(_, engine)| engine.clone())
+            .ok_or_else(|| {
+                ValidationError::new(
+                    ErrorCode::InvalidArgument,
+                    format!("No open project contains {}; call open_project first", path),
+                )
+                .into()
+            })
+    }
+}
+
+impl ProjectRegistry {
+    /// The key a path is registered under: canonical where the path exists,
+    /// normalized either way
+    fn root_key(path: &str) -> String {
+        paths::normalize_path(&SourceWalker::canonical_root(Path::new(path)).to_string_lossy())
+    }
+
+    async fn info(root: &str, engine: &SharedEngine) -> ProjectInfo {
+        ProjectInfo {
+            root: root.to_string(),
+            name: root.rsplit('/').find(|segment| !segment.is_empty()).unwrap_or(root).to_string(),
+            concept_count: engine.concept_count().await,
+            pattern_count: engine.get_learned_patterns().await.len() as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(prefix: &str, file: &str, content: &str) -> tempfile::TempDir {
+        let dir = tempfile::Builder::new().prefix(prefix).tempdir().unwrap();
+        std::fs::write(dir.path().join(file), content).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_projects_are_isolated_by_root() {
+        let registry = ProjectRegistry::new();
+        let billing = project("billing", "invoice.ts", "export class InvoiceService {}\n");
+        let notes = project("notes", "notes.ts", "export function noteTitle() {}\n");
+        let billing_path = billing.path().to_string_lossy().to_string();
+
+        let opened = registry.open_project(billing_path.clone()).await.unwrap();
+        assert!(opened.name.starts_with("billing"));
+        registry.open_project(notes.path().to_string_lossy().to_string()).await.unwrap();
+
+        let engine = registry.get_project(billing_path).await.unwrap();
+        engine.learn_from_codebase(billing.path().to_string_lossy().to_string()).await.unwrap();
+        assert_eq!(engine.search_concepts("InvoiceService".to_string(), None).await.len(), 1);
+
+        // The other project's engine saw none of it
+        let file = notes.path().join("notes.ts").to_string_lossy().to_string();
+        let other = registry.get_project(file).await.unwrap();
+        assert_eq!(other.concept_count().await, 0);
+        assert!(other.get_learned_patterns().await.is_empty());
+
+        let listed = registry.list_projects().await;
+        assert_eq!(listed.len(), 2);
+        let learned = listed.iter().find(|p| p.root == opened.root).unwrap();
+        assert!(learned.concept_count > 0);
+    }
+
+    #[tokio::test]
+    async fn test_quotas_cap_each_project_separately() {
+        let registry = ProjectRegistry::new();
+        let quota = ProjectQuota { max_concepts: Some(2), eviction: Some("least-used".to_string()), ..Default::default() };
+        registry.set_default_quota(Some(quota)).await.unwrap();
+        let large = project(
+            "large",
+            "shop.ts",
+            "export class Cart {}\nexport class Order {}\nexport function total() {}\nexport function tax() {}\n",
+        );
+        let small = project("small", "notes.ts", "export function noteTitle() {}\n");
+        let large_path = large.path().to_string_lossy().to_string();
+        registry.open_project(large_path.clone()).await.unwrap();
+        registry.open_project(small.path().to_string_lossy().to_string()).await.unwrap();
+
+        let engine = registry.get_project(large_path.clone()).await.unwrap();
+        engine.learn_from_codebase(large_path.clone()).await.unwrap();
+        let usage = engine.get_resource_usage().await;
+        assert_eq!(usage.concept_count, 2);
+        assert!(usage.evicted_concepts >= 2);
+        assert_eq!(usage.eviction, "least-used");
+
+        // A new quota for one project leaves the other's alone
+        let lifted = ProjectQuota { max_concepts: Some(100), ..Default::default() };
+        let usage = registry.set_project_quota(large_path, lifted).await.unwrap();
+        assert_eq!((usage.max_concepts, usage.evicted_concepts), (Some(100), 0));
+        let all = registry.get_resource_usage().await;
+        assert_eq!(all.len(), 2);
+        assert!(all.iter().all(|usage| usage.project_root.is_some()));
+        assert!(all.iter().any(|usage| usage.max_concepts == Some(2)));
+
+        let invalid = ProjectQuota { eviction: Some("newest".to_string()), ..Default::default() };
+        assert!(registry.set_default_quota(Some(invalid)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_open_is_idempotent_and_close_forgets() {
+        let registry = ProjectRegistry::new();
+        let dir = project("reopened", "app.ts", "export const app = 1;\n");
+        let path = dir.path().to_string_lossy().to_string();
+        let name = dir.path().file_name().unwrap().to_string_lossy().to_string();
+
+        let first = registry.open_project(path.clone()).await.unwrap();
+        let again = registry.open_project(format!("{}/../{}/", path, name)).await.unwrap();
+        assert_eq!(first.root, again.root);
+        assert_eq!(registry.list_projects().await.len(), 1);
+
+        assert!(registry.close_project(path.clone()).await);
+        assert!(!registry.close_project(path.clone()).await);
+        let missing = registry.get_project(path).await;
+        assert!(missing.err().unwrap().to_string().contains("[INVALID_ARGUMENT]"));
+
+        let not_a_dir = registry.open_project("/no/such/project".to_string()).await;
+        assert!(not_a_dir.unwrap_err().to_string().contains("[PATH_NOT_FOUND]"));
+    }
+}
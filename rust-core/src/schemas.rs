@@ -0,0 +1,115 @@
+//! JSON Schemas for the objects the bindings return and accept
+//!
+//! The MCP layer validates tool arguments and results against these schemas
+//! and generates typed clients from them, so a field renamed in Rust shows up
+//! there instead of as a `-32602` from a stale hand-written shape. Schemas are
+//! derived from the types themselves with `schemars` and describe them as the
+//! Node bindings pass them: camelCase property names, optional fields absent
+//! or `null`. The one document [`get_schemas`] returns holds every type under
+//! `$defs`, so shared types such as `LineRange` are defined once and
+//! referenced as `#/$defs/LineRange`.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::{
+    BlueprintSummary, BuildTaskSummary, ConceptContext, GraphSlice, I18nSummary, ProjectBlueprint, RelationshipEdge, SafetyReport,
+    TestInventory, Tombstone, WarmStartReport,
+};
+use crate::changes::ChangeSet;
+use crate::context_pack::ContextPack;
+use crate::insights::{Insight, InsightInput};
+use crate::jsonl::JsonLinesExport;
+use crate::patterns::types::{
+    ApproachPrediction, ApproachQuery, HistoricalApproach, LearningScope, NamingFix, Pattern, PatternExplanation,
+};
+use crate::patterns::Violation;
+use crate::quotas::{ProjectQuota, ResourceUsage};
+use crate::sessions::{SessionActivity, SessionQuery, WorkSession};
+use crate::tokens::TruncatedText;
+use crate::types::SemanticConcept;
+use schemars::generate::SchemaSettings;
+use serde_json::{json, Value};
+
+/// JSON Schema dialect of the generated document
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Every public result and input type, with the types they contain, under `$defs`
+pub fn schema_document() -> Value {
+    let mut generator = SchemaSettings::draft2020_12().into_generator();
+    // Results
+    generator.subschema_for::<SemanticConcept>();
+    generator.subschema_for::<Pattern>();
+    generator.subschema_for::<PatternExplanation>();
+    generator.subschema_for::<ApproachPrediction>();
+    generator.subschema_for::<HistoricalApproach>();
+    generator.subschema_for::<NamingFix>();
+    generator.subschema_for::<Violation>();
+    generator.subschema_for::<ProjectBlueprint>();
+    generator.subschema_for::<BlueprintSummary>();
+    generator.subschema_for::<RelationshipEdge>();
+    generator.subschema_for::<GraphSlice>();
+    generator.subschema_for::<ConceptContext>();
+    generator.subschema_for::<WarmStartReport>();
+    generator.subschema_for::<Tombstone>();
+    generator.subschema_for::<TestInventory>();
+    generator.subschema_for::<BuildTaskSummary>();
+    generator.subschema_for::<I18nSummary>();
+    generator.subschema_for::<SafetyReport>();
+    generator.subschema_for::<ChangeSet>();
+    generator.subschema_for::<Insight>();
+    generator.subschema_for::<WorkSession>();
+    generator.subschema_for::<ContextPack>();
+    generator.subschema_for::<TruncatedText>();
+    generator.subschema_for::<ResourceUsage>();
+    generator.subschema_for::<JsonLinesExport>();
+    // Inputs
+    generator.subschema_for::<ApproachQuery>();
+    generator.subschema_for::<LearningScope>();
+    generator.subschema_for::<SessionActivity>();
+    generator.subschema_for::<SessionQuery>();
+    generator.subschema_for::<InsightInput>();
+    generator.subschema_for::<ProjectQuota>();
+
+    json!({
+        "$schema": SCHEMA_DIALECT,
+        "$defs": generator.take_definitions(true),
+    })
+}
+
+/// JSON document with the schema of every public result and input type
+#[cfg_attr(feature = "napi-bindings", napi)]
+pub fn get_schemas() -> String {
+    schema_document().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schemas_describe_the_binding_objects() {
+        let document: Value = serde_json::from_str(&get_schemas()).unwrap();
+        assert_eq!(document["$schema"], SCHEMA_DIALECT);
+        let definitions = document["$defs"].as_object().unwrap();
+        let roots = ["SemanticConcept", "Pattern", "ApproachPrediction", "ProjectBlueprint", "Violation", "InsightDocument"];
+        for name in roots {
+            assert!(definitions.contains_key(name), "missing {}", name);
+        }
+        // Contained types are defined once and referenced
+        assert!(definitions.contains_key("LineRange"));
+        assert!(definitions.contains_key("CouplingSummary"));
+
+        let concept = &definitions["SemanticConcept"];
+        assert_eq!(concept["properties"]["lineRange"]["$ref"], "#/$defs/LineRange");
+        assert!(concept["required"].as_array().unwrap().contains(&json!("conceptType")));
+
+        let blueprint = &definitions["ProjectBlueprint"];
+        assert!(blueprint["properties"].get("schemaVersion").is_some());
+        assert!(blueprint["properties"].get("schema_version").is_none());
+
+        // Optional fields are not required
+        let query = &definitions["SessionQuery"];
+        assert!(query.get("required").is_none_or(|required| required.as_array().unwrap().is_empty()));
+    }
+}
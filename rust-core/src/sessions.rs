@@ -0,0 +1,383 @@
+//! Work sessions recorded by agents
+//!
+//! A session is one agent's stretch of work on a feature: the tasks it did,
+//! the decisions it made, the files it touched and the concepts involved,
+//! with when it started and last changed. Sessions are started and updated
+//! explicitly, or implicitly by an insight's `sessionUpdate`, and saved to
+//! `.in-memoria/sessions.json`. [`SessionStore::query`] answers questions
+//! like "what was done recently under src/payments" from this structured
+//! record instead of free-text notes. Concepts are linked by name and
+//! project-relative file, as insights link them.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::insights::{ConceptLink, Insight};
+use crate::types::validation::{self, ErrorCode, ValidationError};
+use crate::types::{paths, ParseError, SemanticConcept, CONFIG_DIR};
+use chrono::{DateTime, FixedOffset};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File holding recorded sessions, inside the project's config directory
+pub const SESSIONS_FILE: &str = "sessions.json";
+
+const SESSIONS_VERSION: u32 = 1;
+
+/// Sessions returned by a query without a limit
+const DEFAULT_QUERY_LIMIT: usize = 10;
+
+/// One agent's recorded stretch of work
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct WorkSession {
+    pub id: String,
+    pub agent: String,
+    #[serde(default)]
+    pub feature: Option<String>,
+    #[serde(default)]
+    pub tasks: Vec<String>,
+    #[serde(default)]
+    pub decisions: Vec<String>,
+    /// Touched files, relative to the project root
+    #[serde(default)]
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub concepts: Vec<ConceptLink>,
+    /// Ids of insights contributed during the session
+    #[serde(default)]
+    pub insights: Vec<String>,
+    /// RFC 3339 times
+    pub started_at: String,
+    pub updated_at: String,
+    #[serde(default)]
+    pub ended_at: Option<String>,
+}
+
+impl WorkSession {
+    pub fn is_open(&self) -> bool {
+        self.ended_at.is_none()
+    }
+
+    /// Whether the session worked in `area`: a feature name, a concept name,
+    /// or a project-relative file or directory
+    pub fn in_area(&self, area: &str, root: Option<&str>) -> bool {
+        let area = area.trim();
+        let key = paths::relative_key(area, root);
+        let under = |file: &str| key == "." || paths::same_file(file, &key) || file.starts_with(&format!("{}/", key));
+        self.feature.as_deref().is_some_and(|feature| feature.eq_ignore_ascii_case(area))
+            || self.concepts.iter().any(|concept| concept.name.eq_ignore_ascii_case(area))
+            || self.files.iter().chain(self.concepts.iter().map(|concept| &concept.file_path)).any(|file| under(file))
+    }
+}
+
+/// Work to add to a session; lists are appended without duplicates
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default, rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct SessionActivity {
+    /// Replaces the session's feature when set
+    pub feature: Option<String>,
+    pub tasks: Vec<String>,
+    pub decisions: Vec<String>,
+    pub files: Vec<String>,
+    /// Concept names or ids involved
+    pub concepts: Vec<String>,
+}
+
+/// Which sessions to return; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default, rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct SessionQuery {
+    /// A feature name, concept name, or file or directory the session worked in
+    pub area: Option<String>,
+    pub agent: Option<String>,
+    /// Only sessions updated at or after this RFC 3339 time
+    pub since: Option<String>,
+    /// Only open (true) or only ended (false) sessions
+    pub open: Option<bool>,
+    /// At most this many sessions, 10 by default
+    pub limit: Option<u32>,
+}
+
+/// All sessions recorded for a project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SessionStore {
+    pub version: u32,
+    pub sessions: Vec<WorkSession>,
+}
+
+impl SessionStore {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(CONFIG_DIR).join(SESSIONS_FILE)
+    }
+
+    /// Read a project's sessions; `None` when none were saved
+    pub fn load(project_root: &Path) -> Result<Option<Self>, ParseError> {
+        let path = Self::path(project_root);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            ParseError::from_reason(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        Ok(Some(validation::parse_json(&content, SESSIONS_FILE)?))
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<(), ParseError> {
+        let mut store = self.clone();
+        store.version = SESSIONS_VERSION;
+        let path = Self::path(project_root);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                ParseError::from_reason(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+        let content = serde_json::to_string_pretty(&store)
+            .map_err(|e| ParseError::from_reason(format!("Failed to serialize sessions: {}", e)))?;
+        std::fs::write(&path, content).map_err(|e| {
+            ParseError::from_reason(format!("Failed to write {}: {}", path.display(), e))
+        })
+    }
+
+    /// Open a new session for `agent`
+    pub fn start(&mut self, agent: &str, feature: Option<String>, now: &str) -> Result<WorkSession, ParseError> {
+        if agent.trim().is_empty() {
+            return Err(ValidationError::new(ErrorCode::InvalidArgument, "agent must not be empty").into());
+        }
+        let session = WorkSession {
+            id: format!("session_{}", uuid::Uuid::new_v4().simple()),
+            agent: agent.trim().to_string(),
+            feature: feature.map(|feature| feature.trim().to_string()).filter(|feature| !feature.is_empty()),
+            tasks: Vec::new(),
+            decisions: Vec::new(),
+            files: Vec::new(),
+            concepts: Vec::new(),
+            insights: Vec::new(),
+            started_at: now.to_string(),
+            updated_at: now.to_string(),
+            ended_at: None,
+        };
+        self.sessions.push(session.clone());
+        Ok(session)
+    }
+
+    /// Add work to an open session, linking the stored concepts it names
+    pub fn record<'a>(
+        &mut self,
+        session_id: &str,
+        activity: SessionActivity,
+        concepts: impl IntoIterator<Item = &'a SemanticConcept>,
+        root: Option<&str>,
+        now: &str,
+    ) -> Result<WorkSession, ParseError> {
+        let links: Vec<ConceptLink> = concepts
+            .into_iter()
+            .filter(|concept| activity.concepts.iter().any(|named| *named == concept.name || *named == concept.id))
+            .map(|concept| ConceptLink {
+                name: concept.name.clone(),
+                file_path: paths::relative_key(&concept.file_path, root),
+            })
+            .collect();
+        let session = self.open_session(session_id)?;
+        if let Some(feature) = activity.feature.filter(|feature| !feature.trim().is_empty()) {
+            session.feature = Some(feature.trim().to_string());
+        }
+        extend_unique(&mut session.tasks, activity.tasks);
+        extend_unique(&mut session.decisions, activity.decisions);
+        extend_unique(&mut session.files, activity.files.iter().map(|file| paths::relative_key(file, root)));
+        extend_unique(&mut session.concepts, links);
+        session.updated_at = now.to_string();
+        Ok(session.clone())
+    }
+
+    /// Close an open session
+    pub fn end(&mut self, session_id: &str, now: &str) -> Result<WorkSession, ParseError> {
+        let session = self.open_session(session_id)?;
+        session.ended_at = Some(now.to_string());
+        session.updated_at = now.to_string();
+        Ok(session.clone())
+    }
+
+    /// Record an insight in its agent's open session for the same feature,
+    /// opening one if there is none. Insights without a `sessionUpdate` are
+    /// not part of a session.
+    pub fn record_insight(&mut self, insight: &Insight, root: Option<&str>, now: &str) -> Option<WorkSession> {
+        let update = insight.session_update.as_ref()?;
+        let feature = update.feature.as_deref().map(str::trim).filter(|feature| !feature.is_empty());
+        let existing = self.sessions.iter().rposition(|session| {
+            session.is_open()
+                && session.agent == insight.source_agent
+                && feature.is_none_or(|feature| session.feature.as_deref() == Some(feature))
+        });
+        let index = match existing {
+            Some(index) => index,
+            None => {
+                self.start(&insight.source_agent, feature.map(str::to_string), now).ok()?;
+                self.sessions.len() - 1
+            }
+        };
+
+        let session = &mut self.sessions[index];
+        extend_unique(&mut session.tasks, update.tasks.iter().cloned());
+        extend_unique(&mut session.decisions, update.decisions.iter().cloned());
+        extend_unique(&mut session.files, update.files.iter().map(|file| paths::relative_key(file, root)));
+        extend_unique(&mut session.concepts, insight.related_concepts.iter().cloned());
+        extend_unique(&mut session.insights, [insight.id.clone()]);
+        session.updated_at = now.to_string();
+        Some(session.clone())
+    }
+
+    /// Sessions matching `query`, most recently updated first
+    pub fn query(&self, query: &SessionQuery, root: Option<&str>) -> Result<Vec<WorkSession>, ParseError> {
+        let since = query.since.as_deref().map(parse_time).transpose()?;
+        let mut sessions: Vec<(Option<DateTime<FixedOffset>>, &WorkSession)> = self
+            .sessions
+            .iter()
+            .map(|session| (DateTime::parse_from_rfc3339(&session.updated_at).ok(), session))
+            .filter(|(updated, session)| {
+                query.area.as_deref().is_none_or(|area| session.in_area(area, root))
+                    && query.agent.as_deref().is_none_or(|agent| session.agent == agent)
+                    && query.open.is_none_or(|open| session.is_open() == open)
+                    && since.is_none_or(|since| updated.is_some_and(|updated| updated >= since))
+            })
+            .collect();
+        // Among sessions updated at the same time, the last recorded comes first
+        sessions.reverse();
+        sessions.sort_by_key(|(updated, _)| std::cmp::Reverse(*updated));
+
+        let limit = query.limit.map_or(DEFAULT_QUERY_LIMIT, |limit| limit as usize);
+        Ok(sessions.into_iter().take(limit).map(|(_, session)| session.clone()).collect())
+    }
+
+    fn open_session(&mut self, session_id: &str) -> Result<&mut WorkSession, ParseError> {
+        let session = self.sessions.iter_mut().find(|session| session.id == session_id).ok_or_else(|| {
+            ValidationError::new(ErrorCode::InvalidArgument, format!("Unknown session: {}", session_id))
+        })?;
+        if !session.is_open() {
+            return Err(ValidationError::new(
+                ErrorCode::InvalidArgument,
+                format!("Session {} has ended", session_id),
+            )
+            .into());
+        }
+        Ok(session)
+    }
+}
+
+fn parse_time(time: &str) -> Result<DateTime<FixedOffset>, ParseError> {
+    DateTime::parse_from_rfc3339(time).map_err(|e| {
+        ValidationError::new(ErrorCode::InvalidArgument, format!("since must be an RFC 3339 time: {}", e)).into()
+    })
+}
+
+fn extend_unique<T: PartialEq>(items: &mut Vec<T>, new: impl IntoIterator<Item = T>) {
+    for item in new {
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::InsightContent;
+    use crate::types::LineRange;
+    use std::collections::HashMap;
+
+    fn concept(name: &str, file_path: &str) -> SemanticConcept {
+        SemanticConcept {
+            id: format!("{}::{}", file_path, name),
+            name: name.to_string(),
+            concept_type: "class".to_string(),
+            confidence: 0.9,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start: 1, end: 10 },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_sessions_record_work_and_answer_area_queries() {
+        let mut store = SessionStore::default();
+        let concepts = [concept("PaymentClient", "/repo/src/payments/client.ts")];
+        let payments = store.start("planner", Some("payments".to_string()), "2026-03-01T10:00:00Z").unwrap();
+        let activity = SessionActivity {
+            tasks: vec!["Add retries".to_string()],
+            files: vec!["/repo/src/payments/client.ts".to_string()],
+            concepts: vec!["PaymentClient".to_string()],
+            ..Default::default()
+        };
+        let recorded = store.record(&payments.id, activity, &concepts, Some("/repo"), "2026-03-01T11:00:00Z").unwrap();
+        assert_eq!(recorded.files, vec!["src/payments/client.ts"]);
+        assert_eq!(recorded.concepts[0].name, "PaymentClient");
+
+        let users = store.start("planner", Some("users".to_string()), "2026-03-02T09:00:00Z").unwrap();
+        store.end(&users.id, "2026-03-02T10:00:00Z").unwrap();
+        assert!(store.record(&users.id, SessionActivity::default(), &concepts, None, "2026-03-02T11:00:00Z").is_err());
+
+        let in_area = |area: &str| {
+            let query = SessionQuery { area: Some(area.to_string()), ..Default::default() };
+            store.query(&query, Some("/repo")).unwrap().into_iter().map(|s| s.id).collect::<Vec<_>>()
+        };
+        assert_eq!(in_area("src/payments"), vec![payments.id.clone()]);
+        assert_eq!(in_area("/repo/src/payments/client.ts"), vec![payments.id.clone()]);
+        assert_eq!(in_area("paymentclient"), vec![payments.id.clone()]);
+        assert_eq!(in_area("users"), vec![users.id.clone()]);
+        assert!(in_area("src/pay").is_empty());
+
+        let recent = store.query(&SessionQuery::default(), None).unwrap();
+        assert_eq!(recent[0].id, users.id);
+        let since = SessionQuery { since: Some("2026-03-02T00:00:00+00:00".to_string()), ..Default::default() };
+        assert_eq!(store.query(&since, None).unwrap().len(), 1);
+        let bad = SessionQuery { since: Some("yesterday".to_string()), ..Default::default() };
+        assert!(store.query(&bad, None).unwrap_err().to_string().contains("[INVALID_ARGUMENT]"));
+    }
+
+    #[test]
+    fn test_insights_join_their_agents_open_session() {
+        let insight = |id: &str, feature: &str, files: &[&str]| Insight {
+            id: id.to_string(),
+            insight_type: "best_practice".to_string(),
+            content: InsightContent { description: "Validate webhooks".to_string(), ..Default::default() },
+            confidence: 0.8,
+            source_agent: "reviewer".to_string(),
+            session_update: Some(crate::insights::SessionUpdate {
+                feature: Some(feature.to_string()),
+                tasks: vec!["Review webhooks".to_string()],
+                files: files.iter().map(|file| file.to_string()).collect(),
+                decisions: Vec::new(),
+            }),
+            impact_prediction: None,
+            related_concepts: Vec::new(),
+            related_patterns: Vec::new(),
+            recorded_at: None,
+        };
+
+        let mut store = SessionStore::default();
+        let hooks = insight("a", "webhooks", &["/repo/src/hooks.ts"]);
+        let first = store.record_insight(&hooks, Some("/repo"), "2026-03-01T10:00:00Z").unwrap();
+        let again = insight("b", "webhooks", &["src/hooks.ts"]);
+        let second = store.record_insight(&again, Some("/repo"), "2026-03-01T10:30:00Z").unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(second.tasks.len(), 1);
+        assert_eq!(second.files, vec!["src/hooks.ts"]);
+        assert_eq!(second.insights, vec!["a", "b"]);
+
+        let other = store.record_insight(&insight("c", "billing", &[]), None, "2026-03-01T11:00:00Z").unwrap();
+        assert_ne!(other.id, first.id);
+        let mut bare = insight("d", "webhooks", &[]);
+        bare.session_update = None;
+        assert!(store.record_insight(&bare, None, "2026-03-01T12:00:00Z").is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        store.save(dir.path()).unwrap();
+        assert_eq!(SessionStore::load(dir.path()).unwrap().unwrap().sessions, store.sessions);
+    }
+}
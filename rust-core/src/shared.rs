@@ -0,0 +1,774 @@
+//! Thread-safe engine handle for serving concurrent requests
+//!
+//! `SemanticAnalyzer` and `PatternLearningEngine` mutate themselves while
+//! learning, so a host holding them directly has to serialize every call.
+//! [`SharedEngine`] keeps each behind its own `Arc<RwLock<...>>`: read-only
+//! queries (concept search, relationships, prediction, learned patterns) take
+//! read locks and run concurrently with each other, and a learning run only
+//! write-locks the component it is updating, so predictions keep being served
+//! while concepts are extracted and concept queries while patterns are learned.
+//!
+//! Subscribers receive a [`ChangeSet`](crate::changes::ChangeSet) after every
+//! learn, load or update call that changed concepts or patterns (see
+//! [`changes`](crate::changes)).
+//!
+//! Insights contributed by agents are kept next to both, linked to the
+//! concepts and patterns they are about (see [`insights`](crate::insights)),
+//! and so are the agents' work sessions (see [`sessions`](crate::sessions)).
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::{
+    BlueprintAnalyzer, BlueprintSummarizer, BlueprintSummary, ConceptContext, GraphSlice, RelationshipEdge,
+    SemanticAnalyzer, DEFAULT_SUMMARY_ITEMS,
+};
+use crate::changes::{ChangeSubscriber, EngineSnapshot, Subscribers};
+use crate::context_pack::{ContextPack, ContextPackBuilder};
+use crate::insights::{Insight, InsightStore, InsightValidator};
+use crate::jsonl::{JsonLinesExport, JsonLinesWriter, RecordKind, DEFAULT_JSON_LINES_PAGE};
+use crate::patterns::types::{ApproachPrediction, Pattern};
+use crate::patterns::{PatternLearningEngine, Violation};
+use crate::quotas::{ProjectQuota, QuotaLedger, ResourceUsage};
+use crate::sessions::{SessionActivity, SessionQuery, SessionStore, WorkSession};
+use crate::types::validation;
+use crate::types::{paths, sort_concepts, ParseError, SemanticConcept};
+use std::fs::File;
+use std::io::BufWriter;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+
+/// Cloneable handle sharing one analyzer and one learning engine
+#[derive(Clone)]
+#[cfg_attr(feature = "napi-bindings", napi)]
+pub struct SharedEngine {
+    analyzer: Arc<RwLock<SemanticAnalyzer>>,
+    learner: Arc<RwLock<PatternLearningEngine>>,
+    /// Held for a whole learning run so two runs never interleave their phases
+    learning: Arc<Mutex<()>>,
+    subscribers: Subscribers,
+    insights: Arc<RwLock<InsightStore>>,
+    sessions: Arc<RwLock<SessionStore>>,
+    /// Caps on concepts and patterns, enforced after every learn, load and update
+    quota: Arc<RwLock<QuotaLedger>>,
+}
+
+#[cfg_attr(feature = "napi-bindings", napi)]
+impl SharedEngine {
+    #[cfg_attr(feature = "napi-bindings", napi(constructor))]
+    pub fn new() -> Result<Self, ParseError> {
+        Ok(SharedEngine {
+            analyzer: Arc::new(RwLock::new(SemanticAnalyzer::new()?)),
+            learner: Arc::new(RwLock::new(PatternLearningEngine::new())),
+            learning: Arc::new(Mutex::new(())),
+            subscribers: Subscribers::default(),
+            insights: Arc::new(RwLock::new(InsightStore::default())),
+            sessions: Arc::new(RwLock::new(SessionStore::default())),
+            quota: Arc::new(RwLock::new(QuotaLedger::default())),
+        })
+    }
+
+    /// Call `callback` with a `ChangeSet` after every learn, load or update
+    /// call that changed concepts or patterns, so a host can update its own
+    /// stores incrementally. Returns an id for `unsubscribe`.
+    #[cfg(feature = "napi-bindings")]
+    #[napi]
+    pub fn subscribe(
+        &self,
+        callback: napi::threadsafe_function::ThreadsafeFunction<
+            crate::changes::ChangeSet,
+            (),
+            crate::changes::ChangeSet,
+            napi::Status,
+            false,
+        >,
+    ) -> u32 {
+        self.add_subscriber(Arc::new(crate::changes::CallbackSubscriber::new(callback)))
+    }
+
+    /// Register a subscriber for change sets; returns an id for `unsubscribe`
+    pub fn add_subscriber(&self, subscriber: Arc<dyn ChangeSubscriber>) -> u32 {
+        self.subscribers.add(subscriber)
+    }
+
+    /// Stop sending change sets to a subscriber; false if the id is unknown
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn unsubscribe(&self, subscription_id: u32) -> bool {
+        self.subscribers.remove(subscription_id)
+    }
+
+    /// Extract concepts and learn patterns from a codebase
+    ///
+    /// Concept extraction holds only the analyzer's write lock and pattern
+    /// learning only the engine's, so the other component keeps answering reads.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn learn_from_codebase(&self, path: String) -> Result<Vec<Pattern>, ParseError> {
+        validation::require_directory(&path)?;
+        let _run = self.learning.lock().await;
+        let before = self.snapshot().await;
+
+        let learned = async {
+            {
+                let mut analyzer = self.analyzer.write().await;
+                // SAFETY: the write guard gives this call exclusive access to the analyzer
+                unsafe { analyzer.learn_from_codebase(path.clone()).await }?;
+            }
+
+            let mut learner = self.learner.write().await;
+            // SAFETY: the write guard gives this call exclusive access to the engine
+            unsafe { learner.learn_from_codebase(path).await }
+        }
+        .await;
+        self.enforce_quota().await;
+
+        // A failed run may still have stored the concepts it extracted
+        self.publish_changes(before, "learn_from_codebase").await;
+        learned
+    }
+
+    /// Load a project's configuration and whatever it has saved: learned
+    /// patterns and approaches, concepts of unchanged files, the concept
+    /// graph, concept tags, insights and work sessions.
+    /// Returns whether any saved state was found.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn load_project(&self, path: String) -> Result<bool, ParseError> {
+        validation::require_directory(&path)?;
+        let _run = self.learning.lock().await;
+        let before = self.snapshot().await;
+
+        let restored_concepts = {
+            let mut analyzer = self.analyzer.write().await;
+            analyzer.load_project_config(path.clone())?;
+            let graph = analyzer.load_concept_graph(path.clone())?;
+            let tags = analyzer.load_concept_tags(path.clone())?;
+            // Rebuilds the graph over the saved concepts still up to date
+            let concepts = analyzer.load_concept_snapshot(path.clone())?;
+            graph || tags || concepts
+        };
+
+        let restored_patterns = {
+            let mut learner = self.learner.write().await;
+            learner.load_project_config(path.clone())?;
+            learner.load_learning_state(path.clone())?
+        };
+
+        let saved_insights = InsightStore::load(std::path::Path::new(&path))?;
+        let restored_insights = saved_insights.is_some();
+        *self.insights.write().await = saved_insights.unwrap_or_default();
+        let saved_sessions = SessionStore::load(std::path::Path::new(&path))?;
+        let restored_sessions = saved_sessions.is_some();
+        *self.sessions.write().await = saved_sessions.unwrap_or_default();
+
+        self.enforce_quota().await;
+        self.publish_changes(before, "load_project").await;
+        Ok(restored_concepts || restored_patterns || restored_insights || restored_sessions)
+    }
+
+    /// Apply one file watcher event (see `PatternLearningEngine::update_from_change`)
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn update_from_change(&self, change_data: String) -> Result<bool, ParseError> {
+        let before = self.snapshot().await;
+        let updated = {
+            let mut learner = self.learner.write().await;
+            // SAFETY: the write guard gives this call exclusive access to the engine
+            unsafe { learner.update_from_change(change_data).await }
+        };
+        self.enforce_quota().await;
+        self.publish_changes(before, "update_from_change").await;
+        updated
+    }
+
+    /// Stored concepts whose name or tags contain `query`, most relevant first
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn search_concepts(&self, query: String, limit: Option<u32>) -> Vec<SemanticConcept> {
+        self.analyzer.read().await.search_concepts(query, limit)
+    }
+
+    /// A stored concept by id
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_concept(&self, concept_id: String) -> Option<SemanticConcept> {
+        self.analyzer.read().await.stored_concept(&concept_id).cloned()
+    }
+
+    /// Stored concepts of one file, in line order
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_file_concepts(&self, file_path: String) -> Vec<SemanticConcept> {
+        let analyzer = self.analyzer.read().await;
+        let mut concepts: Vec<SemanticConcept> = analyzer
+            .stored_concepts()
+            .filter(|concept| paths::same_file(&concept.file_path, &file_path))
+            .cloned()
+            .collect();
+        sort_concepts(&mut concepts);
+        concepts
+    }
+
+    /// Tag a concept with domain terms (see `SemanticAnalyzer::tag_concept`)
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn tag_concept(&self, concept_id: String, tags: Vec<String>) -> Result<Vec<String>, ParseError> {
+        self.analyzer.write().await.tag_concept(concept_id, tags)
+    }
+
+    /// Relationships of `concept_id` as `kind:conceptId` strings
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_concept_relationships(&self, concept_id: String) -> Result<Vec<String>, ParseError> {
+        self.analyzer.read().await.get_concept_relationships(concept_id)
+    }
+
+    /// Typed relationship edges of a concept (see `SemanticAnalyzer::get_relationship_edges`)
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_relationship_edges(
+        &self,
+        concept_id: String,
+        kind: Option<String>,
+        direction: Option<String>,
+    ) -> Result<Vec<RelationshipEdge>, ParseError> {
+        self.analyzer.read().await.get_relationship_edges(concept_id, kind, direction)
+    }
+
+    /// Concepts near a concept (see `SemanticAnalyzer::get_concept_neighbors`)
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_concept_neighbors(
+        &self,
+        concept_id: String,
+        depth: Option<u32>,
+        kind: Option<String>,
+        direction: Option<String>,
+    ) -> Result<GraphSlice, ParseError> {
+        self.analyzer.read().await.get_concept_neighbors(concept_id, depth, kind, direction)
+    }
+
+    /// A concept's source and related signatures for a prompt (see `SemanticAnalyzer::get_concept_context`)
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_concept_context(
+        &self,
+        concept_id: String,
+        before_lines: Option<u32>,
+        after_lines: Option<u32>,
+        include_related: Option<bool>,
+    ) -> Result<ConceptContext, ParseError> {
+        self.analyzer.read().await.get_concept_context(concept_id, before_lines, after_lines, include_related)
+    }
+
+    /// Shortest chain of relationships between two concepts
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn find_concept_path(&self, from_id: String, to_id: String) -> Result<Vec<RelationshipEdge>, ParseError> {
+        self.analyzer.read().await.find_concept_path(from_id, to_id)
+    }
+
+    /// Concepts and relationships involved in a feature (see `SemanticAnalyzer::get_feature_subgraph`)
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_feature_subgraph(&self, feature: String, depth: Option<u32>) -> Result<GraphSlice, ParseError> {
+        self.analyzer.read().await.get_feature_subgraph(feature, depth)
+    }
+
+    /// Predict the best approach for a problem from the learned patterns
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn predict_approach(
+        &self,
+        problem_description: String,
+        context: Option<String>,
+    ) -> Result<ApproachPrediction, ParseError> {
+        self.learner.read().await.predict_approach(problem_description, context)
+    }
+
+    /// Patterns learned so far
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_learned_patterns(&self) -> Vec<Pattern> {
+        self.learner.read().await.get_learned_patterns()
+    }
+
+    /// Violations of rules and risk checks found by the last learning runs
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_violations(&self) -> Vec<Violation> {
+        self.learner.read().await.get_violations()
+    }
+
+    /// Write stored concepts, learned patterns and violations to `path` as
+    /// JSON Lines, one record per line tagged with its `kind`; `kinds` picks
+    /// some of 'concepts', 'patterns' and 'violations', all by default.
+    /// Concepts are written straight from the analyzer, one at a time, so the
+    /// export never holds a second copy of them
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn export_json_lines(
+        &self,
+        path: String,
+        kinds: Option<Vec<String>>,
+    ) -> Result<JsonLinesExport, ParseError> {
+        let kinds = RecordKind::parse_all(kinds.as_deref())?;
+        let file = File::create(&path)
+            .map_err(|e| ParseError::from_reason(format!("Failed to create {}: {}", path, e)))?;
+        let mut writer = JsonLinesWriter::new(BufWriter::new(file));
+        let mut export = JsonLinesExport { path, ..Default::default() };
+
+        for kind in kinds {
+            match kind {
+                RecordKind::Concept => {
+                    let analyzer = self.analyzer.read().await;
+                    export.concept_count = writer.write_all(kind, Self::sorted_concepts(&analyzer))?;
+                }
+                RecordKind::Pattern => {
+                    let patterns = self.learner.read().await.get_learned_patterns();
+                    export.pattern_count = writer.write_all(kind, &patterns)?;
+                }
+                RecordKind::Violation => {
+                    let violations = self.learner.read().await.get_violations();
+                    export.violation_count = writer.write_all(kind, &violations)?;
+                }
+            }
+        }
+        writer.finish()?;
+        Ok(export)
+    }
+
+    /// One page of stored concepts, learned patterns or violations (`kind`)
+    /// as JSON Lines, for reading a large result a page at a time; `limit`
+    /// is 500 by default, and a page past the end is empty
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_json_lines(
+        &self,
+        kind: String,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> Result<String, ParseError> {
+        let kind = RecordKind::parse(&kind)?;
+        let offset = offset.unwrap_or(0) as usize;
+        let limit = limit.unwrap_or(DEFAULT_JSON_LINES_PAGE) as usize;
+        let mut writer = JsonLinesWriter::new(Vec::new());
+        match kind {
+            RecordKind::Concept => {
+                let analyzer = self.analyzer.read().await;
+                writer.write_all(kind, Self::sorted_concepts(&analyzer).into_iter().skip(offset).take(limit))?;
+            }
+            RecordKind::Pattern => {
+                let patterns = self.learner.read().await.get_learned_patterns();
+                writer.write_all(kind, patterns.iter().skip(offset).take(limit))?;
+            }
+            RecordKind::Violation => {
+                let violations = self.learner.read().await.get_violations();
+                writer.write_all(kind, violations.iter().skip(offset).take(limit))?;
+            }
+        }
+        String::from_utf8(writer.finish()?)
+            .map_err(|e| ParseError::from_reason(format!("Failed to write JSON Lines: {}", e)))
+    }
+
+    /// Accept an insight contributed by an agent and link it to the stored
+    /// concepts and learned patterns it is about
+    ///
+    /// The document has a `type` (bug_pattern, optimization,
+    /// refactor_suggestion or best_practice), a `content` (a description, or an
+    /// object with `description` and optional `reasoning`, `code`, `concepts`,
+    /// `patterns` and `files`), a `confidence` between 0 and 1, a `sourceAgent`
+    /// and an optional `sessionUpdate` (`feature`, `tasks`, `files`,
+    /// `decisions`). A rejected document's error names every missing or
+    /// invalid field. An insight with a `sessionUpdate` is recorded in its
+    /// agent's open session for that feature. Once a project is loaded or
+    /// learned, insights and sessions are saved to it as they arrive.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn ingest_insight(&self, insight_json: String) -> Result<Insight, ParseError> {
+        let input = InsightValidator::parse(&insight_json)?;
+        let analyzer = self.analyzer.read().await;
+        let patterns = self.learner.read().await.get_learned_patterns();
+        let root = analyzer.project_root().map(str::to_string);
+
+        let mut insights = self.insights.write().await;
+        let now = chrono::Utc::now().to_rfc3339();
+        let insight = insights.add(input, analyzer.stored_concepts(), &patterns, root.as_deref(), Some(now.clone()));
+        if let Some(root) = &root {
+            insights.save(std::path::Path::new(root))?;
+        }
+
+        let mut sessions = self.sessions.write().await;
+        if sessions.record_insight(&insight, root.as_deref(), &now).is_some() {
+            Self::save_sessions(&sessions, root.as_deref())?;
+        }
+        Ok(insight)
+    }
+
+    /// Insights linked to a stored concept, newest first
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_concept_insights(&self, concept_id: String) -> Result<Vec<Insight>, ParseError> {
+        let analyzer = self.analyzer.read().await;
+        let concept = analyzer.stored_concept(&concept_id).ok_or_else(|| {
+            validation::ValidationError::new(
+                validation::ErrorCode::InvalidArgument,
+                format!("Unknown concept: {}", concept_id),
+            )
+        })?;
+        Ok(self.insights.read().await.for_concept(concept))
+    }
+
+    /// Insights linked to a learned pattern, newest first
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_pattern_insights(&self, pattern_id: String) -> Vec<Insight> {
+        self.insights.read().await.for_pattern(&pattern_id)
+    }
+
+    /// The concepts, patterns, pattern examples and blueprint features most
+    /// relevant to a task, fitted into `budget_tokens`
+    ///
+    /// Features come from the blueprint of the loaded or learned project and
+    /// are left out before one is.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn build_context_pack(
+        &self,
+        task_description: String,
+        budget_tokens: u32,
+    ) -> Result<ContextPack, ParseError> {
+        let root = self.analyzer.read().await.project_root().map(str::to_string);
+        let features = match &root {
+            Some(root) => BlueprintAnalyzer::build_feature_map(root.clone()).await?,
+            None => Vec::new(),
+        };
+
+        let analyzer = self.analyzer.read().await;
+        let patterns = self.learner.read().await.get_learned_patterns();
+        ContextPackBuilder::new(&task_description, root.as_deref())?
+            .concepts(analyzer.stored_concepts())
+            .patterns(&patterns)
+            .features(&features)
+            .build(budget_tokens)
+    }
+
+    /// Ranked summary of the loaded or learned project's blueprint, with the
+    /// learned patterns as its dominant patterns and rule findings; see
+    /// [`BlueprintAnalyzer::summarize_blueprint`]
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn summarize_project_blueprint(&self, max_items: Option<u32>) -> Result<BlueprintSummary, ParseError> {
+        let root = self.analyzer.read().await.project_root().map(str::to_string).ok_or_else(|| {
+            validation::ValidationError::new(validation::ErrorCode::InvalidArgument, "No project loaded or learned")
+        })?;
+        let blueprint = BlueprintAnalyzer::get_project_blueprint(root).await?;
+        let patterns = self.learner.read().await.get_learned_patterns();
+        Ok(BlueprintSummarizer::summarize(
+            &blueprint,
+            &patterns,
+            max_items.unwrap_or(DEFAULT_SUMMARY_ITEMS),
+        ))
+    }
+
+    /// Open a work session for `agent`, optionally on a named feature
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn start_session(&self, agent: String, feature: Option<String>) -> Result<WorkSession, ParseError> {
+        let root = self.analyzer.read().await.project_root().map(str::to_string);
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.start(&agent, feature, &chrono::Utc::now().to_rfc3339())?;
+        Self::save_sessions(&sessions, root.as_deref())?;
+        Ok(session)
+    }
+
+    /// Add tasks, decisions, touched files and involved concepts (names or
+    /// ids of stored concepts) to an open session
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn record_session_activity(
+        &self,
+        session_id: String,
+        activity: SessionActivity,
+    ) -> Result<WorkSession, ParseError> {
+        let analyzer = self.analyzer.read().await;
+        let root = analyzer.project_root().map(str::to_string);
+        let mut sessions = self.sessions.write().await;
+        let now = chrono::Utc::now().to_rfc3339();
+        let session = sessions.record(&session_id, activity, analyzer.stored_concepts(), root.as_deref(), &now)?;
+        Self::save_sessions(&sessions, root.as_deref())?;
+        Ok(session)
+    }
+
+    /// Close an open session
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn end_session(&self, session_id: String) -> Result<WorkSession, ParseError> {
+        let root = self.analyzer.read().await.project_root().map(str::to_string);
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.end(&session_id, &chrono::Utc::now().to_rfc3339())?;
+        Self::save_sessions(&sessions, root.as_deref())?;
+        Ok(session)
+    }
+
+    /// Sessions matching `query`, most recently updated first; e.g. an `area`
+    /// of "src/payments" answers what was done recently under that directory
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_recent_sessions(&self, query: Option<SessionQuery>) -> Result<Vec<WorkSession>, ParseError> {
+        let root = self.analyzer.read().await.project_root().map(str::to_string);
+        self.sessions.read().await.query(&query.unwrap_or_default(), root.as_deref())
+    }
+
+    /// Cap the concepts and patterns this engine keeps, evicting any over the
+    /// caps now and after every later learn, load or update call; replaces the
+    /// previous quota and resets its eviction counts
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn set_quota(&self, quota: ProjectQuota) -> Result<ResourceUsage, ParseError> {
+        let ledger = QuotaLedger::new(quota)?;
+        let _run = self.learning.lock().await;
+        let before = self.snapshot().await;
+        *self.quota.write().await = ledger;
+        self.enforce_quota().await;
+        self.publish_changes(before, "set_quota").await;
+        Ok(self.get_resource_usage().await)
+    }
+
+    /// Concepts and patterns held, against the quota, and how many the quota evicted
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn get_resource_usage(&self) -> ResourceUsage {
+        let ledger = self.quota.read().await.clone();
+        let (root, concept_count) = {
+            let analyzer = self.analyzer.read().await;
+            (analyzer.project_root().map(str::to_string), analyzer.concept_count())
+        };
+        let pattern_count = self.learner.read().await.get_learned_patterns().len();
+        ledger.usage(root, concept_count, pattern_count)
+    }
+
+    /// Number of concepts the analyzer holds
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub async fn concept_count(&self) -> u32 {
+        self.analyzer.read().await.concept_count() as u32
+    }
+
+    /// Evict the concepts and patterns over the quota, holding one lock at a time
+    async fn enforce_quota(&self) {
+        let ledger = self.quota.read().await.clone();
+        let concepts = match ledger.max_concepts() {
+            Some(max) => self.analyzer.write().await.evict_concepts(max, ledger.policy()),
+            None => 0,
+        };
+        let patterns = match ledger.max_patterns() {
+            Some(max) => self.learner.write().await.evict_patterns(max, ledger.policy()),
+            None => 0,
+        };
+        if concepts + patterns > 0 {
+            self.quota.write().await.record(concepts, patterns);
+        }
+    }
+
+    /// Stored concepts in output order (see [`sort_concepts`]) without cloning them
+    fn sorted_concepts(analyzer: &SemanticAnalyzer) -> Vec<&SemanticConcept> {
+        let mut concepts: Vec<&SemanticConcept> = analyzer.stored_concepts().collect();
+        concepts.sort_by(|a, b| {
+            (&a.file_path, a.line_range.start, &a.id).cmp(&(&b.file_path, b.line_range.start, &b.id))
+        });
+        concepts
+    }
+
+    /// Save sessions to the project, once one is loaded or learned
+    fn save_sessions(sessions: &SessionStore, root: Option<&str>) -> Result<(), ParseError> {
+        match root {
+            Some(root) => sessions.save(std::path::Path::new(root)),
+            None => Ok(()),
+        }
+    }
+
+    /// Concepts and patterns as they are now, if anyone is subscribed to changes
+    async fn snapshot(&self) -> Option<EngineSnapshot> {
+        if self.subscribers.is_empty() {
+            return None;
+        }
+        let analyzer = self.analyzer.read().await;
+        let learner = self.learner.read().await;
+        Some(EngineSnapshot::new(analyzer.stored_concepts(), learner.get_learned_patterns()))
+    }
+
+    /// Send subscribers what changed since `before` was taken
+    async fn publish_changes(&self, before: Option<EngineSnapshot>, source: &str) {
+        let Some(before) = before else {
+            return;
+        };
+        if let Some(after) = self.snapshot().await {
+            self.subscribers.publish(before.changes_to(&after, source));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::changes::ChangeSet;
+    use std::time::Duration;
+
+    fn fixture() -> tempfile::TempDir {
+        crate::golden_tests::polyglot_copy()
+    }
+
+    #[tokio::test]
+    async fn test_shared_engine_learns_and_answers_queries() {
+        let fixture = fixture();
+        let engine = SharedEngine::new().unwrap();
+        let patterns = engine.learn_from_codebase(fixture.path().to_string_lossy().to_string()).await.unwrap();
+        assert_eq!(engine.get_learned_patterns().await.len(), patterns.len());
+
+        let concepts = engine.search_concepts(String::new(), Some(3)).await;
+        assert_eq!(concepts.len(), 3);
+        assert!(engine.get_concept_relationships(concepts[0].id.clone()).await.is_ok());
+
+        let missing = engine.learn_from_codebase("/no/such/project".to_string()).await;
+        assert!(missing.unwrap_err().to_string().contains("[PATH_NOT_FOUND]"));
+    }
+
+    #[tokio::test]
+    async fn test_results_export_as_json_lines() {
+        let fixture = fixture();
+        let engine = SharedEngine::new().unwrap();
+        engine.learn_from_codebase(fixture.path().to_string_lossy().to_string()).await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.jsonl").to_string_lossy().to_string();
+
+        let export = engine.export_json_lines(path.clone(), None).await.unwrap();
+        assert_eq!(export.concept_count, engine.concept_count().await);
+        assert_eq!(export.pattern_count as usize, engine.get_learned_patterns().await.len());
+        let content = std::fs::read_to_string(&path).unwrap();
+        let records: Vec<serde_json::Value> = content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        let total = export.concept_count + export.pattern_count + export.violation_count;
+        assert_eq!(records.len(), total as usize);
+        assert_eq!(records[0]["kind"], "concept");
+
+        let page = engine.get_json_lines("concepts".to_string(), Some(1), Some(2)).await.unwrap();
+        assert_eq!(page.lines().collect::<Vec<_>>(), content.lines().skip(1).take(2).collect::<Vec<_>>());
+        let past_end = engine.get_json_lines("patterns".to_string(), Some(u32::MAX), None).await.unwrap();
+        assert!(past_end.is_empty());
+
+        let only = engine.export_json_lines(path, Some(vec!["violations".to_string()])).await.unwrap();
+        assert_eq!((only.concept_count, only.pattern_count), (0, 0));
+        assert!(engine.get_json_lines("rows".to_string(), None, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_insights_are_validated_linked_and_saved() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("orders.ts"), "export class OrderService {}\n").unwrap();
+        let engine = SharedEngine::new().unwrap();
+        engine.learn_from_codebase(dir.path().to_string_lossy().to_string()).await.unwrap();
+
+        let error = engine.ingest_insight(r#"{"type": "best_practice", "confidence": 0.9}"#.to_string()).await.unwrap_err();
+        assert!(error.to_string().contains("content is required"));
+        assert!(error.to_string().contains("sourceAgent is required"));
+
+        let insight = engine
+            .ingest_insight(
+                r#"{"type": "best_practice", "content": {"description": "Keep services stateless", "concepts": ["OrderService"]},
+                    "confidence": 0.9, "sourceAgent": "reviewer"}"#
+                    .to_string(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(insight.related_concepts[0].name, "OrderService");
+        assert!(insight.related_concepts[0].file_path.ends_with("orders.ts"));
+        assert!(InsightStore::path(dir.path()).is_file());
+
+        let service = engine.search_concepts("OrderService".to_string(), Some(1)).await;
+        let linked = engine.get_concept_insights(service[0].id.clone()).await.unwrap();
+        assert_eq!(linked[0].id, insight.id);
+        assert!(engine.get_concept_insights("missing".to_string()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_context_pack_fits_relevant_context_into_the_budget() {
+        let fixture = fixture();
+        let engine = SharedEngine::new().unwrap();
+        engine.learn_from_codebase(fixture.path().to_string_lossy().to_string()).await.unwrap();
+        let concept = engine.search_concepts("UserRepository".to_string(), Some(1)).await.remove(0);
+
+        let pack = engine.build_context_pack("Add caching to UserRepository lookups".to_string(), 400).await.unwrap();
+        assert!(!pack.items.is_empty());
+        assert!(pack.used_tokens <= 400);
+        assert!(pack.items.iter().any(|item| item.kind == "concept" && item.id == concept.id));
+
+        let error = engine.build_context_pack("the with this".to_string(), 400).await.unwrap_err();
+        assert!(error.to_string().contains("[INVALID_ARGUMENT]"));
+    }
+
+    #[tokio::test]
+    async fn test_sessions_record_insights_and_explicit_activity() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("billing")).unwrap();
+        std::fs::write(dir.path().join("billing/invoice.ts"), "export class InvoiceService {}\n").unwrap();
+        let engine = SharedEngine::new().unwrap();
+        engine.learn_from_codebase(dir.path().to_string_lossy().to_string()).await.unwrap();
+
+        let session = engine.start_session("planner".to_string(), Some("invoicing".to_string())).await.unwrap();
+        let activity = SessionActivity {
+            tasks: vec!["Add late fees".to_string()],
+            concepts: vec!["InvoiceService".to_string()],
+            ..Default::default()
+        };
+        let updated = engine.record_session_activity(session.id.clone(), activity).await.unwrap();
+        assert_eq!(updated.concepts[0].file_path, "billing/invoice.ts");
+
+        engine
+            .ingest_insight(
+                r#"{"type": "optimization", "content": "Cache tax rates", "confidence": 0.7, "sourceAgent": "reviewer",
+                    "sessionUpdate": {"feature": "taxes", "files": ["billing/tax.ts"]}}"#
+                    .to_string(),
+            )
+            .await
+            .unwrap();
+        assert!(SessionStore::path(dir.path()).is_file());
+
+        let query = SessionQuery { area: Some("billing".to_string()), ..Default::default() };
+        let recent = engine.get_recent_sessions(Some(query)).await.unwrap();
+        let agents: Vec<&str> = recent.iter().map(|session| session.agent.as_str()).collect();
+        assert_eq!(agents, vec!["reviewer", "planner"]);
+
+        engine.end_session(session.id.clone()).await.unwrap();
+        assert!(engine.end_session(session.id).await.unwrap_err().to_string().contains("has ended"));
+    }
+
+    #[derive(Default)]
+    struct RecordingSubscriber(std::sync::Mutex<Vec<ChangeSet>>);
+
+    impl ChangeSubscriber for RecordingSubscriber {
+        fn on_changes(&self, changes: &ChangeSet) {
+            self.0.lock().unwrap().push(changes.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_receive_what_learning_changed() {
+        let engine = SharedEngine::new().unwrap();
+        let subscriber = Arc::new(RecordingSubscriber::default());
+        let id = engine.add_subscriber(subscriber.clone());
+        let fixture = fixture();
+        let root = fixture.path().to_string_lossy().to_string();
+
+        let patterns = engine.learn_from_codebase(root.clone()).await.unwrap();
+        let changes = subscriber.0.lock().unwrap().clone();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].source, "learn_from_codebase");
+        assert_eq!(changes[0].sequence, 1);
+        assert_eq!(changes[0].concepts_added.len() as u32, engine.concept_count().await);
+        assert_eq!(changes[0].patterns_added.len(), patterns.len());
+        assert!(changes[0].concepts_removed.is_empty());
+
+        assert!(engine.unsubscribe(id));
+        engine.learn_from_codebase(root).await.unwrap();
+        assert_eq!(subscriber.0.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reads_are_not_blocked_by_other_component() {
+        let engine = SharedEngine::new().unwrap();
+
+        // Concept extraction in progress: predictions still go through
+        let analyzer_guard = engine.analyzer.write().await;
+        let prediction = tokio::time::timeout(
+            Duration::from_secs(5),
+            engine.predict_approach("Build a REST API for orders".to_string(), None),
+        )
+        .await
+        .expect("prediction blocked by analyzer write lock");
+        assert!(prediction.is_ok());
+        drop(analyzer_guard);
+
+        // Pattern learning in progress: concept queries still go through
+        let learner_guard = engine.learner.write().await;
+        let clone = engine.clone();
+        let concepts = tokio::time::timeout(Duration::from_secs(5), clone.search_concepts("x".to_string(), None))
+            .await
+            .expect("concept search blocked by engine write lock");
+        assert!(concepts.is_empty());
+        drop(learner_guard);
+
+        // Concurrent readers of the same component
+        let (a, b) = tokio::join!(engine.get_learned_patterns(), engine.get_learned_patterns());
+        assert_eq!(a.len(), b.len());
+    }
+}
@@ -0,0 +1,637 @@
+//! SQLite persistence for concepts, patterns and relationships
+//!
+//! The store lives in `.in-memoria/core.db` under the project root. Opening it
+//! creates the directory and the schema, so a fresh checkout never fails with
+//! a missing database directory. Concepts are indexed by their
+//! project-relative file key, so lookups by path work whichever separators or
+//! root spelling the caller uses.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use crate::analysis::RelationshipEdge;
+use crate::patterns::types::Pattern;
+use crate::types::paths;
+use crate::types::validation::{ErrorCode, ValidationError};
+use crate::types::{LineRange, ParseError, SemanticConcept, CONFIG_DIR};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// Database file inside the project's config directory
+pub const DATABASE_FILE: &str = "core.db";
+
+/// Schema version stored in `PRAGMA user_version`
+const SCHEMA_VERSION: u32 = 1;
+
+/// Rows returned by a query when no limit is given
+const DEFAULT_QUERY_LIMIT: u32 = 100;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS concepts (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        concept_type TEXT NOT NULL,
+        confidence REAL NOT NULL,
+        file_path TEXT NOT NULL,
+        file_key TEXT NOT NULL,
+        line_start INTEGER NOT NULL,
+        line_end INTEGER NOT NULL,
+        relationships TEXT NOT NULL,
+        metadata TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS concepts_file_key ON concepts(file_key);
+    CREATE INDEX IF NOT EXISTS concepts_name ON concepts(name);
+
+    CREATE TABLE IF NOT EXISTS patterns (
+        id TEXT PRIMARY KEY,
+        pattern_type TEXT NOT NULL,
+        confidence REAL NOT NULL,
+        frequency INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS patterns_type ON patterns(pattern_type);
+
+    CREATE TABLE IF NOT EXISTS relationships (
+        from_id TEXT NOT NULL,
+        to_id TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        PRIMARY KEY (from_id, to_id, kind)
+    );
+    CREATE INDEX IF NOT EXISTS relationships_to ON relationships(to_id);
+";
+
+/// Filters for `query_concepts`; unset fields match everything
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct ConceptQuery {
+    /// Case-insensitive substring of the concept name
+    pub name: Option<String>,
+    pub concept_type: Option<String>,
+    /// A file path in any form: absolute, project-relative, either separator
+    pub file_path: Option<String>,
+    pub limit: Option<u32>,
+}
+
+/// Filters for `query_patterns`; unset fields match everything
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct PatternQuery {
+    /// Pattern types starting with this prefix, e.g. `naming` or `structural_`
+    pub pattern_type: Option<String>,
+    pub min_confidence: Option<f64>,
+    pub limit: Option<u32>,
+}
+
+/// Row counts of each table
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct StoreStats {
+    pub concepts: u32,
+    pub patterns: u32,
+    pub relationships: u32,
+}
+
+/// Concepts, patterns and relationships of one project in SQLite
+#[cfg_attr(feature = "napi-bindings", napi)]
+pub struct CoreStore {
+    connection: Mutex<Connection>,
+    /// Root concept file keys are relative to; `None` for in-memory stores
+    root: Option<String>,
+}
+
+#[cfg_attr(feature = "napi-bindings", napi)]
+impl CoreStore {
+    /// Open (creating if needed) `.in-memoria/core.db` under a project root
+    #[cfg_attr(feature = "napi-bindings", napi(constructor))]
+    pub fn new(project_path: String) -> Result<Self, ParseError> {
+        let root = Path::new(&project_path);
+        if !root.is_dir() {
+            return Err(ValidationError::new(
+                ErrorCode::NotADirectory,
+                format!("Project root is not a directory: {}", project_path),
+            )
+            .into());
+        }
+
+        let path = Self::path(root);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                ParseError::from_reason(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+        let connection = Connection::open(&path).map_err(|e| {
+            ParseError::from_reason(format!("Failed to open {}: {}", path.display(), e))
+        })?;
+        Self::with_connection(connection, Some(project_path))
+    }
+
+    /// A store that lives only as long as this handle, for tests and scratch work
+    #[cfg_attr(feature = "napi-bindings", napi(factory))]
+    pub fn open_in_memory() -> Result<Self, ParseError> {
+        let connection = Connection::open_in_memory().map_err(database_error)?;
+        Self::with_connection(connection, None)
+    }
+
+    /// Insert or replace concepts. Returns the number written.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn save_concepts(&self, concepts: Vec<SemanticConcept>) -> Result<u32, ParseError> {
+        let mut connection = self.lock()?;
+        let tx = connection.transaction().map_err(database_error)?;
+        {
+            let mut insert = tx
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO concepts
+                        (id, name, concept_type, confidence, file_path, file_key, line_start, line_end, relationships, metadata)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                )
+                .map_err(database_error)?;
+            for concept in &concepts {
+                insert
+                    .execute(params![
+                        concept.id,
+                        concept.name,
+                        concept.concept_type,
+                        concept.confidence,
+                        concept.file_path,
+                        self.file_key(&concept.file_path),
+                        concept.line_range.start,
+                        concept.line_range.end,
+                        to_json(&concept.relationships)?,
+                        to_json(&concept.metadata)?,
+                    ])
+                    .map_err(database_error)?;
+            }
+        }
+        tx.commit().map_err(database_error)?;
+        Ok(concepts.len() as u32)
+    }
+
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_concept(&self, id: String) -> Result<Option<SemanticConcept>, ParseError> {
+        self.lock()?
+            .query_row(&format!("SELECT {} FROM concepts WHERE id = ?1", CONCEPT_COLUMNS), [id], concept_from_row)
+            .optional()
+            .map_err(database_error)
+    }
+
+    /// Delete a concept and its relationships. Returns false when it wasn't stored.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn delete_concept(&self, id: String) -> Result<bool, ParseError> {
+        let mut connection = self.lock()?;
+        let tx = connection.transaction().map_err(database_error)?;
+        tx.execute("DELETE FROM relationships WHERE from_id = ?1 OR to_id = ?1", [&id])
+            .map_err(database_error)?;
+        let deleted = tx.execute("DELETE FROM concepts WHERE id = ?1", [&id]).map_err(database_error)?;
+        tx.commit().map_err(database_error)?;
+        Ok(deleted > 0)
+    }
+
+    /// Delete every concept of a file, e.g. before storing its re-analysis,
+    /// with their relationships. Returns the number of concepts deleted.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn delete_file_concepts(&self, file_path: String) -> Result<u32, ParseError> {
+        let file_key = self.file_key(&file_path);
+        let mut connection = self.lock()?;
+        let tx = connection.transaction().map_err(database_error)?;
+        tx.execute(
+            "DELETE FROM relationships
+             WHERE from_id IN (SELECT id FROM concepts WHERE file_key = ?1)
+                OR to_id IN (SELECT id FROM concepts WHERE file_key = ?1)",
+            [&file_key],
+        )
+        .map_err(database_error)?;
+        let deleted = tx
+            .execute("DELETE FROM concepts WHERE file_key = ?1", [&file_key])
+            .map_err(database_error)?;
+        tx.commit().map_err(database_error)?;
+        Ok(deleted as u32)
+    }
+
+    /// Concepts matching every given filter, ordered by file and line
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn query_concepts(&self, query: ConceptQuery) -> Result<Vec<SemanticConcept>, ParseError> {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        if let Some(name) = query.name.filter(|name| !name.is_empty()) {
+            conditions.push("name LIKE ? ESCAPE '\\'");
+            values.push(Value::Text(format!("%{}%", escape_like(&name))));
+        }
+        if let Some(concept_type) = query.concept_type {
+            conditions.push("concept_type = ?");
+            values.push(Value::Text(concept_type));
+        }
+        if let Some(file_path) = query.file_path {
+            conditions.push("file_key = ?");
+            values.push(Value::Text(self.file_key(&file_path)));
+        }
+        values.push(Value::Integer(query.limit.unwrap_or(DEFAULT_QUERY_LIMIT) as i64));
+
+        let sql = format!(
+            "SELECT {} FROM concepts {} ORDER BY file_key, line_start, id LIMIT ?",
+            CONCEPT_COLUMNS,
+            where_clause(&conditions)
+        );
+        let connection = self.lock()?;
+        let mut statement = connection.prepare(&sql).map_err(database_error)?;
+        let rows = statement
+            .query_map(params_from_iter(values.iter()), concept_from_row)
+            .map_err(database_error)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(database_error)
+    }
+
+    /// Insert or replace patterns. Returns the number written.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn save_patterns(&self, patterns: Vec<Pattern>) -> Result<u32, ParseError> {
+        let mut connection = self.lock()?;
+        let tx = connection.transaction().map_err(database_error)?;
+        {
+            let mut insert = tx
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO patterns (id, pattern_type, confidence, frequency, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                )
+                .map_err(database_error)?;
+            for pattern in &patterns {
+                insert
+                    .execute(params![
+                        pattern.id,
+                        pattern.pattern_type,
+                        pattern.confidence,
+                        pattern.frequency,
+                        to_json(pattern)?,
+                    ])
+                    .map_err(database_error)?;
+            }
+        }
+        tx.commit().map_err(database_error)?;
+        Ok(patterns.len() as u32)
+    }
+
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_pattern(&self, id: String) -> Result<Option<Pattern>, ParseError> {
+        let data: Option<String> = self
+            .lock()?
+            .query_row("SELECT data FROM patterns WHERE id = ?1", [id], |row| row.get(0))
+            .optional()
+            .map_err(database_error)?;
+        data.map(|data| from_json(&data)).transpose()
+    }
+
+    /// Returns false when the pattern wasn't stored
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn delete_pattern(&self, id: String) -> Result<bool, ParseError> {
+        let deleted = self
+            .lock()?
+            .execute("DELETE FROM patterns WHERE id = ?1", [id])
+            .map_err(database_error)?;
+        Ok(deleted > 0)
+    }
+
+    /// Patterns matching every given filter, most confident first
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn query_patterns(&self, query: PatternQuery) -> Result<Vec<Pattern>, ParseError> {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        if let Some(pattern_type) = query.pattern_type.filter(|prefix| !prefix.is_empty()) {
+            conditions.push("pattern_type LIKE ? ESCAPE '\\'");
+            values.push(Value::Text(format!("{}%", escape_like(&pattern_type))));
+        }
+        if let Some(min_confidence) = query.min_confidence {
+            conditions.push("confidence >= ?");
+            values.push(Value::Real(min_confidence));
+        }
+        values.push(Value::Integer(query.limit.unwrap_or(DEFAULT_QUERY_LIMIT) as i64));
+
+        let sql = format!(
+            "SELECT data FROM patterns {} ORDER BY confidence DESC, id LIMIT ?",
+            where_clause(&conditions)
+        );
+        let connection = self.lock()?;
+        let mut statement = connection.prepare(&sql).map_err(database_error)?;
+        let rows = statement
+            .query_map(params_from_iter(values.iter()), |row| row.get::<_, String>(0))
+            .map_err(database_error)?;
+        rows.map(|data| from_json(&data.map_err(database_error)?)).collect()
+    }
+
+    /// Store relationship edges, ignoring ones already stored. Returns the
+    /// number of new edges.
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn save_relationships(&self, edges: Vec<RelationshipEdge>) -> Result<u32, ParseError> {
+        let mut connection = self.lock()?;
+        let tx = connection.transaction().map_err(database_error)?;
+        let mut added = 0;
+        {
+            let mut insert = tx
+                .prepare_cached("INSERT OR IGNORE INTO relationships (from_id, to_id, kind) VALUES (?1, ?2, ?3)")
+                .map_err(database_error)?;
+            for edge in &edges {
+                added += insert.execute(params![edge.from, edge.to, edge.kind]).map_err(database_error)?;
+            }
+        }
+        tx.commit().map_err(database_error)?;
+        Ok(added as u32)
+    }
+
+    /// Edges of a concept, optionally of one kind; both directions unless
+    /// `direction` is `outgoing` or `incoming`
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn get_relationships(
+        &self,
+        concept_id: String,
+        kind: Option<String>,
+        direction: Option<String>,
+    ) -> Result<Vec<RelationshipEdge>, ParseError> {
+        let side = match direction.as_deref().unwrap_or("both") {
+            "outgoing" => "from_id = ?1",
+            "incoming" => "to_id = ?1",
+            "both" => "(from_id = ?1 OR to_id = ?1)",
+            other => {
+                return Err(ValidationError::new(
+                    ErrorCode::InvalidArgument,
+                    format!("Unknown direction '{}': expected outgoing, incoming or both", other),
+                )
+                .into())
+            }
+        };
+        let sql = format!(
+            "SELECT from_id, to_id, kind FROM relationships WHERE {} AND (?2 IS NULL OR kind = ?2)
+             ORDER BY kind, from_id, to_id",
+            side
+        );
+
+        let connection = self.lock()?;
+        let mut statement = connection.prepare(&sql).map_err(database_error)?;
+        let rows = statement
+            .query_map(params![concept_id, kind], |row| {
+                Ok(RelationshipEdge { from: row.get(0)?, to: row.get(1)?, kind: row.get(2)? })
+            })
+            .map_err(database_error)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(database_error)
+    }
+
+    #[cfg_attr(feature = "napi-bindings", napi)]
+    pub fn stats(&self) -> Result<StoreStats, ParseError> {
+        let connection = self.lock()?;
+        let count = |table: &str| -> Result<u32, ParseError> {
+            connection
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+                .map_err(database_error)
+        };
+        Ok(StoreStats {
+            concepts: count("concepts")?,
+            patterns: count("patterns")?,
+            relationships: count("relationships")?,
+        })
+    }
+}
+
+impl CoreStore {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(CONFIG_DIR).join(DATABASE_FILE)
+    }
+
+    /// Create or check the schema. Databases written by a newer release are
+    /// rejected with `UNSUPPORTED_VERSION` rather than modified.
+    fn with_connection(connection: Connection, root: Option<String>) -> Result<Self, ParseError> {
+        let version: u32 = connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(database_error)?;
+        if version > SCHEMA_VERSION {
+            return Err(ValidationError::new(
+                ErrorCode::UnsupportedVersion,
+                format!(
+                    "{} was written by a newer release (schema {}; this release reads up to {})",
+                    DATABASE_FILE, version, SCHEMA_VERSION
+                ),
+            )
+            .into());
+        }
+
+        connection.execute_batch(SCHEMA).map_err(database_error)?;
+        connection
+            .pragma_update(None, "user_version", SCHEMA_VERSION)
+            .map_err(database_error)?;
+        Ok(Self { connection: Mutex::new(connection), root })
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Connection>, ParseError> {
+        self.connection
+            .lock()
+            .map_err(|_| ParseError::from_reason("Database connection poisoned by a panic".to_string()))
+    }
+
+    fn file_key(&self, file_path: &str) -> String {
+        paths::relative_key(file_path, self.root.as_deref())
+    }
+}
+
+const CONCEPT_COLUMNS: &str =
+    "id, name, concept_type, confidence, file_path, line_start, line_end, relationships, metadata";
+
+fn concept_from_row(row: &Row<'_>) -> rusqlite::Result<SemanticConcept> {
+    let json_column = |index: usize| -> rusqlite::Result<_> {
+        let text: String = row.get(index)?;
+        serde_json::from_str(&text).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+        })
+    };
+    Ok(SemanticConcept {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        concept_type: row.get(2)?,
+        confidence: row.get(3)?,
+        file_path: row.get(4)?,
+        line_range: LineRange { start: row.get(5)?, end: row.get(6)? },
+        relationships: json_column(7)?,
+        metadata: json_column(8)?,
+    })
+}
+
+fn where_clause(conditions: &[&str]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    }
+}
+
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, ParseError> {
+    serde_json::to_string(value).map_err(|e| ParseError::from_reason(format!("Failed to serialize: {}", e)))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(data: &str) -> Result<T, ParseError> {
+    serde_json::from_str(data)
+        .map_err(|e| ParseError::from_reason(format!("Invalid row in {}: {}", DATABASE_FILE, e)))
+}
+
+fn database_error(error: rusqlite::Error) -> ParseError {
+    ParseError::from_reason(format!("Database error: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::types::PatternExample;
+    use std::collections::HashMap;
+
+    fn concept(id: &str, name: &str, file_path: &str, start: u32) -> SemanticConcept {
+        SemanticConcept {
+            id: id.to_string(),
+            name: name.to_string(),
+            concept_type: "class".to_string(),
+            confidence: 0.9,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start, end: start + 5 },
+            relationships: HashMap::from([("calls".to_string(), "Ledger".to_string())]),
+            metadata: HashMap::from([("tags".to_string(), "billing".to_string())]),
+        }
+    }
+
+    fn pattern(id: &str, pattern_type: &str, confidence: f64) -> Pattern {
+        Pattern {
+            id: id.to_string(),
+            pattern_type: pattern_type.to_string(),
+            description: format!("{} pattern", pattern_type),
+            frequency: 3,
+            confidence,
+            examples: vec![PatternExample {
+                code: "class InvoiceService {}".to_string(),
+                file_path: "src/invoice.ts".to_string(),
+                line_range: LineRange { start: 1, end: 1 },
+            }],
+            contexts: vec!["typescript".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_opening_creates_the_config_directory_and_reopens() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        {
+            let store = CoreStore::new(root.clone()).unwrap();
+            store.save_concepts(vec![concept("invoice", "InvoiceService", "src/invoice.ts", 1)]).unwrap();
+        }
+        assert!(CoreStore::path(dir.path()).is_file());
+
+        let reopened = CoreStore::new(root).unwrap();
+        let stored = reopened.get_concept("invoice".to_string()).unwrap().unwrap();
+        assert_eq!(stored.line_range.end, 6);
+        assert_eq!(stored.relationships.get("calls").map(String::as_str), Some("Ledger"));
+        assert_eq!(stored.metadata.get("tags").map(String::as_str), Some("billing"));
+
+        let missing = CoreStore::new(dir.path().join("nope").to_string_lossy().to_string());
+        assert!(missing.err().unwrap().to_string().contains("[NOT_A_DIRECTORY]"));
+    }
+
+    #[test]
+    fn test_concept_queries_and_deletes() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CoreStore::new(dir.path().to_string_lossy().to_string()).unwrap();
+        let absolute = dir.path().join("src").join("invoice.ts").to_string_lossy().to_string();
+        store
+            .save_concepts(vec![
+                concept("invoice", "InvoiceService", &absolute, 1),
+                concept("ledger", "Ledger_100%", "src/ledger.ts", 1),
+                concept("refund", "RefundService", "src/invoice.ts", 10),
+            ])
+            .unwrap();
+
+        let by_file = store
+            .query_concepts(ConceptQuery { file_path: Some(r"src\invoice.ts".to_string()), ..Default::default() })
+            .unwrap();
+        assert_eq!(by_file.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), vec!["invoice", "refund"]);
+
+        let by_name = |name: &str| {
+            store
+                .query_concepts(ConceptQuery { name: Some(name.to_string()), ..Default::default() })
+                .unwrap()
+                .len()
+        };
+        assert_eq!(by_name("service"), 2);
+        assert_eq!(by_name("100%"), 1);
+        assert_eq!(by_name("_1"), 1);
+        let limited = store.query_concepts(ConceptQuery { limit: Some(1), ..Default::default() }).unwrap();
+        assert_eq!(limited.len(), 1);
+
+        store
+            .save_relationships(vec![RelationshipEdge {
+                from: "invoice".to_string(),
+                to: "ledger".to_string(),
+                kind: "calls".to_string(),
+            }])
+            .unwrap();
+        assert_eq!(store.delete_file_concepts(absolute).unwrap(), 2);
+        assert!(!store.delete_concept("invoice".to_string()).unwrap());
+        assert!(store.delete_concept("ledger".to_string()).unwrap());
+        assert_eq!(store.stats().unwrap(), StoreStats { concepts: 0, patterns: 0, relationships: 0 });
+    }
+
+    #[test]
+    fn test_patterns_round_trip_and_filter() {
+        let store = CoreStore::open_in_memory().unwrap();
+        store
+            .save_patterns(vec![
+                pattern("naming_camel", "naming", 0.9),
+                pattern("naming_snake", "naming", 0.4),
+                pattern("structural_mvc", "structural_mvc", 0.7),
+            ])
+            .unwrap();
+
+        let stored = store.get_pattern("naming_camel".to_string()).unwrap().unwrap();
+        assert_eq!(stored.examples[0].file_path, "src/invoice.ts");
+        assert_eq!(stored.contexts, vec!["typescript".to_string()]);
+
+        let naming = store
+            .query_patterns(PatternQuery { pattern_type: Some("naming".to_string()), ..Default::default() })
+            .unwrap();
+        assert_eq!(naming.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(), vec!["naming_camel", "naming_snake"]);
+        let confident = store.query_patterns(PatternQuery { min_confidence: Some(0.6), ..Default::default() }).unwrap();
+        assert_eq!(confident.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(), vec!["naming_camel", "structural_mvc"]);
+
+        assert!(store.delete_pattern("naming_snake".to_string()).unwrap());
+        assert!(store.get_pattern("naming_snake".to_string()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_relationships_by_direction_and_kind() {
+        let store = CoreStore::open_in_memory().unwrap();
+        let edge = |from: &str, to: &str, kind: &str| RelationshipEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind: kind.to_string(),
+        };
+        let added = store
+            .save_relationships(vec![edge("a", "b", "calls"), edge("a", "b", "calls"), edge("c", "a", "imports")])
+            .unwrap();
+        assert_eq!(added, 2);
+
+        assert_eq!(store.get_relationships("a".to_string(), None, None).unwrap().len(), 2);
+        let outgoing = store.get_relationships("a".to_string(), None, Some("outgoing".to_string())).unwrap();
+        assert_eq!(outgoing, vec![edge("a", "b", "calls")]);
+        let imports = store.get_relationships("a".to_string(), Some("imports".to_string()), None).unwrap();
+        assert_eq!(imports, vec![edge("c", "a", "imports")]);
+
+        let bad = store.get_relationships("a".to_string(), None, Some("sideways".to_string()));
+        assert!(bad.unwrap_err().to_string().contains("[INVALID_ARGUMENT]"));
+    }
+
+    #[test]
+    fn test_newer_schema_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(CONFIG_DIR)).unwrap();
+        Connection::open(CoreStore::path(dir.path()))
+            .unwrap()
+            .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+
+        let error = CoreStore::new(dir.path().to_string_lossy().to_string()).err().unwrap();
+        assert!(error.to_string().contains("[UNSUPPORTED_VERSION]"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Approximate token counts and budget-aware truncation
+//!
+//! Prompts are limited in model tokens, not characters. [`TokenCounter`]
+//! approximates the byte-pair tokenizers of the cl100k family without
+//! shipping a vocabulary: text is pre-split the way those tokenizers split it
+//! (words with their leading space, digit runs, punctuation runs, whitespace
+//! runs), words are further split at camelCase boundaries, and each piece
+//! costs what such pieces usually cost. Counts depend only on the text, so
+//! context packs, pattern examples and blueprint sections are measured and
+//! cut the same way on every platform.
+
+#[cfg(feature = "napi-bindings")]
+use napi_derive::napi;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Appended to text cut short by [`TokenCounter::truncate`]
+pub const TRUNCATION_MARKER: &str = "\n…";
+
+/// Letters per token in an ASCII word segment
+const LETTERS_PER_TOKEN: usize = 6;
+
+/// Digits per token in a number
+const DIGITS_PER_TOKEN: usize = 3;
+
+/// Punctuation characters per token
+const SYMBOLS_PER_TOKEN: usize = 2;
+
+/// Text cut to a token budget
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct TruncatedText {
+    pub text: String,
+    /// Tokens in `text`, the marker included
+    pub tokens: u32,
+    /// Whether anything was cut
+    pub truncated: bool,
+}
+
+/// A pre-split piece of text: where it ends and what it costs
+struct Piece {
+    end: usize,
+    tokens: u32,
+}
+
+pub struct TokenCounter;
+
+impl TokenCounter {
+    /// Approximate number of model tokens in `text`
+    pub fn count(text: &str) -> u32 {
+        Self::pieces(text).iter().map(|piece| piece.tokens).sum()
+    }
+
+    /// `text` cut to at most `max_tokens`, marker included
+    ///
+    /// The cut falls between pieces, so no word or number is split, and at the
+    /// end of a line when that keeps at least half of what fits.
+    pub fn truncate(text: &str, max_tokens: u32) -> TruncatedText {
+        let pieces = Self::pieces(text);
+        let total: u32 = pieces.iter().map(|piece| piece.tokens).sum();
+        if total <= max_tokens {
+            return TruncatedText { text: text.to_string(), tokens: total, truncated: false };
+        }
+
+        let marker = Self::count(TRUNCATION_MARKER);
+        let Some(available) = max_tokens.checked_sub(marker) else {
+            return TruncatedText { text: String::new(), tokens: 0, truncated: true };
+        };
+        let mut used = 0;
+        let mut cut = 0;
+        for piece in &pieces {
+            if used + piece.tokens > available {
+                break;
+            }
+            used += piece.tokens;
+            cut = piece.end;
+        }
+        if let Some(line_end) = text[..cut].rfind('\n').filter(|&line_end| line_end >= cut / 2) {
+            cut = line_end;
+        }
+
+        let text = format!("{}{}", text[..cut].trim_end(), TRUNCATION_MARKER);
+        TruncatedText { tokens: Self::count(&text), text, truncated: true }
+    }
+
+    fn pieces(text: &str) -> Vec<Piece> {
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let end_of = |index: usize| chars.get(index).map_or(text.len(), |(offset, _)| *offset);
+        let mut pieces = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i].1;
+            let run = |keep: &dyn Fn(char) -> bool| {
+                let mut j = i + 1;
+                while j < chars.len() && keep(chars[j].1) {
+                    j += 1;
+                }
+                j
+            };
+            let (next, tokens) = if c.is_whitespace() {
+                let next = run(&|c: char| c.is_whitespace());
+                // A single space before a word is part of the word's token
+                let leading_space = c == ' ' && next == i + 1 && next < chars.len();
+                (next, u32::from(!leading_space))
+            } else if c.is_alphabetic() {
+                let next = run(&|c: char| c.is_alphabetic());
+                (next, Self::word_tokens(&text[chars[i].0..end_of(next)]))
+            } else if c.is_ascii_digit() {
+                let next = run(&|c: char| c.is_ascii_digit());
+                (next, (next - i).div_ceil(DIGITS_PER_TOKEN) as u32)
+            } else {
+                let next = run(&|c: char| !c.is_alphanumeric() && !c.is_whitespace());
+                let ascii = chars[i..next].iter().filter(|(_, c)| c.is_ascii()).count();
+                let other: usize = chars[i..next]
+                    .iter()
+                    .filter(|(_, c)| !c.is_ascii())
+                    .map(|(_, c)| Self::char_tokens(*c))
+                    .sum();
+                (next, (ascii.div_ceil(SYMBOLS_PER_TOKEN) + other) as u32)
+            };
+            pieces.push(Piece { end: end_of(next), tokens });
+            i = next;
+        }
+        pieces
+    }
+
+    /// Tokens in a run of letters, split at lower-to-upper case boundaries
+    /// ("getUserById" is get, User, By, Id)
+    fn word_tokens(word: &str) -> u32 {
+        let mut tokens = 0;
+        let mut ascii: usize = 0;
+        let mut previous_lower = false;
+        for c in word.chars() {
+            if c.is_uppercase() && previous_lower {
+                tokens += ascii.div_ceil(LETTERS_PER_TOKEN);
+                ascii = 0;
+            }
+            if c.is_ascii() {
+                ascii += 1;
+            } else {
+                tokens += Self::char_tokens(c);
+            }
+            previous_lower = c.is_lowercase();
+        }
+        (tokens + ascii.div_ceil(LETTERS_PER_TOKEN)).max(1) as u32
+    }
+
+    /// Non-ASCII characters cost about a token per two UTF-8 bytes
+    fn char_tokens(c: char) -> usize {
+        (c.len_utf8() / 2).max(1)
+    }
+}
+
+/// Approximate number of model tokens in `text`
+#[cfg_attr(feature = "napi-bindings", napi)]
+pub fn count_tokens(text: String) -> u32 {
+    TokenCounter::count(&text)
+}
+
+/// `text` cut to at most `max_tokens`, ending in a marker when anything was cut
+#[cfg_attr(feature = "napi-bindings", napi)]
+pub fn truncate_to_tokens(text: String, max_tokens: u32) -> TruncatedText {
+    TokenCounter::truncate(&text, max_tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_follow_tokenizer_pieces() {
+        assert_eq!(TokenCounter::count(""), 0);
+        assert_eq!(TokenCounter::count("hello world"), 2);
+        assert_eq!(TokenCounter::count("getUserById"), 4);
+        assert_eq!(TokenCounter::count("internationalization"), 4);
+        assert_eq!(TokenCounter::count("1234567"), 3);
+        assert_eq!(TokenCounter::count("fn main() {}\n"), 5);
+        assert_eq!(TokenCounter::count("日本語"), 3);
+    }
+
+    #[test]
+    fn test_truncation_fits_the_budget_at_a_boundary() {
+        let text = "first line of text\nsecond line of text\nthird line of text";
+        let whole = TokenCounter::truncate(text, 100);
+        assert!(!whole.truncated);
+        assert_eq!(whole.text, text);
+
+        let cut = TokenCounter::truncate(text, 12);
+        assert!(cut.truncated);
+        assert!(cut.tokens <= 12);
+        assert_eq!(cut.text, format!("first line of text\nsecond line of text{}", TRUNCATION_MARKER));
+        assert_eq!(TokenCounter::truncate(text, 12), cut);
+
+        assert_eq!(TokenCounter::truncate(text, 1).text, "");
+        let words = TokenCounter::truncate("alpha beta gamma delta epsilon", 4);
+        assert_eq!(words.text, format!("alpha beta{}", TRUNCATION_MARKER));
+    }
+}
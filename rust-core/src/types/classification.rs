@@ -0,0 +1,323 @@
+//! Classification of files found during a codebase walk
+//!
+//! Size alone is a poor signal: a bundled or code-generated file can be small
+//! and a hand-written source (a grammar, a lookup table) can be large. Files
+//! are classified from their path and from the start and end of their content,
+//! so output of tools and third-party code is excluded with a reason that ends
+//! up in the analysis report, while large sources are still analyzed.
+
+use crate::types::SemanticConcept;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path};
+
+/// Bytes read from the start of a file to look for markers
+const HEAD_BYTES: usize = 8192;
+
+/// Bytes read from the end of a file, where source map comments live
+const TAIL_BYTES: u64 = 1024;
+
+/// Markers are only honored in comments among the first lines of a file
+const MARKER_LINES: usize = 10;
+
+/// Lines longer than this on average mark a file as minified
+const MINIFIED_AVG_LINE_LENGTH: usize = 300;
+
+/// Directory names holding third-party code checked into the repository
+const VENDORED_DIRS: &[&str] = &["vendor", "vendored", "third_party", "third-party", "bower_components", "jspm_packages"];
+
+/// Comment markers written by code generators, most specific first so the
+/// reported marker names the tool when one is given
+const GENERATED_MARKERS: &[&str] = &[
+    "generated by the protocol buffer compiler",
+    "protoc-gen-",
+    "graphql-codegen",
+    "openapi-generator",
+    "swagger-codegen",
+    "autorest",
+    "<auto-generated",
+    "code generated by",
+    "this file was generated",
+    "automatically generated",
+    "autogenerated",
+    "@generated",
+    "do not edit",
+];
+
+/// File name endings of common code generators: protobuf and gRPC stubs,
+/// Dart builders and .NET designers
+const GENERATED_FILE_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    ".pb.cc",
+    ".pb.h",
+    "_pb2.py",
+    "_pb2_grpc.py",
+    "_pb.js",
+    "_pb.d.ts",
+    "_grpc_pb.js",
+    ".g.dart",
+    ".freezed.dart",
+    ".g.cs",
+    ".designer.cs",
+];
+
+/// Directory names code generators write into, e.g. Relay's `__generated__`
+const GENERATED_DIRS: &[&str] = &["__generated__"];
+
+/// What kind of file a walk found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileClass {
+    Source,
+    Generated,
+    Minified,
+    Vendored,
+    Binary,
+}
+
+impl FileClass {
+    /// Skip reason reported for files of this class
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileClass::Source => "source",
+            FileClass::Generated => "generated",
+            FileClass::Minified => "minified",
+            FileClass::Vendored => "vendored",
+            FileClass::Binary => "binary",
+        }
+    }
+}
+
+/// A file's class and the evidence for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Classification {
+    pub class: FileClass,
+    /// What gave it away, e.g. `@generated marker`; `None` for sources
+    pub detail: Option<String>,
+}
+
+impl Classification {
+    fn source() -> Self {
+        Classification { class: FileClass::Source, detail: None }
+    }
+
+    fn new(class: FileClass, detail: String) -> Self {
+        Classification { class, detail: Some(detail) }
+    }
+}
+
+pub struct FileClassifier;
+
+impl FileClassifier {
+    /// Classify a file on disk; unreadable files count as sources and are
+    /// reported by the reader instead
+    pub fn classify(path: &Path) -> Classification {
+        if let Some(classification) = Self::classify_path(path) {
+            return classification;
+        }
+        let classification = match Self::read_sample(path) {
+            Ok((head, tail)) => Self::classify_content(&head, &tail),
+            Err(_) => Classification::source(),
+        };
+        Self::or_generated_name(path, classification)
+    }
+
+    /// Classify in-memory content, e.g. an unsaved editor buffer, as if it
+    /// were the file at `path`
+    pub fn classify_text(path: &Path, content: &str) -> Classification {
+        if let Some(classification) = Self::classify_path(path) {
+            return classification;
+        }
+        let bytes = content.as_bytes();
+        let head = &bytes[..bytes.len().min(HEAD_BYTES)];
+        // Same sample as read_sample: the tail after the head, or the end of the head
+        let tail_start = bytes.len().saturating_sub(TAIL_BYTES as usize);
+        let tail = if bytes.len() > head.len() {
+            &bytes[tail_start.max(head.len())..]
+        } else {
+            &bytes[tail_start..]
+        };
+        Self::or_generated_name(path, Self::classify_content(head, tail))
+    }
+
+    /// The content's classification, or generated when a source carries the
+    /// name of generator output. Markers win since they name the tool.
+    fn or_generated_name(path: &Path, classification: Classification) -> Classification {
+        if classification.class != FileClass::Source {
+            return classification;
+        }
+        Self::classify_generated_name(path).unwrap_or(classification)
+    }
+
+    /// Generated code recognizable from its file or directory name
+    pub fn classify_generated_name(path: &Path) -> Option<Classification> {
+        let file_name = path.file_name()?.to_string_lossy().to_lowercase();
+        if let Some(suffix) = GENERATED_FILE_SUFFIXES.iter().find(|suffix| file_name.ends_with(*suffix)) {
+            return Some(Classification::new(FileClass::Generated, format!("'*{}' file name", suffix)));
+        }
+        if file_name.contains(".generated.") {
+            return Some(Classification::new(FileClass::Generated, "'*.generated.*' file name".to_string()));
+        }
+        path.components().find_map(|component| match component {
+            Component::Normal(name) => GENERATED_DIRS
+                .iter()
+                .find(|dir| name.to_string_lossy() == **dir)
+                .map(|dir| Classification::new(FileClass::Generated, format!("inside {}/", dir))),
+            _ => None,
+        })
+    }
+
+    /// Why `content` counts as generated code, or `None` for any other class
+    pub fn generated_by(path: &Path, content: &str) -> Option<String> {
+        let classification = Self::classify_text(path, content);
+        (classification.class == FileClass::Generated).then(|| classification.detail.unwrap_or_default())
+    }
+
+    /// Classification from the path alone, for vendored directories
+    pub fn classify_path(path: &Path) -> Option<Classification> {
+        path.components().find_map(|component| match component {
+            Component::Normal(name) => {
+                let name = name.to_string_lossy();
+                VENDORED_DIRS
+                    .iter()
+                    .find(|dir| name.eq_ignore_ascii_case(dir))
+                    .map(|dir| Classification::new(FileClass::Vendored, format!("inside {}/", dir)))
+            }
+            _ => None,
+        })
+    }
+
+    /// Classification from the first and last bytes of a file
+    pub fn classify_content(head: &[u8], tail: &[u8]) -> Classification {
+        if head.contains(&0) {
+            return Classification::new(FileClass::Binary, "contains NUL bytes".to_string());
+        }
+
+        let head_text = String::from_utf8_lossy(head);
+        for line in head_text.lines().take(MARKER_LINES) {
+            if !Self::is_comment(line) {
+                continue;
+            }
+            let lower = line.to_lowercase();
+            if let Some(marker) = GENERATED_MARKERS.iter().find(|marker| lower.contains(*marker)) {
+                return Classification::new(FileClass::Generated, format!("'{}' marker", marker));
+            }
+        }
+
+        let lines: Vec<&str> = head_text.lines().collect();
+        let longest = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        if head.len() >= HEAD_BYTES / 2
+            && longest > MINIFIED_AVG_LINE_LENGTH
+            && head.len() / lines.len().max(1) > MINIFIED_AVG_LINE_LENGTH
+        {
+            return Classification::new(
+                FileClass::Minified,
+                format!("average line length over {}", MINIFIED_AVG_LINE_LENGTH),
+            );
+        }
+
+        let tail_text = String::from_utf8_lossy(tail);
+        if tail_text.contains("sourceMappingURL=") {
+            return Classification::new(FileClass::Generated, "sourceMappingURL comment".to_string());
+        }
+
+        Classification::source()
+    }
+
+    fn is_comment(line: &str) -> bool {
+        let line = line.trim_start();
+        ["//", "/*", "*", "#", "--", "<!--", "\"\"\"", "'''"]
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+    }
+
+    fn read_sample(path: &Path) -> std::io::Result<(Vec<u8>, Vec<u8>)> {
+        let mut file = File::open(path)?;
+        let mut head = Vec::with_capacity(HEAD_BYTES);
+        (&mut file).take(HEAD_BYTES as u64).read_to_end(&mut head)?;
+
+        let mut tail = Vec::new();
+        let len = file.metadata()?.len();
+        if len > head.len() as u64 {
+            file.seek(SeekFrom::Start(len.saturating_sub(TAIL_BYTES).max(head.len() as u64)))?;
+            file.read_to_end(&mut tail)?;
+        } else {
+            tail = head[head.len().saturating_sub(TAIL_BYTES as usize)..].to_vec();
+        }
+        Ok((head, tail))
+    }
+}
+
+/// Tag concepts found in generated code so consumers can tell them from
+/// hand-written ones
+pub fn mark_generated(concepts: &mut [SemanticConcept], detail: &str) {
+    for concept in concepts {
+        concept.metadata.insert("generated".to_string(), "true".to_string());
+        concept.metadata.insert("generated_by".to_string(), detail.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_memory_content_matches_file_classification() {
+        let generated = "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api\n";
+        assert_eq!(FileClassifier::classify_text(Path::new("api.pb.go"), generated).class, FileClass::Generated);
+        assert_eq!(FileClassifier::classify_text(Path::new("api.go"), "package api\n").class, FileClass::Source);
+        assert_eq!(FileClassifier::classify_text(Path::new("vendor/lib.go"), "package lib\n").class, FileClass::Vendored);
+    }
+
+    #[test]
+    fn test_generated_and_minified_content() {
+        let generated = b"// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api\n";
+        assert_eq!(FileClassifier::classify_content(generated, b"").class, FileClass::Generated);
+
+        let python = b"# @generated by tooling\nimport os\n";
+        let classification = FileClassifier::classify_content(python, b"");
+        assert_eq!(classification.detail.as_deref(), Some("'@generated' marker"));
+
+        // Markers outside comments, e.g. in string literals, don't count
+        let source = b"const MARKER = \"@generated\";\nexport function f() {}\n";
+        assert_eq!(FileClassifier::classify_content(source, b"").class, FileClass::Source);
+
+        let bundle = format!("var a={};{}", "{}", "a.b=function(c){return c};".repeat(400));
+        assert_eq!(FileClassifier::classify_content(bundle.as_bytes(), b"").class, FileClass::Minified);
+
+        let compiled = b"//# sourceMappingURL=app.js.map\n";
+        assert_eq!(FileClassifier::classify_content(b"export {};\n", compiled).class, FileClass::Generated);
+
+        assert_eq!(FileClassifier::classify_content(b"\x7fELF\0\0", b"").class, FileClass::Binary);
+    }
+
+    #[test]
+    fn test_generated_file_names_and_tool_markers() {
+        let stub = FileClassifier::classify_text(Path::new("proto/user_pb2.py"), "import grpc\n");
+        assert_eq!(stub.class, FileClass::Generated);
+        assert_eq!(stub.detail.as_deref(), Some("'*_pb2.py' file name"));
+        let relay = Path::new("src/__generated__/UserQuery.graphql.ts");
+        assert_eq!(FileClassifier::classify_text(relay, "export type Q = {};\n").class, FileClass::Generated);
+        let client = Path::new("src/api/Client.generated.ts");
+        assert_eq!(FileClassifier::classify_text(client, "export class Client {}\n").class, FileClass::Generated);
+        assert_eq!(FileClassifier::classify_text(Path::new("src/generator.ts"), "export {};\n").class, FileClass::Source);
+
+        // Markers take precedence over names since they name the tool
+        let header = "/**\n * Generated by openapi-generator (https://openapi-generator.tech)\n */\nexport {};\n";
+        let detail = FileClassifier::generated_by(Path::new("src/api/orders.ts"), header);
+        assert_eq!(detail.as_deref(), Some("'openapi-generator' marker"));
+        assert_eq!(FileClassifier::generated_by(Path::new("src/app.ts"), "export {};\n"), None);
+    }
+
+    #[test]
+    fn test_vendored_paths_and_large_sources() {
+        let vendored = FileClassifier::classify_path(Path::new("project/third_party/zlib/inflate.c")).unwrap();
+        assert_eq!(vendored.class, FileClass::Vendored);
+        assert!(FileClassifier::classify_path(Path::new("src/vendors.ts")).is_none());
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let table = dir.path().join("table.rs");
+        let rows: String = (0..60_000).map(|i| format!("    ({}, {}),\n", i, i * 2)).collect();
+        std::fs::write(&table, format!("pub const TABLE: &[(u32, u32)] = &[\n{}];\n", rows)).unwrap();
+        assert_eq!(FileClassifier::classify(&table).class, FileClass::Source);
+    }
+}
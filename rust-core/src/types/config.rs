@@ -1,37 +1,402 @@
 //! Configuration and file filtering logic for semantic analysis
+//!
+//! The JS layer writes project settings to `.in-memoria/config.json` during
+//! setup. [`ProjectConfig`] reads that same file (or a `config.toml` with the
+//! same keys) so the native analyzers honor the configured languages, watch
+//! patterns, ignored paths, analysis limits and disabled rules.
 
-use std::path::Path;
+use crate::types::validation::{ErrorCode, ValidationError};
+use crate::types::{paths, Classification, FileClass, FileClassifier, LanguageDetector, ParseError};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::{Component, Path, PathBuf};
+
+/// Directory holding the project configuration, relative to the project root
+pub const CONFIG_DIR: &str = ".in-memoria";
+
+/// Directories of non-source files and build artifacts, matched by name
+const IGNORED_DIRECTORY_NAMES: &[&str] = &[
+    "node_modules", ".git", "target", "dist", "build", "out", "output", ".next", ".nuxt", ".svelte-kit",
+    ".vitepress", "_site", "public", "static", "assets", "__pycache__", ".pytest_cache", "coverage",
+    ".coverage", "htmlcov", "vendor", "bin", "obj", "Debug", "Release", ".venv", "venv", "env", ".env",
+    "tmp", "temp", ".tmp", "cache", ".cache", "logs", ".logs", "lib-cov", "nyc_output", ".nyc_output",
+    "bower_components", "jspm_packages",
+];
+
+/// Project settings shared with the JS layer
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ProjectConfig {
+    pub version: Option<String>,
+    pub project: ProjectSettings,
+    pub watching: WatchSettings,
+    pub analysis: AnalysisLimits,
+    pub rules: RuleSettings,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ProjectSettings {
+    pub name: Option<String>,
+    pub languages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WatchSettings {
+    /// Globs for files to analyze, e.g. `**/*.ts`
+    pub patterns: Vec<String>,
+    /// Globs for paths to skip, e.g. `**/node_modules/**`
+    pub ignored: Vec<String>,
+    pub debounce_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AnalysisLimits {
+    pub max_file_size: Option<u64>,
+    pub max_source_file_size: Option<u64>,
+    pub max_files: Option<usize>,
+    pub learning_timeout_secs: Option<u64>,
+    pub checkpoint_interval: Option<usize>,
+    /// Descend into symlinked directories and files; off by default
+    pub follow_symlinks: Option<bool>,
+    /// How often learning yields to other work, 0 (never) to 4 (every file)
+    pub nice: Option<u8>,
+    /// Analyze generated code and learn patterns from it; off by default
+    pub include_generated: Option<bool>,
+    /// How candidate patterns are grouped before merging: `description`,
+    /// `type-context`, `example-similarity` or `embedding`
+    pub consolidation: Option<String>,
+    /// Seconds a removed file's tombstone is kept before patterns left
+    /// without occurrences are deleted
+    pub tombstone_grace_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RuleSettings {
+    /// Rule ids whose violations are never reported, e.g. `no-console-log` or `clone_in_loop`
+    pub disabled: Vec<String>,
+}
+
+impl ProjectConfig {
+    pub fn from_json_str(content: &str) -> Result<Self, ParseError> {
+        serde_json::from_str(content)
+            .map_err(|e| ParseError::from_reason(format!("Invalid config.json: {}", e)))
+    }
+
+    pub fn from_toml_str(content: &str) -> Result<Self, ParseError> {
+        toml::from_str(content)
+            .map_err(|e| ParseError::from_reason(format!("Invalid config.toml: {}", e)))
+    }
+
+    /// Load `.in-memoria/config.json` (or `config.toml`) from a project root.
+    /// Returns `None` when the project has no configuration file.
+    pub fn load(project_root: &Path) -> Result<Option<Self>, ParseError> {
+        let config_dir = project_root.join(CONFIG_DIR);
+
+        let json_path = config_dir.join("config.json");
+        if json_path.is_file() {
+            return Self::read(&json_path).and_then(|c| Self::from_json_str(&c)).map(Some);
+        }
+
+        let toml_path = config_dir.join("config.toml");
+        if toml_path.is_file() {
+            return Self::read(&toml_path).and_then(|c| Self::from_toml_str(&c)).map(Some);
+        }
+
+        Ok(None)
+    }
+
+    fn read(path: &Path) -> Result<String, ParseError> {
+        std::fs::read_to_string(path).map_err(|e| {
+            ParseError::from_reason(format!("Failed to read {}: {}", path.display(), e))
+        })
+    }
+}
+
+/// Why a file was left out of an analysis
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exclusion {
+    pub reason: &'static str,
+    /// Evidence behind the reason, e.g. the marker that identified generated code
+    pub detail: Option<String>,
+}
+
+impl Exclusion {
+    fn new(reason: &'static str) -> Self {
+        Exclusion { reason, detail: None }
+    }
+
+    fn classified(class: FileClass, detail: Option<String>) -> Self {
+        Exclusion { reason: class.as_str(), detail }
+    }
+}
 
 /// Configuration for file analysis
 pub struct AnalysisConfig {
-    /// Maximum file size to analyze (in bytes)
+    /// Files larger than this (in bytes) are only analyzed when they are plain source
     pub max_file_size: u64,
+    /// Hard limit (in bytes) for large sources, e.g. hand-written lookup tables
+    pub max_source_file_size: u64,
     /// Maximum files to process
     pub max_files: usize,
+    /// Time budget for one codebase learning run before it returns a partial result
+    pub learning_timeout_secs: u64,
+    /// Files analyzed between learning checkpoints; 0 disables checkpoints
+    pub checkpoint_interval: usize,
+    /// Follow symbolic links while walking the project. Files reached through
+    /// several paths are still analyzed once.
+    pub follow_symlinks: bool,
+    /// How often long learning loops yield to the async runtime; see
+    /// `analysis::pacing` for the chunk size of each level
+    pub nice: u8,
+    /// Analyze generated code instead of skipping it. Its concepts are tagged
+    /// `generated` and pattern learning counts them like hand-written code.
+    pub include_generated: bool,
+    /// Name of the strategy grouping candidate patterns before they are merged;
+    /// see `patterns::consolidation`
+    pub consolidation: Option<String>,
+    /// How long the tombstone of a removed file is kept; see
+    /// `analysis::tombstones`
+    pub tombstone_grace_secs: u64,
     /// Supported file extensions
     pub supported_extensions: Vec<&'static str>,
+    /// Project root that watch and ignore globs are relative to
+    pub project_root: Option<PathBuf>,
+    /// Compiled watch globs; when non-empty a file must match one of them
+    pub include_patterns: Vec<Regex>,
+    /// Compiled ignore globs from the project configuration
+    pub ignored_patterns: Vec<Regex>,
+    /// Rule ids whose violations are not reported
+    pub disabled_rules: Vec<String>,
 }
 
 impl Default for AnalysisConfig {
     fn default() -> Self {
         Self {
             max_file_size: 1_048_576, // 1MB
+            max_source_file_size: 8_388_608, // 8MB
             max_files: 1000,
+            learning_timeout_secs: 300, // 5 minutes
+            checkpoint_interval: 50,
+            follow_symlinks: false,
+            nice: 2,
+            include_generated: false,
+            consolidation: None,
+            tombstone_grace_secs: 604_800, // 1 week
             supported_extensions: vec![
                 "ts", "tsx", "js", "jsx", "rs", "py", "go", "java",
-                "cpp", "c", "cs", "svelte", "sql", "php", "phtml", "inc"
+                "cpp", "c", "h", "hpp", "cs", "svelte", "sql", "php", "phtml", "inc", "ipynb"
             ],
+            project_root: None,
+            include_patterns: Vec::new(),
+            ignored_patterns: Vec::new(),
+            disabled_rules: Vec::new(),
         }
     }
 }
 
 impl AnalysisConfig {
+    /// Build the analysis configuration for a project root, falling back to
+    /// defaults when it has no `.in-memoria` configuration file
+    pub fn load(project_root: &Path) -> Result<Self, ParseError> {
+        Ok(match ProjectConfig::load(project_root)? {
+            Some(project) => Self::from_project_config(&project, project_root)?,
+            None => Self::default(),
+        })
+    }
+
+    /// Apply project settings on top of the defaults
+    pub fn from_project_config(project: &ProjectConfig, project_root: &Path) -> Result<Self, ParseError> {
+        let mut config = Self::default();
+
+        if let Some(max_file_size) = project.analysis.max_file_size {
+            config.max_file_size = max_file_size;
+        }
+        if let Some(max_source_file_size) = project.analysis.max_source_file_size {
+            config.max_source_file_size = max_source_file_size;
+        }
+        if let Some(max_files) = project.analysis.max_files {
+            config.max_files = max_files;
+        }
+        if let Some(timeout) = project.analysis.learning_timeout_secs {
+            config.learning_timeout_secs = timeout;
+        }
+        if let Some(interval) = project.analysis.checkpoint_interval {
+            config.checkpoint_interval = interval;
+        }
+        if let Some(follow_symlinks) = project.analysis.follow_symlinks {
+            config.follow_symlinks = follow_symlinks;
+        }
+        if let Some(nice) = project.analysis.nice {
+            config.nice = nice;
+        }
+        if let Some(include_generated) = project.analysis.include_generated {
+            config.include_generated = include_generated;
+        }
+        config.consolidation = project.analysis.consolidation.clone();
+        if let Some(grace) = project.analysis.tombstone_grace_secs {
+            config.tombstone_grace_secs = grace;
+        }
+
+        let extensions: Vec<&'static str> = project
+            .project
+            .languages
+            .iter()
+            .flat_map(|language| Self::extensions_for_language(language).iter().copied())
+            .collect();
+        if !extensions.is_empty() {
+            config.supported_extensions = extensions;
+        }
+
+        config.project_root = Some(project_root.to_path_buf());
+        config.include_patterns = Self::compile_globs(&project.watching.patterns)?;
+        config.ignored_patterns = Self::compile_globs(&project.watching.ignored)?;
+        config.disabled_rules = project.rules.disabled.clone();
+        Ok(config)
+    }
+
+    /// Check the project's watch and ignore globs, language list and size limit.
+    /// Unlike `should_analyze_file` this skips the built-in directory heuristics.
+    pub fn matches_project_filters(&self, file_path: &Path) -> bool {
+        if let Some(extension) = file_path.extension().and_then(|s| s.to_str()) {
+            let ext = extension.to_lowercase();
+            if !self.supported_extensions.iter().any(|s| *s == ext) {
+                return false;
+            }
+        }
+
+        if let Ok(metadata) = file_path.metadata() {
+            if metadata.len() > self.max_file_size {
+                return false;
+            }
+        }
+
+        self.matches_project_globs(file_path)
+    }
+
+    /// Check a path against the configured watch and ignore globs
+    fn matches_project_globs(&self, file_path: &Path) -> bool {
+        if self.include_patterns.is_empty() && self.ignored_patterns.is_empty() {
+            return true;
+        }
+
+        let relative = self.relative_path(file_path);
+        if self.ignored_patterns.iter().any(|p| p.is_match(&relative)) {
+            return false;
+        }
+        self.include_patterns.is_empty() || self.include_patterns.iter().any(|p| p.is_match(&relative))
+    }
+
+    fn relative_path(&self, file_path: &Path) -> String {
+        let root = self.project_root.as_ref().map(|root| root.to_string_lossy());
+        paths::relative_key(&file_path.to_string_lossy(), root.as_deref())
+    }
+
+    /// Source extensions analyzed for a language name as written by setup
+    fn extensions_for_language(language: &str) -> &'static [&'static str] {
+        match language.to_lowercase().as_str() {
+            "typescript" => &["ts", "tsx"],
+            "javascript" => &["js", "jsx"],
+            "rust" => &["rs"],
+            "python" => &["py", "ipynb"],
+            "go" => &["go"],
+            "java" => &["java"],
+            "c" => &["c", "h"],
+            "cpp" | "c++" => &["cpp", "h", "hpp"],
+            "csharp" | "c#" => &["cs"],
+            "svelte" => &["svelte"],
+            "sql" => &["sql"],
+            "php" => &["php", "phtml", "inc"],
+            _ => &[],
+        }
+    }
+
+    fn compile_globs(globs: &[String]) -> Result<Vec<Regex>, ParseError> {
+        globs
+            .iter()
+            .map(|glob| glob.trim())
+            .filter(|glob| !glob.is_empty())
+            .map(|glob| {
+                Regex::new(&Self::glob_to_regex(glob)).map_err(|e| {
+                    ParseError::from_reason(format!("Invalid glob '{}': {}", glob, e))
+                })
+            })
+            .collect()
+    }
+
+    /// Translate a glob (`*`, `?`, `**`) into an anchored regex over `/`-separated paths
+    fn glob_to_regex(glob: &str) -> String {
+        let glob = glob.trim_start_matches("./");
+        let mut regex = String::from("^");
+        let mut chars = glob.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        regex.push_str("(?:.*/)?");
+                    } else {
+                        regex.push_str(".*");
+                    }
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                _ => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+
+        regex.push('$');
+        regex
+    }
+
     /// Check if a file should be analyzed based on configuration rules
     pub fn should_analyze_file(&self, file_path: &Path) -> bool {
+        self.skip_reason(file_path).is_none()
+    }
+
+    /// Why a file would be skipped, or `None` if it should be analyzed
+    pub fn skip_reason(&self, file_path: &Path) -> Option<&'static str> {
+        self.exclusion(file_path).map(|exclusion| exclusion.reason)
+    }
+
+    /// Why a file would be skipped, with the evidence for generated, minified,
+    /// vendored and binary files, or `None` if it should be analyzed
+    pub fn exclusion(&self, file_path: &Path) -> Option<Exclusion> {
+        self.path_exclusion(file_path, || LanguageDetector::detect_file(file_path)).or_else(|| {
+            let size = file_path.metadata().ok().map(|metadata| metadata.len());
+            self.content_exclusion(FileClassifier::classify(file_path), size)
+        })
+    }
+
+    /// [`exclusion`](Self::exclusion) for content held in memory rather than
+    /// read from `file_path`
+    pub fn exclusion_for_content(&self, file_path: &Path, content: &str) -> Option<Exclusion> {
+        let script_language = || LanguageDetector::detect(&file_path.to_string_lossy(), content);
+        self.path_exclusion(file_path, script_language).or_else(|| {
+            self.content_exclusion(FileClassifier::classify_text(file_path, content), Some(content.len() as u64))
+        })
+    }
+
+    /// Exclusions decided by the path: directories, names, globs and
+    /// extensions; `script_language` is asked only for files without an extension
+    fn path_exclusion(
+        &self,
+        file_path: &Path,
+        script_language: impl FnOnce() -> Option<&'static str>,
+    ) -> Option<Exclusion> {
+        // Third-party directories are reported as vendored rather than ignored
+        if let Some(classification) = FileClassifier::classify_path(file_path) {
+            return Some(Exclusion::classified(classification.class, classification.detail));
+        }
+
         // Skip common non-source directories and build artifacts
-        let path_str = file_path.to_string_lossy();
-        if self.is_ignored_directory(&path_str) {
-            return false;
+        if self.is_ignored_directory(file_path) {
+            return Some(Exclusion::new("ignored_directory"));
         }
 
         // Skip common generated/minified file patterns
@@ -40,80 +405,77 @@ impl AnalysisConfig {
             .unwrap_or("");
         
         if self.is_ignored_file(file_name) {
-            return false;
+            return Some(Exclusion::new("ignored_file"));
         }
 
-        // Check file size - skip very large files to prevent hanging
-        if let Ok(metadata) = file_path.metadata() {
-            if metadata.len() > self.max_file_size {
-                return false;
-            }
+        if !self.matches_project_globs(file_path) {
+            return Some(Exclusion::new("excluded_by_project_config"));
         }
 
-        // Check if file extension is supported
-        if let Some(extension) = file_path.extension().and_then(|s| s.to_str()) {
-            let ext = extension.to_lowercase();
-            let supported = self.supported_extensions.iter().any(|s| *s == ext);
-
-            if std::env::var("IN_MEMORIA_DEBUG_PHP").is_ok()
-                && matches!(ext.as_str(), "php" | "phtml" | "inc")
-            {
-                eprintln!(
-                    "[PHP DEBUG] should_analyze_file: path={} supported={}",
-                    file_path.display(),
-                    supported
-                );
-            }
+        // Check if file extension is supported; a script without one is
+        // analyzed when its shebang names a supported language
+        let Some(extension) = file_path.extension().and_then(|s| s.to_str()) else {
+            return match script_language() {
+                Some(language) if self.supports_language(language) => None,
+                _ => Some(Exclusion::new("unsupported_extension")),
+            };
+        };
+        let ext = extension.to_lowercase();
+        let supported = self.supported_extensions.iter().any(|s| *s == ext);
 
-            supported
-        } else {
-            false
-        }
-    }
-
-    /// Check if a directory should be ignored
-    fn is_ignored_directory(&self, path_str: &str) -> bool {
-        path_str.contains("node_modules")
-            || path_str.contains(".git")
-            || path_str.contains("target")
-            || path_str.contains("dist")
-            || path_str.contains("build")
-            || path_str.contains("out")
-            || path_str.contains("output")
-            || path_str.contains(".next")
-            || path_str.contains(".nuxt")
-            || path_str.contains(".svelte-kit")
-            || path_str.contains(".vitepress")
-            || path_str.contains("_site")
-            || path_str.contains("public")
-            || path_str.contains("static")
-            || path_str.contains("assets")
-            || path_str.contains("__pycache__")
-            || path_str.contains(".pytest_cache")
-            || path_str.contains("coverage")
-            || path_str.contains(".coverage")
-            || path_str.contains("htmlcov")
-            || path_str.contains("vendor")
-            || path_str.contains("bin")
-            || path_str.contains("obj")
-            || path_str.contains("Debug")
-            || path_str.contains("Release")
-            || path_str.contains(".venv")
-            || path_str.contains("venv")
-            || path_str.contains("env")
-            || path_str.contains(".env")
-            || path_str.contains("tmp")
-            || path_str.contains("temp")
-            || path_str.contains(".tmp")
-            || path_str.contains("cache")
-            || path_str.contains(".cache")
-            || path_str.contains("logs")
-            || path_str.contains(".logs")
-            || path_str.contains("lib-cov")
-            || path_str.contains("nyc_output")
-            || path_str.contains(".nyc_output")
-            || path_str.contains("bower_components")
-            || path_str.contains("jspm_packages")
+        if std::env::var("IN_MEMORIA_DEBUG_PHP").is_ok()
+            && matches!(ext.as_str(), "php" | "phtml" | "inc")
+        {
+            eprintln!(
+                "[PHP DEBUG] should_analyze_file: path={} supported={}",
+                file_path.display(),
+                supported
+            );
+        }
+
+        if !supported {
+            return Some(Exclusion::new("unsupported_extension"));
+        }
+
+        None
+    }
+
+    /// Exclusions decided by the content's classification and size
+    fn content_exclusion(&self, classification: Classification, size: Option<u64>) -> Option<Exclusion> {
+        // Output of code generators and bundlers is excluded whatever its size,
+        // unless the project opted into analyzing generated code
+        let included = classification.class == FileClass::Source
+            || (classification.class == FileClass::Generated && self.include_generated);
+        if !included {
+            return Some(Exclusion::classified(classification.class, classification.detail));
+        }
+
+        // Large sources are still analyzed up to the hard limit to prevent hanging
+        match size {
+            Some(size) if size > self.max_source_file_size => Some(Exclusion {
+                reason: "too_large",
+                detail: Some(format!("{} bytes", size)),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Whether a directory of `file_path` below the project root is one to skip.
+    /// Only whole names count, so `layout/` or a root under `/tmp` are kept.
+    fn is_ignored_directory(&self, file_path: &Path) -> bool {
+        let relative = self
+            .project_root
+            .as_deref()
+            .and_then(|root| file_path.strip_prefix(root).ok())
+            .unwrap_or(file_path);
+        relative
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .any(|component| match component {
+                Component::Normal(name) => IGNORED_DIRECTORY_NAMES.contains(&name.to_string_lossy().as_ref()),
+                _ => false,
+            })
     }
 
     /// Check if a file should be ignored based on its name
@@ -132,6 +494,32 @@ impl AnalysisConfig {
             || file_name == "poetry.lock"
     }
 
+    /// Detect programming language from file path and, when the extension is
+    /// missing or ambiguous (a script, a `.h` header), from the content
+    pub fn detect_language(&self, file_path: &str, content: &str) -> String {
+        match LanguageDetector::needs_content(file_path).then(|| LanguageDetector::detect(file_path, content)) {
+            Some(Some(language)) => language.to_string(),
+            _ => self.detect_language_from_path(file_path),
+        }
+    }
+
+    /// [`detect_language`](Self::detect_language) reading only the start of a
+    /// file on disk, and only when its extension leaves the language open
+    pub fn detect_file_language(&self, file_path: &Path) -> String {
+        let path = file_path.to_string_lossy();
+        match LanguageDetector::needs_content(&path).then(|| LanguageDetector::detect_file(file_path)) {
+            Some(Some(language)) => language.to_string(),
+            _ => self.detect_language_from_path(&path),
+        }
+    }
+
+    /// Whether one of the analyzed extensions is of `language`
+    fn supports_language(&self, language: &str) -> bool {
+        self.supported_extensions
+            .iter()
+            .any(|extension| self.detect_language_from_path(&format!("file.{}", extension)) == language)
+    }
+
     /// Detect programming language from file path
     pub fn detect_language_from_path(&self, file_path: &str) -> String {
         if let Some(extension) = Path::new(file_path)
@@ -142,13 +530,13 @@ impl AnalysisConfig {
                 "ts" | "tsx" => "typescript".to_string(),
                 "js" | "jsx" => "javascript".to_string(),
                 "rs" => "rust".to_string(),
-                "py" => "python".to_string(),
+                "py" | "ipynb" => "python".to_string(),
                 "php" | "phtml" | "inc" => "php".to_string(),
                 "sql" => "sql".to_string(),
                 "go" => "go".to_string(),
                 "java" => "java".to_string(),
-                "c" => "c".to_string(),
-                "cpp" | "cc" | "cxx" => "cpp".to_string(),
+                "c" | "h" => "c".to_string(),
+                "cpp" | "cc" | "cxx" | "hpp" => "cpp".to_string(),
                 "cs" => "csharp".to_string(),
                 "svelte" => "svelte".to_string(),
                 _ => "generic".to_string(),
@@ -159,6 +547,51 @@ impl AnalysisConfig {
     }
 }
 
+/// Part of a project to learn from, as globs relative to the project root.
+/// An empty scope covers the whole project.
+#[derive(Debug, Clone, Default)]
+pub struct PathScope {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl PathScope {
+    /// Compile include and exclude globs; each of `paths` (a directory or
+    /// file) includes itself and everything below it
+    pub fn new(include: &[String], exclude: &[String], paths: &[String]) -> Result<Self, ParseError> {
+        let mut include = include.to_vec();
+        for path in paths.iter().map(|path| path.trim()).filter(|path| !path.is_empty()) {
+            let path = paths::normalize_path(path);
+            if path.starts_with('/') || path.split('/').any(|segment| segment == "..") {
+                return Err(ValidationError::new(
+                    ErrorCode::InvalidArgument,
+                    format!("Scope path '{}' must be relative to the project root", path),
+                )
+                .into());
+            }
+            include.push(path.clone());
+            include.push(format!("{}/**", path));
+        }
+
+        Ok(PathScope {
+            include: AnalysisConfig::compile_globs(&include)?,
+            exclude: AnalysisConfig::compile_globs(exclude)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether a file, given relative to the project root, is in scope
+    pub fn matches(&self, relative: &str) -> bool {
+        if self.exclude.iter().any(|p| p.is_match(relative)) {
+            return false;
+        }
+        self.include.is_empty() || self.include.iter().any(|p| p.is_match(relative))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,6 +605,8 @@ mod tests {
         assert!(config.supported_extensions.contains(&"ts"));
         assert!(config.supported_extensions.contains(&"rs"));
         assert!(config.supported_extensions.contains(&"sql"));
+        assert!(!config.follow_symlinks);
+        assert_eq!(config.nice, crate::analysis::DEFAULT_NICE);
     }
 
     #[test]
@@ -254,6 +689,7 @@ mod tests {
         assert_eq!(config.detect_language_from_path("test.jsx"), "javascript");
         assert_eq!(config.detect_language_from_path("test.rs"), "rust");
         assert_eq!(config.detect_language_from_path("test.py"), "python");
+        assert_eq!(config.detect_language_from_path("analysis.ipynb"), "python");
         assert_eq!(config.detect_language_from_path("test.sql"), "sql");
         assert_eq!(config.detect_language_from_path("test.go"), "go");
         assert_eq!(config.detect_language_from_path("test.java"), "java");
@@ -261,6 +697,8 @@ mod tests {
         assert_eq!(config.detect_language_from_path("test.cpp"), "cpp");
         assert_eq!(config.detect_language_from_path("test.cc"), "cpp");
         assert_eq!(config.detect_language_from_path("test.cxx"), "cpp");
+        assert_eq!(config.detect_language_from_path("test.h"), "c");
+        assert_eq!(config.detect_language_from_path("test.hpp"), "cpp");
         assert_eq!(config.detect_language_from_path("test.cs"), "csharp");
         assert_eq!(config.detect_language_from_path("test.svelte"), "svelte");
         assert_eq!(config.detect_language_from_path("test.php"), "php");
@@ -276,6 +714,7 @@ mod tests {
             max_file_size: 500_000, // 500KB
             max_files: 500,
             supported_extensions: vec!["ts", "js", "rs"],
+            ..Default::default()
         };
 
         assert_eq!(config.max_file_size, 500_000);
@@ -293,12 +732,18 @@ mod tests {
     fn test_is_ignored_directory_directly() {
         let config = AnalysisConfig::default();
         
-        assert!(config.is_ignored_directory("node_modules/package"));
-        assert!(config.is_ignored_directory("dist/build"));
-        assert!(config.is_ignored_directory("target/debug"));
-        assert!(config.is_ignored_directory("__pycache__/test"));
-        assert!(!config.is_ignored_directory("src/components"));
-        assert!(!config.is_ignored_directory("lib/utils"));
+        assert!(config.is_ignored_directory(Path::new("node_modules/package/index.js")));
+        assert!(config.is_ignored_directory(Path::new("dist/build/app.js")));
+        assert!(config.is_ignored_directory(Path::new("target/debug/main.rs")));
+        assert!(config.is_ignored_directory(Path::new("__pycache__/test.py")));
+        assert!(!config.is_ignored_directory(Path::new("src/components/button.ts")));
+        assert!(!config.is_ignored_directory(Path::new("lib/utils/format.ts")));
+        // Whole directory names only, and only below the project root
+        assert!(!config.is_ignored_directory(Path::new("src/layout/outline.ts")));
+        assert!(!config.is_ignored_directory(Path::new("src/build.rs")));
+        let rooted = AnalysisConfig { project_root: Some(PathBuf::from("/tmp/.tmpA1b2")), ..Default::default() };
+        assert!(!rooted.is_ignored_directory(Path::new("/tmp/.tmpA1b2/src/app.ts")));
+        assert!(rooted.is_ignored_directory(Path::new("/tmp/.tmpA1b2/tmp/app.ts")));
     }
 
     #[test]
@@ -313,4 +758,184 @@ mod tests {
         assert!(!config.is_ignored_file("package.json"));
         assert!(!config.is_ignored_file("test.ts"));
     }
+
+    #[test]
+    fn test_glob_to_regex() {
+        let matches = |glob: &str, path: &str| Regex::new(&AnalysisConfig::glob_to_regex(glob)).unwrap().is_match(path);
+
+        assert!(matches("**/*.ts", "app.ts"));
+        assert!(matches("**/*.ts", "src/deep/app.ts"));
+        assert!(!matches("**/*.ts", "src/app.tsx"));
+        assert!(matches("**/node_modules/**", "node_modules/pkg/index.js"));
+        assert!(matches("**/node_modules/**", "web/node_modules/pkg/index.js"));
+        assert!(!matches("src/*.rs", "src/nested/lib.rs"));
+        assert!(matches("./src/?.rs", "src/a.rs"));
+    }
+
+    #[test]
+    fn test_path_scope() {
+        let all = PathScope::default();
+        assert!(all.is_empty() && all.matches("src/app.ts"));
+
+        let globs = |globs: &[&str]| globs.iter().map(|g| g.to_string()).collect::<Vec<_>>();
+        let payments = PathScope::new(&globs(&["src/payments/**"]), &globs(&["**/*.test.ts"]), &[]).unwrap();
+        assert!(payments.matches("src/payments/refunds/api.ts"));
+        assert!(!payments.matches("src/payments/api.test.ts"));
+        assert!(!payments.matches("src/users/api.ts"));
+
+        let paths = PathScope::new(&[], &[], &globs(&["./src/users/", "lib/util.py"])).unwrap();
+        assert!(paths.matches("src/users/model.ts"));
+        assert!(paths.matches("lib/util.py"));
+        assert!(!paths.matches("src/users_legacy/model.ts"));
+        assert!(PathScope::new(&[], &[], &globs(&["../elsewhere"])).is_err());
+    }
+
+    #[test]
+    fn test_load_project_config_from_setup_json() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join(CONFIG_DIR)).unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_DIR).join("config.json"),
+            r#"{
+                "version": "0.6.0",
+                "project": { "name": "demo", "languages": ["typescript", "rust"] },
+                "intelligence": { "enableRealTimeAnalysis": true },
+                "watching": {
+                    "patterns": ["**/*.ts", "**/*.rs"],
+                    "ignored": ["**/generated/**"],
+                    "debounceMs": 500
+                },
+                "analysis": { "maxFileSize": 2048, "maxFiles": 10, "learningTimeoutSecs": 60, "checkpointInterval": 20, "followSymlinks": true, "nice": 4 },
+                "rules": { "disabled": ["no-console-log"] }
+            }"#,
+        )
+        .unwrap();
+
+        let config = AnalysisConfig::load(dir.path()).unwrap();
+        assert_eq!(config.max_file_size, 2048);
+        assert_eq!(config.max_files, 10);
+        assert_eq!(config.learning_timeout_secs, 60);
+        assert_eq!(config.checkpoint_interval, 20);
+        assert!(config.follow_symlinks);
+        assert_eq!(config.nice, 4);
+        assert_eq!(config.supported_extensions, vec!["ts", "tsx", "rs"]);
+        assert_eq!(config.disabled_rules, vec!["no-console-log".to_string()]);
+
+        let root = dir.path();
+        assert!(config.matches_project_filters(&root.join("src/app.ts")));
+        assert!(config.matches_project_filters(&root.join("src/lib.rs")));
+        assert!(!config.matches_project_filters(&root.join("src/app.tsx")));
+        assert!(!config.matches_project_filters(&root.join("src/main.py")));
+        assert!(!config.matches_project_filters(&root.join("src/generated/api.ts")));
+    }
+
+    #[test]
+    fn test_load_project_config_from_toml() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join(CONFIG_DIR)).unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_DIR).join("config.toml"),
+            "[project]\nlanguages = [\"python\"]\n\n[analysis]\nmaxFiles = 5\n",
+        )
+        .unwrap();
+
+        let project = ProjectConfig::load(dir.path()).unwrap().unwrap();
+        assert_eq!(project.project.languages, vec!["python".to_string()]);
+        assert_eq!(project.analysis.max_files, Some(5));
+        assert!(project.watching.patterns.is_empty());
+        assert!(project.rules.disabled.is_empty());
+    }
+
+    #[test]
+    fn test_missing_project_config_uses_defaults() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(ProjectConfig::load(dir.path()).unwrap().is_none());
+
+        let config = AnalysisConfig::load(dir.path()).unwrap();
+        assert_eq!(config.max_files, 1000);
+        assert!(config.include_patterns.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_project_config_is_an_error() {
+        assert!(ProjectConfig::from_json_str("{ not json").is_err());
+        assert!(ProjectConfig::from_json_str(r#"{"analysis": {"maxFiles": "many"}}"#).is_err());
+    }
+
+    #[test]
+    fn test_skip_reasons() {
+        let config = AnalysisConfig::default();
+
+        assert_eq!(config.skip_reason(Path::new("src/app.ts")), None);
+        assert_eq!(config.skip_reason(Path::new("node_modules/x/index.js")), Some("ignored_directory"));
+        assert_eq!(config.skip_reason(Path::new("src/app.min.js")), Some("ignored_file"));
+        assert_eq!(config.skip_reason(Path::new("src/README.md")), Some("unsupported_extension"));
+        assert_eq!(config.skip_reason(Path::new("src/Makefile")), Some("unsupported_extension"));
+        assert_eq!(config.skip_reason(Path::new("lib/vendor/jquery.js")), Some("vendored"));
+    }
+
+    #[test]
+    fn test_scripts_and_headers_detected_from_content() {
+        let config = AnalysisConfig::default();
+
+        let script = "#!/usr/bin/env python3\nimport sys\n\ndef main():\n    sys.exit(0)\n";
+        assert!(config.exclusion_for_content(Path::new("scripts/deploy"), script).is_none());
+        assert_eq!(config.detect_language("scripts/deploy", script), "python");
+        let shell = config.exclusion_for_content(Path::new("scripts/setup"), "#!/bin/bash\nset -e\n");
+        assert_eq!(shell.unwrap().reason, "unsupported_extension");
+
+        let header = "#include <string>\nnamespace app {\nclass Config {\npublic:\n  std::string name;\n};\n}\n";
+        assert_eq!(config.detect_language("include/config.h", header), "cpp");
+        assert_eq!(config.detect_language("include/list.h", "typedef struct list list;\n"), "c");
+        assert_eq!(config.detect_language("src/main.rs", "#!/usr/bin/env python3\n"), "rust");
+    }
+
+    #[test]
+    fn test_exclusions_use_content_not_size() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = AnalysisConfig {
+            max_file_size: 1024,
+            max_source_file_size: 64 * 1024,
+            project_root: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        let generated = dir.path().join("api.pb.go");
+        std::fs::write(&generated, "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api\n").unwrap();
+        let exclusion = config.exclusion(&generated).unwrap();
+        assert_eq!(exclusion.reason, "generated");
+        assert_eq!(exclusion.detail.as_deref(), Some("'protoc-gen-' marker"));
+
+        let compiled = dir.path().join("app.js");
+        std::fs::write(&compiled, "export const a = 1;\n//# sourceMappingURL=app.js.map\n").unwrap();
+        assert_eq!(config.skip_reason(&compiled), Some("generated"));
+
+        // Over max_file_size but hand-written: still analyzed
+        let table = dir.path().join("table.rs");
+        let rows: String = (0..500).map(|i| format!("    {},\n", i)).collect();
+        std::fs::write(&table, format!("pub const TABLE: &[u32] = &[\n{}];\n", rows)).unwrap();
+        assert_eq!(config.skip_reason(&table), None);
+
+        let huge = dir.path().join("huge.rs");
+        std::fs::write(&huge, "// data\n".repeat(10_000)).unwrap();
+        assert_eq!(config.skip_reason(&huge), Some("too_large"));
+    }
+
+    #[test]
+    fn test_include_generated_override() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let stub = dir.path().join("user_pb2.py");
+        std::fs::write(&stub, "import grpc\n").unwrap();
+        let bundle = dir.path().join("app.js");
+        std::fs::write(&bundle, "export const a = 1;\n//# sourceMappingURL=app.js.map\n").unwrap();
+        let defaults = AnalysisConfig::from_project_config(&ProjectConfig::default(), dir.path()).unwrap();
+        assert_eq!(defaults.skip_reason(&stub), Some("generated"));
+
+        let project = ProjectConfig::from_json_str(r#"{ "analysis": { "includeGenerated": true } }"#).unwrap();
+        let config = AnalysisConfig::from_project_config(&project, dir.path()).unwrap();
+        assert!(config.include_generated);
+        assert_eq!(config.skip_reason(&stub), None);
+        assert_eq!(config.skip_reason(&bundle), None);
+        assert_eq!(config.skip_reason(&dir.path().join("app.min.js")), Some("ignored_file"));
+    }
 }
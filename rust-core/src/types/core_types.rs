@@ -2,10 +2,12 @@
 
 #[cfg(feature = "napi-bindings")]
 use napi_derive::napi;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
 #[cfg_attr(feature = "napi-bindings", napi(object))]
 pub struct SemanticConcept {
     pub id: String,
@@ -18,13 +20,55 @@ pub struct SemanticConcept {
     pub metadata: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Order concepts for output: by file path, then start line, then id, so
+/// results don't depend on the hash order they were stored in
+pub fn sort_concepts(concepts: &mut [SemanticConcept]) {
+    concepts.sort_by(|a, b| {
+        a.file_path
+            .cmp(&b.file_path)
+            .then_with(|| a.line_range.start.cmp(&b.line_range.start))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
 #[cfg_attr(feature = "napi-bindings", napi(object))]
 pub struct LineRange {
     pub start: u32,
     pub end: u32,
 }
 
+/// Core pattern representation
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct Pattern {
+    pub id: String,
+    pub pattern_type: String,
+    pub description: String,
+    pub frequency: u32,
+    pub confidence: f64,
+    pub examples: Vec<PatternExample>,
+    pub contexts: Vec<String>,
+}
+
+/// Order patterns for output by id, so results don't depend on the hash order
+/// they were stored in
+pub fn sort_patterns(patterns: &mut [Pattern]) {
+    patterns.sort_by(|a, b| a.id.cmp(&b.id));
+}
+
+/// Example of a pattern occurrence
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi-bindings", napi(object))]
+pub struct PatternExample {
+    pub code: String,
+    pub file_path: String,
+    pub line_range: LineRange,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "napi-bindings", napi(object))]
 pub struct CodebaseAnalysisResult {
@@ -108,6 +152,21 @@ mod tests {
         assert_eq!(concept.line_range.end, 1);
     }
 
+    #[test]
+    fn test_sort_concepts_by_file_line_and_id() {
+        let mut concepts = vec![
+            create_test_concept("b", "function"),
+            create_test_concept("a", "function"),
+            create_test_concept("first", "class"),
+        ];
+        concepts[0].file_path = "z.ts".to_string();
+        concepts[2].line_range = LineRange { start: 0, end: 3 };
+
+        sort_concepts(&mut concepts);
+        let ids: Vec<&str> = concepts.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["test_first", "test_a", "test_b"]);
+    }
+
     #[test]
     fn test_line_range() {
         let range = LineRange { start: 10, end: 20 };
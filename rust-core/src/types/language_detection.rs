@@ -0,0 +1,179 @@
+//! Language detection from file content
+//!
+//! Most extensions name one language, but not every file has one: scripts
+//! in `bin/` often carry a shebang instead, and a `.h` header may be C or
+//! C++. [`LanguageDetector`] settles those files from their content. A
+//! shebang's interpreter decides first; otherwise the candidates of an
+//! ambiguous extension are scored by the tokens characteristic of each, a
+//! small keyword classifier, and a tie (including no evidence at all) goes to
+//! the extension's usual language. Files whose extension names one language
+//! never reach it.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Bytes read from the start of a file on disk to detect its language
+const HEAD_BYTES: u64 = 8192;
+
+/// Occurrences of one token counted towards a score, so a single repeated
+/// keyword can't outweigh the others
+const MAX_TOKEN_HITS: usize = 5;
+
+/// Extensions shared by several languages, the usual language first
+const AMBIGUOUS_EXTENSIONS: &[(&str, &[&str])] = &[("h", &["c", "cpp"]), ("inc", &["php", "cpp", "c"])];
+
+/// Shebang interpreters, without version suffixes, and their languages
+const INTERPRETERS: &[(&str, &str)] = &[
+    ("python", "python"),
+    ("pypy", "python"),
+    ("node", "javascript"),
+    ("nodejs", "javascript"),
+    ("bun", "javascript"),
+    ("deno", "typescript"),
+    ("ts-node", "typescript"),
+    ("tsx", "typescript"),
+    ("php", "php"),
+    ("rust-script", "rust"),
+];
+
+/// Tokens that speak for a language over the others sharing its extensions
+const LANGUAGE_TOKENS: &[(&str, &[(&str, f64)])] = &[
+    (
+        "cpp",
+        &[
+            ("namespace ", 3.0),
+            ("template <", 3.0),
+            ("template<", 3.0),
+            ("std::", 3.0),
+            ("public:", 3.0),
+            ("private:", 3.0),
+            ("protected:", 3.0),
+            ("nullptr", 3.0),
+            ("constexpr", 3.0),
+            ("typename ", 2.0),
+            ("virtual ", 2.0),
+            ("operator", 2.0),
+            ("class ", 2.0),
+            ("#include <string>", 2.0),
+            ("#include <vector>", 2.0),
+        ],
+    ),
+    (
+        "c",
+        &[
+            ("typedef struct", 2.0),
+            ("extern \"C\"", 2.0),
+            ("#include <stdio.h>", 2.0),
+            ("#include <stdlib.h>", 2.0),
+            ("#include <string.h>", 2.0),
+            ("malloc(", 1.5),
+            ("free(", 1.0),
+            ("printf(", 1.0),
+        ],
+    ),
+    ("php", &[("<?php", 10.0), ("$this->", 3.0), ("function ", 1.0), ("echo ", 1.0)]),
+];
+
+pub struct LanguageDetector;
+
+impl LanguageDetector {
+    /// Whether the extension leaves the language to the content: it is
+    /// missing or shared by several languages
+    pub fn needs_content(file_path: &str) -> bool {
+        Self::extension(file_path).is_none() || Self::ambiguous_candidates(file_path).is_some()
+    }
+
+    /// Languages an ambiguous extension may stand for, the usual one first
+    pub fn ambiguous_candidates(file_path: &str) -> Option<&'static [&'static str]> {
+        let extension = Self::extension(file_path)?;
+        AMBIGUOUS_EXTENSIONS
+            .iter()
+            .find(|(ambiguous, _)| *ambiguous == extension)
+            .map(|(_, candidates)| *candidates)
+    }
+
+    /// Language of a file whose extension is missing or ambiguous: its
+    /// shebang's, else the best scoring candidate of its extension; `None`
+    /// for a file without an extension or a shebang naming a known interpreter
+    pub fn detect(file_path: &str, content: &str) -> Option<&'static str> {
+        if let Some(language) = Self::from_shebang(content) {
+            return Some(language);
+        }
+        let candidates = Self::ambiguous_candidates(file_path)?;
+        let mut best = (candidates[0], Self::score(content, candidates[0]));
+        for &candidate in &candidates[1..] {
+            let score = Self::score(content, candidate);
+            if score > best.1 {
+                best = (candidate, score);
+            }
+        }
+        Some(best.0)
+    }
+
+    /// [`detect`](Self::detect) over the start of a file on disk; `None` when
+    /// it can't be read
+    pub fn detect_file(path: &Path) -> Option<&'static str> {
+        let mut head = Vec::new();
+        File::open(path).ok()?.take(HEAD_BYTES).read_to_end(&mut head).ok()?;
+        Self::detect(&path.to_string_lossy(), &String::from_utf8_lossy(&head))
+    }
+
+    /// Language of the interpreter a `#!` line runs, e.g. `/usr/bin/python3`
+    /// or `/usr/bin/env -S node --no-warnings`
+    pub fn from_shebang(content: &str) -> Option<&'static str> {
+        let line = content.trim_start_matches('\u{feff}').lines().next()?.strip_prefix("#!")?;
+        let mut words = line.split_whitespace();
+        let mut program = words.next()?.rsplit('/').next()?;
+        if program == "env" {
+            program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+        }
+        let name = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+        INTERPRETERS
+            .iter()
+            .find(|(interpreter, _)| *interpreter == name)
+            .map(|(_, language)| *language)
+    }
+
+    /// Weighted count of the tokens characteristic of `language` in `content`
+    pub fn score(content: &str, language: &str) -> f64 {
+        LANGUAGE_TOKENS
+            .iter()
+            .find(|(scored, _)| *scored == language)
+            .map_or(0.0, |(_, tokens)| {
+                tokens
+                    .iter()
+                    .map(|(token, weight)| weight * content.matches(token).take(MAX_TOKEN_HITS).count() as f64)
+                    .sum()
+            })
+    }
+
+    fn extension(file_path: &str) -> Option<String> {
+        Path::new(file_path).extension().and_then(|e| e.to_str()).map(str::to_lowercase)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shebangs_and_ambiguous_extensions() {
+        assert_eq!(LanguageDetector::detect("bin/deploy", "#!/usr/bin/env python3.11\nimport sys\n"), Some("python"));
+        assert_eq!(LanguageDetector::detect("bin/serve", "#!/usr/bin/env -S node --no-warnings\n"), Some("javascript"));
+        assert_eq!(LanguageDetector::detect("scripts/build", "#!/usr/local/bin/ts-node\n"), Some("typescript"));
+        assert_eq!(LanguageDetector::detect("bin/setup", "#!/bin/bash\nset -e\n"), None);
+        assert_eq!(LanguageDetector::detect("Makefile", "all:\n\tcc main.c\n"), None);
+
+        let cpp = "#pragma once\n#include <vector>\nnamespace geo {\nclass Shape {\npublic:\n  virtual double area() const = 0;\n};\n}\n";
+        assert_eq!(LanguageDetector::detect("include/shape.h", cpp), Some("cpp"));
+        let c = "#include <stdlib.h>\ntypedef struct point { int x; int y; } point;\npoint *point_new(void);\n";
+        assert_eq!(LanguageDetector::detect("include/point.h", c), Some("c"));
+        assert_eq!(LanguageDetector::detect("include/empty.h", ""), Some("c"));
+        assert_eq!(LanguageDetector::detect("views/header.inc", "<?php echo $title; ?>"), Some("php"));
+
+        assert!(LanguageDetector::needs_content("bin/deploy") && LanguageDetector::needs_content("a.H"));
+        assert!(!LanguageDetector::needs_content("src/main.rs"));
+        assert_eq!(LanguageDetector::detect("src/main.rs", "fn main() {}"), None);
+    }
+}
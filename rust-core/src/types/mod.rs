@@ -1,7 +1,13 @@
 pub mod core_types;
 pub mod errors;
 pub mod config;
+pub mod classification;
+pub mod language_detection;
+pub mod validation;
+pub mod paths;
 
 pub use core_types::*;
 pub use errors::*;
-pub use config::*;
\ No newline at end of file
+pub use config::*;
+pub use classification::*;
+pub use language_detection::*;
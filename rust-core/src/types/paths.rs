@@ -0,0 +1,172 @@
+//! Platform-independent path keys
+//!
+//! Concept ids and persisted state must not depend on whether a path was
+//! written with `/` or `\`, with a `\\?\` verbatim prefix, or relative to a
+//! different working directory. Keys are forward-slash paths relative to the
+//! project root; paths outside the root keep their absolute normalized form.
+
+use crate::types::SemanticConcept;
+use std::path::Path;
+
+/// Forward slashes, no verbatim prefix, no `.` segments or repeated
+/// separators, and an upper-case drive letter. UNC shares keep their leading `//`.
+pub fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let (prefix, rest) = if let Some(share) = path.strip_prefix("//?/UNC/") {
+        ("//", share)
+    } else if let Some(verbatim) = path.strip_prefix("//?/") {
+        ("", verbatim)
+    } else if let Some(share) = path.strip_prefix("//") {
+        ("//", share)
+    } else if let Some(absolute) = path.strip_prefix('/') {
+        ("/", absolute)
+    } else {
+        ("", path.as_str())
+    };
+
+    let mut segments: Vec<String> = rest
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .map(str::to_string)
+        .collect();
+    if let Some(first) = segments.first_mut() {
+        if is_drive(first) {
+            *first = first.to_uppercase();
+        }
+    }
+
+    let normalized = format!("{}{}", prefix, segments.join("/"));
+    if normalized.is_empty() {
+        ".".to_string()
+    } else {
+        normalized
+    }
+}
+
+/// A path's key relative to `root`. Windows paths (drive letters and UNC
+/// shares) compare case-insensitively, as the file system does.
+pub fn relative_key(path: &str, root: Option<&str>) -> String {
+    let path = normalize_path(path);
+    let Some(root) = root.map(normalize_path).filter(|root| root != ".") else {
+        return path;
+    };
+
+    let case_insensitive = is_windows_path(&root);
+    let (haystack, needle) = if case_insensitive {
+        (path.to_ascii_lowercase(), root.to_ascii_lowercase())
+    } else {
+        (path.clone(), root.clone())
+    };
+
+    if haystack == needle {
+        return ".".to_string();
+    }
+    let needle = if needle.ends_with('/') { needle } else { format!("{}/", needle) };
+    if haystack.starts_with(&needle) {
+        path[needle.len()..].to_string()
+    } else {
+        path
+    }
+}
+
+/// [`relative_key`] for a root given as a path
+pub fn relative_to(path: &str, root: &Path) -> String {
+    relative_key(path, Some(&root.to_string_lossy()))
+}
+
+/// Whether two paths name the same file, or one is the other's project-relative
+/// form, once both are normalized
+pub fn same_file(a: &str, b: &str) -> bool {
+    let (a, b) = (normalize_path(a), normalize_path(b));
+    if a == b {
+        return true;
+    }
+    let (longer, shorter) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    !shorter.starts_with('/') && longer.ends_with(&format!("/{}", shorter))
+}
+
+/// A concept id built from the project-relative path, type, name and line,
+/// so the same concept gets the same id on every platform and run
+pub fn concept_id(concept: &SemanticConcept, root: Option<&str>) -> String {
+    format!(
+        "{}::{}::{}::L{}",
+        relative_key(&concept.file_path, root),
+        concept.concept_type,
+        concept.name,
+        concept.line_range.start
+    )
+}
+
+fn is_drive(segment: &str) -> bool {
+    let bytes = segment.as_bytes();
+    bytes.len() == 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+fn is_windows_path(normalized: &str) -> bool {
+    normalized.starts_with("//") || normalized.split('/').next().is_some_and(is_drive)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LineRange;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_normalize_windows_and_unc_paths() {
+        assert_eq!(normalize_path(r"c:\Projects\app\src\main.ts"), "C:/Projects/app/src/main.ts");
+        assert_eq!(normalize_path(r"\\?\C:\Projects\app\.\src"), "C:/Projects/app/src");
+        assert_eq!(normalize_path(r"\\server\share\app\lib.rs"), "//server/share/app/lib.rs");
+        assert_eq!(normalize_path(r"\\?\UNC\server\share\app\lib.rs"), "//server/share/app/lib.rs");
+        assert_eq!(normalize_path("/home//dev/./app/"), "/home/dev/app");
+        assert_eq!(normalize_path("./src\\utils.py"), "src/utils.py");
+        assert_eq!(normalize_path(""), ".");
+    }
+
+    #[test]
+    fn test_relative_keys_round_trip_across_platforms() {
+        let windows = relative_key(r"C:\Projects\App\src\billing\invoice.ts", Some(r"c:\projects\app\"));
+        let verbatim = relative_key(r"\\?\C:\Projects\App\src\billing\invoice.ts", Some(r"C:\Projects\App"));
+        let unix = relative_key("/home/dev/app/src/billing/invoice.ts", Some("/home/dev/app"));
+        assert_eq!(windows, "src/billing/invoice.ts");
+        assert_eq!(verbatim, windows);
+        assert_eq!(unix, windows);
+        assert_eq!(relative_key(&windows, Some("/home/dev/app")), windows);
+
+        let unc = relative_key(r"\\server\share\app\lib.rs", Some(r"\\SERVER\share\app"));
+        assert_eq!(unc, "lib.rs");
+
+        // Unix paths are case-sensitive and siblings sharing a prefix are not inside the root
+        assert_eq!(relative_key("/home/Dev/app/a.ts", Some("/home/dev/app")), "/home/Dev/app/a.ts");
+        assert_eq!(relative_key("/home/dev/app2/a.ts", Some("/home/dev/app")), "/home/dev/app2/a.ts");
+        assert_eq!(relative_key("/home/dev/app", Some("/home/dev/app")), ".");
+    }
+
+    #[test]
+    fn test_same_file_matches_relative_forms() {
+        assert!(same_file(r"C:\app\src\a.ts", "src/a.ts"));
+        assert!(same_file("/home/dev/app/src/a.ts", r"src\a.ts"));
+        assert!(!same_file("/home/dev/app/src/a.ts", "rc/a.ts"));
+        assert!(!same_file("/a/src/a.ts", "/b/src/a.ts"));
+    }
+
+    #[test]
+    fn test_concept_ids_ignore_separator_style() {
+        let concept = |file_path: &str| SemanticConcept {
+            id: String::new(),
+            name: "InvoiceService".to_string(),
+            concept_type: "class".to_string(),
+            confidence: 0.9,
+            file_path: file_path.to_string(),
+            line_range: LineRange { start: 4, end: 20 },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        };
+
+        let windows = concept_id(&concept(r"D:\work\app\src\invoice.ts"), Some(r"D:\work\app"));
+        let unix = concept_id(&concept("/srv/app/src/invoice.ts"), Some("/srv/app"));
+        assert_eq!(windows, "src/invoice.ts::class::InvoiceService::L4");
+        assert_eq!(windows, unix);
+        assert_eq!(concept_id(&concept(r"src\invoice.ts"), None), unix);
+    }
+}
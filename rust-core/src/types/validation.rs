@@ -0,0 +1,247 @@
+//! Input validation for the exported API
+//!
+//! Paths and JSON payloads coming from JavaScript are checked here before any
+//! analysis starts. Failures carry an [`ErrorCode`] that is kept at the start
+//! of the error message (`[PATH_NOT_FOUND] ...`) so callers can tell a bad
+//! request apart from a failure during analysis.
+
+use crate::types::{paths, LineRange, ParseError, SemanticConcept};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Category of a rejected request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// The path does not exist
+    PathNotFound,
+    /// The path exists but is not a directory
+    NotADirectory,
+    /// The payload is not valid JSON
+    InvalidJson,
+    /// The payload is JSON but does not have the expected shape
+    SchemaMismatch,
+    /// A required argument is empty or out of range
+    InvalidArgument,
+    /// Persisted data was written by a newer, incompatible release
+    UnsupportedVersion,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::PathNotFound => "PATH_NOT_FOUND",
+            ErrorCode::NotADirectory => "NOT_A_DIRECTORY",
+            ErrorCode::InvalidJson => "INVALID_JSON",
+            ErrorCode::SchemaMismatch => "SCHEMA_MISMATCH",
+            ErrorCode::InvalidArgument => "INVALID_ARGUMENT",
+            ErrorCode::UnsupportedVersion => "UNSUPPORTED_VERSION",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A request rejected before any work started
+#[derive(Debug, Clone)]
+pub struct ValidationError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new<S: Into<String>>(code: ErrorCode, message: S) -> Self {
+        ValidationError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+#[cfg(feature = "napi-bindings")]
+impl From<ValidationError> for ParseError {
+    fn from(error: ValidationError) -> Self {
+        napi::Error::new(napi::Status::InvalidArg, error.to_string())
+    }
+}
+
+#[cfg(not(feature = "napi-bindings"))]
+impl From<ValidationError> for ParseError {
+    fn from(error: ValidationError) -> Self {
+        ParseError::from_reason(error.to_string())
+    }
+}
+
+/// Reject empty or whitespace-only arguments
+pub fn require_non_empty(name: &str, value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new(
+            ErrorCode::InvalidArgument,
+            format!("{} must not be empty", name),
+        ));
+    }
+    Ok(())
+}
+
+/// The path must exist
+pub fn require_path(path: &str) -> Result<PathBuf, ValidationError> {
+    require_non_empty("path", path)?;
+    let resolved = Path::new(path);
+    if !resolved.exists() {
+        return Err(ValidationError::new(
+            ErrorCode::PathNotFound,
+            format!("Path does not exist: {}", path),
+        ));
+    }
+    Ok(resolved.to_path_buf())
+}
+
+/// The path must exist and be a directory
+pub fn require_directory(path: &str) -> Result<PathBuf, ValidationError> {
+    let resolved = require_path(path)?;
+    if !resolved.is_dir() {
+        return Err(ValidationError::new(
+            ErrorCode::NotADirectory,
+            format!("Not a directory: {}", path),
+        ));
+    }
+    Ok(resolved)
+}
+
+/// Deserialize a JSON payload into its schema type
+///
+/// Syntax errors are reported as `INVALID_JSON`; well-formed JSON of the wrong
+/// shape as `SCHEMA_MISMATCH`, naming the schema that was expected.
+pub fn parse_json<T: DeserializeOwned>(input: &str, schema: &str) -> Result<T, ValidationError> {
+    serde_json::from_str(input).map_err(|e| {
+        let code = match e.classify() {
+            serde_json::error::Category::Data => ErrorCode::SchemaMismatch,
+            _ => ErrorCode::InvalidJson,
+        };
+        ValidationError::new(code, format!("Invalid {}: {}", schema, e))
+    })
+}
+
+/// Payload of `learn_from_analysis`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AnalysisInput {
+    pub concepts: Vec<ConceptInput>,
+    /// A list of pattern objects, or a `{detected, learned}` summary that is ignored
+    pub patterns: Option<Value>,
+    pub approaches: Option<Vec<Value>>,
+}
+
+/// A concept reported by the JS analysis layer
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ConceptInput {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub concept_type: String,
+    pub file: String,
+    pub confidence: f64,
+}
+
+impl Default for ConceptInput {
+    fn default() -> Self {
+        ConceptInput {
+            name: "unknown".to_string(),
+            concept_type: "unknown".to_string(),
+            file: "unknown".to_string(),
+            confidence: 0.5,
+        }
+    }
+}
+
+impl ConceptInput {
+    pub fn into_concept(self) -> SemanticConcept {
+        let mut concept = SemanticConcept {
+            id: String::new(),
+            name: self.name,
+            concept_type: self.concept_type,
+            confidence: self.confidence,
+            file_path: self.file,
+            line_range: LineRange { start: 1, end: 1 },
+            relationships: HashMap::new(),
+            metadata: HashMap::new(),
+        };
+        concept.id = paths::concept_id(&concept, None);
+        concept
+    }
+}
+
+/// Payload of `update_from_change`: one file watcher event
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeInput {
+    /// add/create, modify/change, delete/remove/unlink or rename/move
+    #[serde(rename = "type")]
+    pub change_type: String,
+    pub path: Option<String>,
+    pub content: Option<String>,
+    pub language: Option<String>,
+    /// Previous path of a renamed file
+    pub old_path: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_validation() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("main.rs");
+        std::fs::write(&file, "fn main() {}").unwrap();
+        let dir_path = dir.path().to_string_lossy().to_string();
+        let file_path = file.to_string_lossy().to_string();
+
+        assert!(require_directory(&dir_path).is_ok());
+        assert!(require_path(&file_path).is_ok());
+        assert_eq!(require_directory(&file_path).unwrap_err().code, ErrorCode::NotADirectory);
+        assert_eq!(require_path("/no/such/dir").unwrap_err().code, ErrorCode::PathNotFound);
+        assert_eq!(require_path("  ").unwrap_err().code, ErrorCode::InvalidArgument);
+
+        let error = require_path("/no/such/dir").unwrap_err();
+        assert_eq!(error.to_string(), "[PATH_NOT_FOUND] Path does not exist: /no/such/dir");
+    }
+
+    #[test]
+    fn test_json_schema_validation() {
+        let change: ChangeInput =
+            parse_json(r#"{"type": "rename", "path": "b.ts", "oldPath": "a.ts"}"#, "change event").unwrap();
+        assert_eq!(change.change_type, "rename");
+        assert_eq!(change.old_path.as_deref(), Some("a.ts"));
+
+        let syntax = parse_json::<ChangeInput>("{\"type\": ", "change event").unwrap_err();
+        assert_eq!(syntax.code, ErrorCode::InvalidJson);
+        let missing_type = parse_json::<ChangeInput>(r#"{"path": "a.ts"}"#, "change event").unwrap_err();
+        assert_eq!(missing_type.code, ErrorCode::SchemaMismatch);
+        assert!(missing_type.message.contains("change event"));
+
+        let analysis: AnalysisInput =
+            parse_json(r#"{"concepts": [{"name": "UserService", "type": "class"}]}"#, "analysis data").unwrap();
+        let concept = analysis.concepts.into_iter().next().unwrap().into_concept();
+        assert_eq!(concept.file_path, "unknown");
+        assert_eq!(concept.confidence, 0.5);
+        assert_eq!(
+            parse_json::<AnalysisInput>(r#"{"concepts": "UserService"}"#, "analysis data").unwrap_err().code,
+            ErrorCode::SchemaMismatch
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! WebAssembly bindings for in-browser and editor-web analysis
+//!
+//! The host supplies file contents directly; nothing here touches the filesystem.
+//! Complex values cross the boundary as JSON strings, matching the C FFI layer.
+
+use crate::analysis::{ComplexityAnalyzer, SemanticAnalyzer};
+use crate::parsing::ParserManager;
+use crate::patterns::{ImplementationPatternAnalyzer, NamingPatternAnalyzer};
+use crate::types::SemanticConcept;
+
+use wasm_bindgen::prelude::*;
+
+fn to_js_error<E: std::fmt::Display>(e: E) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+fn parse_concepts(concepts_json: &str) -> Result<Vec<SemanticConcept>, JsError> {
+    serde_json::from_str(concepts_json).map_err(to_js_error)
+}
+
+/// Concept extraction and pattern analysis over host-provided file contents
+#[wasm_bindgen]
+pub struct WasmAnalyzer {
+    analyzer: SemanticAnalyzer,
+    naming: NamingPatternAnalyzer,
+    implementation: ImplementationPatternAnalyzer,
+}
+
+#[wasm_bindgen]
+impl WasmAnalyzer {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Result<WasmAnalyzer, JsError> {
+        Ok(WasmAnalyzer {
+            analyzer: SemanticAnalyzer::new().map_err(to_js_error)?,
+            naming: NamingPatternAnalyzer::new(),
+            implementation: ImplementationPatternAnalyzer::new(),
+        })
+    }
+
+    /// Extract semantic concepts from a single file, returned as a JSON array
+    #[wasm_bindgen(js_name = extractConcepts)]
+    pub fn extract_concepts(&mut self, file_path: &str, content: &str) -> Result<String, JsError> {
+        let concepts = self.analyzer.extract_file_concepts_sync(file_path, content);
+        serde_json::to_string(&concepts).map_err(to_js_error)
+    }
+
+    /// Learn naming patterns from a JSON array of concepts, returned as a JSON array of patterns
+    #[wasm_bindgen(js_name = analyzeNamingPatterns)]
+    pub fn analyze_naming_patterns(&mut self, concepts_json: &str, language: &str) -> Result<String, JsError> {
+        let concepts = parse_concepts(concepts_json)?;
+        let patterns = self
+            .naming
+            .analyze_concepts(&concepts, language)
+            .map_err(to_js_error)?;
+        serde_json::to_string(&patterns).map_err(to_js_error)
+    }
+
+    /// Detect implementation patterns from a JSON array of concepts
+    #[wasm_bindgen(js_name = analyzeImplementationPatterns)]
+    pub fn analyze_implementation_patterns(&mut self, concepts_json: &str) -> Result<String, JsError> {
+        let concepts = parse_concepts(concepts_json)?;
+        let patterns = self
+            .implementation
+            .analyze_concepts(&concepts)
+            .map_err(to_js_error)?;
+        serde_json::to_string(&patterns).map_err(to_js_error)
+    }
+
+    /// Compute complexity metrics for a JSON array of concepts
+    #[wasm_bindgen(js_name = calculateComplexity)]
+    pub fn calculate_complexity(&self, concepts_json: &str) -> Result<String, JsError> {
+        let concepts = parse_concepts(concepts_json)?;
+        let metrics = ComplexityAnalyzer::calculate_complexity(&concepts);
+        serde_json::to_string(&metrics).map_err(to_js_error)
+    }
+}
+
+/// Languages with tree-sitter grammars compiled into this build
+#[wasm_bindgen(js_name = supportedLanguages)]
+pub fn supported_languages() -> Result<Vec<String>, JsError> {
+    Ok(ParserManager::new().map_err(to_js_error)?.available_languages())
+}
@@ -179,6 +179,10 @@ export class InteractiveSetup {
         ignored: config.ignoredPaths,
         debounceMs: 500
       },
+      analysis: {
+        maxFileSize: 1048576,
+        maxFiles: 1000
+      },
       mcp: {
         serverPort: 3000,
         enableAllTools: true
@@ -469,6 +469,10 @@ async function initializeProject(path: string): Promise<void> {
       ],
       debounceMs: 500
     },
+    analysis: {
+      maxFileSize: 1048576,
+      maxFiles: 1000
+    },
     mcp: {
       serverPort: 3000,
       enableAllTools: true